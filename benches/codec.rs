@@ -0,0 +1,85 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+//! Canonical encoding of consensus messages, as done for every gossiped vote and commit.
+//!
+//! ```text
+//! cargo bench --bench codec
+//! ```
+
+use amunchain::core::types::{
+    decode_canonical_limited, encode_canonical, CanonicalMap, Commit, ConsensusMsg, Epoch, Height,
+    Round, Signature, ValidatorId, Vote, H256,
+};
+use amunchain::networking::wire::MAX_WIRE_BYTES;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// Validators signing one commit certificate.
+const SIGNERS: u8 = 64;
+
+fn vote(n: u8) -> Vote {
+    Vote {
+        height: Height(4),
+        round: Round(1),
+        epoch: Epoch(1),
+        msg_counter: 9,
+        sent_ts_ms: 1_700_000_000_000,
+        ttl_ms: 30_000,
+        block_hash: H256::from_bytes([7; 32]),
+        voter: ValidatorId::from_bytes([n; 32]),
+        signature: Signature::from_bytes([n; 64]),
+    }
+}
+
+fn commit() -> Commit {
+    let v = vote(0);
+    Commit {
+        height: v.height,
+        round: v.round,
+        epoch: v.epoch,
+        msg_counter: v.msg_counter,
+        sent_ts_ms: v.sent_ts_ms,
+        ttl_ms: v.ttl_ms,
+        block_hash: v.block_hash,
+        signatures: (1..=SIGNERS)
+            .map(|n| {
+                (
+                    ValidatorId::from_bytes([n; 32]),
+                    Signature::from_bytes([n; 64]),
+                )
+            })
+            .collect::<CanonicalMap<_, _>>(),
+        voting_power: u128::from(SIGNERS),
+        validator_set_hash: H256::from_bytes([3; 32]),
+    }
+}
+
+fn bench_msg(c: &mut Criterion, name: &str, msg: &ConsensusMsg) {
+    let raw = encode_canonical(msg).unwrap();
+    let mut g = c.benchmark_group(name);
+    g.bench_function("encode", |b| b.iter(|| encode_canonical(black_box(msg))));
+    g.bench_function("decode", |b| {
+        b.iter(|| {
+            decode_canonical_limited::<ConsensusMsg>(black_box(&raw), MAX_WIRE_BYTES).unwrap()
+        })
+    });
+    g.finish();
+}
+
+fn codec(c: &mut Criterion) {
+    bench_msg(c, "codec_vote", &ConsensusMsg::Vote(vote(1)));
+    bench_msg(c, "codec_commit_64", &ConsensusMsg::Commit(commit()));
+}
+
+criterion_group!(benches, codec);
+criterion_main!(benches);
@@ -0,0 +1,268 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+//! Consensus hot paths: vote verification in the finalizer, commit certificate threshold
+//! verification, and the gossip replay cache every inbound message goes through.
+//!
+//! ```text
+//! cargo bench --bench consensus
+//! ```
+
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use amunchain::core::consensus::signing::{
+    validator_set_hash, vote_signing_bytes_v3, SigningDomain,
+};
+use amunchain::core::consensus::tide::{
+    verify_commit_certificate_for_chain, NoopSlashing, TideConfig, TideFinalizer,
+};
+use amunchain::core::types::{
+    Commit, ConsensusMsg, Epoch, Height, Round, Signature, ValidatorId, Vote, H256,
+};
+use amunchain::networking::dedup::{content_key, SeenCache};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+
+const CHAIN_ID: &str = "amunchain-bench";
+/// Validators in the set.
+const VALIDATORS: u8 = 64;
+
+struct Fixture {
+    keys: Vec<Ed25519KeyPair>,
+    validators: BTreeSet<ValidatorId>,
+    set_hash: H256,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let keys: Vec<_> = (1..=VALIDATORS)
+            .map(|n| Ed25519KeyPair::from_seed_unchecked(&[n; 32]).unwrap())
+            .collect();
+        let validators: BTreeSet<_> = keys
+            .iter()
+            .map(|k| ValidatorId::from_slice(k.public_key().as_ref()).unwrap())
+            .collect();
+        let set_hash = validator_set_hash(&validators).unwrap();
+        Self {
+            keys,
+            validators,
+            set_hash,
+        }
+    }
+
+    /// Vote with the given replay fields.
+    fn vote_with(
+        &self,
+        i: usize,
+        height: u64,
+        epoch: Epoch,
+        counter: u64,
+        sent_ts_ms: u64,
+    ) -> Vote {
+        let kp = &self.keys[i];
+        let voter = ValidatorId::from_slice(kp.public_key().as_ref()).unwrap();
+        let block_hash = H256::from_bytes([7; 32]);
+        let msg = vote_signing_bytes_v3(
+            CHAIN_ID,
+            Height(height),
+            Round::ZERO,
+            epoch,
+            counter,
+            sent_ts_ms,
+            0,
+            block_hash,
+            self.set_hash,
+            &voter,
+        )
+        .unwrap();
+        Vote {
+            height: Height(height),
+            round: Round::ZERO,
+            epoch,
+            msg_counter: counter,
+            sent_ts_ms,
+            ttl_ms: 0,
+            block_hash,
+            voter,
+            signature: Signature::from_slice(kp.sign(&msg).as_ref()).unwrap(),
+        }
+    }
+
+    /// Vote as a live validator sends it: epoch 1, `counter` and the current time, so the
+    /// finalizer's replay checks pass with or without the `production` feature.
+    fn fresh_vote(&self, i: usize, height: u64, counter: u64) -> Vote {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        self.vote_with(i, height, Epoch(1), counter, now_ms)
+    }
+
+    /// Commit for height 1 signed by the first `signers` validators. Certificates are checked
+    /// without replay state, so legacy fields (all zero) do.
+    fn commit(&self, signers: usize) -> Commit {
+        let votes: Vec<_> = (0..signers)
+            .map(|i| self.vote_with(i, 1, Epoch::ZERO, 0, 0))
+            .collect();
+        Commit {
+            height: Height(1),
+            round: Round::ZERO,
+            epoch: Epoch::ZERO,
+            msg_counter: 0,
+            sent_ts_ms: 0,
+            ttl_ms: 0,
+            block_hash: votes[0].block_hash,
+            signatures: votes.into_iter().map(|v| (v.voter, v.signature)).collect(),
+            voting_power: signers as u128,
+            validator_set_hash: self.set_hash,
+        }
+    }
+
+    fn domain(&self) -> SigningDomain<'static> {
+        SigningDomain {
+            chain_id: CHAIN_ID,
+            legacy_until_height: None,
+        }
+    }
+}
+
+fn vote_verify(c: &mut Criterion) {
+    let fx = Fixture::new();
+    let cfg = TideConfig::new(fx.validators.clone()).with_chain_id(CHAIN_ID, None);
+    let mut tide = TideFinalizer::new(cfg.clone(), NoopSlashing);
+
+    let mut g = c.benchmark_group("tide_vote");
+    // A validator's first vote at a height is verified and recorded; each iteration gets a
+    // finalizer that has not seen it yet.
+    g.bench_function("process_verified", |b| {
+        b.iter_batched(
+            || {
+                (
+                    TideFinalizer::new(cfg.clone(), NoopSlashing),
+                    fx.fresh_vote(0, 1, 1),
+                )
+            },
+            |(mut tide, v)| {
+                assert!(tide.process_vote_verified(v).unwrap().is_none());
+                tide
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    // Forged votes never advance the replay counter, so the same one can be resent.
+    g.bench_function("reject_forged", |b| {
+        b.iter_batched(
+            || {
+                let mut forged = fx.fresh_vote(0, 1, 1);
+                forged.block_hash = H256::from_bytes([8; 32]);
+                forged
+            },
+            |v| tide.process_vote_verified(v).unwrap_err(),
+            BatchSize::SmallInput,
+        )
+    });
+    g.finish();
+}
+
+fn commit_verify(c: &mut Criterion) {
+    let fx = Fixture::new();
+    let quorum = usize::from(VALIDATORS) * 2 / 3 + 1;
+    let mut g = c.benchmark_group("commit_certificate_64");
+    for (name, signers) in [("quorum", quorum), ("all", usize::from(VALIDATORS))] {
+        let commit = fx.commit(signers);
+        g.bench_function(name, |b| {
+            b.iter(|| {
+                verify_commit_certificate_for_chain(
+                    black_box(&commit),
+                    &fx.validators,
+                    None,
+                    fx.domain(),
+                )
+                .unwrap()
+            })
+        });
+    }
+    // Below threshold is rejected before any signature is checked.
+    let short = fx.commit(quorum - 1);
+    g.bench_function("below_threshold", |b| {
+        b.iter(|| {
+            verify_commit_certificate_for_chain(
+                black_box(&short),
+                &fx.validators,
+                None,
+                fx.domain(),
+            )
+            .unwrap_err()
+        })
+    });
+    g.finish();
+}
+
+fn replay_cache(c: &mut Criterion) {
+    let fx = Fixture::new();
+    let msgs: Vec<_> = (0..4096)
+        .map(|i| {
+            ConsensusMsg::Vote(fx.vote_with(
+                i % usize::from(VALIDATORS),
+                1 + i as u64,
+                Epoch::ZERO,
+                0,
+                0,
+            ))
+        })
+        .collect();
+    let keys: Vec<_> = msgs.iter().map(|m| content_key(m).unwrap()).collect();
+    let ttl = Duration::from_secs(120);
+
+    let mut g = c.benchmark_group("replay_cache");
+    g.throughput(criterion::Throughput::Elements(keys.len() as u64));
+    g.bench_function("content_key", |b| {
+        b.iter(|| {
+            for m in &msgs {
+                black_box(content_key(m).unwrap());
+            }
+        })
+    });
+    // Fresh keys into a cache at capacity: every insert also evicts.
+    g.bench_function("insert_evicting", |b| {
+        b.iter_batched(
+            || SeenCache::new(1024, ttl),
+            |mut cache| {
+                let now = Instant::now();
+                for k in &keys {
+                    assert!(cache.insert(*k, now));
+                }
+                cache
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    // Duplicates, the common case under gossip fan-out.
+    let mut seen = SeenCache::new(keys.len(), ttl);
+    let now = Instant::now();
+    for k in &keys {
+        seen.insert(*k, now);
+    }
+    g.bench_function("duplicate", |b| {
+        b.iter(|| {
+            for k in &keys {
+                assert!(!seen.insert(*k, now));
+            }
+        })
+    });
+    g.finish();
+}
+
+criterion_group!(benches, vote_verify, commit_verify, replay_cache);
+criterion_main!(benches);
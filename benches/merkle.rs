@@ -0,0 +1,64 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+//! State root and inclusion proofs over sorted key/value pairs.
+//!
+//! ```text
+//! cargo bench --bench merkle
+//! ```
+
+use amunchain::core::state::merkle::{merkle_proof_sorted, merkle_root_sorted, verify_proof};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// `n` sorted pairs shaped like account entries.
+fn pairs(n: u32) -> Vec<(Vec<u8>, Vec<u8>)> {
+    (0..n)
+        .map(|i| {
+            let key = [b"acct/".as_slice(), &i.to_be_bytes()].concat();
+            (key, vec![i as u8; 64])
+        })
+        .collect()
+}
+
+fn root(c: &mut Criterion) {
+    let mut g = c.benchmark_group("merkle_root");
+    g.sample_size(10);
+    for n in [10_000, 100_000] {
+        let kv = pairs(n);
+        g.bench_with_input(BenchmarkId::from_parameter(n), &kv, |b, kv| {
+            b.iter(|| merkle_root_sorted(black_box(kv)))
+        });
+    }
+    g.finish();
+}
+
+fn proof(c: &mut Criterion) {
+    let mut g = c.benchmark_group("merkle_proof");
+    g.sample_size(10);
+    for n in [10_000, 100_000] {
+        let kv = pairs(n);
+        let index = kv.len() / 3;
+        g.bench_with_input(BenchmarkId::new("generate", n), &kv, |b, kv| {
+            b.iter(|| merkle_proof_sorted(black_box(kv), black_box(index)).unwrap())
+        });
+        let root = merkle_root_sorted(&kv);
+        let p = merkle_proof_sorted(&kv, index).unwrap();
+        g.bench_with_input(BenchmarkId::new("verify", n), &p, |b, p| {
+            b.iter(|| assert!(verify_proof(black_box(root), black_box(p))))
+        });
+    }
+    g.finish();
+}
+
+criterion_group!(benches, root, proof);
+criterion_main!(benches);
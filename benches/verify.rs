@@ -0,0 +1,81 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+//! Vote signature verification: per-call key parsing vs `PublicKeyCache`.
+//!
+//! ```text
+//! cargo bench --bench verify
+//! ```
+
+use amunchain::core::security::keystore::{verify_pubkey_bytes, PublicKeyCache};
+use amunchain::core::types::Signature;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+
+/// Validators signing one commit certificate.
+const SIGNERS: u8 = 64;
+
+fn signed(n: u8, msg: &[u8]) -> ([u8; 32], Signature) {
+    let kp = Ed25519KeyPair::from_seed_unchecked(&[n; 32]).unwrap();
+    let pk = kp.public_key().as_ref().try_into().unwrap();
+    (pk, Signature::from_slice(kp.sign(msg).as_ref()).unwrap())
+}
+
+fn single(c: &mut Criterion) {
+    let msg = b"Amunchain-Tide-Vote-v3 payload".as_slice();
+    let (pk, sig) = signed(1, msg);
+    let cache = PublicKeyCache::new([&pk]);
+
+    let mut g = c.benchmark_group("verify_single");
+    g.bench_function("unparsed", |b| {
+        b.iter(|| verify_pubkey_bytes(black_box(&pk), black_box(msg), black_box(&sig)))
+    });
+    g.bench_function("cached", |b| {
+        b.iter(|| cache.verify(black_box(&pk), black_box(msg), black_box(&sig)))
+    });
+    g.finish();
+}
+
+fn certificate(c: &mut Criterion) {
+    let msg = b"Amunchain-Tide-Vote-v3 payload".as_slice();
+    let sigs: Vec<_> = (1..=SIGNERS).map(|n| signed(n, msg)).collect();
+    let cache = PublicKeyCache::new(sigs.iter().map(|(pk, _)| pk));
+
+    let mut g = c.benchmark_group("verify_certificate_64");
+    g.bench_function("unparsed", |b| {
+        b.iter(|| {
+            for (pk, sig) in &sigs {
+                verify_pubkey_bytes(pk, black_box(msg), sig).unwrap();
+            }
+        })
+    });
+    g.bench_function("cached", |b| {
+        b.iter(|| {
+            for (pk, sig) in &sigs {
+                cache.verify(pk, black_box(msg), sig).unwrap();
+            }
+        })
+    });
+    // Building the cache is paid once per validator set change.
+    g.bench_function("cache_build", |b| {
+        b.iter_batched(
+            || sigs.clone(),
+            |sigs| PublicKeyCache::new(sigs.iter().map(|(pk, _)| pk)),
+            BatchSize::SmallInput,
+        )
+    });
+    g.finish();
+}
+
+criterion_group!(benches, single, certificate);
+criterion_main!(benches);
@@ -1,8 +1,38 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
 #![no_main]
+#![forbid(unsafe_code)]
+
 use libfuzzer_sys::fuzz_target;
+use amunchain::core::types::encode_canonical;
+use amunchain::networking::wire::{decode_and_validate_consensus_msg, WireCodec, WIRE_V2};
 
+// The first byte picks the topic codec (even: bincode, odd: protobuf); the rest is the gossip
+// payload exactly as p2p.rs receives it.
 fuzz_target!(|data: &[u8]| {
-    // Best-effort: ensure decoding/parsing paths do not panic.
-    // This target is intentionally conservative and should be extended with real protocol framing.
-    let _ = std::str::from_utf8(data);
+    let Some((&sel, frame)) = data.split_first() else {
+        return;
+    };
+    let (codec, decoded) = if sel % 2 == 0 {
+        (WireCodec::Bincode, decode_and_validate_consensus_msg(frame))
+    } else {
+        (WireCodec::Protobuf, WireCodec::Protobuf.decode_validated(frame))
+    };
+    let Ok(msg) = decoded else {
+        return;
+    };
+    // Anything accepted must re-encode and decode back to the same message.
+    let bytes = codec.encode(&msg, WIRE_V2).expect("re-encode accepted message");
+    let again = codec.decode_validated(&bytes).expect("decode re-encoded message");
+    assert_eq!(encode_canonical(&again).ok(), encode_canonical(&msg).ok());
 });
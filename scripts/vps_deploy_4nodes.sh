@@ -98,6 +98,7 @@ LimitNOFILE=65536
 ExecStart=/srv/amunchain/bin/amunchain --config /srv/amunchain/configs/node%i.toml
 Restart=always
 RestartSec=2
+RestartPreventExitStatus=10 11 12 14
 TimeoutStopSec=20
 
 [Install]
@@ -0,0 +1,87 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+//! Audit log archive tool.
+//!
+//! ```text
+//! audit export --from <ms> --to <ms> [--data-dir <dir>] [--out <file>]
+//! audit verify <archive>
+//! ```
+
+use amunchain::core::security::audit;
+use amunchain::errors::ExitCode;
+use std::path::PathBuf;
+
+const USAGE: &str = "usage:
+  audit export --from <ms> --to <ms> [--data-dir <dir>] [--out <file>]
+  audit verify <archive>";
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(ExitCode::Config.code());
+}
+
+fn flag<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1))
+        .map(|s| s.as_str())
+}
+
+fn flag_ms(args: &[String], name: &str) -> u64 {
+    match flag(args, name).map(str::parse::<u64>) {
+        Some(Ok(v)) => v,
+        _ => usage(),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(|s| s.as_str()) {
+        Some("export") => {
+            let from = flag_ms(&args, "--from");
+            let to = flag_ms(&args, "--to");
+            if from > to {
+                usage();
+            }
+            let data_dir = PathBuf::from(flag(&args, "--data-dir").unwrap_or("data"));
+            let out = flag(&args, "--out")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from(format!("audit-{from}-{to}.amunaudit")));
+            audit::export(&data_dir, from, to, &out).map(|m| {
+                println!(
+                    "{}: {} entries in {} chunks, last_hash={}",
+                    out.display(),
+                    m.entries,
+                    m.chunks.len(),
+                    m.last_hash
+                );
+            })
+        }
+        Some("verify") => {
+            let Some(path) = args.get(1) else { usage() };
+            audit::verify_archive(PathBuf::from(path).as_path()).map(|(m, _)| {
+                println!(
+                    "ok: {} entries, {}..={} ms, last_hash={}",
+                    m.entries, m.from_ms, m.to_ms, m.last_hash
+                );
+            })
+        }
+        _ => usage(),
+    };
+
+    if let Err(e) = result {
+        eprintln!("audit: {e}");
+        std::process::exit(ExitCode::Internal.code());
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+//! Epoch preview client.
+//!
+//! ```text
+//! epoch preview [--rpc <host:port>] [--bond <delegator>:<validator>:<amount>]...
+//!               [--unbond <delegator>:<validator>:<amount>]...
+//! ```
+//!
+//! Ids are hex. Asks the node's `/staking/epoch/preview` endpoint and prints the JSON
+//! response; nothing is submitted.
+
+use amunchain::errors::ExitCode;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+const USAGE: &str = "usage:
+  epoch preview [--rpc <host:port>] [--bond <delegator>:<validator>:<amount>]...
+                [--unbond <delegator>:<validator>:<amount>]...";
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(ExitCode::Config.code());
+}
+
+fn op(kind: &str, spec: &str) -> serde_json::Value {
+    let parts: Vec<&str> = spec.split(':').collect();
+    let [delegator, validator, amount] = parts.as_slice() else {
+        usage()
+    };
+    let Ok(amount) = amount.parse::<u128>() else {
+        usage()
+    };
+    serde_json::json!({
+        "op": kind,
+        "delegator": delegator,
+        "validator": validator,
+        "amount": amount,
+    })
+}
+
+fn post(addr: &str, path: &str, body: &str) -> std::io::Result<(u16, String)> {
+    let mut s = TcpStream::connect(addr)?;
+    s.set_read_timeout(Some(Duration::from_secs(10)))?;
+    write!(
+        s,
+        "POST {path} HTTP/1.1\r\nHost: {addr}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    let mut resp = String::new();
+    s.read_to_string(&mut resp)?;
+    let status = resp
+        .split(' ')
+        .nth(1)
+        .and_then(|c| c.parse().ok())
+        .unwrap_or(0);
+    let body = resp
+        .split_once("\r\n\r\n")
+        .map(|(_, b)| b.to_string())
+        .unwrap_or_default();
+    Ok((status, body))
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(|s| s.as_str()) != Some("preview") {
+        usage();
+    }
+    let mut rpc = "127.0.0.1:9090".to_string();
+    let mut ops = Vec::new();
+    let mut it = args.iter().skip(1);
+    while let Some(flag) = it.next() {
+        let Some(value) = it.next() else { usage() };
+        match flag.as_str() {
+            "--rpc" => rpc = value.clone(),
+            "--bond" => ops.push(op("bond", value)),
+            "--unbond" => ops.push(op("unbond", value)),
+            _ => usage(),
+        }
+    }
+
+    let body = serde_json::json!({ "ops": ops }).to_string();
+    match post(&rpc, "/staking/epoch/preview", &body) {
+        Ok((200, body)) => println!("{body}"),
+        Ok((status, _)) => {
+            eprintln!("epoch: node returned HTTP {status}");
+            std::process::exit(ExitCode::Internal.code());
+        }
+        Err(e) => {
+            eprintln!("epoch: {e}");
+            std::process::exit(ExitCode::Internal.code());
+        }
+    }
+}
@@ -11,30 +11,78 @@
 
 #![forbid(unsafe_code)]
 
-use anyhow::Result;
-use ring::signature::{Ed25519KeyPair, KeyPair};
-use std::path::PathBuf;
+//! Validator key tool.
+//!
+//! ```text
+//! keygen [<data-dir>]                create <data-dir>/validator.key (refuses to replace one)
+//! keygen --rotate [<data-dir>]       replace the key; the old one stays as validator.key.<ms>.old
+//! keygen --show-pubkey [<data-dir>]  print the public key (hex)
+//! keygen --export-pem [<data-dir>]   print the public key as PEM (SubjectPublicKeyInfo)
+//! ```
+//!
+//! `<data-dir>` defaults to `data`. With `AMUNCHAIN_KEY_PASSPHRASE` set, new keys are written
+//! in the keystore's encrypted format, and encrypted keys can be read. Results go to stdout,
+//! guidance to stderr.
 
-fn main() -> Result<()> {
-    let out_dir = std::env::args()
-        .nth(1)
-        .unwrap_or_else(|| "data".to_string());
-    let mut key_path = PathBuf::from(out_dir);
-    std::fs::create_dir_all(&key_path)?;
-    key_path.push("validator.key");
+use amunchain::errors::{Classify, ExitCode};
+use amunchain::node::cli::{self, CliError};
+use std::path::PathBuf;
 
-    let rng = ring::rand::SystemRandom::new();
-    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng)?;
-    std::fs::write(&key_path, pkcs8.as_ref())?;
+const USAGE: &str = "usage:
+  keygen [<data-dir>]
+  keygen --rotate [<data-dir>]
+  keygen --show-pubkey [<data-dir>]
+  keygen --export-pem [<data-dir>]";
 
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let _ = std::fs::set_permissions(&key_path, std::fs::Permissions::from_mode(0o600));
+fn run(mode: Option<&str>, data_dir: PathBuf) -> Result<(), CliError> {
+    match mode {
+        None => {
+            let pk = cli::keygen(&data_dir, false)?;
+            println!("{pk}");
+            eprintln!("{}", cli::key_guidance(&cli::key_info(&data_dir)?));
+        }
+        Some("--rotate") => {
+            let r = cli::rotate_key(&data_dir)?;
+            println!("{}", r.current.public_key);
+            eprintln!("{}", cli::key_guidance(&r.current));
+            eprintln!(
+                "Replace {} with the new key in consensus.validators_hex on every node before \
+                 restarting this one. The previous key is kept at {}.",
+                r.previous.public_key,
+                r.previous.path.display()
+            );
+        }
+        Some("--show-pubkey") => println!("{}", cli::key_info(&data_dir)?.public_key),
+        Some("--export-pem") => print!("{}", cli::public_key_pem(&data_dir)?),
+        Some(_) => unreachable!("checked in main"),
     }
-
-    let kp = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref())?;
-    let pk = kp.public_key().as_ref();
-    println!("{}", hex::encode(pk));
     Ok(())
 }
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (mode, rest) = match args.first().map(String::as_str) {
+        Some(m @ ("--rotate" | "--show-pubkey" | "--export-pem")) => (Some(m), &args[1..]),
+        Some(a) if a.starts_with('-') => {
+            eprintln!("{USAGE}");
+            std::process::exit(ExitCode::Config.code());
+        }
+        _ => (None, &args[..]),
+    };
+    if rest.len() > 1 {
+        eprintln!("{USAGE}");
+        std::process::exit(ExitCode::Config.code());
+    }
+    let data_dir = PathBuf::from(rest.first().map_or("data", String::as_str));
+    match run(mode, data_dir) {
+        Ok(()) => {}
+        Err(CliError::Exists(path)) => {
+            eprintln!("keygen failed: {path} already exists; use --rotate to replace it");
+            std::process::exit(ExitCode::Config.code());
+        }
+        Err(e) => {
+            eprintln!("keygen failed: {e}");
+            std::process::exit(e.exit_code().code());
+        }
+    }
+}
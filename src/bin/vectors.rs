@@ -0,0 +1,57 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+//! Test vector generator.
+//!
+//! ```text
+//! vectors [--out <file>]   write the JSON vectors to <file> (default: stdout)
+//! ```
+//!
+//! Output is deterministic; see `amunchain::node::vectors` for what is covered.
+
+use amunchain::errors::ExitCode;
+use amunchain::node::vectors;
+
+const USAGE: &str = "usage:
+  vectors [--out <file>]";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let out = match args.as_slice() {
+        [] => None,
+        [flag, path] if flag == "--out" => Some(path.clone()),
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(ExitCode::Config.code());
+        }
+    };
+
+    let doc = match vectors::generate()
+        .and_then(|v| serde_json::to_string_pretty(&v).map_err(|_| vectors::VectorsError::Codec))
+    {
+        Ok(doc) => doc,
+        Err(e) => {
+            eprintln!("vectors: {e}");
+            std::process::exit(ExitCode::Internal.code());
+        }
+    };
+    match out {
+        None => println!("{doc}"),
+        Some(path) => {
+            if let Err(e) = std::fs::write(&path, format!("{doc}\n")) {
+                eprintln!("vectors: {path}: {e}");
+                std::process::exit(ExitCode::Internal.code());
+            }
+        }
+    }
+}
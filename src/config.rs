@@ -0,0 +1,238 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Layered `NodeConfig` loading.
+//!
+//! Layers, lowest precedence first:
+//!
+//! 1. built-in defaults (`configs/node.toml`),
+//! 2. the config file, merged table by table (a file may set only what it changes),
+//! 3. environment variables `AMUNCHAIN__<SECTION>__<KEY>=<value>`,
+//!    e.g. `AMUNCHAIN__P2P__LISTEN_ADDR=/ip4/0.0.0.0/tcp/4001`,
+//! 4. command-line overrides (`--set p2p.listen_addr=...`, `--data-dir`).
+//!
+//! Override values are read as TOML values (`200`, `true`, `["a", "b"]`); anything that does
+//! not parse is taken as a plain string, so addresses need no quoting. Quote a value to force
+//! a string (`AMUNCHAIN__NODE__NAME='"123"'`). Arrays are replaced, not appended to.
+//!
+//! The older single-purpose `AMUN_*` variables (see `LEGACY_ENV`, e.g. `AMUN_HTTP_ADDR`) are
+//! part of the environment layer: they lose to an `AMUNCHAIN__*` variable for the same key and
+//! to command-line overrides.
+
+use crate::core::types::{ConfigError, NodeConfig};
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Built-in defaults; also the template written by `amunchain init`.
+pub const DEFAULT_CONFIG: &str = include_str!("../configs/node.toml");
+/// Prefix of config override variables.
+pub const ENV_PREFIX: &str = "AMUNCHAIN__";
+
+/// Older variables, the key each one sets, and whether its value is always a string.
+pub const LEGACY_ENV: &[(&str, &str, bool)] = &[
+    ("AMUN_DATA_DIR", "node.data_dir", true),
+    ("AMUN_CHAIN_ID", "node.chain_id", true),
+    ("AMUN_HTTP_ADDR", "http.listen_addr", true),
+    ("AMUN_P2P_TOPIC", "p2p.topic", true),
+    (
+        "AMUN_CONSENSUS_WORKERS",
+        "runtime.consensus_worker_threads",
+        false,
+    ),
+    ("AMUN_RPC_WORKERS", "runtime.rpc_worker_threads", false),
+    ("AMUN_RPC_MAX_IN_FLIGHT", "runtime.rpc_max_in_flight", false),
+    ("AMUN_HEALTH_MIN_PEERS", "health.min_peers", false),
+    ("AMUN_HEALTH_MAX_STALL_SECS", "health.max_stall_secs", false),
+    (
+        "AMUN_HEALTH_MAX_CLOCK_SKEW_MS",
+        "health.max_clock_skew_ms",
+        false,
+    ),
+    ("AMUN_OTLP_ENDPOINT", "telemetry.otlp_endpoint", true),
+    ("AMUN_OTLP_SAMPLE_RATIO", "telemetry.sample_ratio", false),
+    ("AMUN_OTLP_SERVICE_NAME", "telemetry.service_name", true),
+    ("RUST_LOG", "telemetry.log_filter", true),
+];
+
+/// Builds a `NodeConfig` from defaults, a file, environment and explicit overrides.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigLoader {
+    file: Option<PathBuf>,
+    env: Vec<(String, String)>,
+    overrides: Vec<(String, String)>,
+}
+
+impl ConfigLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Merge this file over the defaults.
+    pub fn file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.file = Some(path.into());
+        self
+    }
+
+    /// The config file, if any.
+    pub fn file_path(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    /// Take `AMUNCHAIN__*` overrides from the process environment.
+    pub fn with_env(self) -> Self {
+        self.env_vars(std::env::vars())
+    }
+
+    /// Take `AMUNCHAIN__*` and `LEGACY_ENV` overrides from `vars`; other variables are
+    /// ignored.
+    pub fn env_vars(mut self, vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        let mut legacy: Vec<(usize, String)> = Vec::new();
+        let mut vars: Vec<(String, String)> = vars
+            .into_iter()
+            .filter_map(|(k, v)| {
+                if let Some(i) = LEGACY_ENV.iter().position(|(name, _, _)| *name == k) {
+                    legacy.push((i, v));
+                    return None;
+                }
+                let path = k.strip_prefix(ENV_PREFIX)?;
+                Some((
+                    path.split("__")
+                        .collect::<Vec<_>>()
+                        .join(".")
+                        .to_ascii_lowercase(),
+                    v,
+                ))
+            })
+            .collect();
+        // Environment order is unspecified; sort so the result does not depend on it.
+        vars.sort();
+        legacy.sort();
+        // Legacy variables go first so the `AMUNCHAIN__*` spelling of a key wins.
+        self.env = legacy
+            .into_iter()
+            .map(|(i, v)| {
+                let (_, key, string) = LEGACY_ENV[i];
+                let v = if string {
+                    Value::String(v).to_string()
+                } else {
+                    v
+                };
+                (key.to_string(), v)
+            })
+            .chain(vars)
+            .collect();
+        self
+    }
+
+    /// Override one value by dotted key, e.g. `set("p2p.max_peers_per_ip", "8")`.
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.overrides.push((key.into(), value.into()));
+        self
+    }
+
+    /// Override from a `key=value` argument.
+    pub fn set_arg(self, arg: &str) -> Result<Self, ConfigError> {
+        match arg.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => Ok(self.set(key.trim(), value)),
+            _ => Err(ConfigError::Override {
+                key: arg.to_string(),
+                reason: "expected key=value".to_string(),
+            }),
+        }
+    }
+
+    /// Merge all layers and deserialize.
+    pub fn load(&self) -> Result<NodeConfig, ConfigError> {
+        let mut root: Table = toml::from_str(DEFAULT_CONFIG).map_err(|e| ConfigError::Parse {
+            path: "built-in defaults".to_string(),
+            reason: e.to_string(),
+        })?;
+        if let Some(path) = self.file.as_deref() {
+            let raw = std::fs::read_to_string(path).map_err(|e| ConfigError::Read {
+                path: path.display().to_string(),
+                reason: e.to_string(),
+            })?;
+            let file: Table = toml::from_str(&raw).map_err(|e| ConfigError::Parse {
+                path: path.display().to_string(),
+                reason: e.to_string(),
+            })?;
+            merge(&mut root, file);
+        }
+        for (key, raw) in self.env.iter().chain(self.overrides.iter()) {
+            set_path(&mut root, key, parse_value(raw))?;
+        }
+        serde_path_to_error::deserialize(Value::Table(root)).map_err(
+            |e: serde_path_to_error::Error<toml::de::Error>| ConfigError::Parse {
+                path: self.describe(),
+                reason: format!("{}: {}", e.path(), e.inner().message()),
+            },
+        )
+    }
+
+    // Where the config came from, for error messages.
+    fn describe(&self) -> String {
+        let mut parts = vec![self
+            .file
+            .as_deref()
+            .map_or("built-in defaults".to_string(), |p| p.display().to_string())];
+        if !self.env.is_empty() {
+            parts.push(format!("{ENV_PREFIX}* environment"));
+        }
+        if !self.overrides.is_empty() {
+            parts.push("command-line overrides".to_string());
+        }
+        parts.join(" + ")
+    }
+}
+
+fn merge(base: &mut Table, over: Table) {
+    for (k, v) in over {
+        match (base.get_mut(&k), v) {
+            (Some(Value::Table(b)), Value::Table(o)) => merge(b, o),
+            (_, v) => {
+                base.insert(k, v);
+            }
+        }
+    }
+}
+
+fn set_path(root: &mut Table, key: &str, value: Value) -> Result<(), ConfigError> {
+    let err = |reason: &str| ConfigError::Override {
+        key: key.to_string(),
+        reason: reason.to_string(),
+    };
+    let parts: Vec<&str> = key.split('.').collect();
+    if parts.iter().any(|p| p.is_empty()) {
+        return Err(err("empty key segment"));
+    }
+    let (last, sections) = parts.split_last().ok_or_else(|| err("empty key"))?;
+    let mut table = root;
+    for s in sections {
+        let entry = table
+            .entry(s.to_string())
+            .or_insert_with(|| Value::Table(Table::new()));
+        table = match entry {
+            Value::Table(t) => t,
+            _ => return Err(err("not a table")),
+        };
+    }
+    table.insert(last.to_string(), value);
+    Ok(())
+}
+
+fn parse_value(raw: &str) -> Value {
+    toml::from_str::<Table>(&format!("v = {raw}"))
+        .ok()
+        .and_then(|mut t| t.remove("v"))
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
@@ -0,0 +1,791 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Canonical JSON: one byte string per value, for tooling that cannot speak bincode.
+//!
+//! Rules (what `encode` writes and the only thing `decode` accepts):
+//! - no whitespace;
+//! - object keys sorted by their UTF-8 bytes, no duplicates;
+//! - numbers are integers in shortest decimal form (`-?(0|[1-9][0-9]*)`), any width up to
+//!   128 bits; floats are rejected, so there is no formatting to disagree on;
+//! - strings escape `"`, `\` and control characters only (`\b \f \n \r \t`, else `\u00xx`),
+//!   everything else is raw UTF-8;
+//! - serde's data model maps as in `serde_json`: structs are objects, sequences and tuples
+//!   arrays, `None`/unit `null`, newtypes their content, enums externally tagged;
+//! - maps with string keys are objects; maps with other keys (e.g. `ValidatorId`) are arrays of
+//!   `[key, value]` pairs sorted by the encoded key;
+//! - `serialize_bytes` (not `Vec<u8>`, which serde sees as a sequence) is a lowercase hex
+//!   string.
+//!
+//! `decode` re-encodes what it parsed and rejects the input unless the bytes match, so a
+//! value has exactly one accepted encoding.
+
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::ser::{self, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Deepest nesting `decode` accepts.
+const MAX_DEPTH: usize = 128;
+
+/// Encode or decode failure, with the reason.
+#[derive(Debug)]
+pub struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+fn err<T>(msg: impl Into<String>) -> Result<T, Error> {
+    Err(Error(msg.into()))
+}
+
+/// Intermediate value tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Value {
+    Null,
+    Bool(bool),
+    /// Sign and magnitude; zero is never negative.
+    Int {
+        neg: bool,
+        abs: u128,
+    },
+    Str(String),
+    Array(Vec<Value>),
+    Object(BTreeMap<String, Value>),
+}
+
+impl Value {
+    fn uint(v: u128) -> Self {
+        Value::Int { neg: false, abs: v }
+    }
+
+    fn int(v: i128) -> Self {
+        Value::Int {
+            neg: v < 0,
+            abs: v.unsigned_abs(),
+        }
+    }
+}
+
+/// Canonical JSON bytes of `v`.
+pub fn encode<T: Serialize + ?Sized>(v: &T) -> Result<Vec<u8>, Error> {
+    let mut out = Vec::new();
+    write(&v.serialize(ValueSerializer)?, &mut out);
+    Ok(out)
+}
+
+/// Decode canonical JSON; anything but the exact bytes `encode` would write is rejected.
+pub fn decode<T: Serialize + DeserializeOwned>(bytes: &[u8]) -> Result<T, Error> {
+    let mut p = Parser { bytes, pos: 0 };
+    let tree = p.value(0)?;
+    if p.pos != bytes.len() {
+        return err("trailing bytes");
+    }
+    let v = T::deserialize(tree)?;
+    if encode(&v)? != bytes {
+        return err("not in canonical form");
+    }
+    Ok(v)
+}
+
+// ---- writer ----
+
+fn write(v: &Value, out: &mut Vec<u8>) {
+    match v {
+        Value::Null => out.extend_from_slice(b"null"),
+        Value::Bool(b) => out.extend_from_slice(if *b { b"true" } else { b"false" }),
+        Value::Int { neg, abs } => {
+            if *neg {
+                out.push(b'-');
+            }
+            out.extend_from_slice(abs.to_string().as_bytes());
+        }
+        Value::Str(s) => write_str(s, out),
+        Value::Array(items) => {
+            out.push(b'[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write(item, out);
+            }
+            out.push(b']');
+        }
+        Value::Object(fields) => {
+            out.push(b'{');
+            for (i, (k, v)) in fields.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_str(k, out);
+                out.push(b':');
+                write(v, out);
+            }
+            out.push(b'}');
+        }
+    }
+}
+
+fn write_str(s: &str, out: &mut Vec<u8>) {
+    out.push(b'"');
+    for c in s.chars() {
+        match c {
+            '"' => out.extend_from_slice(b"\\\""),
+            '\\' => out.extend_from_slice(b"\\\\"),
+            '\u{8}' => out.extend_from_slice(b"\\b"),
+            '\u{c}' => out.extend_from_slice(b"\\f"),
+            '\n' => out.extend_from_slice(b"\\n"),
+            '\r' => out.extend_from_slice(b"\\r"),
+            '\t' => out.extend_from_slice(b"\\t"),
+            c if (c as u32) < 0x20 => {
+                out.extend_from_slice(format!("\\u{:04x}", c as u32).as_bytes())
+            }
+            c => {
+                let mut buf = [0u8; 4];
+                out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            }
+        }
+    }
+    out.push(b'"');
+}
+
+// ---- serializer ----
+
+struct ValueSerializer;
+
+struct SeqSer(Vec<Value>);
+
+struct VariantSeqSer {
+    variant: &'static str,
+    items: Vec<Value>,
+}
+
+struct MapSer {
+    entries: Vec<(Value, Value)>,
+    key: Option<Value>,
+}
+
+struct StructSer(BTreeMap<String, Value>);
+
+struct VariantStructSer {
+    variant: &'static str,
+    fields: BTreeMap<String, Value>,
+}
+
+fn tagged(variant: &str, payload: Value) -> Value {
+    Value::Object(BTreeMap::from([(variant.to_string(), payload)]))
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = SeqSer;
+    type SerializeTuple = SeqSer;
+    type SerializeTupleStruct = SeqSer;
+    type SerializeTupleVariant = VariantSeqSer;
+    type SerializeMap = MapSer;
+    type SerializeStruct = StructSer;
+    type SerializeStructVariant = VariantStructSer;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+        Ok(Value::Bool(v))
+    }
+    fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        Ok(Value::int(v.into()))
+    }
+    fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        Ok(Value::int(v.into()))
+    }
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        Ok(Value::int(v.into()))
+    }
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        Ok(Value::int(v.into()))
+    }
+    fn serialize_i128(self, v: i128) -> Result<Value, Error> {
+        Ok(Value::int(v))
+    }
+    fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        Ok(Value::uint(v.into()))
+    }
+    fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        Ok(Value::uint(v.into()))
+    }
+    fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        Ok(Value::uint(v.into()))
+    }
+    fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        Ok(Value::uint(v.into()))
+    }
+    fn serialize_u128(self, v: u128) -> Result<Value, Error> {
+        Ok(Value::uint(v))
+    }
+    fn serialize_f32(self, _: f32) -> Result<Value, Error> {
+        err("floats have no canonical form")
+    }
+    fn serialize_f64(self, _: f64) -> Result<Value, Error> {
+        err("floats have no canonical form")
+    }
+    fn serialize_char(self, v: char) -> Result<Value, Error> {
+        Ok(Value::Str(v.to_string()))
+    }
+    fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        Ok(Value::Str(v.to_string()))
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, Error> {
+        Ok(Value::Str(hex::encode(v)))
+    }
+    fn serialize_none(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+    fn serialize_some<T: Serialize + ?Sized>(self, v: &T) -> Result<Value, Error> {
+        v.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Value, Error> {
+        Ok(Value::Str(variant.to_string()))
+    }
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        v: &T,
+    ) -> Result<Value, Error> {
+        v.serialize(self)
+    }
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        v: &T,
+    ) -> Result<Value, Error> {
+        Ok(tagged(variant, v.serialize(self)?))
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSer, Error> {
+        Ok(SeqSer(Vec::with_capacity(len.unwrap_or(0))))
+    }
+    fn serialize_tuple(self, len: usize) -> Result<SeqSer, Error> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_struct(self, _: &'static str, len: usize) -> Result<SeqSer, Error> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<VariantSeqSer, Error> {
+        Ok(VariantSeqSer {
+            variant,
+            items: Vec::with_capacity(len),
+        })
+    }
+    fn serialize_map(self, len: Option<usize>) -> Result<MapSer, Error> {
+        Ok(MapSer {
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<StructSer, Error> {
+        Ok(StructSer(BTreeMap::new()))
+    }
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<VariantStructSer, Error> {
+        Ok(VariantStructSer {
+            variant,
+            fields: BTreeMap::new(),
+        })
+    }
+}
+
+impl ser::SerializeSeq for SeqSer {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Error> {
+        self.0.push(v.serialize(ValueSerializer)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Array(self.0))
+    }
+}
+
+impl ser::SerializeTuple for SeqSer {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, v)
+    }
+    fn end(self) -> Result<Value, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSer {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, v)
+    }
+    fn end(self) -> Result<Value, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleVariant for VariantSeqSer {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Error> {
+        self.items.push(v.serialize(ValueSerializer)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Value, Error> {
+        Ok(tagged(self.variant, Value::Array(self.items)))
+    }
+}
+
+impl ser::SerializeMap for MapSer {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, k: &T) -> Result<(), Error> {
+        self.key = Some(k.serialize(ValueSerializer)?);
+        Ok(())
+    }
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Error> {
+        let Some(key) = self.key.take() else {
+            return err("map value without a key");
+        };
+        self.entries.push((key, v.serialize(ValueSerializer)?));
+        Ok(())
+    }
+    fn end(self) -> Result<Value, Error> {
+        if self.entries.iter().all(|(k, _)| matches!(k, Value::Str(_))) {
+            let mut fields = BTreeMap::new();
+            for (k, v) in self.entries {
+                let Value::Str(k) = k else { unreachable!() };
+                if fields.insert(k, v).is_some() {
+                    return err("duplicate map key");
+                }
+            }
+            return Ok(Value::Object(fields));
+        }
+        let mut pairs: Vec<(Vec<u8>, Value, Value)> = self
+            .entries
+            .into_iter()
+            .map(|(k, v)| {
+                let mut enc = Vec::new();
+                write(&k, &mut enc);
+                (enc, k, v)
+            })
+            .collect();
+        pairs.sort_by(|a, b| a.0.cmp(&b.0));
+        if pairs.windows(2).any(|w| w[0].0 == w[1].0) {
+            return err("duplicate map key");
+        }
+        Ok(Value::Array(
+            pairs
+                .into_iter()
+                .map(|(_, k, v)| Value::Array(vec![k, v]))
+                .collect(),
+        ))
+    }
+}
+
+impl ser::SerializeStruct for StructSer {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        v: &T,
+    ) -> Result<(), Error> {
+        self.0
+            .insert(key.to_string(), v.serialize(ValueSerializer)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Object(self.0))
+    }
+}
+
+impl ser::SerializeStructVariant for VariantStructSer {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        v: &T,
+    ) -> Result<(), Error> {
+        self.fields
+            .insert(key.to_string(), v.serialize(ValueSerializer)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Value, Error> {
+        Ok(tagged(self.variant, Value::Object(self.fields)))
+    }
+}
+
+// ---- parser ----
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), Error> {
+        if self.peek() != Some(b) {
+            return err(format!("expected '{}' at byte {}", b as char, self.pos));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn literal(&mut self, lit: &[u8], v: Value) -> Result<Value, Error> {
+        if !self.bytes[self.pos..].starts_with(lit) {
+            return err(format!("invalid literal at byte {}", self.pos));
+        }
+        self.pos += lit.len();
+        Ok(v)
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, Error> {
+        if depth > MAX_DEPTH {
+            return err("nested too deeply");
+        }
+        match self.peek() {
+            Some(b'n') => self.literal(b"null", Value::Null),
+            Some(b't') => self.literal(b"true", Value::Bool(true)),
+            Some(b'f') => self.literal(b"false", Value::Bool(false)),
+            Some(b'"') => Ok(Value::Str(self.string()?)),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                    return Ok(Value::Array(items));
+                }
+                loop {
+                    items.push(self.value(depth + 1)?);
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(Value::Array(items));
+                        }
+                        _ => return err(format!("expected ',' or ']' at byte {}", self.pos)),
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                let mut fields = BTreeMap::new();
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                    return Ok(Value::Object(fields));
+                }
+                loop {
+                    let key = self.string()?;
+                    self.expect(b':')?;
+                    if fields.insert(key, self.value(depth + 1)?).is_some() {
+                        return err("duplicate object key");
+                    }
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => {
+                            self.pos += 1;
+                            return Ok(Value::Object(fields));
+                        }
+                        _ => return err(format!("expected ',' or '}}' at byte {}", self.pos)),
+                    }
+                }
+            }
+            _ => err(format!("unexpected input at byte {}", self.pos)),
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, Error> {
+        let neg = self.peek() == Some(b'-');
+        if neg {
+            self.pos += 1;
+        }
+        let start = self.pos;
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.pos += 1;
+        }
+        if matches!(self.peek(), Some(b'.' | b'e' | b'E')) {
+            return err("only integers are allowed");
+        }
+        let digits = std::str::from_utf8(&self.bytes[start..self.pos]).unwrap_or("");
+        let abs: u128 = digits
+            .parse()
+            .map_err(|_| Error(format!("invalid integer at byte {start}")))?;
+        if neg && abs > 1u128 << 127 {
+            return err("integer out of range");
+        }
+        // Leading zeros and "-0" are caught by the re-encode check.
+        Ok(Value::Int {
+            neg: neg && abs != 0,
+            abs,
+        })
+    }
+
+    fn hex4(&mut self) -> Result<u32, Error> {
+        let raw = self
+            .bytes
+            .get(self.pos..self.pos + 4)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u32::from_str_radix(h, 16).ok())
+            .ok_or_else(|| Error(format!("invalid \\u escape at byte {}", self.pos)))?;
+        self.pos += 4;
+        Ok(raw)
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.expect(b'"')?;
+        let mut out = Vec::new();
+        loop {
+            let Some(b) = self.peek() else {
+                return err("unterminated string");
+            };
+            self.pos += 1;
+            match b {
+                b'"' => break,
+                b'\\' => {
+                    let Some(e) = self.peek() else {
+                        return err("unterminated string");
+                    };
+                    self.pos += 1;
+                    let c = match e {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            let hi = self.hex4()?;
+                            let code = if (0xd800..0xdc00).contains(&hi) {
+                                self.expect(b'\\')?;
+                                self.expect(b'u')?;
+                                let lo = self.hex4()?;
+                                if !(0xdc00..0xe000).contains(&lo) {
+                                    return err("invalid surrogate pair");
+                                }
+                                0x10000 + ((hi - 0xd800) << 10) + (lo - 0xdc00)
+                            } else {
+                                hi
+                            };
+                            char::from_u32(code)
+                                .ok_or_else(|| Error("invalid \\u escape".into()))?
+                        }
+                        _ => return err(format!("invalid escape at byte {}", self.pos - 1)),
+                    };
+                    let mut buf = [0u8; 4];
+                    out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+                b if b < 0x20 => return err("unescaped control character"),
+                b => out.push(b),
+            }
+        }
+        String::from_utf8(out).map_err(|_| Error("invalid UTF-8".into()))
+    }
+}
+
+// ---- deserializer ----
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::Int { neg: false, abs } => match u64::try_from(abs) {
+                Ok(v) => visitor.visit_u64(v),
+                Err(_) => visitor.visit_u128(abs),
+            },
+            Value::Int { neg: true, abs } => match i64::try_from(abs).ok().map(|v| -v) {
+                Some(v) => visitor.visit_i64(v),
+                // `abs` may be exactly 2^127 (i128::MIN).
+                None => visitor.visit_i128(0i128.wrapping_sub_unsigned(abs)),
+            },
+            Value::Str(s) => visitor.visit_string(s),
+            Value::Array(items) => {
+                visitor.visit_seq(de::value::SeqDeserializer::new(items.into_iter()))
+            }
+            Value::Object(fields) => {
+                visitor.visit_map(de::value::MapDeserializer::new(fields.into_iter()))
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Value::Null => visitor.visit_none(),
+            v => visitor.visit_some(v),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Value::Str(s) => visitor.visit_byte_buf(
+                hex::decode(&s).map_err(|_| Error("bytes must be lowercase hex".into()))?,
+            ),
+            v => v.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Value::Array(pairs) => {
+                let mut entries = Vec::with_capacity(pairs.len());
+                for pair in pairs {
+                    match pair {
+                        Value::Array(kv) if kv.len() == 2 => {
+                            let mut kv = kv.into_iter();
+                            entries.push((
+                                kv.next().unwrap_or(Value::Null),
+                                kv.next().unwrap_or(Value::Null),
+                            ));
+                        }
+                        _ => return err("map entries must be [key, value] pairs"),
+                    }
+                }
+                visitor.visit_map(de::value::MapDeserializer::new(entries.into_iter()))
+            }
+            v => v.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self {
+            Value::Str(variant) => visitor.visit_enum(variant.into_deserializer()),
+            Value::Object(fields) if fields.len() == 1 => match fields.into_iter().next() {
+                Some((variant, payload)) => visitor.visit_enum(EnumAccess { variant, payload }),
+                None => err("empty enum object"),
+            },
+            _ => err("enum must be a variant name or a single-key object"),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct seq tuple tuple_struct struct identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Value {
+    type Deserializer = Self;
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+struct EnumAccess {
+    variant: String,
+    payload: Value,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccess {
+    type Error = Error;
+    type Variant = Value;
+    fn variant_seed<S: de::DeserializeSeed<'de>>(
+        self,
+        seed: S,
+    ) -> Result<(S::Value, Value), Error> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, self.payload))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Value {
+    type Error = Error;
+    fn unit_variant(self) -> Result<(), Error> {
+        match self {
+            Value::Null => Ok(()),
+            _ => err("unit variant takes no payload"),
+        }
+    }
+    fn newtype_variant_seed<S: de::DeserializeSeed<'de>>(self, seed: S) -> Result<S::Value, Error> {
+        seed.deserialize(self)
+    }
+    fn tuple_variant<V: Visitor<'de>>(self, _: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
@@ -0,0 +1,229 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Epoch randomness beacon.
+//!
+//! VRF outputs of finalized blocks are hash-chained into a per-epoch accumulator:
+//!
+//! acc' = H( "Amunchain-Beacon-Acc-v1" || acc || height || vrf_output )
+//!
+//! When the last block of an epoch is absorbed, the next epoch's randomness is derived as
+//!
+//! r(e+1) = H( "Amunchain-Beacon-Epoch-v1" || e+1 || r(e) || acc )
+//!
+//! and the accumulator resets. Only finalized blocks are fed in, every height in order, so
+//! every node derives the same value; no single producer can predict it before the epoch's
+//! last block is final.
+//!
+//! `ConsensusDriver::with_beacon` absorbs the header of every height it finalizes and keeps
+//! the beacon in a `BeaconStore`; `BlockImporter::with_beacon` checks leader VRF proofs
+//! against the randomness of the block's epoch. Headers reach each node at its own pace, so
+//! the store lives outside the state root: the root must not depend on when a node saw them.
+
+use crate::core::consensus::hydro::HydroConfig;
+use crate::core::state::persistent_state::{PersistentState, StateError};
+use crate::core::types::{decode_canonical_limited, encode_canonical, Epoch, Height};
+use ring::digest;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Tree of the state database holding the beacon, outside the state root.
+const BEACON_TREE: &str = "beacon";
+const CURRENT_KEY: &[u8] = b"current";
+const EPOCH_PREFIX: &[u8] = b"epoch/";
+
+const ACC_DOMAIN: &[u8] = b"Amunchain-Beacon-Acc-v1";
+const EPOCH_DOMAIN: &[u8] = b"Amunchain-Beacon-Epoch-v1";
+
+/// Upper bound for the encoded beacon.
+const MAX_BEACON_BYTES: usize = 256;
+
+/// Beacon errors.
+#[derive(Debug, Error)]
+pub enum BeaconError {
+    #[error("epoch length must be non-zero")]
+    BadEpochLength,
+    #[error("height does not advance")]
+    NonMonotonicHeight,
+    /// A finalized height was skipped; the node must absorb it before going on.
+    #[error("expected height {expected}, got {got}")]
+    NonContiguousHeight { expected: Height, got: Height },
+    #[error("state")]
+    State,
+}
+
+impl From<StateError> for BeaconError {
+    fn from(_: StateError) -> Self {
+        BeaconError::State
+    }
+}
+
+/// Beacon state (persisted).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RandomnessBeacon {
+    /// Blocks per epoch.
+    pub epoch_length: u64,
+    /// Current epoch (1-based; 0 is reserved for legacy messages).
+    pub epoch: Epoch,
+    /// Randomness in effect for `epoch`.
+    pub randomness: [u8; 32],
+    /// Running accumulator over this epoch's VRF outputs.
+    pub accumulator: [u8; 32],
+    /// Last absorbed finalized height.
+    pub last_height: Height,
+}
+
+fn sha256(parts: &[&[u8]]) -> [u8; 32] {
+    let mut ctx = digest::Context::new(&digest::SHA256);
+    for p in parts {
+        ctx.update(p);
+    }
+    let d = ctx.finish();
+    let mut out = [0u8; 32];
+    out.copy_from_slice(d.as_ref());
+    out
+}
+
+impl RandomnessBeacon {
+    /// Start at epoch 1 from genesis randomness (`HydroConfig.epoch_randomness`).
+    pub fn genesis(epoch_length: u64, genesis_randomness: [u8; 32]) -> Result<Self, BeaconError> {
+        if epoch_length == 0 {
+            return Err(BeaconError::BadEpochLength);
+        }
+        Ok(Self {
+            epoch_length,
+            epoch: Epoch(1),
+            randomness: genesis_randomness,
+            accumulator: [0u8; 32],
+            last_height: Height::ZERO,
+        })
+    }
+
+    /// Epoch a height belongs to (heights start at 1).
+    pub fn epoch_of(&self, height: Height) -> Epoch {
+        Epoch(height.get().saturating_sub(1) / self.epoch_length + 1)
+    }
+
+    /// Absorb the VRF output of a finalized block.
+    ///
+    /// `height` must directly follow `last_height`: a skipped block would leave this node with
+    /// a different accumulator than its peers, or miss an epoch rollover altogether.
+    ///
+    /// Returns the new randomness if `height` closed the epoch.
+    pub fn absorb(
+        &mut self,
+        height: Height,
+        vrf_output: &[u8; 32],
+    ) -> Result<Option<[u8; 32]>, BeaconError> {
+        if height <= self.last_height {
+            return Err(BeaconError::NonMonotonicHeight);
+        }
+        let expected = self.last_height.saturating_add(1);
+        if height != expected {
+            return Err(BeaconError::NonContiguousHeight {
+                expected,
+                got: height,
+            });
+        }
+        self.accumulator = sha256(&[
+            ACC_DOMAIN,
+            &self.accumulator,
+            &height.to_be_bytes(),
+            vrf_output,
+        ]);
+        self.last_height = height;
+
+        if height.get() % self.epoch_length != 0 {
+            return Ok(None);
+        }
+
+        let next_epoch = self.epoch_of(height).saturating_add(1);
+        self.randomness = sha256(&[
+            EPOCH_DOMAIN,
+            &next_epoch.to_be_bytes(),
+            &self.randomness,
+            &self.accumulator,
+        ]);
+        self.epoch = next_epoch;
+        self.accumulator = [0u8; 32];
+        Ok(Some(self.randomness))
+    }
+
+    /// Install the current randomness into a Hydro config.
+    pub fn apply(&self, hydro: &mut HydroConfig) {
+        hydro.epoch_randomness = self.randomness;
+    }
+}
+
+/// Beacon persisted in the `beacon` tree of a state database, with the randomness of every
+/// epoch it reached, so blocks of past epochs can still be checked.
+#[derive(Clone)]
+pub struct BeaconStore {
+    tree: sled::Tree,
+}
+
+impl BeaconStore {
+    /// Open the beacon tree inside an existing state database.
+    pub fn open(state: &PersistentState) -> Result<Self, BeaconError> {
+        Ok(Self {
+            tree: state.open_tree(BEACON_TREE)?,
+        })
+    }
+
+    /// Persist `beacon` and the randomness of its current epoch in one batch.
+    pub fn save(&self, beacon: &RandomnessBeacon) -> Result<(), BeaconError> {
+        let value = encode_canonical(beacon).map_err(|_| BeaconError::State)?;
+        let mut batch = sled::Batch::default();
+        batch.insert(CURRENT_KEY, value);
+        batch.insert(epoch_key(beacon.epoch), beacon.randomness.as_slice());
+        self.tree.apply_batch(batch).map_err(|_| BeaconError::State)
+    }
+
+    /// Load the beacon, if one was saved.
+    pub fn load(&self) -> Result<Option<RandomnessBeacon>, BeaconError> {
+        let Some(raw) = self.tree.get(CURRENT_KEY).map_err(|_| BeaconError::State)? else {
+            return Ok(None);
+        };
+        let b: RandomnessBeacon =
+            decode_canonical_limited(&raw, MAX_BEACON_BYTES).map_err(|_| BeaconError::State)?;
+        if b.epoch_length == 0 {
+            return Err(BeaconError::BadEpochLength);
+        }
+        Ok(Some(b))
+    }
+
+    /// Randomness of the epoch `height` belongs to, if the saved beacon has reached it.
+    pub fn randomness_at(&self, height: Height) -> Result<Option<[u8; 32]>, BeaconError> {
+        match self.load()? {
+            Some(b) => self.randomness(b.epoch_of(height)),
+            None => Ok(None),
+        }
+    }
+
+    /// Randomness of `epoch`, if the saved beacon has reached it.
+    pub fn randomness(&self, epoch: Epoch) -> Result<Option<[u8; 32]>, BeaconError> {
+        let Some(raw) = self
+            .tree
+            .get(epoch_key(epoch))
+            .map_err(|_| BeaconError::State)?
+        else {
+            return Ok(None);
+        };
+        let bytes: [u8; 32] = raw.as_ref().try_into().map_err(|_| BeaconError::State)?;
+        Ok(Some(bytes))
+    }
+}
+
+fn epoch_key(epoch: Epoch) -> Vec<u8> {
+    [EPOCH_PREFIX, epoch.to_be_bytes().as_slice()].concat()
+}
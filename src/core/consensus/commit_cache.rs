@@ -0,0 +1,100 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Cache of commit certificates the driver already verified.
+//!
+//! Every peer relays the same certificate, and each copy would otherwise cost a full quorum
+//! of signature checks. Certificates are keyed by a domain-separated hash of their canonical
+//! encoding, so only a byte-identical copy hits; one with a different signature subset is
+//! verified on its own. A hit is always a duplicate, since the commit was already applied.
+
+use crate::core::types::{encode_canonical, CodecError, Commit, H256};
+use std::collections::{BTreeMap, HashMap};
+
+/// Certificates remembered by default.
+pub const DEFAULT_VERIFIED_COMMITS: usize = 1024;
+
+/// Cache key of a commit certificate.
+pub fn commit_key(commit: &Commit) -> Result<H256, CodecError> {
+    let mut ctx = ring::digest::Context::new(&ring::digest::SHA256);
+    ctx.update(b"Amunchain-VerifiedCommit-v1");
+    ctx.update(&encode_canonical(commit)?);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(ctx.finish().as_ref());
+    Ok(H256::from_bytes(out))
+}
+
+/// Least-recently-used set of verified certificate keys.
+#[derive(Clone, Debug)]
+pub struct VerifiedCommits {
+    capacity: usize,
+    tick: u64,
+    // key -> last use, and last use -> key for eviction in use order.
+    keys: HashMap<H256, u64>,
+    by_use: BTreeMap<u64, H256>,
+}
+
+impl VerifiedCommits {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            tick: 0,
+            keys: HashMap::new(),
+            by_use: BTreeMap::new(),
+        }
+    }
+
+    /// True if `key` is cached; marks it as recently used.
+    pub fn touch(&mut self, key: &H256) -> bool {
+        let Some(last) = self.keys.get(key).copied() else {
+            return false;
+        };
+        self.by_use.remove(&last);
+        self.use_now(*key);
+        true
+    }
+
+    /// Remember `key`, evicting the least recently used key when full.
+    pub fn insert(&mut self, key: H256) {
+        if self.touch(&key) {
+            return;
+        }
+        while self.keys.len() >= self.capacity {
+            let Some((_, old)) = self.by_use.pop_first() else {
+                break;
+            };
+            self.keys.remove(&old);
+        }
+        self.use_now(key);
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    fn use_now(&mut self, key: H256) {
+        self.tick += 1;
+        self.keys.insert(key, self.tick);
+        self.by_use.insert(self.tick, key);
+    }
+}
+
+impl Default for VerifiedCommits {
+    fn default() -> Self {
+        Self::new(DEFAULT_VERIFIED_COMMITS)
+    }
+}
@@ -0,0 +1,198 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Compact commits: a signer bitmap and vote hashes in place of signatures.
+//!
+//! A full commit carries a 32-byte key and a 64-byte signature per signer. In compact mode
+//! (`consensus.compact_commits`) the node gossips `CompactCommit` instead: the commit fields,
+//! a bitmap over the sorted validator set marking the signers, and the SHA-256 of each
+//! signer's vote (`vote_hash`). Every signer's vote has already been gossiped, so receivers
+//! rebuild the full commit from the votes Tide holds (`CompactCommit::expand`) and verify it
+//! like any other; nothing is trusted that a full commit would not prove. Votes a receiver
+//! lacks are asked for with a `VoteRequest` to the peer that relayed the compact commit,
+//! which republishes them on the consensus topic. Once they arrive Tide builds the commit
+//! from the votes itself.
+
+use crate::core::types::{
+    encode_canonical, CanonicalMap, Commit, Epoch, Height, Round, Signature, ValidatorId, Vote,
+    H256,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use thiserror::Error;
+
+/// Most voters one `VoteRequest` may name.
+pub const MAX_REQUESTED_VOTES: usize = 1024;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CompactError {
+    /// The commit has a signer outside the validator set.
+    #[error("signer outside the validator set")]
+    UnknownSigner,
+    /// The bitmap does not fit the validator set, or the hashes do not match its signers.
+    #[error("signer bitmap does not match the validator set")]
+    Bitmap,
+    #[error("encoding")]
+    Codec,
+    /// Votes of these signers are not held locally, or differ from the ones committed.
+    #[error("{} votes missing", .0.len())]
+    Missing(Vec<ValidatorId>),
+}
+
+/// A commit by reference to its votes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactCommit {
+    pub height: Height,
+    pub round: Round,
+    pub epoch: Epoch,
+    pub msg_counter: u64,
+    pub sent_ts_ms: u64,
+    pub ttl_ms: u32,
+    pub block_hash: H256,
+    pub voting_power: u128,
+    pub validator_set_hash: H256,
+    /// Bit `i` (least significant first) is set when the `i`-th validator in sorted order
+    /// signed; `ceil(n / 8)` bytes.
+    pub signers: Vec<u8>,
+    /// `vote_hash` of every signer's vote, in validator order.
+    pub vote_hashes: Vec<H256>,
+}
+
+/// Ask `responder` (peer id bytes) to republish the votes of `voters` for a commit.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoteRequest {
+    pub responder: Vec<u8>,
+    pub height: Height,
+    pub round: Round,
+    pub block_hash: H256,
+    pub voters: Vec<ValidatorId>,
+}
+
+/// What travels on the commit sync topic.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CommitSync {
+    Compact(CompactCommit),
+    Request(VoteRequest),
+}
+
+/// SHA-256 of the canonical encoding of `v`.
+pub fn vote_hash(v: &Vote) -> Result<H256, CompactError> {
+    let bytes = encode_canonical(v).map_err(|_| CompactError::Codec)?;
+    let digest = ring::digest::digest(&ring::digest::SHA256, &bytes);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(digest.as_ref());
+    Ok(H256::from_bytes(out))
+}
+
+impl CompactCommit {
+    /// The compact form of `c` for `validators`.
+    pub fn from_commit(
+        c: &Commit,
+        validators: &BTreeSet<ValidatorId>,
+    ) -> Result<Self, CompactError> {
+        let mut out = Self {
+            height: c.height,
+            round: c.round,
+            epoch: c.epoch,
+            msg_counter: c.msg_counter,
+            sent_ts_ms: c.sent_ts_ms,
+            ttl_ms: c.ttl_ms,
+            block_hash: c.block_hash,
+            voting_power: c.voting_power,
+            validator_set_hash: c.validator_set_hash,
+            signers: vec![0; validators.len().div_ceil(8)],
+            vote_hashes: Vec::with_capacity(c.signatures.len()),
+        };
+        for (i, vid) in validators.iter().enumerate() {
+            if let Some(sig) = c.signatures.get(vid) {
+                out.signers[i / 8] |= 1 << (i % 8);
+                out.vote_hashes
+                    .push(vote_hash(&out.vote(vid, sig.clone()))?);
+            }
+        }
+        if out.vote_hashes.len() != c.signatures.len() {
+            return Err(CompactError::UnknownSigner);
+        }
+        Ok(out)
+    }
+
+    /// Signers named by the bitmap, checked against `validators`.
+    pub fn signers(
+        &self,
+        validators: &BTreeSet<ValidatorId>,
+    ) -> Result<Vec<ValidatorId>, CompactError> {
+        if self.signers.len() != validators.len().div_ceil(8) {
+            return Err(CompactError::Bitmap);
+        }
+        let signers: Vec<ValidatorId> = validators
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.signers[i / 8] & (1 << (i % 8)) != 0)
+            .map(|(_, vid)| vid.clone())
+            .collect();
+        let set_bits: u32 = self.signers.iter().map(|b| b.count_ones()).sum();
+        if set_bits as usize != signers.len() || signers.len() != self.vote_hashes.len() {
+            return Err(CompactError::Bitmap);
+        }
+        Ok(signers)
+    }
+
+    /// The vote `voter` cast for this commit, with `signature`.
+    pub fn vote(&self, voter: &ValidatorId, signature: Signature) -> Vote {
+        Vote {
+            height: self.height,
+            round: self.round,
+            epoch: self.epoch,
+            msg_counter: self.msg_counter,
+            sent_ts_ms: self.sent_ts_ms,
+            ttl_ms: self.ttl_ms,
+            block_hash: self.block_hash,
+            voter: voter.clone(),
+            signature,
+        }
+    }
+
+    /// Rebuild the full commit from locally held votes (`lookup`), or name the signers whose
+    /// votes are missing. The result still has to be verified.
+    pub fn expand(
+        &self,
+        validators: &BTreeSet<ValidatorId>,
+        lookup: impl Fn(&ValidatorId) -> Option<Vote>,
+    ) -> Result<Commit, CompactError> {
+        let mut signatures = CanonicalMap::new();
+        let mut missing = Vec::new();
+        for (vid, hash) in self.signers(validators)?.into_iter().zip(&self.vote_hashes) {
+            match lookup(&vid) {
+                Some(v) if vote_hash(&v)? == *hash => {
+                    signatures.insert(vid, v.signature);
+                }
+                _ => missing.push(vid),
+            }
+        }
+        if !missing.is_empty() {
+            return Err(CompactError::Missing(missing));
+        }
+        Ok(Commit {
+            height: self.height,
+            round: self.round,
+            epoch: self.epoch,
+            msg_counter: self.msg_counter,
+            sent_ts_ms: self.sent_ts_ms,
+            ttl_ms: self.ttl_ms,
+            block_hash: self.block_hash,
+            signatures,
+            voting_power: self.voting_power,
+            validator_set_hash: self.validator_set_hash,
+        })
+    }
+}
@@ -0,0 +1,216 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Hydro PoW difficulty retargeting.
+//!
+//! Every `window` finalized blocks the target is rescaled by how long the window actually
+//! took against `window * block_time_ms`:
+//!
+//! target' = min(pow_limit, target * clamp(actual, expected / k, expected * k) / expected)
+//!
+//! with `k = max_adjustment`. Block timestamps rather than local clocks drive it and the
+//! arithmetic is exact 256-bit integer math, so every node computes the same target. A
+//! larger target is easier (`HydroConfig::verify_difficulty` accepts `hash < target`).
+
+use crate::core::state::persistent_state::{KvOp, PersistentState, StateError};
+use crate::core::types::{decode_canonical_limited, encode_canonical, Height};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// State key for the persisted difficulty.
+pub const DIFFICULTY_KEY: &[u8] = b"consensus/difficulty";
+
+/// Upper bound for the encoded difficulty state.
+const MAX_DIFFICULTY_BYTES: usize = 256;
+
+/// Difficulty errors.
+#[derive(Debug, Error)]
+pub enum DifficultyError {
+    #[error("retarget window, block time and adjustment bound must be non-zero")]
+    BadParams,
+    #[error("target must be non-zero and within the pow limit")]
+    BadTarget,
+    #[error("height does not advance")]
+    NonMonotonicHeight,
+    #[error("state")]
+    State,
+}
+
+impl From<StateError> for DifficultyError {
+    fn from(_: StateError) -> Self {
+        DifficultyError::State
+    }
+}
+
+/// Retarget parameters; identical on every node (part of the chain spec).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetargetParams {
+    /// Blocks per retarget window.
+    pub window: u64,
+    /// Intended time between blocks, in ms.
+    pub block_time_ms: u64,
+    /// Largest factor the target may move by in one retarget.
+    pub max_adjustment: u64,
+    /// Easiest allowed target.
+    pub pow_limit: [u8; 32],
+}
+
+impl RetargetParams {
+    fn validate(&self) -> Result<(), DifficultyError> {
+        if self.window == 0 || self.block_time_ms == 0 || self.max_adjustment == 0 {
+            return Err(DifficultyError::BadParams);
+        }
+        Ok(())
+    }
+
+    /// Time one window should take, in ms.
+    pub fn expected_window_ms(&self) -> u64 {
+        self.window.saturating_mul(self.block_time_ms)
+    }
+}
+
+/// Target for the next window, given the current one and the window's duration.
+pub fn retarget(current: [u8; 32], actual_ms: u64, params: &RetargetParams) -> [u8; 32] {
+    let expected = params.expected_window_ms().max(1);
+    let k = params.max_adjustment.max(1);
+    let actual = actual_ms.clamp((expected / k).max(1), expected.saturating_mul(k));
+    let next = mul_div(current, actual, expected).unwrap_or(params.pow_limit);
+    if next > params.pow_limit {
+        params.pow_limit
+    } else if next == [0u8; 32] {
+        one()
+    } else {
+        next
+    }
+}
+
+fn one() -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out[31] = 1;
+    out
+}
+
+// target * num / den over big-endian 256-bit integers; None on overflow.
+fn mul_div(target: [u8; 32], num: u64, den: u64) -> Option<[u8; 32]> {
+    let mut limbs = [0u64; 4];
+    for (i, l) in limbs.iter_mut().enumerate() {
+        let mut b = [0u8; 8];
+        b.copy_from_slice(&target[i * 8..i * 8 + 8]);
+        *l = u64::from_be_bytes(b);
+    }
+    // Product, most significant limb first.
+    let mut wide = [0u64; 5];
+    let mut carry = 0u128;
+    for i in (0..4).rev() {
+        let p = u128::from(limbs[i]) * u128::from(num) + carry;
+        wide[i + 1] = p as u64;
+        carry = p >> 64;
+    }
+    wide[0] = carry as u64;
+
+    let mut rem = 0u128;
+    for l in wide.iter_mut() {
+        let cur = (rem << 64) | u128::from(*l);
+        *l = (cur / u128::from(den)) as u64;
+        rem = cur % u128::from(den);
+    }
+    if wide[0] != 0 {
+        return None;
+    }
+    let mut out = [0u8; 32];
+    for (i, l) in wide[1..].iter().enumerate() {
+        out[i * 8..i * 8 + 8].copy_from_slice(&l.to_be_bytes());
+    }
+    Some(out)
+}
+
+/// Current target and the window being measured (persisted).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DifficultyState {
+    /// Target in effect.
+    pub target: [u8; 32],
+    /// Timestamp of the block that opened the current window (genesis time at first).
+    pub window_start_ms: u64,
+    /// Last absorbed finalized height.
+    pub last_height: Height,
+}
+
+impl DifficultyState {
+    /// Start from `initial_target` at genesis.
+    pub fn genesis(
+        initial_target: [u8; 32],
+        genesis_time_ms: u64,
+        params: &RetargetParams,
+    ) -> Result<Self, DifficultyError> {
+        params.validate()?;
+        if initial_target == [0u8; 32] || initial_target > params.pow_limit {
+            return Err(DifficultyError::BadTarget);
+        }
+        Ok(Self {
+            target: initial_target,
+            window_start_ms: genesis_time_ms,
+            last_height: Height::ZERO,
+        })
+    }
+
+    /// Absorb a finalized block and its timestamp.
+    ///
+    /// Returns the new target if `height` closed a window.
+    pub fn on_block(
+        &mut self,
+        height: Height,
+        timestamp_ms: u64,
+        params: &RetargetParams,
+    ) -> Result<Option<[u8; 32]>, DifficultyError> {
+        params.validate()?;
+        if height <= self.last_height {
+            return Err(DifficultyError::NonMonotonicHeight);
+        }
+        self.last_height = height;
+        if height.get() % params.window != 0 {
+            return Ok(None);
+        }
+        let actual = timestamp_ms.saturating_sub(self.window_start_ms);
+        self.target = retarget(self.target, actual, params);
+        self.window_start_ms = timestamp_ms;
+        Ok(Some(self.target))
+    }
+
+    /// State operation persisting the difficulty.
+    pub fn to_op(&self) -> Result<KvOp, DifficultyError> {
+        let value = encode_canonical(self).map_err(|_| DifficultyError::State)?;
+        Ok(KvOp::Put {
+            key: DIFFICULTY_KEY.to_vec(),
+            value,
+        })
+    }
+
+    /// Persist the difficulty.
+    pub fn commit(&self, state: &PersistentState) -> Result<(), DifficultyError> {
+        state.commit_atomic(vec![self.to_op()?])?;
+        Ok(())
+    }
+
+    /// Load the difficulty from state, if present.
+    pub fn load(state: &PersistentState) -> Result<Option<Self>, DifficultyError> {
+        let Some(raw) = state.get(DIFFICULTY_KEY)? else {
+            return Ok(None);
+        };
+        let d: Self = decode_canonical_limited(&raw, MAX_DIFFICULTY_BYTES)
+            .map_err(|_| DifficultyError::State)?;
+        if d.target == [0u8; 32] {
+            return Err(DifficultyError::BadTarget);
+        }
+        Ok(Some(d))
+    }
+}
@@ -13,22 +13,148 @@
 
 //! Consensus driver wiring for inbound messages.
 
-use crate::core::consensus::tide::{NoopSlashing, TideConfig, TideFinalizer};
-use crate::core::types::{ConsensusMsg, ValidatorId};
+use crate::core::consensus::beacon::{BeaconStore, RandomnessBeacon};
+use crate::core::consensus::commit_cache::{commit_key, VerifiedCommits};
+use crate::core::consensus::compact::{CommitSync, CompactCommit, CompactError, VoteRequest};
+use crate::core::consensus::events::{ChainEvent, ChainEvents};
+use crate::core::consensus::evidence::EvidencePool;
+use crate::core::consensus::import::ChainView;
+use crate::core::consensus::liveness::{
+    ChainLiveness, LivenessPolicy, LivenessTracker, ValidatorUptime,
+};
+use crate::core::consensus::pending::{Admission, PendingBuffer, PendingConfig};
+use crate::core::consensus::quorum::QuorumRule;
+use crate::core::consensus::signing::validator_set_hash_weighted;
+use crate::core::consensus::slot_clock::SlotClock;
+use crate::core::consensus::tide::{
+    staking_power, NoopSlashing, TideConfig, TideError, TideFinalizer,
+};
+use crate::core::consensus::vote_timing::{CommitTimings, ValidatorPerformance, VoteTimings};
+use crate::core::economics::bank::Bank;
+use crate::core::economics::staking::{Offense, StakingError, StakingLedger, UnjailRequest};
+use crate::core::state::commit_store::{CommitStore, FinalityProof};
+use crate::core::state::persistent_state::PersistentState;
+use crate::core::types::{Commit, ConsensusMsg, Evidence, Height, Round, ValidatorId, Vote, H256};
+use crate::monitoring::metrics::Metrics;
+use crate::node::channel::Sender;
 use std::collections::BTreeSet;
+use std::sync::Arc;
 use thiserror::Error;
+use tokio::sync::watch;
+use tracing::{info, warn, Span};
 
 /// Driver errors.
 #[derive(Debug, Error)]
 pub enum DriverError {
     #[error("invalid validator set")]
     InvalidValidators,
+    #[error("commit store unavailable")]
+    NoCommitStore,
+    #[error("state")]
+    State,
+    #[error("staking: {0}")]
+    Staking(#[from] StakingError),
+    /// Included evidence that does not verify.
+    #[error("evidence: {0}")]
+    Evidence(TideError),
+    /// Included commit that does not verify.
+    #[error("commit: {0}")]
+    Commit(TideError),
+}
+
+/// What the driver did with an inbound message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MsgOutcome {
+    /// Applied, or buffered until the local view catches up.
+    Accepted,
+    /// Already known: the same vote is recorded, or the commit's height is finalized.
+    Duplicate,
+    /// Refused; the reason is also counted in `amunchain_consensus_msgs_rejected_total`.
+    Rejected(TideError),
+    /// A local vote not cast because fewer than `min_consensus_peers` peers are connected
+    /// (see `ConsensusDriver::with_min_consensus_peers`).
+    Withheld,
+}
+
+/// Validator set rotation at epoch boundaries.
+#[derive(Clone, Copy, Debug)]
+pub struct EpochRotation {
+    /// Finalized heights per epoch.
+    pub epoch_length: u64,
+    /// Largest active set (see `StakingLedger::active_set`).
+    pub max_validators: usize,
+}
+
+/// Read-only view of the driver, republished whenever it changes (see
+/// `ConsensusDriver::snapshots`), so readers such as the HTTP API never take the driver lock.
+#[derive(Clone, Debug, Default)]
+pub struct ConsensusSnapshot {
+    /// Highest finalized height.
+    pub finalized_height: Height,
+    /// Size of the active set.
+    pub active_validators: usize,
+    /// Latest commit finalized here (with any late votes), or stored for `finalized_height`.
+    pub last_commit: Option<Commit>,
+    /// `LivenessTracker::report`.
+    pub liveness: Vec<ValidatorUptime>,
+    /// `VoteTimings::report`.
+    pub performance: Vec<ValidatorPerformance>,
+    /// Randomness beacon, if one is attached (see `ConsensusDriver::with_beacon`).
+    pub beacon: Option<RandomnessBeacon>,
+}
+
+/// Randomness beacon fed from finalized headers.
+struct BeaconFeed {
+    beacon: RandomnessBeacon,
+    headers: Box<dyn ChainView>,
+    store: Option<BeaconStore>,
+}
+
+impl BeaconFeed {
+    fn save(&self, height: Height) {
+        if let Some(Err(e)) = self.store.as_ref().map(|s| s.save(&self.beacon)) {
+            warn!(?e, height = height.get(), "failed to persist beacon");
+        }
+    }
 }
 
 /// Top-level consensus driver.
 pub struct ConsensusDriver {
     /// Tide finality gadget.
     pub tide: TideFinalizer<NoopSlashing>,
+    /// Finalized-round participation per validator.
+    pub liveness: LivenessTracker,
+    /// Where `liveness` is persisted after every recorded commit.
+    liveness_state: Option<PersistentState>,
+    /// Auto-jail policy for `apply_included_commit`.
+    liveness_policy: LivenessPolicy,
+    /// Configured validator set, or the ledger's active set once `sync_staking` ran.
+    validators: BTreeSet<ValidatorId>,
+    pending: PendingBuffer,
+    rotation: Option<EpochRotation>,
+    rotated_epoch: u64,
+    commits: Option<CommitStore>,
+    metrics: Option<Arc<Metrics>>,
+    events: Option<ChainEvents>,
+    outbound: Option<Sender<ConsensusMsg>>,
+    evidence_outbound: Option<Sender<Evidence>>,
+    sync_outbound: Option<Sender<CommitSync>>,
+    /// Own commits go out as `CompactCommit`s.
+    compact_commits: bool,
+    evidence: EvidencePool,
+    verified: VerifiedCommits,
+    timings: VoteTimings,
+    slot_clock: Option<SlotClock>,
+    /// Highest height a vote was accepted for (see `ChainEvent::NewHead`).
+    head: Height,
+    /// Local votes are cast at all (off for non-validator roles).
+    local_votes: bool,
+    /// Local votes are withheld below this many connected peers (0 => never).
+    min_consensus_peers: usize,
+    connected_peers: usize,
+    last_commit: Option<Commit>,
+    beacon: Option<BeaconFeed>,
+    snapshot: watch::Sender<Arc<ConsensusSnapshot>>,
 }
 
 impl ConsensusDriver {
@@ -37,21 +163,918 @@ impl ConsensusDriver {
         if validators.is_empty() {
             return Err(DriverError::InvalidValidators);
         }
-        let cfg = TideConfig::new(validators);
+        let cfg = TideConfig::new(validators.clone());
+        let (snapshot, _) = watch::channel(Arc::new(ConsensusSnapshot {
+            active_validators: validators.len(),
+            ..ConsensusSnapshot::default()
+        }));
         Ok(Self {
             tide: TideFinalizer::new(cfg, NoopSlashing),
+            liveness: LivenessTracker::new(),
+            liveness_state: None,
+            liveness_policy: LivenessPolicy::default(),
+            validators,
+            pending: PendingBuffer::new(PendingConfig::default()),
+            rotation: None,
+            rotated_epoch: 0,
+            commits: None,
+            metrics: None,
+            events: None,
+            outbound: None,
+            evidence_outbound: None,
+            sync_outbound: None,
+            compact_commits: false,
+            evidence: EvidencePool::default(),
+            verified: VerifiedCommits::default(),
+            timings: VoteTimings::default(),
+            slot_clock: None,
+            head: Height::ZERO,
+            local_votes: true,
+            min_consensus_peers: 0,
+            connected_peers: 0,
+            last_commit: None,
+            beacon: None,
+            snapshot,
         })
     }
 
-    /// Handle inbound consensus message.
-    pub fn on_msg(&mut self, msg: ConsensusMsg) {
-        match msg {
+    /// Snapshots of the driver, updated as it changes.
+    pub fn snapshots(&self) -> watch::Receiver<Arc<ConsensusSnapshot>> {
+        self.snapshot.subscribe()
+    }
+
+    /// Commit store attached with `with_commit_store`.
+    pub fn commit_store(&self) -> Option<&CommitStore> {
+        self.commits.as_ref()
+    }
+
+    /// Persist the liveness counters to the `liveness` tree of `state` (outside the state root)
+    /// whenever a commit is recorded, so uptime and miss streaks survive restarts (load them
+    /// with `LivenessTracker::load`).
+    pub fn with_liveness_state(mut self, state: PersistentState) -> Self {
+        self.liveness_state = Some(state);
+        self
+    }
+
+    /// Replace the liveness tracker (e.g. one loaded from state).
+    pub fn with_liveness(mut self, liveness: LivenessTracker) -> Self {
+        self.liveness = liveness;
+        self.publish_snapshot();
+        self
+    }
+
+    /// Feed `beacon` the VRF output of every finalized block, looked up in `headers` by the
+    /// commit's block hash (earlier heights by the commit store's), and save it to `store`
+    /// now and whenever it moves. Leader election reads the randomness of each epoch from
+    /// the store (see `BlockImporter::with_beacon`).
+    pub fn with_beacon(
+        mut self,
+        beacon: RandomnessBeacon,
+        headers: Box<dyn ChainView>,
+        store: Option<BeaconStore>,
+    ) -> Self {
+        if let Some(Err(e)) = store.as_ref().map(|s| s.save(&beacon)) {
+            warn!(?e, "failed to persist beacon");
+        }
+        self.beacon = Some(BeaconFeed {
+            beacon,
+            headers,
+            store,
+        });
+        self.publish_snapshot();
+        self
+    }
+
+    /// Randomness beacon attached with `with_beacon`.
+    pub fn beacon(&self) -> Option<&RandomnessBeacon> {
+        self.beacon.as_ref().map(|f| &f.beacon)
+    }
+
+    /// Auto-jail policy applied to included commits (see `apply_included_commit`).
+    pub fn with_liveness_policy(mut self, policy: LivenessPolicy) -> Self {
+        self.liveness_policy = policy;
+        self
+    }
+
+    /// Weigh votes by stake (self stake plus delegations) instead of counting validators.
+    pub fn with_staking(mut self, ledger: &StakingLedger) -> Self {
+        self.tide
+            .set_voting_power(Some(staking_power(ledger, &self.validators)));
+        self
+    }
+
+    /// Require v3 signatures for `chain_id`, accepting legacy ones up to `legacy_until`.
+    pub fn with_chain_id(mut self, chain_id: &str, legacy_until: Option<Height>) -> Self {
+        self.tide.set_chain_id(chain_id, legacy_until);
+        self
+    }
+
+    /// Commit with `rule` (`consensus.quorum`) instead of the default two thirds.
+    pub fn with_quorum(mut self, rule: Arc<dyn QuorumRule>) -> Self {
+        self.tide.set_quorum(rule);
+        self
+    }
+
+    /// Rotate onto the staking ledger's active set at epoch boundaries
+    /// (see `on_epoch_boundary`). A zero epoch length is treated as one.
+    pub fn with_epoch_rotation(mut self, mut rotation: EpochRotation) -> Self {
+        rotation.epoch_length = rotation.epoch_length.max(1);
+        self.rotation = Some(rotation);
+        self
+    }
+
+    /// Replace the early-vote buffer limits.
+    pub fn with_pending(mut self, cfg: PendingConfig) -> Self {
+        self.pending = PendingBuffer::new(cfg);
+        self
+    }
+
+    /// Resume after a restart: treat `height` as already finalized.
+    pub fn with_finalized_height(mut self, height: Height) -> Self {
+        self.tide.mark_finalized(height);
+        self.head = self.head.max(height);
+        self.update_buffer_metrics();
+        if let Some(store) = self.commits.as_ref() {
+            self.last_commit = store.get(height.get()).ok().flatten().map(|p| p.commit);
+        }
+        self.publish_snapshot();
+        self
+    }
+
+    /// Publish new heads, finalized commits and validator set changes here.
+    pub fn with_events(mut self, events: ChainEvents) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Broadcast commits this driver assembles from votes here (usually `P2pNode::outbound`).
+    /// Commits received from peers are not re-sent; gossip already relays them.
+    pub fn with_outbound(mut self, outbound: Sender<ConsensusMsg>) -> Self {
+        self.outbound = Some(outbound);
+        self
+    }
+
+    /// Broadcast evidence of double votes this driver detects here (usually
+    /// `P2pNode::evidence_outbound`). Evidence received from peers is not re-sent.
+    pub fn with_evidence_outbound(mut self, outbound: Sender<Evidence>) -> Self {
+        self.evidence_outbound = Some(outbound);
+        self
+    }
+
+    /// Send compact commits and vote requests here (usually `P2pNode::commit_sync_outbound`).
+    /// Also needed to answer vote requests from peers.
+    pub fn with_commit_sync_outbound(mut self, outbound: Sender<CommitSync>) -> Self {
+        self.sync_outbound = Some(outbound);
+        self
+    }
+
+    /// Broadcast assembled commits as `CompactCommit`s on the commit sync channel instead of
+    /// in full (see `consensus::compact`). Every node of the network must read them.
+    pub fn with_compact_commits(mut self, enabled: bool) -> Self {
+        self.compact_commits = enabled;
+        self
+    }
+
+    /// Offenses recorded from local detection and verified peer evidence.
+    pub fn evidence(&self) -> &EvidencePool {
+        &self.evidence
+    }
+
+    /// Feed the send timestamps of verified votes to this clock's drift estimate.
+    pub fn with_slot_clock(mut self, clock: SlotClock) -> Self {
+        self.slot_clock = Some(clock);
+        self
+    }
+
+    /// Withhold local votes while fewer than `min` peers are connected
+    /// (`consensus.min_consensus_peers`), so a validator cut off from the network does not sign
+    /// for its partitioned view. Messages from peers are still verified and stored. Peer counts
+    /// come from `set_connected_peers`; until the first one the node counts as isolated.
+    pub fn with_min_consensus_peers(mut self, min: usize) -> Self {
+        self.min_consensus_peers = min;
+        self.update_participation();
+        self
+    }
+
+    /// Whether local votes are cast at all. Nodes that do not vote (`node::role`) still
+    /// verify, store and finalize what peers send; their own votes are `Withheld`.
+    pub fn with_local_votes(mut self, enabled: bool) -> Self {
+        self.local_votes = enabled;
+        self
+    }
+
+    /// Report how many peers are connected.
+    pub fn set_connected_peers(&mut self, peers: usize) {
+        let was = self.is_participating();
+        self.connected_peers = peers;
+        let now = self.is_participating();
+        if was != now {
+            let min = self.min_consensus_peers;
+            if now {
+                info!(peers, min, "enough peers; casting local votes again");
+            } else {
+                warn!(peers, min, "too few peers; withholding local votes");
+            }
+        }
+        self.update_participation();
+    }
+
+    /// Whether local votes are cast (enough peers are connected).
+    pub fn is_participating(&self) -> bool {
+        self.connected_peers >= self.min_consensus_peers
+    }
+
+    fn update_participation(&self) {
+        if let Some(m) = self.metrics.as_ref() {
+            m.consensus_votes_gated
+                .set(i64::from(!self.is_participating()));
+        }
+    }
+
+    /// Recent vote delays per validator (see `vote_timing`).
+    pub fn vote_timings(&self) -> &VoteTimings {
+        &self.timings
+    }
+
+    /// Timing sidecar of a height this node finalized. `Ok(None)` if there is none.
+    pub fn commit_timings(&self, height: u64) -> Result<Option<CommitTimings>, DriverError> {
+        let store = self.commits.as_ref().ok_or(DriverError::NoCommitStore)?;
+        store.timings(height).map_err(|_| DriverError::State)
+    }
+
+    /// Height the driver is currently collecting votes for.
+    pub fn view(&self) -> Height {
+        self.tide.finalized_height().saturating_add(1)
+    }
+
+    /// Votes buffered for later views.
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Persist finalized commits so finality proofs can be served later.
+    pub fn with_commit_store(mut self, commits: CommitStore) -> Self {
+        self.commits = Some(commits);
+        self
+    }
+
+    /// Finality proof for a finalized height: the stored commit plus the hash of the
+    /// validator set that signed it. `Ok(None)` if the height is not finalized locally.
+    pub fn finality_proof(&self, height: u64) -> Result<Option<FinalityProof>, DriverError> {
+        let store = self.commits.as_ref().ok_or(DriverError::NoCommitStore)?;
+        store.get(height).map_err(|_| DriverError::State)
+    }
+
+    /// Attach metrics for liveness reporting and consensus latencies.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.tide.set_metrics(metrics.clone());
+        self.metrics = Some(metrics);
+        self.update_participation();
+        self
+    }
+
+    /// Rotate Tide onto the ledger's active set (capped by `EpochRotation::max_validators`
+    /// when configured). In stake-weighted mode the power table is refreshed too.
+    ///
+    /// Only the ledger is read: this node's uptime counters never change the set (see
+    /// `apply_included_commit`).
+    pub fn sync_staking(&mut self, ledger: &StakingLedger) -> Result<(), DriverError> {
+        let max = self.rotation.map_or(usize::MAX, |r| r.max_validators);
+        let set: BTreeSet<ValidatorId> = ledger
+            .active_set(max)
+            .into_iter()
+            .filter_map(|(id, _)| ValidatorId::try_from(id).ok())
+            .collect();
+        if set.is_empty() {
+            return Err(DriverError::InvalidValidators);
+        }
+        self.validators = set;
+        self.set_active(self.validators.clone());
+        if self.tide.voting_power().is_some() {
+            self.tide
+                .set_voting_power(Some(staking_power(ledger, &self.validators)));
+        }
+        if let Some(m) = self.metrics.as_ref() {
+            let jailed = ledger.validators.values().filter(|v| v.jailed).count();
+            m.consensus_validators_jailed.set(jailed as i64);
+        }
+        self.publish_snapshot();
+        Ok(())
+    }
+
+    /// Record a `commit` included in a finalized block whose timestamp is `block_time_unix`
+    /// in `chain`, and slash the validators the liveness policy finds offline for
+    /// `Offense::Downtime` at the commit's height (burning the stake, see
+    /// `StakingLedger::slash_from`), which jails them. Like `apply_included_evidence`, every
+    /// node applies the same commits with the same time, so `chain`, the ledger and the
+    /// bank, all part of the state root, stay the same everywhere. If the commit does not
+    /// verify against the current set or a slash fails, none of them is touched. The jailed
+    /// validators leave the active set at the next `sync_staking`. Returns them.
+    pub fn apply_included_commit(
+        &self,
+        ledger: &mut StakingLedger,
+        bank: &mut Bank,
+        chain: &mut ChainLiveness,
+        commit: &Commit,
+        block_time_unix: u64,
+    ) -> Result<Vec<ValidatorId>, DriverError> {
+        self.tide
+            .verify_included_commit(commit)
+            .map_err(DriverError::Commit)?;
+        let mut next_chain = chain.clone();
+        let offline =
+            next_chain.record_included(commit, self.tide.validators(), &self.liveness_policy);
+        let (mut next_ledger, mut next_bank) = (ledger.clone(), bank.clone());
+        for v in offline.iter() {
+            next_ledger.slash_from(
+                &mut next_bank,
+                v.as_bytes(),
+                Offense::Downtime,
+                commit.height.get(),
+                block_time_unix,
+            )?;
+        }
+        *ledger = next_ledger;
+        *bank = next_bank;
+        *chain = next_chain;
+        Ok(offline)
+    }
+
+    /// Slash the offenders of `evidence` included in a finalized block whose timestamp is
+    /// `block_time_unix`, each at the height of its offense, burning the stake (see
+    /// `StakingLedger::slash_from`). Every node applies the same evidence with the same
+    /// time, so the ledger and the bank, which are part of the state root, stay the same
+    /// everywhere. All of it is verified first, and the slashes apply to copies: one invalid
+    /// piece or failed slash fails the batch and leaves both untouched. Including a piece
+    /// only once is up to block validity. Returns the total slashed.
+    pub fn apply_included_evidence(
+        &self,
+        ledger: &mut StakingLedger,
+        bank: &mut Bank,
+        evidence: &[Evidence],
+        block_time_unix: u64,
+    ) -> Result<u128, DriverError> {
+        for ev in evidence {
+            self.tide
+                .verify_evidence(ev)
+                .map_err(DriverError::Evidence)?;
+        }
+        let (mut next_ledger, mut next_bank) = (ledger.clone(), bank.clone());
+        let mut slashed: u128 = 0;
+        for ev in evidence {
+            let (height, _) = ev.position();
+            slashed = slashed.saturating_add(next_ledger.slash_from(
+                &mut next_bank,
+                ev.offender().as_bytes(),
+                Offense::DoubleSign,
+                height.get(),
+                block_time_unix,
+            )?);
+        }
+        *ledger = next_ledger;
+        *bank = next_bank;
+        Ok(slashed)
+    }
+
+    /// Call after finality advances: once the finalized height enters a new epoch, apply the
+    /// ledger's active set (via `sync_staking`). Returns true if the set was rotated.
+    pub fn on_epoch_boundary(&mut self, ledger: &StakingLedger) -> Result<bool, DriverError> {
+        let Some(r) = self.rotation else {
+            return Ok(false);
+        };
+        let epoch = self.tide.finalized_height().get() / r.epoch_length;
+        if epoch <= self.rotated_epoch {
+            return Ok(false);
+        }
+        self.sync_staking(ledger)?;
+        self.rotated_epoch = epoch;
+        Ok(true)
+    }
+
+    /// Process a signed unjail request against the ledger and rotate the validator back in.
+    pub fn unjail_staked(
+        &mut self,
+        ledger: &mut StakingLedger,
+        req: &UnjailRequest,
+        now_unix: u64,
+    ) -> Result<(), DriverError> {
+        ledger.unjail(req, now_unix)?;
+        self.sync_staking(ledger)
+    }
+
+    /// Handle a locally produced consensus message. Local votes are `Withheld` on nodes that
+    /// do not vote (see `with_local_votes`) and while the node has too few peers (see
+    /// `with_min_consensus_peers`).
+    pub fn on_msg(&mut self, msg: ConsensusMsg) -> MsgOutcome {
+        if matches!(msg, ConsensusMsg::Vote(_)) && !(self.local_votes && self.is_participating()) {
+            self.record_outcome(MsgOutcome::Withheld);
+            return MsgOutcome::Withheld;
+        }
+        self.on_peer_msg(&[], msg)
+    }
+
+    /// `on_peer_msg` under a `consensus.msg` span linked to `origin`, the span the message
+    /// was sent under (see `channel::Receiver::recv_with_span`).
+    pub fn on_traced_msg(&mut self, peer: &[u8], msg: ConsensusMsg, origin: &Span) -> MsgOutcome {
+        let kind = match &msg {
+            ConsensusMsg::Vote(_) => "vote",
+            ConsensusMsg::Commit(_) => "commit",
+        };
+        let span = tracing::info_span!("consensus.msg", kind);
+        span.follows_from(origin);
+        let _guard = span.enter();
+        self.on_peer_msg(peer, msg)
+    }
+
+    /// Handle an inbound consensus message from `peer` (peer id bytes).
+    ///
+    /// Votes ahead of the local view are buffered (bounded per peer) and replayed once the
+    /// view advances. A buffered vote is `Accepted`; if it is refused on replay the
+    /// rejection is only counted, since the sender has long been answered.
+    pub fn on_peer_msg(&mut self, peer: &[u8], msg: ConsensusMsg) -> MsgOutcome {
+        let (outcome, advanced) = match msg {
             ConsensusMsg::Vote(v) => {
-                let _ = self.tide.process_vote_verified(v);
+                if let Some(m) = self.metrics.as_ref() {
+                    m.consensus_votes_received_total.inc();
+                }
+                if self.tide.has_vote(&v) {
+                    (MsgOutcome::Duplicate, false)
+                } else {
+                    match self.pending.admit(peer, v, self.view()) {
+                        Admission::Ready(v) => match self.process_vote(v) {
+                            Ok(advanced) => (MsgOutcome::Accepted, advanced),
+                            Err(e) => (MsgOutcome::Rejected(e), false),
+                        },
+                        Admission::Buffered => (MsgOutcome::Accepted, false),
+                        Admission::Dropped => {
+                            if let Some(m) = self.metrics.as_ref() {
+                                m.consensus_pending_dropped_total.inc();
+                            }
+                            (MsgOutcome::Rejected(TideError::OutOfWindow), false)
+                        }
+                    }
+                }
             }
             ConsensusMsg::Commit(c) => {
-                let _ = self.tide.process_commit_verified(c);
+                let key = commit_key(&c).ok();
+                if key.is_some_and(|k| self.verified.touch(&k)) {
+                    if let Some(m) = self.metrics.as_ref() {
+                        m.consensus_commit_cache_hits_total.inc();
+                    }
+                    (MsgOutcome::Duplicate, false)
+                } else {
+                    let before = self.tide.finalized_height();
+                    let result = self.tide.process_commit_verified(c.clone());
+                    if let (Ok(()), Some(k)) = (&result, key) {
+                        self.verified.insert(k);
+                    }
+                    match result {
+                        Ok(()) if c.height <= before && !before.is_zero() => {
+                            (MsgOutcome::Duplicate, false)
+                        }
+                        Ok(()) => {
+                            self.on_finalized(&c);
+                            (MsgOutcome::Accepted, self.tide.finalized_height() > before)
+                        }
+                        Err(e) => (MsgOutcome::Rejected(e), false),
+                    }
+                }
+            }
+        };
+        self.record_outcome(outcome);
+        if advanced {
+            self.replay_pending();
+        }
+        self.update_buffer_metrics();
+        outcome
+    }
+
+    fn record_outcome(&self, outcome: MsgOutcome) {
+        let Some(m) = self.metrics.as_ref() else {
+            return;
+        };
+        match outcome {
+            MsgOutcome::Accepted => {}
+            MsgOutcome::Withheld => m.consensus_votes_withheld_total.inc(),
+            MsgOutcome::Duplicate => m.consensus_msgs_duplicate_total.inc(),
+            MsgOutcome::Rejected(e) => m
+                .consensus_msgs_rejected_total
+                .with_label_values(&[e.label()])
+                .inc(),
+        }
+    }
+
+    /// Ok(true) when the vote finalized a height.
+    fn process_vote(&mut self, v: Vote) -> Result<bool, TideError> {
+        let (height, round, block_hash, sent_ts_ms) =
+            (v.height, v.round, v.block_hash, v.sent_ts_ms);
+        let voter = v.voter.clone();
+        let result = self.tide.process_vote_verified(v);
+        if result.is_ok() {
+            self.timings.on_vote(height, round, &voter, now_ms());
+            // A vote for the height just finalized still counts for the voter's liveness.
+            if self.liveness.record_late_vote(height, &voter) {
+                self.on_liveness_changed(height);
+                self.publish_snapshot();
+            }
+        }
+        // Only signed timestamps count: forged ones could push the drift over the limit.
+        if let (Ok(_), Some(clock)) = (&result, self.slot_clock.as_ref()) {
+            clock.observe_peer_time(sent_ts_ms);
+        }
+        if result.is_ok() && height > self.head {
+            self.head = height;
+            self.publish(ChainEvent::new_head(height, round, &block_hash));
+        }
+        match result {
+            Ok(Some(c)) => {
+                self.on_finalized(&c);
+                // Peers echo our own certificate back; no need to verify it then.
+                if let Ok(k) = commit_key(&c) {
+                    self.verified.insert(k);
+                }
+                self.broadcast(c);
+                Ok(true)
+            }
+            Ok(None) => Ok(false),
+            Err(e) => {
+                if e == TideError::DoubleVote {
+                    if let Some(m) = self.metrics.as_ref() {
+                        m.consensus_double_votes_total.inc();
+                    }
+                    for ev in self.tide.take_evidence() {
+                        if self.record_evidence(ev.clone(), "local") {
+                            self.broadcast_evidence(ev);
+                        }
+                    }
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Handle double-vote evidence gossiped by `peer`. Both votes must verify against the
+    /// validator set (`TideFinalizer::verify_evidence`) before the offense is recorded and
+    /// reported to slashing; an offense already recorded is a `Duplicate`.
+    pub fn on_evidence(&mut self, peer: &[u8], ev: Evidence) -> MsgOutcome {
+        let outcome = if self.evidence.contains(&ev) {
+            MsgOutcome::Duplicate
+        } else {
+            match self.tide.process_evidence(&ev) {
+                Ok(()) => {
+                    let (height, _) = ev.position();
+                    warn!(
+                        offender = %hex::encode(ev.offender().as_bytes()),
+                        height = height.get(),
+                        peer = %hex::encode(peer),
+                        "double vote evidence from peer"
+                    );
+                    self.record_evidence(ev, "peer");
+                    MsgOutcome::Accepted
+                }
+                Err(e) => MsgOutcome::Rejected(e),
+            }
+        };
+        self.record_outcome(outcome);
+        outcome
+    }
+
+    /// Handle a message from the commit sync topic. A compact commit whose votes are all held
+    /// is expanded and handled like a full commit; for missing votes a `VoteRequest` goes to
+    /// `peer`, and the commit is built from the votes once they arrive. A vote request is
+    /// answered by republishing the requested votes this node holds.
+    pub fn on_commit_sync(&mut self, peer: &[u8], msg: CommitSync) -> MsgOutcome {
+        let cc = match msg {
+            CommitSync::Compact(cc) => cc,
+            CommitSync::Request(req) => {
+                self.serve_votes(&req);
+                return MsgOutcome::Accepted;
+            }
+        };
+        let finalized = self.tide.finalized_height();
+        // The bitmap indexes our set; read against another one it names the wrong signers.
+        let other_set = cc.validator_set_hash != H256::ZERO
+            && self.tide.validator_set_hash().ok() != Some(cc.validator_set_hash);
+        let outcome = if cc.height <= finalized && !finalized.is_zero() {
+            MsgOutcome::Duplicate
+        } else if other_set {
+            MsgOutcome::Rejected(TideError::ValidatorSetMismatch)
+        } else {
+            match cc.expand(self.tide.validators(), |v| {
+                self.tide.vote(cc.height, cc.round, v)
+            }) {
+                Ok(commit) => {
+                    self.count_sync("compact_expanded", 1);
+                    return self.on_peer_msg(peer, ConsensusMsg::Commit(commit));
+                }
+                Err(CompactError::Missing(voters)) => {
+                    self.request_votes(peer, &cc, voters);
+                    MsgOutcome::Accepted
+                }
+                // Usually a validator set change not seen here yet.
+                Err(CompactError::UnknownSigner | CompactError::Bitmap) => {
+                    MsgOutcome::Rejected(TideError::ValidatorSetMismatch)
+                }
+                Err(CompactError::Codec) => MsgOutcome::Rejected(TideError::Signing),
+            }
+        };
+        self.record_outcome(outcome);
+        outcome
+    }
+
+    fn request_votes(&self, peer: &[u8], cc: &CompactCommit, voters: Vec<ValidatorId>) {
+        let Some(out) = self.sync_outbound.as_ref() else {
+            return;
+        };
+        if peer.is_empty() {
+            return;
+        }
+        let requested = voters.len();
+        let req = VoteRequest {
+            responder: peer.to_vec(),
+            height: cc.height,
+            round: cc.round,
+            block_hash: cc.block_hash,
+            voters,
+        };
+        match out.try_send(CommitSync::Request(req)) {
+            Ok(()) => self.count_sync("votes_requested", requested as u64),
+            Err(e) => warn!(err = %e, height = cc.height.get(), "vote request not sent"),
+        }
+    }
+
+    fn serve_votes(&self, req: &VoteRequest) {
+        let Some(out) = self.outbound.as_ref() else {
+            return;
+        };
+        let mut served = 0;
+        for voter in &req.voters {
+            let Some(v) = self.tide.vote(req.height, req.round, voter) else {
+                continue;
+            };
+            if v.block_hash != req.block_hash {
+                continue;
+            }
+            if let Err(e) = out.try_send(ConsensusMsg::Vote(v)) {
+                warn!(err = %e, height = req.height.get(), "requested vote not sent");
+                break;
+            }
+            served += 1;
+        }
+        self.count_sync("votes_served", served);
+    }
+
+    fn count_sync(&self, event: &str, n: u64) {
+        if let Some(m) = self.metrics.as_ref() {
+            m.consensus_commit_sync_total
+                .with_label_values(&[event])
+                .inc_by(n);
+        }
+    }
+
+    fn record_evidence(&mut self, ev: Evidence, source: &str) -> bool {
+        if !self.evidence.insert(ev) {
+            return false;
+        }
+        if let Some(m) = self.metrics.as_ref() {
+            m.consensus_evidence_total
+                .with_label_values(&[source])
+                .inc();
+            m.consensus_evidence_pool.set(self.evidence.len() as i64);
+        }
+        true
+    }
+
+    // Under the driver lock like `broadcast`; a full channel drops and counts the evidence.
+    fn broadcast_evidence(&self, ev: Evidence) {
+        if let Some(out) = self.evidence_outbound.as_ref() {
+            let (height, _) = ev.position();
+            if let Err(e) = out.try_send(ev) {
+                warn!(err = %e, height = height.get(), "evidence not broadcast");
+            }
+        }
+    }
+
+    // Replaying can finalize further heights, which in turn releases more votes.
+    fn replay_pending(&mut self) {
+        loop {
+            let ready = self.pending.drain_ready(self.view());
+            if ready.is_empty() {
+                return;
+            }
+            let mut advanced = false;
+            for (_, v) in ready {
+                match self.process_vote(v) {
+                    Ok(a) => advanced |= a,
+                    Err(e) => self.record_outcome(MsgOutcome::Rejected(e)),
+                }
+            }
+            if !advanced {
+                return;
+            }
+        }
+    }
+
+    fn update_buffer_metrics(&self) {
+        if let Some(m) = self.metrics.as_ref() {
+            m.consensus_pending_votes.set(self.pending.len() as i64);
+            m.consensus_retained_heights
+                .set(self.tide.retained_heights() as i64);
+            m.consensus_retained_votes
+                .set(self.tide.retained_votes() as i64);
+            m.block_height
+                .set(self.tide.finalized_height().get() as i64);
+            let (round, votes) = self
+                .tide
+                .latest_round(self.view())
+                .unwrap_or((Round::ZERO, 0));
+            m.consensus_round.set(round.get() as i64);
+            m.consensus_round_votes.set(votes as i64);
+        }
+    }
+
+    // Called under the driver lock, so never waits; a full channel drops and counts the commit.
+    fn broadcast(&self, commit: Commit) {
+        if let (true, Some(out)) = (self.compact_commits, self.sync_outbound.as_ref()) {
+            match CompactCommit::from_commit(&commit, self.tide.validators()) {
+                Ok(cc) => {
+                    let height = cc.height.get();
+                    match out.try_send(CommitSync::Compact(cc)) {
+                        Ok(()) => self.count_sync("compact_sent", 1),
+                        Err(e) => warn!(err = %e, height, "compact commit not broadcast"),
+                    }
+                    return;
+                }
+                Err(e) => warn!(err = %e, "commit sent in full"),
+            }
+        }
+        if let Some(out) = self.outbound.as_ref() {
+            let height = commit.height.get();
+            if let Err(e) = out.try_send(ConsensusMsg::Commit(commit)) {
+                warn!(err = %e, height, "commit not broadcast");
+            }
+        }
+    }
+
+    fn publish(&self, event: ChainEvent) {
+        if let Some(events) = self.events.as_ref() {
+            events.publish(event);
+        }
+    }
+
+    // Replaces Tide's active set, announcing it if it changed.
+    fn set_active(&mut self, next: BTreeSet<ValidatorId>) {
+        if &next == self.tide.validators() {
+            return;
+        }
+        let event = ChainEvent::validator_set(self.tide.finalized_height(), &next);
+        self.tide.set_validators(next);
+        self.publish(event);
+    }
+
+    fn on_finalized(&mut self, commit: &Commit) {
+        self.publish(ChainEvent::finalized(commit));
+        let active = self.tide.validators().clone();
+        // The certificate holds the first votes to reach quorum; every verified vote counts
+        // for uptime.
+        let voters = self.timings.voters(commit.height);
+        let timings = self.timings.on_commit(commit, now_ms());
+        if let Some(m) = self.metrics.as_ref() {
+            m.consensus_commits_total.inc();
+            if commit.sent_ts_ms != 0 {
+                m.consensus_clock_skew_ms
+                    .set((now_ms() as i64).saturating_sub(commit.sent_ts_ms as i64));
+            }
+            if let Some(ms) = timings.finality_ms() {
+                m.consensus_finality_latency_seconds
+                    .observe(ms as f64 / 1000.0);
+            }
+            for a in timings.votes.iter() {
+                if let Some(perf) = self.timings.performance(&a.validator) {
+                    m.consensus_validator_vote_delay_ms
+                        .with_label_values(&[&perf.validator])
+                        .set(perf.p50_ms as i64);
+                }
+            }
+        }
+
+        if let Some(store) = self.commits.as_ref() {
+            match validator_set_hash_weighted(&active, self.tide.voting_power()) {
+                Ok(vsh) => {
+                    let proof = FinalityProof {
+                        commit: commit.clone(),
+                        validator_set_hash: vsh,
+                    };
+                    if let Err(e) = store.put(&proof) {
+                        warn!(?e, height = commit.height.get(), "failed to persist commit");
+                    }
+                }
+                Err(e) => warn!(?e, "validator set hash failed"),
+            }
+            if let Err(e) = store.put_timings(&timings) {
+                warn!(
+                    ?e,
+                    height = commit.height.get(),
+                    "failed to persist commit timings"
+                );
+            }
+        }
+
+        if self.liveness.record_votes(commit, &voters, &active) {
+            self.on_liveness_changed(commit.height);
+        }
+        self.feed_beacon(commit);
+        self.last_commit = Some(commit.clone());
+        self.publish_snapshot();
+    }
+
+    fn publish_snapshot(&self) {
+        self.snapshot.send_replace(Arc::new(ConsensusSnapshot {
+            finalized_height: self.tide.finalized_height(),
+            active_validators: self.tide.validators().len(),
+            last_commit: self.last_commit.clone(),
+            liveness: self.liveness.report(),
+            performance: self.timings.report(),
+            beacon: self.beacon().cloned(),
+        }));
+    }
+
+    // Absorbs every finalized height the beacon has not seen yet, up to `commit`, and saves
+    // it at every epoch rollover and once it stops, so the store keeps each epoch's
+    // randomness. Stops at the first height whose header is unknown here; the next
+    // finalization picks up from there.
+    fn feed_beacon(&mut self, commit: &Commit) {
+        let Some(feed) = self.beacon.as_mut() else {
+            return;
+        };
+        let before = feed.beacon.last_height;
+        while feed.beacon.last_height < commit.height {
+            let height = feed.beacon.last_height.saturating_add(1);
+            let hash = if height == commit.height {
+                Some(commit.block_hash)
+            } else {
+                self.commits
+                    .as_ref()
+                    .and_then(|store| store.get(height.get()).ok().flatten())
+                    .map(|proof| proof.commit.block_hash)
+            };
+            let Some(header) = hash
+                .and_then(|h| feed.headers.header(&h))
+                .filter(|header| header.height == height)
+            else {
+                warn!(
+                    height = height.get(),
+                    "finalized header unknown, beacon stalled"
+                );
+                break;
+            };
+            match feed.beacon.absorb(height, header.vrf_output.as_bytes()) {
+                Ok(Some(_)) => {
+                    info!(epoch = feed.beacon.epoch.get(), "epoch randomness derived");
+                    feed.save(height);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    warn!(
+                        ?e,
+                        height = height.get(),
+                        "beacon rejected finalized header"
+                    );
+                    break;
+                }
+            }
+        }
+        if feed.beacon.last_height != before {
+            feed.save(feed.beacon.last_height);
+        }
+    }
+
+    // Persists the liveness counters and updates the liveness metrics.
+    fn on_liveness_changed(&mut self, height: Height) {
+        if let Some(state) = self.liveness_state.as_ref() {
+            if let Err(e) = self.liveness.commit(state) {
+                warn!(?e, height = height.get(), "failed to persist liveness");
+            }
+        }
+
+        if let Some(m) = self.metrics.as_ref() {
+            for v in self.validators.iter() {
+                if let Some(rec) = self.liveness.record(v) {
+                    let label = hex::encode(v.as_bytes());
+                    m.consensus_validator_missed_rounds
+                        .with_label_values(&[&label])
+                        .set(rec.consecutive_missed as i64);
+                    m.consensus_validator_uptime_bps
+                        .with_label_values(&[&label])
+                        .set(i64::from(rec.uptime_bps()));
+                }
             }
         }
     }
 }
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
@@ -0,0 +1,135 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Chain events published by the consensus driver.
+//!
+//! `ChainEvents` is a bounded broadcast channel. Publishing never blocks consensus: a
+//! subscriber that falls more than `capacity` events behind loses the oldest ones and is
+//! told how many on its next receive (`RecvError::Lagged`).
+
+use crate::core::types::{Commit, Height, Round, ValidatorId, H256};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use tokio::sync::broadcast;
+
+/// Default number of events buffered per subscriber.
+pub const DEFAULT_EVENT_CAPACITY: usize = 1024;
+
+/// Something subscribers may want to follow. Hashes and keys are hex.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChainEvent {
+    /// First accepted vote for a height above every earlier one.
+    NewHead {
+        height: u64,
+        round: u64,
+        block_hash: String,
+    },
+    /// A height was finalized.
+    Finalized {
+        height: u64,
+        round: u64,
+        block_hash: String,
+        /// Validators whose signatures are in the commit.
+        signers: usize,
+    },
+    /// The active validator set changed (jailing, unjailing, epoch rotation).
+    ValidatorSetChanged {
+        /// Finalized height when the change took effect.
+        height: u64,
+        validators: Vec<String>,
+    },
+}
+
+impl ChainEvent {
+    /// Name used by subscription filters (the `type` field).
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ChainEvent::NewHead { .. } => "new_head",
+            ChainEvent::Finalized { .. } => "finalized",
+            ChainEvent::ValidatorSetChanged { .. } => "validator_set_changed",
+        }
+    }
+
+    /// All `kind` values.
+    pub const KINDS: &'static [&'static str] = &["new_head", "finalized", "validator_set_changed"];
+
+    pub(crate) fn new_head(height: Height, round: Round, block_hash: &H256) -> Self {
+        ChainEvent::NewHead {
+            height: height.get(),
+            round: round.get(),
+            block_hash: hex::encode(block_hash.as_bytes()),
+        }
+    }
+
+    pub(crate) fn finalized(commit: &Commit) -> Self {
+        ChainEvent::Finalized {
+            height: commit.height.get(),
+            round: commit.round.get(),
+            block_hash: hex::encode(commit.block_hash.as_bytes()),
+            signers: commit.signatures.len(),
+        }
+    }
+
+    pub(crate) fn validator_set(height: Height, validators: &BTreeSet<ValidatorId>) -> Self {
+        ChainEvent::ValidatorSetChanged {
+            height: height.get(),
+            validators: validators
+                .iter()
+                .map(|v| hex::encode(v.as_bytes()))
+                .collect(),
+        }
+    }
+}
+
+/// Sending side of the event stream; cheap to clone.
+#[derive(Clone, Debug)]
+pub struct ChainEvents {
+    tx: broadcast::Sender<ChainEvent>,
+    capacity: usize,
+}
+
+impl ChainEvents {
+    /// Buffer up to `capacity` (at least 1) events per subscriber.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let (tx, _) = broadcast::channel(capacity);
+        Self { tx, capacity }
+    }
+
+    /// Events buffered per subscriber.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Receive events published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<ChainEvent> {
+        self.tx.subscribe()
+    }
+
+    /// Current subscriber count.
+    pub fn subscribers(&self) -> usize {
+        self.tx.receiver_count()
+    }
+
+    /// Publish to current subscribers; a no-op without any.
+    pub fn publish(&self, event: ChainEvent) {
+        let _ = self.tx.send(event);
+    }
+}
+
+impl Default for ChainEvents {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_CAPACITY)
+    }
+}
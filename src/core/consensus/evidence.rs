@@ -0,0 +1,95 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Evidence of validator misbehaviour the node has accepted.
+//!
+//! Double votes are found by whichever nodes happen to receive both votes; those nodes
+//! gossip the pair on the evidence topic (`Evidence`), and every node that verifies it
+//! records the offense here, once per validator, height and round. The pool is what later
+//! block inclusion draws from; until then it is bounded, dropping the lowest heights first.
+
+use crate::core::types::{Evidence, Height, Round, ValidatorId};
+use std::collections::BTreeMap;
+
+/// Default capacity of an `EvidencePool`.
+pub const DEFAULT_MAX_EVIDENCE: usize = 256;
+
+/// One offense: the offender, where it happened.
+type OffenseKey = (Height, Round, ValidatorId);
+
+/// Accepted evidence, keyed by offense.
+#[derive(Debug)]
+pub struct EvidencePool {
+    max: usize,
+    entries: BTreeMap<OffenseKey, Evidence>,
+}
+
+impl Default for EvidencePool {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_EVIDENCE)
+    }
+}
+
+impl EvidencePool {
+    /// Empty pool holding at most `max` offenses (at least one).
+    pub fn new(max: usize) -> Self {
+        Self {
+            max: max.max(1),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    fn key(ev: &Evidence) -> OffenseKey {
+        let (height, round) = ev.position();
+        (height, round, ev.offender().clone())
+    }
+
+    /// Whether the offense `ev` proves is already recorded (by this or other evidence).
+    pub fn contains(&self, ev: &Evidence) -> bool {
+        self.entries.contains_key(&Self::key(ev))
+    }
+
+    /// Record `ev`; false if its offense was already known. When full, the offense at the
+    /// lowest height goes, unless `ev` is lower still.
+    pub fn insert(&mut self, ev: Evidence) -> bool {
+        let key = Self::key(&ev);
+        if self.entries.contains_key(&key) {
+            return false;
+        }
+        if self.entries.len() >= self.max {
+            match self.entries.first_key_value() {
+                Some((lowest, _)) if *lowest < key => {
+                    self.entries.pop_first();
+                }
+                _ => return false,
+            }
+        }
+        self.entries.insert(key, ev);
+        true
+    }
+
+    /// Recorded offenses.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// True if nothing is recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Recorded evidence, by height, round and offender.
+    pub fn iter(&self) -> impl Iterator<Item = &Evidence> {
+        self.entries.values()
+    }
+}
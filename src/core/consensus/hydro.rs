@@ -43,15 +43,22 @@ pub struct HydroConfig {
     pub slot_ms: u64,
     /// Allowed clock skew in ms.
     pub skew_ms: u64,
-    /// Epoch randomness (32 bytes).
+    /// Epoch randomness (32 bytes). Genesis value from config; afterwards maintained by
+    /// `beacon::RandomnessBeacon`.
     pub epoch_randomness: [u8; 32],
+    /// Chain identifier; binds VRF outputs to one network (same value as `TideConfig.chain_id`).
+    pub chain_id: String,
 }
 
 impl HydroConfig {
-    /// Build canonical VRF transcript.
+    /// Build canonical VRF transcript:
+    /// domain || len(chain_id) || chain_id || slot || parent_hash || epoch_randomness
     pub fn build_vrf_transcript(&self, slot: u64, parent_hash: H256) -> Vec<u8> {
-        let mut transcript = Vec::with_capacity(4 + 8 + 32 + 32);
-        transcript.extend_from_slice(b"Amunchain-Hydro-VRF-v1");
+        let chain = self.chain_id.as_bytes();
+        let mut transcript = Vec::with_capacity(24 + 4 + chain.len() + 8 + 32 + 32);
+        transcript.extend_from_slice(b"Amunchain-Hydro-VRF-v2");
+        transcript.extend_from_slice(&(chain.len() as u32).to_be_bytes());
+        transcript.extend_from_slice(chain);
         transcript.extend_from_slice(&slot.to_be_bytes());
         transcript.extend_from_slice(parent_hash.as_bytes());
         transcript.extend_from_slice(&self.epoch_randomness);
@@ -75,7 +82,7 @@ impl HydroConfig {
         Ok(current_abs_ms.saturating_sub(self.genesis_time_ms))
     }
 
-    /// PoW difficulty: hash < target.
+    /// PoW difficulty: hash < target (see `difficulty::DifficultyState` for the target).
     pub fn verify_difficulty(&self, hash: &H256, target: [u8; 32]) -> Result<(), HydroError> {
         if hash.as_bytes() < &target {
             Ok(())
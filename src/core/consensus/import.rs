@@ -0,0 +1,486 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Staged block import.
+//!
+//! A block from gossip, sync or the local producer goes through, in order:
+//!
+//! 1. header sanity: height, size limits, transaction root;
+//! 2. slot/time window: timestamp inside its slot, slot not in the future;
+//! 3. VRF proof over the Hydro transcript for `(slot, parent_hash)`, with the randomness of
+//!    the block's epoch once a beacon is attached (see `with_beacon`);
+//! 4. difficulty: header hash below the current target;
+//! 5. parent availability: parent known, height and slot advance;
+//! 6. transaction execution on top of the parent state;
+//! 7. state root match;
+//! 8. hand-off to fork-choice.
+//!
+//! Cheap stateless checks run first, so a forged block costs no parent lookup or
+//! execution. Each stage fails with its own `ImportError`. Failures that prove the block
+//! invalid (as opposed to early, or orphaned) are reported against the peer that relayed it
+//! through the p2p peer-report channel, as the consensus driver does for rejected messages.
+//!
+//! With an orphan pool attached, blocks with an unknown parent are held instead of rejected
+//! and re-imported once the parent lands (see `orphans`).
+
+use crate::core::consensus::beacon::BeaconStore;
+use crate::core::consensus::hydro::HydroConfig;
+use crate::core::consensus::orphans::{OrphanConfig, OrphanPool};
+use crate::core::consensus::slot_clock::SlotClock;
+use crate::core::types::{encode_canonical, Block, BlockHeader, Height, ValidatorId, H256};
+use crate::monitoring::metrics::Metrics;
+use crate::node::channel::Sender;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::watch;
+
+/// Pipeline stage, in execution order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ImportStage {
+    Header,
+    SlotWindow,
+    Vrf,
+    Difficulty,
+    Parent,
+    Execution,
+    StateRoot,
+    ForkChoice,
+}
+
+impl ImportStage {
+    /// Short label for the `stage` metric label.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImportStage::Header => "header",
+            ImportStage::SlotWindow => "slot_window",
+            ImportStage::Vrf => "vrf",
+            ImportStage::Difficulty => "difficulty",
+            ImportStage::Parent => "parent",
+            ImportStage::Execution => "execution",
+            ImportStage::StateRoot => "state_root",
+            ImportStage::ForkChoice => "fork_choice",
+        }
+    }
+}
+
+/// Import errors.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum ImportError {
+    #[error("malformed header: {0}")]
+    Malformed(&'static str),
+    #[error("block timestamp outside its slot")]
+    OutsideSlot,
+    /// Slot starts later than local time allows; may be our clock.
+    #[error("block from a future slot")]
+    FutureSlot,
+    #[error("invalid VRF proof")]
+    BadVrf,
+    /// The beacon has not derived the randomness of the block's epoch yet.
+    #[error("no randomness for the block's epoch")]
+    UnknownEpoch,
+    #[error("header hash above difficulty target")]
+    Difficulty,
+    /// Parent not known yet; the block may become importable later.
+    #[error("unknown parent {0:?}")]
+    UnknownParent(H256),
+    #[error("height or slot does not follow the parent")]
+    NotAChild,
+    #[error("execution failed: {0}")]
+    Execution(String),
+    #[error("state root mismatch")]
+    StateRootMismatch,
+    #[error("fork choice: {0}")]
+    ForkChoice(String),
+}
+
+impl ImportError {
+    /// Stage that produced the error.
+    pub fn stage(&self) -> ImportStage {
+        match self {
+            ImportError::Malformed(_) => ImportStage::Header,
+            ImportError::OutsideSlot | ImportError::FutureSlot => ImportStage::SlotWindow,
+            ImportError::BadVrf | ImportError::UnknownEpoch => ImportStage::Vrf,
+            ImportError::Difficulty => ImportStage::Difficulty,
+            ImportError::UnknownParent(_) | ImportError::NotAChild => ImportStage::Parent,
+            ImportError::Execution(_) => ImportStage::Execution,
+            ImportError::StateRootMismatch => ImportStage::StateRoot,
+            ImportError::ForkChoice(_) => ImportStage::ForkChoice,
+        }
+    }
+
+    /// Whether the block itself is invalid, so the relaying peer is at fault. Future slots
+    /// (clock drift), unknown parents (out-of-order delivery), epochs the local beacon has not
+    /// reached and local fork-choice failures are not.
+    pub fn is_invalid(&self) -> bool {
+        !matches!(
+            self,
+            ImportError::FutureSlot
+                | ImportError::UnknownParent(_)
+                | ImportError::UnknownEpoch
+                | ImportError::ForkChoice(_)
+        )
+    }
+}
+
+/// Where a block came from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlockSource {
+    /// Produced by this node.
+    Local,
+    /// Relayed on gossip by this peer (peer id bytes).
+    Gossip(Vec<u8>),
+    /// Fetched from this peer by the sync protocol.
+    Sync(Vec<u8>),
+}
+
+impl BlockSource {
+    /// Relaying peer, if any.
+    pub fn peer(&self) -> Option<&[u8]> {
+        match self {
+            BlockSource::Local => None,
+            BlockSource::Gossip(p) | BlockSource::Sync(p) => Some(p),
+        }
+    }
+}
+
+/// Result of a successful import.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportOutcome {
+    /// New block handed to fork-choice.
+    Imported(H256),
+    /// Already known; nothing done.
+    Known(H256),
+    /// Parent unknown; held in the orphan pool.
+    Orphaned(H256),
+}
+
+/// Import limits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImportConfig {
+    /// Largest canonical encoding of a block.
+    pub max_block_bytes: usize,
+    /// Most transactions in a block.
+    pub max_transactions: usize,
+}
+
+impl Default for ImportConfig {
+    fn default() -> Self {
+        Self {
+            max_block_bytes: 4 * 1024 * 1024,
+            max_transactions: 10_000,
+        }
+    }
+}
+
+/// Known block headers.
+pub trait ChainView: Send {
+    fn header(&self, hash: &H256) -> Option<BlockHeader>;
+}
+
+/// VRF proof check for a producer over a transcript.
+pub trait VrfVerifier: Send {
+    fn verify(
+        &self,
+        producer: &ValidatorId,
+        transcript: &[u8],
+        output: &H256,
+        proof: &[u8],
+    ) -> bool;
+}
+
+/// Executes a block on top of its parent's state and returns the resulting state root.
+pub trait BlockExecutor: Send {
+    fn execute(&mut self, parent: &BlockHeader, block: &Block) -> Result<H256, String>;
+}
+
+/// Receives fully validated blocks.
+pub trait ForkChoice: Send {
+    fn on_block(&mut self, hash: H256, block: Block) -> Result<(), String>;
+}
+
+/// Staged block importer.
+pub struct BlockImporter {
+    cfg: ImportConfig,
+    hydro: HydroConfig,
+    clock: SlotClock,
+    target: [u8; 32],
+    chain: Box<dyn ChainView>,
+    vrf: Box<dyn VrfVerifier>,
+    executor: Box<dyn BlockExecutor>,
+    fork_choice: Box<dyn ForkChoice>,
+    orphans: Option<OrphanPool>,
+    beacon: Option<BeaconStore>,
+    reports: Option<Sender<Vec<u8>>>,
+    metrics: Option<Arc<Metrics>>,
+}
+
+impl BlockImporter {
+    /// Importer checking PoW against `target` (see `difficulty::DifficultyState`).
+    pub fn new(
+        hydro: HydroConfig,
+        target: [u8; 32],
+        chain: Box<dyn ChainView>,
+        vrf: Box<dyn VrfVerifier>,
+        executor: Box<dyn BlockExecutor>,
+        fork_choice: Box<dyn ForkChoice>,
+    ) -> Self {
+        Self {
+            cfg: ImportConfig::default(),
+            clock: SlotClock::new(&hydro),
+            hydro,
+            target,
+            chain,
+            vrf,
+            executor,
+            fork_choice,
+            orphans: None,
+            beacon: None,
+            reports: None,
+            metrics: None,
+        }
+    }
+
+    /// Replace the default size limits.
+    pub fn with_config(mut self, cfg: ImportConfig) -> Self {
+        self.cfg = cfg;
+        self
+    }
+
+    /// Hold blocks with an unknown parent instead of rejecting them.
+    pub fn with_orphan_pool(mut self, cfg: OrphanConfig) -> Self {
+        self.orphans = Some(OrphanPool::new(cfg));
+        self
+    }
+
+    /// Orphans currently held.
+    pub fn orphan_count(&self) -> usize {
+        self.orphans.as_ref().map_or(0, OrphanPool::len)
+    }
+
+    /// Check VRF proofs against the randomness of the block's epoch in `store`, kept by the
+    /// driver's beacon (see `ConsensusDriver::with_beacon`), instead of
+    /// `HydroConfig.epoch_randomness`. Blocks of any epoch the beacon has reached import, so
+    /// a syncing node can still check past ones.
+    pub fn with_beacon(mut self, store: BeaconStore) -> Self {
+        self.beacon = Some(store);
+        self
+    }
+
+    /// Report peers that relay invalid blocks here (usually `P2pNode::peer_reports`).
+    pub fn with_peer_reports(mut self, reports: Sender<Vec<u8>>) -> Self {
+        self.reports = Some(reports);
+        self
+    }
+
+    /// Count imports and rejections by stage.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Difficulty target for the next blocks (after a retarget).
+    pub fn set_target(&mut self, target: [u8; 32]) {
+        self.target = target;
+    }
+
+    pub fn import(
+        &mut self,
+        block: Block,
+        source: BlockSource,
+    ) -> Result<ImportOutcome, ImportError> {
+        self.import_at(block, source, SlotClock::now_ms())
+    }
+
+    /// Import as of local time `now_ms` (for tests and replays). A successful import also
+    /// re-imports any orphans descending from the block.
+    pub fn import_at(
+        &mut self,
+        block: Block,
+        source: BlockSource,
+        now_ms: u64,
+    ) -> Result<ImportOutcome, ImportError> {
+        if let Some(pool) = self.orphans.as_mut() {
+            pool.prune(now_ms);
+        }
+        let result = self.import_one(block, source, now_ms);
+        if let Ok(ImportOutcome::Imported(hash)) = result {
+            self.connect_orphans(hash, now_ms);
+        }
+        self.flush_orphan_state();
+        result
+    }
+
+    fn import_one(
+        &mut self,
+        block: Block,
+        source: BlockSource,
+        now_ms: u64,
+    ) -> Result<ImportOutcome, ImportError> {
+        let mut result = self.run(&block, now_ms);
+        if let (Err(ImportError::UnknownParent(_)), Some(pool)) = (&result, self.orphans.as_mut()) {
+            if let Ok(hash) = block.header.hash() {
+                if pool.insert(hash, block, source.clone(), now_ms) {
+                    result = Ok(ImportOutcome::Orphaned(hash));
+                }
+            }
+        }
+        match &result {
+            Ok(ImportOutcome::Imported(_)) => {
+                if let Some(m) = self.metrics.as_ref() {
+                    m.block_imports_total.inc();
+                }
+            }
+            Ok(ImportOutcome::Known(_)) | Ok(ImportOutcome::Orphaned(_)) => {}
+            Err(e) => {
+                if let Some(m) = self.metrics.as_ref() {
+                    m.block_import_rejected_total
+                        .with_label_values(&[e.stage().label()])
+                        .inc();
+                }
+                if let (true, Some(peer), Some(reports)) =
+                    (e.is_invalid(), source.peer(), self.reports.as_ref())
+                {
+                    let _ = reports.try_send(peer.to_vec());
+                }
+            }
+        }
+        result
+    }
+
+    // Re-import orphans below `hash`, depth first through each newly imported block.
+    fn connect_orphans(&mut self, hash: H256, now_ms: u64) {
+        let mut parents = vec![hash];
+        while let Some(parent) = parents.pop() {
+            let Some(pool) = self.orphans.as_mut() else {
+                return;
+            };
+            for o in pool.take_children(&parent) {
+                if let Ok(ImportOutcome::Imported(h)) = self.import_one(o.block, o.source, now_ms) {
+                    parents.push(h);
+                }
+            }
+        }
+    }
+
+    fn flush_orphan_state(&mut self) {
+        let Some(pool) = self.orphans.as_mut() else {
+            return;
+        };
+        for peer in pool.take_penalized() {
+            if let Some(reports) = self.reports.as_ref() {
+                let _ = reports.try_send(peer);
+            }
+        }
+        if let Some(m) = self.metrics.as_ref() {
+            m.block_orphans.set(pool.len() as i64);
+            m.block_orphans_evicted_total.inc_by(pool.take_evicted());
+        }
+    }
+
+    fn run(&mut self, block: &Block, now_ms: u64) -> Result<ImportOutcome, ImportError> {
+        let header = &block.header;
+        let hash = header
+            .hash()
+            .map_err(|_| ImportError::Malformed("unencodable"))?;
+        if self.chain.header(&hash).is_some() {
+            return Ok(ImportOutcome::Known(hash));
+        }
+
+        self.check_header(block)?;
+        self.check_slot(header, now_ms)?;
+
+        let transcript = self
+            .epoch_hydro(header.height)?
+            .build_vrf_transcript(header.slot, header.parent_hash);
+        if !self.vrf.verify(
+            &header.producer,
+            &transcript,
+            &header.vrf_output,
+            &header.vrf_proof,
+        ) {
+            return Err(ImportError::BadVrf);
+        }
+
+        self.hydro
+            .verify_difficulty(&hash, self.target)
+            .map_err(|_| ImportError::Difficulty)?;
+
+        let parent = self
+            .chain
+            .header(&header.parent_hash)
+            .ok_or(ImportError::UnknownParent(header.parent_hash))?;
+        if parent.height.checked_next() != Some(header.height) || header.slot <= parent.slot {
+            return Err(ImportError::NotAChild);
+        }
+
+        let root = self
+            .executor
+            .execute(&parent, block)
+            .map_err(ImportError::Execution)?;
+        if root != header.state_root {
+            return Err(ImportError::StateRootMismatch);
+        }
+
+        self.fork_choice
+            .on_block(hash, block.clone())
+            .map_err(ImportError::ForkChoice)?;
+        Ok(ImportOutcome::Imported(hash))
+    }
+
+    // Hydro config with the randomness of the epoch `height` belongs to.
+    fn epoch_hydro(&self, height: Height) -> Result<HydroConfig, ImportError> {
+        let mut hydro = self.hydro.clone();
+        if let Some(store) = self.beacon.as_ref() {
+            // An unreadable store is no fault of the block either: it may import later.
+            hydro.epoch_randomness = store
+                .randomness_at(height)
+                .ok()
+                .flatten()
+                .ok_or(ImportError::UnknownEpoch)?;
+        }
+        Ok(hydro)
+    }
+
+    fn check_header(&self, block: &Block) -> Result<(), ImportError> {
+        if block.header.height.is_zero() {
+            return Err(ImportError::Malformed("height 0 is genesis"));
+        }
+        if block.header.timestamp_ms == 0 {
+            return Err(ImportError::Malformed("no timestamp"));
+        }
+        if block.transactions.len() > self.cfg.max_transactions {
+            return Err(ImportError::Malformed("too many transactions"));
+        }
+        let size = encode_canonical(block)
+            .map_err(|_| ImportError::Malformed("unencodable"))?
+            .len();
+        if size > self.cfg.max_block_bytes {
+            return Err(ImportError::Malformed("block too large"));
+        }
+        if Block::tx_root(&block.transactions) != block.header.tx_root {
+            return Err(ImportError::Malformed("transaction root mismatch"));
+        }
+        Ok(())
+    }
+
+    fn check_slot(&self, header: &BlockHeader, now_ms: u64) -> Result<(), ImportError> {
+        let start = self.clock.slot_start_ms(header.slot);
+        if start > now_ms.saturating_add(self.hydro.skew_ms) {
+            return Err(ImportError::FutureSlot);
+        }
+        self.hydro
+            .check_time_window_abs(header.timestamp_ms, start)
+            .map(|_| ())
+            .map_err(|_| ImportError::OutsideSlot)
+    }
+}
@@ -0,0 +1,363 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Validator liveness tracking.
+//!
+//! Every finalized height records which validators took part: the certificate's signers plus
+//! every other vote this node verified for the height. Tide builds a certificate from the
+//! first votes to reach quorum, so a validator that is merely slower than the rest is often
+//! left out of it; its vote still counts for uptime, even if it arrives after finality, as
+//! long as it arrives before the next height is recorded (see `record_late_vote`).
+//!
+//! Uptime counters are this node's observation, so they are persisted in a dedicated sled
+//! tree (`liveness`) that survives restarts but is not part of the state root. They never
+//! jail anyone.
+//!
+//! Auto-jail is driven by `ChainLiveness` instead, which only counts commits included on
+//! chain. Every node records the same commits in the same order, so its miss streaks are
+//! kept under the state root (`liveness/v1/`) and the validators it reports as offline, which
+//! are slashed for downtime and jailed, are the same everywhere (see
+//! `ConsensusDriver::apply_included_commit`).
+
+use crate::core::state::persistent_state::{KvOp, PersistentState, StateError};
+use crate::core::types::{decode_canonical_limited, encode_canonical, Commit, Height, ValidatorId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Tree name for liveness records, keyed by validator key.
+const LIVENESS_TREE: &str = "liveness";
+
+/// Key in the liveness tree holding the last height recorded by the tracker.
+const LIVENESS_HEIGHT_KEY: &[u8] = b"last_height";
+
+/// Upper bound for a single encoded liveness record.
+const MAX_RECORD_BYTES: usize = 256;
+
+/// State key prefix for `ChainLiveness`.
+pub const CHAIN_LIVENESS_PREFIX: &[u8] = b"liveness/v1/";
+const CHAIN_HEIGHT_KEY: &[u8] = b"liveness/v1/height";
+const CHAIN_STREAK_PREFIX: &[u8] = b"liveness/v1/miss/";
+
+/// Auto-jail policy. It decides the active set, so every node must run the same one.
+#[derive(Clone, Debug)]
+pub struct LivenessPolicy {
+    /// If true, validators over `jail_after_missed` consecutive included commits without
+    /// their signature are jailed.
+    pub auto_jail: bool,
+    /// Consecutive included commits without a validator's signature after which it is
+    /// considered offline.
+    pub jail_after_missed: u64,
+}
+
+impl Default for LivenessPolicy {
+    fn default() -> Self {
+        Self {
+            auto_jail: false,
+            jail_after_missed: 100,
+        }
+    }
+}
+
+/// Per-validator liveness counters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LivenessRecord {
+    /// Finalized rounds this validator signed.
+    pub signed: u64,
+    /// Finalized rounds this validator missed.
+    pub missed: u64,
+    /// Current streak of consecutive missed rounds.
+    pub consecutive_missed: u64,
+    /// Last finalized height this validator signed (0 => never).
+    pub last_signed_height: u64,
+}
+
+impl LivenessRecord {
+    /// Uptime in basis points (10000 => signed every recorded round).
+    pub fn uptime_bps(&self) -> u16 {
+        let total = self.signed.saturating_add(self.missed);
+        if total == 0 {
+            return 10_000;
+        }
+        ((self.signed as u128 * 10_000) / total as u128) as u16
+    }
+}
+
+/// Uptime report entry (RPC-facing).
+#[derive(Clone, Debug, Serialize)]
+pub struct ValidatorUptime {
+    /// Validator public key (hex).
+    pub validator: String,
+    /// Raw counters.
+    #[serde(flatten)]
+    pub record: LivenessRecord,
+    /// Uptime in basis points.
+    pub uptime_bps: u16,
+}
+
+/// Tracks finalized-round participation per validator.
+#[derive(Clone, Debug, Default)]
+pub struct LivenessTracker {
+    records: BTreeMap<ValidatorId, LivenessRecord>,
+    last_height: u64,
+    // Records of the validators that missed `last_height`, as they were before the miss, so a
+    // late vote can still be credited.
+    missed_last: BTreeMap<ValidatorId, LivenessRecord>,
+}
+
+impl LivenessTracker {
+    /// Create an empty tracker.
+    pub fn new() -> Self {
+        Self {
+            records: BTreeMap::new(),
+            last_height: 0,
+            missed_last: BTreeMap::new(),
+        }
+    }
+
+    /// Last finalized height recorded.
+    pub fn last_height(&self) -> u64 {
+        self.last_height
+    }
+
+    /// Counters for a validator, if it has been observed.
+    pub fn record(&self, validator: &ValidatorId) -> Option<&LivenessRecord> {
+        self.records.get(validator)
+    }
+
+    /// Record a finalized commit against the active set it was built from, with only its
+    /// signers as voters (see `record_votes`).
+    pub fn record_commit(&mut self, commit: &Commit, validators: &BTreeSet<ValidatorId>) -> bool {
+        self.record_votes(commit, &BTreeSet::new(), validators)
+    }
+
+    /// Record a finalized commit: its signers and `voters` (any other verified votes for the
+    /// height) signed, the rest of `validators`, the active set, missed.
+    ///
+    /// Heights below the last recorded one are ignored, so the same certificate arriving from
+    /// several peers is only counted once. The last recorded height itself only credits the
+    /// voters that were counted as missing (see `record_late_vote`). Returns true if anything
+    /// was recorded.
+    pub fn record_votes(
+        &mut self,
+        commit: &Commit,
+        voters: &BTreeSet<ValidatorId>,
+        validators: &BTreeSet<ValidatorId>,
+    ) -> bool {
+        let height = commit.height;
+        let h = height.get();
+        let signed = |v: &ValidatorId| commit.signatures.contains_key(v) || voters.contains(v);
+        if h < self.last_height {
+            return false;
+        }
+        if h == self.last_height {
+            let mut credited = false;
+            for v in validators.iter().filter(|v| signed(v)) {
+                credited |= self.record_late_vote(height, v);
+            }
+            return credited;
+        }
+        self.missed_last.clear();
+        for vid in validators.iter() {
+            let rec = self.records.entry(vid.clone()).or_default();
+            if signed(vid) {
+                rec.signed = rec.signed.saturating_add(1);
+                rec.consecutive_missed = 0;
+                rec.last_signed_height = h;
+            } else {
+                self.missed_last.insert(vid.clone(), *rec);
+                rec.missed = rec.missed.saturating_add(1);
+                rec.consecutive_missed = rec.consecutive_missed.saturating_add(1);
+            }
+        }
+        self.last_height = h;
+        true
+    }
+
+    /// Credit a verified vote that arrived after `height` was recorded: the miss counted
+    /// against `voter` becomes a signed round. Only the last recorded height can be
+    /// corrected. Returns true if the record changed.
+    pub fn record_late_vote(&mut self, height: Height, voter: &ValidatorId) -> bool {
+        if height.get() != self.last_height {
+            return false;
+        }
+        let (Some(before), Some(rec)) =
+            (self.missed_last.remove(voter), self.records.get_mut(voter))
+        else {
+            return false;
+        };
+        rec.signed = before.signed.saturating_add(1);
+        rec.missed = before.missed;
+        rec.consecutive_missed = 0;
+        rec.last_signed_height = height.get();
+        true
+    }
+
+    /// Uptime report for all tracked validators (canonical ordering by key).
+    pub fn report(&self) -> Vec<ValidatorUptime> {
+        self.records
+            .iter()
+            .map(|(v, r)| ValidatorUptime {
+                validator: hex::encode(v.as_bytes()),
+                record: *r,
+                uptime_bps: r.uptime_bps(),
+            })
+            .collect()
+    }
+
+    /// Persist the current counters atomically to the `liveness` tree of `state`.
+    pub fn commit(&self, state: &PersistentState) -> Result<(), StateError> {
+        let tree = state.open_tree(LIVENESS_TREE)?;
+        let mut batch = sled::Batch::default();
+        for (vid, rec) in self.records.iter() {
+            let value = encode_canonical(rec).map_err(|_| StateError::DbIo)?;
+            batch.insert(vid.as_bytes().as_slice(), value);
+        }
+        batch.insert(
+            LIVENESS_HEIGHT_KEY,
+            self.last_height.to_be_bytes().as_slice(),
+        );
+        tree.apply_batch(batch).map_err(|_| StateError::DbIo)
+    }
+
+    /// Load counters for `validators` from the `liveness` tree of `state`. Unknown validators
+    /// start at zero.
+    pub fn load(
+        state: &PersistentState,
+        validators: &BTreeSet<ValidatorId>,
+    ) -> Result<Self, StateError> {
+        let tree = state.open_tree(LIVENESS_TREE)?;
+        let mut tracker = Self::new();
+        if let Some(raw) = tree
+            .get(LIVENESS_HEIGHT_KEY)
+            .map_err(|_| StateError::DbIo)?
+        {
+            let bytes: [u8; 8] = raw.as_ref().try_into().map_err(|_| StateError::DbIo)?;
+            tracker.last_height = u64::from_be_bytes(bytes);
+        }
+        for vid in validators.iter() {
+            if let Some(raw) = tree.get(vid.as_bytes()).map_err(|_| StateError::DbIo)? {
+                let rec: LivenessRecord = decode_canonical_limited(&raw, MAX_RECORD_BYTES)
+                    .map_err(|_| StateError::DbIo)?;
+                tracker.records.insert(vid.clone(), rec);
+            }
+        }
+        Ok(tracker)
+    }
+}
+
+/// Miss streaks counted from commits included on chain, stored under the state root.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChainLiveness {
+    last_height: u64,
+    streaks: BTreeMap<ValidatorId, u64>,
+}
+
+impl ChainLiveness {
+    /// Height of the last included commit recorded.
+    pub fn last_height(&self) -> u64 {
+        self.last_height
+    }
+
+    /// Consecutive included commits `validator` has not signed.
+    pub fn missed(&self, validator: &ValidatorId) -> u64 {
+        self.streaks.get(validator).copied().unwrap_or(0)
+    }
+
+    /// Record an included commit against `validators`, the active set it was built from:
+    /// signers reset their streak, the others extend it, and validators outside the set
+    /// are forgotten. Commits at or below the last recorded height are ignored.
+    ///
+    /// Returns the validators whose streak reached `policy.jail_after_missed`, if
+    /// `policy.auto_jail` is set; their streaks start over.
+    pub fn record_included(
+        &mut self,
+        commit: &Commit,
+        validators: &BTreeSet<ValidatorId>,
+        policy: &LivenessPolicy,
+    ) -> Vec<ValidatorId> {
+        let h = commit.height.get();
+        if h <= self.last_height {
+            return Vec::new();
+        }
+        self.last_height = h;
+        self.streaks.retain(|v, _| validators.contains(v));
+        let mut offline = Vec::new();
+        for vid in validators.iter() {
+            if commit.signatures.contains_key(vid) {
+                self.streaks.remove(vid);
+                continue;
+            }
+            let streak = self.streaks.entry(vid.clone()).or_default();
+            *streak = streak.saturating_add(1);
+            if policy.auto_jail && *streak >= policy.jail_after_missed {
+                self.streaks.remove(vid);
+                offline.push(vid.clone());
+            }
+        }
+        offline
+    }
+
+    /// Put operations for the last height and every non-zero streak, in key order.
+    pub fn to_ops(&self) -> Result<Vec<KvOp>, StateError> {
+        let mut ops = vec![KvOp::Put {
+            key: CHAIN_HEIGHT_KEY.to_vec(),
+            value: encode_canonical(&self.last_height).map_err(|_| StateError::DbIo)?,
+        }];
+        for (vid, streak) in self.streaks.iter() {
+            let mut key = CHAIN_STREAK_PREFIX.to_vec();
+            key.extend_from_slice(vid.as_bytes());
+            ops.push(KvOp::Put {
+                key,
+                value: encode_canonical(streak).map_err(|_| StateError::DbIo)?,
+            });
+        }
+        Ok(ops)
+    }
+
+    /// Persist the streaks atomically, deleting the ones that ended.
+    pub fn commit(&self, state: &PersistentState) -> Result<(), StateError> {
+        let puts = self.to_ops()?;
+        let live: BTreeSet<&[u8]> = puts
+            .iter()
+            .filter_map(|op| match op {
+                KvOp::Put { key, .. } => Some(key.as_slice()),
+                KvOp::Del { .. } => None,
+            })
+            .collect();
+        let mut ops: Vec<KvOp> = state
+            .scan_prefix(CHAIN_LIVENESS_PREFIX)?
+            .into_iter()
+            .filter(|(k, _)| !live.contains(k.as_slice()))
+            .map(|(key, _)| KvOp::Del { key })
+            .collect();
+        ops.extend(puts);
+        state.commit_atomic(ops)
+    }
+
+    /// Load the streaks from state.
+    pub fn load(state: &PersistentState) -> Result<Self, StateError> {
+        let mut chain = Self::default();
+        if let Some(raw) = state.get(CHAIN_HEIGHT_KEY)? {
+            chain.last_height =
+                decode_canonical_limited(&raw, MAX_RECORD_BYTES).map_err(|_| StateError::DbIo)?;
+        }
+        for (k, raw) in state.scan_prefix(CHAIN_STREAK_PREFIX)? {
+            let vid = ValidatorId::from_slice(&k[CHAIN_STREAK_PREFIX.len()..])
+                .map_err(|_| StateError::DbIo)?;
+            let streak: u64 =
+                decode_canonical_limited(&raw, MAX_RECORD_BYTES).map_err(|_| StateError::DbIo)?;
+            chain.streaks.insert(vid, streak);
+        }
+        Ok(chain)
+    }
+}
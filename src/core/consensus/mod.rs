@@ -17,10 +17,33 @@
 
 //! Consensus: Hydro (block production placeholder) + Tide (finality).
 
+/// Epoch randomness beacon derived from finalized VRF outputs.
+pub mod beacon;
+/// Cache of already verified commit certificates.
+pub mod commit_cache;
+pub mod compact;
+/// Hydro PoW difficulty retargeting.
+pub mod difficulty;
 /// Consensus driver: wires Tide to network + state.
 pub mod driver;
+/// Chain events (new heads, finality, validator set changes) for subscribers.
+pub mod events;
+pub mod evidence;
 pub mod hydro;
+/// Staged block import: validation pipeline ending in fork-choice.
+pub mod import;
+/// Validator liveness tracking and auto-jail policy.
+pub mod liveness;
+/// Orphan block pool: out-of-order blocks waiting for their parent.
+pub mod orphans;
+/// Bounded buffer for votes that arrive ahead of the local view.
+pub mod pending;
+/// Commit thresholds (`QuorumRule`).
+pub mod quorum;
 /// Domain-separated signing and verification helpers.
 pub mod signing;
+/// Hydro slot clock: slot ticks and drift against peer timestamps.
+pub mod slot_clock;
 /// Tide: BFT-lite finality gadget implementation.
 pub mod tide;
+pub mod vote_timing;
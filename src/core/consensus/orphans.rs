@@ -0,0 +1,224 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Orphan block pool.
+//!
+//! Blocks whose parent is not known yet are held here, keyed by parent hash, until the
+//! parent is imported; `BlockImporter` then re-imports them. Only blocks that already passed
+//! the stateless import stages (header, slot, VRF, difficulty) get in, so an orphan costs a
+//! sender real work.
+//!
+//! The pool is bounded in total and per peer. Entries leave by age or, when full, oldest
+//! first. An orphan that leaves without its parent ever arriving counts as a strike against
+//! the peer that sent it; a peer reaching `max_strikes` is handed back for reporting.
+
+use crate::core::consensus::import::BlockSource;
+use crate::core::types::{Block, H256};
+use std::collections::{BTreeMap, HashMap};
+
+/// Pool limits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OrphanConfig {
+    /// Most orphans held at once.
+    pub max_blocks: usize,
+    /// Orphans older than this are dropped.
+    pub max_age_ms: u64,
+    /// Most orphans held from one peer.
+    pub max_per_peer: usize,
+    /// Unconnectable orphans a peer may send before it is reported.
+    pub max_strikes: u32,
+}
+
+impl Default for OrphanConfig {
+    fn default() -> Self {
+        Self {
+            max_blocks: 256,
+            max_age_ms: 60_000,
+            max_per_peer: 32,
+            max_strikes: 8,
+        }
+    }
+}
+
+/// A held block.
+#[derive(Clone, Debug)]
+pub struct Orphan {
+    pub block: Block,
+    pub source: BlockSource,
+    /// Local time the block was first held.
+    pub received_ms: u64,
+    seq: u64,
+}
+
+/// Bounded orphan pool.
+#[derive(Debug)]
+pub struct OrphanPool {
+    cfg: OrphanConfig,
+    entries: HashMap<H256, Orphan>,
+    by_parent: HashMap<H256, Vec<H256>>,
+    // Arrival sequence -> hash, oldest first.
+    order: BTreeMap<u64, H256>,
+    next_seq: u64,
+    per_peer: HashMap<Vec<u8>, usize>,
+    strikes: HashMap<Vec<u8>, u32>,
+    penalized: Vec<Vec<u8>>,
+    evicted: u64,
+}
+
+impl OrphanPool {
+    pub fn new(cfg: OrphanConfig) -> Self {
+        Self {
+            cfg,
+            entries: HashMap::new(),
+            by_parent: HashMap::new(),
+            order: BTreeMap::new(),
+            next_seq: 0,
+            per_peer: HashMap::new(),
+            strikes: HashMap::new(),
+            penalized: Vec::new(),
+            evicted: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, hash: &H256) -> bool {
+        self.entries.contains_key(hash)
+    }
+
+    /// Orphans dropped without connecting since the last call.
+    pub fn take_evicted(&mut self) -> u64 {
+        std::mem::take(&mut self.evicted)
+    }
+
+    /// Hold `block` (hash `hash`) until its parent arrives. Returns false if the sender is at
+    /// its per-peer limit, which counts as a strike.
+    pub fn insert(&mut self, hash: H256, block: Block, source: BlockSource, now_ms: u64) -> bool {
+        if self.entries.contains_key(&hash) {
+            return true;
+        }
+        let peer = source.peer().map(<[u8]>::to_vec);
+        if let Some(p) = peer.as_ref() {
+            if self.per_peer.get(p).copied().unwrap_or(0) >= self.cfg.max_per_peer {
+                self.strike(p.clone());
+                return false;
+            }
+        }
+        self.prune(now_ms);
+        while self.entries.len() >= self.cfg.max_blocks.max(1) {
+            let Some(&oldest) = self.order.values().next() else {
+                break;
+            };
+            self.evict(&oldest);
+        }
+
+        let seq = self.next_seq;
+        self.next_seq = self.next_seq.wrapping_add(1);
+        self.order.insert(seq, hash);
+        self.by_parent
+            .entry(block.header.parent_hash)
+            .or_default()
+            .push(hash);
+        if let Some(p) = peer {
+            *self.per_peer.entry(p).or_default() += 1;
+        }
+        self.entries.insert(
+            hash,
+            Orphan {
+                block,
+                source,
+                received_ms: now_ms,
+                seq,
+            },
+        );
+        true
+    }
+
+    /// Remove and return the orphans waiting on `parent`, oldest first.
+    pub fn take_children(&mut self, parent: &H256) -> Vec<Orphan> {
+        let Some(hashes) = self.by_parent.remove(parent) else {
+            return Vec::new();
+        };
+        let mut out: Vec<Orphan> = hashes.iter().filter_map(|h| self.remove(h)).collect();
+        out.sort_by_key(|o| o.seq);
+        out
+    }
+
+    /// Drop orphans held longer than `max_age_ms`.
+    pub fn prune(&mut self, now_ms: u64) {
+        let expired: Vec<H256> = self
+            .order
+            .values()
+            .filter(|h| {
+                self.entries
+                    .get(h)
+                    .is_some_and(|o| now_ms.saturating_sub(o.received_ms) > self.cfg.max_age_ms)
+            })
+            .copied()
+            .collect();
+        for h in expired {
+            self.evict(&h);
+        }
+    }
+
+    /// Peers that reached `max_strikes` since the last call.
+    pub fn take_penalized(&mut self) -> Vec<Vec<u8>> {
+        std::mem::take(&mut self.penalized)
+    }
+
+    fn evict(&mut self, hash: &H256) {
+        let Some(o) = self.remove(hash) else {
+            return;
+        };
+        if let Some(children) = self.by_parent.get_mut(&o.block.header.parent_hash) {
+            children.retain(|h| h != hash);
+            if children.is_empty() {
+                self.by_parent.remove(&o.block.header.parent_hash);
+            }
+        }
+        self.evicted = self.evicted.saturating_add(1);
+        if let Some(p) = o.source.peer() {
+            self.strike(p.to_vec());
+        }
+    }
+
+    // Remove from `entries`, `order` and the per-peer count; `by_parent` is the caller's.
+    fn remove(&mut self, hash: &H256) -> Option<Orphan> {
+        let o = self.entries.remove(hash)?;
+        self.order.remove(&o.seq);
+        if let Some(p) = o.source.peer() {
+            if let Some(n) = self.per_peer.get_mut(p) {
+                *n = n.saturating_sub(1);
+                if *n == 0 {
+                    self.per_peer.remove(p);
+                }
+            }
+        }
+        Some(o)
+    }
+
+    fn strike(&mut self, peer: Vec<u8>) {
+        let n = self.strikes.entry(peer.clone()).or_default();
+        *n = n.saturating_add(1);
+        if *n >= self.cfg.max_strikes.max(1) {
+            self.strikes.remove(&peer);
+            self.penalized.push(peer);
+        }
+    }
+}
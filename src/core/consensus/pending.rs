@@ -0,0 +1,135 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Bounded buffer for early votes.
+//!
+//! A vote for a height above the local view (`finalized + 1`) is held here instead of being
+//! fed to Tide, and replayed once the view reaches its height. Votes more than
+//! `max_heights_ahead` above the view are dropped, and every peer may hold at most
+//! `max_per_peer` buffered votes so one peer cannot crowd out the others.
+//!
+//! Commits are never buffered: a commit certificate carries its own quorum and is checked
+//! only against the validator set.
+
+use crate::core::types::{Height, Vote};
+use std::collections::{BTreeMap, HashMap};
+
+/// Pending buffer limits.
+#[derive(Clone, Debug)]
+pub struct PendingConfig {
+    /// Votes up to this many heights above the local view are buffered.
+    pub max_heights_ahead: u64,
+    /// Buffered votes per peer.
+    pub max_per_peer: usize,
+    /// Buffered votes overall.
+    pub max_total: usize,
+}
+
+impl Default for PendingConfig {
+    fn default() -> Self {
+        Self {
+            max_heights_ahead: 4,
+            max_per_peer: 64,
+            max_total: 4096,
+        }
+    }
+}
+
+/// What to do with an inbound vote.
+#[derive(Debug)]
+pub enum Admission {
+    /// Vote is for the current view (or older); process it now.
+    Ready(Vote),
+    /// Vote was buffered for a later view.
+    Buffered,
+    /// Vote was dropped (too far ahead or over a cap).
+    Dropped,
+}
+
+/// Height-indexed buffer of early votes with per-peer accounting.
+#[derive(Default)]
+pub struct PendingBuffer {
+    cfg: PendingConfig,
+    by_height: BTreeMap<Height, Vec<(Vec<u8>, Vote)>>,
+    per_peer: HashMap<Vec<u8>, usize>,
+    total: usize,
+}
+
+impl PendingBuffer {
+    /// Empty buffer.
+    pub fn new(cfg: PendingConfig) -> Self {
+        Self {
+            cfg,
+            ..Self::default()
+        }
+    }
+
+    /// Buffered vote count.
+    pub fn len(&self) -> usize {
+        self.total
+    }
+
+    /// True if nothing is buffered.
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    /// Buffered vote count for one peer.
+    pub fn peer_len(&self, peer: &[u8]) -> usize {
+        self.per_peer.get(peer).copied().unwrap_or(0)
+    }
+
+    /// Admit a vote from `peer` given the local view height.
+    pub fn admit(&mut self, peer: &[u8], vote: Vote, view: Height) -> Admission {
+        if vote.height <= view {
+            return Admission::Ready(vote);
+        }
+        if vote.height > view.saturating_add(self.cfg.max_heights_ahead) {
+            return Admission::Dropped;
+        }
+        if self.total >= self.cfg.max_total || self.peer_len(peer) >= self.cfg.max_per_peer {
+            return Admission::Dropped;
+        }
+        *self.per_peer.entry(peer.to_vec()).or_default() += 1;
+        self.total += 1;
+        self.by_height
+            .entry(vote.height)
+            .or_default()
+            .push((peer.to_vec(), vote));
+        Admission::Buffered
+    }
+
+    /// Remove and return all votes at or below `view`, in height then arrival order.
+    pub fn drain_ready(&mut self, view: Height) -> Vec<(Vec<u8>, Vote)> {
+        let keep = self.by_height.split_off(&view.saturating_add(1));
+        let ready = std::mem::replace(&mut self.by_height, keep);
+        let mut out = Vec::new();
+        for (_, votes) in ready {
+            for (peer, vote) in votes {
+                self.release(&peer);
+                out.push((peer, vote));
+            }
+        }
+        out
+    }
+
+    fn release(&mut self, peer: &[u8]) {
+        self.total = self.total.saturating_sub(1);
+        if let Some(n) = self.per_peer.get_mut(peer) {
+            *n = n.saturating_sub(1);
+            if *n == 0 {
+                self.per_peer.remove(peer);
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Commit thresholds of Tide.
+//!
+//! A block commits once its signers' power reaches the network's `QuorumRule`. Safety rests
+//! on any two quorums of one validator set sharing a validator: two conflicting blocks can
+//! then only both commit at a height and round if a shared validator votes twice, which Tide
+//! detects. Every rule of `QuorumConfig` asks for strictly more than half of the power, the
+//! least that guarantees the overlap. What a rule tolerates follows from its threshold: the
+//! default `TwoThirds` (2f+1 of 3f+1) stays safe and live with fewer than a third of the
+//! power Byzantine; `All` (f = 0, for permissioned deployments where every validator must
+//! sign) halts while any validator is down.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+/// Decides whether signers holding `signed` of `total` voting power commit a block.
+///
+/// Implementations must be monotone (more power never loses a quorum) and must keep any
+/// two quorums of the same `total` overlapping, i.e. never accept half of the power or less.
+pub trait QuorumRule: Send + Sync + fmt::Debug {
+    /// Whether `signed` out of `total` (> 0) power is a quorum.
+    fn reached(&self, signed: u128, total: u128) -> bool;
+}
+
+/// Why a `QuorumConfig` was rejected.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+pub enum QuorumError {
+    #[error("denominator must not be 0")]
+    ZeroDenominator,
+    #[error("{numerator}/{denominator} is below one half; two quorums could disjointly commit")]
+    BelowHalf { numerator: u64, denominator: u64 },
+    #[error("{numerator}/{denominator} cannot be exceeded; use `all`")]
+    Unreachable { numerator: u64, denominator: u64 },
+}
+
+/// Built-in quorum rules, the same on every node of a network (`consensus.quorum`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum QuorumConfig {
+    /// Strictly more than two thirds of the power. By count this is the usual `2n/3+1`.
+    #[default]
+    TwoThirds,
+    /// All of the power: every validator signs (f = 0).
+    All,
+    /// Strictly more than `numerator / denominator` of the power, at least one half.
+    Fraction { numerator: u64, denominator: u64 },
+}
+
+impl QuorumConfig {
+    /// Check that the rule keeps quorums overlapping and can be reached.
+    pub fn validate(&self) -> Result<(), QuorumError> {
+        let Self::Fraction {
+            numerator,
+            denominator,
+        } = *self
+        else {
+            return Ok(());
+        };
+        if denominator == 0 {
+            return Err(QuorumError::ZeroDenominator);
+        }
+        if u128::from(numerator) * 2 < u128::from(denominator) {
+            return Err(QuorumError::BelowHalf {
+                numerator,
+                denominator,
+            });
+        }
+        if numerator >= denominator {
+            return Err(QuorumError::Unreachable {
+                numerator,
+                denominator,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl QuorumRule for QuorumConfig {
+    fn reached(&self, signed: u128, total: u128) -> bool {
+        if total == 0 {
+            return false;
+        }
+        match *self {
+            Self::TwoThirds => signed.saturating_mul(3) > total.saturating_mul(2),
+            Self::All => signed >= total,
+            Self::Fraction {
+                numerator,
+                denominator,
+            } => {
+                signed.saturating_mul(u128::from(denominator))
+                    > total.saturating_mul(u128::from(numerator))
+            }
+        }
+    }
+}
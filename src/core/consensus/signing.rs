@@ -13,7 +13,8 @@
 
 //! Domain-separated signing bytes for consensus messages.
 
-use crate::core::types::{encode_canonical, ValidatorId, H256};
+use crate::core::types::{encode_canonical, Epoch, Height, Round, Transaction, ValidatorId, H256};
+use std::collections::{BTreeMap, BTreeSet};
 use thiserror::Error;
 
 /// Signing error.
@@ -28,17 +29,17 @@ pub enum SigningError {
 /// This payload is also used for commit verification (commit signatures are
 /// expected to be the signatures of the corresponding precommit votes).
 pub fn vote_signing_bytes_v1(
-    height: u64,
-    round: u64,
+    height: Height,
+    round: Round,
     block_hash: H256,
     voter: &ValidatorId,
 ) -> Result<Vec<u8>, SigningError> {
-    let mut out = Vec::with_capacity(32 + 8 + 8 + 32 + voter.0.len());
+    let mut out = Vec::with_capacity(32 + 8 + 8 + 32 + ValidatorId::LEN);
     out.extend_from_slice(b"Amunchain-Tide-Vote-v1");
     out.extend_from_slice(&height.to_be_bytes());
     out.extend_from_slice(&round.to_be_bytes());
     out.extend_from_slice(block_hash.as_bytes());
-    let vb = encode_canonical(&voter.0).map_err(|_| SigningError::Codec)?;
+    let vb = encode_canonical(voter).map_err(|_| SigningError::Codec)?;
     out.extend_from_slice(&vb);
     Ok(out)
 }
@@ -46,16 +47,16 @@ pub fn vote_signing_bytes_v1(
 /// Vote signing payload v2 (replay-window sealed):
 /// domain || height || round || epoch || msg_counter || sent_ts_ms || ttl_ms || block_hash || voter
 pub fn vote_signing_bytes_v2(
-    height: u64,
-    round: u64,
-    epoch: u64,
+    height: Height,
+    round: Round,
+    epoch: Epoch,
     msg_counter: u64,
     sent_ts_ms: u64,
     ttl_ms: u32,
     block_hash: H256,
     voter: &ValidatorId,
 ) -> Result<Vec<u8>, SigningError> {
-    let mut out = Vec::with_capacity(40 + 8 * 5 + 4 + 32 + voter.0.len());
+    let mut out = Vec::with_capacity(40 + 8 * 5 + 4 + 32 + ValidatorId::LEN);
     out.extend_from_slice(b"Amunchain-Tide-Vote-v2");
     out.extend_from_slice(&height.to_be_bytes());
     out.extend_from_slice(&round.to_be_bytes());
@@ -64,7 +65,7 @@ pub fn vote_signing_bytes_v2(
     out.extend_from_slice(&sent_ts_ms.to_be_bytes());
     out.extend_from_slice(&ttl_ms.to_be_bytes());
     out.extend_from_slice(block_hash.as_bytes());
-    let vb = encode_canonical(&voter.0).map_err(|_| SigningError::Codec)?;
+    let vb = encode_canonical(voter).map_err(|_| SigningError::Codec)?;
     out.extend_from_slice(&vb);
     Ok(out)
 }
@@ -73,16 +74,16 @@ pub fn vote_signing_bytes_v2(
 /// - If `epoch/msg_counter/sent_ts_ms/ttl_ms` are all zero => v1 (legacy).
 /// - Otherwise => v2.
 pub fn vote_signing_bytes_auto(
-    height: u64,
-    round: u64,
-    epoch: u64,
+    height: Height,
+    round: Round,
+    epoch: Epoch,
     msg_counter: u64,
     sent_ts_ms: u64,
     ttl_ms: u32,
     block_hash: H256,
     voter: &ValidatorId,
 ) -> Result<Vec<u8>, SigningError> {
-    if epoch == 0 && msg_counter == 0 && sent_ts_ms == 0 && ttl_ms == 0 {
+    if epoch.is_zero() && msg_counter == 0 && sent_ts_ms == 0 && ttl_ms == 0 {
         vote_signing_bytes_v1(height, round, block_hash, voter)
     } else {
         vote_signing_bytes_v2(
@@ -97,3 +98,185 @@ pub fn vote_signing_bytes_auto(
         )
     }
 }
+
+/// Vote signing payload v3 (chain-bound):
+/// domain || len(chain_id) || chain_id || height || round || epoch || msg_counter ||
+/// sent_ts_ms || ttl_ms || block_hash || [validator_set_hash] || voter
+///
+/// v1/v2 payloads carry no chain identifier, so a signature made on one network verifies on
+/// any other network with the same validator keys. v3 always includes the chain id, even
+/// for legacy (all-zero) replay fields. `validator_set_hash` (see
+/// `validator_set_hash_weighted`) binds the signature to the set it was cast for; it is left
+/// out when zero, which gives the unbound payload signed before the field existed.
+pub fn vote_signing_bytes_v3(
+    chain_id: &str,
+    height: Height,
+    round: Round,
+    epoch: Epoch,
+    msg_counter: u64,
+    sent_ts_ms: u64,
+    ttl_ms: u32,
+    block_hash: H256,
+    validator_set_hash: H256,
+    voter: &ValidatorId,
+) -> Result<Vec<u8>, SigningError> {
+    let chain = chain_id.as_bytes();
+    let chain_len = u32::try_from(chain.len()).map_err(|_| SigningError::Codec)?;
+    let mut out = Vec::with_capacity(40 + 4 + chain.len() + 8 * 5 + 4 + 32 * 2 + ValidatorId::LEN);
+    out.extend_from_slice(b"Amunchain-Tide-Vote-v3");
+    out.extend_from_slice(&chain_len.to_be_bytes());
+    out.extend_from_slice(chain);
+    out.extend_from_slice(&height.to_be_bytes());
+    out.extend_from_slice(&round.to_be_bytes());
+    out.extend_from_slice(&epoch.to_be_bytes());
+    out.extend_from_slice(&msg_counter.to_be_bytes());
+    out.extend_from_slice(&sent_ts_ms.to_be_bytes());
+    out.extend_from_slice(&ttl_ms.to_be_bytes());
+    out.extend_from_slice(block_hash.as_bytes());
+    if validator_set_hash != H256::ZERO {
+        out.extend_from_slice(validator_set_hash.as_bytes());
+    }
+    let vb = encode_canonical(voter).map_err(|_| SigningError::Codec)?;
+    out.extend_from_slice(&vb);
+    Ok(out)
+}
+
+/// Which vote payloads a verifier accepts.
+///
+/// v3 payloads for `chain_id` bound to the expected validator set are always accepted.
+/// Unbound v3 payloads and legacy v1/v2 payloads (see `vote_signing_bytes_auto`) are accepted
+/// only for heights up to and including `legacy_until_height`, so a network can migrate
+/// signers before closing the window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SigningDomain<'a> {
+    pub chain_id: &'a str,
+    pub legacy_until_height: Option<Height>,
+}
+
+impl<'a> SigningDomain<'a> {
+    /// Pre-v3 behaviour: no chain binding, legacy payloads accepted at every height. Only for
+    /// stateless payload checks (`wire::validate_msg`); signatures are verified against the
+    /// network's domain.
+    pub(crate) const LEGACY: SigningDomain<'static> = SigningDomain {
+        chain_id: "",
+        legacy_until_height: Some(Height::MAX),
+    };
+
+    /// True if v1/v2 signatures are still accepted at `height`.
+    pub fn accepts_legacy(&self, height: Height) -> bool {
+        self.legacy_until_height.is_some_and(|h| height <= h)
+    }
+
+    /// Candidate signing payloads for a message at `height`, in order of preference: v3
+    /// bound to `validator_set_hash` (unbound if it is zero), then, if the compatibility window
+    /// is open, unbound v3 and the legacy payload.
+    pub fn candidates(
+        &self,
+        height: Height,
+        round: Round,
+        epoch: Epoch,
+        msg_counter: u64,
+        sent_ts_ms: u64,
+        ttl_ms: u32,
+        block_hash: H256,
+        validator_set_hash: H256,
+        voter: &ValidatorId,
+    ) -> Result<Vec<Vec<u8>>, SigningError> {
+        let v3 = |set_hash| {
+            vote_signing_bytes_v3(
+                self.chain_id,
+                height,
+                round,
+                epoch,
+                msg_counter,
+                sent_ts_ms,
+                ttl_ms,
+                block_hash,
+                set_hash,
+                voter,
+            )
+        };
+        let mut out = vec![v3(validator_set_hash)?];
+        if self.accepts_legacy(height) {
+            if validator_set_hash != H256::ZERO {
+                out.push(v3(H256::ZERO)?);
+            }
+            out.push(vote_signing_bytes_auto(
+                height,
+                round,
+                epoch,
+                msg_counter,
+                sent_ts_ms,
+                ttl_ms,
+                block_hash,
+                voter,
+            )?);
+        }
+        Ok(out)
+    }
+}
+
+/// Transaction signing payload:
+/// domain || chain_id_len || chain_id || sender || nonce || gas_limit || max_fee_per_gas ||
+/// max_priority_fee_per_gas || call
+///
+/// Integers are big-endian; `sender` and `call` use the canonical encoding.
+pub fn tx_signing_bytes(tx: &Transaction) -> Result<Vec<u8>, SigningError> {
+    let chain = tx.chain_id.as_bytes();
+    let chain_len = u32::try_from(chain.len()).map_err(|_| SigningError::Codec)?;
+    let call = encode_canonical(&tx.call).map_err(|_| SigningError::Codec)?;
+    let sender = encode_canonical(&tx.sender).map_err(|_| SigningError::Codec)?;
+    let mut out =
+        Vec::with_capacity(15 + 4 + chain.len() + sender.len() + 8 * 2 + 16 * 2 + call.len());
+    out.extend_from_slice(b"Amunchain-Tx-v1");
+    out.extend_from_slice(&chain_len.to_be_bytes());
+    out.extend_from_slice(chain);
+    out.extend_from_slice(&sender);
+    out.extend_from_slice(&tx.nonce.to_be_bytes());
+    out.extend_from_slice(&tx.gas_limit.to_be_bytes());
+    out.extend_from_slice(&tx.max_fee_per_gas.to_be_bytes());
+    out.extend_from_slice(&tx.max_priority_fee_per_gas.to_be_bytes());
+    out.extend_from_slice(&call);
+    Ok(out)
+}
+
+/// Hash of a validator set with voting powers, as bound into v3 signatures and
+/// `Commit.validator_set_hash`. Without powers (count mode) this is `validator_set_hash`;
+/// otherwise SHA-256( domain || count || key_1 || power_1 || ... ) in key order, with
+/// powers as big-endian u128 (0 for a key missing from `power`).
+pub fn validator_set_hash_weighted(
+    validators: &BTreeSet<ValidatorId>,
+    power: Option<&BTreeMap<ValidatorId, u128>>,
+) -> Result<H256, SigningError> {
+    let Some(power) = power else {
+        return validator_set_hash(validators);
+    };
+    let mut buf = Vec::with_capacity(32 + 8 + validators.len() * 56);
+    buf.extend_from_slice(b"Amunchain-ValidatorSet-v2");
+    buf.extend_from_slice(&(validators.len() as u64).to_be_bytes());
+    for v in validators.iter() {
+        let vb = encode_canonical(v).map_err(|_| SigningError::Codec)?;
+        buf.extend_from_slice(&vb);
+        buf.extend_from_slice(&power.get(v).copied().unwrap_or(0).to_be_bytes());
+    }
+    let d = ring::digest::digest(&ring::digest::SHA256, &buf);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(d.as_ref());
+    Ok(H256::from_bytes(out))
+}
+
+/// Canonical validator set hash:
+/// SHA-256( domain || count || key_1 || ... || key_n ) with keys in canonical (sorted) order.
+pub fn validator_set_hash(validators: &BTreeSet<ValidatorId>) -> Result<H256, SigningError> {
+    let mut buf = Vec::with_capacity(32 + 8 + validators.len() * 40);
+    buf.extend_from_slice(b"Amunchain-ValidatorSet-v1");
+    buf.extend_from_slice(&(validators.len() as u64).to_be_bytes());
+    for v in validators.iter() {
+        let vb = encode_canonical(v).map_err(|_| SigningError::Codec)?;
+        buf.extend_from_slice(&vb);
+    }
+    let d = ring::digest::digest(&ring::digest::SHA256, &buf);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(d.as_ref());
+    Ok(H256::from_bytes(out))
+}
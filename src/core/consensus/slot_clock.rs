@@ -0,0 +1,184 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Hydro slot clock.
+//!
+//! Slots are `slot_ms` long and numbered from `genesis_time_ms`, so every node derives the
+//! same slot from its wall clock alone. That only holds while the clocks agree: the clock
+//! keeps the offsets between local time and the send timestamps of verified peer messages
+//! and reports their median as the local drift. Beyond `skew_ms` the node's slots no longer
+//! line up with its peers', which the `slot_clock` health check reports as unhealthy.
+//!
+//! Offsets include network delay, so a healthy node shows a small positive drift.
+
+use crate::core::consensus::hydro::HydroConfig;
+use crate::monitoring::metrics::Metrics;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::watch;
+
+/// Peer timestamps the drift estimate is taken over.
+pub const DRIFT_SAMPLES: usize = 64;
+
+/// Start of a slot, as delivered to the block producer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SlotTick {
+    pub slot: u64,
+    /// Slot start, ms since the unix epoch.
+    pub start_ms: u64,
+}
+
+/// Slot arithmetic plus drift tracking. Cheap to clone; clones share state.
+#[derive(Clone)]
+pub struct SlotClock {
+    genesis_time_ms: u64,
+    slot_ms: u64,
+    skew_ms: u64,
+    // local - peer send time, oldest first.
+    offsets: Arc<Mutex<VecDeque<i64>>>,
+    ticks: watch::Sender<Option<SlotTick>>,
+    metrics: Option<Arc<Metrics>>,
+}
+
+impl SlotClock {
+    /// Clock for `cfg`'s genesis time and slot length. A zero slot length is treated as 1 ms.
+    pub fn new(cfg: &HydroConfig) -> Self {
+        Self {
+            genesis_time_ms: cfg.genesis_time_ms,
+            slot_ms: cfg.slot_ms.max(1),
+            skew_ms: cfg.skew_ms,
+            offsets: Arc::new(Mutex::new(VecDeque::with_capacity(DRIFT_SAMPLES))),
+            ticks: watch::Sender::new(None),
+            metrics: None,
+        }
+    }
+
+    /// Publish the drift estimate as `amunchain_hydro_clock_skew_ms`.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Local wall clock, ms since the unix epoch.
+    pub fn now_ms() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64)
+    }
+
+    /// Slot containing `now_ms`; `None` before genesis.
+    pub fn slot_at(&self, now_ms: u64) -> Option<u64> {
+        now_ms
+            .checked_sub(self.genesis_time_ms)
+            .map(|since| since / self.slot_ms)
+    }
+
+    /// Slot containing the current local time.
+    pub fn current_slot(&self) -> Option<u64> {
+        self.slot_at(Self::now_ms())
+    }
+
+    /// Start of `slot`, ms since the unix epoch.
+    pub fn slot_start_ms(&self, slot: u64) -> u64 {
+        self.genesis_time_ms
+            .saturating_add(slot.saturating_mul(self.slot_ms))
+    }
+
+    /// Time from `now_ms` to the start of the next slot (or of slot 0 before genesis).
+    pub fn until_next_slot(&self, now_ms: u64) -> Duration {
+        let next = self.slot_at(now_ms).map_or(self.genesis_time_ms, |s| {
+            self.slot_start_ms(s.saturating_add(1))
+        });
+        Duration::from_millis(next.saturating_sub(now_ms))
+    }
+
+    /// Latest slot tick; `None` until the ticker has fired once.
+    pub fn ticks(&self) -> watch::Receiver<Option<SlotTick>> {
+        self.ticks.subscribe()
+    }
+
+    /// Fire a tick at the start of every slot until the returned task is aborted. A slow
+    /// receiver only sees the latest slot, never a backlog.
+    pub fn spawn_ticker(&self) -> tokio::task::JoinHandle<()> {
+        let clock = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(clock.until_next_slot(Self::now_ms())).await;
+                let Some(slot) = clock.current_slot() else {
+                    continue;
+                };
+                let tick = SlotTick {
+                    slot,
+                    start_ms: clock.slot_start_ms(slot),
+                };
+                clock.ticks.send_if_modified(|t| {
+                    let new = t.is_none_or(|cur| cur.slot < slot);
+                    if new {
+                        *t = Some(tick);
+                    }
+                    new
+                });
+            }
+        })
+    }
+
+    /// Record the send timestamp of a verified peer message, received now.
+    pub fn observe_peer_time(&self, sent_ts_ms: u64) {
+        self.observe_peer_time_at(sent_ts_ms, Self::now_ms());
+    }
+
+    /// Record a peer send timestamp received at local time `local_ms`. Legacy messages
+    /// without a timestamp (0) are ignored.
+    pub fn observe_peer_time_at(&self, sent_ts_ms: u64, local_ms: u64) {
+        if sent_ts_ms == 0 || local_ms == 0 {
+            return;
+        }
+        let offset = (i128::from(local_ms) - i128::from(sent_ts_ms))
+            .clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64;
+        let Ok(mut offsets) = self.offsets.lock() else {
+            return;
+        };
+        if offsets.len() == DRIFT_SAMPLES {
+            offsets.pop_front();
+        }
+        offsets.push_back(offset);
+        let drift = median(&offsets);
+        drop(offsets);
+        if let (Some(m), Some(d)) = (self.metrics.as_ref(), drift) {
+            m.hydro_clock_skew_ms.set(d);
+        }
+    }
+
+    /// Median of recent offsets (local minus peer time); `None` before any sample.
+    pub fn drift_ms(&self) -> Option<i64> {
+        self.offsets.lock().ok().and_then(|o| median(&o))
+    }
+
+    /// Allowed drift (`HydroConfig.skew_ms`).
+    pub fn skew_ms(&self) -> u64 {
+        self.skew_ms
+    }
+
+    /// Whether the drift estimate is beyond `skew_ms`.
+    pub fn drift_exceeded(&self) -> bool {
+        self.drift_ms()
+            .is_some_and(|d| d.unsigned_abs() > self.skew_ms)
+    }
+}
+
+fn median(offsets: &VecDeque<i64>) -> Option<i64> {
+    let mut sorted: Vec<i64> = offsets.iter().copied().collect();
+    sorted.sort_unstable();
+    sorted.get(sorted.len() / 2).copied()
+}
@@ -11,18 +11,27 @@ use std::time::{SystemTime, UNIX_EPOCH};
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::core::consensus::quorum::{QuorumConfig, QuorumRule};
+use crate::core::economics::staking::StakingLedger;
 /// Tide finality gadget (BFT-lite) with signature verification and double-vote detection.
-use crate::core::security::keystore::verify_pubkey_bytes;
+use crate::core::security::keystore::PublicKeyCache;
 use crate::core::{
-    consensus::signing::{vote_signing_bytes_auto, SigningError},
+    consensus::signing::{
+        validator_set_hash_weighted, vote_signing_bytes_v3, SigningDomain, SigningError,
+    },
     security::keystore::{Keystore, KeystoreError},
-    types::{CanonicalMap, Commit, Signature, ValidatorId, Vote, H256},
+    types::{
+        CanonicalMap, Commit, DoubleVoteEvidence, Epoch, Evidence, Height, Round, Signature,
+        ValidatorId, Vote, H256,
+    },
 };
+use crate::monitoring::metrics::Metrics;
 use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
 use thiserror::Error;
 
 /// Tide errors.
-#[derive(Debug, Error)]
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
 pub enum TideError {
     /// Replay, stale, or out-of-window message rejected.
     #[error("replay/stale message")]
@@ -39,6 +48,83 @@ pub enum TideError {
     Signing,
     #[error("keystore")]
     Keystore,
+    /// Vote too far ahead of the finalized height, or for a round older than the voter's
+    /// buffered ones.
+    #[error("vote outside buffering window")]
+    OutOfWindow,
+    /// Commit records a voting power that does not match its signers.
+    #[error("commit voting power mismatch")]
+    PowerMismatch,
+    /// Commit is bound to a different validator set, or unbound after the legacy window.
+    #[error("commit validator set mismatch")]
+    ValidatorSetMismatch,
+    /// Evidence whose votes do not conflict.
+    #[error("invalid evidence")]
+    InvalidEvidence,
+}
+
+impl TideError {
+    /// Short label for the `reason` metric label.
+    pub fn label(&self) -> &'static str {
+        match self {
+            TideError::Replay => "replay",
+            TideError::UnknownValidator => "unknown_validator",
+            TideError::BadSignature => "bad_signature",
+            TideError::DoubleVote => "double_vote",
+            TideError::NotEnoughVotes => "not_enough_votes",
+            TideError::Signing => "signing",
+            TideError::Keystore => "keystore",
+            TideError::OutOfWindow => "out_of_window",
+            TideError::PowerMismatch => "power_mismatch",
+            TideError::ValidatorSetMismatch => "validator_set_mismatch",
+            TideError::InvalidEvidence => "invalid_evidence",
+        }
+    }
+
+    /// Whether the message itself is forged or malformed, as opposed to stale, early or
+    /// from a validator outside our view of the set. Only these count against the relaying
+    /// peer: honest peers forward the others during ordinary churn.
+    pub fn is_invalid(&self) -> bool {
+        matches!(
+            self,
+            TideError::BadSignature
+                | TideError::NotEnoughVotes
+                | TideError::Signing
+                | TideError::PowerMismatch
+                | TideError::InvalidEvidence
+        )
+    }
+}
+
+/// Per-validator voting power for stake-weighted mode.
+pub type VotingPower = BTreeMap<ValidatorId, u128>;
+
+/// Chain id used when none is configured.
+pub const DEFAULT_CHAIN_ID: &str = "amunchain-devnet";
+
+/// Power of `signers` and of the whole set. Without a power table every validator counts 1.
+fn tally<'a>(
+    signers: impl Iterator<Item = &'a ValidatorId>,
+    validators: &BTreeSet<ValidatorId>,
+    power: Option<&VotingPower>,
+) -> (u128, u128) {
+    let weight = |v: &ValidatorId| match power {
+        Some(p) => p.get(v).copied().unwrap_or(0),
+        None => 1,
+    };
+    let signed = signers.fold(0u128, |acc, v| acc.saturating_add(weight(v)));
+    let total = validators
+        .iter()
+        .fold(0u128, |acc, v| acc.saturating_add(weight(v)));
+    (signed, total)
+}
+
+/// Voting power table for `validators` from the staking ledger.
+pub fn staking_power(ledger: &StakingLedger, validators: &BTreeSet<ValidatorId>) -> VotingPower {
+    validators
+        .iter()
+        .map(|v| (v.clone(), ledger.voting_power(v.as_bytes())))
+        .collect()
 }
 
 impl From<SigningError> for TideError {
@@ -79,6 +165,24 @@ pub struct TideConfig {
     pub max_ttl_ms: u32,
     /// If true, reject legacy messages where `epoch == 0`.
     pub require_epoch: bool,
+    /// Votes more than this many heights above the finalized height are rejected.
+    pub max_future_heights: u64,
+    /// Max distinct rounds buffered per validator and height. A vote for a later round
+    /// replaces the validator's lowest one; a vote for an earlier round is rejected. The cap is
+    /// per validator so that one signer spamming rounds cannot crowd out everyone else's.
+    pub max_rounds_per_voter: usize,
+    /// Replay counters are kept for this many epochs back from the newest seen (at least 1);
+    /// messages from older epochs are rejected.
+    pub replay_epochs: u64,
+    /// Stake-weighted mode: per-validator voting power. `None` counts one vote per validator.
+    pub voting_power: Option<VotingPower>,
+    /// Chain identifier bound into v3 vote signatures.
+    pub chain_id: String,
+    /// Last height at which legacy (v1/v2, chain-less) vote signatures are accepted.
+    /// `None` accepts v3 only.
+    pub legacy_signing_until: Option<Height>,
+    /// Power a commit needs (see `consensus::quorum`).
+    pub quorum: Arc<dyn QuorumRule>,
 }
 
 impl TideConfig {
@@ -91,13 +195,168 @@ impl TideConfig {
             // 60s TTL cap for gossip consensus messages.
             max_ttl_ms: 60_000,
             require_epoch: cfg!(feature = "production"),
+            max_future_heights: 64,
+            max_rounds_per_voter: 2,
+            replay_epochs: 2,
+            voting_power: None,
+            chain_id: DEFAULT_CHAIN_ID.to_string(),
+            // Production nodes accept v3 only; elsewhere legacy signers keep working until
+            // a window is configured with `with_chain_id`.
+            legacy_signing_until: if cfg!(feature = "production") {
+                None
+            } else {
+                Some(Height::MAX)
+            },
+            quorum: Arc::new(QuorumConfig::TwoThirds),
+        }
+    }
+
+    /// Commit with `rule` instead of the default two thirds.
+    pub fn with_quorum(mut self, rule: Arc<dyn QuorumRule>) -> Self {
+        self.quorum = rule;
+        self
+    }
+
+    /// Bind signatures to `chain_id`, accepting legacy v1/v2 signatures up to and including
+    /// `legacy_until` (`None` closes the window immediately).
+    pub fn with_chain_id(mut self, chain_id: &str, legacy_until: Option<Height>) -> Self {
+        self.chain_id = chain_id.to_string();
+        self.legacy_signing_until = legacy_until;
+        self
+    }
+
+    /// Signing domain derived from `chain_id` and the legacy window.
+    pub fn signing_domain(&self) -> SigningDomain<'_> {
+        SigningDomain {
+            chain_id: &self.chain_id,
+            legacy_until_height: self.legacy_signing_until,
         }
     }
+
+    /// Enable stake-weighted mode with power taken from the staking ledger
+    /// (self stake plus delegations) for every configured validator.
+    pub fn with_staking(mut self, ledger: &StakingLedger) -> Self {
+        self.voting_power = Some(staking_power(ledger, &self.validators));
+        self
+    }
+}
+/// Hash of `validators` with `power`, as bound into votes and commits.
+pub fn expected_set_hash(
+    validators: &BTreeSet<ValidatorId>,
+    power: Option<&VotingPower>,
+) -> Result<H256, TideError> {
+    Ok(validator_set_hash_weighted(validators, power)?)
+}
+
+/// Verify a commit certificate against a validator set: every signer must be a member,
+/// signers must reach the `2n/3+1` threshold (over voting power when `power` is set), and
+/// every signature must be a v3 payload for `domain.chain_id`, or a legacy payload within
+/// the domain's compatibility window. Networks with another rule use
+/// [`verify_commit_certificate_with_rule`].
+///
+/// A non-zero `Commit.voting_power` must match the signers' power, and a non-zero
+/// `Commit.validator_set_hash` must match `validators` and `power`; a zero (unbound) hash is
+/// only accepted within the compatibility window.
+///
+/// Freshness and replay windows are not checked, so this is usable for historical
+/// certificates (finality proofs, light clients).
+pub fn verify_commit_certificate_for_chain(
+    c: &Commit,
+    validators: &BTreeSet<ValidatorId>,
+    power: Option<&VotingPower>,
+    domain: SigningDomain<'_>,
+) -> Result<(), TideError> {
+    verify_commit_certificate_with_rule(c, validators, power, domain, &QuorumConfig::TwoThirds)
+}
+
+/// Like [`verify_commit_certificate_for_chain`], with the signers' power checked against
+/// `rule`.
+pub fn verify_commit_certificate_with_rule(
+    c: &Commit,
+    validators: &BTreeSet<ValidatorId>,
+    power: Option<&VotingPower>,
+    domain: SigningDomain<'_>,
+    rule: &dyn QuorumRule,
+) -> Result<(), TideError> {
+    verify_commit_with_keys(
+        c,
+        validators,
+        power,
+        domain,
+        rule,
+        &PublicKeyCache::default(),
+    )
 }
+
+fn verify_commit_with_keys(
+    c: &Commit,
+    validators: &BTreeSet<ValidatorId>,
+    power: Option<&VotingPower>,
+    domain: SigningDomain<'_>,
+    rule: &dyn QuorumRule,
+    keys: &PublicKeyCache,
+) -> Result<(), TideError> {
+    for (vid, _sig) in c.signatures.iter() {
+        if !validators.contains(vid) {
+            return Err(TideError::UnknownValidator);
+        }
+    }
+
+    let (signed, total) = tally(c.signatures.keys(), validators, power);
+    if !rule.reached(signed, total) {
+        return Err(TideError::NotEnoughVotes);
+    }
+    if c.voting_power != 0 && c.voting_power != signed {
+        return Err(TideError::PowerMismatch);
+    }
+    if c.validator_set_hash == H256::ZERO {
+        if !domain.accepts_legacy(c.height) {
+            return Err(TideError::ValidatorSetMismatch);
+        }
+    } else if c.validator_set_hash != expected_set_hash(validators, power)? {
+        return Err(TideError::ValidatorSetMismatch);
+    }
+
+    for (vid, sig) in c.signatures.iter() {
+        let pk_bytes = vid.as_bytes();
+        let candidates = domain.candidates(
+            c.height,
+            c.round,
+            c.epoch,
+            c.msg_counter,
+            c.sent_ts_ms,
+            c.ttl_ms,
+            c.block_hash,
+            c.validator_set_hash,
+            vid,
+        )?;
+        verify_any(keys, pk_bytes, &candidates, sig)?;
+    }
+
+    Ok(())
+}
+
+/// Accept `sig` if it verifies over any of the candidate payloads.
+fn verify_any(
+    keys: &PublicKeyCache,
+    pk_bytes: &[u8; 32],
+    candidates: &[Vec<u8>],
+    sig: &Signature,
+) -> Result<(), TideError> {
+    if candidates
+        .iter()
+        .any(|msg| keys.verify(pk_bytes, msg, sig).is_ok())
+    {
+        Ok(())
+    } else {
+        Err(TideError::BadSignature)
+    }
+}
+
 /// Stored metadata for replay-window sealed votes.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 struct VoteMeta {
-    epoch: u64,
+    epoch: Epoch,
     msg_counter: u64,
     sent_ts_ms: u64,
     ttl_ms: u32,
@@ -105,32 +364,207 @@ struct VoteMeta {
 
 #[derive(Clone, Copy, Debug)]
 struct ReplayState {
-    epoch: u64,
     last_counter: u64,
     last_sent_ts_ms: u64,
 }
 type VoteMap = BTreeMap<ValidatorId, (H256, Signature, VoteMeta)>;
 
+/// Evidence Tide holds for `take_evidence`; older entries are dropped beyond this.
+const MAX_LOCAL_EVIDENCE: usize = 64;
+
 /// Tide finalizer state.
 pub struct TideFinalizer<S: Slashing> {
     cfg: TideConfig,
     slashing: S,
     // votes[height][round] = { voter -> (block_hash, sig, meta) }
-    votes: BTreeMap<u64, BTreeMap<u64, VoteMap>>,
-    // Per-validator replay protection state (best-effort).
-    replay: BTreeMap<ValidatorId, ReplayState>,
+    votes: BTreeMap<Height, BTreeMap<Round, VoteMap>>,
+    // replay[epoch][voter]: last accepted counter and timestamp. Only the newest
+    // `cfg.replay_epochs` epochs are kept.
+    replay: BTreeMap<Epoch, BTreeMap<ValidatorId, ReplayState>>,
+    // Highest finalized height observed; vote state below it is pruned.
+    finalized_height: Height,
+    // Set hash in effect before the last validator set or power change, to tell votes
+    // still in flight for the old set apart from forgeries.
+    previous_set_hash: Option<H256>,
+    // Parsed keys of `cfg.validators`, rebuilt with the set.
+    keys: PublicKeyCache,
+    metrics: Option<Arc<Metrics>>,
+    // Double votes detected locally, not yet taken by the driver.
+    evidence: Vec<Evidence>,
 }
 impl<S: Slashing> TideFinalizer<S> {
     /// Create a new finalizer.
     pub fn new(cfg: TideConfig, slashing: S) -> Self {
+        let keys = PublicKeyCache::new(cfg.validators.iter().map(ValidatorId::as_bytes));
         Self {
+            keys,
             cfg,
             slashing,
             votes: BTreeMap::new(),
             replay: BTreeMap::new(),
+            finalized_height: Height::ZERO,
+            previous_set_hash: None,
+            metrics: None,
+            evidence: Vec::new(),
+        }
+    }
+
+    /// Record vote verification and commit build latencies.
+    pub fn set_metrics(&mut self, metrics: Arc<Metrics>) {
+        self.metrics = Some(metrics);
+    }
+
+    /// Highest finalized height observed.
+    pub fn finalized_height(&self) -> Height {
+        self.finalized_height
+    }
+
+    /// Number of heights with buffered votes.
+    pub fn retained_heights(&self) -> usize {
+        self.votes.len()
+    }
+
+    /// Highest round with votes at `height`, and how many votes it has.
+    pub fn latest_round(&self, height: Height) -> Option<(Round, usize)> {
+        self.votes
+            .get(&height)?
+            .iter()
+            .next_back()
+            .map(|(round, m)| (*round, m.len()))
+    }
+
+    /// Number of epochs with replay counters.
+    pub fn retained_replay_epochs(&self) -> usize {
+        self.replay.len()
+    }
+
+    /// Total buffered votes across all heights and rounds.
+    pub fn retained_votes(&self) -> usize {
+        self.votes
+            .values()
+            .flat_map(|rounds| rounds.values())
+            .map(|m| m.len())
+            .sum()
+    }
+
+    /// Record finality of `height` and drop vote state below it.
+    ///
+    /// Votes at `height` itself are kept so late conflicting votes are still detected as
+    /// double votes.
+    pub fn mark_finalized(&mut self, height: Height) {
+        if height <= self.finalized_height {
+            return;
+        }
+        self.finalized_height = height;
+        self.votes = self.votes.split_off(&height);
+    }
+
+    fn check_window(
+        &self,
+        height: Height,
+        round: Round,
+        voter: &ValidatorId,
+    ) -> Result<(), TideError> {
+        if height < self.finalized_height {
+            return Err(TideError::Replay);
+        }
+        if height
+            > self
+                .finalized_height
+                .saturating_add(self.cfg.max_future_heights)
+        {
+            return Err(TideError::OutOfWindow);
+        }
+        let held = self.voter_rounds(height, voter);
+        if held.len() >= self.cfg.max_rounds_per_voter.max(1)
+            && !held.contains(&round)
+            && held.first().is_some_and(|lowest| round < *lowest)
+        {
+            return Err(TideError::OutOfWindow);
+        }
+        Ok(())
+    }
+
+    // Rounds `voter` has a vote buffered in at `height`, lowest first.
+    fn voter_rounds(&self, height: Height, voter: &ValidatorId) -> Vec<Round> {
+        self.votes.get(&height).map_or_else(Vec::new, |rounds| {
+            rounds
+                .iter()
+                .filter(|(_, votes)| votes.contains_key(voter))
+                .map(|(round, _)| *round)
+                .collect()
+        })
+    }
+
+    // Drops `voter`'s lowest rounds at `height` beyond `max_rounds_per_voter`.
+    fn evict_voter_rounds(&mut self, height: Height, voter: &ValidatorId) {
+        let held = self.voter_rounds(height, voter);
+        let excess = held
+            .len()
+            .saturating_sub(self.cfg.max_rounds_per_voter.max(1));
+        let Some(rounds) = self.votes.get_mut(&height) else {
+            return;
+        };
+        for round in held.into_iter().take(excess) {
+            if let Some(votes) = rounds.get_mut(&round) {
+                votes.remove(voter);
+                if votes.is_empty() {
+                    rounds.remove(&round);
+                }
+            }
+        }
+    }
+
+    /// Validator set currently used for verification and thresholds.
+    pub fn validators(&self) -> &BTreeSet<ValidatorId> {
+        &self.cfg.validators
+    }
+
+    /// Replace the active validator set (e.g. after liveness jailing or an epoch change).
+    pub fn set_validators(&mut self, validators: BTreeSet<ValidatorId>) {
+        self.remember_set_hash();
+        self.keys = PublicKeyCache::new(validators.iter().map(ValidatorId::as_bytes));
+        self.cfg.validators = validators;
+    }
+
+    /// Voting power table in effect (`None` in count mode).
+    pub fn voting_power(&self) -> Option<&VotingPower> {
+        self.cfg.voting_power.as_ref()
+    }
+
+    /// Hash of the current validator set and power table, which votes must be bound to.
+    pub fn validator_set_hash(&self) -> Result<H256, TideError> {
+        expected_set_hash(&self.cfg.validators, self.cfg.voting_power.as_ref())
+    }
+
+    /// Switch between count mode (`None`) and stake-weighted mode.
+    pub fn set_voting_power(&mut self, power: Option<VotingPower>) {
+        self.remember_set_hash();
+        self.cfg.voting_power = power;
+    }
+
+    fn remember_set_hash(&mut self) {
+        if let Ok(h) = self.validator_set_hash() {
+            self.previous_set_hash = Some(h);
         }
     }
 
+    /// Bind vote and commit signatures to `chain_id`; see [`TideConfig::with_chain_id`].
+    pub fn set_chain_id(&mut self, chain_id: &str, legacy_until: Option<Height>) {
+        self.cfg.chain_id = chain_id.to_string();
+        self.cfg.legacy_signing_until = legacy_until;
+    }
+
+    /// Rule commits are built and verified with; see [`TideConfig::with_quorum`].
+    pub fn quorum(&self) -> &dyn QuorumRule {
+        self.cfg.quorum.as_ref()
+    }
+
+    /// Replace the commit rule. Votes already buffered are counted with the new rule.
+    pub fn set_quorum(&mut self, rule: Arc<dyn QuorumRule>) {
+        self.cfg.quorum = rule;
+    }
+
     fn now_ms() -> u64 {
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -179,63 +613,95 @@ impl<S: Slashing> TideFinalizer<S> {
         Ok(())
     }
 
+    /// Oldest epoch whose replay state is kept, given the newest seen so far.
+    fn oldest_replay_epoch(&self) -> Epoch {
+        self.replay
+            .last_key_value()
+            .map_or(Epoch::ZERO, |(newest, _)| {
+                newest.saturating_sub(self.cfg.replay_epochs.max(1) - 1)
+            })
+    }
+
     fn check_replay_counter(
-        &mut self,
+        &self,
         voter: &ValidatorId,
-        epoch: u64,
+        epoch: Epoch,
         msg_counter: u64,
         sent_ts_ms: u64,
     ) -> Result<(), TideError> {
         // Legacy messages do not carry replay protection fields.
-        if epoch == 0 && msg_counter == 0 && sent_ts_ms == 0 {
+        if epoch.is_zero() && msg_counter == 0 && sent_ts_ms == 0 {
             if self.cfg.require_epoch {
                 return Err(TideError::Replay);
             }
             return Ok(());
         }
-        if self.cfg.require_epoch && epoch == 0 {
+        if self.cfg.require_epoch && epoch.is_zero() {
             return Err(TideError::Replay);
         }
-
-        let st = self.replay.get(voter).copied();
-        if let Some(prev) = st {
-            if prev.epoch == epoch {
-                if msg_counter != 0 && msg_counter <= prev.last_counter {
-                    return Err(TideError::Replay);
-                }
-                // Best-effort: also require non-decreasing timestamps if provided.
-                if sent_ts_ms != 0 && prev.last_sent_ts_ms != 0 && sent_ts_ms < prev.last_sent_ts_ms
-                {
-                    return Err(TideError::Replay);
-                }
+        // Pruned epochs have no counters left to compare against.
+        if epoch < self.oldest_replay_epoch() {
+            return Err(TideError::Replay);
+        }
+        // A validator's epochs only move forward; an older one after a newer is a replay.
+        let next = epoch.checked_next().unwrap_or(Epoch::MAX);
+        if self
+            .replay
+            .range(next..)
+            .any(|(_, voters)| voters.contains_key(voter))
+        {
+            return Err(TideError::Replay);
+        }
+        if let Some(prev) = self.replay.get(&epoch).and_then(|e| e.get(voter)) {
+            if msg_counter != 0 && msg_counter <= prev.last_counter {
+                return Err(TideError::Replay);
+            }
+            // Best-effort: also require non-decreasing timestamps if provided.
+            if sent_ts_ms != 0 && prev.last_sent_ts_ms != 0 && sent_ts_ms < prev.last_sent_ts_ms {
+                return Err(TideError::Replay);
             }
         }
+        Ok(())
+    }
 
-        // Update replay state (best-effort).
-        self.replay.insert(
+    // Only called once the signature checked out, so forged messages cannot advance a
+    // validator's counter.
+    fn record_replay_counter(
+        &mut self,
+        voter: &ValidatorId,
+        epoch: Epoch,
+        msg_counter: u64,
+        sent_ts_ms: u64,
+    ) {
+        if epoch.is_zero() && msg_counter == 0 && sent_ts_ms == 0 {
+            return;
+        }
+        self.replay.entry(epoch).or_default().insert(
             voter.clone(),
             ReplayState {
-                epoch,
                 last_counter: msg_counter,
                 last_sent_ts_ms: sent_ts_ms,
             },
         );
-
-        Ok(())
+        let oldest = self.oldest_replay_epoch();
+        self.replay = self.replay.split_off(&oldest);
     }
     /// Verify vote signature then process.
     pub fn process_vote_verified(&mut self, v: Vote) -> Result<Option<Commit>, TideError> {
         if !self.cfg.validators.contains(&v.voter) {
             return Err(TideError::UnknownValidator);
         }
+        self.check_window(v.height, v.round, &v.voter)?;
 
         self.check_freshness(v.sent_ts_ms, v.ttl_ms)?;
         self.check_replay_counter(&v.voter, v.epoch, v.msg_counter, v.sent_ts_ms)?;
-        let pk_bytes = v
-            .voter
-            .as_public_key_bytes()
-            .ok_or(TideError::BadSignature)?;
-        let msg = vote_signing_bytes_auto(
+        let span = tracing::debug_span!("consensus.verify_vote", height = v.height.get()).entered();
+        let timer = self
+            .metrics
+            .as_ref()
+            .map(|m| m.consensus_vote_verify_seconds.start_timer());
+        let pk_bytes = v.voter.as_bytes();
+        let candidates = self.cfg.signing_domain().candidates(
             v.height,
             v.round,
             v.epoch,
@@ -243,50 +709,160 @@ impl<S: Slashing> TideFinalizer<S> {
             v.sent_ts_ms,
             v.ttl_ms,
             v.block_hash,
+            self.validator_set_hash()?,
             &v.voter,
         )?;
-        verify_pubkey_bytes(&pk_bytes, &msg, &v.signature).map_err(|_| TideError::BadSignature)?;
+        if let Err(e) = verify_any(&self.keys, pk_bytes, &candidates, &v.signature) {
+            return Err(self.classify_bad_vote(&v).unwrap_or(e));
+        }
+        drop(timer);
+        drop(span);
+        self.record_replay_counter(&v.voter, v.epoch, v.msg_counter, v.sent_ts_ms);
 
         self.process_vote_inner(v)
     }
 
+    // A vote that fails verification but is validly bound to the previous set was signed
+    // before the set changed under it: stale, not forged.
+    fn classify_bad_vote(&self, v: &Vote) -> Option<TideError> {
+        let prev = self.previous_set_hash?;
+        if prev == H256::ZERO || Some(prev) == self.validator_set_hash().ok() {
+            return None;
+        }
+        let msg = vote_signing_bytes_v3(
+            &self.cfg.chain_id,
+            v.height,
+            v.round,
+            v.epoch,
+            v.msg_counter,
+            v.sent_ts_ms,
+            v.ttl_ms,
+            v.block_hash,
+            prev,
+            &v.voter,
+        )
+        .ok()?;
+        self.keys
+            .verify(v.voter.as_bytes(), &msg, &v.signature)
+            .is_ok()
+            .then_some(TideError::ValidatorSetMismatch)
+    }
+
     /// Verify commit signatures (supermajority) and accept.
     pub fn process_commit_verified(&mut self, c: Commit) -> Result<(), TideError> {
         self.check_freshness(c.sent_ts_ms, c.ttl_ms)?;
-        if self.cfg.require_epoch && c.epoch == 0 {
+        if self.cfg.require_epoch && c.epoch.is_zero() {
             return Err(TideError::Replay);
         }
-        for (vid, _sig) in c.signatures.iter() {
-            if !self.cfg.validators.contains(vid) {
-                return Err(TideError::UnknownValidator);
-            }
-        }
+        verify_commit_with_keys(
+            &c,
+            &self.cfg.validators,
+            self.cfg.voting_power.as_ref(),
+            self.cfg.signing_domain(),
+            self.cfg.quorum.as_ref(),
+            &self.keys,
+        )?;
+        self.mark_finalized(c.height);
+        Ok(())
+    }
 
-        let n = self.cfg.validators.len();
-        let threshold = (2 * n) / 3 + 1;
-        if c.signatures.len() < threshold {
-            return Err(TideError::NotEnoughVotes);
-        }
+    /// Check a commit included on chain against the current set: signers, quorum, set hash
+    /// and signatures. Freshness and epoch requirements do not apply; the commit may be old.
+    pub fn verify_included_commit(&self, c: &Commit) -> Result<(), TideError> {
+        verify_commit_with_keys(
+            c,
+            &self.cfg.validators,
+            self.cfg.voting_power.as_ref(),
+            self.cfg.signing_domain(),
+            self.cfg.quorum.as_ref(),
+            &self.keys,
+        )
+    }
+
+    /// Evidence for the double votes detected since the last call, oldest first.
+    pub fn take_evidence(&mut self) -> Vec<Evidence> {
+        std::mem::take(&mut self.evidence)
+    }
 
-        for (vid, sig) in c.signatures.iter() {
-            let pk_bytes = vid.as_public_key_bytes().ok_or(TideError::BadSignature)?;
-            let bytes = vote_signing_bytes_auto(
-                c.height,
-                c.round,
-                c.epoch,
-                c.msg_counter,
-                c.sent_ts_ms,
-                c.ttl_ms,
-                c.block_hash,
-                vid,
-            )?;
-            verify_pubkey_bytes(&pk_bytes, &bytes, sig).map_err(|_| TideError::BadSignature)?;
+    /// Check evidence received from a peer: the offender is in the current set, the votes
+    /// conflict, and both signatures verify for the current or the previous validator set.
+    /// Freshness and replay windows do not apply; the offense may be old.
+    pub fn verify_evidence(&self, ev: &Evidence) -> Result<(), TideError> {
+        let Evidence::DoubleVote(dv) = ev;
+        if !self.cfg.validators.contains(&dv.first.voter) {
+            return Err(TideError::UnknownValidator);
+        }
+        if !dv.is_conflicting() {
+            return Err(TideError::InvalidEvidence);
         }
+        let current = self.validator_set_hash()?;
+        for v in [&dv.first, &dv.second] {
+            let signed_for = |set_hash: H256| -> Result<(), TideError> {
+                let candidates = self.cfg.signing_domain().candidates(
+                    v.height,
+                    v.round,
+                    v.epoch,
+                    v.msg_counter,
+                    v.sent_ts_ms,
+                    v.ttl_ms,
+                    v.block_hash,
+                    set_hash,
+                    &v.voter,
+                )?;
+                verify_any(&self.keys, v.voter.as_bytes(), &candidates, &v.signature)
+            };
+            signed_for(current).or_else(|e| match self.previous_set_hash {
+                Some(prev) if prev != current => signed_for(prev),
+                _ => Err(e),
+            })?;
+        }
+        Ok(())
+    }
 
+    /// `verify_evidence`, then report the offender to the slashing hook.
+    pub fn process_evidence(&self, ev: &Evidence) -> Result<(), TideError> {
+        self.verify_evidence(ev)?;
+        self.slashing.on_double_vote(ev.offender());
         Ok(())
     }
 
+    /// Whether this exact vote (same block and replay fields) is already recorded.
+    pub fn has_vote(&self, v: &Vote) -> bool {
+        self.votes
+            .get(&v.height)
+            .and_then(|h| h.get(&v.round))
+            .and_then(|r| r.get(&v.voter))
+            .is_some_and(|(hash, sig, meta)| {
+                hash == &v.block_hash
+                    && sig == &v.signature
+                    && *meta
+                        == VoteMeta {
+                            epoch: v.epoch,
+                            msg_counter: v.msg_counter,
+                            sent_ts_ms: v.sent_ts_ms,
+                            ttl_ms: v.ttl_ms,
+                        }
+            })
+    }
+
+    /// The vote `voter` cast at `height`/`round`, while it is retained.
+    pub fn vote(&self, height: Height, round: Round, voter: &ValidatorId) -> Option<Vote> {
+        let (block_hash, signature, meta) = self.votes.get(&height)?.get(&round)?.get(voter)?;
+        Some(Vote {
+            height,
+            round,
+            epoch: meta.epoch,
+            msg_counter: meta.msg_counter,
+            sent_ts_ms: meta.sent_ts_ms,
+            ttl_ms: meta.ttl_ms,
+            block_hash: *block_hash,
+            voter: voter.clone(),
+            signature: signature.clone(),
+        })
+    }
+
     fn process_vote_inner(&mut self, v: Vote) -> Result<Option<Commit>, TideError> {
+        self.check_window(v.height, v.round, &v.voter)?;
         let height_votes = self.votes.entry(v.height).or_default();
         let round_votes = height_votes.entry(v.round).or_default();
 
@@ -297,19 +873,46 @@ impl<S: Slashing> TideFinalizer<S> {
             ttl_ms: v.ttl_ms,
         };
 
-        if let Some((prev_hash, _prev_sig, prev_meta)) = round_votes.get(&v.voter) {
+        if let Some((prev_hash, prev_sig, prev_meta)) = round_votes.get(&v.voter) {
             if prev_hash != &v.block_hash || prev_meta != &meta {
+                let prev = Vote {
+                    height: v.height,
+                    round: v.round,
+                    epoch: prev_meta.epoch,
+                    msg_counter: prev_meta.msg_counter,
+                    sent_ts_ms: prev_meta.sent_ts_ms,
+                    ttl_ms: prev_meta.ttl_ms,
+                    block_hash: *prev_hash,
+                    voter: v.voter.clone(),
+                    signature: prev_sig.clone(),
+                };
                 self.slashing.on_double_vote(&v.voter);
+                if let Some(ev) = DoubleVoteEvidence::new(prev, v) {
+                    if self.evidence.len() >= MAX_LOCAL_EVIDENCE {
+                        self.evidence.remove(0);
+                    }
+                    self.evidence.push(Evidence::DoubleVote(ev));
+                }
                 return Err(TideError::DoubleVote);
             }
             return Ok(None); // duplicate same vote
         }
 
         round_votes.insert(v.voter.clone(), (v.block_hash, v.signature.clone(), meta));
-        self.try_build_commit(v.height, v.round)
+        self.evict_voter_rounds(v.height, &v.voter);
+        let timer = self
+            .metrics
+            .as_ref()
+            .map(|m| m.consensus_commit_build_seconds.start_timer());
+        let commit = self.try_build_commit(v.height, v.round)?;
+        drop(timer);
+        if commit.is_some() {
+            self.mark_finalized(v.height);
+        }
+        Ok(commit)
     }
 
-    fn try_build_commit(&self, height: u64, round: u64) -> Result<Option<Commit>, TideError> {
+    fn try_build_commit(&self, height: Height, round: Round) -> Result<Option<Commit>, TideError> {
         let Some(hm) = self.votes.get(&height) else {
             return Ok(None);
         };
@@ -317,16 +920,18 @@ impl<S: Slashing> TideFinalizer<S> {
             return Ok(None);
         };
 
-        let mut counts: BTreeMap<(H256, VoteMeta), usize> = BTreeMap::new();
-        for (hash, _sig, meta) in rm.values() {
-            *counts.entry((*hash, *meta)).or_insert(0) += 1;
+        let mut groups: BTreeMap<(H256, VoteMeta), Vec<&ValidatorId>> = BTreeMap::new();
+        for (vid, (hash, _sig, meta)) in rm.iter() {
+            groups.entry((*hash, *meta)).or_default().push(vid);
         }
 
-        let n = self.cfg.validators.len();
-        let threshold = (2 * n) / 3 + 1;
-
-        for ((hash, meta), c) in counts.iter() {
-            if *c >= threshold {
+        for ((hash, meta), voters) in groups.iter() {
+            let (signed, total) = tally(
+                voters.iter().copied(),
+                &self.cfg.validators,
+                self.cfg.voting_power.as_ref(),
+            );
+            if self.cfg.quorum.reached(signed, total) {
                 let mut sigs: CanonicalMap<ValidatorId, Signature> = CanonicalMap::new();
                 for (vid, (vh, vsig, vm)) in rm.iter() {
                     if vh == hash && vm == meta {
@@ -342,6 +947,8 @@ impl<S: Slashing> TideFinalizer<S> {
                     ttl_ms: meta.ttl_ms,
                     block_hash: *hash,
                     signatures: sigs,
+                    voting_power: signed,
+                    validator_set_hash: self.validator_set_hash()?,
                 }));
             }
         }
@@ -0,0 +1,185 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Vote arrival times, for finality-latency analytics.
+//!
+//! A commit certificate carries one `sent_ts_ms`, shared by every vote it aggregates (Tide
+//! groups votes by block and replay fields), so the certificate alone cannot tell a fast
+//! validator from a slow one. The driver therefore notes when each verified vote arrived and,
+//! once a height is finalized, writes a `CommitTimings` sidecar next to the certificate in
+//! the commit store: the signed send time, the local arrival time of each contributing vote,
+//! and the local finalization time. It does not change the certificate or its signatures.
+//!
+//! `VoteTimings` also keeps the last `LATENCY_WINDOW` vote delays (arrival minus send time)
+//! per validator, which `GET /consensus/performance` summarizes for delegators. Delays are
+//! measured against the local clock, so they include this node's clock skew
+//! (`amunchain_consensus_clock_skew_ms`).
+
+use crate::core::types::{Commit, Height, Round, ValidatorId, H256};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// Vote delays kept per validator.
+pub const LATENCY_WINDOW: usize = 512;
+
+/// Heights whose vote arrivals are kept while waiting for a commit.
+const MAX_OPEN_HEIGHTS: usize = 64;
+
+/// Timing sidecar of one finalized commit.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitTimings {
+    pub height: Height,
+    pub round: Round,
+    pub block_hash: H256,
+    /// Send time signed into the certificate's votes (0 => legacy, no timing).
+    pub sent_ts_ms: u64,
+    /// Local time the height was finalized.
+    pub finalized_ms: u64,
+    /// Arrival of each signer's vote, by validator. Signers whose vote this node never saw
+    /// (it finalized from a peer's certificate) are absent.
+    pub votes: Vec<VoteArrival>,
+}
+
+/// Local arrival time of one contributing vote.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoteArrival {
+    pub validator: ValidatorId,
+    pub received_ms: u64,
+}
+
+impl CommitTimings {
+    /// Arrival delay of `validator`'s vote, if it was seen and the commit is timed.
+    pub fn vote_delay_ms(&self, validator: &ValidatorId) -> Option<u64> {
+        let i = self
+            .votes
+            .binary_search_by(|a| a.validator.cmp(validator))
+            .ok()?;
+        let received = self.votes[i].received_ms;
+        (self.sent_ts_ms != 0).then(|| received.saturating_sub(self.sent_ts_ms))
+    }
+
+    /// Time from the signed send time to local finality.
+    pub fn finality_ms(&self) -> Option<u64> {
+        (self.sent_ts_ms != 0).then(|| self.finalized_ms.saturating_sub(self.sent_ts_ms))
+    }
+}
+
+/// Vote delay distribution of one validator over its recent finalized votes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorPerformance {
+    /// Validator public key, hex.
+    pub validator: String,
+    /// Delays the figures are computed over (at most `LATENCY_WINDOW`).
+    pub samples: usize,
+    pub p50_ms: u64,
+    pub p90_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
+}
+
+/// Vote arrivals of open heights and delay windows per validator.
+#[derive(Debug, Default)]
+pub struct VoteTimings {
+    // arrivals[(height, round)][voter] = first local arrival, ms.
+    arrivals: BTreeMap<(Height, Round), BTreeMap<ValidatorId, u64>>,
+    delays: BTreeMap<ValidatorId, VecDeque<u64>>,
+}
+
+impl VoteTimings {
+    /// Note the arrival of a verified vote. Only the first arrival per voter counts.
+    pub fn on_vote(&mut self, height: Height, round: Round, voter: &ValidatorId, now_ms: u64) {
+        self.arrivals
+            .entry((height, round))
+            .or_default()
+            .entry(voter.clone())
+            .or_insert(now_ms);
+        while self.arrivals.len() > MAX_OPEN_HEIGHTS {
+            self.arrivals.pop_first();
+        }
+    }
+
+    /// Every validator with a verified vote at `height`, in any round. Only heights not yet
+    /// passed to `on_commit` (and late votes for the last one) are still known.
+    pub fn voters(&self, height: Height) -> BTreeSet<ValidatorId> {
+        self.arrivals
+            .range((height, Round::ZERO)..=(height, Round::MAX))
+            .flat_map(|(_, voters)| voters.keys().cloned())
+            .collect()
+    }
+
+    /// Build the sidecar of a finalized commit, fold its delays into the windows and drop
+    /// the arrivals of its height and below.
+    pub fn on_commit(&mut self, commit: &Commit, now_ms: u64) -> CommitTimings {
+        let seen = self
+            .arrivals
+            .get(&(commit.height, commit.round))
+            .cloned()
+            .unwrap_or_default();
+        // Signatures are keyed by validator, so the arrivals come out sorted.
+        let votes: Vec<VoteArrival> = commit
+            .signatures
+            .keys()
+            .filter_map(|v| {
+                seen.get(v).map(|t| VoteArrival {
+                    validator: v.clone(),
+                    received_ms: *t,
+                })
+            })
+            .collect();
+        let timings = CommitTimings {
+            height: commit.height,
+            round: commit.round,
+            block_hash: commit.block_hash,
+            sent_ts_ms: commit.sent_ts_ms,
+            finalized_ms: now_ms,
+            votes,
+        };
+        for a in timings.votes.iter() {
+            if let Some(delay) = timings.vote_delay_ms(&a.validator) {
+                let window = self.delays.entry(a.validator.clone()).or_default();
+                if window.len() >= LATENCY_WINDOW {
+                    window.pop_front();
+                }
+                window.push_back(delay);
+            }
+        }
+        let next = commit.height.saturating_add(1);
+        self.arrivals = self.arrivals.split_off(&(next, Round::ZERO));
+        timings
+    }
+
+    /// Delay distribution of `validator`, if it has any timed votes.
+    pub fn performance(&self, validator: &ValidatorId) -> Option<ValidatorPerformance> {
+        let window = self.delays.get(validator).filter(|w| !w.is_empty())?;
+        let mut sorted: Vec<u64> = window.iter().copied().collect();
+        sorted.sort_unstable();
+        // Nearest rank.
+        let rank = |p: usize| sorted[(sorted.len() * p).div_ceil(100).max(1) - 1];
+        Some(ValidatorPerformance {
+            validator: hex::encode(validator.as_bytes()),
+            samples: sorted.len(),
+            p50_ms: rank(50),
+            p90_ms: rank(90),
+            p99_ms: rank(99),
+            max_ms: sorted[sorted.len() - 1],
+        })
+    }
+
+    /// `performance` of every validator with timed votes, by validator.
+    pub fn report(&self) -> Vec<ValidatorPerformance> {
+        self.delays
+            .keys()
+            .filter_map(|v| self.performance(v))
+            .collect()
+    }
+}
@@ -0,0 +1,170 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0
+
+//! Token supply and account balances.
+//!
+//! Balances live in the main state tree next to the staking ledger:
+//!
+//! ```text
+//! bank/v1/supply          -> u128
+//! bank/v1/bal/<account>   -> u128
+//! ```
+//!
+//! Bonded stake is held by the `BONDED_POOL` account, so bonding and unbonding are plain
+//! transfers and `total_supply` always equals the sum of all balances. New tokens only
+//! enter through `mint` (genesis allocation, and rewards minted into `REWARDS_POOL` by
+//! `StakingLedger::distribute_rewards_from`); slashed stake leaves through `burn` (see
+//! `StakingLedger::slash_from`).
+
+#![forbid(unsafe_code)]
+
+use crate::core::state::persistent_state::{KvOp, PersistentState, StateError};
+use crate::core::types::{decode_canonical_limited, encode_canonical};
+use std::collections::{BTreeMap, BTreeSet};
+use thiserror::Error;
+
+/// Prefix of all bank keys (current layout).
+pub const BANK_PREFIX: &[u8] = b"bank/v1/";
+const SUPPLY_KEY: &[u8] = b"bank/v1/supply";
+const BALANCE_PREFIX: &[u8] = b"bank/v1/bal/";
+
+/// Account holding all bonded and unbonding stake. `StakingLedger::slash_from` burns
+/// slashed stake from here.
+pub const BONDED_POOL: &[u8] = b"module/bonded";
+
+/// Account holding distributed rewards and commission until they are claimed (see
+/// `StakingLedger::claim_rewards_to`).
+pub const REWARDS_POOL: &[u8] = b"module/rewards";
+
+#[derive(Debug, Error)]
+pub enum BankError {
+    #[error("insufficient funds")]
+    InsufficientFunds,
+    #[error("invalid amount")]
+    InvalidAmount,
+    #[error("supply overflow")]
+    Overflow,
+    #[error("supply underflow")]
+    SupplyUnderflow,
+}
+
+/// Account balances and total supply.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bank {
+    pub balances: BTreeMap<Vec<u8>, u128>,
+    pub total_supply: u128,
+}
+
+impl Bank {
+    /// Balance of `account` (zero if unknown).
+    pub fn balance(&self, account: &[u8]) -> u128 {
+        self.balances.get(account).copied().unwrap_or(0)
+    }
+
+    /// Move `amount` between accounts.
+    pub fn transfer(&mut self, from: &[u8], to: &[u8], amount: u128) -> Result<(), BankError> {
+        if amount == 0 {
+            return Err(BankError::InvalidAmount);
+        }
+        self.debit(from, amount)?;
+        self.credit(to, amount);
+        Ok(())
+    }
+
+    /// Create `amount` new tokens in `to`. Only genesis allocation and reward issuance mint.
+    pub fn mint(&mut self, to: &[u8], amount: u128) -> Result<(), BankError> {
+        if amount == 0 {
+            return Err(BankError::InvalidAmount);
+        }
+        self.total_supply = self
+            .total_supply
+            .checked_add(amount)
+            .ok_or(BankError::Overflow)?;
+        self.credit(to, amount);
+        Ok(())
+    }
+
+    /// Destroy `amount` tokens held by `from`. Nothing changes on error.
+    pub fn burn(&mut self, from: &[u8], amount: u128) -> Result<(), BankError> {
+        if amount == 0 {
+            return Err(BankError::InvalidAmount);
+        }
+        let supply = self
+            .total_supply
+            .checked_sub(amount)
+            .ok_or(BankError::SupplyUnderflow)?;
+        self.debit(from, amount)?;
+        self.total_supply = supply;
+        Ok(())
+    }
+
+    fn debit(&mut self, account: &[u8], amount: u128) -> Result<(), BankError> {
+        let bal = self.balance(account);
+        if bal < amount {
+            return Err(BankError::InsufficientFunds);
+        }
+        if bal == amount {
+            self.balances.remove(account);
+        } else {
+            self.balances.insert(account.to_vec(), bal - amount);
+        }
+        Ok(())
+    }
+
+    // Cannot overflow: every balance is bounded by `total_supply`.
+    fn credit(&mut self, account: &[u8], amount: u128) {
+        *self.balances.entry(account.to_vec()).or_default() += amount;
+    }
+
+    /// Put operations for the supply and every non-zero balance, in key order.
+    pub fn to_ops(&self) -> Result<Vec<KvOp>, StateError> {
+        let mut ops = vec![KvOp::Put {
+            key: SUPPLY_KEY.to_vec(),
+            value: encode_canonical(&self.total_supply).map_err(|_| StateError::DbIo)?,
+        }];
+        for (account, bal) in self.balances.iter().filter(|(_, b)| **b > 0) {
+            let mut key = BALANCE_PREFIX.to_vec();
+            key.extend_from_slice(account);
+            ops.push(KvOp::Put {
+                key,
+                value: encode_canonical(bal).map_err(|_| StateError::DbIo)?,
+            });
+        }
+        Ok(ops)
+    }
+
+    /// Persist balances atomically, deleting emptied accounts.
+    pub fn commit(&self, state: &PersistentState) -> Result<(), StateError> {
+        let puts = self.to_ops()?;
+        let live: BTreeSet<&[u8]> = puts
+            .iter()
+            .filter_map(|op| match op {
+                KvOp::Put { key, .. } => Some(key.as_slice()),
+                KvOp::Del { .. } => None,
+            })
+            .collect();
+        let mut ops: Vec<KvOp> = state
+            .scan_prefix(BANK_PREFIX)?
+            .into_iter()
+            .filter(|(k, _)| !live.contains(k.as_slice()))
+            .map(|(key, _)| KvOp::Del { key })
+            .collect();
+        ops.extend(puts);
+        state.commit_atomic(ops)
+    }
+
+    /// Load balances from state.
+    pub fn load(state: &PersistentState) -> Result<Self, StateError> {
+        let mut bank = Self::default();
+        if let Some(raw) = state.get(SUPPLY_KEY)? {
+            bank.total_supply = decode_canonical_limited(&raw, 32).map_err(|_| StateError::DbIo)?;
+        }
+        for (k, raw) in state.scan_prefix(BALANCE_PREFIX)? {
+            let bal: u128 = decode_canonical_limited(&raw, 32).map_err(|_| StateError::DbIo)?;
+            bank.balances
+                .insert(k[BALANCE_PREFIX.len()..].to_vec(), bal);
+        }
+        Ok(bank)
+    }
+}
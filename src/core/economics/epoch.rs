@@ -0,0 +1,156 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0
+
+//! Epoch transition preview.
+//!
+//! Computes what the next epoch would look like from the current ledger (validator set,
+//! voting power distribution, expected rewards) without touching it. Hypothetical bonds
+//! and unbonds can be layered on top to answer "what if" questions before submitting them.
+
+#![forbid(unsafe_code)]
+
+use crate::core::economics::staking::{StakingError, StakingLedger};
+use serde::Serialize;
+
+/// Epoch selection and reward policy.
+#[derive(Clone, Debug)]
+pub struct EpochPolicy {
+    /// Largest active set; validators are ranked by voting power.
+    pub max_validators: usize,
+    /// Reward minted per epoch, split across the active set by voting power.
+    pub epoch_reward: u128,
+}
+
+impl Default for EpochPolicy {
+    fn default() -> Self {
+        Self {
+            max_validators: 100,
+            epoch_reward: 0,
+        }
+    }
+}
+
+/// Hypothetical ledger change applied before the preview.
+#[derive(Clone, Debug)]
+pub enum PendingOp {
+    Bond {
+        delegator: Vec<u8>,
+        validator: Vec<u8>,
+        amount: u128,
+    },
+    Unbond {
+        delegator: Vec<u8>,
+        validator: Vec<u8>,
+        amount: u128,
+    },
+}
+
+/// Active-set entry.
+#[derive(Clone, Debug, Serialize)]
+pub struct ValidatorPreview {
+    /// Validator id (hex).
+    pub validator: String,
+    /// Self stake plus delegations.
+    pub power: u128,
+    /// Share of the active set's power, in bps.
+    pub power_bps: u16,
+    pub commission_bps: u16,
+    /// Gross epoch reward for this validator.
+    pub expected_reward: u128,
+    /// Part of `expected_reward` kept as commission.
+    pub expected_commission: u128,
+}
+
+/// Expected reward for one delegation to an active validator.
+#[derive(Clone, Debug, Serialize)]
+pub struct DelegationPreview {
+    /// Delegator id (hex).
+    pub delegator: String,
+    /// Validator id (hex).
+    pub validator: String,
+    pub stake: u128,
+    pub expected_reward: u128,
+}
+
+/// Next-epoch preview.
+#[derive(Clone, Debug, Serialize)]
+pub struct EpochPreview {
+    /// Power of the active set.
+    pub total_power: u128,
+    /// Active set, highest power first.
+    pub validators: Vec<ValidatorPreview>,
+    /// Delegations to active validators.
+    pub delegations: Vec<DelegationPreview>,
+}
+
+/// Preview the next epoch from `ledger` with `ops` applied to a copy of it.
+///
+/// The active set is `StakingLedger::active_set(policy.max_validators)`. Rewards follow `StakingLedger::distribute_rewards`.
+pub fn preview_next_epoch(
+    ledger: &StakingLedger,
+    policy: &EpochPolicy,
+    ops: &[PendingOp],
+) -> Result<EpochPreview, StakingError> {
+    let mut next = ledger.clone();
+    for op in ops {
+        match op {
+            PendingOp::Bond {
+                delegator,
+                validator,
+                amount,
+            } => next.bond(delegator.clone(), validator.clone(), *amount)?,
+            PendingOp::Unbond {
+                delegator,
+                validator,
+                amount,
+            } => next.begin_unbond(delegator.clone(), validator.clone(), *amount, 0, 0)?,
+        }
+    }
+
+    let ranked = next.active_set(policy.max_validators);
+
+    let total_power = ranked
+        .iter()
+        .fold(0u128, |acc, (_, p)| acc.saturating_add(*p));
+
+    // Run the real reward path on a scratch copy and diff pending rewards.
+    let mut rewarded = next.clone();
+    let mut validators = Vec::with_capacity(ranked.len());
+    for (id, power) in ranked.iter() {
+        let reward = policy
+            .epoch_reward
+            .saturating_mul(*power)
+            .checked_div(total_power)
+            .unwrap_or(0);
+        let commission = rewarded.distribute_rewards(id, reward);
+        validators.push(ValidatorPreview {
+            validator: hex::encode(id),
+            power: *power,
+            power_bps: (power.saturating_mul(10_000) / total_power.max(1)) as u16,
+            commission_bps: next.validators.get(id).map_or(0, |v| v.commission_bps),
+            expected_reward: reward,
+            expected_commission: commission,
+        });
+    }
+
+    let delegations = next
+        .delegations
+        .iter()
+        .filter(|((_, v), d)| d.amount > 0 && ranked.iter().any(|(id, _)| id == v))
+        .map(|((delegator, validator), d)| DelegationPreview {
+            delegator: hex::encode(delegator),
+            validator: hex::encode(validator),
+            stake: d.amount,
+            expected_reward: rewarded
+                .pending_rewards(delegator, validator)
+                .saturating_sub(next.pending_rewards(delegator, validator)),
+        })
+        .collect();
+
+    Ok(EpochPreview {
+        total_power,
+        validators,
+        delegations,
+    })
+}
@@ -0,0 +1,269 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0
+
+//! Fee market: per-block gas limit and an EIP-1559-style base fee.
+//!
+//! The base fee moves by at most `1 / base_fee_change_denominator` per block towards keeping
+//! blocks at `block_gas_limit / elasticity`. A transaction pays `base_fee + tip` per gas for
+//! its whole gas limit when it is admitted to a block (there is no execution refund yet); the
+//! payment sits in `FEE_COLLECTOR` until `finish_block`, which burns the base fee part (less
+//! the treasury share) and pays the tips to the block producer.
+//!
+//! The current base fee is persisted at `fees/v1/base_fee`.
+
+#![forbid(unsafe_code)]
+
+use crate::core::economics::bank::{Bank, BankError};
+use crate::core::state::persistent_state::{KvOp, PersistentState, StateError};
+use crate::core::types::{
+    decode_canonical_limited, encode_canonical, ConsensusConfig, Transaction,
+};
+use thiserror::Error;
+
+/// Account holding fees of the block being built.
+pub const FEE_COLLECTOR: &[u8] = b"module/fees";
+/// Account receiving the treasury share of base fees.
+pub const TREASURY: &[u8] = b"module/treasury";
+const BASE_FEE_KEY: &[u8] = b"fees/v1/base_fee";
+
+const BPS: u128 = 10_000;
+
+#[derive(Debug, Error)]
+pub enum FeeError {
+    #[error("block gas limit exceeded")]
+    BlockGasLimitExceeded,
+    #[error("max fee per gas below base fee")]
+    FeeCapTooLow,
+    #[error("fee overflow")]
+    Overflow,
+    #[error("invalid fee params")]
+    InvalidParams,
+    #[error(transparent)]
+    Bank(#[from] BankError),
+}
+
+/// Fee market parameters (fixed per network at genesis).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeeParams {
+    /// Most gas all transactions of one block may reserve.
+    pub block_gas_limit: u64,
+    /// Target usage is `block_gas_limit / elasticity`.
+    pub elasticity: u64,
+    /// Largest per-block base fee change is `1 / base_fee_change_denominator`.
+    pub base_fee_change_denominator: u64,
+    /// The base fee never drops below this.
+    pub min_base_fee: u128,
+    /// Share of base fees sent to `TREASURY` instead of burned, in bps.
+    pub treasury_bps: u16,
+}
+
+impl Default for FeeParams {
+    fn default() -> Self {
+        Self {
+            block_gas_limit: 30_000_000,
+            elasticity: 2,
+            base_fee_change_denominator: 8,
+            min_base_fee: 1,
+            treasury_bps: 1_000,
+        }
+    }
+}
+
+impl FeeParams {
+    /// Defaults with the gas limit from node config.
+    pub fn from_config(cfg: &ConsensusConfig) -> Self {
+        Self {
+            block_gas_limit: cfg.block_gas_limit,
+            ..Self::default()
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), FeeError> {
+        if self.elasticity == 0
+            || self.block_gas_limit / self.elasticity == 0
+            || self.base_fee_change_denominator == 0
+            || self.min_base_fee == 0
+            || u128::from(self.treasury_bps) > BPS
+        {
+            return Err(FeeError::InvalidParams);
+        }
+        Ok(())
+    }
+
+    /// Gas usage at which the base fee stays put.
+    pub fn target_gas(&self) -> u64 {
+        self.block_gas_limit / self.elasticity
+    }
+
+    /// Base fee of the block after one that used `gas_used` at `base_fee`.
+    pub fn next_base_fee(&self, base_fee: u128, gas_used: u64) -> u128 {
+        let target = u128::from(self.target_gas());
+        let used = u128::from(gas_used);
+        let denom = u128::from(self.base_fee_change_denominator);
+        let next = if used > target {
+            let delta = (base_fee.saturating_mul(used - target) / target / denom).max(1);
+            base_fee.saturating_add(delta)
+        } else {
+            let delta = base_fee.saturating_mul(target - used) / target / denom;
+            base_fee - delta
+        };
+        next.max(self.min_base_fee)
+    }
+}
+
+/// Fee fields of a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeLimits {
+    pub gas_limit: u64,
+    /// Highest total price per gas the sender accepts.
+    pub max_fee_per_gas: u128,
+    /// Highest tip per gas the sender offers the producer.
+    pub max_priority_fee_per_gas: u128,
+}
+
+impl From<&Transaction> for FeeLimits {
+    fn from(tx: &Transaction) -> Self {
+        Self {
+            gas_limit: tx.gas_limit,
+            max_fee_per_gas: tx.max_fee_per_gas,
+            max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+        }
+    }
+}
+
+/// Where a block's fees went.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockFees {
+    pub gas_used: u64,
+    pub burned: u128,
+    pub treasury: u128,
+    pub producer: u128,
+}
+
+/// Base fee state plus the fees of the block being built.
+#[derive(Clone, Debug)]
+pub struct FeeMarket {
+    params: FeeParams,
+    base_fee: u128,
+    gas_used: u64,
+    base_fees: u128,
+    tips: u128,
+}
+
+impl FeeMarket {
+    pub fn new(params: FeeParams, base_fee: u128) -> Result<Self, FeeError> {
+        params.validate()?;
+        Ok(Self {
+            base_fee: base_fee.max(params.min_base_fee),
+            params,
+            gas_used: 0,
+            base_fees: 0,
+            tips: 0,
+        })
+    }
+
+    pub fn params(&self) -> &FeeParams {
+        &self.params
+    }
+
+    /// Base fee per gas of the block being built.
+    pub fn base_fee(&self) -> u128 {
+        self.base_fee
+    }
+
+    /// Gas reserved so far in the block being built.
+    pub fn gas_used(&self) -> u64 {
+        self.gas_used
+    }
+
+    /// Admit a transaction to the current block: reserve its gas and move its fee from
+    /// `sender` to `FEE_COLLECTOR`. Returns the amount charged. Nothing changes on error.
+    pub fn admit(
+        &mut self,
+        bank: &mut Bank,
+        sender: &[u8],
+        tx: FeeLimits,
+    ) -> Result<u128, FeeError> {
+        let gas_used = self
+            .gas_used
+            .checked_add(tx.gas_limit)
+            .filter(|g| *g <= self.params.block_gas_limit)
+            .ok_or(FeeError::BlockGasLimitExceeded)?;
+        if tx.max_fee_per_gas < self.base_fee {
+            return Err(FeeError::FeeCapTooLow);
+        }
+        let tip = tx
+            .max_priority_fee_per_gas
+            .min(tx.max_fee_per_gas - self.base_fee);
+        let gas = u128::from(tx.gas_limit);
+        let base_part = gas.checked_mul(self.base_fee).ok_or(FeeError::Overflow)?;
+        let tip_part = gas.checked_mul(tip).ok_or(FeeError::Overflow)?;
+        let charge = base_part.checked_add(tip_part).ok_or(FeeError::Overflow)?;
+        if charge > 0 {
+            bank.transfer(sender, FEE_COLLECTOR, charge)?;
+        }
+        self.gas_used = gas_used;
+        self.base_fees = self.base_fees.saturating_add(base_part);
+        self.tips = self.tips.saturating_add(tip_part);
+        Ok(charge)
+    }
+
+    /// Close the current block: burn base fees (less the treasury share), pay tips to
+    /// `producer`, and move the base fee for the next block. Nothing changes on error.
+    pub fn finish_block(
+        &mut self,
+        bank: &mut Bank,
+        producer: &[u8],
+    ) -> Result<BlockFees, FeeError> {
+        let treasury = self
+            .base_fees
+            .saturating_mul(u128::from(self.params.treasury_bps))
+            / BPS;
+        let out = BlockFees {
+            gas_used: self.gas_used,
+            burned: self.base_fees - treasury,
+            treasury,
+            producer: self.tips,
+        };
+        // Once `FEE_COLLECTOR` covers all three, only the burn, which goes first, can fail.
+        let owed = self
+            .base_fees
+            .checked_add(self.tips)
+            .ok_or(FeeError::Overflow)?;
+        if bank.balance(FEE_COLLECTOR) < owed {
+            return Err(BankError::InsufficientFunds.into());
+        }
+        if out.burned > 0 {
+            bank.burn(FEE_COLLECTOR, out.burned)?;
+        }
+        if out.treasury > 0 {
+            bank.transfer(FEE_COLLECTOR, TREASURY, out.treasury)?;
+        }
+        if out.producer > 0 {
+            bank.transfer(FEE_COLLECTOR, producer, out.producer)?;
+        }
+        self.base_fee = self.params.next_base_fee(self.base_fee, self.gas_used);
+        self.gas_used = 0;
+        self.base_fees = 0;
+        self.tips = 0;
+        Ok(out)
+    }
+
+    /// Put operation for the current base fee.
+    pub fn to_ops(&self) -> Result<Vec<KvOp>, StateError> {
+        Ok(vec![KvOp::Put {
+            key: BASE_FEE_KEY.to_vec(),
+            value: encode_canonical(&self.base_fee).map_err(|_| StateError::DbIo)?,
+        }])
+    }
+
+    /// Market with the stored base fee, or `params.min_base_fee` at genesis.
+    pub fn load(state: &PersistentState, params: FeeParams) -> Result<Self, StateError> {
+        let base_fee = match state.get(BASE_FEE_KEY)? {
+            Some(raw) => decode_canonical_limited(&raw, 32).map_err(|_| StateError::DbIo)?,
+            None => params.min_base_fee,
+        };
+        Self::new(params, base_fee).map_err(|_| StateError::DbIo)
+    }
+}
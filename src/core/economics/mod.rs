@@ -14,5 +14,11 @@
 
 //! Economics: staking / slashing skeleton.
 
+/// Token supply and account balances.
+pub mod bank;
+/// Epoch transition preview (what-if API).
+pub mod epoch;
+/// Fee market (gas limit, base fee, fee distribution).
+pub mod fees;
 /// Staking and slashing ledger.
 pub mod staking;
@@ -3,14 +3,50 @@
 // Licensed under the Apache License, Version 2.0
 
 //! Deterministic staking ledger: bonding, unbonding, slashing, rewards.
+//!
+//! The ledger is persisted in the main state tree (so it is covered by the state root)
+//! under a versioned prefix, one key per record:
+//!
+//! ```text
+//! staking/v1/params                          -> StakingParams
+//! staking/v1/val/<id>                        -> Validator
+//! staking/v1/del/<delegator><validator>      -> Delegation
+//! staking/v1/unb/<delegator><validator>      -> Vec<UnbondingEntry>
+//! staking/v1/pool/<id>                       -> RewardPool
+//! staking/v1/red/<delegator><src><dst>       -> Vec<RedelegationEntry>
+//! ```
+//!
+//! Ids are length-prefixed (u16, big endian) so composite keys parse unambiguously and sort
+//! by delegator first. A future layout gets a new version prefix and a migration instead of
+//! reinterpreting v1 keys.
 
 #![forbid(unsafe_code)]
 
-use std::collections::BTreeMap;
+use crate::core::economics::bank::{Bank, BankError, BONDED_POOL, REWARDS_POOL};
+use crate::core::security::keystore::verify_pubkey_bytes;
+use crate::core::state::persistent_state::{KvOp, PersistentState, StateError};
+use crate::core::types::{decode_canonical_limited, encode_canonical, Signature};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use thiserror::Error;
 
+/// Prefix of all staking keys (current layout).
+pub const STAKING_PREFIX: &[u8] = b"staking/v1/";
+const PARAMS_KEY: &[u8] = b"staking/v1/params";
+const VALIDATOR_PREFIX: &[u8] = b"staking/v1/val/";
+const DELEGATION_PREFIX: &[u8] = b"staking/v1/del/";
+const UNBONDING_PREFIX: &[u8] = b"staking/v1/unb/";
+const POOL_PREFIX: &[u8] = b"staking/v1/pool/";
+const REDELEGATION_PREFIX: &[u8] = b"staking/v1/red/";
+
+/// Fixed-point scale of `RewardPool::reward_per_stake`.
+pub const REWARD_SCALE: u128 = 1_000_000_000_000;
+
+/// Upper bound for a single encoded record.
+const MAX_RECORD_BYTES: usize = 64 * 1024;
+
 const SECONDS_PER_DAY: u64 = 86_400;
-const MIN_UNBONDING_DAYS: u64 = 7;
+const BPS: u128 = 10_000;
 
 #[derive(Debug, Error)]
 pub enum StakingError {
@@ -18,27 +54,191 @@ pub enum StakingError {
     InvalidAmount,
     #[error("insufficient stake")]
     InsufficientStake,
+    #[error("commission above maximum")]
+    CommissionTooHigh,
+    #[error("self stake below minimum")]
+    SelfStakeTooLow,
+    #[error("invalid staking params")]
+    InvalidParams,
+    #[error("unknown validator")]
+    UnknownValidator,
+    #[error("validator not jailed")]
+    NotJailed,
+    #[error("jail period not over")]
+    StillJailed,
+    #[error("invalid signature")]
+    BadSignature,
+    #[error("stake is still in an immature redelegation")]
+    RedelegationInProgress,
+    #[error("delegation below minimum amount")]
+    DelegationTooSmall,
+    #[error("validator has too many delegations")]
+    TooManyDelegations,
+    #[error(transparent)]
+    Bank(#[from] BankError),
+}
+
+/// Slashable offenses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Offense {
+    /// Two conflicting votes at the same height and round.
+    DoubleSign,
+    /// Missed too many included commits in a row (see `ConsensusDriver::apply_included_commit`).
+    Downtime,
+}
+
+/// Staking parameters (normally fixed per network at genesis).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StakingParams {
+    /// Delay between `begin_unbond` and the stake becoming withdrawable.
+    pub unbonding_period_secs: u64,
+    /// Highest commission a validator may charge, in bps.
+    pub max_commission_bps: u16,
+    /// Minimum self stake to register, and to keep accepting delegations.
+    pub min_self_stake: u128,
+    /// Fraction slashed for a double sign, in bps.
+    pub slash_double_sign_bps: u16,
+    /// Fraction slashed for downtime, in bps.
+    pub slash_downtime_bps: u16,
+    /// Slashes of at least this fraction (bps) also jail the validator. Downtime always jails.
+    pub jail_slash_threshold_bps: u16,
+    /// Minimum time a jailed validator stays jailed.
+    pub min_jail_secs: u64,
+    /// Smallest non-zero delegation a delegator may hold with a validator.
+    pub min_delegation_amount: u128,
+    /// Most delegators a single validator may have.
+    pub max_delegations_per_validator: u32,
+}
+
+impl Default for StakingParams {
+    fn default() -> Self {
+        Self {
+            unbonding_period_secs: 7 * SECONDS_PER_DAY,
+            max_commission_bps: 2_000,
+            min_self_stake: 0,
+            slash_double_sign_bps: 500,
+            slash_downtime_bps: 1,
+            jail_slash_threshold_bps: 100,
+            min_jail_secs: SECONDS_PER_DAY,
+            min_delegation_amount: 1,
+            max_delegations_per_validator: 10_000,
+        }
+    }
+}
+
+impl StakingParams {
+    /// Reject zero unbonding periods and fractions above 100%.
+    pub fn validate(&self) -> Result<(), StakingError> {
+        let bps_ok = [
+            self.max_commission_bps,
+            self.slash_double_sign_bps,
+            self.slash_downtime_bps,
+            self.jail_slash_threshold_bps,
+        ]
+        .iter()
+        .all(|b| u128::from(*b) <= BPS);
+        if self.unbonding_period_secs == 0 || self.max_delegations_per_validator == 0 || !bps_ok {
+            return Err(StakingError::InvalidParams);
+        }
+        Ok(())
+    }
+
+    /// Slash fraction for `offense`, in bps.
+    pub fn slash_fraction_bps(&self, offense: Offense) -> u16 {
+        match offense {
+            Offense::DoubleSign => self.slash_double_sign_bps,
+            Offense::Downtime => self.slash_downtime_bps,
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Delegation {
+    /// Bonded principal.
     pub amount: u128,
+    /// `RewardPool::reward_per_stake` when rewards were last settled into `pending_rewards`.
+    pub reward_index: u128,
+    /// Settled, unclaimed rewards.
+    pub pending_rewards: u128,
+}
+
+impl Delegation {
+    /// Rewards accrued since the last settlement at pool index `index`.
+    fn accrued(&self, index: u128) -> u128 {
+        self.amount
+            .saturating_mul(index.saturating_sub(self.reward_index))
+            / REWARD_SCALE
+    }
+}
+
+/// Per-validator reward accounting.
+///
+/// Rewards are not written to each delegation. Instead `reward_per_stake` grows by
+/// `reward / total_delegated` (scaled by `REWARD_SCALE`) per distribution, and a delegation
+/// settles `amount * (reward_per_stake - reward_index)` whenever its amount changes or its
+/// owner claims. Distribution is O(1) in the number of delegations.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewardPool {
+    /// Sum of delegation principal to this validator.
+    pub total_delegated: u128,
+    /// Cumulative reward per unit of delegated stake, scaled by `REWARD_SCALE`.
+    pub reward_per_stake: u128,
+    /// Unclaimed commission.
+    pub commission: u128,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UnbondingEntry {
     pub amount: u128,
     pub unlock_time: u64,
+    /// Block height at which unbonding began.
+    pub creation_height: u64,
 }
 
-#[derive(Clone, Debug, Default)]
+/// Stake moved from one validator to another, still answerable for the source's offenses.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedelegationEntry {
+    /// Stake moved (reduced if the source is slashed).
+    pub amount: u128,
+    /// Unix time after which the entry stops being slashable.
+    pub completion_time: u64,
+    /// Block height of the redelegation.
+    pub creation_height: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Validator {
     pub commission_bps: u16,
     pub self_stake: u128,
     pub slashed: u128,
+    /// Excluded from active set selection until unjailed.
+    pub jailed: bool,
+    /// Earliest unix time at which `unjail` is accepted.
+    pub jailed_until: u64,
+}
+
+/// Signed request to leave jail.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UnjailRequest {
+    /// Validator id (Ed25519 public key bytes).
+    pub validator: Vec<u8>,
+    /// Signature over `unjail_signing_bytes(validator, jailed_until)`.
+    pub signature: Signature,
+}
+
+/// Unjail signing payload: domain || len(validator) || validator || jailed_until
+///
+/// Binding `jailed_until` makes a request valid for one jail period only.
+pub fn unjail_signing_bytes(validator: &[u8], jailed_until: u64) -> Vec<u8> {
+    let mut out = Vec::with_capacity(24 + 8 + validator.len() + 8);
+    out.extend_from_slice(b"Amunchain-Unjail-v1");
+    out.extend_from_slice(&(validator.len() as u64).to_be_bytes());
+    out.extend_from_slice(validator);
+    out.extend_from_slice(&jailed_until.to_be_bytes());
+    out
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct StakingLedger {
     /// Registered validators keyed by validator id bytes.
     pub validators: BTreeMap<Vec<u8>, Validator>,
@@ -46,56 +246,276 @@ pub struct StakingLedger {
     pub delegations: BTreeMap<(Vec<u8>, Vec<u8>), Delegation>,
     /// Pending unbonding entries keyed by (delegator, validator).
     pub unbonding: BTreeMap<(Vec<u8>, Vec<u8>), Vec<UnbondingEntry>>,
+    /// Reward accounting keyed by validator id.
+    pub pools: BTreeMap<Vec<u8>, RewardPool>,
+    /// Immature redelegations keyed by (delegator, source, destination).
+    pub redelegations: BTreeMap<(Vec<u8>, Vec<u8>, Vec<u8>), Vec<RedelegationEntry>>,
+    /// Network staking parameters.
+    pub params: StakingParams,
 }
 
 impl StakingLedger {
-    /// Bond stake from a delegator to a validator.
+    /// Empty ledger with the given parameters.
+    pub fn with_params(params: StakingParams) -> Result<Self, StakingError> {
+        params.validate()?;
+        Ok(Self {
+            params,
+            ..Self::default()
+        })
+    }
+
+    /// Register (or re-register) a validator with its commission and self stake.
+    pub fn register_validator(
+        &mut self,
+        validator: Vec<u8>,
+        commission_bps: u16,
+        self_stake: u128,
+    ) -> Result<(), StakingError> {
+        self.check_registration(commission_bps, self_stake)?;
+        let v = self.validators.entry(validator).or_default();
+        v.commission_bps = commission_bps;
+        v.self_stake = self_stake;
+        Ok(())
+    }
+
+    /// `register_validator`, paying any increase in self stake from the validator's balance
+    /// into `BONDED_POOL`. Self stake cannot be lowered this way, since that would release
+    /// stake without an unbonding period.
+    pub fn register_validator_from(
+        &mut self,
+        bank: &mut Bank,
+        validator: Vec<u8>,
+        commission_bps: u16,
+        self_stake: u128,
+    ) -> Result<(), StakingError> {
+        let current = self.validators.get(&validator).map_or(0, |v| v.self_stake);
+        if self_stake < current {
+            return Err(StakingError::InvalidAmount);
+        }
+        self.check_registration(commission_bps, self_stake)?;
+        if self_stake > current {
+            bank.transfer(&validator, BONDED_POOL, self_stake - current)?;
+        }
+        self.register_validator(validator, commission_bps, self_stake)
+    }
+
+    /// Checks `register_validator` applies before touching the ledger.
+    fn check_registration(
+        &self,
+        commission_bps: u16,
+        self_stake: u128,
+    ) -> Result<(), StakingError> {
+        if commission_bps > self.params.max_commission_bps {
+            return Err(StakingError::CommissionTooHigh);
+        }
+        if self_stake < self.params.min_self_stake {
+            return Err(StakingError::SelfStakeTooLow);
+        }
+        Ok(())
+    }
+
+    /// Change a registered validator's commission.
+    pub fn set_commission(
+        &mut self,
+        validator: &[u8],
+        commission_bps: u16,
+    ) -> Result<(), StakingError> {
+        if commission_bps > self.params.max_commission_bps {
+            return Err(StakingError::CommissionTooHigh);
+        }
+        let v = self
+            .validators
+            .get_mut(validator)
+            .ok_or(StakingError::InsufficientStake)?;
+        v.commission_bps = commission_bps;
+        Ok(())
+    }
+
+    /// Bond stake from a delegator to a validator. A registered validator whose self stake
+    /// fell below `min_self_stake` (e.g. after slashing) accepts no new delegations.
+    ///
+    /// The resulting delegation must be at least `min_delegation_amount`, and a new
+    /// delegator is refused once the validator has `max_delegations_per_validator`.
     pub fn bond(
         &mut self,
         delegator: Vec<u8>,
         validator: Vec<u8>,
         amount: u128,
     ) -> Result<(), StakingError> {
-        if amount == 0 {
-            return Err(StakingError::InvalidAmount);
-        }
-        let key = (delegator, validator);
-        let entry = self
-            .delegations
-            .entry(key)
-            .or_insert(Delegation { amount: 0 });
+        self.check_bond(&delegator, &validator, amount)?;
+        let pool = self.pools.entry(validator.clone()).or_default();
+        pool.total_delegated = pool.total_delegated.saturating_add(amount);
+        let index = pool.reward_per_stake;
+        let entry = self.delegations.entry((delegator, validator)).or_default();
+        settle(entry, index);
         entry.amount = entry.amount.saturating_add(amount);
         Ok(())
     }
 
-    /// Start unbonding: decreases delegation and creates a timed unbonding entry.
+    /// `bond`, paid from the delegator's balance into `BONDED_POOL`.
+    pub fn bond_from(
+        &mut self,
+        bank: &mut Bank,
+        delegator: Vec<u8>,
+        validator: Vec<u8>,
+        amount: u128,
+    ) -> Result<(), StakingError> {
+        self.check_bond(&delegator, &validator, amount)?;
+        bank.transfer(&delegator, BONDED_POOL, amount)?;
+        self.bond(delegator, validator, amount)
+    }
+
+    /// Start unbonding at block `height`: decreases delegation and creates a timed unbonding
+    /// entry. The entry stays slashable for offenses committed at or before `height`.
     pub fn begin_unbond(
         &mut self,
         delegator: Vec<u8>,
         validator: Vec<u8>,
         amount: u128,
         now_unix: u64,
+        height: u64,
+    ) -> Result<(), StakingError> {
+        self.check_remainder(&delegator, &validator, amount)?;
+        self.withdraw(&delegator, &validator, amount)?;
+
+        let key = (delegator, validator);
+        let unlock_time = now_unix.saturating_add(self.params.unbonding_period_secs);
+        let ub = self.unbonding.entry(key).or_default();
+        ub.push(UnbondingEntry {
+            amount,
+            unlock_time,
+            creation_height: height,
+        });
+        Ok(())
+    }
+
+    /// Move stake between validators without waiting for the unbonding period.
+    ///
+    /// The moved stake stays slashable for offenses of `from` committed at or before
+    /// `height` until `now_unix + unbonding_period_secs`. Stake that arrived at `from`
+    /// through a redelegation still in that window cannot be moved again, so a delegator
+    /// cannot hop away ahead of a slash.
+    pub fn redelegate(
+        &mut self,
+        delegator: Vec<u8>,
+        from: Vec<u8>,
+        to: Vec<u8>,
+        amount: u128,
+        now_unix: u64,
+        height: u64,
+    ) -> Result<(), StakingError> {
+        if amount == 0 || from == to {
+            return Err(StakingError::InvalidAmount);
+        }
+        let hopping = self
+            .redelegations
+            .iter()
+            .filter(|((d, _, dst), _)| *d == delegator && *dst == from)
+            .flat_map(|(_, list)| list.iter())
+            .any(|e| now_unix < e.completion_time);
+        if hopping {
+            return Err(StakingError::RedelegationInProgress);
+        }
+        self.check_bond(&delegator, &to, amount)?;
+        self.check_remainder(&delegator, &from, amount)?;
+
+        self.withdraw(&delegator, &from, amount)?;
+        self.bond(delegator.clone(), to.clone(), amount)?;
+
+        let completion_time = now_unix.saturating_add(self.params.unbonding_period_secs);
+        let list = self.redelegations.entry((delegator, from, to)).or_default();
+        list.retain(|e| now_unix < e.completion_time);
+        list.push(RedelegationEntry {
+            amount,
+            completion_time,
+            creation_height: height,
+        });
+        Ok(())
+    }
+
+    /// Drop redelegation entries that are no longer slashable.
+    pub fn prune_redelegations(&mut self, now_unix: u64) {
+        for list in self.redelegations.values_mut() {
+            list.retain(|e| now_unix < e.completion_time);
+        }
+        self.redelegations.retain(|_, list| !list.is_empty());
+    }
+
+    /// Checks `bond` applies before touching the ledger.
+    fn check_bond(
+        &self,
+        delegator: &[u8],
+        validator: &[u8],
+        amount: u128,
+    ) -> Result<(), StakingError> {
+        if amount == 0 {
+            return Err(StakingError::InvalidAmount);
+        }
+        if let Some(v) = self.validators.get(validator) {
+            if v.self_stake < self.params.min_self_stake {
+                return Err(StakingError::SelfStakeTooLow);
+            }
+        }
+        let current = self.delegation_amount(delegator, validator);
+        if current.saturating_add(amount) < self.params.min_delegation_amount {
+            return Err(StakingError::DelegationTooSmall);
+        }
+        if current == 0 {
+            let count = self
+                .delegations
+                .iter()
+                .filter(|((_, v), d)| v.as_slice() == validator && d.amount > 0)
+                .count();
+            if count >= self.params.max_delegations_per_validator as usize {
+                return Err(StakingError::TooManyDelegations);
+            }
+        }
+        Ok(())
+    }
+
+    /// Removing `amount` must leave either nothing or at least `min_delegation_amount`.
+    fn check_remainder(
+        &self,
+        delegator: &[u8],
+        validator: &[u8],
+        amount: u128,
+    ) -> Result<(), StakingError> {
+        let rest = self
+            .delegation_amount(delegator, validator)
+            .saturating_sub(amount);
+        if rest != 0 && rest < self.params.min_delegation_amount {
+            return Err(StakingError::DelegationTooSmall);
+        }
+        Ok(())
+    }
+
+    fn delegation_amount(&self, delegator: &[u8], validator: &[u8]) -> u128 {
+        self.delegations
+            .get(&(delegator.to_vec(), validator.to_vec()))
+            .map_or(0, |d| d.amount)
+    }
+
+    /// Reduce a delegation's principal, settling its rewards first.
+    fn withdraw(
+        &mut self,
+        delegator: &[u8],
+        validator: &[u8],
+        amount: u128,
     ) -> Result<(), StakingError> {
         if amount == 0 {
             return Err(StakingError::InvalidAmount);
         }
-        let key = (delegator.clone(), validator.clone());
         let del = self
             .delegations
-            .get_mut(&key)
+            .get_mut(&(delegator.to_vec(), validator.to_vec()))
             .ok_or(StakingError::InsufficientStake)?;
         if del.amount < amount {
             return Err(StakingError::InsufficientStake);
         }
+        let pool = self.pools.entry(validator.to_vec()).or_default();
+        settle(del, pool.reward_per_stake);
         del.amount -= amount;
-
-        let unlock_time =
-            now_unix.saturating_add(MIN_UNBONDING_DAYS.saturating_mul(SECONDS_PER_DAY));
-        let ub = self.unbonding.entry(key).or_default();
-        ub.push(UnbondingEntry {
-            amount,
-            unlock_time,
-        });
+        pool.total_delegated = pool.total_delegated.saturating_sub(amount);
         Ok(())
     }
 
@@ -124,15 +544,102 @@ impl StakingLedger {
         Ok(released)
     }
 
-    /// Apply slashing to all delegations to a validator by fraction in bps (0..=10000).
-    pub fn slash_validator(&mut self, validator: &[u8], fraction_bps: u16) -> u128 {
+    /// `finalize_unbond`, crediting the released stake from `BONDED_POOL` back to the
+    /// delegator's balance.
+    pub fn finalize_unbond_to(
+        &mut self,
+        bank: &mut Bank,
+        delegator: Vec<u8>,
+        validator: Vec<u8>,
+        now_unix: u64,
+    ) -> Result<u128, StakingError> {
+        let matured = self
+            .unbonding
+            .get(&(delegator.clone(), validator.clone()))
+            .map_or(0u128, |list| {
+                list.iter()
+                    .filter(|e| now_unix >= e.unlock_time)
+                    .fold(0, |acc, e| acc.saturating_add(e.amount))
+            });
+        if bank.balance(BONDED_POOL) < matured {
+            return Err(BankError::InsufficientFunds.into());
+        }
+        let released = self.finalize_unbond(delegator.clone(), validator, now_unix)?;
+        if released > 0 {
+            bank.transfer(BONDED_POOL, &delegator, released)?;
+        }
+        Ok(released)
+    }
+
+    /// Apply slashing for an offense at `infraction_height` by fraction in bps (0..=10000).
+    ///
+    /// Hits the validator's self stake, all delegations to it, and unbonding entries created
+    /// at or after the infraction height: that stake was still bonded when the offense was
+    /// committed. The same goes for redelegations away from the validator, which are slashed
+    /// at their destination.
+    pub fn slash_validator(
+        &mut self,
+        validator: &[u8],
+        fraction_bps: u16,
+        infraction_height: u64,
+    ) -> u128 {
         let frac = (fraction_bps.min(10_000)) as u128;
         let mut total_slashed: u128 = 0;
 
+        if let Some(val) = self.validators.get_mut(validator) {
+            let sl = val.self_stake.saturating_mul(frac) / 10_000u128;
+            val.self_stake = val.self_stake.saturating_sub(sl);
+            total_slashed = total_slashed.saturating_add(sl);
+        }
+
+        let mut pool = self.pools.remove(validator).unwrap_or_default();
         for ((_, v), del) in self.delegations.iter_mut() {
             if v.as_slice() == validator {
+                settle(del, pool.reward_per_stake);
                 let sl = del.amount.saturating_mul(frac) / 10_000u128;
                 del.amount = del.amount.saturating_sub(sl);
+                pool.total_delegated = pool.total_delegated.saturating_sub(sl);
+                total_slashed = total_slashed.saturating_add(sl);
+            }
+        }
+        self.pools.insert(validator.to_vec(), pool);
+
+        for ((_, v), list) in self.unbonding.iter_mut() {
+            if v.as_slice() != validator {
+                continue;
+            }
+            for e in list
+                .iter_mut()
+                .filter(|e| e.creation_height >= infraction_height)
+            {
+                let sl = e.amount.saturating_mul(frac) / 10_000u128;
+                e.amount = e.amount.saturating_sub(sl);
+                total_slashed = total_slashed.saturating_add(sl);
+            }
+        }
+
+        // Stake redelegated away after the infraction is slashed at its destination.
+        let mut hits: Vec<(Vec<u8>, Vec<u8>, u128)> = Vec::new();
+        for ((d, src, dst), list) in self.redelegations.iter_mut() {
+            if src.as_slice() != validator {
+                continue;
+            }
+            for e in list
+                .iter_mut()
+                .filter(|e| e.creation_height >= infraction_height)
+            {
+                let sl = e.amount.saturating_mul(frac) / 10_000u128;
+                e.amount = e.amount.saturating_sub(sl);
+                hits.push((d.clone(), dst.clone(), sl));
+            }
+        }
+        for (d, dst, sl) in hits {
+            let available = self
+                .delegations
+                .get(&(d.clone(), dst.clone()))
+                .map_or(0, |del| del.amount);
+            let sl = sl.min(available);
+            if sl > 0 && self.withdraw(&d, &dst, sl).is_ok() {
                 total_slashed = total_slashed.saturating_add(sl);
             }
         }
@@ -143,27 +650,403 @@ impl StakingLedger {
         total_slashed
     }
 
-    /// Distribute rewards proportional to stake to delegators of a validator.
-    pub fn distribute_rewards(&mut self, validator: &[u8], total_reward: u128) {
+    /// Slash a validator by the configured fraction for `offense` (see `slash_validator`),
+    /// and jail it for downtime or when the fraction reaches `jail_slash_threshold_bps`.
+    pub fn slash(
+        &mut self,
+        validator: &[u8],
+        offense: Offense,
+        infraction_height: u64,
+        now_unix: u64,
+    ) -> u128 {
+        let bps = self.params.slash_fraction_bps(offense);
+        let slashed = self.slash_validator(validator, bps, infraction_height);
+        if offense == Offense::Downtime || bps >= self.params.jail_slash_threshold_bps {
+            self.jail(validator, now_unix);
+        }
+        slashed
+    }
+
+    /// `slash`, burning the slashed stake from `BONDED_POOL` so it leaves the total supply.
+    ///
+    /// Only fails if the pool holds less than would be slashed, i.e. some of the stake was
+    /// bonded with `bond` or `register_validator` rather than `bond_from` or
+    /// `register_validator_from`; the ledger and the bank are then left unchanged.
+    pub fn slash_from(
+        &mut self,
+        bank: &mut Bank,
+        validator: &[u8],
+        offense: Offense,
+        infraction_height: u64,
+        now_unix: u64,
+    ) -> Result<u128, StakingError> {
+        // Redelegation hits make the amount hard to predict: slash a copy first.
+        let mut next = self.clone();
+        let slashed = next.slash(validator, offense, infraction_height, now_unix);
+        if bank.balance(BONDED_POOL) < slashed {
+            return Err(BankError::InsufficientFunds.into());
+        }
+        if slashed > 0 {
+            bank.burn(BONDED_POOL, slashed)?;
+        }
+        *self = next;
+        Ok(slashed)
+    }
+
+    /// Jail a registered validator for at least `min_jail_secs`. Jailing an already jailed
+    /// validator extends the period if the new one ends later.
+    pub fn jail(&mut self, validator: &[u8], now_unix: u64) {
+        let until = now_unix.saturating_add(self.params.min_jail_secs);
+        if let Some(v) = self.validators.get_mut(validator) {
+            v.jailed = true;
+            v.jailed_until = v.jailed_until.max(until);
+        }
+    }
+
+    /// Leave jail. Requires the jail period to be over, a signature by the validator key
+    /// over the current jail period, and self stake at or above `min_self_stake`.
+    pub fn unjail(&mut self, req: &UnjailRequest, now_unix: u64) -> Result<(), StakingError> {
+        let min_self_stake = self.params.min_self_stake;
+        let v = self
+            .validators
+            .get_mut(&req.validator)
+            .ok_or(StakingError::UnknownValidator)?;
+        if !v.jailed {
+            return Err(StakingError::NotJailed);
+        }
+        if now_unix < v.jailed_until {
+            return Err(StakingError::StillJailed);
+        }
+        let pk: [u8; 32] = req
+            .validator
+            .as_slice()
+            .try_into()
+            .map_err(|_| StakingError::BadSignature)?;
+        let msg = unjail_signing_bytes(&req.validator, v.jailed_until);
+        verify_pubkey_bytes(&pk, &msg, &req.signature).map_err(|_| StakingError::BadSignature)?;
+        if v.self_stake < min_self_stake {
+            return Err(StakingError::SelfStakeTooLow);
+        }
+        v.jailed = false;
+        Ok(())
+    }
+
+    /// Validators eligible for the active set: registered, not jailed, meeting
+    /// `min_self_stake`, with non-zero voting power.
+    pub fn eligible_validators(&self) -> Vec<(Vec<u8>, u128)> {
+        self.validators
+            .iter()
+            .filter(|(_, v)| !v.jailed && v.self_stake >= self.params.min_self_stake)
+            .map(|(id, _)| (id.clone(), self.voting_power(id)))
+            .filter(|(_, p)| *p > 0)
+            .collect()
+    }
+
+    /// Top `max_validators` eligible validators by total stake (self stake plus delegations),
+    /// highest first; equal stake is ordered by validator id.
+    pub fn active_set(&self, max_validators: usize) -> Vec<(Vec<u8>, u128)> {
+        let mut ranked = self.eligible_validators();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked.truncate(max_validators);
+        ranked
+    }
+
+    /// Voting power of a validator: self stake plus all delegations to it.
+    pub fn voting_power(&self, validator: &[u8]) -> u128 {
+        let self_stake = self
+            .validators
+            .get(validator)
+            .map(|v| v.self_stake)
+            .unwrap_or(0);
+        self.delegations
+            .iter()
+            .filter(|((_, v), _)| v.as_slice() == validator)
+            .fold(self_stake, |acc, (_, d)| acc.saturating_add(d.amount))
+    }
+
+    /// Distribute a validator's reward: the validator's commission (capped at
+    /// `max_commission_bps`) accrues to its pool, and the rest accrues to its delegators in
+    /// proportion to stake, claimable with `claim_rewards`. Bonded amounts are not touched.
+    /// Returns the commission taken.
+    ///
+    /// With no delegations nothing is distributed.
+    pub fn distribute_rewards(&mut self, validator: &[u8], total_reward: u128) -> u128 {
         if total_reward == 0 {
-            return;
+            return 0;
+        }
+        let bps = self
+            .validators
+            .get(validator)
+            .map_or(0, |v| v.commission_bps.min(self.params.max_commission_bps));
+        let Some(pool) = self.pools.get_mut(validator) else {
+            return 0;
+        };
+        if pool.total_delegated == 0 {
+            return 0;
         }
 
-        let mut total_stake: u128 = 0;
-        for ((_, v), del) in self.delegations.iter() {
-            if v.as_slice() == validator {
-                total_stake = total_stake.saturating_add(del.amount);
-            }
+        let commission = total_reward.saturating_mul(u128::from(bps)) / BPS;
+        pool.commission = pool.commission.saturating_add(commission);
+        let per_stake =
+            (total_reward - commission).saturating_mul(REWARD_SCALE) / pool.total_delegated;
+        pool.reward_per_stake = pool.reward_per_stake.saturating_add(per_stake);
+        commission
+    }
+
+    /// `distribute_rewards`, minting the distributed reward into `REWARDS_POOL`, where claims
+    /// are paid from. Nothing is minted when nothing is distributed.
+    pub fn distribute_rewards_from(
+        &mut self,
+        bank: &mut Bank,
+        validator: &[u8],
+        total_reward: u128,
+    ) -> Result<u128, StakingError> {
+        let delegated = self
+            .pools
+            .get(validator)
+            .is_some_and(|p| p.total_delegated > 0);
+        if total_reward == 0 || !delegated {
+            return Ok(0);
         }
-        if total_stake == 0 {
-            return;
+        bank.mint(REWARDS_POOL, total_reward)?;
+        Ok(self.distribute_rewards(validator, total_reward))
+    }
+
+    /// Unclaimed rewards of a delegation.
+    pub fn pending_rewards(&self, delegator: &[u8], validator: &[u8]) -> u128 {
+        let index = self.pools.get(validator).map_or(0, |p| p.reward_per_stake);
+        self.delegations
+            .get(&(delegator.to_vec(), validator.to_vec()))
+            .map_or(0, |d| d.pending_rewards.saturating_add(d.accrued(index)))
+    }
+
+    /// Withdraw a delegation's rewards, leaving its bond unchanged.
+    pub fn claim_rewards(
+        &mut self,
+        delegator: &[u8],
+        validator: &[u8],
+    ) -> Result<u128, StakingError> {
+        let index = self.pools.get(validator).map_or(0, |p| p.reward_per_stake);
+        let del = self
+            .delegations
+            .get_mut(&(delegator.to_vec(), validator.to_vec()))
+            .ok_or(StakingError::InsufficientStake)?;
+        settle(del, index);
+        Ok(std::mem::take(&mut del.pending_rewards))
+    }
+
+    /// `claim_rewards`, paying the rewards from `REWARDS_POOL` to the delegator's balance.
+    /// Fails without claiming if the pool cannot cover them.
+    pub fn claim_rewards_to(
+        &mut self,
+        bank: &mut Bank,
+        delegator: &[u8],
+        validator: &[u8],
+    ) -> Result<u128, StakingError> {
+        if bank.balance(REWARDS_POOL) < self.pending_rewards(delegator, validator) {
+            return Err(BankError::InsufficientFunds.into());
+        }
+        let rewards = self.claim_rewards(delegator, validator)?;
+        if rewards > 0 {
+            bank.transfer(REWARDS_POOL, delegator, rewards)?;
         }
+        Ok(rewards)
+    }
 
-        for ((_, v), del) in self.delegations.iter_mut() {
-            if v.as_slice() == validator {
-                let share = total_reward.saturating_mul(del.amount) / total_stake;
-                del.amount = del.amount.saturating_add(share);
+    /// Withdraw a validator's accrued commission.
+    pub fn claim_commission(&mut self, validator: &[u8]) -> u128 {
+        self.pools
+            .get_mut(validator)
+            .map_or(0, |p| std::mem::take(&mut p.commission))
+    }
+
+    /// `claim_commission`, paying it from `REWARDS_POOL` to the validator's balance. Fails
+    /// without claiming if the pool cannot cover it.
+    pub fn claim_commission_to(
+        &mut self,
+        bank: &mut Bank,
+        validator: &[u8],
+    ) -> Result<u128, StakingError> {
+        let commission = self.pools.get(validator).map_or(0, |p| p.commission);
+        if bank.balance(REWARDS_POOL) < commission {
+            return Err(BankError::InsufficientFunds.into());
+        }
+        let commission = self.claim_commission(validator);
+        if commission > 0 {
+            bank.transfer(REWARDS_POOL, validator, commission)?;
+        }
+        Ok(commission)
+    }
+}
+
+/// Move rewards accrued up to pool index `index` into `pending_rewards`.
+fn settle(del: &mut Delegation, index: u128) {
+    del.pending_rewards = del.pending_rewards.saturating_add(del.accrued(index));
+    del.reward_index = index;
+}
+
+fn push_id(key: &mut Vec<u8>, id: &[u8]) -> Result<(), StateError> {
+    let len = u16::try_from(id.len()).map_err(|_| StateError::DbIo)?;
+    key.extend_from_slice(&len.to_be_bytes());
+    key.extend_from_slice(id);
+    Ok(())
+}
+
+/// Split one length-prefixed id off the front of `raw`.
+fn take_id(raw: &[u8]) -> Result<(Vec<u8>, &[u8]), StateError> {
+    if raw.len() < 2 {
+        return Err(StateError::DbIo);
+    }
+    let len = u16::from_be_bytes([raw[0], raw[1]]) as usize;
+    let rest = &raw[2..];
+    if rest.len() < len {
+        return Err(StateError::DbIo);
+    }
+    Ok((rest[..len].to_vec(), &rest[len..]))
+}
+
+/// State key for a validator record.
+pub fn validator_key(validator: &[u8]) -> Result<Vec<u8>, StateError> {
+    let mut key = VALIDATOR_PREFIX.to_vec();
+    push_id(&mut key, validator)?;
+    Ok(key)
+}
+
+/// State key for a validator's reward pool.
+pub fn pool_key(validator: &[u8]) -> Result<Vec<u8>, StateError> {
+    let mut key = POOL_PREFIX.to_vec();
+    push_id(&mut key, validator)?;
+    Ok(key)
+}
+
+fn pair_key(prefix: &[u8], delegator: &[u8], validator: &[u8]) -> Result<Vec<u8>, StateError> {
+    let mut key = prefix.to_vec();
+    push_id(&mut key, delegator)?;
+    push_id(&mut key, validator)?;
+    Ok(key)
+}
+
+/// Parse the (delegator, validator) suffix of a delegation or unbonding key.
+fn parse_pair(suffix: &[u8]) -> Result<(Vec<u8>, Vec<u8>), StateError> {
+    let (delegator, rest) = take_id(suffix)?;
+    let (validator, rest) = take_id(rest)?;
+    if !rest.is_empty() {
+        return Err(StateError::DbIo);
+    }
+    Ok((delegator, validator))
+}
+
+fn encode<T: Serialize>(v: &T) -> Result<Vec<u8>, StateError> {
+    encode_canonical(v).map_err(|_| StateError::DbIo)
+}
+
+fn decode<T: serde::de::DeserializeOwned>(raw: &[u8]) -> Result<T, StateError> {
+    decode_canonical_limited(raw, MAX_RECORD_BYTES).map_err(|_| StateError::DbIo)
+}
+
+impl StakingLedger {
+    /// Put operations for every record, in key order. Delegations with no principal and no
+    /// unclaimed rewards, and empty unbonding lists, are omitted so equal ledgers always
+    /// produce the same keys.
+    pub fn to_ops(&self) -> Result<Vec<KvOp>, StateError> {
+        let mut ops = vec![KvOp::Put {
+            key: PARAMS_KEY.to_vec(),
+            value: encode(&self.params)?,
+        }];
+        for (id, v) in self.validators.iter() {
+            ops.push(KvOp::Put {
+                key: validator_key(id)?,
+                value: encode(v)?,
+            });
+        }
+        for (id, pool) in self.pools.iter() {
+            ops.push(KvOp::Put {
+                key: pool_key(id)?,
+                value: encode(pool)?,
+            });
+        }
+        for ((d, v), del) in self
+            .delegations
+            .iter()
+            .filter(|(_, d)| d.amount > 0 || d.pending_rewards > 0)
+        {
+            ops.push(KvOp::Put {
+                key: pair_key(DELEGATION_PREFIX, d, v)?,
+                value: encode(del)?,
+            });
+        }
+        for ((d, src, dst), list) in self.redelegations.iter().filter(|(_, l)| !l.is_empty()) {
+            let mut key = pair_key(REDELEGATION_PREFIX, d, src)?;
+            push_id(&mut key, dst)?;
+            ops.push(KvOp::Put {
+                key,
+                value: encode(list)?,
+            });
+        }
+        for ((d, v), list) in self.unbonding.iter().filter(|(_, l)| !l.is_empty()) {
+            ops.push(KvOp::Put {
+                key: pair_key(UNBONDING_PREFIX, d, v)?,
+                value: encode(list)?,
+            });
+        }
+        Ok(ops)
+    }
+
+    /// Persist the ledger atomically, deleting records that no longer exist.
+    pub fn commit(&self, state: &PersistentState) -> Result<(), StateError> {
+        let puts = self.to_ops()?;
+        let live: BTreeSet<&[u8]> = puts
+            .iter()
+            .filter_map(|op| match op {
+                KvOp::Put { key, .. } => Some(key.as_slice()),
+                KvOp::Del { .. } => None,
+            })
+            .collect();
+        let mut ops: Vec<KvOp> = state
+            .scan_prefix(STAKING_PREFIX)?
+            .into_iter()
+            .filter(|(k, _)| !live.contains(k.as_slice()))
+            .map(|(key, _)| KvOp::Del { key })
+            .collect();
+        ops.extend(puts);
+        state.commit_atomic(ops)
+    }
+
+    /// Load the ledger from state. Without stored params the defaults apply.
+    pub fn load(state: &PersistentState) -> Result<Self, StateError> {
+        let mut ledger = Self::default();
+        if let Some(raw) = state.get(PARAMS_KEY)? {
+            ledger.params = decode(&raw)?;
+        }
+        for (k, raw) in state.scan_prefix(VALIDATOR_PREFIX)? {
+            let (id, rest) = take_id(&k[VALIDATOR_PREFIX.len()..])?;
+            if !rest.is_empty() {
+                return Err(StateError::DbIo);
+            }
+            ledger.validators.insert(id, decode(&raw)?);
+        }
+        for (k, raw) in state.scan_prefix(POOL_PREFIX)? {
+            let (id, rest) = take_id(&k[POOL_PREFIX.len()..])?;
+            if !rest.is_empty() {
+                return Err(StateError::DbIo);
             }
+            ledger.pools.insert(id, decode(&raw)?);
+        }
+        for (k, raw) in state.scan_prefix(DELEGATION_PREFIX)? {
+            let pair = parse_pair(&k[DELEGATION_PREFIX.len()..])?;
+            ledger.delegations.insert(pair, decode(&raw)?);
+        }
+        for (k, raw) in state.scan_prefix(REDELEGATION_PREFIX)? {
+            let (delegator, rest) = take_id(&k[REDELEGATION_PREFIX.len()..])?;
+            let (src, dst) = parse_pair(rest)?;
+            ledger
+                .redelegations
+                .insert((delegator, src, dst), decode(&raw)?);
+        }
+        for (k, raw) in state.scan_prefix(UNBONDING_PREFIX)? {
+            let pair = parse_pair(&k[UNBONDING_PREFIX.len()..])?;
+            ledger.unbonding.insert(pair, decode(&raw)?);
         }
+        Ok(ledger)
     }
 }
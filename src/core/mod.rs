@@ -14,6 +14,8 @@
 
 //! Core modules: types, consensus, state, security, economics, runtime.
 
+/// Canonical JSON encoding (see `types::encode_canonical_json`).
+pub mod canonical_json;
 /// Finality gadget and consensus driver.
 pub mod consensus;
 /// Economic primitives (staking, fees).
@@ -15,33 +15,313 @@
 // limitations under the License.
 #![forbid(unsafe_code)]
 
-//! EVM executor placeholder.
+//! Transaction executor.
 //!
-//! The `revm` crate is included as a dependency and can be wired into `PersistentState`
-//! via a proper `StateProvider` + commit path. This file intentionally provides a
-//! minimal compile-safe interface.
+//! Native transfers execute against the bank, and calls to a registered precompile run it
+//! (see `precompiles`). Other contract calls revert until the `revm` dependency is wired
+//! into `PersistentState` via a `StateProvider` + commit path. Every
+//! executed transaction consumes its nonce and yields a `Receipt`, whether it succeeded or
+//! not. A transaction that cannot be included at all (wrong nonce, gas limit below the
+//! intrinsic cost) is an `ExecError` instead, and makes the whole block invalid.
+//!
+//! Gas is metered per transaction with a `GasMeter`; running out yields an `OutOfGas`
+//! receipt that uses the whole gas limit. Per block, a transaction is only included if its
+//! gas limit fits in what the block has left (`block_gas_limit` less gas used so far), so
+//! the block limit is a consensus rule, not a producer policy.
+//!
+//! `call_readonly` dry-runs a transaction against a copy of the committed state, for gas
+//! estimates and contract queries; nothing it does is persisted. The loaded state is kept
+//! until the state root changes, so repeated calls do not reload the bank and ledger.
+//!
+//! Signatures are checked before execution (`tx::verify_transaction`) and fees are charged
+//! by `FeeMarket::admit`; neither happens here.
 
+use crate::core::economics::bank::Bank;
+use crate::core::economics::staking::StakingLedger;
+use crate::core::runtime::gas::GasMeter;
+use crate::core::runtime::precompiles::{PrecompileError, PrecompileRegistry};
+use crate::core::runtime::receipt::{Log, LogsBloom, Receipt, TxStatus};
+use crate::core::runtime::tx::{NonceTracker, TxError};
+use crate::core::state::merkle::Hash32;
+use crate::core::state::persistent_state::PersistentState;
+use crate::core::types::{Transaction, TxCall, H256};
+use crate::monitoring::metrics::Metrics;
+use std::sync::{Arc, Mutex};
 use thiserror::Error;
 
+/// Gas charged for a native transfer, and the minimum gas limit of any transaction.
+pub const TRANSFER_GAS: u64 = 21_000;
+
+/// Default most gas one block may use.
+pub const DEFAULT_BLOCK_GAS_LIMIT: u64 = 30_000_000;
+
+/// Address of logs emitted by native transfers.
+pub const BANK_LOG_ADDRESS: &[u8] = b"module/bank";
+
 /// Execution error.
-#[derive(Debug, Error)]
+#[derive(Debug, Error, PartialEq, Eq)]
 pub enum ExecError {
     #[error("not implemented")]
     NotImplemented,
+    #[error("gas limit below intrinsic gas")]
+    IntrinsicGas,
+    /// The transaction's gas limit exceeds what is left of the block's.
+    #[error("block gas limit exceeded")]
+    BlockGasLimit,
+    #[error("codec")]
+    Codec,
+    /// A read-only call asked for a state root this node does not hold.
+    #[error("unknown state root")]
+    UnknownStateRoot,
+    #[error("state")]
+    State,
+    #[error(transparent)]
+    Tx(#[from] TxError),
+}
+
+/// State a block executes against. Execute on a copy: a failed block leaves it half applied.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExecState {
+    pub bank: Bank,
+    pub nonces: NonceTracker,
+    /// Read by the staking precompiles.
+    pub staking: StakingLedger,
+}
+
+impl ExecState {
+    /// Load the committed state.
+    pub fn load(state: &PersistentState) -> Result<Self, ExecError> {
+        Ok(Self {
+            bank: Bank::load(state).map_err(|_| ExecError::State)?,
+            nonces: NonceTracker::load(state).map_err(|_| ExecError::State)?,
+            staking: StakingLedger::load(state).map_err(|_| ExecError::State)?,
+        })
+    }
+}
+
+/// Receipts of one block, in transaction order, and their bloom.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockReceipts {
+    pub receipts: Vec<Receipt>,
+    pub bloom: LogsBloom,
+    pub gas_used: u64,
+}
+
+/// Topic of native transfer logs: SHA-256("Transfer(from,to,amount)").
+pub fn transfer_topic() -> H256 {
+    sha256(b"Transfer(from,to,amount)")
+}
+
+/// Log topic identifying an account: SHA-256(account).
+pub fn account_topic(account: &[u8]) -> H256 {
+    sha256(account)
+}
+
+fn sha256(b: &[u8]) -> H256 {
+    let mut out = [0u8; 32];
+    out.copy_from_slice(ring::digest::digest(&ring::digest::SHA256, b).as_ref());
+    H256::from_bytes(out)
 }
 
 /// EVM executor.
-#[derive(Clone, Debug, Default)]
-pub struct EvmExecutor;
+#[derive(Clone)]
+pub struct EvmExecutor {
+    precompiles: PrecompileRegistry,
+    block_gas_limit: u64,
+    metrics: Option<Arc<Metrics>>,
+    /// State `call_readonly` last loaded, and its root; shared by clones.
+    snapshot: Arc<Mutex<Option<(Hash32, Arc<ExecState>)>>>,
+}
+
+impl std::fmt::Debug for EvmExecutor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EvmExecutor")
+            .field("precompiles", &self.precompiles)
+            .field("block_gas_limit", &self.block_gas_limit)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for EvmExecutor {
+    fn default() -> Self {
+        Self {
+            precompiles: PrecompileRegistry::default(),
+            block_gas_limit: DEFAULT_BLOCK_GAS_LIMIT,
+            metrics: None,
+            snapshot: Arc::default(),
+        }
+    }
+}
 
 impl EvmExecutor {
-    /// Create a new executor.
+    /// Create a new executor with the default precompiles and block gas limit.
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Set the most gas one block may use (`consensus.block_gas_limit`).
+    pub fn with_block_gas_limit(mut self, limit: u64) -> Self {
+        self.block_gas_limit = limit;
+        self
+    }
+
+    /// Record per-block gas usage and out-of-gas transactions.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    pub fn block_gas_limit(&self) -> u64 {
+        self.block_gas_limit
+    }
+
+    /// Replace the precompile registry. Every node must use the same one.
+    pub fn with_precompiles(mut self, precompiles: PrecompileRegistry) -> Self {
+        self.precompiles = precompiles;
+        self
+    }
+
+    pub fn precompiles(&self) -> &PrecompileRegistry {
+        &self.precompiles
+    }
+
+    /// Execute one transaction and return its receipt.
+    pub fn execute(&self, state: &mut ExecState, tx: &Transaction) -> Result<Receipt, ExecError> {
+        let mut meter = GasMeter::new(tx.gas_limit);
+        meter
+            .charge(TRANSFER_GAS)
+            .map_err(|_| ExecError::IntrinsicGas)?;
+        let tx_hash = tx.hash().map_err(|_| ExecError::Codec)?;
+        let sender = tx.sender.as_bytes();
+        state.nonces.apply(sender, tx.nonce)?;
+
+        let mut receipt = Receipt {
+            tx_hash,
+            status: TxStatus::Reverted,
+            gas_used: 0,
+            logs: Vec::new(),
+            output: Vec::new(),
+        };
+        match &tx.call {
+            TxCall::Transfer { to, amount } => {
+                if state.bank.transfer(sender, to, *amount).is_ok() {
+                    receipt.status = TxStatus::Success;
+                    receipt.logs.push(Log {
+                        address: BANK_LOG_ADDRESS.to_vec(),
+                        topics: vec![transfer_topic(), account_topic(sender), account_topic(to)],
+                        data: amount.to_be_bytes().to_vec(),
+                    });
+                }
+            }
+            // Precompiles take no value.
+            TxCall::Call { to, value, input }
+                if *value == 0 && self.precompiles.get(to).is_some() =>
+            {
+                match self.precompiles.call(to, input, meter.remaining(), state) {
+                    Ok(out) => {
+                        // Within `remaining` by construction.
+                        let _ = meter.charge(out.gas_used);
+                        receipt.status = TxStatus::Success;
+                        receipt.output = out.output;
+                    }
+                    // A failed precompile call consumes all its gas.
+                    Err(e) => {
+                        if e == PrecompileError::OutOfGas {
+                            receipt.status = TxStatus::OutOfGas;
+                        }
+                        meter.exhaust();
+                    }
+                }
+            }
+            TxCall::Call { .. } => {}
+        }
+        receipt.gas_used = meter.used();
+        if receipt.status == TxStatus::OutOfGas {
+            if let Some(m) = self.metrics.as_ref() {
+                m.tx_out_of_gas_total.inc();
+            }
+        }
+        Ok(receipt)
+    }
+
+    /// Dry-run `tx` against the committed state in `state` and return its receipt without
+    /// persisting anything. The receipt's `gas_used` is the gas estimate.
+    ///
+    /// Only the current state is kept, so `at_state_root` must be the current root (or
+    /// `None`); a root that changes while the state is read also fails with
+    /// `UnknownStateRoot`. The signature and nonce are not checked, and a zero gas limit
+    /// means the block gas limit.
+    pub fn call_readonly(
+        &self,
+        state: &PersistentState,
+        tx: &Transaction,
+        at_state_root: Option<Hash32>,
+    ) -> Result<Receipt, ExecError> {
+        let root = state.state_root().map_err(|_| ExecError::State)?;
+        if at_state_root.is_some_and(|r| r != root) {
+            return Err(ExecError::UnknownStateRoot);
+        }
+        let mut snapshot = ExecState::clone(&*self.snapshot_at(state, root, at_state_root)?);
+        let mut tx = tx.clone();
+        tx.nonce = snapshot.nonces.next(tx.sender.as_bytes());
+        if tx.gas_limit == 0 {
+            tx.gas_limit = self.block_gas_limit;
+        }
+        if tx.gas_limit > self.block_gas_limit {
+            return Err(ExecError::BlockGasLimit);
+        }
+        // Dry runs are not executed blocks: keep them out of the metrics.
+        let exec = Self {
+            metrics: None,
+            ..self.clone()
+        };
+        exec.execute(&mut snapshot, &tx)
+    }
+
+    /// Committed state at `root`, loaded once per root.
+    fn snapshot_at(
+        &self,
+        state: &PersistentState,
+        root: Hash32,
+        at_state_root: Option<Hash32>,
+    ) -> Result<Arc<ExecState>, ExecError> {
+        let mut cached = self.snapshot.lock().map_err(|_| ExecError::State)?;
+        if let Some((r, snapshot)) = cached.as_ref() {
+            if *r == root {
+                return Ok(snapshot.clone());
+            }
+        }
+        let snapshot = Arc::new(ExecState::load(state)?);
+        // A commit landed while the state was read: the copy may mix both roots.
+        if state.state_root().map_err(|_| ExecError::State)? != root {
+            return match at_state_root {
+                Some(_) => Err(ExecError::UnknownStateRoot),
+                None => Ok(snapshot),
+            };
+        }
+        *cached = Some((root, snapshot.clone()));
+        Ok(snapshot)
     }
 
-    /// Execute a transaction (placeholder).
-    pub fn execute(&self) -> Result<(), ExecError> {
-        Err(ExecError::NotImplemented)
+    /// Execute a block's transactions in order.
+    pub fn execute_block(
+        &self,
+        state: &mut ExecState,
+        txs: &[Transaction],
+    ) -> Result<BlockReceipts, ExecError> {
+        let mut out = BlockReceipts::default();
+        for tx in txs {
+            if tx.gas_limit > self.block_gas_limit.saturating_sub(out.gas_used) {
+                return Err(ExecError::BlockGasLimit);
+            }
+            let receipt = self.execute(state, tx)?;
+            out.gas_used = out.gas_used.saturating_add(receipt.gas_used);
+            out.receipts.push(receipt);
+        }
+        out.bloom = LogsBloom::from_receipts(&out.receipts);
+        if let Some(m) = self.metrics.as_ref() {
+            m.block_gas_used.observe(out.gas_used as f64);
+        }
+        Ok(out)
     }
 }
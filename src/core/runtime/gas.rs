@@ -0,0 +1,64 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Gas accounting shared by every runtime.
+//!
+//! A transaction runs against a `GasMeter` holding its gas limit; each step charges the
+//! meter before doing any work. Running out is deterministic: every node stops at the same
+//! step, the transaction gets an out-of-gas receipt and its whole gas limit is used.
+
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("out of gas")]
+pub struct OutOfGas;
+
+/// Gas used so far by one transaction, against its limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GasMeter {
+    limit: u64,
+    used: u64,
+}
+
+impl GasMeter {
+    pub fn new(limit: u64) -> Self {
+        Self { limit, used: 0 }
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    pub fn used(&self) -> u64 {
+        self.used
+    }
+
+    pub fn remaining(&self) -> u64 {
+        self.limit - self.used
+    }
+
+    /// Charge `amount`. On failure the meter is exhausted: nothing is left for later steps.
+    pub fn charge(&mut self, amount: u64) -> Result<(), OutOfGas> {
+        if amount > self.remaining() {
+            self.used = self.limit;
+            return Err(OutOfGas);
+        }
+        self.used += amount;
+        Ok(())
+    }
+
+    /// Use all remaining gas, as a failed call does.
+    pub fn exhaust(&mut self) {
+        self.used = self.limit;
+    }
+}
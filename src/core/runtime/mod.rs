@@ -15,7 +15,15 @@
 #![forbid(unsafe_code)]
 #![allow(missing_docs)]
 
-//! Runtime execution (EVM adapter placeholder).
+//! Runtime execution: native transfers now, EVM adapter to come.
 
-/// Execution engine interface (currently a placeholder).
+/// Transaction executor.
 pub mod executor;
+/// Per-transaction gas metering.
+pub mod gas;
+/// Chain-native functions at fixed call addresses.
+pub mod precompiles;
+/// Receipts, event logs and log blooms.
+pub mod receipt;
+/// Transaction signature checks and per-account nonce tracking.
+pub mod tx;
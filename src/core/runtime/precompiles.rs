@@ -0,0 +1,343 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Precompiles: chain-native functions callable at fixed addresses.
+//!
+//! A call to a registered address runs native code instead of contract bytecode. Every
+//! precompile is a pure function of its input and the execution state, and prices itself
+//! from the `GasSchedule`, so all nodes agree on both output and gas used. Default addresses:
+//!
+//! ```text
+//! 0x…0a01  ed25519 verify    pubkey(32) || signature(64) || message      -> word(0|1)
+//! 0x…0a02  merkle verify     root(32) || leaf(32) || (side(1) || sibling(32))*  -> word(0|1)
+//! 0x…0a03  voting power      validator                                   -> word(u128)
+//! 0x…0a04  pending rewards   delegator_len(u32 BE) || delegator || validator -> word(u128)
+//! ```
+//!
+//! A word is 32 bytes, big-endian. Malformed input fails the call.
+
+use crate::core::runtime::executor::ExecState;
+use crate::core::security::keystore::verify_pubkey_bytes;
+use crate::core::state::merkle::{verify_proof, MerkleProof, ProofItem, Side};
+use crate::core::types::Signature;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// 20-byte call target.
+pub type Address = [u8; 20];
+
+/// Precompile address with low bytes `0x0a, n`.
+pub const fn native_address(n: u8) -> Address {
+    let mut a = [0u8; 20];
+    a[18] = 0x0a;
+    a[19] = n;
+    a
+}
+
+pub const ED25519_VERIFY: Address = native_address(1);
+pub const MERKLE_VERIFY: Address = native_address(2);
+pub const VOTING_POWER: Address = native_address(3);
+pub const PENDING_REWARDS: Address = native_address(4);
+
+/// Gas costs of every precompile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GasSchedule {
+    /// Charged on every precompile call.
+    pub call_base: u64,
+    /// Charged per started 32-byte word of input.
+    pub per_input_word: u64,
+    pub ed25519_verify: u64,
+    pub merkle_per_item: u64,
+    /// One staking ledger read.
+    pub staking_read: u64,
+}
+
+impl GasSchedule {
+    pub const DEFAULT: Self = Self {
+        call_base: 100,
+        per_input_word: 3,
+        ed25519_verify: 2_000,
+        merkle_per_item: 60,
+        staking_read: 800,
+    };
+
+    fn input_cost(&self, input: &[u8]) -> u64 {
+        let words = (input.len() as u64).div_ceil(32);
+        self.call_base
+            .saturating_add(self.per_input_word.saturating_mul(words))
+    }
+}
+
+impl Default for GasSchedule {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PrecompileError {
+    #[error("no precompile at address")]
+    Unknown,
+    #[error("address already registered")]
+    Duplicate,
+    #[error("out of gas")]
+    OutOfGas,
+    #[error("malformed input")]
+    BadInput,
+}
+
+/// A chain-native function.
+pub trait Precompile: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// Gas for `input`, excluding `GasSchedule::call_base` and input words.
+    fn gas(&self, input: &[u8], schedule: &GasSchedule) -> u64;
+    fn call(&self, input: &[u8], state: &ExecState) -> Result<Vec<u8>, PrecompileError>;
+}
+
+/// Result of a successful precompile call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrecompileOutput {
+    pub gas_used: u64,
+    pub output: Vec<u8>,
+}
+
+/// Precompiles by address, with the schedule that prices them.
+#[derive(Clone)]
+pub struct PrecompileRegistry {
+    schedule: GasSchedule,
+    entries: BTreeMap<Address, Arc<dyn Precompile>>,
+}
+
+impl std::fmt::Debug for PrecompileRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrecompileRegistry")
+            .field("schedule", &self.schedule)
+            .field(
+                "entries",
+                &self
+                    .entries
+                    .iter()
+                    .map(|(a, p)| (hex::encode(a), p.name()))
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl Default for PrecompileRegistry {
+    fn default() -> Self {
+        Self::with_defaults(GasSchedule::DEFAULT)
+    }
+}
+
+impl PrecompileRegistry {
+    /// Empty registry.
+    pub fn new(schedule: GasSchedule) -> Self {
+        Self {
+            schedule,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Registry with the built-in precompiles at their default addresses.
+    pub fn with_defaults(schedule: GasSchedule) -> Self {
+        let mut r = Self::new(schedule);
+        let builtins: [(Address, Arc<dyn Precompile>); 4] = [
+            (ED25519_VERIFY, Arc::new(Ed25519Verify)),
+            (MERKLE_VERIFY, Arc::new(MerkleVerify)),
+            (VOTING_POWER, Arc::new(VotingPower)),
+            (PENDING_REWARDS, Arc::new(PendingRewards)),
+        ];
+        for (addr, p) in builtins {
+            r.entries.insert(addr, p);
+        }
+        r
+    }
+
+    pub fn schedule(&self) -> &GasSchedule {
+        &self.schedule
+    }
+
+    /// Add a precompile; an address can only be registered once.
+    pub fn register(
+        &mut self,
+        addr: Address,
+        precompile: Arc<dyn Precompile>,
+    ) -> Result<(), PrecompileError> {
+        if self.entries.contains_key(&addr) {
+            return Err(PrecompileError::Duplicate);
+        }
+        self.entries.insert(addr, precompile);
+        Ok(())
+    }
+
+    /// Remove the precompile at `addr`; calls to it then reach the EVM instead.
+    pub fn remove(&mut self, addr: &Address) -> bool {
+        self.entries.remove(addr).is_some()
+    }
+
+    /// Precompile registered at `to`, if `to` is a 20-byte address.
+    pub fn get(&self, to: &[u8]) -> Option<&Arc<dyn Precompile>> {
+        let addr: Address = to.try_into().ok()?;
+        self.entries.get(&addr)
+    }
+
+    pub fn addresses(&self) -> impl Iterator<Item = &Address> {
+        self.entries.keys()
+    }
+
+    /// Gas `input` costs at `to`.
+    pub fn gas(&self, to: &[u8], input: &[u8]) -> Result<u64, PrecompileError> {
+        let p = self.get(to).ok_or(PrecompileError::Unknown)?;
+        Ok(self
+            .schedule
+            .input_cost(input)
+            .saturating_add(p.gas(input, &self.schedule)))
+    }
+
+    /// Call the precompile at `to` with at most `gas_limit` gas. Gas is checked before the
+    /// call runs.
+    pub fn call(
+        &self,
+        to: &[u8],
+        input: &[u8],
+        gas_limit: u64,
+        state: &ExecState,
+    ) -> Result<PrecompileOutput, PrecompileError> {
+        let gas_used = self.gas(to, input)?;
+        if gas_used > gas_limit {
+            return Err(PrecompileError::OutOfGas);
+        }
+        let p = self.get(to).ok_or(PrecompileError::Unknown)?;
+        Ok(PrecompileOutput {
+            gas_used,
+            output: p.call(input, state)?,
+        })
+    }
+}
+
+fn word(v: u128) -> Vec<u8> {
+    let mut out = vec![0u8; 32];
+    out[16..].copy_from_slice(&v.to_be_bytes());
+    out
+}
+
+struct Ed25519Verify;
+
+impl Precompile for Ed25519Verify {
+    fn name(&self) -> &'static str {
+        "ed25519_verify"
+    }
+
+    fn gas(&self, _input: &[u8], schedule: &GasSchedule) -> u64 {
+        schedule.ed25519_verify
+    }
+
+    fn call(&self, input: &[u8], _state: &ExecState) -> Result<Vec<u8>, PrecompileError> {
+        if input.len() < 96 {
+            return Err(PrecompileError::BadInput);
+        }
+        let pk: [u8; 32] = input[..32]
+            .try_into()
+            .map_err(|_| PrecompileError::BadInput)?;
+        let sig = Signature::from_slice(&input[32..96]).map_err(|_| PrecompileError::BadInput)?;
+        let ok = verify_pubkey_bytes(&pk, &input[96..], &sig).is_ok();
+        Ok(word(u128::from(ok)))
+    }
+}
+
+struct MerkleVerify;
+
+impl MerkleVerify {
+    fn items(input: &[u8]) -> Option<usize> {
+        let rest = input.len().checked_sub(64)?;
+        (rest % 33 == 0).then_some(rest / 33)
+    }
+}
+
+impl Precompile for MerkleVerify {
+    fn name(&self) -> &'static str {
+        "merkle_verify"
+    }
+
+    fn gas(&self, input: &[u8], schedule: &GasSchedule) -> u64 {
+        let items = Self::items(input).unwrap_or(0) as u64;
+        schedule.merkle_per_item.saturating_mul(items)
+    }
+
+    fn call(&self, input: &[u8], _state: &ExecState) -> Result<Vec<u8>, PrecompileError> {
+        Self::items(input).ok_or(PrecompileError::BadInput)?;
+        let mut root = [0u8; 32];
+        root.copy_from_slice(&input[..32]);
+        let mut leaf = [0u8; 32];
+        leaf.copy_from_slice(&input[32..64]);
+        let path = input[64..]
+            .chunks_exact(33)
+            .map(|c| {
+                let side = match c[0] {
+                    0 => Side::Left,
+                    1 => Side::Right,
+                    _ => return Err(PrecompileError::BadInput),
+                };
+                let mut sibling = [0u8; 32];
+                sibling.copy_from_slice(&c[1..]);
+                Ok(ProofItem { side, sibling })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let ok = verify_proof(root, &MerkleProof { leaf, path });
+        Ok(word(u128::from(ok)))
+    }
+}
+
+struct VotingPower;
+
+impl Precompile for VotingPower {
+    fn name(&self) -> &'static str {
+        "voting_power"
+    }
+
+    fn gas(&self, _input: &[u8], schedule: &GasSchedule) -> u64 {
+        schedule.staking_read
+    }
+
+    fn call(&self, input: &[u8], state: &ExecState) -> Result<Vec<u8>, PrecompileError> {
+        Ok(word(state.staking.voting_power(input)))
+    }
+}
+
+struct PendingRewards;
+
+impl Precompile for PendingRewards {
+    fn name(&self) -> &'static str {
+        "pending_rewards"
+    }
+
+    fn gas(&self, _input: &[u8], schedule: &GasSchedule) -> u64 {
+        schedule.staking_read
+    }
+
+    fn call(&self, input: &[u8], state: &ExecState) -> Result<Vec<u8>, PrecompileError> {
+        let len: [u8; 4] = input
+            .get(..4)
+            .and_then(|b| b.try_into().ok())
+            .ok_or(PrecompileError::BadInput)?;
+        let len = u32::from_be_bytes(len) as usize;
+        let rest = &input[4..];
+        if len > rest.len() {
+            return Err(PrecompileError::BadInput);
+        }
+        let (delegator, validator) = rest.split_at(len);
+        Ok(word(state.staking.pending_rewards(delegator, validator)))
+    }
+}
@@ -0,0 +1,123 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Transaction receipts, event logs and per-block log blooms.
+//!
+//! The bloom is 2048 bits. Each log adds its address and every topic; an input sets the
+//! three bits given by the first three big-endian `u16`s of SHA-256(input), modulo 2048. A
+//! block whose bloom lacks any of an input's bits has no log matching it, so indexers only
+//! fetch receipts of blocks that may.
+
+use crate::core::types::H256;
+use serde::{Deserialize, Serialize};
+
+const BLOOM_BYTES: usize = 256;
+
+/// Event emitted during execution.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Log {
+    /// Emitting account or contract.
+    pub address: Vec<u8>,
+    pub topics: Vec<H256>,
+    pub data: Vec<u8>,
+}
+
+/// How a transaction ended. Failed transactions are still included: their nonce is used and
+/// their fee is paid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TxStatus {
+    Success,
+    /// Execution failed; state changes other than the fee were discarded.
+    Reverted,
+    /// Ran out of gas; state changes other than the fee were discarded.
+    OutOfGas,
+}
+
+/// Outcome of one included transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Receipt {
+    pub tx_hash: H256,
+    pub status: TxStatus,
+    pub gas_used: u64,
+    /// Logs in emission order; empty unless `status` is `Success`.
+    pub logs: Vec<Log>,
+    /// Return data of a successful call; empty for transfers.
+    pub output: Vec<u8>,
+}
+
+impl Receipt {
+    pub fn is_success(&self) -> bool {
+        self.status == TxStatus::Success
+    }
+}
+
+/// 2048-bit log bloom.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogsBloom([[u8; 32]; BLOOM_BYTES / 32]);
+
+impl Default for LogsBloom {
+    fn default() -> Self {
+        Self([[0; 32]; BLOOM_BYTES / 32])
+    }
+}
+
+impl LogsBloom {
+    /// Bloom over every log of `receipts`.
+    pub fn from_receipts(receipts: &[Receipt]) -> Self {
+        let mut bloom = Self::default();
+        for log in receipts.iter().flat_map(|r| &r.logs) {
+            bloom.accrue_log(log);
+        }
+        bloom
+    }
+
+    /// Add a log's address and topics.
+    pub fn accrue_log(&mut self, log: &Log) {
+        self.accrue(&log.address);
+        for topic in &log.topics {
+            self.accrue(topic.as_bytes());
+        }
+    }
+
+    /// Add an address or topic.
+    pub fn accrue(&mut self, input: &[u8]) {
+        for bit in bits(input) {
+            self.0[bit / 256][(bit % 256) / 8] |= 1 << (bit % 8);
+        }
+    }
+
+    /// Whether a log with this address or topic may be present. False positives happen;
+    /// false negatives do not.
+    pub fn may_contain(&self, input: &[u8]) -> bool {
+        bits(input)
+            .into_iter()
+            .all(|bit| self.0[bit / 256][(bit % 256) / 8] & (1 << (bit % 8)) != 0)
+    }
+
+    /// Add every bit of `other`.
+    pub fn union(&mut self, other: &LogsBloom) {
+        for (a, b) in self.0.iter_mut().flatten().zip(other.0.iter().flatten()) {
+            *a |= b;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().flatten().all(|b| *b == 0)
+    }
+}
+
+fn bits(input: &[u8]) -> [usize; 3] {
+    let h = ring::digest::digest(&ring::digest::SHA256, input);
+    let h = h.as_ref();
+    [0, 1, 2].map(|i| usize::from(u16::from_be_bytes([h[2 * i], h[2 * i + 1]])) % 2048)
+}
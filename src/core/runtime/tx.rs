@@ -0,0 +1,155 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Transaction checks: chain id, size, signature, and per-account nonce ordering.
+//!
+//! Nonces give application-level replay protection: an account's transactions execute
+//! strictly in nonce order, each exactly once. The next expected nonce per account lives in
+//! the main state tree:
+//!
+//! ```text
+//! nonce/v1/<account>   -> u64   (absent = 0)
+//! ```
+//!
+//! The executor requires `tx.nonce == next`; the mempool also admits a bounded run of
+//! future nonces so senders can queue transactions.
+
+use crate::core::consensus::signing::tx_signing_bytes;
+use crate::core::security::keystore::verify_pubkey_bytes;
+use crate::core::state::persistent_state::{KvOp, PersistentState, StateError};
+use crate::core::types::{decode_canonical_limited, encode_canonical, Transaction, MAX_TX_BYTES};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// Prefix of all nonce keys (current layout).
+pub const NONCE_PREFIX: &[u8] = b"nonce/v1/";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TxError {
+    #[error("transaction for another chain")]
+    WrongChain,
+    #[error("transaction too large")]
+    TooLarge,
+    #[error("bad signature")]
+    BadSignature,
+    #[error("codec")]
+    Codec,
+    /// Already used: a replay, or superseded.
+    #[error("nonce {got} already used (next {expected})")]
+    NonceTooLow { expected: u64, got: u64 },
+    /// Earlier nonces are still missing.
+    #[error("nonce {got} ahead of next {expected}")]
+    NonceGap { expected: u64, got: u64 },
+    #[error("nonce overflow")]
+    NonceOverflow,
+}
+
+/// Check `tx` is for `chain_id`, within `MAX_TX_BYTES`, and signed by its sender.
+pub fn verify_transaction(tx: &Transaction, chain_id: &str) -> Result<(), TxError> {
+    if tx.chain_id != chain_id {
+        return Err(TxError::WrongChain);
+    }
+    if tx.to_bytes().map_err(|_| TxError::Codec)?.len() > MAX_TX_BYTES {
+        return Err(TxError::TooLarge);
+    }
+    let msg = tx_signing_bytes(tx).map_err(|_| TxError::Codec)?;
+    verify_pubkey_bytes(tx.sender.as_bytes(), &msg, &tx.signature)
+        .map_err(|_| TxError::BadSignature)
+}
+
+/// Next expected nonce per account.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NonceTracker {
+    pub nonces: BTreeMap<Vec<u8>, u64>,
+}
+
+impl NonceTracker {
+    /// Nonce the next executed transaction from `account` must carry.
+    pub fn next(&self, account: &[u8]) -> u64 {
+        self.nonces.get(account).copied().unwrap_or(0)
+    }
+
+    /// Whether `nonce` can execute now (it is exactly the next one).
+    pub fn check_executable(&self, account: &[u8], nonce: u64) -> Result<(), TxError> {
+        let expected = self.next(account);
+        match nonce.cmp(&expected) {
+            std::cmp::Ordering::Equal => Ok(()),
+            std::cmp::Ordering::Less => Err(TxError::NonceTooLow {
+                expected,
+                got: nonce,
+            }),
+            std::cmp::Ordering::Greater => Err(TxError::NonceGap {
+                expected,
+                got: nonce,
+            }),
+        }
+    }
+
+    /// Mempool admission: `nonce` is unused and at most `max_ahead` past the next one.
+    pub fn check_pending(&self, account: &[u8], nonce: u64, max_ahead: u64) -> Result<(), TxError> {
+        let expected = self.next(account);
+        if nonce < expected {
+            return Err(TxError::NonceTooLow {
+                expected,
+                got: nonce,
+            });
+        }
+        if nonce - expected > max_ahead {
+            return Err(TxError::NonceGap {
+                expected,
+                got: nonce,
+            });
+        }
+        Ok(())
+    }
+
+    /// Consume `nonce` for `account` after executing its transaction (successful or not).
+    pub fn apply(&mut self, account: &[u8], nonce: u64) -> Result<(), TxError> {
+        self.check_executable(account, nonce)?;
+        let next = nonce.checked_add(1).ok_or(TxError::NonceOverflow)?;
+        self.nonces.insert(account.to_vec(), next);
+        Ok(())
+    }
+
+    /// Put operations for every non-zero nonce, in key order. Nonces never decrease, so a
+    /// commit never deletes.
+    pub fn to_ops(&self) -> Result<Vec<KvOp>, StateError> {
+        self.nonces
+            .iter()
+            .filter(|(_, n)| **n > 0)
+            .map(|(account, n)| {
+                let mut key = NONCE_PREFIX.to_vec();
+                key.extend_from_slice(account);
+                Ok(KvOp::Put {
+                    key,
+                    value: encode_canonical(n).map_err(|_| StateError::DbIo)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Persist nonces atomically.
+    pub fn commit(&self, state: &PersistentState) -> Result<(), StateError> {
+        state.commit_atomic(self.to_ops()?)
+    }
+
+    /// Load nonces from state.
+    pub fn load(state: &PersistentState) -> Result<Self, StateError> {
+        let mut tracker = Self::default();
+        for (k, raw) in state.scan_prefix(NONCE_PREFIX)? {
+            let n: u64 = decode_canonical_limited(&raw, 16).map_err(|_| StateError::DbIo)?;
+            tracker.nonces.insert(k[NONCE_PREFIX.len()..].to_vec(), n);
+        }
+        Ok(tracker)
+    }
+}
@@ -0,0 +1,432 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Hash-chained signing audit log and compliance archive export.
+//!
+//! Every line of `audit.log` is a JSON entry whose `hash` covers the previous entry's hash:
+//!
+//! hash = SHA-256( "Amunchain-Audit-v1" || prev || seq || ts_ms || action || msg_sha256 )
+//!
+//! The chain continues across rotation (`audit.log` -> `audit.log.1` -> ...), so removing or
+//! editing a line anywhere in the retained files breaks verification.
+//!
+//! ## Archive format
+//! `MAGIC(8) || manifest_len u32 BE || manifest JSON || chunk_0 || chunk_1 || ...`
+//!
+//! Each chunk is a zstd-compressed run of up to `CHUNK_ENTRIES` JSON lines. The manifest
+//! records, per chunk, its byte range, entry range and SHA-256 of the compressed bytes, plus
+//! the hash the first entry links to and the hash of the last entry.
+
+use ring::digest;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
+
+/// Audit log file name inside the data directory.
+pub const AUDIT_FILE: &str = "audit.log";
+
+const MAX_AUDIT_BYTES: u64 = 32 * 1024 * 1024; // 32 MiB
+const AUDIT_ROTATE_KEEP: usize = 3;
+
+const CHAIN_DOMAIN: &[u8] = b"Amunchain-Audit-v1";
+const ARCHIVE_MAGIC: &[u8; 8] = b"AMUNAUD1";
+const CHUNK_ENTRIES: usize = 4096;
+const ZSTD_LEVEL: i32 = 9;
+
+/// Upper bounds when reading archives.
+const MAX_MANIFEST_BYTES: usize = 4 * 1024 * 1024;
+const MAX_CHUNK_DECOMPRESSED: usize = 64 * 1024 * 1024;
+
+/// Audit errors.
+#[derive(Debug, Error)]
+pub enum AuditError {
+    #[error("io")]
+    Io,
+    #[error("malformed audit entry")]
+    Malformed,
+    #[error("hash chain broken at seq {0}")]
+    ChainBroken(u64),
+    #[error("invalid archive")]
+    Archive,
+    #[error("archive chunk {0} hash mismatch")]
+    ChunkHash(usize),
+    #[error("no audit entries in range")]
+    Empty,
+}
+
+impl From<std::io::Error> for AuditError {
+    fn from(_: std::io::Error) -> Self {
+        AuditError::Io
+    }
+}
+
+/// One audit log line.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub ts_ms: u64,
+    pub action: String,
+    /// SHA-256 of the signed message (content is never logged).
+    pub msg_sha256: String,
+    /// Hash of the previous entry (hex; zeros for the first entry).
+    pub prev: String,
+    /// Hash of this entry (hex).
+    pub hash: String,
+}
+
+impl AuditEntry {
+    fn compute_hash(&self) -> Result<[u8; 32], AuditError> {
+        let prev = decode32(&self.prev)?;
+        let mut ctx = digest::Context::new(&digest::SHA256);
+        ctx.update(CHAIN_DOMAIN);
+        ctx.update(&prev);
+        ctx.update(&self.seq.to_be_bytes());
+        ctx.update(&self.ts_ms.to_be_bytes());
+        ctx.update(&(self.action.len() as u64).to_be_bytes());
+        ctx.update(self.action.as_bytes());
+        ctx.update(self.msg_sha256.as_bytes());
+        let mut out = [0u8; 32];
+        out.copy_from_slice(ctx.finish().as_ref());
+        Ok(out)
+    }
+
+    /// Check this entry's own hash.
+    pub fn verify(&self) -> Result<(), AuditError> {
+        if hex::encode(self.compute_hash()?) != self.hash {
+            return Err(AuditError::ChainBroken(self.seq));
+        }
+        Ok(())
+    }
+}
+
+fn decode32(s: &str) -> Result<[u8; 32], AuditError> {
+    let v = hex::decode(s).map_err(|_| AuditError::Malformed)?;
+    v.try_into().map_err(|_| AuditError::Malformed)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(digest::digest(&digest::SHA256, bytes).as_ref())
+}
+
+/// Verify that `entries` form one unbroken chain (each hash valid, each `prev` linking to
+/// the entry before it, sequence numbers consecutive).
+pub fn verify_chain(entries: &[AuditEntry]) -> Result<(), AuditError> {
+    let mut last: Option<&AuditEntry> = None;
+    for e in entries {
+        e.verify()?;
+        if let Some(p) = last {
+            if e.prev != p.hash || e.seq != p.seq.wrapping_add(1) {
+                return Err(AuditError::ChainBroken(e.seq));
+            }
+        }
+        last = Some(e);
+    }
+    Ok(())
+}
+
+/// Audit files in `data_dir`, oldest first (`audit.log.3`, ..., `audit.log`).
+pub fn audit_files(data_dir: &Path) -> Vec<PathBuf> {
+    let base = data_dir.join(AUDIT_FILE);
+    let mut out = Vec::new();
+    for i in (1..=AUDIT_ROTATE_KEEP).rev() {
+        let p = PathBuf::from(format!("{}.{}", base.display(), i));
+        if p.exists() {
+            out.push(p);
+        }
+    }
+    if base.exists() {
+        out.push(base);
+    }
+    out
+}
+
+/// Parse chained entries from one file. Lines written before chaining existed are skipped.
+fn read_file(path: &Path) -> Result<Vec<AuditEntry>, AuditError> {
+    let raw = fs::read_to_string(path)?;
+    let mut out = Vec::new();
+    for line in raw.lines().filter(|l| !l.trim().is_empty()) {
+        let v: serde_json::Value = serde_json::from_str(line).map_err(|_| AuditError::Malformed)?;
+        if v.get("hash").is_none() {
+            continue;
+        }
+        out.push(serde_json::from_value(v).map_err(|_| AuditError::Malformed)?);
+    }
+    Ok(out)
+}
+
+/// All retained entries in chain order, verified across rotation boundaries.
+pub fn read_chain(data_dir: &Path) -> Result<Vec<AuditEntry>, AuditError> {
+    let mut entries = Vec::new();
+    for f in audit_files(data_dir) {
+        entries.extend(read_file(&f)?);
+    }
+    verify_chain(&entries)?;
+    Ok(entries)
+}
+
+/// Append-only audit log writer.
+pub struct AuditLog {
+    path: PathBuf,
+    // (seq, hash) of the last written entry; loaded from disk on first append.
+    head: Mutex<Option<(u64, String)>>,
+}
+
+impl AuditLog {
+    /// Audit log at `data_dir/audit.log`.
+    pub fn open(data_dir: &Path) -> Self {
+        Self {
+            path: data_dir.join(AUDIT_FILE),
+            head: Mutex::new(None),
+        }
+    }
+
+    /// Append an entry for `action` over `msg` (only its hash is stored) and return it.
+    pub fn append(&self, action: &str, msg: &[u8]) -> Result<AuditEntry, AuditError> {
+        let mut head = self.head.lock().map_err(|_| AuditError::Io)?;
+        if head.is_none() {
+            *head = Some(self.load_head()?);
+        }
+        let (seq, prev) = match head.as_ref() {
+            Some((s, h)) if !h.is_empty() => (s.wrapping_add(1), h.clone()),
+            _ => (0, hex::encode([0u8; 32])),
+        };
+
+        let mut e = AuditEntry {
+            seq,
+            ts_ms: now_ms(),
+            action: action.to_string(),
+            msg_sha256: sha256_hex(msg),
+            prev,
+            hash: String::new(),
+        };
+        e.hash = hex::encode(e.compute_hash()?);
+
+        rotate_if_needed(&self.path);
+        let mut line = serde_json::to_string(&e).map_err(|_| AuditError::Malformed)?;
+        line.push('\n');
+        let mut f = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        set_private_perms_best_effort(&self.path);
+        f.write_all(line.as_bytes())?;
+
+        *head = Some((e.seq, e.hash.clone()));
+        Ok(e)
+    }
+
+    /// Last chained entry across the current and rotated files (empty hash if none).
+    fn load_head(&self) -> Result<(u64, String), AuditError> {
+        let dir = self.path.parent().unwrap_or_else(|| Path::new("."));
+        for f in audit_files(dir).iter().rev() {
+            if let Some(e) = read_file(f)?.pop() {
+                return Ok((e.seq, e.hash));
+            }
+        }
+        Ok((0, String::new()))
+    }
+}
+
+fn rotate_if_needed(path: &Path) {
+    let Ok(md) = fs::metadata(path) else {
+        return;
+    };
+    if md.len() <= MAX_AUDIT_BYTES {
+        return;
+    }
+
+    // best-effort rotation (no crash if it fails)
+    for i in (1..=AUDIT_ROTATE_KEEP).rev() {
+        let dst = PathBuf::from(format!("{}.{}", path.display(), i));
+        let src = if i == 1 {
+            path.to_path_buf()
+        } else {
+            PathBuf::from(format!("{}.{}", path.display(), i - 1))
+        };
+        if src.exists() {
+            let _ = fs::rename(&src, &dst);
+        }
+    }
+}
+
+fn set_private_perms_best_effort(path: &Path) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o600));
+    }
+}
+
+/// Archive chunk descriptor.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkInfo {
+    /// Offset from the end of the manifest.
+    pub offset: u64,
+    pub len: u64,
+    pub entries: u64,
+    pub first_seq: u64,
+    pub last_seq: u64,
+    /// SHA-256 of the compressed chunk (hex).
+    pub sha256: String,
+}
+
+/// Archive manifest.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub version: u32,
+    pub from_ms: u64,
+    pub to_ms: u64,
+    pub entries: u64,
+    /// Hash the first exported entry links to.
+    pub first_prev: String,
+    /// Hash of the last exported entry.
+    pub last_hash: String,
+    pub chunks: Vec<ChunkInfo>,
+}
+
+/// Export entries with `from_ms <= ts_ms <= to_ms` from all retained audit files into a
+/// chunked, compressed archive at `out`. The full retained chain is verified first.
+pub fn export(
+    data_dir: &Path,
+    from_ms: u64,
+    to_ms: u64,
+    out: &Path,
+) -> Result<ArchiveManifest, AuditError> {
+    let chain = read_chain(data_dir)?;
+    let selected: Vec<&AuditEntry> = chain
+        .iter()
+        .filter(|e| e.ts_ms >= from_ms && e.ts_ms <= to_ms)
+        .collect();
+    let (Some(first), Some(last)) = (selected.first(), selected.last()) else {
+        return Err(AuditError::Empty);
+    };
+
+    let mut body = Vec::new();
+    let mut chunks = Vec::new();
+    for group in selected.chunks(CHUNK_ENTRIES) {
+        let mut raw = Vec::new();
+        for e in group {
+            raw.extend(serde_json::to_vec(e).map_err(|_| AuditError::Malformed)?);
+            raw.push(b'\n');
+        }
+        let compressed = zstd::bulk::compress(&raw, ZSTD_LEVEL)?;
+        chunks.push(ChunkInfo {
+            offset: body.len() as u64,
+            len: compressed.len() as u64,
+            entries: group.len() as u64,
+            first_seq: group[0].seq,
+            last_seq: group[group.len() - 1].seq,
+            sha256: sha256_hex(&compressed),
+        });
+        body.extend(compressed);
+    }
+
+    let manifest = ArchiveManifest {
+        version: 1,
+        from_ms,
+        to_ms,
+        entries: selected.len() as u64,
+        first_prev: first.prev.clone(),
+        last_hash: last.hash.clone(),
+        chunks,
+    };
+    let m = serde_json::to_vec(&manifest).map_err(|_| AuditError::Malformed)?;
+
+    let mut tmp = out.to_path_buf();
+    tmp.set_extension("tmp");
+    {
+        let mut f = fs::File::create(&tmp)?;
+        f.write_all(ARCHIVE_MAGIC)?;
+        f.write_all(&(m.len() as u32).to_be_bytes())?;
+        f.write_all(&m)?;
+        f.write_all(&body)?;
+        f.sync_all()?;
+    }
+    fs::rename(&tmp, out)?;
+    Ok(manifest)
+}
+
+/// Verify an archive: chunk hashes, the hash chain across chunks, and the manifest's
+/// endpoints. Returns the manifest and the entries.
+pub fn verify_archive(path: &Path) -> Result<(ArchiveManifest, Vec<AuditEntry>), AuditError> {
+    let mut f = fs::File::open(path)?;
+    let mut magic = [0u8; 8];
+    f.read_exact(&mut magic)?;
+    if &magic != ARCHIVE_MAGIC {
+        return Err(AuditError::Archive);
+    }
+    let mut len = [0u8; 4];
+    f.read_exact(&mut len)?;
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_MANIFEST_BYTES {
+        return Err(AuditError::Archive);
+    }
+    let mut m = vec![0u8; len];
+    f.read_exact(&mut m)?;
+    let manifest: ArchiveManifest = serde_json::from_slice(&m).map_err(|_| AuditError::Archive)?;
+    if manifest.version != 1 {
+        return Err(AuditError::Archive);
+    }
+    let mut body = Vec::new();
+    f.read_to_end(&mut body)?;
+
+    let mut entries = Vec::new();
+    for (i, c) in manifest.chunks.iter().enumerate() {
+        let start = usize::try_from(c.offset).map_err(|_| AuditError::Archive)?;
+        let end = start
+            .checked_add(usize::try_from(c.len).map_err(|_| AuditError::Archive)?)
+            .ok_or(AuditError::Archive)?;
+        let bytes = body.get(start..end).ok_or(AuditError::Archive)?;
+        if sha256_hex(bytes) != c.sha256 {
+            return Err(AuditError::ChunkHash(i));
+        }
+        let raw = zstd::bulk::decompress(bytes, MAX_CHUNK_DECOMPRESSED)
+            .map_err(|_| AuditError::Archive)?;
+        let before = entries.len();
+        for line in raw.split(|b| *b == b'\n').filter(|l| !l.is_empty()) {
+            entries.push(serde_json::from_slice(line).map_err(|_| AuditError::Malformed)?);
+        }
+        let got: &[AuditEntry] = &entries[before..];
+        if got.len() as u64 != c.entries
+            || got.first().map(|e| e.seq) != Some(c.first_seq)
+            || got.last().map(|e| e.seq) != Some(c.last_seq)
+        {
+            return Err(AuditError::Archive);
+        }
+    }
+
+    verify_chain(&entries)?;
+    let (Some(first), Some(last)) = (entries.first(), entries.last()) else {
+        return Err(AuditError::Empty);
+    };
+    if entries.len() as u64 != manifest.entries
+        || first.prev != manifest.first_prev
+        || last.hash != manifest.last_hash
+    {
+        return Err(AuditError::Archive);
+    }
+    Ok((manifest, entries))
+}
@@ -0,0 +1,401 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Audit sinks: ship signing audit entries to a file, syslog or an HTTP collector (SIEM).
+//!
+//! The local hash-chained `audit.log` stays the source of truth; sinks get a copy of each
+//! entry. An `AuditForwarder` gives every sink its own bounded queue and worker thread, and
+//! `forward` never blocks: when a sink is slow or down and its queue is full, the entry is
+//! dropped for that sink and counted in `SinkStats::dropped`. Delivery errors are counted,
+//! never returned to the signer.
+//!
+//! Wire formats, one entry per record, as the `AuditEntry` JSON:
+//! - file: JSON lines, appended;
+//! - syslog: RFC 5424, facility authpriv, severity notice, app-name `amunchain`, msgid
+//!   `audit`, over `udp://host:port` or a unix datagram socket (`unix:///dev/log`);
+//! - http: `POST` with `Content-Type: application/json` to an `http://` URL; any 2xx is
+//!   success. Put a TLS-terminating forwarder in front of collectors that need HTTPS.
+
+use crate::core::security::audit::{AuditEntry, AuditError};
+use crate::core::types::{AuditSinkConfig, KeystoreConfig};
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Write},
+    net::{TcpStream, ToSocketAddrs, UdpSocket},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, SyncSender, TrySendError},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+/// `<PRI>` of syslog messages: facility authpriv (10) * 8 + severity notice (5).
+const SYSLOG_PRI: u8 = 85;
+
+/// Upper bound on an HTTP collector's response read.
+const MAX_HTTP_RESPONSE_BYTES: u64 = 64 * 1024;
+
+/// Destination for copies of audit entries. Called from a forwarder worker thread, so it may
+/// block.
+pub trait AuditSink: Send + Sync {
+    /// Short label for logs and stats.
+    fn name(&self) -> &str;
+    fn deliver(&self, entry: &AuditEntry) -> Result<(), AuditError>;
+}
+
+/// Appends entries as JSON lines.
+pub struct FileSink {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl FileSink {
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, AuditError> {
+        let path = path.into();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl AuditSink for FileSink {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    fn deliver(&self, entry: &AuditEntry) -> Result<(), AuditError> {
+        let mut line = serde_json::to_string(entry).map_err(|_| AuditError::Malformed)?;
+        line.push('\n');
+        let mut f = self.file.lock().map_err(|_| AuditError::Io)?;
+        f.write_all(line.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for FileSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileSink")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+enum SyslogTransport {
+    Udp(UdpSocket),
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixDatagram),
+}
+
+/// Sends RFC 5424 messages over UDP or a unix datagram socket.
+pub struct SyslogSink {
+    transport: SyslogTransport,
+    hostname: String,
+}
+
+impl SyslogSink {
+    /// Connect to `udp://host:port` or `unix:///path`.
+    pub fn connect(addr: &str) -> Result<Self, AuditError> {
+        let transport = if let Some(hostport) = addr.strip_prefix("udp://") {
+            let target = hostport.to_socket_addrs()?.next().ok_or(AuditError::Io)?;
+            let bind = if target.is_ipv4() {
+                "0.0.0.0:0"
+            } else {
+                "[::]:0"
+            };
+            let sock = UdpSocket::bind(bind)?;
+            sock.connect(target)?;
+            SyslogTransport::Udp(sock)
+        } else if let Some(path) = addr.strip_prefix("unix://") {
+            unix_transport(path)?
+        } else {
+            return Err(AuditError::Io);
+        };
+        let hostname = std::env::var("HOSTNAME")
+            .ok()
+            .filter(|h| !h.is_empty() && h.is_ascii() && !h.contains(' '))
+            .unwrap_or_else(|| "-".to_string());
+        Ok(Self {
+            transport,
+            hostname,
+        })
+    }
+
+    /// The RFC 5424 message for `entry`; the timestamp is the entry's own.
+    pub fn format(&self, entry: &AuditEntry) -> Result<String, AuditError> {
+        let json = serde_json::to_string(entry).map_err(|_| AuditError::Malformed)?;
+        let ts = rfc3339_ms(entry.ts_ms);
+        Ok(format!(
+            "<{SYSLOG_PRI}>1 {ts} {} amunchain {} audit - {json}",
+            self.hostname,
+            std::process::id()
+        ))
+    }
+}
+
+/// `ts_ms` (Unix epoch, UTC) as `YYYY-MM-DDTHH:MM:SS.mmmZ`.
+fn rfc3339_ms(ts_ms: u64) -> String {
+    let secs = ts_ms / 1000;
+    let days = secs / 86_400;
+    let sod = secs % 86_400;
+    // Civil date from days since 1970-01-01 (H. Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        sod / 3600,
+        sod % 3600 / 60,
+        sod % 60,
+        ts_ms % 1000
+    )
+}
+
+#[cfg(unix)]
+fn unix_transport(path: &str) -> Result<SyslogTransport, AuditError> {
+    let sock = std::os::unix::net::UnixDatagram::unbound()?;
+    sock.connect(path)?;
+    Ok(SyslogTransport::Unix(sock))
+}
+
+#[cfg(not(unix))]
+fn unix_transport(_path: &str) -> Result<SyslogTransport, AuditError> {
+    Err(AuditError::Io)
+}
+
+impl AuditSink for SyslogSink {
+    fn name(&self) -> &str {
+        "syslog"
+    }
+
+    fn deliver(&self, entry: &AuditEntry) -> Result<(), AuditError> {
+        let msg = self.format(entry)?;
+        match &self.transport {
+            SyslogTransport::Udp(s) => s.send(msg.as_bytes())?,
+            #[cfg(unix)]
+            SyslogTransport::Unix(s) => s.send(msg.as_bytes())?,
+        };
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for SyslogSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SyslogSink")
+            .field("hostname", &self.hostname)
+            .finish_non_exhaustive()
+    }
+}
+
+/// POSTs each entry as JSON to an HTTP collector.
+#[derive(Debug)]
+pub struct HttpSink {
+    host: String,
+    port: u16,
+    path: String,
+    timeout: Duration,
+}
+
+impl HttpSink {
+    /// `url` must be `http://host[:port][/path]`.
+    pub fn new(url: &str, timeout: Duration) -> Result<Self, AuditError> {
+        let rest = url.strip_prefix("http://").ok_or(AuditError::Io)?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            // `[v6]:port`, or `host:port`; a bare `[v6]` takes the default port.
+            Some((h, p)) if !h.contains(':') || (h.starts_with('[') && h.ends_with(']')) => {
+                (h, p.parse().map_err(|_| AuditError::Io)?)
+            }
+            _ => (authority, 80),
+        };
+        if host.is_empty() || timeout.is_zero() {
+            return Err(AuditError::Io);
+        }
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+            timeout,
+        })
+    }
+}
+
+impl AuditSink for HttpSink {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    fn deliver(&self, entry: &AuditEntry) -> Result<(), AuditError> {
+        let body = serde_json::to_vec(entry).map_err(|_| AuditError::Malformed)?;
+        let host = self.host.trim_start_matches('[').trim_end_matches(']');
+        let addr = (host, self.port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or(AuditError::Io)?;
+        let mut s = TcpStream::connect_timeout(&addr, self.timeout)?;
+        s.set_read_timeout(Some(self.timeout))?;
+        s.set_write_timeout(Some(self.timeout))?;
+        let head = format!(
+            "POST {} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n",
+            self.path,
+            self.host,
+            self.port,
+            body.len()
+        );
+        s.write_all(head.as_bytes())?;
+        s.write_all(&body)?;
+
+        let mut resp = Vec::new();
+        (&mut s)
+            .take(MAX_HTTP_RESPONSE_BYTES)
+            .read_to_end(&mut resp)?;
+        // "HTTP/1.1 2xx ..."
+        match resp.get(9) {
+            Some(b'2') => Ok(()),
+            _ => Err(AuditError::Io),
+        }
+    }
+}
+
+/// Build the sink described by `cfg`.
+pub fn build_sink(cfg: &AuditSinkConfig) -> Result<Arc<dyn AuditSink>, AuditError> {
+    Ok(match cfg {
+        AuditSinkConfig::File { path } => Arc::new(FileSink::open(path)?),
+        AuditSinkConfig::Syslog { addr } => Arc::new(SyslogSink::connect(addr)?),
+        AuditSinkConfig::Http { url, timeout_ms } => {
+            Arc::new(HttpSink::new(url, Duration::from_millis(*timeout_ms))?)
+        }
+    })
+}
+
+/// Delivery counters of one sink.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SinkStats {
+    pub name: String,
+    pub delivered: u64,
+    /// `deliver` returned an error.
+    pub failed: u64,
+    /// The queue was full (or the worker gone) when the entry was forwarded.
+    pub dropped: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    delivered: AtomicU64,
+    failed: AtomicU64,
+    dropped: AtomicU64,
+}
+
+struct Lane {
+    name: String,
+    tx: SyncSender<AuditEntry>,
+    counters: Arc<Counters>,
+}
+
+/// Fans audit entries out to sinks without blocking the caller.
+///
+/// Dropping the forwarder closes the queues; workers deliver what is already queued and exit.
+pub struct AuditForwarder {
+    lanes: Vec<Lane>,
+}
+
+impl std::fmt::Debug for AuditForwarder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditForwarder")
+            .field(
+                "sinks",
+                &self.lanes.iter().map(|l| &l.name).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl AuditForwarder {
+    /// Start one worker per sink, each with a queue of `queue_len` entries (at least 1).
+    pub fn new(sinks: Vec<Arc<dyn AuditSink>>, queue_len: usize) -> Result<Self, AuditError> {
+        let mut lanes = Vec::with_capacity(sinks.len());
+        for sink in sinks {
+            let (tx, rx) = mpsc::sync_channel::<AuditEntry>(queue_len.max(1));
+            let counters = Arc::new(Counters::default());
+            let name = sink.name().to_string();
+            let c = counters.clone();
+            thread::Builder::new()
+                .name(format!("audit-sink-{name}"))
+                .spawn(move || {
+                    for entry in rx {
+                        match sink.deliver(&entry) {
+                            Ok(()) => c.delivered.fetch_add(1, Ordering::Relaxed),
+                            Err(e) => {
+                                tracing::warn!(sink = sink.name(), seq = entry.seq, error = %e, "audit sink delivery failed");
+                                c.failed.fetch_add(1, Ordering::Relaxed)
+                            }
+                        };
+                    }
+                })?;
+            lanes.push(Lane { name, tx, counters });
+        }
+        Ok(Self { lanes })
+    }
+
+    /// Build the sinks in `cfg.audit_sinks`; `None` when there are none.
+    pub fn from_config(cfg: &KeystoreConfig) -> Result<Option<Self>, AuditError> {
+        if cfg.audit_sinks.is_empty() {
+            return Ok(None);
+        }
+        let sinks = cfg
+            .audit_sinks
+            .iter()
+            .map(build_sink)
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(sinks, cfg.audit_queue_len).map(Some)
+    }
+
+    /// Queue `entry` for every sink. Never blocks; a full queue drops the entry for that sink.
+    pub fn forward(&self, entry: &AuditEntry) {
+        for lane in &self.lanes {
+            match lane.tx.try_send(entry.clone()) {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) | Err(TrySendError::Disconnected(_)) => {
+                    lane.counters.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    /// Counters per sink, in configuration order.
+    pub fn stats(&self) -> Vec<SinkStats> {
+        self.lanes
+            .iter()
+            .map(|l| SinkStats {
+                name: l.name.clone(),
+                delivered: l.counters.delivered.load(Ordering::Relaxed),
+                failed: l.counters.failed.load(Ordering::Relaxed),
+                dropped: l.counters.dropped.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+}
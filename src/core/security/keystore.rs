@@ -16,13 +16,17 @@
 //! ## Production hardening
 //! - **Atomic writes** for private key material.
 //! - **Key-at-rest encryption** (optional) via `AMUNCHAIN_KEY_PASSPHRASE` (or legacy `NEXUS_KEY_PASSPHRASE`).
-//! - **Audit log rotation** (best-effort).
+//! - **Hash-chained audit log** with rotation (see `audit`).
 //! - **Best-effort zeroization** of sensitive buffers.
 //!
 //! ### Key encryption format
 //! If `AMUNCHAIN_KEY_PASSPHRASE` is set, `validator.key` is stored as:
 //! `MAGIC(9) || SALT(16) || NONCE(12) || CIPHERTEXT+TAG(..)`
 //! where the ciphertext is AES-256-GCM over the Ed25519 PKCS#8 bytes.
+//!
+//! `write_secret_file` / `read_secret_file` are the only code that knows this format; key
+//! files (`write_key_file` / `read_key_file`), the `keygen` tool and the encrypted p2p
+//! identity all go through them.
 
 use ring::{
     aead, pbkdf2,
@@ -30,6 +34,7 @@ use ring::{
     signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519},
 };
 use std::{
+    collections::HashMap,
     fs,
     io::Write,
     num::NonZeroU32,
@@ -40,6 +45,10 @@ use std::{
 use thiserror::Error;
 use zeroize::Zeroize;
 
+use crate::core::security::audit::AuditLog;
+use crate::core::security::audit_sink::AuditForwarder;
+use crate::core::security::secrets::{default_chain, SecretError, SecretProvider};
+use crate::core::security::sign_policy::{PolicyError, SignPolicy};
 use crate::core::types::Signature;
 
 fn env_first(keys: &[&str]) -> Option<String> {
@@ -53,18 +62,15 @@ fn env_first(keys: &[&str]) -> Option<String> {
     None
 }
 
-const KEY_FILE_MAGIC: &[u8] = b"AMUNKEY1"; // 8 bytes // 8 bytes
+const KEY_FILE_MAGIC: &[u8] = b"AMUNKEY1"; // 8 bytes
 const KEY_SALT_LEN: usize = 16;
 const KEY_NONCE_LEN: usize = 12;
 
-const MAX_AUDIT_BYTES: u64 = 32 * 1024 * 1024; // 32 MiB
-const AUDIT_ROTATE_KEEP: usize = 3;
-
 // PBKDF2 params: 100k iterations is a reasonable baseline for server-side passphrases.
 // Increase if your deployment can afford it.
 const PBKDF2_ITERS_DEFAULT: u32 = 100_000;
 
-fn pbkdf2_iters() -> NonZeroU32 {
+pub(crate) fn pbkdf2_iters() -> NonZeroU32 {
     // Optional override via env (defense-in-depth; keep bounds sane).
     // Example: AMUNCHAIN_PBKDF2_ITERS=300000 (or legacy NEXUS_PBKDF2_ITERS=300000)
     let iters = env_first(&["AMUNCHAIN_PBKDF2_ITERS", "NEXUS_PBKDF2_ITERS"])
@@ -92,6 +98,10 @@ pub enum KeystoreError {
     RateLimited,
     #[error("bad signature")]
     BadSignature,
+    #[error("sign policy: {0}")]
+    Policy(#[from] PolicyError),
+    #[error("secret: {0}")]
+    Secret(#[from] SecretError),
 }
 
 /// Signer backend abstraction (HSM compatible).
@@ -107,28 +117,6 @@ pub struct FileEd25519Backend {
     keypair: Ed25519KeyPair,
 }
 
-fn rotate_audit_if_needed(path: &Path) {
-    let Ok(md) = fs::metadata(path) else {
-        return;
-    };
-    if md.len() <= MAX_AUDIT_BYTES {
-        return;
-    }
-
-    // best-effort rotation (no crash if it fails)
-    for i in (1..=AUDIT_ROTATE_KEEP).rev() {
-        let dst = PathBuf::from(format!("{}.{}", path.display(), i));
-        let src = if i == 1 {
-            path.to_path_buf()
-        } else {
-            PathBuf::from(format!("{}.{}", path.display(), i - 1))
-        };
-        if src.exists() {
-            let _ = fs::rename(&src, &dst);
-        }
-    }
-}
-
 fn set_private_perms_best_effort(path: &Path) {
     #[cfg(unix)]
     {
@@ -138,7 +126,7 @@ fn set_private_perms_best_effort(path: &Path) {
 }
 
 /// Atomic write to disk (best-effort fsync, then rename).
-fn atomic_write_private(path: &Path, bytes: &[u8]) -> Result<(), KeystoreError> {
+pub(crate) fn atomic_write_private(path: &Path, bytes: &[u8]) -> Result<(), KeystoreError> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).map_err(|_| KeystoreError::Io)?;
     }
@@ -243,47 +231,147 @@ fn decrypt_pkcs8(passphrase: &[u8], bytes: &[u8]) -> Result<Vec<u8>, KeystoreErr
     Ok(plain.to_vec())
 }
 
+/// Key file passphrase `AMUNCHAIN_KEY_PASSPHRASE` (or legacy `NEXUS_KEY_PASSPHRASE`), from
+/// the environment, a `_FILE` variable or `AMUNCHAIN_SECRETS_DIR` (see `secrets`).
+pub fn passphrase_from_env() -> Result<Option<String>, KeystoreError> {
+    passphrase_from(&default_chain())
+}
+
+/// Key file passphrase from `secrets`.
+pub fn passphrase_from(secrets: &dyn SecretProvider) -> Result<Option<String>, KeystoreError> {
+    for key in ["AMUNCHAIN_KEY_PASSPHRASE", "NEXUS_KEY_PASSPHRASE"] {
+        if let Some(v) = secrets.get(key)? {
+            return Ok(Some(v));
+        }
+    }
+    Ok(None)
+}
+
+/// Whether key file contents are in the encrypted format.
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(KEY_FILE_MAGIC)
+}
+
+/// Generate a new Ed25519 key and write it to `path` (see `write_key_file`).
+pub fn generate_key_file(
+    path: &Path,
+    passphrase: Option<&str>,
+) -> Result<Ed25519KeyPair, KeystoreError> {
+    let rng = SystemRandom::new();
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).map_err(|_| KeystoreError::InvalidKey)?;
+    write_key_file(path, pkcs8.as_ref(), passphrase)?;
+    Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).map_err(|_| KeystoreError::InvalidKey)
+}
+
+/// Atomically write PKCS#8 `pkcs8` to `path` (mode 0600), encrypted when `passphrase` is set.
+pub fn write_key_file(
+    path: &Path,
+    pkcs8: &[u8],
+    passphrase: Option<&str>,
+) -> Result<(), KeystoreError> {
+    write_secret_file(path, pkcs8, passphrase)
+}
+
+/// Atomically write secret `bytes` to `path` (mode 0600), encrypted when `passphrase` is set.
+pub fn write_secret_file(
+    path: &Path,
+    bytes: &[u8],
+    passphrase: Option<&str>,
+) -> Result<(), KeystoreError> {
+    match passphrase {
+        Some(p) => atomic_write_private(path, &encrypt_pkcs8(p.as_bytes(), bytes)?),
+        None => atomic_write_private(path, bytes),
+    }
+}
+
+/// Read a file written by `write_secret_file`. Encrypted files need `passphrase`.
+pub fn read_secret_file(path: &Path, passphrase: Option<&str>) -> Result<Vec<u8>, KeystoreError> {
+    let bytes = fs::read(path).map_err(|_| KeystoreError::Io)?;
+    if !is_encrypted(&bytes) {
+        return Ok(bytes);
+    }
+    let Some(p) = passphrase else {
+        return Err(KeystoreError::MissingPassphrase);
+    };
+    decrypt_pkcs8(p.as_bytes(), &bytes)
+}
+
+/// Read a key file written by `write_key_file`. Encrypted files need `passphrase`.
+pub fn read_key_file(
+    path: &Path,
+    passphrase: Option<&str>,
+) -> Result<Ed25519KeyPair, KeystoreError> {
+    let mut pkcs8 = read_secret_file(path, passphrase)?;
+    let kp = Ed25519KeyPair::from_pkcs8(&pkcs8).map_err(|_| KeystoreError::InvalidKey);
+    pkcs8.zeroize();
+    kp
+}
+
+// PKCS#8 (v1 and v2) Ed25519 private key: 5 bytes of header, then this, then the seed.
+const PKCS8_ED25519_SEED_PREFIX: [u8; 11] = [
+    0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22, 0x04, 0x20,
+];
+
+/// Ed25519 seed (the RFC 8032 private key) of a key file written by `write_key_file`, for
+/// using the same key in another library (e.g. as the libp2p identity). Zeroize it after use.
+pub fn read_key_seed(path: &Path, passphrase: Option<&str>) -> Result<[u8; 32], KeystoreError> {
+    let mut pkcs8 = read_secret_file(path, passphrase)?;
+    let seed = pkcs8_seed(&pkcs8);
+    pkcs8.zeroize();
+    seed
+}
+
+fn pkcs8_seed(pkcs8: &[u8]) -> Result<[u8; 32], KeystoreError> {
+    if pkcs8.get(5..16) != Some(&PKCS8_ED25519_SEED_PREFIX[..]) {
+        return Err(KeystoreError::InvalidKey);
+    }
+    let seed: [u8; 32] = pkcs8
+        .get(16..48)
+        .and_then(|s| s.try_into().ok())
+        .ok_or(KeystoreError::InvalidKey)?;
+    // The seed must be the key ring loads from the same bytes.
+    let kp = Ed25519KeyPair::from_pkcs8(pkcs8).map_err(|_| KeystoreError::InvalidKey)?;
+    let derived =
+        Ed25519KeyPair::from_seed_unchecked(&seed).map_err(|_| KeystoreError::InvalidKey)?;
+    if derived.public_key().as_ref() != kp.public_key().as_ref() {
+        return Err(KeystoreError::InvalidKey);
+    }
+    Ok(seed)
+}
+
+// DER prefix of an Ed25519 SubjectPublicKeyInfo (RFC 8410), followed by the 32 key bytes.
+const ED25519_SPKI_PREFIX: [u8; 12] = [
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+/// Ed25519 public key as a PEM `PUBLIC KEY` (SubjectPublicKeyInfo), as `openssl pkey -pubin`
+/// and most HSM/KMS tooling expect.
+pub fn public_key_pem(pk: &[u8; 32]) -> String {
+    use base64::Engine;
+    let mut der = ED25519_SPKI_PREFIX.to_vec();
+    der.extend_from_slice(pk);
+    format!(
+        "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----\n",
+        base64::engine::general_purpose::STANDARD.encode(der)
+    )
+}
+
 impl FileEd25519Backend {
     /// Load or create an Ed25519 PKCS#8 key file.
     ///
     /// If `AMUNCHAIN_KEY_PASSPHRASE` is set, the key file is encrypted at rest.
     pub fn load_or_create(path: &Path) -> Result<Self, KeystoreError> {
-        let pass = env_first(&["AMUNCHAIN_KEY_PASSPHRASE", "NEXUS_KEY_PASSPHRASE"]);
-
-        if path.exists() {
-            let bytes = fs::read(path).map_err(|_| KeystoreError::Io)?;
-            // If it's encrypted, passphrase is required.
-            let pkcs8 = if bytes.starts_with(KEY_FILE_MAGIC) {
-                let Some(p) = pass.as_deref() else {
-                    return Err(KeystoreError::MissingPassphrase);
-                };
-                decrypt_pkcs8(p.as_bytes(), &bytes)?
-            } else {
-                bytes
-            };
-            let kp = Ed25519KeyPair::from_pkcs8(&pkcs8).map_err(|_| KeystoreError::InvalidKey)?;
-            return Ok(Self { keypair: kp });
-        }
-
-        let rng = SystemRandom::new();
-        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).map_err(|_| KeystoreError::InvalidKey)?;
+        Self::load_or_create_with(path, passphrase_from_env()?.as_deref())
+    }
 
-        // Write key: encrypted if passphrase is present.
-        let mut buf = pkcs8.as_ref().to_vec();
-        let on_disk = if let Some(p) = pass.as_deref() {
-            let enc = encrypt_pkcs8(p.as_bytes(), &buf)?;
-            buf.zeroize();
-            enc
+    /// `load_or_create` with an explicit passphrase.
+    pub fn load_or_create_with(path: &Path, pass: Option<&str>) -> Result<Self, KeystoreError> {
+        let keypair = if path.exists() {
+            read_key_file(path, pass)?
         } else {
-            buf.clone()
+            generate_key_file(path, pass)?
         };
-        atomic_write_private(path, &on_disk)?;
-        buf.zeroize();
-
-        // Parse from plaintext pkcs8 (already in `pkcs8`).
-        let kp =
-            Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).map_err(|_| KeystoreError::InvalidKey)?;
-        Ok(Self { keypair: kp })
+        Ok(Self { keypair })
     }
 }
 
@@ -297,20 +385,20 @@ impl SignerBackend for FileEd25519Backend {
 
     fn sign(&self, msg: &[u8]) -> Result<Signature, KeystoreError> {
         let sig = self.keypair.sign(msg);
-        Ok(Signature(sig.as_ref().to_vec()))
+        Signature::from_slice(sig.as_ref()).map_err(|_| KeystoreError::BadSignature)
     }
 }
 
 /// Rate limiter (token bucket style, simple and deterministic).
 #[derive(Debug)]
-struct RateLimiter {
+pub(crate) struct RateLimiter {
     window_start: Instant,
     count: u32,
     limit_per_sec: u32,
 }
 
 impl RateLimiter {
-    fn new(limit_per_sec: u32) -> Self {
+    pub(crate) fn new(limit_per_sec: u32) -> Self {
         Self {
             window_start: Instant::now(),
             count: 0,
@@ -318,7 +406,7 @@ impl RateLimiter {
         }
     }
 
-    fn allow(&mut self) -> bool {
+    pub(crate) fn allow(&mut self) -> bool {
         let now = Instant::now();
         if now.duration_since(self.window_start) >= Duration::from_secs(1) {
             self.window_start = now;
@@ -336,23 +424,32 @@ impl RateLimiter {
 pub struct Keystore<B: SignerBackend> {
     backend: B,
     limiter: Mutex<RateLimiter>,
-    audit_path: PathBuf,
+    audit: AuditLog,
+    forwarder: Option<AuditForwarder>,
+    policy: Option<SignPolicy>,
 }
 
 impl Keystore<FileEd25519Backend> {
     /// Load or create keystore in `data_dir/validator.key` and write audit to `data_dir/audit.log`.
     pub fn open(data_dir: &str) -> Result<Self, KeystoreError> {
+        Self::open_with(data_dir, &default_chain())
+    }
+
+    /// `open`, with the key passphrase from `secrets`.
+    pub fn open_with(data_dir: &str, secrets: &dyn SecretProvider) -> Result<Self, KeystoreError> {
         let mut key_path = PathBuf::from(data_dir);
         key_path.push("validator.key");
 
-        let mut audit_path = PathBuf::from(data_dir);
-        audit_path.push("audit.log");
-
-        let backend = FileEd25519Backend::load_or_create(&key_path)?;
+        let backend = FileEd25519Backend::load_or_create_with(
+            &key_path,
+            passphrase_from(secrets)?.as_deref(),
+        )?;
         Ok(Self {
             backend,
             limiter: Mutex::new(RateLimiter::new(10_000)),
-            audit_path,
+            audit: AuditLog::open(Path::new(data_dir)),
+            forwarder: None,
+            policy: None,
         })
     }
 }
@@ -363,6 +460,26 @@ impl<B: SignerBackend> Keystore<B> {
         self.backend.public_key()
     }
 
+    /// Also ship audit entries to `forwarder`'s sinks (`keystore.audit_sinks`).
+    pub fn with_audit_forwarder(mut self, forwarder: AuditForwarder) -> Self {
+        self.forwarder = Some(forwarder);
+        self
+    }
+
+    /// Only sign what `policy` allows (`keystore.policy`). Refusals are audited as `deny`.
+    pub fn with_sign_policy(mut self, policy: SignPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Sink delivery counters; empty without a forwarder.
+    pub fn audit_sink_stats(&self) -> Vec<crate::core::security::audit_sink::SinkStats> {
+        self.forwarder
+            .as_ref()
+            .map(AuditForwarder::stats)
+            .unwrap_or_default()
+    }
+
     /// Sign with rate limiting and an audit trail (best-effort).
     pub fn sign(&self, msg: &[u8]) -> Result<Signature, KeystoreError> {
         let mut guard = self
@@ -373,9 +490,24 @@ impl<B: SignerBackend> Keystore<B> {
             return Err(KeystoreError::RateLimited);
         }
 
-        let _ = append_audit(&self.audit_path, "sign", msg);
+        if let Some(policy) = self.policy.as_ref() {
+            if let Err(e) = policy.check(msg) {
+                self.audit_best_effort("deny", msg);
+                return Err(e.into());
+            }
+        }
+
+        self.audit_best_effort("sign", msg);
         self.backend.sign(msg)
     }
+
+    fn audit_best_effort(&self, action: &str, msg: &[u8]) {
+        if let Ok(entry) = self.audit.append(action, msg) {
+            if let Some(f) = self.forwarder.as_ref() {
+                f.forward(&entry);
+            }
+        }
+    }
 }
 
 /// Verify signature given raw pubkey bytes.
@@ -384,35 +516,65 @@ pub fn verify_pubkey_bytes(
     msg: &[u8],
     sig: &Signature,
 ) -> Result<(), KeystoreError> {
-    // ring requires signature length 64 for Ed25519
-    if sig.0.len() != 64 {
-        return Err(KeystoreError::BadSignature);
-    }
     let pk = UnparsedPublicKey::new(&ED25519, pk_bytes);
-    pk.verify(msg, &sig.0)
+    pk.verify(msg, sig.as_bytes())
         .map_err(|_| KeystoreError::BadSignature)
 }
 
-fn append_audit(path: &Path, action: &str, msg: &[u8]) -> Result<(), KeystoreError> {
-    rotate_audit_if_needed(path);
+/// Parsed Ed25519 public keys of a fixed set of signers (e.g. the validator set).
+///
+/// `UnparsedPublicKey` decodes the key point on every verification; a cached key is decoded
+/// once. Keys outside the cache fall back to `verify_pubkey_bytes`, so results do not depend
+/// on what is cached.
+#[derive(Clone, Debug, Default)]
+pub struct PublicKeyCache {
+    keys: HashMap<[u8; 32], ed25519_dalek::VerifyingKey>,
+}
 
-    // best-effort: store SHA-256(message) only (avoid leaking content)
-    let digest = ring::digest::digest(&ring::digest::SHA256, msg);
-    let line = format!(
-        "{{\"action\":\"{}\",\"msg_sha256\":\"{}\"}}\n",
-        action,
-        hex::encode(digest.as_ref())
-    );
+impl PublicKeyCache {
+    /// Parse `keys`. Only canonical point encodings are cached: the rest are verified
+    /// uncached, so a key `ring` would reject is never accepted here.
+    pub fn new<'a>(keys: impl IntoIterator<Item = &'a [u8; 32]>) -> Self {
+        Self {
+            keys: keys
+                .into_iter()
+                .filter_map(|k| {
+                    ed25519_dalek::VerifyingKey::from_bytes(k)
+                        .ok()
+                        .filter(|vk| vk.to_edwards().compress().as_bytes() == k)
+                        .map(|vk| (*k, vk))
+                })
+                .collect(),
+        }
+    }
 
-    let mut f = fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(path)
-        .map_err(|_| KeystoreError::Io)?;
-    set_private_perms_best_effort(path);
-    f.write_all(line.as_bytes())
-        .map_err(|_| KeystoreError::Io)?;
-    Ok(())
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn contains(&self, pk_bytes: &[u8; 32]) -> bool {
+        self.keys.contains_key(pk_bytes)
+    }
+
+    /// Same result as `verify_pubkey_bytes`.
+    pub fn verify(
+        &self,
+        pk_bytes: &[u8; 32],
+        msg: &[u8],
+        sig: &Signature,
+    ) -> Result<(), KeystoreError> {
+        use ed25519_dalek::Verifier;
+        match self.keys.get(pk_bytes) {
+            Some(vk) => vk
+                .verify(msg, &ed25519_dalek::Signature::from_bytes(sig.as_bytes()))
+                .map_err(|_| KeystoreError::BadSignature),
+            None => verify_pubkey_bytes(pk_bytes, msg, sig),
+        }
+    }
 }
 
 /// Verify an Ed25519 signature provided as raw 64 bytes.
@@ -421,7 +583,5 @@ pub fn verify_sig_bytes64(
     msg: &[u8],
     sig64: &[u8; 64],
 ) -> Result<(), KeystoreError> {
-    // Signature is defined in crate::core::types; in this codebase it is a tuple struct over [u8; 64].
-    let sig: Signature = Signature((*sig64).to_vec());
-    verify_pubkey_bytes(pk_bytes, msg, &sig)
+    verify_pubkey_bytes(pk_bytes, msg, &Signature::from_bytes(*sig64))
 }
@@ -14,5 +14,13 @@
 
 //! Security: key management and signing.
 
+/// Hash-chained audit log and compliance archive export.
+pub mod audit;
+/// Forwarding audit entries to file, syslog and HTTP sinks.
+pub mod audit_sink;
 /// Keystore and signature verification helpers.
 pub mod keystore;
+/// Secrets from the environment, `_FILE` variables and secret directories.
+pub mod secrets;
+/// What the validator key may sign.
+pub mod sign_policy;
@@ -0,0 +1,177 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Secrets (key passphrases, API tokens) without putting them in the process environment.
+//!
+//! A secret is looked up by its environment variable name, e.g. `AMUNCHAIN_KEY_PASSPHRASE`.
+//! `default_chain` tries, in order:
+//! - the variable itself (kept for compatibility);
+//! - `<NAME>_FILE`, a path to a file holding the secret;
+//! - `$AMUNCHAIN_SECRETS_DIR/<NAME>`, for a Kubernetes secret volume or the directory a
+//!   Vault Agent renders secrets into.
+//!
+//! One trailing newline is stripped from file contents; empty values count as unset. A
+//! secret file that is named but unreadable is an error, not a missing secret: falling
+//! through could create an unencrypted key. Other backends implement `SecretProvider`.
+
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use zeroize::Zeroize;
+
+/// Directory of secret files, one file per secret name.
+pub const SECRETS_DIR_ENV: &str = "AMUNCHAIN_SECRETS_DIR";
+
+/// Secret lookup errors.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SecretError {
+    #[error("secret file {path}: {reason}")]
+    Read { path: String, reason: String },
+    #[error("secret provider {provider}: {reason}")]
+    Provider {
+        provider: &'static str,
+        reason: String,
+    },
+}
+
+/// A source of secrets, keyed by environment variable name.
+pub trait SecretProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// The secret `key`, `None` if this provider does not have it.
+    fn get(&self, key: &str) -> Result<Option<String>, SecretError>;
+}
+
+/// `<NAME>` and `<NAME>_FILE` environment variables.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EnvProvider;
+
+impl SecretProvider for EnvProvider {
+    fn name(&self) -> &'static str {
+        "env"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, SecretError> {
+        if let Some(v) = std::env::var(key).ok().filter(|v| !v.trim().is_empty()) {
+            return Ok(Some(v));
+        }
+        match std::env::var(format!("{key}_FILE")) {
+            Ok(path) if !path.trim().is_empty() => read_secret_path(Path::new(&path)),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// One file per secret in a directory (Kubernetes secret volume, Vault Agent output).
+/// A missing file means the secret is not set.
+#[derive(Clone, Debug)]
+pub struct DirProvider {
+    dir: PathBuf,
+}
+
+impl DirProvider {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl SecretProvider for DirProvider {
+    fn name(&self) -> &'static str {
+        "dir"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, SecretError> {
+        // Names are env var names; anything else could escape the directory.
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Ok(None);
+        }
+        let path = self.dir.join(key);
+        if !path.exists() {
+            return Ok(None);
+        }
+        read_secret_path(&path)
+    }
+}
+
+/// Providers tried in order; the first that has a secret wins.
+#[derive(Default)]
+pub struct SecretChain {
+    providers: Vec<Box<dyn SecretProvider>>,
+}
+
+impl SecretChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Try `provider` after the ones already added.
+    pub fn with(mut self, provider: impl SecretProvider + 'static) -> Self {
+        self.providers.push(Box::new(provider));
+        self
+    }
+
+    /// First of `keys` any provider has (earlier keys win over later ones).
+    pub fn get_any(&self, keys: &[&str]) -> Result<Option<String>, SecretError> {
+        for key in keys {
+            if let Some(v) = self.get(key)? {
+                return Ok(Some(v));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl SecretProvider for SecretChain {
+    fn name(&self) -> &'static str {
+        "chain"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, SecretError> {
+        for p in &self.providers {
+            if let Some(v) = p.get(key)? {
+                return Ok(Some(v));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Environment, `_FILE` variables, then `AMUNCHAIN_SECRETS_DIR` if set.
+pub fn default_chain() -> SecretChain {
+    let chain = SecretChain::new().with(EnvProvider);
+    match std::env::var(SECRETS_DIR_ENV) {
+        Ok(dir) if !dir.trim().is_empty() => chain.with(DirProvider::new(dir)),
+        _ => chain,
+    }
+}
+
+/// Contents of the secret file at `path`, without one trailing newline.
+pub fn read_secret_path(path: &Path) -> Result<Option<String>, SecretError> {
+    let read_err = |reason: String| SecretError::Read {
+        path: path.display().to_string(),
+        reason,
+    };
+    let mut raw = std::fs::read(path).map_err(|e| read_err(e.to_string()))?;
+    let text = std::str::from_utf8(&raw).map(str::to_owned);
+    raw.zeroize();
+    let mut text = text.map_err(|_| read_err("not UTF-8".into()))?;
+    let trimmed = text
+        .strip_suffix("\r\n")
+        .or_else(|| text.strip_suffix('\n'))
+        .map(str::len);
+    if let Some(len) = trimmed {
+        text.truncate(len);
+    }
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(text))
+}
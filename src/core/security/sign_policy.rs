@@ -0,0 +1,228 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Sign policy: what the validator key may sign.
+//!
+//! Every payload the node signs starts with a domain tag (see `consensus::signing`,
+//! `staking::unjail_signing_bytes`, `HydroConfig::build_vrf_transcript`). The policy parses
+//! that tag and refuses:
+//! - payloads of a domain not in `allowed_domains`, and any payload with no known tag;
+//! - with `monotonic_height`, a vote whose (height, round) is below the highest one signed,
+//!   or a vote for a different block at that same (height, round) — the two signatures
+//!   an equivocation would need;
+//! - more than `max_signs_per_sec` signatures per domain per second.
+//!
+//! The vote high-water mark is kept in memory and, with `with_watermark_file`, in a JSON
+//! file written before the signature is released, so a restart does not reopen old heights.
+
+use crate::core::security::keystore::{atomic_write_private, RateLimiter};
+use crate::core::types::SignPolicyConfig;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::PathBuf, sync::Mutex};
+use thiserror::Error;
+
+const VOTE_V1: &[u8] = b"Amunchain-Tide-Vote-v1";
+const VOTE_V2: &[u8] = b"Amunchain-Tide-Vote-v2";
+const VOTE_V3: &[u8] = b"Amunchain-Tide-Vote-v3";
+const TX_V1: &[u8] = b"Amunchain-Tx-v1";
+const UNJAIL_V1: &[u8] = b"Amunchain-Unjail-v1";
+const VRF_V2: &[u8] = b"Amunchain-Hydro-VRF-v2";
+const BINDING_V1: &[u8] = b"Amunchain-PeerBinding-v1";
+const PROVENANCE_V1: &[u8] = b"Amunchain-Provenance-v1";
+const BACKUP_V1: &[u8] = b"Amunchain-Backup-v1";
+
+/// Kind of payload, from its domain tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignDomain {
+    /// Consensus votes (v1, v2, v3 payloads).
+    Vote,
+    /// Transactions.
+    Tx,
+    /// Unjail requests.
+    Unjail,
+    /// Leader-election VRF transcripts.
+    Vrf,
+    /// Validator peer bindings.
+    Binding,
+    /// Startup provenance records.
+    Provenance,
+    /// Backup manifests.
+    Backup,
+}
+
+impl SignDomain {
+    /// Domain of `msg`, if it starts with a known tag.
+    pub fn of(msg: &[u8]) -> Option<Self> {
+        [
+            (VOTE_V1, Self::Vote),
+            (VOTE_V2, Self::Vote),
+            (VOTE_V3, Self::Vote),
+            (TX_V1, Self::Tx),
+            (UNJAIL_V1, Self::Unjail),
+            (VRF_V2, Self::Vrf),
+            (BINDING_V1, Self::Binding),
+            (PROVENANCE_V1, Self::Provenance),
+            (BACKUP_V1, Self::Backup),
+        ]
+        .into_iter()
+        .find_map(|(tag, d)| msg.starts_with(tag).then_some(d))
+    }
+}
+
+/// Position of a vote payload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VotePosition {
+    pub height: u64,
+    pub round: u64,
+    pub block_hash: [u8; 32],
+}
+
+/// Height, round and block hash of a vote payload, or `None` if it is malformed.
+pub fn parse_vote(msg: &[u8]) -> Option<VotePosition> {
+    let (fields, hash_at) = if let Some(rest) = msg.strip_prefix(VOTE_V1) {
+        (rest, 16)
+    } else if let Some(rest) = msg.strip_prefix(VOTE_V2) {
+        // height, round, epoch, msg_counter, sent_ts_ms (u64) and ttl_ms (u32).
+        (rest, 8 * 5 + 4)
+    } else if let Some(rest) = msg.strip_prefix(VOTE_V3) {
+        let chain_len = u32::from_be_bytes(rest.get(..4)?.try_into().ok()?) as usize;
+        (rest.get(4usize.checked_add(chain_len)?..)?, 8 * 5 + 4)
+    } else {
+        return None;
+    };
+    let u64_at = |i: usize| Some(u64::from_be_bytes(fields.get(i..i + 8)?.try_into().ok()?));
+    Some(VotePosition {
+        height: u64_at(0)?,
+        round: u64_at(8)?,
+        block_hash: fields.get(hash_at..hash_at + 32)?.try_into().ok()?,
+    })
+}
+
+/// Why a payload was refused.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PolicyError {
+    #[error("payload has no known domain tag")]
+    UnknownDomain,
+    #[error("domain {0:?} not allowed")]
+    DomainNotAllowed(SignDomain),
+    #[error("malformed vote payload")]
+    MalformedVote,
+    #[error("vote at height {height} round {round} is below the signed high-water mark")]
+    HeightRegression { height: u64, round: u64 },
+    #[error("conflicting vote at height {height} round {round}")]
+    Conflict { height: u64, round: u64 },
+    #[error("rate limit for {0:?}")]
+    RateLimited(SignDomain),
+    #[error("watermark io")]
+    Io,
+}
+
+#[derive(Debug, Default)]
+struct PolicyState {
+    last_vote: Option<VotePosition>,
+    limiters: BTreeMap<SignDomain, RateLimiter>,
+}
+
+/// Enforces a `SignPolicyConfig`; see the module docs.
+#[derive(Debug)]
+pub struct SignPolicy {
+    cfg: SignPolicyConfig,
+    watermark_file: Option<PathBuf>,
+    state: Mutex<PolicyState>,
+}
+
+impl SignPolicy {
+    pub fn new(cfg: SignPolicyConfig) -> Self {
+        Self {
+            cfg,
+            watermark_file: None,
+            state: Mutex::new(PolicyState::default()),
+        }
+    }
+
+    /// Persist the vote high-water mark in `path`, resuming from it if it exists.
+    pub fn with_watermark_file(mut self, path: impl Into<PathBuf>) -> Result<Self, PolicyError> {
+        let path = path.into();
+        if path.exists() {
+            let raw = fs::read(&path).map_err(|_| PolicyError::Io)?;
+            let last: VotePosition = serde_json::from_slice(&raw).map_err(|_| PolicyError::Io)?;
+            self.state.get_mut().map_err(|_| PolicyError::Io)?.last_vote = Some(last);
+        }
+        self.watermark_file = Some(path);
+        Ok(self)
+    }
+
+    pub fn config(&self) -> &SignPolicyConfig {
+        &self.cfg
+    }
+
+    /// Highest vote signed so far.
+    pub fn last_vote(&self) -> Option<VotePosition> {
+        self.state.lock().ok().and_then(|s| s.last_vote)
+    }
+
+    /// Check `msg` and, if it may be signed, record it. Call right before signing.
+    pub fn check(&self, msg: &[u8]) -> Result<SignDomain, PolicyError> {
+        let domain = SignDomain::of(msg).ok_or(PolicyError::UnknownDomain)?;
+        if !self.cfg.allowed_domains.contains(&domain) {
+            return Err(PolicyError::DomainNotAllowed(domain));
+        }
+        let mut state = self.state.lock().map_err(|_| PolicyError::Io)?;
+        let vote = if domain == SignDomain::Vote && self.cfg.monotonic_height {
+            let v = parse_vote(msg).ok_or(PolicyError::MalformedVote)?;
+            if let Some(last) = state.last_vote {
+                let (pos, last_pos) = ((v.height, v.round), (last.height, last.round));
+                if pos < last_pos {
+                    return Err(PolicyError::HeightRegression {
+                        height: v.height,
+                        round: v.round,
+                    });
+                }
+                if pos == last_pos && v.block_hash != last.block_hash {
+                    return Err(PolicyError::Conflict {
+                        height: v.height,
+                        round: v.round,
+                    });
+                }
+            }
+            Some(v)
+        } else {
+            None
+        };
+        let limit = self.cfg.max_signs_per_sec;
+        if !state
+            .limiters
+            .entry(domain)
+            .or_insert_with(|| RateLimiter::new(limit))
+            .allow()
+        {
+            return Err(PolicyError::RateLimited(domain));
+        }
+        if let Some(v) = vote {
+            if state.last_vote != Some(v) {
+                self.persist(&v)?;
+                state.last_vote = Some(v);
+            }
+        }
+        Ok(domain)
+    }
+
+    fn persist(&self, v: &VotePosition) -> Result<(), PolicyError> {
+        let Some(path) = self.watermark_file.as_deref() else {
+            return Ok(());
+        };
+        let bytes = serde_json::to_vec(v).map_err(|_| PolicyError::Io)?;
+        atomic_write_private(path, &bytes).map_err(|_| PolicyError::Io)
+    }
+}
@@ -0,0 +1,122 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Shutdown checkpoint.
+//!
+//! A clean shutdown records the last finalized height and the state root it left behind in
+//! the auxiliary `meta` tree (outside the state root). The next start takes the checkpoint
+//! (removing it, so a later crash is not mistaken for a clean stop) and resumes from it if
+//! the state root still matches. Without a usable checkpoint the node falls back to the
+//! highest height in the commit store.
+
+use crate::core::state::commit_store::CommitStore;
+use crate::core::state::merkle::Hash32;
+use crate::core::state::persistent_state::{PersistentState, StateError};
+use crate::core::types::{decode_canonical_limited, encode_canonical};
+use serde::{Deserialize, Serialize};
+
+/// Auxiliary tree for node bookkeeping outside the state root.
+pub(crate) const META_TREE: &str = "meta";
+const CHECKPOINT_KEY: &[u8] = b"shutdown_checkpoint";
+const MAX_CHECKPOINT_BYTES: usize = 1024;
+
+/// State at a clean shutdown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShutdownCheckpoint {
+    /// Last finalized height.
+    pub finalized_height: u64,
+    /// State root after the last applied write.
+    pub state_root: Hash32,
+    /// Wall clock at shutdown (ms since UNIX epoch).
+    pub written_at_ms: u64,
+}
+
+/// Where a restart resumes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resume {
+    /// A clean-shutdown checkpoint whose state root matches the database.
+    Checkpoint(ShutdownCheckpoint),
+    /// No usable checkpoint; the highest stored commit (0 when there is none).
+    CommitStore(u64),
+}
+
+impl Resume {
+    /// Finalized height to resume at.
+    pub fn height(&self) -> u64 {
+        match self {
+            Resume::Checkpoint(cp) => cp.finalized_height,
+            Resume::CommitStore(h) => *h,
+        }
+    }
+}
+
+/// Record `finalized_height` with the current state root and flush everything.
+pub fn write(
+    state: &PersistentState,
+    finalized_height: u64,
+) -> Result<ShutdownCheckpoint, StateError> {
+    let cp = ShutdownCheckpoint {
+        finalized_height,
+        state_root: state.state_root()?,
+        written_at_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64),
+    };
+    let raw = encode_canonical(&cp).map_err(|_| StateError::DbIo)?;
+    state
+        .open_tree(META_TREE)?
+        .insert(CHECKPOINT_KEY, raw)
+        .map_err(|_| StateError::DbIo)?;
+    state.flush()?;
+    Ok(cp)
+}
+
+/// The stored checkpoint, if any, without removing it.
+pub fn read(state: &PersistentState) -> Result<Option<ShutdownCheckpoint>, StateError> {
+    let Some(raw) = state
+        .open_tree(META_TREE)?
+        .get(CHECKPOINT_KEY)
+        .map_err(|_| StateError::DbIo)?
+    else {
+        return Ok(None);
+    };
+    decode_canonical_limited(&raw, MAX_CHECKPOINT_BYTES)
+        .map(Some)
+        .map_err(|_| StateError::DbIo)
+}
+
+/// Drop the checkpoint. Offline tools that change the state call this, so the next start
+/// does not take the change for corruption (see `integrity`).
+pub fn clear(state: &PersistentState) -> Result<(), StateError> {
+    let meta = state.open_tree(META_TREE)?;
+    meta.remove(CHECKPOINT_KEY).map_err(|_| StateError::DbIo)?;
+    meta.flush().map_err(|_| StateError::DbIo)?;
+    Ok(())
+}
+
+/// Take the checkpoint and decide where to resume. A checkpoint whose root no longer matches
+/// the state (written to after shutdown, or restored from elsewhere) is ignored.
+pub fn restore(state: &PersistentState, commits: &CommitStore) -> Result<Resume, StateError> {
+    let cp = read(state)?;
+    clear(state)?;
+    if let Some(cp) = cp {
+        if cp.state_root == state.state_root()? {
+            return Ok(Resume::Checkpoint(cp));
+        }
+        tracing::warn!(
+            height = cp.finalized_height,
+            "shutdown checkpoint does not match the state root; ignoring it"
+        );
+    }
+    Ok(Resume::CommitStore(commits.latest_height()?.unwrap_or(0)))
+}
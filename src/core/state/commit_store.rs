@@ -0,0 +1,159 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Persistent store of finalized commit certificates.
+//!
+//! Commits live in a dedicated sled tree (`commits`) keyed by big-endian height, so they are
+//! iterable in height order and do not contribute to the state root. Each entry also records
+//! the hash of the validator set that produced it, which is what light clients need to check
+//! a certificate without replaying validator set history.
+//!
+//! A second tree (`commit_timings`) holds the `CommitTimings` sidecar of each height this
+//! node finalized (see `consensus::vote_timing`); it is local observation, not consensus data.
+
+use crate::core::consensus::quorum::{QuorumConfig, QuorumRule};
+use crate::core::consensus::signing::validator_set_hash_weighted;
+use crate::core::consensus::signing::SigningDomain;
+use crate::core::consensus::tide::{verify_commit_certificate_with_rule, TideError, VotingPower};
+use crate::core::consensus::vote_timing::CommitTimings;
+use crate::core::state::persistent_state::{PersistentState, StateError};
+use crate::core::types::{decode_canonical_limited, encode_canonical, Commit, ValidatorId, H256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Tree name for commit certificates.
+const COMMITS_TREE: &str = "commits";
+
+/// Tree name for commit timing sidecars.
+const TIMINGS_TREE: &str = "commit_timings";
+
+/// Upper bound for one stored certificate (matches the gossip frame cap).
+const MAX_STORED_COMMIT_BYTES: usize = 256 * 1024;
+
+/// Commit certificate plus the validator set hash it was verified against.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FinalityProof {
+    /// Finalized commit.
+    pub commit: Commit,
+    /// Canonical hash of the validator set that signed `commit`, with powers in stake-weighted
+    /// mode (`validator_set_hash_weighted`).
+    pub validator_set_hash: H256,
+}
+
+impl FinalityProof {
+    /// Verify against a validator set obtained out of band (e.g. from a trusted checkpoint),
+    /// with `power` for a certificate produced in stake-weighted mode, and signatures bound
+    /// to a chain id (see [`SigningDomain`]).
+    pub fn verify_for_chain(
+        &self,
+        validators: &BTreeSet<ValidatorId>,
+        power: Option<&VotingPower>,
+        domain: SigningDomain<'_>,
+    ) -> Result<(), TideError> {
+        self.verify_with_rule(validators, power, domain, &QuorumConfig::TwoThirds)
+    }
+
+    /// `verify_for_chain` on a network with another commit rule (`consensus.quorum`).
+    pub fn verify_with_rule(
+        &self,
+        validators: &BTreeSet<ValidatorId>,
+        power: Option<&VotingPower>,
+        domain: SigningDomain<'_>,
+        rule: &dyn QuorumRule,
+    ) -> Result<(), TideError> {
+        let h = validator_set_hash_weighted(validators, power)?;
+        if h != self.validator_set_hash {
+            return Err(TideError::ValidatorSetMismatch);
+        }
+        verify_commit_certificate_with_rule(&self.commit, validators, power, domain, rule)
+    }
+}
+
+/// Height-indexed commit store.
+#[derive(Clone)]
+pub struct CommitStore {
+    tree: sled::Tree,
+    timings: sled::Tree,
+}
+
+impl CommitStore {
+    /// Open the commit tree inside an existing state database.
+    pub fn open(state: &PersistentState) -> Result<Self, StateError> {
+        Ok(Self {
+            tree: state.open_tree(COMMITS_TREE)?,
+            timings: state.open_tree(TIMINGS_TREE)?,
+        })
+    }
+
+    /// Persist a finality proof. An existing entry at the same height is kept.
+    pub fn put(&self, proof: &FinalityProof) -> Result<bool, StateError> {
+        let key = proof.commit.height.to_be_bytes();
+        let value = encode_canonical(proof).map_err(|_| StateError::DbIo)?;
+        let res = self
+            .tree
+            .compare_and_swap(key, None as Option<&[u8]>, Some(value))
+            .map_err(|_| StateError::DbIo)?;
+        Ok(res.is_ok())
+    }
+
+    /// Load the finality proof stored at `height`.
+    pub fn get(&self, height: u64) -> Result<Option<FinalityProof>, StateError> {
+        let Some(raw) = self
+            .tree
+            .get(height.to_be_bytes())
+            .map_err(|_| StateError::DbIo)?
+        else {
+            return Ok(None);
+        };
+        decode_canonical_limited(&raw, MAX_STORED_COMMIT_BYTES)
+            .map(Some)
+            .map_err(|_| StateError::DbIo)
+    }
+
+    /// Persist the timing sidecar of a finalized height, replacing an earlier one.
+    pub fn put_timings(&self, timings: &CommitTimings) -> Result<(), StateError> {
+        let value = encode_canonical(timings).map_err(|_| StateError::DbIo)?;
+        self.timings
+            .insert(timings.height.to_be_bytes(), value)
+            .map(|_| ())
+            .map_err(|_| StateError::DbIo)
+    }
+
+    /// Load the timing sidecar stored at `height`.
+    pub fn timings(&self, height: u64) -> Result<Option<CommitTimings>, StateError> {
+        let Some(raw) = self
+            .timings
+            .get(height.to_be_bytes())
+            .map_err(|_| StateError::DbIo)?
+        else {
+            return Ok(None);
+        };
+        decode_canonical_limited(&raw, MAX_STORED_COMMIT_BYTES)
+            .map(Some)
+            .map_err(|_| StateError::DbIo)
+    }
+
+    /// Highest stored height, if any.
+    pub fn latest_height(&self) -> Result<Option<u64>, StateError> {
+        let Some((k, _)) = self.tree.last().map_err(|_| StateError::DbIo)? else {
+            return Ok(None);
+        };
+        let bytes: [u8; 8] = k.as_ref().try_into().map_err(|_| StateError::DbIo)?;
+        Ok(Some(u64::from_be_bytes(bytes)))
+    }
+
+    /// Flush pending writes to disk.
+    pub fn flush(&self) -> Result<(), StateError> {
+        self.tree.flush().map(|_| ()).map_err(|_| StateError::DbIo)
+    }
+}
@@ -0,0 +1,304 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Encryption at rest for state values (`state.encryption`).
+//!
+//! Values of the state tree are stored as `version || nonce || AES-256-GCM(value)`, with the
+//! key of the entry as associated data so values cannot be swapped between keys. Keys stay
+//! in plaintext (sled orders by them). The Merkle root and proofs are computed over the
+//! plaintext values, so roots do not depend on whether a node encrypts. Auxiliary trees
+//! (commits, receipts, checkpoints) are not encrypted.
+//!
+//! The AES key is either derived from the keystore passphrase with PBKDF2 (`passphrase`) or
+//! read as 32 hex-encoded bytes from the `AMUNCHAIN_STATE_KEY` secret (`key`; see
+//! `core::security::secrets`). The KDF parameters and a key check value live in the
+//! `__state_encryption` tree, so an encrypted database is recognized and unlocked from the
+//! same secret by any later `open`, and a wrong secret is refused before anything is read.
+//!
+//! Enabling encryption on an existing database encrypts every value in one transaction.
+//! Turning it off again is not supported: export the state and import it into a fresh one.
+
+use crate::core::security::keystore::{passphrase_from, pbkdf2_iters};
+use crate::core::security::secrets::{default_chain, SecretProvider};
+use crate::core::state::persistent_state::StateError;
+use ring::aead::{self, Aad, LessSafeKey, Nonce, UnboundKey};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use sled::Transactional;
+use std::num::NonZeroU32;
+use zeroize::Zeroize;
+
+/// Secret holding the raw state key (hex, 32 bytes) for `key` mode.
+pub const STATE_KEY_SECRET: &str = "AMUNCHAIN_STATE_KEY";
+
+/// Auxiliary tree with the KDF parameters and key check value.
+pub const ENCRYPTION_TREE: &str = "__state_encryption";
+
+const PARAMS_KEY: &[u8] = b"params";
+const CHECK_PLAINTEXT: &[u8] = b"amunchain-state-key-check";
+const SEALED_VERSION: u8 = 1;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// Whether and how state values are encrypted (`state.encryption`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StateEncryption {
+    /// Plaintext values (an already encrypted database stays encrypted).
+    #[default]
+    Off,
+    /// Key derived from the keystore passphrase.
+    Passphrase,
+    /// Raw key from the `AMUNCHAIN_STATE_KEY` secret.
+    Key,
+}
+
+/// Secret a state key comes from.
+pub enum StateSecret {
+    Passphrase(String),
+    Key([u8; 32]),
+}
+
+impl Drop for StateSecret {
+    fn drop(&mut self) {
+        match self {
+            StateSecret::Passphrase(p) => p.zeroize(),
+            StateSecret::Key(k) => k.zeroize(),
+        }
+    }
+}
+
+impl StateSecret {
+    /// The secret `mode` names, from the default secret chain. `None` for `Off`.
+    pub fn resolve(mode: StateEncryption) -> Result<Option<Self>, StateError> {
+        Self::resolve_from(mode, &default_chain())
+    }
+
+    /// `resolve` with secrets from `secrets`.
+    pub fn resolve_from(
+        mode: StateEncryption,
+        secrets: &dyn SecretProvider,
+    ) -> Result<Option<Self>, StateError> {
+        let unavailable = |reason: String| StateError::KeyUnavailable(reason);
+        match mode {
+            StateEncryption::Off => Ok(None),
+            StateEncryption::Passphrase => passphrase_from(secrets)
+                .map_err(|e| unavailable(e.to_string()))?
+                .map(|p| Some(Self::Passphrase(p)))
+                .ok_or_else(|| unavailable("set AMUNCHAIN_KEY_PASSPHRASE".into())),
+            StateEncryption::Key => {
+                let mut hex_key = secrets
+                    .get(STATE_KEY_SECRET)
+                    .map_err(|e| unavailable(e.to_string()))?
+                    .ok_or_else(|| unavailable(format!("set {STATE_KEY_SECRET}")))?;
+                let decoded = hex::decode(hex_key.trim());
+                hex_key.zeroize();
+                let mut bytes = decoded.map_err(|_| unavailable("key is not hex".into()))?;
+                let key = <[u8; 32]>::try_from(bytes.as_slice())
+                    .map_err(|_| unavailable("key must be 32 bytes".into()));
+                bytes.zeroize();
+                Ok(Some(Self::Key(key?)))
+            }
+        }
+    }
+
+    /// The mode this secret serves.
+    pub fn mode(&self) -> StateEncryption {
+        match self {
+            StateSecret::Passphrase(_) => StateEncryption::Passphrase,
+            StateSecret::Key(_) => StateEncryption::Key,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum Kdf {
+    Raw,
+    Pbkdf2 { salt: [u8; 16], iters: u32 },
+}
+
+impl Kdf {
+    fn mode(&self) -> StateEncryption {
+        match self {
+            Kdf::Raw => StateEncryption::Key,
+            Kdf::Pbkdf2 { .. } => StateEncryption::Passphrase,
+        }
+    }
+}
+
+/// Stored in `ENCRYPTION_TREE`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Params {
+    kdf: Kdf,
+    /// `CHECK_PLAINTEXT` sealed under the key.
+    check: Vec<u8>,
+}
+
+/// AES-256-GCM over state values.
+pub struct StateCipher {
+    key: LessSafeKey,
+    rng: SystemRandom,
+}
+
+impl StateCipher {
+    fn new(mut raw: [u8; 32]) -> Result<Self, StateError> {
+        let unbound = UnboundKey::new(&aead::AES_256_GCM, &raw);
+        raw.zeroize();
+        Ok(Self {
+            key: LessSafeKey::new(unbound.map_err(|_| StateError::Crypto)?),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    fn derive(secret: &StateSecret, kdf: &Kdf) -> Result<Self, StateError> {
+        match (secret, kdf) {
+            (StateSecret::Key(k), Kdf::Raw) => Self::new(*k),
+            (StateSecret::Passphrase(p), Kdf::Pbkdf2 { salt, iters }) => {
+                let iters = NonZeroU32::new(*iters).ok_or(StateError::Crypto)?;
+                let mut out = [0u8; 32];
+                pbkdf2::derive(
+                    pbkdf2::PBKDF2_HMAC_SHA256,
+                    iters,
+                    salt,
+                    p.as_bytes(),
+                    &mut out,
+                );
+                Self::new(out)
+            }
+            _ => Err(StateError::WrongStateKey),
+        }
+    }
+
+    /// `value` sealed for `key`.
+    pub fn seal(&self, key: &[u8], value: &[u8]) -> Result<Vec<u8>, StateError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng.fill(&mut nonce).map_err(|_| StateError::Crypto)?;
+        let mut in_out = value.to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(key),
+                &mut in_out,
+            )
+            .map_err(|_| StateError::Crypto)?;
+        let mut out = Vec::with_capacity(1 + NONCE_LEN + in_out.len());
+        out.push(SEALED_VERSION);
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&in_out);
+        Ok(out)
+    }
+
+    /// Plaintext of `sealed`, stored under `key`.
+    pub fn open(&self, key: &[u8], sealed: &[u8]) -> Result<Vec<u8>, StateError> {
+        if sealed.len() < 1 + NONCE_LEN + TAG_LEN || sealed[0] != SEALED_VERSION {
+            return Err(StateError::Crypto);
+        }
+        let nonce: [u8; NONCE_LEN] = sealed[1..1 + NONCE_LEN]
+            .try_into()
+            .map_err(|_| StateError::Crypto)?;
+        let mut in_out = sealed[1 + NONCE_LEN..].to_vec();
+        let plain = self
+            .key
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(key),
+                &mut in_out,
+            )
+            .map_err(|_| StateError::Crypto)?;
+        Ok(plain.to_vec())
+    }
+}
+
+fn load_params(db: &sled::Db) -> Result<Option<Params>, StateError> {
+    let tree = db
+        .open_tree(ENCRYPTION_TREE)
+        .map_err(|_| StateError::DbOpen)?;
+    let Some(raw) = tree.get(PARAMS_KEY).map_err(|_| StateError::DbIo)? else {
+        return Ok(None);
+    };
+    serde_json::from_slice(&raw)
+        .map(Some)
+        .map_err(|_| StateError::Crypto)
+}
+
+/// Whether the database at `db` stores encrypted values, and in which mode.
+pub(crate) fn stored_mode(db: &sled::Db) -> Result<Option<StateEncryption>, StateError> {
+    Ok(load_params(db)?.map(|p| p.kdf.mode()))
+}
+
+/// Cipher for `db`. With `secret` and a plaintext database, every value is encrypted first.
+pub(crate) fn unlock(
+    db: &sled::Db,
+    secret: Option<&StateSecret>,
+) -> Result<Option<StateCipher>, StateError> {
+    match (load_params(db)?, secret) {
+        (None, None) => Ok(None),
+        (Some(_), None) => Err(StateError::EncryptionKeyRequired),
+        (Some(params), Some(secret)) => {
+            if params.kdf.mode() != secret.mode() {
+                return Err(StateError::WrongStateKey);
+            }
+            let cipher = StateCipher::derive(secret, &params.kdf)?;
+            match cipher.open(PARAMS_KEY, &params.check) {
+                Ok(check) if check == CHECK_PLAINTEXT => Ok(Some(cipher)),
+                _ => Err(StateError::WrongStateKey),
+            }
+        }
+        (None, Some(secret)) => encrypt_existing(db, secret).map(Some),
+    }
+}
+
+fn encrypt_existing(db: &sled::Db, secret: &StateSecret) -> Result<StateCipher, StateError> {
+    let kdf = match secret {
+        StateSecret::Key(_) => Kdf::Raw,
+        StateSecret::Passphrase(_) => {
+            let mut salt = [0u8; 16];
+            SystemRandom::new()
+                .fill(&mut salt)
+                .map_err(|_| StateError::Crypto)?;
+            Kdf::Pbkdf2 {
+                salt,
+                iters: pbkdf2_iters().get(),
+            }
+        }
+    };
+    let cipher = StateCipher::derive(secret, &kdf)?;
+    let params = Params {
+        check: cipher.seal(PARAMS_KEY, CHECK_PLAINTEXT)?,
+        kdf,
+    };
+    let params = serde_json::to_vec(&params).map_err(|_| StateError::Crypto)?;
+
+    let mut sealed = Vec::new();
+    for item in db.iter() {
+        let (k, v) = item.map_err(|_| StateError::DbIo)?;
+        sealed.push((k.to_vec(), cipher.seal(&k, &v)?));
+    }
+    let meta = db
+        .open_tree(ENCRYPTION_TREE)
+        .map_err(|_| StateError::DbOpen)?;
+    // Values and parameters change together, or not at all.
+    let state: &sled::Tree = db;
+    (state, &meta)
+        .transaction(|(t, m)| {
+            for (k, v) in &sealed {
+                t.insert(k.as_slice(), v.as_slice())?;
+            }
+            m.insert(PARAMS_KEY, params.as_slice())?;
+            Ok(())
+        })
+        .map_err(|_: sled::transaction::TransactionError<()>| StateError::DbIo)?;
+    db.flush().map_err(|_| StateError::DbIo)?;
+    Ok(cipher)
+}
@@ -0,0 +1,76 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Persistent store of block headers.
+//!
+//! Headers live in a dedicated sled tree (`headers`) keyed by header hash, outside the state
+//! root. Whatever imports blocks records their headers here; the consensus driver's beacon
+//! reads the VRF output of each finalized block from it (see `ConsensusDriver::with_beacon`).
+
+use crate::core::consensus::import::ChainView;
+use crate::core::state::persistent_state::{PersistentState, StateError};
+use crate::core::types::{decode_canonical_limited, encode_canonical, BlockHeader, H256};
+
+/// Tree name for block headers.
+const HEADERS_TREE: &str = "headers";
+
+/// Upper bound for one stored header.
+const MAX_STORED_HEADER_BYTES: usize = 4 * 1024;
+
+/// Hash-indexed header store.
+#[derive(Clone)]
+pub struct HeaderStore {
+    tree: sled::Tree,
+}
+
+impl HeaderStore {
+    /// Open the header tree inside an existing state database.
+    pub fn open(state: &PersistentState) -> Result<Self, StateError> {
+        Ok(Self {
+            tree: state.open_tree(HEADERS_TREE)?,
+        })
+    }
+
+    /// Persist `header` under its hash, which is returned.
+    pub fn put(&self, header: &BlockHeader) -> Result<H256, StateError> {
+        let hash = header.hash().map_err(|_| StateError::DbIo)?;
+        let value = encode_canonical(header).map_err(|_| StateError::DbIo)?;
+        if value.len() > MAX_STORED_HEADER_BYTES {
+            return Err(StateError::DbIo);
+        }
+        self.tree
+            .insert(hash.as_bytes(), value)
+            .map_err(|_| StateError::DbIo)?;
+        Ok(hash)
+    }
+
+    /// Load the header stored under `hash`.
+    pub fn get(&self, hash: &H256) -> Result<Option<BlockHeader>, StateError> {
+        let Some(raw) = self
+            .tree
+            .get(hash.as_bytes())
+            .map_err(|_| StateError::DbIo)?
+        else {
+            return Ok(None);
+        };
+        decode_canonical_limited(&raw, MAX_STORED_HEADER_BYTES)
+            .map(Some)
+            .map_err(|_| StateError::DbIo)
+    }
+}
+
+impl ChainView for HeaderStore {
+    fn header(&self, hash: &H256) -> Option<BlockHeader> {
+        self.get(hash).ok().flatten()
+    }
+}
@@ -0,0 +1,216 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Startup integrity check of the state database.
+//!
+//! Before the node uses its database, `check` reads every entry of every tree (sled checks
+//! its checksums on read; encrypted values must decrypt) and recomputes the state root; a
+//! sparse tree must also agree with the root rebuilt from the pairs (`smt`). If a
+//! clean shutdown left a checkpoint (see `checkpoint`), the root must match it: nothing
+//! writes to the database between a clean stop and the next start, and the offline commands
+//! that do (`import-state`) drop the checkpoint.
+//!
+//! sled treats a damaged log as a torn write and discards it on open, checkpoint included,
+//! so a clean shutdown also leaves a copy of the checkpoint in `integrity.json` next to the
+//! database files. `open_checked` compares the root with that copy as well; the node
+//! removes it once the check has passed, as it does the stored checkpoint.
+//!
+//! A failed check stops the node with exit code 14, unless it runs with `--repair`: then the
+//! database is moved aside (`quarantine`) and the node starts from an empty state, catching
+//! up from peers like a new node, or from a snapshot (`StateRepair`).
+
+use crate::core::state::checkpoint::{self, ShutdownCheckpoint};
+use crate::core::state::encryption::StateEncryption;
+use crate::core::state::merkle::Hash32;
+use crate::core::state::persistent_state::{PersistentState, StateError};
+use crate::core::state::smt::StateTree;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Copy of the shutdown checkpoint inside the database directory.
+pub const MARKER_FILE: &str = "integrity.json";
+
+/// Why the state database failed its check.
+#[derive(Debug, Error)]
+pub enum IntegrityError {
+    #[error("state database unreadable: {0}")]
+    Unreadable(StateError),
+    #[error("{path}: {reason}")]
+    Marker { path: String, reason: String },
+    #[error(
+        "state root {} does not match the shutdown checkpoint at height {height} ({})",
+        hex::encode(actual),
+        hex::encode(checkpoint)
+    )]
+    CheckpointMismatch {
+        height: u64,
+        checkpoint: Hash32,
+        actual: Hash32,
+    },
+    #[error(
+        "stored sparse tree root {} does not match the root of the state, {}",
+        hex::encode(stored),
+        hex::encode(rebuilt)
+    )]
+    TreeMismatch { stored: Hash32, rebuilt: Hash32 },
+    /// Not a sign of corruption (database locked, state key missing).
+    #[error(transparent)]
+    State(StateError),
+}
+
+impl From<StateError> for IntegrityError {
+    fn from(e: StateError) -> Self {
+        match e {
+            StateError::DbOpen | StateError::DbIo | StateError::Crypto => Self::Unreadable(e),
+            e => Self::State(e),
+        }
+    }
+}
+
+impl IntegrityError {
+    /// Metric label, `None` when this is not corruption (and repair would not help).
+    pub fn kind(&self) -> Option<&'static str> {
+        match self {
+            Self::Unreadable(_) => Some("unreadable"),
+            Self::Marker { .. } => Some("marker"),
+            Self::CheckpointMismatch { .. } => Some("checkpoint_mismatch"),
+            Self::TreeMismatch { .. } => Some("tree_mismatch"),
+            Self::State(_) => None,
+        }
+    }
+}
+
+/// Result of a passed check.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// Entries read, over all trees.
+    pub entries: u64,
+    pub state_root: Hash32,
+    /// The shutdown checkpoint the root was checked against, if there was one.
+    pub checkpoint: Option<ShutdownCheckpoint>,
+}
+
+/// What `--repair` does with a database that failed its check.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum StateRepair {
+    /// Fail startup.
+    #[default]
+    Off,
+    /// Start from an empty state and catch up from peers.
+    Resync,
+    /// Start from a state snapshot written by `export-state`.
+    Snapshot(PathBuf),
+}
+
+/// Read the whole database and compare its root with the shutdown checkpoint.
+pub fn check(state: &PersistentState) -> Result<IntegrityReport, IntegrityError> {
+    let entries = state.verify_readable()?;
+    let state_root = state.state_root()?;
+    if state.state_tree() == StateTree::Sparse {
+        let rebuilt = state.rebuilt_state_root()?;
+        if rebuilt != state_root {
+            return Err(IntegrityError::TreeMismatch {
+                stored: state_root,
+                rebuilt,
+            });
+        }
+    }
+    let checkpoint = checkpoint::read(state)?;
+    if let Some(cp) = checkpoint.filter(|cp| cp.state_root != state_root) {
+        return Err(IntegrityError::CheckpointMismatch {
+            height: cp.finalized_height,
+            checkpoint: cp.state_root,
+            actual: state_root,
+        });
+    }
+    Ok(IntegrityReport {
+        entries,
+        state_root,
+        checkpoint,
+    })
+}
+
+/// Open the database at `path` (see `PersistentState::open_with`) and `check` it, also
+/// against the checkpoint copy in `MARKER_FILE`.
+pub fn open_checked(
+    path: &Path,
+    mode: StateEncryption,
+) -> Result<(PersistentState, IntegrityReport), IntegrityError> {
+    let marker = read_marker(path)?;
+    let state = PersistentState::open_with(&path.to_string_lossy(), mode)?;
+    let mut report = check(&state)?;
+    if let Some(cp) = marker {
+        if cp.state_root != report.state_root {
+            return Err(IntegrityError::CheckpointMismatch {
+                height: cp.finalized_height,
+                checkpoint: cp.state_root,
+                actual: report.state_root,
+            });
+        }
+        report.checkpoint.get_or_insert(cp);
+    }
+    Ok((state, report))
+}
+
+/// Write `cp` to `MARKER_FILE` in the database directory `dir`.
+pub fn write_marker(dir: &Path, cp: &ShutdownCheckpoint) -> Result<(), IntegrityError> {
+    let path = dir.join(MARKER_FILE);
+    let tmp = dir.join(format!("{MARKER_FILE}.tmp"));
+    let marker_err = marker_err(&path);
+    let json = serde_json::to_vec(cp).map_err(|e| marker_err(e.to_string()))?;
+    std::fs::write(&tmp, json)
+        .and_then(|()| std::fs::rename(&tmp, &path))
+        .map_err(|e| marker_err(e.to_string()))
+}
+
+/// The checkpoint copy in `dir`, if there is one.
+pub fn read_marker(dir: &Path) -> Result<Option<ShutdownCheckpoint>, IntegrityError> {
+    let path = dir.join(MARKER_FILE);
+    let raw = match std::fs::read(&path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(marker_err(&path)(e.to_string())),
+    };
+    serde_json::from_slice(&raw)
+        .map(Some)
+        .map_err(|e| marker_err(&path)(e.to_string()))
+}
+
+/// Remove the checkpoint copy in `dir`, if there is one.
+pub fn clear_marker(dir: &Path) -> Result<(), IntegrityError> {
+    let path = dir.join(MARKER_FILE);
+    match std::fs::remove_file(&path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(marker_err(&path)(e.to_string())),
+        _ => Ok(()),
+    }
+}
+
+fn marker_err(path: &Path) -> impl Fn(String) -> IntegrityError + '_ {
+    move |reason| IntegrityError::Marker {
+        path: path.display().to_string(),
+        reason,
+    }
+}
+
+/// Move the database directory at `path` aside, to `<path>.corrupt-<unix-ms>`. Returns the
+/// new location. The database must not be open.
+pub fn quarantine(path: &Path) -> std::io::Result<PathBuf> {
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis());
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".corrupt-{now_ms}"));
+    let dest = path.with_file_name(name);
+    std::fs::rename(path, &dest)?;
+    Ok(dest)
+}
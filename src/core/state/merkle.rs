@@ -16,8 +16,14 @@
 //!
 //! leaf = H( "Amunchain-State-Leaf-v1" || H(key) || H(value) )
 //! node = H( "Amunchain-State-Node-v1" || left || right )
+//!
+//! Proofs have one canonical byte encoding (`MerkleProof::to_bytes`), which serde uses: hex
+//! in human-readable formats such as JSON, raw bytes otherwise.
 
 use ring::digest;
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use thiserror::Error;
 
 /// Hash32 type.
 pub type Hash32 = [u8; 32];
@@ -25,8 +31,14 @@ pub type Hash32 = [u8; 32];
 const LEAF_DOMAIN: &[u8] = b"Amunchain-State-Leaf-v1";
 const NODE_DOMAIN: &[u8] = b"Amunchain-State-Node-v1";
 
+/// Deepest proof that decodes (trees of up to 2^64 leaves).
+pub const MAX_PROOF_DEPTH: usize = 64;
+
+const PROOF_VERSION: u8 = 1;
+const PROOF_HEADER_LEN: usize = 2 + 32;
+
 /// Side of sibling in proof.
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Side {
     /// Sibling is left.
     Left,
@@ -35,7 +47,7 @@ pub enum Side {
 }
 
 /// One proof item.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ProofItem {
     /// Whether sibling is left or right of current hash.
     pub side: Side,
@@ -44,7 +56,7 @@ pub struct ProofItem {
 }
 
 /// Merkle inclusion proof.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MerkleProof {
     /// Leaf hash.
     pub leaf: Hash32,
@@ -52,16 +64,156 @@ pub struct MerkleProof {
     pub path: Vec<ProofItem>,
 }
 
-fn h(data: &[u8]) -> Hash32 {
+/// Why proof bytes did not decode.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ProofCodecError {
+    #[error("proof truncated")]
+    Truncated,
+    #[error("unsupported proof encoding version {0}")]
+    Version(u8),
+    #[error("proof depth {0} exceeds {MAX_PROOF_DEPTH}")]
+    TooDeep(usize),
+    #[error("proof is {actual} bytes; its depth needs {expected}")]
+    Length { expected: usize, actual: usize },
+    #[error("proof has unused side bits set")]
+    Padding,
+    #[error("unknown proof flags {0:#04x}")]
+    Flags(u8),
+}
+
+impl MerkleProof {
+    /// Length of `to_bytes` for a proof of `depth` items.
+    pub const fn encoded_len(depth: usize) -> usize {
+        PROOF_HEADER_LEN + depth.div_ceil(8) + depth * 32
+    }
+
+    /// Canonical encoding: version (1) || depth (1) || leaf || sides || siblings.
+    ///
+    /// `sides` packs one bit per path item, least significant bit first, set when the
+    /// sibling is on the left; unused bits are zero. Siblings follow as 32 bytes each, leaf
+    /// to root. Fails only on paths deeper than `MAX_PROOF_DEPTH`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProofCodecError> {
+        let depth = self.path.len();
+        if depth > MAX_PROOF_DEPTH {
+            return Err(ProofCodecError::TooDeep(depth));
+        }
+        let mut out = Vec::with_capacity(Self::encoded_len(depth));
+        out.extend_from_slice(&[PROOF_VERSION, depth as u8]);
+        out.extend_from_slice(&self.leaf);
+        let mut sides = vec![0u8; depth.div_ceil(8)];
+        for (i, item) in self.path.iter().enumerate() {
+            if item.side == Side::Left {
+                sides[i / 8] |= 1 << (i % 8);
+            }
+        }
+        out.extend_from_slice(&sides);
+        for item in &self.path {
+            out.extend_from_slice(&item.sibling);
+        }
+        Ok(out)
+    }
+
+    /// Decode `to_bytes` output. Rejects anything but the canonical encoding.
+    pub fn from_bytes(raw: &[u8]) -> Result<Self, ProofCodecError> {
+        let (&[version, depth], rest) = raw
+            .split_first_chunk::<2>()
+            .ok_or(ProofCodecError::Truncated)?;
+        if version != PROOF_VERSION {
+            return Err(ProofCodecError::Version(version));
+        }
+        let depth = usize::from(depth);
+        if depth > MAX_PROOF_DEPTH {
+            return Err(ProofCodecError::TooDeep(depth));
+        }
+        let expected = Self::encoded_len(depth);
+        if raw.len() != expected {
+            return Err(ProofCodecError::Length {
+                expected,
+                actual: raw.len(),
+            });
+        }
+        let (leaf, rest) = rest.split_at(32);
+        let (sides, siblings) = rest.split_at(depth.div_ceil(8));
+        if depth % 8 != 0 && sides[depth / 8] >> (depth % 8) != 0 {
+            return Err(ProofCodecError::Padding);
+        }
+        let path = siblings
+            .chunks_exact(32)
+            .enumerate()
+            .map(|(i, sibling)| ProofItem {
+                side: if sides[i / 8] & (1 << (i % 8)) != 0 {
+                    Side::Left
+                } else {
+                    Side::Right
+                },
+                sibling: sibling.try_into().unwrap_or_default(),
+            })
+            .collect();
+        Ok(MerkleProof {
+            leaf: leaf.try_into().unwrap_or_default(),
+            path,
+        })
+    }
+}
+
+impl Serialize for MerkleProof {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let raw = self.to_bytes().map_err(serde::ser::Error::custom)?;
+        if s.is_human_readable() {
+            s.serialize_str(&hex::encode(raw))
+        } else {
+            s.serialize_bytes(&raw)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MerkleProof {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        struct ProofVisitor;
+
+        impl Visitor<'_> for ProofVisitor {
+            type Value = MerkleProof;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("an encoded Merkle proof")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<MerkleProof, E> {
+                // Checked before decoding the hex, so oversized input costs nothing.
+                if v.len() > 2 * MerkleProof::encoded_len(MAX_PROOF_DEPTH) {
+                    return Err(E::invalid_length(v.len(), &self));
+                }
+                let raw = hex::decode(v).map_err(E::custom)?;
+                MerkleProof::from_bytes(&raw).map_err(E::custom)
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<MerkleProof, E> {
+                MerkleProof::from_bytes(v).map_err(E::custom)
+            }
+        }
+
+        if d.is_human_readable() {
+            d.deserialize_str(ProofVisitor)
+        } else {
+            d.deserialize_bytes(ProofVisitor)
+        }
+    }
+}
+
+pub(crate) fn h(data: &[u8]) -> Hash32 {
     let d = digest::digest(&digest::SHA256, data);
     let mut out = [0u8; 32];
     out.copy_from_slice(d.as_ref());
     out
 }
 
-fn hash_leaf(key: &[u8], value: &[u8]) -> Hash32 {
-    let hk = h(key);
-    let hv = h(value);
+/// Leaf hash: SHA-256(LEAF_DOMAIN || SHA-256(key) || SHA-256(value)).
+pub fn hash_leaf(key: &[u8], value: &[u8]) -> Hash32 {
+    hash_leaf_hashed(h(key), h(value))
+}
+
+/// `hash_leaf` of a key and value given by their SHA-256 hashes.
+pub fn hash_leaf_hashed(hk: Hash32, hv: Hash32) -> Hash32 {
     let mut buf = Vec::with_capacity(LEAF_DOMAIN.len() + 32 + 32);
     buf.extend_from_slice(LEAF_DOMAIN);
     buf.extend_from_slice(&hk);
@@ -69,7 +221,8 @@ fn hash_leaf(key: &[u8], value: &[u8]) -> Hash32 {
     h(&buf)
 }
 
-fn hash_node(left: Hash32, right: Hash32) -> Hash32 {
+/// Inner node hash: SHA-256(NODE_DOMAIN || left || right).
+pub fn hash_node(left: Hash32, right: Hash32) -> Hash32 {
     let mut buf = Vec::with_capacity(NODE_DOMAIN.len() + 32 + 32);
     buf.extend_from_slice(NODE_DOMAIN);
     buf.extend_from_slice(&left);
@@ -148,6 +301,18 @@ pub fn merkle_proof_sorted(pairs: &[(Vec<u8>, Vec<u8>)], index: usize) -> Option
     Some(MerkleProof { leaf, path })
 }
 
+/// Position of the proven leaf, read from the sides of its path.
+pub fn proof_index(proof: &MerkleProof) -> u64 {
+    proof
+        .path
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| matches!(item.side, Side::Left))
+        .fold(0, |idx, (level, _)| {
+            idx | 1u64.checked_shl(level as u32).unwrap_or(0)
+        })
+}
+
 /// Verify proof against root.
 pub fn verify_proof(root: Hash32, proof: &MerkleProof) -> bool {
     let mut cur = proof.leaf;
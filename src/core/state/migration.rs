@@ -0,0 +1,172 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Versioned state schema migrations.
+//!
+//! The layout of stored values has a schema version, recorded in the auxiliary `meta` tree
+//! (outside the state root). At startup `run` applies, in order, every registered migration
+//! above the recorded version and records each one as it completes. A database recorded at a
+//! version above the newest registered one was written by a newer binary and is refused.
+//!
+//! A migration rewrites state through `PersistentState` and must be deterministic (every
+//! node derives the same state root from the same state) and idempotent: a crash between a
+//! migration's writes and its version record runs it again at the next start. A new,
+//! empty database starts at the newest version without running anything.
+
+use crate::core::state::checkpoint::META_TREE;
+use crate::core::state::persistent_state::{PersistentState, StateError};
+use thiserror::Error;
+
+/// Key of the schema version in the `meta` tree (big-endian u32).
+pub const SCHEMA_VERSION_KEY: &[u8] = b"schema_version";
+
+/// Schema version of databases written by this binary.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Migration failures.
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error("state schema version {stored} is newer than this binary supports ({supported})")]
+    NewerSchema { stored: u32, supported: u32 },
+    #[error("migration {version} is out of order; expected version {expected}")]
+    OutOfOrder { version: u32, expected: u32 },
+    #[error("stored schema version is malformed")]
+    BadVersion,
+    #[error("migration {version} ({name}) failed: {source}")]
+    Failed {
+        version: u32,
+        name: &'static str,
+        source: StateError,
+    },
+    #[error(transparent)]
+    State(#[from] StateError),
+}
+
+/// One schema change: brings a database at `version - 1` to `version`.
+#[derive(Clone, Copy, Debug)]
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub apply: fn(&PersistentState) -> Result<(), StateError>,
+}
+
+/// What `run` did.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// Recorded version before the run (0: none recorded).
+    pub from: u32,
+    /// Recorded version after the run.
+    pub to: u32,
+    /// Names of the migrations applied, in order.
+    pub applied: Vec<&'static str>,
+}
+
+/// Ordered migrations, versions 1, 2, ... without gaps.
+#[derive(Clone, Debug, Default)]
+pub struct MigrationRegistry {
+    migrations: Vec<Migration>,
+}
+
+impl MigrationRegistry {
+    /// Append `m`, which must be the next version.
+    pub fn register(mut self, m: Migration) -> Result<Self, MigrationError> {
+        let expected = self.latest() + 1;
+        if m.version != expected {
+            return Err(MigrationError::OutOfOrder {
+                version: m.version,
+                expected,
+            });
+        }
+        self.migrations.push(m);
+        Ok(self)
+    }
+
+    /// Newest version the registry migrates to (0 when empty).
+    pub fn latest(&self) -> u32 {
+        self.migrations.last().map_or(0, |m| m.version)
+    }
+
+    /// Bring `state` to `latest`, applying and recording each pending migration in order.
+    pub fn run(&self, state: &PersistentState) -> Result<MigrationReport, MigrationError> {
+        let latest = self.latest();
+        let from = match stored_version(state)? {
+            Some(v) => v,
+            None if state.scan_prefix_page(b"", None, 1)?.entries.is_empty() => {
+                record_version(state, latest)?;
+                return Ok(MigrationReport {
+                    from: 0,
+                    to: latest,
+                    applied: Vec::new(),
+                });
+            }
+            None => 0,
+        };
+        if from > latest {
+            return Err(MigrationError::NewerSchema {
+                stored: from,
+                supported: latest,
+            });
+        }
+        let mut applied = Vec::new();
+        for m in self.migrations.iter().filter(|m| m.version > from) {
+            (m.apply)(state).map_err(|source| MigrationError::Failed {
+                version: m.version,
+                name: m.name,
+                source,
+            })?;
+            record_version(state, m.version)?;
+            applied.push(m.name);
+        }
+        Ok(MigrationReport {
+            from,
+            to: latest,
+            applied,
+        })
+    }
+}
+
+/// Migrations of this binary, up to `SCHEMA_VERSION`.
+pub fn builtin() -> MigrationRegistry {
+    MigrationRegistry {
+        migrations: vec![Migration {
+            version: 1,
+            name: "baseline",
+            // Databases written before schema versioning already have the version 1 layout.
+            apply: |_| Ok(()),
+        }],
+    }
+}
+
+/// The recorded schema version, if any.
+pub fn stored_version(state: &PersistentState) -> Result<Option<u32>, MigrationError> {
+    let raw = state
+        .open_tree(META_TREE)?
+        .get(SCHEMA_VERSION_KEY)
+        .map_err(|_| StateError::DbIo)?;
+    raw.map(|v| {
+        <[u8; 4]>::try_from(v.as_ref())
+            .map(u32::from_be_bytes)
+            .map_err(|_| MigrationError::BadVersion)
+    })
+    .transpose()
+}
+
+/// Record `version` and flush.
+pub fn record_version(state: &PersistentState, version: u32) -> Result<(), MigrationError> {
+    state
+        .open_tree(META_TREE)?
+        .insert(SCHEMA_VERSION_KEY, &version.to_be_bytes())
+        .map_err(|_| StateError::DbIo)?;
+    state.flush()?;
+    Ok(())
+}
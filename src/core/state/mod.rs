@@ -14,6 +14,27 @@
 
 //! State management: persistent KV + deterministic Merkle proofs.
 
+/// Clean-shutdown checkpoint (finalized height + state root).
+pub mod checkpoint;
+/// Finalized commit certificates keyed by height.
+pub mod commit_store;
+/// Encryption at rest for state values.
+pub mod encryption;
+/// Block headers keyed by hash.
+pub mod header_store;
+/// Startup integrity check and repair of the state database.
+pub mod integrity;
 /// Merkle tree primitives and proofs.
 pub mod merkle;
+/// Versioned schema migrations run at startup.
+pub mod migration;
 pub mod persistent_state;
+/// Transaction receipts and per-block log blooms.
+pub mod receipt_store;
+/// Sparse Merkle tree state backend with proofs of absence.
+pub mod smt;
+/// Differential test harness for state roots.
+#[cfg(feature = "testing")]
+pub mod testing;
+/// Key change notifications for `PersistentState` commits.
+pub mod watch;
@@ -13,11 +13,27 @@
 #![forbid(unsafe_code)]
 
 //! Persistent key-value state using sled, with deterministic Merkle roots and inclusion proofs.
+//! Values can be encrypted at rest; see `core::state::encryption`. Commits notify key
+//! watches; see `core::state::watch`. The root comes from the sorted tree of `merkle` or,
+//! if the database was built with it, the sparse tree of `core::state::smt`.
 
+use crate::core::state::checkpoint::META_TREE;
+use crate::core::state::encryption::{self, StateCipher, StateEncryption, StateSecret};
 use crate::core::state::merkle::{
-    merkle_proof_sorted, merkle_root_sorted, verify_proof, Hash32, MerkleProof,
+    h, hash_leaf, merkle_proof_sorted, merkle_root_sorted, proof_index, verify_proof, Hash32,
+    MerkleProof,
 };
-use sled::transaction::ConflictableTransactionError;
+use crate::core::state::smt::{self, SparseProof, StateTree, SMT_TREE, STATE_TREE_KEY};
+use crate::core::state::watch::{StateWatch, WatchFilter, Watchers};
+use crate::monitoring::metrics::Metrics;
+use sled::transaction::{
+    ConflictableTransactionError, ConflictableTransactionResult, TransactionError,
+    TransactionalTree,
+};
+use sled::Transactional;
+use std::cell::RefCell;
+use std::ops::Bound;
+use std::sync::Arc;
 use thiserror::Error;
 
 /// State errors.
@@ -25,10 +41,30 @@ use thiserror::Error;
 pub enum StateError {
     #[error("db open")]
     DbOpen,
+    #[error("db is in use by another process")]
+    Locked,
     #[error("db io")]
     DbIo,
     #[error("tx conflict")]
     TxConflict,
+    #[error("state is encrypted; its key is not available")]
+    EncryptionKeyRequired,
+    #[error("state key does not match the encrypted state")]
+    WrongStateKey,
+    #[error("state key unavailable: {0}")]
+    KeyUnavailable(String),
+    #[error("state value failed to decrypt")]
+    Crypto,
+    #[error("not available with the {0} state tree")]
+    UnsupportedTree(StateTree),
+    #[error(
+        "state database uses the {stored} state tree, the config the {configured} one; \
+         run `amunchain migrate-state-tree --to {configured}` to convert it"
+    )]
+    TreeMismatch {
+        stored: StateTree,
+        configured: StateTree,
+    },
 }
 
 /// State operation.
@@ -40,46 +76,379 @@ pub enum KvOp {
     Del { key: Vec<u8> },
 }
 
+/// One page of a paged prefix scan (`PersistentState::scan_prefix_page`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StatePage {
+    /// Pairs in key order.
+    pub entries: Vec<(Vec<u8>, Vec<u8>)>,
+    /// Pass as `start_after` to fetch the next page; `None` on the last page.
+    pub next_start_after: Option<Vec<u8>>,
+}
+
+/// Inclusion proofs of a page's first and last entries against one state root.
+///
+/// Leaf positions follow from the proof paths, so boundaries `entries.len() - 1` leaves
+/// apart also show that no entry between them was left out of the page.
+#[derive(Clone, Debug)]
+pub struct PageProof {
+    pub state_root: Hash32,
+    pub first: MerkleProof,
+    pub last: MerkleProof,
+}
+
+impl PageProof {
+    /// Check the proofs against `state_root` and the entries of `page`.
+    pub fn verify(&self, page: &StatePage) -> bool {
+        let (Some((fk, fv)), Some((lk, lv))) = (page.entries.first(), page.entries.last()) else {
+            return false;
+        };
+        let span = proof_index(&self.last).checked_sub(proof_index(&self.first));
+        self.first.leaf == hash_leaf(fk, fv)
+            && self.last.leaf == hash_leaf(lk, lv)
+            && span == Some(page.entries.len() as u64 - 1)
+            && verify_proof(self.state_root, &self.first)
+            && verify_proof(self.state_root, &self.last)
+    }
+}
+
+// Collect up to `limit` pairs from `items` (sorted, starting at or after the first key the
+// page may hold) while their keys start with `prefix`.
+fn collect_page(
+    items: impl Iterator<Item = Result<(Vec<u8>, Vec<u8>), StateError>>,
+    prefix: &[u8],
+    limit: usize,
+) -> Result<StatePage, StateError> {
+    let mut page = StatePage::default();
+    for item in items {
+        let (k, v) = item?;
+        if !k.starts_with(prefix) {
+            break;
+        }
+        if page.entries.len() == limit.max(1) {
+            page.next_start_after = page.entries.last().map(|(k, _)| k.clone());
+            break;
+        }
+        page.entries.push((k, v));
+    }
+    Ok(page)
+}
+
+// First key a page may hold: after `start_after`, but never before `prefix`.
+fn page_start<'a>(prefix: &'a [u8], start_after: Option<&'a [u8]>) -> Bound<&'a [u8]> {
+    match start_after {
+        Some(after) if after >= prefix => Bound::Excluded(after),
+        _ => Bound::Included(prefix),
+    }
+}
+
 /// Persistent state wrapper.
 #[derive(Clone)]
 pub struct PersistentState {
     db: sled::Db,
+    cipher: Option<Arc<StateCipher>>,
+    tree: StateTree,
+    metrics: Option<Arc<Metrics>>,
+    watchers: Arc<Watchers>,
+}
+
+// The tree recorded in the meta tree.
+fn stored_tree(db: &sled::Db) -> Result<StateTree, StateError> {
+    let meta = db.open_tree(META_TREE).map_err(|_| StateError::DbOpen)?;
+    match meta.get(STATE_TREE_KEY).map_err(|_| StateError::DbIo)? {
+        None => Ok(StateTree::Sorted),
+        Some(raw) => std::str::from_utf8(&raw)
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .ok_or(StateError::DbIo),
+    }
+}
+
+fn open_db(path: &str) -> Result<sled::Db, StateError> {
+    sled::open(path).map_err(|e| match e {
+        // Another handle (usually a running node) holds the database lock. sled wraps the
+        // `WouldBlock` from the lock call in an error of its own.
+        sled::Error::Io(io)
+            if io.kind() == std::io::ErrorKind::WouldBlock
+                || io.to_string().contains("could not acquire lock") =>
+        {
+            StateError::Locked
+        }
+        _ => StateError::DbOpen,
+    })
 }
 
 impl PersistentState {
-    /// Open sled DB at path (directory).
+    /// Open sled DB at path (directory). An encrypted database is unlocked with the secret
+    /// its mode names (see `core::state::encryption`).
     pub fn open(path: &str) -> Result<Self, StateError> {
-        let db = sled::open(path).map_err(|_| StateError::DbOpen)?;
-        Ok(Self { db })
+        Self::open_with(path, StateEncryption::Off)
+    }
+
+    /// `open`, encrypting a plaintext database if `mode` is not `Off`.
+    pub fn open_with(path: &str, mode: StateEncryption) -> Result<Self, StateError> {
+        let db = open_db(path)?;
+        let mode = match (encryption::stored_mode(&db)?, mode) {
+            (Some(stored), StateEncryption::Off) => stored,
+            (_, mode) => mode,
+        };
+        let secret = StateSecret::resolve(mode)?;
+        Self::from_db(db, secret.as_ref())
+    }
+
+    /// `open` with an explicit secret (`None` => plaintext; fails on an encrypted database).
+    pub fn open_encrypted(path: &str, secret: Option<&StateSecret>) -> Result<Self, StateError> {
+        let db = open_db(path)?;
+        Self::from_db(db, secret)
+    }
+
+    fn from_db(db: sled::Db, secret: Option<&StateSecret>) -> Result<Self, StateError> {
+        let cipher = encryption::unlock(&db, secret)?.map(Arc::new);
+        let tree = stored_tree(&db)?;
+        Ok(Self {
+            db,
+            cipher,
+            tree,
+            metrics: None,
+            watchers: Arc::default(),
+        })
+    }
+
+    /// Whether values are encrypted at rest.
+    pub fn is_encrypted(&self) -> bool {
+        self.cipher.is_some()
+    }
+
+    /// The tree the state root is computed with.
+    pub fn state_tree(&self) -> StateTree {
+        self.tree
+    }
+
+    /// Fail unless the database uses the `configured` tree. An empty state tree is converted
+    /// to it instead.
+    pub fn require_state_tree(&mut self, configured: StateTree) -> Result<(), StateError> {
+        if self.tree == configured {
+            return Ok(());
+        }
+        if !self.db.is_empty() {
+            return Err(StateError::TreeMismatch {
+                stored: self.tree,
+                configured,
+            });
+        }
+        self.convert_state_tree(configured)
+    }
+
+    /// Rebuild the state root with tree `to` and record it in the database. Offline only:
+    /// other handles keep the tree they were opened with, and commits during the rebuild
+    /// are lost to it. The state root changes, so the shutdown checkpoint no longer holds.
+    pub fn convert_state_tree(&mut self, to: StateTree) -> Result<(), StateError> {
+        if self.tree == to {
+            return Ok(());
+        }
+        let nodes = self.open_tree(SMT_TREE)?;
+        let meta = self.open_tree(META_TREE)?;
+        // Nodes left by an interrupted conversion; unused while the meta key says `sorted`.
+        nodes.clear().map_err(|_| StateError::DbIo)?;
+        if to == StateTree::Sparse {
+            let built = smt::build(&self.sorted_pairs()?)?;
+            (&nodes, &meta)
+                .transaction(|(n, m)| {
+                    for (pos, node) in &built {
+                        n.insert(pos.as_slice(), node.encode())?;
+                    }
+                    m.insert(STATE_TREE_KEY, to.as_str().as_bytes())?;
+                    Ok(())
+                })
+                .map_err(|_: TransactionError<()>| StateError::DbIo)?;
+        } else {
+            meta.remove(STATE_TREE_KEY).map_err(|_| StateError::DbIo)?;
+        }
+        self.tree = to;
+        Ok(())
+    }
+
+    fn plain(&self, key: &[u8], stored: &[u8]) -> Result<Vec<u8>, StateError> {
+        match &self.cipher {
+            Some(c) => c.open(key, stored),
+            None => Ok(stored.to_vec()),
+        }
+    }
+
+    // Every pair of the state tree, plaintext, sorted by key.
+    fn sorted_pairs(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>, StateError> {
+        let mut pairs: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
+        for item in self.db.iter() {
+            let (k, v) = item.map_err(|_| StateError::DbIo)?;
+            let v = self.plain(&k, &v)?;
+            pairs.push((k.to_vec(), v));
+        }
+        pairs.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(pairs)
+    }
+
+    /// Record commit and state root latencies.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Notify `KeyChange`s of keys matching any of `filters`, buffering up to `capacity`
+    /// of them (see `core::state::watch`). Watches are shared by clones of this handle.
+    pub fn watch(&self, filters: Vec<WatchFilter>, capacity: usize) -> StateWatch {
+        self.watchers.register(filters, capacity)
+    }
+
+    /// Block height reported with the changes of later commits.
+    pub fn set_block_height(&self, height: u64) {
+        self.watchers.set_height(height);
+    }
+
+    /// Open an auxiliary tree in the same database.
+    ///
+    /// Auxiliary trees (commit certificates, indexes) are not part of the state root.
+    pub fn open_tree(&self, name: &str) -> Result<sled::Tree, StateError> {
+        self.db.open_tree(name).map_err(|_| StateError::DbOpen)
     }
 
     /// Get value.
     pub fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, StateError> {
         let v = self.db.get(key).map_err(|_| StateError::DbIo)?;
-        Ok(v.map(|iv| iv.to_vec()))
+        v.map(|iv| self.plain(key, &iv)).transpose()
+    }
+
+    /// All pairs whose key starts with `prefix`, in key order.
+    pub fn scan_prefix(&self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>, StateError> {
+        let mut out = Vec::new();
+        for item in self.db.scan_prefix(prefix) {
+            let (k, v) = item.map_err(|_| StateError::DbIo)?;
+            let v = self.plain(&k, &v)?;
+            out.push((k.to_vec(), v));
+        }
+        Ok(out)
+    }
+
+    /// Up to `limit` (at least one) pairs whose key starts with `prefix` and sorts after
+    /// `start_after`, in key order. Pages are read live: entries committed between two
+    /// calls may show up in, or be missing from, later pages.
+    pub fn scan_prefix_page(
+        &self,
+        prefix: &[u8],
+        start_after: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<StatePage, StateError> {
+        let items = self
+            .db
+            .range::<&[u8], _>((page_start(prefix, start_after), Bound::Unbounded))
+            .map(|item| {
+                let (k, v) = item.map_err(|_| StateError::DbIo)?;
+                Ok((k.to_vec(), self.plain(&k, &v)?))
+            });
+        collect_page(items, prefix, limit)
+    }
+
+    /// `scan_prefix_page`, read from one snapshot of the whole state together with a proof
+    /// of the page boundaries (`None` for an empty page). Walks the database like
+    /// `state_root` of the sorted tree, the only one it supports.
+    pub fn scan_prefix_page_proven(
+        &self,
+        prefix: &[u8],
+        start_after: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<(StatePage, Option<PageProof>), StateError> {
+        if self.tree != StateTree::Sorted {
+            return Err(StateError::UnsupportedTree(self.tree));
+        }
+        let pairs = self.sorted_pairs()?;
+        let start = match page_start(prefix, start_after) {
+            Bound::Excluded(after) => pairs.partition_point(|(k, _)| k.as_slice() <= after),
+            _ => pairs.partition_point(|(k, _)| k.as_slice() < prefix),
+        };
+        let page = collect_page(pairs[start..].iter().cloned().map(Ok), prefix, limit)?;
+        if page.entries.is_empty() {
+            return Ok((page, None));
+        }
+        let end = start + page.entries.len() - 1;
+        let proof = merkle_proof_sorted(&pairs, start)
+            .zip(merkle_proof_sorted(&pairs, end))
+            .map(|(first, last)| PageProof {
+                state_root: merkle_root_sorted(&pairs),
+                first,
+                last,
+            });
+        Ok((page, proof))
     }
 
     /// Atomic commit using sled transactions.
     pub fn commit_atomic(&self, ops: Vec<KvOp>) -> Result<(), StateError> {
-        let tree = &self.db;
+        let _span = tracing::debug_span!("state.commit", ops = ops.len()).entered();
+        let _timer = self
+            .metrics
+            .as_ref()
+            .map(|m| m.state_commit_seconds.start_timer());
+        // Watched keys: their plaintext new value, and (filled in by the transaction) old.
+        let watched: Vec<Option<Option<Vec<u8>>>> = ops
+            .iter()
+            .map(|op| match op {
+                KvOp::Put { key, value } => self.watchers.follows(key).then(|| Some(value.clone())),
+                KvOp::Del { key } => self.watchers.follows(key).then_some(None),
+            })
+            .collect();
+        let old_values: RefCell<Vec<Option<sled::IVec>>> = RefCell::new(Vec::new());
+        // Sparse tree updates, by key path and plaintext value hash.
+        let paths: Vec<_> = match self.tree {
+            StateTree::Sparse => ops
+                .iter()
+                .map(|op| match op {
+                    KvOp::Put { key, value } => (smt::key_path(key), Some(h(value))),
+                    KvOp::Del { key } => (smt::key_path(key), None),
+                })
+                .collect(),
+            StateTree::Sorted => Vec::new(),
+        };
+        let ops = match &self.cipher {
+            Some(c) => ops
+                .into_iter()
+                .map(|op| match op {
+                    KvOp::Put { key, value } => {
+                        let value = c.seal(&key, &value)?;
+                        Ok(KvOp::Put { key, value })
+                    }
+                    del => Ok(del),
+                })
+                .collect::<Result<Vec<_>, StateError>>()?,
+            None => ops,
+        };
+        let write = |t: &TransactionalTree| -> ConflictableTransactionResult<(), StateError> {
+            // A conflicting transaction runs again; keep the old values of the last run.
+            let mut old_values = old_values.borrow_mut();
+            old_values.clear();
+            for op in ops.iter() {
+                let old = match op {
+                    KvOp::Put { key, value } => t.insert(key.as_slice(), value.as_slice()),
+                    KvOp::Del { key } => t.remove(key.as_slice()),
+                }
+                .map_err(|_| ConflictableTransactionError::Abort(StateError::DbIo))?;
+                old_values.push(old);
+            }
+            Ok(())
+        };
+        let tree: &sled::Tree = &self.db;
         let res: Result<(), ConflictableTransactionError<StateError>> = {
-            tree.transaction(|t| {
-                for op in ops.iter() {
-                    match op {
-                        KvOp::Put { key, value } => {
-                            t.insert(key.as_slice(), value.as_slice()).map_err(|_| {
-                                ConflictableTransactionError::Abort(StateError::DbIo)
-                            })?;
+            match self.tree {
+                StateTree::Sorted => tree.transaction(write),
+                StateTree::Sparse => {
+                    let nodes = self.open_tree(SMT_TREE)?;
+                    (tree, &nodes).transaction(|(t, n)| {
+                        write(t)?;
+                        let mut n = n;
+                        for (path, value) in &paths {
+                            smt::update(&mut n, *path, *value)
+                                .map_err(ConflictableTransactionError::Abort)?;
                         }
-                        KvOp::Del { key } => {
-                            t.remove(key.as_slice()).map_err(|_| {
-                                ConflictableTransactionError::Abort(StateError::DbIo)
-                            })?;
-                        }
-                    }
+                        Ok(())
+                    })
                 }
-                Ok(())
-            })
+            }
             .map_err(|e| match e {
                 sled::transaction::TransactionError::Abort(se) => {
                     ConflictableTransactionError::Abort(se)
@@ -91,7 +460,10 @@ impl PersistentState {
         };
 
         match res {
-            Ok(()) => Ok(()),
+            Ok(()) => {
+                self.notify(&ops, watched, old_values.into_inner());
+                Ok(())
+            }
             Err(ConflictableTransactionError::Abort(StateError::TxConflict)) => {
                 Err(StateError::TxConflict)
             }
@@ -101,33 +473,127 @@ impl PersistentState {
         }
     }
 
-    /// Deterministic Merkle root over all KV pairs in DB.
+    fn notify(
+        &self,
+        ops: &[KvOp],
+        watched: Vec<Option<Option<Vec<u8>>>>,
+        old_values: Vec<Option<sled::IVec>>,
+    ) {
+        let changes = ops
+            .iter()
+            .zip(watched)
+            .zip(old_values)
+            .filter_map(|((op, new), old)| {
+                let key = match op {
+                    KvOp::Put { key, .. } | KvOp::Del { key } => key,
+                };
+                // A stored value that no longer decrypts reads as absent.
+                let old = old.and_then(|v| self.plain(key, &v).ok());
+                Some((key.clone(), old, new?))
+            })
+            .collect::<Vec<_>>();
+        if !changes.is_empty() {
+            self.watchers.notify(changes);
+        }
+    }
+
+    /// Flush every tree (state and auxiliary) to disk.
+    pub fn flush(&self) -> Result<(), StateError> {
+        self.db.flush().map(|_| ()).map_err(|_| StateError::DbIo)
+    }
+
+    /// Read every entry of every tree, decrypting state values. sled checks its checksums
+    /// on read, so this fails on the first damaged entry. Returns the number of entries.
+    pub fn verify_readable(&self) -> Result<u64, StateError> {
+        let mut entries = 0u64;
+        for name in self.db.tree_names() {
+            let tree = self.db.open_tree(&name).map_err(|_| StateError::DbOpen)?;
+            let is_state = name.as_ref() == self.db.name().as_ref();
+            for item in tree.iter() {
+                let (k, v) = item.map_err(|_| StateError::DbIo)?;
+                if is_state {
+                    self.plain(&k, &v)?;
+                }
+                entries += 1;
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Bytes the database occupies on disk.
+    pub fn size_on_disk(&self) -> Result<u64, StateError> {
+        self.db.size_on_disk().map_err(|_| StateError::DbIo)
+    }
+
+    /// Deterministic Merkle root over all KV pairs in DB. The sparse tree reads its stored
+    /// root; the sorted tree walks the database.
     pub fn state_root(&self) -> Result<Hash32, StateError> {
-        let mut pairs: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
-        for item in self.db.iter() {
-            let kv = item.map_err(|_| StateError::DbIo)?;
-            pairs.push((kv.0.to_vec(), kv.1.to_vec()));
+        let _timer = self
+            .metrics
+            .as_ref()
+            .map(|m| m.state_root_seconds.start_timer());
+        match self.tree {
+            StateTree::Sorted => Ok(merkle_root_sorted(&self.sorted_pairs()?)),
+            StateTree::Sparse => self.read_sparse(|_, nodes| smt::root(&nodes)),
         }
-        pairs.sort_by(|a, b| a.0.cmp(&b.0));
-        Ok(merkle_root_sorted(&pairs))
     }
 
-    /// Produce an inclusion proof for a key, if it exists.
+    /// The state root recomputed from every pair, ignoring stored sparse tree nodes.
+    pub fn rebuilt_state_root(&self) -> Result<Hash32, StateError> {
+        let pairs = self.sorted_pairs()?;
+        Ok(match self.tree {
+            StateTree::Sorted => merkle_root_sorted(&pairs),
+            StateTree::Sparse => smt::sparse_root(&pairs),
+        })
+    }
+
+    /// The value of `key` (`None`: absent) with its proof against the state root, read
+    /// together. Sparse tree only.
+    pub fn prove_sparse(
+        &self,
+        key: &[u8],
+    ) -> Result<(Option<Vec<u8>>, Hash32, SparseProof), StateError> {
+        if self.tree != StateTree::Sparse {
+            return Err(StateError::UnsupportedTree(self.tree));
+        }
+        let path = smt::key_path(key);
+        self.read_sparse(|t, nodes| {
+            let value = t.get(key).map_err(|_| StateError::DbIo)?;
+            let value = value.map(|v| self.plain(key, &v)).transpose()?;
+            Ok((value, smt::root(&nodes)?, smt::prove(&nodes, &path)?))
+        })
+    }
+
+    // `f` over the state and sparse node trees, in one read transaction.
+    fn read_sparse<T>(
+        &self,
+        f: impl Fn(&TransactionalTree, &TransactionalTree) -> Result<T, StateError>,
+    ) -> Result<T, StateError> {
+        let nodes = self.open_tree(SMT_TREE)?;
+        let tree: &sled::Tree = &self.db;
+        (tree, &nodes)
+            .transaction(|(t, n)| f(t, n).map_err(ConflictableTransactionError::Abort))
+            .map_err(|e| match e {
+                TransactionError::Abort(e) => e,
+                TransactionError::Storage(_) => StateError::DbIo,
+            })
+    }
+
+    /// Produce an inclusion proof for a key, if it exists. Sorted tree only; see
+    /// `prove_sparse`.
     pub fn prove_key(
         &self,
         key: &[u8],
     ) -> Result<Option<(Vec<u8>, Vec<u8>, Hash32, MerkleProof)>, StateError> {
+        if self.tree != StateTree::Sorted {
+            return Err(StateError::UnsupportedTree(self.tree));
+        }
         let v = self.get(key)?;
         let Some(_value) = v else {
             return Ok(None);
         };
 
-        let mut pairs: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
-        for item in self.db.iter() {
-            let kv = item.map_err(|_| StateError::DbIo)?;
-            pairs.push((kv.0.to_vec(), kv.1.to_vec()));
-        }
-        pairs.sort_by(|a, b| a.0.cmp(&b.0));
+        let pairs = self.sorted_pairs()?;
 
         let root = merkle_root_sorted(&pairs);
         let idx = pairs.binary_search_by(|p| p.0.as_slice().cmp(key)).ok();
@@ -0,0 +1,142 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Persistent store of transaction receipts and per-block log blooms.
+//!
+//! Like commits, receipts live outside the state tree: tree `receipts` maps a transaction
+//! hash to its receipt plus block position, and tree `blooms` maps a big-endian height to the
+//! block's log bloom. The bloom is written last, so its presence marks a complete block.
+
+use crate::core::runtime::receipt::{LogsBloom, Receipt};
+use crate::core::state::persistent_state::{PersistentState, StateError};
+use crate::core::types::{decode_canonical_limited, encode_canonical, Height, H256};
+use serde::{Deserialize, Serialize};
+
+const RECEIPTS_TREE: &str = "receipts";
+const BLOOMS_TREE: &str = "blooms";
+
+/// Upper bound for one stored receipt.
+const MAX_STORED_RECEIPT_BYTES: usize = 1024 * 1024;
+
+/// Receipt and where its transaction was included.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredReceipt {
+    pub receipt: Receipt,
+    pub height: Height,
+    pub block_hash: H256,
+    /// Position of the transaction in the block.
+    pub index: u32,
+}
+
+/// Receipt and bloom store.
+#[derive(Clone)]
+pub struct ReceiptStore {
+    receipts: sled::Tree,
+    blooms: sled::Tree,
+}
+
+impl ReceiptStore {
+    /// Open the receipt trees inside an existing state database.
+    pub fn open(state: &PersistentState) -> Result<Self, StateError> {
+        Ok(Self {
+            receipts: state.open_tree(RECEIPTS_TREE)?,
+            blooms: state.open_tree(BLOOMS_TREE)?,
+        })
+    }
+
+    /// Persist a block's receipts (in transaction order) and their bloom.
+    pub fn put_block(
+        &self,
+        height: Height,
+        block_hash: H256,
+        receipts: &[Receipt],
+    ) -> Result<(), StateError> {
+        let mut batch = sled::Batch::default();
+        for (index, receipt) in receipts.iter().enumerate() {
+            let stored = StoredReceipt {
+                receipt: receipt.clone(),
+                height,
+                block_hash,
+                index: u32::try_from(index).map_err(|_| StateError::DbIo)?,
+            };
+            let value = encode_canonical(&stored).map_err(|_| StateError::DbIo)?;
+            batch.insert(receipt.tx_hash.as_bytes().as_slice(), value);
+        }
+        self.receipts
+            .apply_batch(batch)
+            .map_err(|_| StateError::DbIo)?;
+        let bloom =
+            encode_canonical(&LogsBloom::from_receipts(receipts)).map_err(|_| StateError::DbIo)?;
+        self.blooms
+            .insert(height.to_be_bytes(), bloom)
+            .map_err(|_| StateError::DbIo)?;
+        Ok(())
+    }
+
+    /// Receipt of the transaction with hash `tx_hash`.
+    pub fn get(&self, tx_hash: &H256) -> Result<Option<StoredReceipt>, StateError> {
+        let Some(raw) = self
+            .receipts
+            .get(tx_hash.as_bytes())
+            .map_err(|_| StateError::DbIo)?
+        else {
+            return Ok(None);
+        };
+        decode_canonical_limited(&raw, MAX_STORED_RECEIPT_BYTES)
+            .map(Some)
+            .map_err(|_| StateError::DbIo)
+    }
+
+    /// Log bloom of the block at `height`.
+    pub fn bloom(&self, height: Height) -> Result<Option<LogsBloom>, StateError> {
+        let Some(raw) = self
+            .blooms
+            .get(height.to_be_bytes())
+            .map_err(|_| StateError::DbIo)?
+        else {
+            return Ok(None);
+        };
+        decode_canonical_limited(&raw, 1024)
+            .map(Some)
+            .map_err(|_| StateError::DbIo)
+    }
+
+    /// Heights in `from..=to` whose bloom may contain `input` (an address or topic).
+    pub fn blocks_matching(
+        &self,
+        from: Height,
+        to: Height,
+        input: &[u8],
+    ) -> Result<Vec<Height>, StateError> {
+        let mut out = Vec::new();
+        for entry in self.blooms.range(from.to_be_bytes()..=to.to_be_bytes()) {
+            let (k, raw) = entry.map_err(|_| StateError::DbIo)?;
+            let bytes: [u8; 8] = k.as_ref().try_into().map_err(|_| StateError::DbIo)?;
+            let bloom: LogsBloom =
+                decode_canonical_limited(&raw, 1024).map_err(|_| StateError::DbIo)?;
+            if bloom.may_contain(input) {
+                out.push(Height(u64::from_be_bytes(bytes)));
+            }
+        }
+        Ok(out)
+    }
+
+    /// Flush pending writes to disk.
+    pub fn flush(&self) -> Result<(), StateError> {
+        self.receipts.flush().map_err(|_| StateError::DbIo)?;
+        self.blooms
+            .flush()
+            .map(|_| ())
+            .map_err(|_| StateError::DbIo)
+    }
+}
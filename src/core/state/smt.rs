@@ -0,0 +1,474 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Sparse Merkle tree over the state (the `sparse` state tree, see `StateTree`).
+//!
+//! Every key has a 256-bit path, SHA-256(key), walked most significant bit first, 0 to the
+//! left. The tree is compact: an empty subtree hashes to ZERO and a subtree holding a single
+//! pair is that pair's leaf, so n pairs make a tree about log2(n) deep, and an update or a
+//! proof touches that many nodes.
+//!
+//! leaf = `merkle::hash_leaf(key, value)`
+//! node = `merkle::hash_node(left, right)`
+//!
+//! Nodes are stored in the `SMT_TREE` auxiliary tree by position (depth || path prefix);
+//! `PersistentState::commit_atomic` updates them in the transaction that writes the pairs.
+//! Unlike the sorted tree, a `SparseProof` also shows that a key is absent: its path ends in
+//! an empty subtree or in the leaf of another key.
+
+use crate::core::state::merkle::{h, hash_leaf_hashed, hash_node, Hash32, ProofCodecError};
+use crate::core::state::persistent_state::StateError;
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use sled::transaction::TransactionalTree;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Auxiliary tree holding the nodes of the sparse tree.
+pub const SMT_TREE: &str = "__state_smt";
+
+/// Meta tree key naming the state tree of a database; absent for `Sorted`.
+pub(crate) const STATE_TREE_KEY: &[u8] = b"state_tree";
+
+/// Bits of a key path, and the deepest a leaf can sit.
+pub const PATH_BITS: usize = 256;
+
+const PROOF_VERSION: u8 = 1;
+const PROOF_HEADER_LEN: usize = 4;
+const FLAG_LEAF: u8 = 1;
+
+/// Which Merkle tree the state root is computed with. A network picks one for all of its
+/// nodes (`consensus.state_tree`); a database records the tree it was built with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StateTree {
+    /// Balanced tree over the pairs in key order (`merkle`). Rebuilt from every pair for each
+    /// root; inclusion proofs only.
+    #[default]
+    Sorted,
+    /// This module's sparse tree: stored nodes, O(log n) updates, proofs of absence.
+    Sparse,
+}
+
+impl StateTree {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Sorted => "sorted",
+            Self::Sparse => "sparse",
+        }
+    }
+}
+
+impl fmt::Display for StateTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for StateTree {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "sorted" => Ok(Self::Sorted),
+            "sparse" => Ok(Self::Sparse),
+            _ => Err(format!("unknown state tree `{s}` (sorted, sparse)")),
+        }
+    }
+}
+
+/// Path of `key` in the sparse tree.
+pub fn key_path(key: &[u8]) -> Hash32 {
+    h(key)
+}
+
+/// A stored node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Node {
+    /// A pair, by the hashes of its key (= its path) and value.
+    Leaf { path: Hash32, value: Hash32 },
+    /// A subtree of at least two pairs, by its hash.
+    Branch(Hash32),
+}
+
+impl Node {
+    fn hash(&self) -> Hash32 {
+        match self {
+            Self::Leaf { path, value } => hash_leaf_hashed(*path, *value),
+            Self::Branch(hash) => *hash,
+        }
+    }
+
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        match self {
+            Self::Leaf { path, value } => [&[0][..], path, value].concat(),
+            Self::Branch(hash) => [&[1][..], hash].concat(),
+        }
+    }
+
+    fn decode(raw: &[u8]) -> Result<Self, StateError> {
+        let hash = |b: &[u8]| Hash32::try_from(b).map_err(|_| StateError::DbIo);
+        match raw {
+            [0, rest @ ..] if rest.len() == 64 => Ok(Self::Leaf {
+                path: hash(&rest[..32])?,
+                value: hash(&rest[32..])?,
+            }),
+            [1, rest @ ..] => Ok(Self::Branch(hash(rest)?)),
+            _ => Err(StateError::DbIo),
+        }
+    }
+}
+
+/// Where the nodes of a tree live: the `SMT_TREE` inside a transaction, or memory.
+pub(crate) trait NodeStore {
+    fn node(&self, pos: &[u8]) -> Result<Option<Node>, StateError>;
+    fn set(&mut self, pos: Vec<u8>, node: Option<Node>) -> Result<(), StateError>;
+}
+
+impl NodeStore for &TransactionalTree {
+    fn node(&self, pos: &[u8]) -> Result<Option<Node>, StateError> {
+        let raw = self.get(pos).map_err(|_| StateError::DbIo)?;
+        raw.map(|raw| Node::decode(&raw)).transpose()
+    }
+
+    fn set(&mut self, pos: Vec<u8>, node: Option<Node>) -> Result<(), StateError> {
+        match node {
+            Some(node) => self.insert(pos, node.encode()),
+            None => self.remove(pos),
+        }
+        .map(|_| ())
+        .map_err(|_| StateError::DbIo)
+    }
+}
+
+impl NodeStore for BTreeMap<Vec<u8>, Node> {
+    fn node(&self, pos: &[u8]) -> Result<Option<Node>, StateError> {
+        Ok(self.get(pos).copied())
+    }
+
+    fn set(&mut self, pos: Vec<u8>, node: Option<Node>) -> Result<(), StateError> {
+        match node {
+            Some(node) => self.insert(pos, node),
+            None => self.remove(&pos),
+        };
+        Ok(())
+    }
+}
+
+fn bit(path: &Hash32, i: usize) -> bool {
+    path[i / 8] >> (7 - i % 8) & 1 == 1
+}
+
+// `path` with bit `i` flipped: on the path to the sibling of the subtree at depth `i + 1`.
+fn flip(path: &Hash32, i: usize) -> Hash32 {
+    let mut out = *path;
+    out[i / 8] ^= 1 << (7 - i % 8);
+    out
+}
+
+// Storage key of the subtree at `depth` on `path`: depth (u16, big endian) || the first
+// `depth` bits of `path`, zero padded.
+fn pos(path: &Hash32, depth: usize) -> Vec<u8> {
+    let mut prefix = [0u8; 32];
+    let full = depth / 8;
+    prefix[..full].copy_from_slice(&path[..full]);
+    if !depth.is_multiple_of(8) {
+        prefix[full] = path[full] & (0xff << (8 - depth % 8));
+    }
+    [&(depth as u16).to_be_bytes()[..], &prefix].concat()
+}
+
+fn hash_at(s: &impl NodeStore, pos: &[u8]) -> Result<Hash32, StateError> {
+    Ok(s.node(pos)?.map_or([0u8; 32], |n| n.hash()))
+}
+
+/// Root hash of the tree in `s`; ZERO when empty.
+pub(crate) fn root(s: &impl NodeStore) -> Result<Hash32, StateError> {
+    hash_at(s, &pos(&[0u8; 32], 0))
+}
+
+// Depth of the first node on `path` that is not a branch, and that node.
+fn descend(s: &impl NodeStore, path: &Hash32) -> Result<(usize, Option<Node>), StateError> {
+    for depth in 0..=PATH_BITS {
+        match s.node(&pos(path, depth))? {
+            Some(Node::Branch(_)) => continue,
+            node => return Ok((depth, node)),
+        }
+    }
+    // A branch at the deepest level has nothing below it.
+    Err(StateError::DbIo)
+}
+
+/// Set the value hash at `path`, or remove the pair (`None`).
+pub(crate) fn update(
+    s: &mut impl NodeStore,
+    path: Hash32,
+    value: Option<Hash32>,
+) -> Result<(), StateError> {
+    let (depth, found) = descend(s, &path)?;
+    let new = value.map(|value| Node::Leaf { path, value });
+    let rehash_from = match (found, new) {
+        (None, None) => return Ok(()),
+        (None, Some(leaf)) => {
+            s.set(pos(&path, depth), Some(leaf))?;
+            depth
+        }
+        (Some(old @ Node::Leaf { path: p, .. }), new) if p == path => {
+            if new == Some(old) {
+                return Ok(());
+            }
+            s.set(pos(&path, depth), new)?;
+            if new.is_some() {
+                depth
+            } else {
+                collapse(s, &path, depth)?
+            }
+        }
+        (Some(Node::Leaf { .. }), None) => return Ok(()),
+        (Some(other @ Node::Leaf { path: p, .. }), Some(leaf)) => {
+            // Both leaves move below the first bit where their paths differ; the branches
+            // above them are written by the rehash.
+            let split = (depth..PATH_BITS)
+                .find(|&i| bit(&p, i) != bit(&path, i))
+                .ok_or(StateError::DbIo)?;
+            s.set(pos(&p, split + 1), Some(other))?;
+            s.set(pos(&path, split + 1), Some(leaf))?;
+            split + 1
+        }
+        (Some(Node::Branch(_)), _) => return Err(StateError::DbIo),
+    };
+    rehash(s, &path, rehash_from)
+}
+
+// After the leaf at `depth` on `path` was removed, move a leaf left alone in its subtree up
+// to where that subtree now starts, as if the removed pair had never been there. Returns the
+// depth the branches above need rehashing from.
+fn collapse(s: &mut impl NodeStore, path: &Hash32, mut depth: usize) -> Result<usize, StateError> {
+    while depth > 0 {
+        let here = pos(path, depth);
+        let sibling = pos(&flip(path, depth - 1), depth);
+        let lifted = match (s.node(&here)?, s.node(&sibling)?) {
+            (cur @ (None | Some(Node::Leaf { .. })), None) => {
+                s.set(here, None)?;
+                cur
+            }
+            (None, Some(leaf @ Node::Leaf { .. })) => {
+                s.set(sibling, None)?;
+                Some(leaf)
+            }
+            _ => break,
+        };
+        depth -= 1;
+        s.set(pos(path, depth), lifted)?;
+    }
+    Ok(depth)
+}
+
+// Recompute the branches on `path` above `depth`, bottom up.
+fn rehash(s: &mut impl NodeStore, path: &Hash32, depth: usize) -> Result<(), StateError> {
+    for d in (0..depth).rev() {
+        let (left, right) = if bit(path, d) {
+            (pos(&flip(path, d), d + 1), pos(path, d + 1))
+        } else {
+            (pos(path, d + 1), pos(&flip(path, d), d + 1))
+        };
+        let hash = hash_node(hash_at(s, &left)?, hash_at(s, &right)?);
+        s.set(pos(path, d), Some(Node::Branch(hash)))?;
+    }
+    Ok(())
+}
+
+/// Proof for `path` against `root(s)`.
+pub(crate) fn prove(s: &impl NodeStore, path: &Hash32) -> Result<SparseProof, StateError> {
+    let (depth, node) = descend(s, path)?;
+    let siblings = (0..depth)
+        .map(|d| hash_at(s, &pos(&flip(path, d), d + 1)))
+        .collect::<Result<_, _>>()?;
+    let leaf = match node {
+        Some(Node::Leaf { path, value }) => Some(SparseLeaf {
+            key_hash: path,
+            value_hash: value,
+        }),
+        _ => None,
+    };
+    Ok(SparseProof { siblings, leaf })
+}
+
+/// The nodes of a tree over `pairs`, by storage key.
+pub(crate) fn build(pairs: &[(Vec<u8>, Vec<u8>)]) -> Result<BTreeMap<Vec<u8>, Node>, StateError> {
+    let mut nodes = BTreeMap::new();
+    for (k, v) in pairs {
+        update(&mut nodes, key_path(k), Some(h(v)))?;
+    }
+    Ok(nodes)
+}
+
+/// Sparse root over `pairs` (in any order, keys unique), computed in memory.
+pub fn sparse_root(pairs: &[(Vec<u8>, Vec<u8>)]) -> Hash32 {
+    build(pairs)
+        .and_then(|nodes| root(&nodes))
+        .unwrap_or([0u8; 32])
+}
+
+/// The leaf a `SparseProof` ends in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SparseLeaf {
+    pub key_hash: Hash32,
+    pub value_hash: Hash32,
+}
+
+/// Proof that a key holds a value, or is absent, in the sparse tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseProof {
+    /// Sibling hashes from the root down; ZERO for empty subtrees.
+    pub siblings: Vec<Hash32>,
+    /// The leaf at the end of the path: the key's own, another key's (absent) or none
+    /// (absent, the path ends in an empty subtree).
+    pub leaf: Option<SparseLeaf>,
+}
+
+impl SparseProof {
+    /// Whether the proof shows `key` holding `value` (`None`: absent) under `root`.
+    pub fn verify(&self, root: Hash32, key: &[u8], value: Option<&[u8]>) -> bool {
+        let path = key_path(key);
+        let depth = self.siblings.len();
+        if depth > PATH_BITS {
+            return false;
+        }
+        let mut node = match (&self.leaf, value) {
+            (None, None) => [0u8; 32],
+            (Some(l), Some(v)) if l.key_hash == path && l.value_hash == h(v) => {
+                hash_leaf_hashed(l.key_hash, l.value_hash)
+            }
+            // Another key's leaf can only sit where the two paths still agree.
+            (Some(l), None)
+                if l.key_hash != path
+                    && (0..depth).all(|i| bit(&l.key_hash, i) == bit(&path, i)) =>
+            {
+                hash_leaf_hashed(l.key_hash, l.value_hash)
+            }
+            _ => return false,
+        };
+        for (d, sibling) in self.siblings.iter().enumerate().rev() {
+            node = if bit(&path, d) {
+                hash_node(*sibling, node)
+            } else {
+                hash_node(node, *sibling)
+            };
+        }
+        node == root
+    }
+
+    /// Length of `to_bytes` for a proof of `depth` siblings.
+    pub const fn encoded_len(depth: usize, leaf: bool) -> usize {
+        PROOF_HEADER_LEN + if leaf { 64 } else { 0 } + depth * 32
+    }
+
+    /// Canonical encoding: version (1) || flags (1) || depth (2, big endian) || leaf key hash
+    /// and value hash (when flag bit 0 is set) || siblings, root first.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProofCodecError> {
+        let depth = self.siblings.len();
+        if depth > PATH_BITS {
+            return Err(ProofCodecError::TooDeep(depth));
+        }
+        let mut out = Vec::with_capacity(Self::encoded_len(depth, self.leaf.is_some()));
+        out.extend_from_slice(&[PROOF_VERSION, self.leaf.map_or(0, |_| FLAG_LEAF)]);
+        out.extend_from_slice(&(depth as u16).to_be_bytes());
+        if let Some(leaf) = &self.leaf {
+            out.extend_from_slice(&leaf.key_hash);
+            out.extend_from_slice(&leaf.value_hash);
+        }
+        for sibling in &self.siblings {
+            out.extend_from_slice(sibling);
+        }
+        Ok(out)
+    }
+
+    /// Decode `to_bytes` output. Rejects anything but the canonical encoding.
+    pub fn from_bytes(raw: &[u8]) -> Result<Self, ProofCodecError> {
+        let (&[version, flags, d0, d1], rest) = raw
+            .split_first_chunk::<PROOF_HEADER_LEN>()
+            .ok_or(ProofCodecError::Truncated)?;
+        if version != PROOF_VERSION {
+            return Err(ProofCodecError::Version(version));
+        }
+        if flags & !FLAG_LEAF != 0 {
+            return Err(ProofCodecError::Flags(flags));
+        }
+        let depth = usize::from(u16::from_be_bytes([d0, d1]));
+        if depth > PATH_BITS {
+            return Err(ProofCodecError::TooDeep(depth));
+        }
+        let has_leaf = flags & FLAG_LEAF != 0;
+        let expected = Self::encoded_len(depth, has_leaf);
+        if raw.len() != expected {
+            return Err(ProofCodecError::Length {
+                expected,
+                actual: raw.len(),
+            });
+        }
+        let hash = |b: &[u8]| Hash32::try_from(b).unwrap_or_default();
+        let (leaf, siblings) = rest.split_at(if has_leaf { 64 } else { 0 });
+        Ok(SparseProof {
+            siblings: siblings.chunks_exact(32).map(hash).collect(),
+            leaf: has_leaf.then(|| SparseLeaf {
+                key_hash: hash(&leaf[..32]),
+                value_hash: hash(&leaf[32..]),
+            }),
+        })
+    }
+}
+
+impl Serialize for SparseProof {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let raw = self.to_bytes().map_err(serde::ser::Error::custom)?;
+        if s.is_human_readable() {
+            s.serialize_str(&hex::encode(raw))
+        } else {
+            s.serialize_bytes(&raw)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SparseProof {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        struct ProofVisitor;
+
+        impl Visitor<'_> for ProofVisitor {
+            type Value = SparseProof;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("an encoded sparse Merkle proof")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<SparseProof, E> {
+                if v.len() > 2 * SparseProof::encoded_len(PATH_BITS, true) {
+                    return Err(E::invalid_length(v.len(), &self));
+                }
+                let raw = hex::decode(v).map_err(E::custom)?;
+                SparseProof::from_bytes(&raw).map_err(E::custom)
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<SparseProof, E> {
+                SparseProof::from_bytes(v).map_err(E::custom)
+            }
+        }
+
+        if d.is_human_readable() {
+            d.deserialize_str(ProofVisitor)
+        } else {
+            d.deserialize_bytes(ProofVisitor)
+        }
+    }
+}
@@ -0,0 +1,108 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Differential testing harness for state roots (`testing` feature).
+//!
+//! Any root that is maintained across writes instead of rebuilt from scratch must agree with
+//! `merkle_root_sorted` over the full key set after every batch. `check_against_rebuild`
+//! drives an implementation under test with random batches of puts and deletes and compares
+//! it to a `BTreeMap` model rebuilt each time.
+//!
+//! Keys are drawn from a small space so deletes and overwrites hit existing entries.
+
+use crate::core::state::merkle::{merkle_root_sorted, Hash32};
+use crate::core::state::persistent_state::KvOp;
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+use std::collections::BTreeMap;
+
+/// Random put or delete over a key space of `key_space` single-prefix keys.
+pub fn arb_op(key_space: u8) -> impl Strategy<Value = KvOp> {
+    let key = (
+        0..key_space.max(1),
+        prop::collection::vec(any::<u8>(), 0..3),
+    )
+        .prop_map(|(k, suffix)| {
+            let mut key = vec![k];
+            key.extend(suffix);
+            key
+        });
+    prop_oneof![
+        3 => (key.clone(), prop::collection::vec(any::<u8>(), 0..32))
+            .prop_map(|(key, value)| KvOp::Put { key, value }),
+        1 => key.prop_map(|key| KvOp::Del { key }),
+    ]
+}
+
+/// Up to `max_batches` batches of up to `max_ops` operations each.
+pub fn arb_batches(max_batches: usize, max_ops: usize) -> impl Strategy<Value = Vec<Vec<KvOp>>> {
+    prop::collection::vec(
+        prop::collection::vec(arb_op(32), 0..=max_ops),
+        1..=max_batches,
+    )
+}
+
+/// Reference model: key/value map rebuilt into a root with the full sorted-pairs tree.
+#[derive(Clone, Debug, Default)]
+pub struct RebuildModel {
+    kv: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl RebuildModel {
+    /// Apply a batch in order (later ops win).
+    pub fn apply(&mut self, ops: &[KvOp]) {
+        for op in ops {
+            match op {
+                KvOp::Put { key, value } => {
+                    self.kv.insert(key.clone(), value.clone());
+                }
+                KvOp::Del { key } => {
+                    self.kv.remove(key);
+                }
+            }
+        }
+    }
+
+    /// Root over the current key set, rebuilt from scratch.
+    pub fn root(&self) -> Hash32 {
+        let pairs: Vec<(Vec<u8>, Vec<u8>)> = self
+            .kv
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        merkle_root_sorted(&pairs)
+    }
+}
+
+/// Feed every batch to `apply_and_root` (which applies it to the implementation under test
+/// and returns its root) and fail on the first batch where it differs from a full rebuild.
+pub fn check_against_rebuild<F>(
+    batches: &[Vec<KvOp>],
+    mut apply_and_root: F,
+) -> Result<(), TestCaseError>
+where
+    F: FnMut(&[KvOp]) -> Hash32,
+{
+    let mut model = RebuildModel::default();
+    for (i, batch) in batches.iter().enumerate() {
+        model.apply(batch);
+        let got = apply_and_root(batch);
+        prop_assert_eq!(
+            got,
+            model.root(),
+            "root diverged from full rebuild after batch {}",
+            i
+        );
+    }
+    Ok(())
+}
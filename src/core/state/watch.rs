@@ -0,0 +1,166 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Key change notifications.
+//!
+//! `PersistentState::watch` registers interest in keys and key prefixes. Once a
+//! `commit_atomic` has landed, every watch whose filters match a changed key receives a
+//! `KeyChange` for it, in commit order. Writes that leave a value as it was are not changes.
+//!
+//! Delivery never blocks a commit: each watch buffers up to its capacity, and changes that
+//! do not fit are dropped and counted. The next receive reports the count
+//! (`WatchError::Lagged`); the changes still buffered follow it. A dropped `StateWatch` is
+//! unregistered on the next commit.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+/// Changes buffered per watch when the caller does not choose.
+pub const DEFAULT_WATCH_CAPACITY: usize = 1024;
+
+/// Keys a watch follows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WatchFilter {
+    /// Exactly this key.
+    Key(Vec<u8>),
+    /// Every key starting with this prefix (empty: every key).
+    Prefix(Vec<u8>),
+}
+
+impl WatchFilter {
+    pub fn matches(&self, key: &[u8]) -> bool {
+        match self {
+            WatchFilter::Key(k) => k == key,
+            WatchFilter::Prefix(p) => key.starts_with(p),
+        }
+    }
+}
+
+/// One key changed by a commit. Values are plaintext; `None` means absent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyChange {
+    pub key: Vec<u8>,
+    pub old: Option<Vec<u8>>,
+    pub new: Option<Vec<u8>>,
+    /// Block height of the commit (`PersistentState::set_block_height`).
+    pub height: u64,
+}
+
+/// Why no change was received.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum WatchError {
+    #[error("watch fell behind; {0} changes dropped")]
+    Lagged(u64),
+    #[error("no change pending")]
+    Empty,
+    #[error("state closed")]
+    Closed,
+}
+
+/// Receiving side of a watch. Dropping it unregisters the watch.
+#[derive(Debug)]
+pub struct StateWatch {
+    rx: mpsc::Receiver<KeyChange>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl StateWatch {
+    /// Next change; waits for one.
+    pub async fn recv(&mut self) -> Result<KeyChange, WatchError> {
+        self.take_lagged()?;
+        self.rx.recv().await.ok_or(WatchError::Closed)
+    }
+
+    /// Next change if one is pending.
+    pub fn try_recv(&mut self) -> Result<KeyChange, WatchError> {
+        self.take_lagged()?;
+        self.rx.try_recv().map_err(|e| match e {
+            mpsc::error::TryRecvError::Empty => WatchError::Empty,
+            mpsc::error::TryRecvError::Disconnected => WatchError::Closed,
+        })
+    }
+
+    fn take_lagged(&self) -> Result<(), WatchError> {
+        match self.dropped.swap(0, Ordering::Relaxed) {
+            0 => Ok(()),
+            n => Err(WatchError::Lagged(n)),
+        }
+    }
+}
+
+struct Watcher {
+    filters: Vec<WatchFilter>,
+    tx: mpsc::Sender<KeyChange>,
+    dropped: Arc<AtomicU64>,
+}
+
+/// Registered watches of one database, shared by every `PersistentState` handle.
+#[derive(Default)]
+pub(crate) struct Watchers {
+    watchers: Mutex<Vec<Watcher>>,
+    height: AtomicU64,
+}
+
+impl Watchers {
+    pub(crate) fn register(&self, filters: Vec<WatchFilter>, capacity: usize) -> StateWatch {
+        let (tx, rx) = mpsc::channel(capacity.max(1));
+        let dropped = Arc::new(AtomicU64::new(0));
+        if let Ok(mut w) = self.watchers.lock() {
+            w.push(Watcher {
+                filters,
+                tx,
+                dropped: dropped.clone(),
+            });
+        }
+        StateWatch { rx, dropped }
+    }
+
+    pub(crate) fn set_height(&self, height: u64) {
+        self.height.store(height, Ordering::Relaxed);
+    }
+
+    /// Whether any open watch follows `key`.
+    pub(crate) fn follows(&self, key: &[u8]) -> bool {
+        self.watchers.lock().is_ok_and(|w| {
+            w.iter()
+                .any(|w| !w.tx.is_closed() && w.filters.iter().any(|f| f.matches(key)))
+        })
+    }
+
+    /// Deliver `(key, old, new)` of a landed commit.
+    pub(crate) fn notify(&self, changes: Vec<(Vec<u8>, Option<Vec<u8>>, Option<Vec<u8>>)>) {
+        let Ok(mut watchers) = self.watchers.lock() else {
+            return;
+        };
+        watchers.retain(|w| !w.tx.is_closed());
+        let height = self.height.load(Ordering::Relaxed);
+        for (key, old, new) in changes.into_iter().filter(|(_, old, new)| old != new) {
+            for w in watchers
+                .iter()
+                .filter(|w| w.filters.iter().any(|f| f.matches(&key)))
+            {
+                let change = KeyChange {
+                    key: key.clone(),
+                    old: old.clone(),
+                    new: new.clone(),
+                    height,
+                };
+                if w.tx.try_send(change).is_err() {
+                    w.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+}
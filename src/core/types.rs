@@ -13,6 +13,16 @@
 
 //! Deterministic core types and canonical encoding helpers.
 
+use crate::core::consensus::quorum::QuorumConfig;
+use crate::core::security::sign_policy::SignDomain;
+use crate::core::state::encryption::StateEncryption;
+use crate::core::state::smt::StateTree;
+use crate::monitoring::health::ReadinessCriteria;
+use crate::networking::gossip_tuning::GossipTuning;
+use crate::networking::p2p_identity::IdentitySource;
+use crate::networking::wire::WireCodec;
+use crate::networking::wire_limits::WireLimits;
+use crate::node::role::NodeRole;
 use bincode::Options;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -44,6 +54,24 @@ pub fn encode_canonical<T: Serialize>(v: &T) -> Result<Vec<u8>, CodecError> {
         .map_err(|_| CodecError::Serialize)
 }
 
+/// Canonical JSON for tooling that needs a readable encoding: sorted keys, integer-only
+/// numbers, no whitespace (rules in `core::canonical_json`). Floats are rejected.
+pub fn encode_canonical_json<T: Serialize>(v: &T) -> Result<Vec<u8>, CodecError> {
+    crate::core::canonical_json::encode(v).map_err(|_| CodecError::Serialize)
+}
+
+/// Decode canonical JSON with a hard size cap. Input that is valid JSON but not the exact
+/// bytes `encode_canonical_json` would produce for the result is rejected.
+pub fn decode_canonical_json<T: Serialize + DeserializeOwned>(
+    bytes: &[u8],
+    max: usize,
+) -> Result<T, CodecError> {
+    if bytes.len() > max {
+        return Err(CodecError::TooLarge);
+    }
+    crate::core::canonical_json::decode(bytes).map_err(|_| CodecError::Deserialize)
+}
+
 /// Decode with a hard size cap.
 pub fn decode_canonical_limited<T: DeserializeOwned>(
     bytes: &[u8],
@@ -62,10 +90,15 @@ pub fn decode_canonical_limited<T: DeserializeOwned>(
 }
 
 /// 256-bit hash type (32 bytes).
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
 pub struct H256([u8; 32]);
 
 impl H256 {
+    /// All-zero hash.
+    pub const ZERO: Self = Self([0; 32]);
+
     /// Construct from raw bytes.
     pub fn from_bytes(b: [u8; 32]) -> Self {
         Self(b)
@@ -76,26 +109,226 @@ impl H256 {
     }
 }
 
-/// Ed25519 signature bytes (expected 64).
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
-pub struct Signature(pub Vec<u8>);
+macro_rules! consensus_counter {
+    ($(#[$doc:meta])* $name:ident) => {
+        $(#[$doc])*
+        ///
+        /// Serializes as the bare `u64`, so wire formats and signing payloads are unchanged.
+        /// There are no arithmetic operators: steps go through the checked/saturating methods.
+        #[derive(
+            Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+        )]
+        #[serde(transparent)]
+        pub struct $name(pub u64);
+
+        impl $name {
+            pub const ZERO: Self = Self(0);
+            pub const MAX: Self = Self(u64::MAX);
+
+            /// The raw value.
+            pub const fn get(self) -> u64 {
+                self.0
+            }
+
+            /// True for 0 (legacy or genesis, depending on the type).
+            pub const fn is_zero(self) -> bool {
+                self.0 == 0
+            }
 
-/// Validator identity (Ed25519 public key bytes, expected 32).
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
-pub struct ValidatorId(pub Vec<u8>);
+            /// The following value; `None` at `MAX`.
+            pub fn checked_next(self) -> Option<Self> {
+                self.0.checked_add(1).map(Self)
+            }
 
-impl ValidatorId {
-    /// Interpret as Ed25519 public key bytes if length is 32.
-    pub fn as_public_key_bytes(&self) -> Option<[u8; 32]> {
-        if self.0.len() != 32 {
-            return None;
+            /// The preceding value; `None` at `ZERO`.
+            pub fn checked_prev(self) -> Option<Self> {
+                self.0.checked_sub(1).map(Self)
+            }
+
+            /// `n` steps later; `None` on overflow.
+            pub fn checked_add(self, n: u64) -> Option<Self> {
+                self.0.checked_add(n).map(Self)
+            }
+
+            /// `n` steps later, clamped at `MAX`.
+            pub fn saturating_add(self, n: u64) -> Self {
+                Self(self.0.saturating_add(n))
+            }
+
+            /// `n` steps earlier, clamped at `ZERO`.
+            pub fn saturating_sub(self, n: u64) -> Self {
+                Self(self.0.saturating_sub(n))
+            }
+
+            /// Steps from `earlier` to `self`; `None` if `earlier` is in fact later.
+            pub fn since(self, earlier: Self) -> Option<u64> {
+                self.0.checked_sub(earlier.0)
+            }
+
+            /// Big-endian bytes, as used in signing payloads.
+            pub const fn to_be_bytes(self) -> [u8; 8] {
+                self.0.to_be_bytes()
+            }
         }
-        let mut out = [0u8; 32];
-        out.copy_from_slice(&self.0);
-        Some(out)
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl From<u64> for $name {
+            fn from(v: u64) -> Self {
+                Self(v)
+            }
+        }
+
+        impl From<$name> for u64 {
+            fn from(v: $name) -> u64 {
+                v.0
+            }
+        }
+    };
+}
+
+consensus_counter!(
+    /// Block height (finalized heights start at 1).
+    Height
+);
+consensus_counter!(
+    /// Consensus round within a height.
+    Round
+);
+consensus_counter!(
+    /// Validator-set epoch (0 marks legacy messages without replay fields).
+    Epoch
+);
+
+/// A byte string of the wrong length for a fixed-size type.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("expected {expected} bytes, got {got}")]
+pub struct LengthError {
+    pub expected: usize,
+    pub got: usize,
+}
+
+macro_rules! fixed_bytes {
+    ($(#[$doc:meta])* $name:ident, $len:expr) => {
+        $(#[$doc])*
+        ///
+        /// Encodes like the `Vec<u8>` it replaced (length prefix, then the bytes), so wire
+        /// formats are unchanged; decoding rejects any other length without buffering it.
+        #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name([u8; $len]);
+
+        impl $name {
+            pub const LEN: usize = $len;
+
+            /// Wrap exactly `LEN` bytes.
+            pub const fn from_bytes(b: [u8; $len]) -> Self {
+                Self(b)
+            }
+
+            /// Copy from a slice; fails unless it is exactly `LEN` bytes.
+            pub fn from_slice(b: &[u8]) -> Result<Self, LengthError> {
+                b.try_into().map(Self).map_err(|_| LengthError {
+                    expected: $len,
+                    got: b.len(),
+                })
+            }
+
+            /// Return bytes.
+            pub const fn as_bytes(&self) -> &[u8; $len] {
+                &self.0
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl TryFrom<&[u8]> for $name {
+            type Error = LengthError;
+
+            fn try_from(b: &[u8]) -> Result<Self, LengthError> {
+                Self::from_slice(b)
+            }
+        }
+
+        impl TryFrom<Vec<u8>> for $name {
+            type Error = LengthError;
+
+            fn try_from(b: Vec<u8>) -> Result<Self, LengthError> {
+                Self::from_slice(&b)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                self.0[..].serialize(s)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                d.deserialize_seq(FixedBytesVisitor::<$len>).map(Self)
+            }
+        }
+    };
+}
+
+struct FixedBytesVisitor<const N: usize>;
+
+impl<'de, const N: usize> serde::de::Visitor<'de> for FixedBytesVisitor<N> {
+    type Value = [u8; N];
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{N} bytes")
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<[u8; N], A::Error> {
+        use serde::de::Error as _;
+        if seq.size_hint().is_some_and(|n| n != N) {
+            return Err(A::Error::invalid_length(
+                seq.size_hint().unwrap_or(0),
+                &self,
+            ));
+        }
+        let mut out = [0u8; N];
+        for (i, b) in out.iter_mut().enumerate() {
+            *b = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(A::Error::invalid_length(N + 1, &self));
+        }
+        Ok(out)
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, b: &[u8]) -> Result<[u8; N], E> {
+        b.try_into().map_err(|_| E::invalid_length(b.len(), &self))
     }
 }
 
+fixed_bytes!(
+    /// Ed25519 signature bytes.
+    Signature,
+    64
+);
+fixed_bytes!(
+    /// Validator identity (Ed25519 public key bytes).
+    ValidatorId,
+    32
+);
+fixed_bytes!(
+    /// Account key (Ed25519 public key bytes); its bytes are the bank account.
+    AccountKey,
+    32
+);
+
 /// Canonical map type alias.
 pub type CanonicalMap<K, V> = BTreeMap<K, V>;
 
@@ -103,12 +336,12 @@ pub type CanonicalMap<K, V> = BTreeMap<K, V>;
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Vote {
     /// Block height.
-    pub height: u64,
+    pub height: Height,
     /// Consensus round (height-bound).
-    pub round: u64,
+    pub round: Round,
     /// Epoch identifier (0 => legacy messages).
     #[serde(default)]
-    pub epoch: u64,
+    pub epoch: Epoch,
     /// Per-sender monotonically increasing message counter (0 => legacy).
     #[serde(default)]
     pub msg_counter: u64,
@@ -130,12 +363,12 @@ pub struct Vote {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Commit {
     /// Height.
-    pub height: u64,
+    pub height: Height,
     /// Round.
-    pub round: u64,
+    pub round: Round,
     /// Epoch identifier (0 => legacy messages).
     #[serde(default)]
-    pub epoch: u64,
+    pub epoch: Epoch,
     /// Per-sender monotonically increasing message counter (0 => legacy).
     #[serde(default)]
     pub msg_counter: u64,
@@ -149,6 +382,13 @@ pub struct Commit {
     pub block_hash: H256,
     /// Signatures by validators (canonical ordering by key).
     pub signatures: CanonicalMap<ValidatorId, Signature>,
+    /// Voting power the signers represent (signer count in count mode; 0 => legacy).
+    #[serde(default)]
+    pub voting_power: u128,
+    /// Hash of the validator set (with powers) the signatures were made for
+    /// (`signing::validator_set_hash_weighted`; zero => legacy, unbound).
+    #[serde(default)]
+    pub validator_set_hash: H256,
 }
 
 /// Wire-level consensus messages.
@@ -160,6 +400,234 @@ pub enum ConsensusMsg {
     Commit(Commit),
 }
 
+/// Two conflicting votes by one validator at the same height and round: different blocks,
+/// or the same block under different replay fields (what Tide reports as a double vote).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DoubleVoteEvidence {
+    /// The vote whose canonical encoding sorts first.
+    pub first: Vote,
+    /// The other vote.
+    pub second: Vote,
+}
+
+impl DoubleVoteEvidence {
+    /// Evidence from two votes in either order; `None` unless they conflict. The votes are
+    /// ordered by their canonical encoding, so every node builds the same evidence.
+    pub fn new(a: Vote, b: Vote) -> Option<Self> {
+        let (ka, kb) = (encode_canonical(&a).ok()?, encode_canonical(&b).ok()?);
+        let (first, second) = if ka <= kb { (a, b) } else { (b, a) };
+        let ev = Self { first, second };
+        ev.is_conflicting().then_some(ev)
+    }
+
+    /// Same voter, height and round, and not the same vote. Signatures are not checked.
+    pub fn is_conflicting(&self) -> bool {
+        let (a, b) = (&self.first, &self.second);
+        a.voter == b.voter
+            && a.height == b.height
+            && a.round == b.round
+            && (a.block_hash != b.block_hash
+                || (a.epoch, a.msg_counter, a.sent_ts_ms, a.ttl_ms)
+                    != (b.epoch, b.msg_counter, b.sent_ts_ms, b.ttl_ms))
+    }
+}
+
+/// Proof of a validator's misbehaviour, gossiped on the evidence topic so every node can
+/// record the offense, not only the ones that saw it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Evidence {
+    /// Conflicting votes.
+    DoubleVote(DoubleVoteEvidence),
+}
+
+impl Evidence {
+    /// Validator the evidence is against.
+    pub fn offender(&self) -> &ValidatorId {
+        match self {
+            Evidence::DoubleVote(ev) => &ev.first.voter,
+        }
+    }
+
+    /// Height and round of the offense.
+    pub fn position(&self) -> (Height, Round) {
+        match self {
+            Evidence::DoubleVote(ev) => (ev.first.height, ev.first.round),
+        }
+    }
+}
+
+/// Hydro block header.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockHeader {
+    /// Block height (genesis is 0).
+    pub height: Height,
+    /// Hash of the parent header.
+    pub parent_hash: H256,
+    /// Hydro slot the block was produced in.
+    pub slot: u64,
+    /// Producer wall-clock timestamp in milliseconds since UNIX epoch.
+    pub timestamp_ms: u64,
+    /// Block producer.
+    pub producer: ValidatorId,
+    /// VRF output over `HydroConfig::build_vrf_transcript(slot, parent_hash)`.
+    pub vrf_output: H256,
+    /// Proof for `vrf_output`.
+    pub vrf_proof: Vec<u8>,
+    /// Root over the block's transactions (`Block::tx_root`).
+    pub tx_root: H256,
+    /// State root after executing the block.
+    pub state_root: H256,
+    /// PoW nonce; the header hash must be below the difficulty target.
+    pub pow_nonce: u64,
+}
+
+impl BlockHeader {
+    /// Header hash: SHA-256( "Amunchain-BlockHeader-v1" || canonical header ).
+    pub fn hash(&self) -> Result<H256, CodecError> {
+        let mut ctx = ring::digest::Context::new(&ring::digest::SHA256);
+        ctx.update(b"Amunchain-BlockHeader-v1");
+        ctx.update(&encode_canonical(self)?);
+        let mut out = [0u8; 32];
+        out.copy_from_slice(ctx.finish().as_ref());
+        Ok(H256(out))
+    }
+}
+
+/// Hydro block: header plus opaque transactions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Block {
+    pub header: BlockHeader,
+    pub transactions: Vec<Vec<u8>>,
+}
+
+impl Block {
+    /// Transaction root: SHA-256( "Amunchain-TxRoot-v1" || count || (len || tx)* ).
+    pub fn tx_root(transactions: &[Vec<u8>]) -> H256 {
+        let mut ctx = ring::digest::Context::new(&ring::digest::SHA256);
+        ctx.update(b"Amunchain-TxRoot-v1");
+        ctx.update(&(transactions.len() as u64).to_be_bytes());
+        for tx in transactions {
+            ctx.update(&(tx.len() as u64).to_be_bytes());
+            ctx.update(tx);
+        }
+        let mut out = [0u8; 32];
+        out.copy_from_slice(ctx.finish().as_ref());
+        H256(out)
+    }
+}
+
+/// Largest canonical encoding of a transaction.
+pub const MAX_TX_BYTES: usize = 128 * 1024;
+
+/// What a transaction does.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TxCall {
+    /// Move `amount` to account `to`.
+    Transfer { to: Vec<u8>, amount: u128 },
+    /// Call the contract or precompile at `to` with `input`, attaching `value`.
+    Call {
+        to: Vec<u8>,
+        value: u128,
+        input: Vec<u8>,
+    },
+}
+
+/// Signed application transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transaction {
+    /// Chain the transaction is valid on.
+    pub chain_id: String,
+    pub sender: AccountKey,
+    /// Sender's transaction count; must equal the account's next nonce when executed.
+    pub nonce: u64,
+    pub call: TxCall,
+    pub gas_limit: u64,
+    /// Highest total price per gas the sender accepts.
+    pub max_fee_per_gas: u128,
+    /// Highest tip per gas the sender offers the producer.
+    pub max_priority_fee_per_gas: u128,
+    /// Sender's signature over `signing::tx_signing_bytes`.
+    pub signature: Signature,
+}
+
+impl Transaction {
+    /// Transaction hash: SHA-256( "Amunchain-TxHash-v1" || canonical transaction ).
+    /// Covers the signature, so it identifies exactly one encoding.
+    pub fn hash(&self) -> Result<H256, CodecError> {
+        let mut ctx = ring::digest::Context::new(&ring::digest::SHA256);
+        ctx.update(b"Amunchain-TxHash-v1");
+        ctx.update(&encode_canonical(self)?);
+        let mut out = [0u8; 32];
+        out.copy_from_slice(ctx.finish().as_ref());
+        Ok(H256(out))
+    }
+
+    /// Canonical encoding, as carried in `Block::transactions`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, CodecError> {
+        encode_canonical(self)
+    }
+
+    /// Decode a block or mempool entry (at most `MAX_TX_BYTES`).
+    pub fn from_bytes(raw: &[u8]) -> Result<Self, CodecError> {
+        decode_canonical_limited(raw, MAX_TX_BYTES)
+    }
+}
+
+/// Fingerprint of the consensus wire layout: SHA-256 over the canonical bincode and JSON
+/// encodings of fixed reference messages. Reordering, renaming or resizing a field, or
+/// adding an enum variant ahead of an existing one, changes it. Downstream crates can pin
+/// the value to catch encoding changes when they bump this one (see `tests/wire_vectors.rs`).
+pub fn wire_digest() -> Result<H256, CodecError> {
+    // Distinct bytes in every field, so width and byte order both show up.
+    let vote = Vote {
+        height: Height(0x0102_0304_0506_0708),
+        round: Round(0x1112_1314_1516_1718),
+        epoch: Epoch(0x2122_2324_2526_2728),
+        msg_counter: 0x3132_3334_3536_3738,
+        sent_ts_ms: 0x4142_4344_4546_4748,
+        ttl_ms: 0x5152_5354,
+        block_hash: H256::from_bytes([0x61; 32]),
+        voter: ValidatorId::from_bytes([0x71; 32]),
+        signature: Signature::from_bytes([0x81; 64]),
+    };
+    let commit = Commit {
+        height: vote.height,
+        round: vote.round,
+        epoch: vote.epoch,
+        msg_counter: vote.msg_counter,
+        sent_ts_ms: vote.sent_ts_ms,
+        ttl_ms: vote.ttl_ms,
+        block_hash: vote.block_hash,
+        signatures: CanonicalMap::from([(vote.voter.clone(), vote.signature.clone())]),
+        voting_power: 0x9192_9394_9596_9798_99a0_a1a2_a3a4_a5a6,
+        validator_set_hash: H256([0xb0; 32]),
+    };
+    let msgs = [ConsensusMsg::Vote(vote), ConsensusMsg::Commit(commit)];
+
+    let mut ctx = ring::digest::Context::new(&ring::digest::SHA256);
+    ctx.update(b"Amunchain-WireLayout-v1");
+    for msg in &msgs {
+        for enc in [encode_canonical(msg)?, encode_canonical_json(msg)?] {
+            ctx.update(&(enc.len() as u64).to_be_bytes());
+            ctx.update(&enc);
+        }
+    }
+    let mut out = [0u8; 32];
+    out.copy_from_slice(ctx.finish().as_ref());
+    Ok(H256::from_bytes(out))
+}
+
+/// Config file errors.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("cannot read {path}: {reason}")]
+    Read { path: String, reason: String },
+    #[error("invalid config {path}: {reason}")]
+    Parse { path: String, reason: String },
+    #[error("invalid override {key}: {reason}")]
+    Override { key: String, reason: String },
+}
+
 /// Node configuration root.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NodeConfig {
@@ -171,6 +639,35 @@ pub struct NodeConfig {
     pub p2p: NodeP2pConfig,
     /// Consensus settings.
     pub consensus: ConsensusConfig,
+    /// Async runtime sizing.
+    #[serde(default)]
+    pub runtime: RuntimeSettings,
+    /// Trace export.
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    /// Readiness thresholds for `/readyz`.
+    #[serde(default)]
+    pub health: ReadinessCriteria,
+    /// Signing key settings.
+    #[serde(default)]
+    pub keystore: KeystoreConfig,
+    /// State database settings.
+    #[serde(default)]
+    pub state: StateConfig,
+}
+
+impl NodeConfig {
+    /// Read and parse a TOML config file.
+    pub fn load(path: &std::path::Path) -> Result<Self, ConfigError> {
+        let raw = std::fs::read_to_string(path).map_err(|e| ConfigError::Read {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+        toml::from_str(&raw).map_err(|e| ConfigError::Parse {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })
+    }
 }
 
 /// Node settings.
@@ -180,6 +677,16 @@ pub struct NodeSettings {
     pub name: String,
     /// Data directory (db + keys).
     pub data_dir: String,
+    /// Chain id bound into consensus signatures (also settable with `AMUN_CHAIN_ID`).
+    #[serde(default = "default_chain_id")]
+    pub chain_id: String,
+    /// `"validator"` (default), `"full"` or `"observer"`; see `node::role`.
+    #[serde(default)]
+    pub role: NodeRole,
+}
+
+fn default_chain_id() -> String {
+    crate::core::consensus::tide::DEFAULT_CHAIN_ID.to_string()
 }
 
 /// HTTP config.
@@ -187,6 +694,200 @@ pub struct NodeSettings {
 pub struct HttpConfig {
     /// Listen address, e.g. 0.0.0.0:9090.
     pub listen_addr: String,
+    /// PEM certificate chain. With `tls_key_path`, the API is served over HTTPS only.
+    #[serde(default)]
+    pub tls_cert_path: Option<String>,
+    /// PEM private key for `tls_cert_path`.
+    #[serde(default)]
+    pub tls_key_path: Option<String>,
+    /// PEM CA bundle for client certificates. When set, `/admin` routes also require a
+    /// client certificate signed by one of these CAs (mTLS); other routes do not.
+    #[serde(default)]
+    pub tls_client_ca_path: Option<String>,
+    /// Most concurrent `/ws` subscriptions; more are refused with 503.
+    #[serde(default = "default_ws_max_subscribers")]
+    pub ws_max_subscribers: usize,
+    /// Chain events buffered per `/ws` subscriber before the oldest are dropped.
+    #[serde(default = "default_ws_buffer")]
+    pub ws_buffer: usize,
+}
+
+fn default_ws_max_subscribers() -> usize {
+    64
+}
+
+fn default_ws_buffer() -> usize {
+    crate::core::consensus::events::DEFAULT_EVENT_CAPACITY
+}
+
+/// Async runtime sizing.
+///
+/// Consensus and P2P run on one runtime, the HTTP API on another, so heavy API load
+/// cannot starve vote processing.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RuntimeSettings {
+    /// Worker threads for consensus + P2P (0 => number of CPUs).
+    #[serde(default)]
+    pub consensus_worker_threads: usize,
+    /// Worker threads for the HTTP API.
+    #[serde(default = "default_rpc_worker_threads")]
+    pub rpc_worker_threads: usize,
+    /// Max in-flight HTTP requests; excess requests get 503 instead of queueing.
+    #[serde(default = "default_rpc_max_in_flight")]
+    pub rpc_max_in_flight: usize,
+}
+
+fn default_rpc_worker_threads() -> usize {
+    2
+}
+
+fn default_rpc_max_in_flight() -> usize {
+    256
+}
+
+impl Default for RuntimeSettings {
+    fn default() -> Self {
+        Self {
+            consensus_worker_threads: 0,
+            rpc_worker_threads: default_rpc_worker_threads(),
+            rpc_max_in_flight: default_rpc_max_in_flight(),
+        }
+    }
+}
+
+/// Signing key settings (`[keystore]`).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeystoreConfig {
+    /// Where signing audit entries are forwarded, besides the local `audit.log`.
+    #[serde(default)]
+    pub audit_sinks: Vec<AuditSinkConfig>,
+    /// Entries queued per sink; when a sink falls this far behind, new entries are dropped
+    /// for it (the local log keeps them).
+    #[serde(default = "default_audit_queue_len")]
+    pub audit_queue_len: usize,
+    /// What the key may sign (`[keystore.policy]`).
+    #[serde(default)]
+    pub policy: SignPolicyConfig,
+}
+
+impl Default for KeystoreConfig {
+    fn default() -> Self {
+        Self {
+            audit_sinks: Vec::new(),
+            audit_queue_len: default_audit_queue_len(),
+            policy: SignPolicyConfig::default(),
+        }
+    }
+}
+
+fn default_audit_queue_len() -> usize {
+    1024
+}
+
+/// State database settings (`[state]`).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateConfig {
+    /// Encryption at rest for state values: `"off"` (default), `"passphrase"` (key derived
+    /// from `AMUNCHAIN_KEY_PASSPHRASE`) or `"key"` (`AMUNCHAIN_STATE_KEY`, 32 bytes hex); see
+    /// `core::state::encryption`.
+    #[serde(default)]
+    pub encryption: StateEncryption,
+}
+
+/// Sign policy (`[keystore.policy]`); see `security::sign_policy`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignPolicyConfig {
+    /// Payload domains the key signs; defaults to votes only.
+    #[serde(default = "default_sign_domains")]
+    pub allowed_domains: Vec<SignDomain>,
+    /// Refuse votes below the highest (height, round) signed, and a second block at it.
+    #[serde(default = "default_true")]
+    pub monotonic_height: bool,
+    /// Most signatures per second, per domain.
+    #[serde(default = "default_max_signs_per_sec")]
+    pub max_signs_per_sec: u32,
+}
+
+impl Default for SignPolicyConfig {
+    fn default() -> Self {
+        Self {
+            allowed_domains: default_sign_domains(),
+            monotonic_height: true,
+            max_signs_per_sec: default_max_signs_per_sec(),
+        }
+    }
+}
+
+fn default_sign_domains() -> Vec<SignDomain> {
+    vec![SignDomain::Vote]
+}
+
+fn default_max_signs_per_sec() -> u32 {
+    50
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// One audit sink (`[[keystore.audit_sinks]]`).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AuditSinkConfig {
+    /// Append JSON lines to `path`.
+    File { path: String },
+    /// RFC 5424 messages to `udp://host:port` or `unix:///dev/log`.
+    Syslog { addr: String },
+    /// POST each entry as JSON to an `http://` URL.
+    Http {
+        url: String,
+        #[serde(default = "default_audit_http_timeout_ms")]
+        timeout_ms: u64,
+    },
+}
+
+fn default_audit_http_timeout_ms() -> u64 {
+    5_000
+}
+
+/// Trace export (`[telemetry]`). Export needs the `otel` feature.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    /// OTLP/HTTP traces endpoint, e.g. `http://127.0.0.1:4318/v1/traces` (unset => no export).
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    /// Fraction of root traces sampled (0.0..=1.0).
+    #[serde(default = "default_sample_ratio")]
+    pub sample_ratio: f64,
+    /// `service.name` resource attribute.
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+    /// Initial log filter (`EnvFilter` syntax); changeable at runtime.
+    #[serde(default = "default_log_filter")]
+    pub log_filter: String,
+}
+
+fn default_log_filter() -> String {
+    "info".to_string()
+}
+
+fn default_sample_ratio() -> f64 {
+    1.0
+}
+
+fn default_service_name() -> String {
+    "amunchain".to_string()
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            sample_ratio: default_sample_ratio(),
+            service_name: default_service_name(),
+            log_filter: default_log_filter(),
+        }
+    }
 }
 
 /// P2P config embedded in node config.
@@ -196,10 +897,42 @@ pub struct NodeP2pConfig {
     pub listen_addr: String,
     /// Gossipsub topic.
     pub topic: String,
+    /// Encoding of consensus messages on `topic`: `"bincode"` (default, versioned) or
+    /// `"protobuf"` (for clients outside Rust). All nodes on a topic must agree.
+    #[serde(default)]
+    pub codec: WireCodec,
     /// Max messages/sec per peer.
     pub max_msg_per_sec: u32,
     /// Max peers per IP (best-effort).
     pub max_peers_per_ip: usize,
+    /// Gossipsub mesh and heartbeat tuning (`[p2p.gossipsub]`); read at startup.
+    #[serde(default)]
+    pub gossipsub: GossipTuning,
+    /// Message size limits per class (`[p2p.limits]`); read at startup.
+    #[serde(default)]
+    pub limits: WireLimits,
+    /// Seconds between finalized-head announcements (0 => neither announce nor listen).
+    #[serde(default = "default_status_interval_secs")]
+    pub status_interval_secs: u64,
+    /// A peer head more than this many heights above ours triggers a sync.
+    #[serde(default = "default_sync_lag_threshold")]
+    pub sync_lag_threshold: u64,
+
+    /// Where the p2p identity key comes from: `"file"` (default), `"encrypted"` (with
+    /// `AMUNCHAIN_KEY_PASSPHRASE`) or `"validator"` (the validator key is the identity).
+    #[serde(default)]
+    pub identity: IdentitySource,
+    /// Seconds between signed validator-to-PeerId binding announcements on
+    /// "amunchain/binding/v1" (0 => neither announce nor listen).
+    #[serde(default)]
+    pub binding_interval_secs: u64,
+    /// Bindings more than this many epochs behind the current one are dropped.
+    #[serde(default = "default_binding_max_epoch_age")]
+    pub binding_max_epoch_age: u64,
+    /// Seconds between signed provenance announcements on "amunchain/provenance/v1"
+    /// (0 => neither announce, listen nor serve `/ext/provenance`).
+    #[serde(default)]
+    pub provenance_interval_secs: u64,
 
     /// Bootstrap peers to dial at startup.
     #[serde(default)]
@@ -238,9 +971,58 @@ pub struct NodeP2pConfig {
     pub peer_registry_require_fresh: bool,
 }
 
+fn default_status_interval_secs() -> u64 {
+    10
+}
+
+fn default_sync_lag_threshold() -> u64 {
+    2
+}
+
+fn default_binding_max_epoch_age() -> u64 {
+    2
+}
+
 /// Consensus config.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ConsensusConfig {
     /// Validator public keys in hex (32 bytes each).
     pub validators_hex: Vec<String>,
+    /// Most gas the transactions of one block may use.
+    #[serde(default = "default_block_gas_limit")]
+    pub block_gas_limit: u64,
+    /// Merkle tree of the state root, the same on every node of the network. A node refuses
+    /// to start on a database built with another one (see `amunchain migrate-state-tree`).
+    #[serde(default)]
+    pub state_tree: StateTree,
+    /// Power a commit needs, the same on every node of the network (see
+    /// `core::consensus::quorum`). Defaults to two thirds.
+    #[serde(default)]
+    pub quorum: QuorumConfig,
+    /// Connected peers a validator needs to cast its own votes; below it, it keeps verifying
+    /// and storing what peers send but signs nothing. 0 disables the gate.
+    #[serde(default)]
+    pub min_consensus_peers: usize,
+    /// Gossip own commits as signer bitmaps and vote hashes (`consensus::compact`) instead of
+    /// full signatures. Every node of the network must run a version that reads them.
+    #[serde(default)]
+    pub compact_commits: bool,
+    /// Derive epoch randomness from finalized VRF outputs. Off by default, in which case
+    /// leader election keeps its configured randomness.
+    #[serde(default)]
+    pub beacon: Option<BeaconConfig>,
+}
+
+/// Randomness beacon for leader election (see `core::consensus::beacon`), the same on every
+/// node of the network.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BeaconConfig {
+    /// Finalized heights per epoch.
+    pub epoch_length: u64,
+    /// Randomness of epoch 1, 32 bytes in hex.
+    pub genesis_randomness_hex: String,
+}
+
+fn default_block_gas_limit() -> u64 {
+    30_000_000
 }
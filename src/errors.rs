@@ -0,0 +1,220 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Process exit codes.
+//!
+//! The node exits with a code that identifies the failure class, so restart policies
+//! (systemd `RestartPreventExitStatus=`, k8s probes) and runbooks can branch on it:
+//!
+//! | code | meaning                                              | restart helps? |
+//! |------|------------------------------------------------------|----------------|
+//! | 0    | clean shutdown                                       | -              |
+//! | 1    | internal error / subsystem exited unexpectedly       | yes            |
+//! | 10   | configuration error (bad env, bad config file)       | no             |
+//! | 11   | peer registry missing, unverifiable or expired       | no             |
+//! | 12   | key material missing, unreadable or undecryptable    | no             |
+//! | 13   | a listen port could not be bound                     | maybe          |
+//! | 14   | database cannot be opened or is corrupt              | no             |
+//! | 15   | a supervised task panicked or exited (watchdog)      | yes            |
+//!
+//! Codes 10-14 are stable; new classes get new codes rather than reusing old ones.
+
+use crate::core::security::keystore::KeystoreError;
+use crate::core::state::integrity::IntegrityError;
+use crate::core::state::migration::MigrationError;
+use crate::core::state::persistent_state::StateError;
+use crate::core::types::ConfigError;
+use crate::networking::p2p::P2pError;
+use crate::networking::p2p_identity::IdentityError;
+use crate::networking::peer_registry::PeerRegistryError;
+use crate::node::backup::BackupError;
+use crate::node::cli::CliError;
+use crate::node::runtimes::RuntimeError;
+use crate::rpc::server::RpcError;
+use crate::rpc::tls::TlsError;
+
+/// Failure class reported as the process exit code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(i32)]
+pub enum ExitCode {
+    Success = 0,
+    Internal = 1,
+    Config = 10,
+    Registry = 11,
+    Key = 12,
+    PortBind = 13,
+    DbCorruption = 14,
+    TaskFailure = 15,
+}
+
+impl ExitCode {
+    /// Numeric process exit code.
+    pub const fn code(self) -> i32 {
+        self as i32
+    }
+
+    /// Short label for logs.
+    pub const fn label(self) -> &'static str {
+        match self {
+            ExitCode::Success => "success",
+            ExitCode::Internal => "internal",
+            ExitCode::Config => "config",
+            ExitCode::Registry => "registry",
+            ExitCode::Key => "key",
+            ExitCode::PortBind => "port-bind",
+            ExitCode::DbCorruption => "db-corruption",
+            ExitCode::TaskFailure => "task-failure",
+        }
+    }
+}
+
+/// Errors that map onto a failure class.
+pub trait Classify {
+    fn exit_code(&self) -> ExitCode;
+}
+
+impl Classify for StateError {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            StateError::DbOpen | StateError::DbIo | StateError::Locked => ExitCode::DbCorruption,
+            StateError::TxConflict => ExitCode::Internal,
+            StateError::EncryptionKeyRequired
+            | StateError::WrongStateKey
+            | StateError::KeyUnavailable(_) => ExitCode::Config,
+            StateError::Crypto => ExitCode::DbCorruption,
+            StateError::UnsupportedTree(_) => ExitCode::Internal,
+            StateError::TreeMismatch { .. } => ExitCode::Config,
+        }
+    }
+}
+
+impl Classify for BackupError {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            BackupError::Io { .. } => ExitCode::Internal,
+            BackupError::State(e) => e.exit_code(),
+            BackupError::Keystore(e) => e.exit_code(),
+            BackupError::Exists(_)
+            | BackupError::NotEmpty(_)
+            | BackupError::InsideDataDir(_)
+            | BackupError::Unsigned
+            | BackupError::UntrustedSigner(_) => ExitCode::Config,
+            BackupError::Manifest(_)
+            | BackupError::BadSignature
+            | BackupError::Corrupt { .. }
+            | BackupError::RootMismatch { .. } => ExitCode::DbCorruption,
+        }
+    }
+}
+
+impl Classify for IntegrityError {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            IntegrityError::State(e) => e.exit_code(),
+            IntegrityError::Unreadable(_)
+            | IntegrityError::Marker { .. }
+            | IntegrityError::CheckpointMismatch { .. }
+            | IntegrityError::TreeMismatch { .. } => ExitCode::DbCorruption,
+        }
+    }
+}
+
+impl Classify for MigrationError {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            MigrationError::NewerSchema { .. } => ExitCode::Config,
+            MigrationError::OutOfOrder { .. } => ExitCode::Internal,
+            MigrationError::BadVersion => ExitCode::DbCorruption,
+            MigrationError::Failed { source: e, .. } | MigrationError::State(e) => e.exit_code(),
+        }
+    }
+}
+
+impl Classify for PeerRegistryError {
+    fn exit_code(&self) -> ExitCode {
+        ExitCode::Registry
+    }
+}
+
+impl Classify for KeystoreError {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            KeystoreError::MissingPassphrase | KeystoreError::Secret(_) => ExitCode::Config,
+            _ => ExitCode::Key,
+        }
+    }
+}
+
+impl Classify for IdentityError {
+    fn exit_code(&self) -> ExitCode {
+        ExitCode::Key
+    }
+}
+
+impl Classify for P2pError {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            P2pError::Io => ExitCode::Internal,
+            P2pError::Config => ExitCode::Config,
+            P2pError::Identity => ExitCode::Key,
+        }
+    }
+}
+
+impl Classify for RpcError {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            RpcError::Addr => ExitCode::Config,
+            RpcError::Bind => ExitCode::PortBind,
+            RpcError::Serve => ExitCode::Internal,
+        }
+    }
+}
+
+impl Classify for TlsError {
+    fn exit_code(&self) -> ExitCode {
+        ExitCode::Config
+    }
+}
+
+impl Classify for RuntimeError {
+    fn exit_code(&self) -> ExitCode {
+        ExitCode::Internal
+    }
+}
+
+impl Classify for ConfigError {
+    fn exit_code(&self) -> ExitCode {
+        ExitCode::Config
+    }
+}
+
+impl Classify for CliError {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            CliError::Config(e) => e.exit_code(),
+            CliError::State(e) => e.exit_code(),
+            CliError::Identity | CliError::Key => ExitCode::Key,
+            CliError::Keystore(e) => e.exit_code(),
+            CliError::Backup(e) => e.exit_code(),
+            CliError::Integrity(e) => e.exit_code(),
+            CliError::Io { .. } => ExitCode::Internal,
+            CliError::Exists(_)
+            | CliError::NotInitialized(_)
+            | CliError::NotEmpty(_)
+            | CliError::MissingArg(_)
+            | CliError::InvalidConfig { .. }
+            | CliError::Snapshot(_) => ExitCode::Config,
+        }
+    }
+}
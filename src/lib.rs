@@ -22,8 +22,15 @@
 //! - Monitoring via Prometheus metrics and structured JSON logging
 
 /// Core protocol primitives (types, consensus, state, security).
+pub mod config;
 pub mod core;
+/// Process exit codes and failure classification.
+pub mod errors;
 /// Observability (metrics, structured logging helpers).
 pub mod monitoring;
 /// P2P networking stack (libp2p transport, scoring, anti-abuse).
 pub mod networking;
+/// Node process wiring (runtimes, lifecycle).
+pub mod node;
+/// HTTP API (metrics scrape, node introspection).
+pub mod rpc;
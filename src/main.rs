@@ -2,107 +2,157 @@
 #![warn(missing_docs)]
 
 //! Amunchain node entrypoint (systemd-friendly).
-//! Starts P2P and keeps the process alive.
-
-use std::path::Path;
-use std::sync::Arc;
-
-use tracing::{info, warn};
-
-fn env(key: &str, default: &str) -> String {
-    std::env::var(key).unwrap_or_else(|_| default.to_string())
-}
-
-/// Extract node index from a path like `/srv/amunchain/node3/data`.
-fn node_index_from_data_dir(data_dir: &str) -> u16 {
-    for part in Path::new(data_dir).components() {
-        let s = part.as_os_str().to_string_lossy();
-        if let Some(rest) = s.strip_prefix("node") {
-            if let Ok(n) = rest.parse::<u16>() {
-                if (1..=99).contains(&n) {
-                    return n;
-                }
-            }
+//! `amunchain run` (or no command) starts the node; see `node::cli` for the other commands.
+
+use amunchain::config::ConfigLoader;
+use amunchain::core::state::integrity::StateRepair;
+use amunchain::core::types::NodeConfig;
+use amunchain::errors::{Classify, ExitCode};
+use amunchain::monitoring::telemetry;
+use amunchain::node::backup;
+use amunchain::node::builder::NodeBuilder;
+use amunchain::node::cli::{self, Cli, CliError, Command};
+use clap::Parser;
+use std::path::PathBuf;
+
+fn main() {
+    let args = Cli::parse();
+    let code = match dispatch(args) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {e}");
+            e.exit_code()
         }
-    }
-    1
+    };
+    std::process::exit(code.code());
 }
 
-#[tokio::main]
-async fn main() {
-    let _ = tracing_subscriber::fmt()
-        .with_target(false)
-        .with_level(true)
-        .compact()
-        .try_init();
-
-    let data_dir = env("AMUN_DATA_DIR", "./data");
-    let node_idx = node_index_from_data_dir(&data_dir);
-
-    // per-node ports: node1=4001, node2=4002, ...
-    let p2p_port: u16 = 4000 + node_idx;
-    let listen_addr = format!("/ip4/0.0.0.0/tcp/{p2p_port}");
-
-    // API now uses `consensus_topic`
-    let consensus_topic = env("AMUN_P2P_TOPIC", "amunchain-consensus");
-
-    // Bootstrap nodes 2..N to node1
-    let mut bootstrap: Vec<String> = Vec::new();
-    if node_idx != 1 {
-        // robust: load node1 peerid from its persisted identity (same VPS)
-        let node1_data_dir = "/srv/amunchain/node1/data";
-        match amunchain::networking::p2p_identity::load_or_create_identity(node1_data_dir) {
-            Ok((peer_id, _kp)) => {
-                bootstrap.push(format!("/ip4/127.0.0.1/tcp/4001/p2p/{peer_id}"));
-            }
-            Err(e) => {
-                warn!(
-                    ?e,
-                    "failed to load node1 identity for bootstrap; starting without bootstrap"
-                );
+fn dispatch(args: Cli) -> Result<ExitCode, CliError> {
+    let loader = args.loader()?;
+    // Effective config and data directory. `init` writes `--config` rather than reading it.
+    let layered = |read_file: bool| -> Result<(NodeConfig, PathBuf), CliError> {
+        let config = match args.config.as_ref().filter(|_| read_file) {
+            Some(path) => loader.clone().file(path).load()?,
+            None => loader.load()?,
+        };
+        let data_dir = args.resolve_data_dir(&config);
+        Ok((config, data_dir))
+    };
+    match args.command.as_ref().unwrap_or(&Command::DEFAULT) {
+        Command::Run {
+            repair,
+            repair_from,
+        } => {
+            let (config, data_dir) = layered(true)?;
+            let loader = match args.config.as_ref() {
+                Some(path) => loader.clone().file(path),
+                None => loader.clone(),
+            };
+            let repair = match repair_from {
+                Some(file) => StateRepair::Snapshot(file.clone()),
+                None if *repair => StateRepair::Resync,
+                None => StateRepair::Off,
+            };
+            Ok(run(config, data_dir, loader, repair))
+        }
+        Command::Init { force } => {
+            let (_, data_dir) = layered(false)?;
+            let config_path = args
+                .config
+                .clone()
+                .unwrap_or_else(|| data_dir.join("node.toml"));
+            let peer_id = cli::init(&data_dir, &config_path, *force)?;
+            println!("{peer_id}");
+            Ok(ExitCode::Success)
+        }
+        Command::Keygen { force } => {
+            let (_, data_dir) = layered(true)?;
+            println!("{}", cli::keygen(&data_dir, *force)?);
+            eprintln!("{}", cli::key_guidance(&cli::key_info(&data_dir)?));
+            Ok(ExitCode::Success)
+        }
+        Command::PrintPeerId => {
+            let (config, data_dir) = layered(true)?;
+            println!("{}", cli::peer_id(&data_dir, config.p2p.identity)?);
+            Ok(ExitCode::Success)
+        }
+        Command::CheckConfig { path } => {
+            let Some(path) = path.as_ref().or(args.config.as_ref()) else {
+                return Err(CliError::MissingArg("a config path"));
+            };
+            cli::check_config(&loader.clone().file(path))?;
+            println!("{}: ok", path.display());
+            Ok(ExitCode::Success)
+        }
+        Command::ExportState { out } => {
+            let (_, data_dir) = layered(true)?;
+            let info = cli::export_state(&data_dir, out)?;
+            println!("{} entries, state root {}", info.entries, info.state_root);
+            Ok(ExitCode::Success)
+        }
+        Command::ImportState { input } => {
+            let (_, data_dir) = layered(true)?;
+            let info = cli::import_state(&data_dir, input)?;
+            println!("{} entries, state root {}", info.entries, info.state_root);
+            Ok(ExitCode::Success)
+        }
+        Command::MigrateStateTree { to } => {
+            let (_, data_dir) = layered(true)?;
+            let done = cli::migrate_state_tree(&data_dir, *to)?;
+            println!(
+                "state tree {} -> {}, state root {}",
+                done.from, done.to, done.state_root
+            );
+            Ok(ExitCode::Success)
+        }
+        Command::Backup { dest, include_keys } => {
+            let (_, data_dir) = layered(true)?;
+            let info = backup::backup(&data_dir, dest, *include_keys)?;
+            println!("{} files, state root {}", info.files, info.state_root);
+            if info.signed_by.is_none() {
+                eprintln!("warning: no validator key in the data directory; manifest is unsigned");
             }
+            Ok(ExitCode::Success)
+        }
+        Command::Restore {
+            src,
+            allow_unsigned,
+        } => {
+            let (config, data_dir) = layered(true)?;
+            let info = backup::restore(
+                &data_dir,
+                src,
+                &config.consensus.validators_hex,
+                *allow_unsigned,
+            )?;
+            println!("{} files, state root {}", info.files, info.state_root);
+            Ok(ExitCode::Success)
         }
     }
+}
 
-    // Metrics: spawn_p2p now needs Arc<Metrics>
-    // (Path may be `amunchain::monitoring::metrics::Metrics` OR `amunchain::core::metrics::Metrics` depending on your tree.)
-    let metrics: Arc<amunchain::monitoring::metrics::Metrics> =
-        Arc::new(amunchain::monitoring::metrics::Metrics::new().expect("metrics init failed"));
-
-    let cfg = amunchain::networking::p2p::P2pConfig {
-        data_dir: data_dir.clone(),
-        listen_addr,
-        consensus_topic,
-        max_msg_per_sec: 200,
-        max_peers_per_ip: 4,
-        bootstrap,
-        allow_peers: vec![
-            "12D3KooWLh9S2QyVMgQgHmuzu2tCA6KsL9mpNgnvoiA1SQ9nHnMA".to_string(),
-            "12D3KooWLCZAkdjaD7FAT65FvUhwHVC5RBvYRTDZ6QXCbks7ZTaJ".to_string(),
-            "12D3KooWS9xDuptBksMQs7hAvKAJQhW5G9wYYVg7yemgGSZkQxWX".to_string(),
-            "12D3KooWEdXmay5QGhLnJnuDD9Wt2M3v2ADEjmEHFsN33XkTaTN4".to_string(),
-        ],
-    };
-
-    info!(node = node_idx, data_dir = %data_dir, "amunchain node starting");
-
-    let (_node, mut ev_rx, p2p_handle) = match amunchain::networking::p2p::spawn_p2p(cfg, metrics) {
-        Ok(v) => v,
+fn run(
+    config: NodeConfig,
+    data_dir: PathBuf,
+    loader: ConfigLoader,
+    repair: StateRepair,
+) -> ExitCode {
+    // `AMUN_OTLP_*` and `RUST_LOG` are part of the loader's environment layer.
+    let guard = match telemetry::init(&config.telemetry) {
+        Ok(g) => g,
         Err(e) => {
-            eprintln!("P2P start failed: {e}");
-            std::process::exit(1);
+            eprintln!("telemetry: {e}");
+            return ExitCode::Config;
         }
     };
 
-    // keep alive + log events
-    let ev_task = tokio::spawn(async move {
-        while let Some(ev) = ev_rx.recv().await {
-            info!(?ev, "p2p event");
-        }
-        warn!("p2p event channel closed");
-    });
-
-    // Wait forever (or until crash)
-    let _ = p2p_handle.await;
-    let _ = ev_task.await;
+    let node = NodeBuilder::new()
+        .log_filter(guard.log_filter())
+        .data_dir(data_dir.to_string_lossy())
+        .config(config)
+        .reload_from(loader)
+        .repair(repair);
+    let code = node.run();
+    drop(guard);
+    code
 }
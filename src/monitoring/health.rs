@@ -0,0 +1,316 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Node health: per-subsystem checks rolled up into healthy / degraded / unhealthy.
+//!
+//! Checks read the node's metrics (listen addresses, peers, finalized height, clock skew)
+//! and probe the database with a write to an auxiliary tree. With a `Watchdog` attached,
+//! a dead supervised task makes the node unhealthy, and with a Hydro `SlotClock` attached
+//! so does local clock drift beyond the slot clock's `skew_ms`. A validator withholding its
+//! votes for lack of peers (`consensus.min_consensus_peers`) is unhealthy too. A degraded node
+//! still serves (`/readyz` returns 200); an unhealthy one is taken out of rotation (503).
+
+use crate::core::consensus::slot_clock::SlotClock;
+use crate::core::state::persistent_state::PersistentState;
+use crate::monitoring::metrics::Metrics;
+use crate::monitoring::watchdog::Watchdog;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Status of one check, or of the whole node (the worst of its checks).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+/// Result of one subsystem check.
+#[derive(Clone, Debug, Serialize)]
+pub struct SubsystemHealth {
+    pub name: &'static str,
+    pub status: HealthStatus,
+    pub detail: String,
+}
+
+/// Node health report (`/healthz`, `/readyz`).
+#[derive(Clone, Debug, Serialize)]
+pub struct HealthReport {
+    pub status: HealthStatus,
+    pub checks: Vec<SubsystemHealth>,
+}
+
+/// Readiness thresholds (`[health]`).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReadinessCriteria {
+    /// Fewer connected peers than this is degraded; none at all is unhealthy.
+    #[serde(default = "default_min_peers")]
+    pub min_peers: usize,
+    /// No new finalized height for this long is unhealthy (0 => consensus not checked).
+    #[serde(default = "default_max_stall_secs")]
+    pub max_stall_secs: u64,
+    /// Clock skew against the last commit beyond this is degraded.
+    #[serde(default = "default_max_clock_skew_ms")]
+    pub max_clock_skew_ms: u64,
+}
+
+fn default_min_peers() -> usize {
+    1
+}
+
+fn default_max_stall_secs() -> u64 {
+    60
+}
+
+fn default_max_clock_skew_ms() -> u64 {
+    2_000
+}
+
+impl Default for ReadinessCriteria {
+    fn default() -> Self {
+        Self {
+            min_peers: default_min_peers(),
+            max_stall_secs: default_max_stall_secs(),
+            max_clock_skew_ms: default_max_clock_skew_ms(),
+        }
+    }
+}
+
+// Last finalized height and when it was first seen.
+struct Progress {
+    height: i64,
+    since: Instant,
+}
+
+/// Evaluates health on demand.
+#[derive(Clone)]
+pub struct HealthMonitor {
+    criteria: ReadinessCriteria,
+    metrics: Arc<Metrics>,
+    state: Option<PersistentState>,
+    watchdog: Option<Watchdog>,
+    slot_clock: Option<SlotClock>,
+    progress: Arc<Mutex<Progress>>,
+}
+
+impl HealthMonitor {
+    pub fn new(criteria: ReadinessCriteria, metrics: Arc<Metrics>) -> Self {
+        let height = metrics.block_height.get();
+        Self {
+            criteria,
+            metrics,
+            state: None,
+            watchdog: None,
+            slot_clock: None,
+            progress: Arc::new(Mutex::new(Progress {
+                height,
+                since: Instant::now(),
+            })),
+        }
+    }
+
+    /// Probe this database for writability.
+    pub fn with_state(mut self, state: PersistentState) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Report supervised task failures.
+    pub fn with_watchdog(mut self, watchdog: Watchdog) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+
+    /// Fail readiness when the slot clock drifts beyond its `skew_ms`.
+    pub fn with_slot_clock(mut self, clock: SlotClock) -> Self {
+        self.slot_clock = Some(clock);
+        self
+    }
+
+    pub fn report(&self) -> HealthReport {
+        self.report_at(Instant::now())
+    }
+
+    /// Report as of `now` (for tests that fake the passage of time).
+    pub fn report_at(&self, now: Instant) -> HealthReport {
+        let checks = vec![
+            self.check_p2p_listening(),
+            self.check_peers(),
+            self.check_consensus(now),
+            self.check_participation(),
+            self.check_db(),
+            self.check_clock(),
+            self.check_slot_clock(),
+            self.check_tasks(),
+        ];
+        let status = checks
+            .iter()
+            .map(|c| c.status)
+            .max()
+            .unwrap_or(HealthStatus::Healthy);
+        HealthReport { status, checks }
+    }
+
+    fn check_p2p_listening(&self) -> SubsystemHealth {
+        let addrs = self.metrics.p2p_listen_addrs.get();
+        check(
+            "p2p_listening",
+            if addrs > 0 {
+                HealthStatus::Healthy
+            } else {
+                HealthStatus::Unhealthy
+            },
+            format!("{addrs} listen addresses"),
+        )
+    }
+
+    fn check_peers(&self) -> SubsystemHealth {
+        let peers = self.metrics.p2p_peers.get().max(0) as usize;
+        let status = if peers >= self.criteria.min_peers {
+            HealthStatus::Healthy
+        } else if peers > 0 {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Unhealthy
+        };
+        check(
+            "peers",
+            status,
+            format!("{peers} connected (min {})", self.criteria.min_peers),
+        )
+    }
+
+    fn check_consensus(&self, now: Instant) -> SubsystemHealth {
+        let height = self.metrics.block_height.get();
+        let stalled_for = match self.progress.lock() {
+            Ok(mut p) => {
+                if height != p.height {
+                    p.height = height;
+                    p.since = now;
+                }
+                now.saturating_duration_since(p.since)
+            }
+            Err(_) => Duration::ZERO,
+        };
+        let limit = self.criteria.max_stall_secs;
+        let status = if limit == 0 || stalled_for < Duration::from_secs(limit) {
+            HealthStatus::Healthy
+        } else {
+            HealthStatus::Unhealthy
+        };
+        check(
+            "consensus",
+            status,
+            format!(
+                "finalized height {height}, unchanged for {}s",
+                stalled_for.as_secs()
+            ),
+        )
+    }
+
+    fn check_participation(&self) -> SubsystemHealth {
+        if self.metrics.consensus_votes_gated.get() > 0 {
+            let peers = self.metrics.p2p_peers.get();
+            return check(
+                "consensus_participation",
+                HealthStatus::Unhealthy,
+                format!("too few peers to vote ({peers} connected)"),
+            );
+        }
+        check(
+            "consensus_participation",
+            HealthStatus::Healthy,
+            "voting".to_string(),
+        )
+    }
+
+    fn check_db(&self) -> SubsystemHealth {
+        let Some(state) = self.state.as_ref() else {
+            return check("db", HealthStatus::Healthy, "not attached".to_string());
+        };
+        let writable = state
+            .open_tree("health")
+            .is_ok_and(|t| t.insert(b"probe", b"ok".as_slice()).is_ok());
+        if writable {
+            check("db", HealthStatus::Healthy, "writable".to_string())
+        } else {
+            check(
+                "db",
+                HealthStatus::Unhealthy,
+                "write probe failed".to_string(),
+            )
+        }
+    }
+
+    fn check_clock(&self) -> SubsystemHealth {
+        let skew = self.metrics.consensus_clock_skew_ms.get();
+        let status = if skew.unsigned_abs() <= self.criteria.max_clock_skew_ms {
+            HealthStatus::Healthy
+        } else {
+            HealthStatus::Degraded
+        };
+        check(
+            "clock_skew",
+            status,
+            format!("{skew}ms (max {})", self.criteria.max_clock_skew_ms),
+        )
+    }
+
+    fn check_slot_clock(&self) -> SubsystemHealth {
+        let Some(clock) = self.slot_clock.as_ref() else {
+            return check(
+                "slot_clock",
+                HealthStatus::Healthy,
+                "not attached".to_string(),
+            );
+        };
+        let Some(drift) = clock.drift_ms() else {
+            return check(
+                "slot_clock",
+                HealthStatus::Healthy,
+                "no peer timestamps yet".to_string(),
+            );
+        };
+        let status = if clock.drift_exceeded() {
+            HealthStatus::Unhealthy
+        } else {
+            HealthStatus::Healthy
+        };
+        check(
+            "slot_clock",
+            status,
+            format!("drift {drift}ms (max {})", clock.skew_ms()),
+        )
+    }
+
+    fn check_tasks(&self) -> SubsystemHealth {
+        match self.watchdog.as_ref().and_then(Watchdog::failure) {
+            Some(f) => check(
+                "tasks",
+                HealthStatus::Unhealthy,
+                format!("{} {}", f.task, f.reason),
+            ),
+            None => check("tasks", HealthStatus::Healthy, "running".to_string()),
+        }
+    }
+}
+
+fn check(name: &'static str, status: HealthStatus, detail: String) -> SubsystemHealth {
+    SubsystemHealth {
+        name,
+        status,
+        detail,
+    }
+}
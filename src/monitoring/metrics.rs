@@ -11,7 +11,11 @@
 // limitations under the License.
 #![forbid(unsafe_code)]
 
-use prometheus::{IntCounter, IntGauge, Registry};
+use prometheus::{
+    exponential_buckets, Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::sync::{Arc, Mutex};
 use thiserror::Error;
 
 /// Metrics errors.
@@ -26,11 +30,19 @@ pub enum MetricsError {
 pub struct Metrics {
     /// Registry.
     pub registry: Registry,
+    /// libp2p's own gossipsub metrics (`amunchain_gossipsub_*`: mesh and topic peers, per-topic
+    /// published/received counts with and without duplicates, IWANT requests, mesh churn).
+    /// The p2p task installs it; `encode_text` appends it to the exposition.
+    pub gossipsub: Arc<Mutex<prometheus_client::registry::Registry>>,
 
     /// Connected peers gauge.
     pub p2p_peers: IntGauge,
-    /// Block height gauge (optional wiring).
+    /// Addresses the swarm is listening on.
+    pub p2p_listen_addrs: IntGauge,
+    /// Highest finalized height.
     pub block_height: IntGauge,
+    /// 1 for the role the node runs as (`role` = validator, full or observer).
+    pub node_role: IntGaugeVec,
     /// Total transactions counter (optional wiring).
     pub transactions_total: IntCounter,
 
@@ -44,6 +56,134 @@ pub struct Metrics {
     pub p2p_reputation_throttled_total: IntCounter,
     /// Banned peer events.
     pub p2p_banned_total: IntCounter,
+    /// Peers currently banned by score and blacklisted from gossip.
+    pub p2p_banned_peers: IntGauge,
+    /// Wire format version consensus messages are published with.
+    pub p2p_wire_version: IntGauge,
+    /// Messages in, and peers advertising only, wire versions this node does not read.
+    pub p2p_wire_unsupported_total: IntCounter,
+    /// New peer ids from addresses of banned peers, and addresses banned for id churn.
+    pub p2p_ban_evasion_suspected_total: IntCounter,
+    /// IPs and subnets banned for peer id churn.
+    pub p2p_banned_ips: IntGauge,
+    /// Consensus messages dropped by gossipsub before forwarding because their TTL ran out.
+    pub p2p_expired_dropped_total: IntCounter,
+    /// Gossip messages over the size limit of their class, inbound and outbound, by topic kind
+    /// (consensus, evidence, commit_sync, other).
+    pub p2p_oversize_total: IntCounterVec,
+    /// Peer exchange messages by result (sent, accepted, rejected, ignored).
+    pub p2p_pex_total: IntCounterVec,
+    /// Dial-backs of advertised addresses by result (verified, failed, skipped).
+    pub p2p_dialback_total: IntCounterVec,
+    /// Verified peer addresses in the address book.
+    pub p2p_known_addrs: IntGauge,
+
+    /// Consecutive missed finalized rounds per validator (hex key label).
+    pub consensus_validator_missed_rounds: IntGaugeVec,
+    /// Validators jailed in the staking ledger (as of the last `sync_staking`).
+    pub consensus_validators_jailed: IntGauge,
+    /// Heights with buffered (not yet pruned) votes.
+    pub consensus_retained_heights: IntGauge,
+    /// Buffered votes across all retained heights.
+    pub consensus_retained_votes: IntGauge,
+    /// Votes buffered ahead of the local view.
+    pub consensus_pending_votes: IntGauge,
+    /// Early votes dropped (too far ahead or over a per-peer cap).
+    pub consensus_pending_dropped_total: IntCounter,
+    /// Highest round seen at the height being voted on.
+    pub consensus_round: IntGauge,
+    /// Votes collected for `consensus_round`.
+    pub consensus_round_votes: IntGauge,
+    /// Consensus votes received.
+    pub consensus_votes_received_total: IntCounter,
+    /// Heights finalized (own quorum or verified commit).
+    pub consensus_commits_total: IntCounter,
+    /// Conflicting votes detected.
+    pub consensus_double_votes_total: IntCounter,
+    /// 1 while local votes are withheld for lack of peers (`min_consensus_peers`).
+    pub consensus_votes_gated: IntGauge,
+    /// Local votes withheld for lack of peers.
+    pub consensus_votes_withheld_total: IntCounter,
+    /// Evidence recorded in the evidence pool, by source (`local`, `peer`).
+    pub consensus_evidence_total: IntCounterVec,
+    /// Compact commit traffic, by event (`compact_sent`, `compact_expanded`,
+    /// `votes_requested`, `votes_served`).
+    pub consensus_commit_sync_total: IntCounterVec,
+    /// Offenses held in the evidence pool.
+    pub consensus_evidence_pool: IntGauge,
+    /// Consensus messages refused by the driver, by reason.
+    pub consensus_msgs_rejected_total: IntCounterVec,
+    /// Consensus messages the driver already had.
+    pub consensus_msgs_duplicate_total: IntCounter,
+    /// Commit certificates accepted from the verified-commit cache without re-verification.
+    pub consensus_commit_cache_hits_total: IntCounter,
+    /// Local clock minus the send timestamp of the last finalized commit, in ms.
+    pub consensus_clock_skew_ms: IntGauge,
+    /// Median local clock minus verified peer send timestamps, in ms (Hydro slot clock).
+    pub hydro_clock_skew_ms: IntGauge,
+    /// Blocks that passed every import stage.
+    pub block_imports_total: IntCounter,
+    /// Blocks rejected during import, by stage.
+    pub block_import_rejected_total: IntCounterVec,
+    /// Blocks held in the orphan pool.
+    pub block_orphans: IntGauge,
+    /// Orphans dropped (age or pool size) before their parent arrived.
+    pub block_orphans_evicted_total: IntCounter,
+    /// Share of finalized rounds signed per validator, in bps (hex key label).
+    pub consensus_validator_uptime_bps: IntGaugeVec,
+    /// Median vote arrival delay per validator over its recent finalized votes, in ms.
+    pub consensus_validator_vote_delay_ms: IntGaugeVec,
+
+    /// Worker threads per runtime (`consensus`, `rpc`).
+    pub runtime_workers: IntGaugeVec,
+    /// Alive tasks per runtime.
+    pub runtime_alive_tasks: IntGaugeVec,
+    /// Tasks waiting in the global scheduler queue per runtime.
+    pub runtime_global_queue_depth: IntGaugeVec,
+    /// HTTP requests rejected because the in-flight limit was reached.
+    pub rpc_rejected_total: IntCounter,
+    /// HTTPS connections dropped during the TLS handshake (bad or missing certs, timeouts).
+    pub rpc_tls_handshake_failures_total: IntCounter,
+    /// Open `/ws` event subscriptions.
+    pub rpc_ws_subscribers: IntGauge,
+    /// Chain events `/ws` subscribers missed by falling behind.
+    pub rpc_ws_events_dropped_total: IntCounter,
+    /// Queued messages per internal channel.
+    pub channel_depth: IntGaugeVec,
+    /// Messages dropped by lossy internal channels.
+    pub channel_dropped_total: IntCounterVec,
+    /// Supervised tasks that panicked or exited unexpectedly (`task` label).
+    pub node_task_failures_total: IntCounterVec,
+    /// Config reloads by `result` (`applied`, `unchanged`, `rejected`).
+    pub config_reloads_total: IntCounterVec,
+
+    /// Vote signature verification time.
+    pub consensus_vote_verify_seconds: Histogram,
+    /// Time to tally a round and build its commit certificate.
+    pub consensus_commit_build_seconds: Histogram,
+    /// From the commit's signed send time to local finality.
+    pub consensus_finality_latency_seconds: Histogram,
+    /// `PersistentState::commit_atomic` duration.
+    pub state_commit_seconds: Histogram,
+    /// `PersistentState::state_root` duration.
+    pub state_root_seconds: Histogram,
+    /// Gossipsub publish latency.
+    pub p2p_publish_seconds: Histogram,
+    /// Gas used per executed block.
+    pub block_gas_used: Histogram,
+    /// Transactions that ran out of gas.
+    pub tx_out_of_gas_total: IntCounter,
+    /// State database integrity check failures at startup, by kind.
+    pub state_corruption_detected_total: IntCounterVec,
+    /// State databases rebuilt by `--repair`, by source (`resync`, `snapshot`).
+    pub state_repairs_total: IntCounterVec,
+}
+
+/// Latency histogram with buckets from 10µs to ~10s.
+fn latency_histogram(name: &str, help: &str) -> Result<Histogram, MetricsError> {
+    let buckets = exponential_buckets(0.000_01, 4.0, 11).map_err(|_| MetricsError::Prom)?;
+    Histogram::with_opts(HistogramOpts::new(name, help).buckets(buckets))
+        .map_err(|_| MetricsError::Prom)
 }
 
 impl Metrics {
@@ -53,8 +193,18 @@ impl Metrics {
 
         let p2p_peers = IntGauge::new("amunchain_p2p_peers", "Connected peers")
             .map_err(|_| MetricsError::Prom)?;
-        let block_height = IntGauge::new("amunchain_block_height", "Current block height")
+        let p2p_listen_addrs = IntGauge::new(
+            "amunchain_p2p_listen_addrs",
+            "Addresses the swarm listens on",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let block_height = IntGauge::new("amunchain_block_height", "Highest finalized height")
             .map_err(|_| MetricsError::Prom)?;
+        let node_role = IntGaugeVec::new(
+            Opts::new("amunchain_node_role", "Role the node runs as"),
+            &["role"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
         let transactions_total =
             IntCounter::new("amunchain_transactions_total", "Total tx processed")
                 .map_err(|_| MetricsError::Prom)?;
@@ -77,15 +227,338 @@ impl Metrics {
             "Reputation-based throttled messages",
         )
         .map_err(|_| MetricsError::Prom)?;
+        let p2p_wire_version = IntGauge::new(
+            "amunchain_p2p_wire_version",
+            "Wire format version used for publishing",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let p2p_wire_unsupported_total = IntCounter::new(
+            "amunchain_p2p_wire_unsupported_total",
+            "Messages or peers with an unsupported wire version",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let p2p_ban_evasion_suspected_total = IntCounter::new(
+            "amunchain_p2p_ban_evasion_suspected_total",
+            "Suspected ban evasion through peer id churn",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let p2p_banned_ips = IntGauge::new(
+            "amunchain_p2p_banned_ips",
+            "IPs and subnets banned for peer id churn",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let p2p_expired_dropped_total = IntCounter::new(
+            "amunchain_p2p_expired_dropped_total",
+            "Expired consensus messages dropped before forwarding",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let p2p_oversize_total = IntCounterVec::new(
+            Opts::new(
+                "amunchain_p2p_oversize_total",
+                "Gossip messages over the size limit of their class",
+            ),
+            &["topic"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let p2p_pex_total = IntCounterVec::new(
+            Opts::new("amunchain_p2p_pex_total", "Peer exchange messages"),
+            &["result"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let p2p_dialback_total = IntCounterVec::new(
+            Opts::new(
+                "amunchain_p2p_dialback_total",
+                "Dial-backs of advertised peer addresses",
+            ),
+            &["result"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let p2p_known_addrs = IntGauge::new(
+            "amunchain_p2p_known_addrs",
+            "Verified peer addresses in the address book",
+        )
+        .map_err(|_| MetricsError::Prom)?;
         let p2p_banned_total = IntCounter::new("amunchain_p2p_banned_total", "Banned peer events")
             .map_err(|_| MetricsError::Prom)?;
+        let p2p_banned_peers = IntGauge::new(
+            "amunchain_p2p_banned_peers",
+            "Peers currently banned by score",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+
+        let consensus_validator_missed_rounds = IntGaugeVec::new(
+            Opts::new(
+                "amunchain_consensus_validator_missed_rounds",
+                "Consecutive missed finalized rounds per validator",
+            ),
+            &["validator"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_validators_jailed = IntGauge::new(
+            "amunchain_consensus_validators_jailed",
+            "Validators jailed in the staking ledger",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_retained_heights = IntGauge::new(
+            "amunchain_consensus_retained_heights",
+            "Heights with buffered votes",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_retained_votes = IntGauge::new(
+            "amunchain_consensus_retained_votes",
+            "Buffered votes across retained heights",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_pending_votes = IntGauge::new(
+            "amunchain_consensus_pending_votes",
+            "Votes buffered ahead of the local view",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_pending_dropped_total = IntCounter::new(
+            "amunchain_consensus_pending_dropped_total",
+            "Early votes dropped by the pending buffer",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+
+        let consensus_round = IntGauge::new(
+            "amunchain_consensus_round",
+            "Highest round seen at the current height",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_round_votes = IntGauge::new(
+            "amunchain_consensus_round_votes",
+            "Votes collected for the current round",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_votes_received_total = IntCounter::new(
+            "amunchain_consensus_votes_received_total",
+            "Consensus votes received",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_commits_total =
+            IntCounter::new("amunchain_consensus_commits_total", "Heights finalized")
+                .map_err(|_| MetricsError::Prom)?;
+        let consensus_double_votes_total = IntCounter::new(
+            "amunchain_consensus_double_votes_total",
+            "Conflicting votes detected",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_votes_gated = IntGauge::new(
+            "amunchain_consensus_votes_gated",
+            "1 while local votes are withheld for lack of peers",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_votes_withheld_total = IntCounter::new(
+            "amunchain_consensus_votes_withheld_total",
+            "Local votes withheld for lack of peers",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_commit_sync_total = IntCounterVec::new(
+            Opts::new(
+                "amunchain_consensus_commit_sync_total",
+                "Compact commits and vote requests",
+            ),
+            &["event"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_evidence_total = IntCounterVec::new(
+            Opts::new(
+                "amunchain_consensus_evidence_total",
+                "Evidence recorded in the evidence pool",
+            ),
+            &["source"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_evidence_pool = IntGauge::new(
+            "amunchain_consensus_evidence_pool",
+            "Offenses held in the evidence pool",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_msgs_rejected_total = IntCounterVec::new(
+            Opts::new(
+                "amunchain_consensus_msgs_rejected_total",
+                "Consensus messages refused by the driver",
+            ),
+            &["reason"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_msgs_duplicate_total = IntCounter::new(
+            "amunchain_consensus_msgs_duplicate_total",
+            "Consensus messages the driver already had",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_commit_cache_hits_total = IntCounter::new(
+            "amunchain_consensus_commit_cache_hits_total",
+            "Commit certificates found in the verified-commit cache",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_clock_skew_ms = IntGauge::new(
+            "amunchain_consensus_clock_skew_ms",
+            "Local clock minus last finalized commit timestamp",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let hydro_clock_skew_ms = IntGauge::new(
+            "amunchain_hydro_clock_skew_ms",
+            "Median local clock minus verified peer message timestamps",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let block_imports_total = IntCounter::new(
+            "amunchain_block_imports_total",
+            "Blocks that passed every import stage",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let block_import_rejected_total = IntCounterVec::new(
+            Opts::new(
+                "amunchain_block_import_rejected_total",
+                "Blocks rejected during import, by stage",
+            ),
+            &["stage"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let block_orphans =
+            IntGauge::new("amunchain_block_orphans", "Blocks held in the orphan pool")
+                .map_err(|_| MetricsError::Prom)?;
+        let block_orphans_evicted_total = IntCounter::new(
+            "amunchain_block_orphans_evicted_total",
+            "Orphans dropped before their parent arrived",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_validator_uptime_bps = IntGaugeVec::new(
+            Opts::new(
+                "amunchain_consensus_validator_uptime_bps",
+                "Finalized rounds signed per validator, in bps",
+            ),
+            &["validator"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let consensus_validator_vote_delay_ms = IntGaugeVec::new(
+            Opts::new(
+                "amunchain_consensus_validator_vote_delay_ms",
+                "Median vote arrival delay per validator, in ms",
+            ),
+            &["validator"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+
+        let runtime_workers = IntGaugeVec::new(
+            Opts::new("amunchain_runtime_workers", "Worker threads per runtime"),
+            &["runtime"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let runtime_alive_tasks = IntGaugeVec::new(
+            Opts::new("amunchain_runtime_alive_tasks", "Alive tasks per runtime"),
+            &["runtime"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let runtime_global_queue_depth = IntGaugeVec::new(
+            Opts::new(
+                "amunchain_runtime_global_queue_depth",
+                "Tasks pending in the global queue per runtime",
+            ),
+            &["runtime"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let rpc_rejected_total = IntCounter::new(
+            "amunchain_rpc_rejected_total",
+            "HTTP requests rejected by the in-flight limit",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let rpc_tls_handshake_failures_total = IntCounter::new(
+            "amunchain_rpc_tls_handshake_failures_total",
+            "HTTPS connections dropped during the TLS handshake",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let rpc_ws_subscribers = IntGauge::new(
+            "amunchain_rpc_ws_subscribers",
+            "Open /ws event subscriptions",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let rpc_ws_events_dropped_total = IntCounter::new(
+            "amunchain_rpc_ws_events_dropped_total",
+            "Chain events /ws subscribers missed by falling behind",
+        )
+        .map_err(|_| MetricsError::Prom)?;
+
+        let channel_depth = IntGaugeVec::new(
+            Opts::new(
+                "amunchain_channel_depth",
+                "Queued messages per internal channel",
+            ),
+            &["channel"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let channel_dropped_total = IntCounterVec::new(
+            Opts::new(
+                "amunchain_channel_dropped_total",
+                "Messages dropped by full internal channels",
+            ),
+            &["channel"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+
+        let node_task_failures_total = IntCounterVec::new(
+            Opts::new(
+                "amunchain_node_task_failures_total",
+                "Supervised tasks that panicked or exited unexpectedly",
+            ),
+            &["task"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+
+        let config_reloads_total = IntCounterVec::new(
+            Opts::new(
+                "amunchain_config_reloads_total",
+                "Config reloads (SIGHUP) by outcome",
+            ),
+            &["result"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+
+        let consensus_vote_verify_seconds = latency_histogram(
+            "amunchain_consensus_vote_verify_seconds",
+            "Vote signature verification time",
+        )?;
+        let consensus_commit_build_seconds = latency_histogram(
+            "amunchain_consensus_commit_build_seconds",
+            "Commit certificate build time",
+        )?;
+        let consensus_finality_latency_seconds = latency_histogram(
+            "amunchain_consensus_finality_latency_seconds",
+            "Time from a commit's signed send time to local finality",
+        )?;
+        let state_commit_seconds =
+            latency_histogram("amunchain_state_commit_seconds", "Atomic state commit time")?;
+        let state_root_seconds = latency_histogram(
+            "amunchain_state_root_seconds",
+            "State root computation time",
+        )?;
+        let p2p_publish_seconds =
+            latency_histogram("amunchain_p2p_publish_seconds", "Gossip publish latency")?;
+        // One transfer up to ~43M gas.
+        let gas_buckets = exponential_buckets(21_000.0, 2.0, 12).map_err(|_| MetricsError::Prom)?;
+        let block_gas_used = Histogram::with_opts(
+            HistogramOpts::new("amunchain_block_gas_used", "Gas used per executed block")
+                .buckets(gas_buckets),
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let tx_out_of_gas_total = IntCounter::new(
+            "amunchain_tx_out_of_gas_total",
+            "Transactions that ran out of gas",
+        )
+        .map_err(|_| MetricsError::Prom)?;
 
         registry
             .register(Box::new(p2p_peers.clone()))
             .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(p2p_listen_addrs.clone()))
+            .map_err(|_| MetricsError::Prom)?;
         registry
             .register(Box::new(block_height.clone()))
             .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(node_role.clone()))
+            .map_err(|_| MetricsError::Prom)?;
         registry
             .register(Box::new(transactions_total.clone()))
             .map_err(|_| MetricsError::Prom)?;
@@ -105,17 +578,281 @@ impl Metrics {
         registry
             .register(Box::new(p2p_banned_total.clone()))
             .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(p2p_banned_peers.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(p2p_wire_version.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(p2p_wire_unsupported_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(p2p_ban_evasion_suspected_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(p2p_banned_ips.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(p2p_expired_dropped_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(p2p_oversize_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(p2p_pex_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(p2p_dialback_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(p2p_known_addrs.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+
+        registry
+            .register(Box::new(consensus_validator_missed_rounds.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_validators_jailed.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_retained_heights.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_retained_votes.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_pending_votes.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_pending_dropped_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+
+        registry
+            .register(Box::new(consensus_round.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_round_votes.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_votes_received_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_commits_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_double_votes_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_votes_gated.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_votes_withheld_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_commit_sync_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_evidence_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_evidence_pool.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_msgs_rejected_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_msgs_duplicate_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_commit_cache_hits_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_clock_skew_ms.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(hydro_clock_skew_ms.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(block_imports_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(block_import_rejected_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(block_orphans.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(block_orphans_evicted_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_validator_uptime_bps.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(consensus_validator_vote_delay_ms.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+
+        registry
+            .register(Box::new(runtime_workers.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(runtime_alive_tasks.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(runtime_global_queue_depth.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(rpc_rejected_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(rpc_tls_handshake_failures_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(rpc_ws_subscribers.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(rpc_ws_events_dropped_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(channel_depth.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(channel_dropped_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(node_task_failures_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(config_reloads_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+
+        for h in [
+            &consensus_vote_verify_seconds,
+            &consensus_commit_build_seconds,
+            &consensus_finality_latency_seconds,
+            &state_commit_seconds,
+            &state_root_seconds,
+            &p2p_publish_seconds,
+            &block_gas_used,
+        ] {
+            registry
+                .register(Box::new(h.clone()))
+                .map_err(|_| MetricsError::Prom)?;
+        }
+        registry
+            .register(Box::new(tx_out_of_gas_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        let state_corruption_detected_total = IntCounterVec::new(
+            Opts::new(
+                "amunchain_state_corruption_detected_total",
+                "State database integrity check failures at startup, by kind",
+            ),
+            &["kind"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        let state_repairs_total = IntCounterVec::new(
+            Opts::new(
+                "amunchain_state_repairs_total",
+                "State databases rebuilt at startup, by source",
+            ),
+            &["source"],
+        )
+        .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(state_corruption_detected_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
+        registry
+            .register(Box::new(state_repairs_total.clone()))
+            .map_err(|_| MetricsError::Prom)?;
 
         Ok(Self {
             registry,
+            gossipsub: Arc::default(),
             p2p_peers,
+            p2p_listen_addrs,
             block_height,
+            node_role,
             transactions_total,
             p2p_replay_dropped_total,
             p2p_invalid_msg_total,
             p2p_rate_limited_total,
             p2p_reputation_throttled_total,
             p2p_banned_total,
+            p2p_banned_peers,
+            p2p_wire_version,
+            p2p_wire_unsupported_total,
+            p2p_ban_evasion_suspected_total,
+            p2p_banned_ips,
+            p2p_expired_dropped_total,
+            p2p_oversize_total,
+            p2p_pex_total,
+            p2p_dialback_total,
+            p2p_known_addrs,
+            consensus_validator_missed_rounds,
+            consensus_validators_jailed,
+            consensus_retained_heights,
+            consensus_retained_votes,
+            consensus_pending_votes,
+            consensus_pending_dropped_total,
+            consensus_round,
+            consensus_round_votes,
+            consensus_votes_received_total,
+            consensus_commits_total,
+            consensus_double_votes_total,
+            consensus_votes_gated,
+            consensus_votes_withheld_total,
+            consensus_evidence_total,
+            consensus_commit_sync_total,
+            consensus_evidence_pool,
+            consensus_msgs_rejected_total,
+            consensus_msgs_duplicate_total,
+            consensus_commit_cache_hits_total,
+            consensus_clock_skew_ms,
+            hydro_clock_skew_ms,
+            block_imports_total,
+            block_import_rejected_total,
+            block_orphans,
+            block_orphans_evicted_total,
+            consensus_validator_uptime_bps,
+            consensus_validator_vote_delay_ms,
+            runtime_workers,
+            runtime_alive_tasks,
+            runtime_global_queue_depth,
+            rpc_rejected_total,
+            rpc_tls_handshake_failures_total,
+            rpc_ws_subscribers,
+            rpc_ws_events_dropped_total,
+            channel_depth,
+            channel_dropped_total,
+            node_task_failures_total,
+            config_reloads_total,
+            consensus_vote_verify_seconds,
+            consensus_commit_build_seconds,
+            consensus_finality_latency_seconds,
+            state_commit_seconds,
+            state_root_seconds,
+            p2p_publish_seconds,
+            block_gas_used,
+            tx_out_of_gas_total,
+            state_corruption_detected_total,
+            state_repairs_total,
         })
     }
+
+    /// Prometheus text exposition of `registry` followed by the gossipsub metrics.
+    pub fn encode_text(&self) -> Result<String, MetricsError> {
+        let mut buf = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buf)
+            .map_err(|_| MetricsError::Prom)?;
+        let mut out = String::from_utf8(buf).map_err(|_| MetricsError::Prom)?;
+
+        let mut gossip = String::new();
+        let registry = self.gossipsub.lock().map_err(|_| MetricsError::Prom)?;
+        prometheus_client::encoding::text::encode(&mut gossip, &registry)
+            .map_err(|_| MetricsError::Prom)?;
+        // OpenMetrics terminates the exposition; the combined output continues past it.
+        out.push_str(gossip.trim_end_matches("# EOF\n"));
+        Ok(out)
+    }
 }
@@ -13,4 +13,7 @@
 
 //! Monitoring and metrics.
 
+pub mod health;
 pub mod metrics;
+pub mod telemetry;
+pub mod watchdog;
@@ -0,0 +1,189 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Tracing setup: compact logs, plus OTLP span export with the `otel` feature.
+//!
+//! The message path is covered by spans: `p2p.receive` (with `p2p.decode`) in the swarm
+//! task, `consensus.msg` and `consensus.verify_vote` in the driver, `state.commit` in
+//! `PersistentState`. Named channels carry the sender's span, so `consensus.msg` links
+//! back to the `p2p.receive` that produced it (see `channel::Receiver::recv_with_span`).
+//!
+//! The level filter sits behind a reload layer: `LogFilterHandle::set` swaps it at runtime
+//! (used by `PUT /admin/loglevel` and SIGHUP) without restarting the node.
+
+use crate::core::types::TelemetryConfig;
+use std::sync::Arc;
+use thiserror::Error;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+#[derive(Debug, Error)]
+pub enum TelemetryError {
+    #[error("sample ratio must be within 0..=1")]
+    SampleRatio,
+    #[error("otlp exporter: {0}")]
+    Exporter(String),
+    #[error("otlp endpoint set but the node was built without the `otel` feature")]
+    Unsupported,
+    #[error("invalid log filter: {0}")]
+    Filter(String),
+    #[error("log filter reload failed")]
+    Reload,
+}
+
+/// Runtime handle to the log filter (`EnvFilter` directive syntax, e.g.
+/// `info,amunchain::networking::p2p=debug`).
+#[derive(Clone)]
+pub struct LogFilterHandle {
+    handle: reload::Handle<EnvFilter, Registry>,
+    current: Arc<std::sync::Mutex<String>>,
+}
+
+impl LogFilterHandle {
+    /// Replace the active filter. The old filter stays on a parse error.
+    pub fn set(&self, directives: &str) -> Result<(), TelemetryError> {
+        let directives = directives.trim();
+        let filter = parse_filter(directives)?;
+        self.handle
+            .reload(filter)
+            .map_err(|_| TelemetryError::Reload)?;
+        if let Ok(mut cur) = self.current.lock() {
+            *cur = directives.to_string();
+        }
+        tracing::info!(filter = %directives, "log filter changed");
+        Ok(())
+    }
+
+    /// Directives of the active filter.
+    pub fn current(&self) -> String {
+        self.current.lock().map(|c| c.clone()).unwrap_or_default()
+    }
+}
+
+fn parse_filter(directives: &str) -> Result<EnvFilter, TelemetryError> {
+    EnvFilter::builder()
+        .parse(directives)
+        .map_err(|e| TelemetryError::Filter(e.to_string()))
+}
+
+/// Keeps the log filter handle and flushes span export on drop.
+#[must_use = "dropping the guard stops span export"]
+pub struct TelemetryGuard {
+    log_filter: LogFilterHandle,
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+}
+
+impl TelemetryGuard {
+    /// Handle for changing the log filter at runtime.
+    pub fn log_filter(&self) -> LogFilterHandle {
+        self.log_filter.clone()
+    }
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if let Some(p) = self.provider.take() {
+            let _ = p.shutdown();
+        }
+    }
+}
+
+/// Install the global subscriber. Export is enabled when `cfg.otlp_endpoint` is set.
+pub fn init(cfg: &TelemetryConfig) -> Result<TelemetryGuard, TelemetryError> {
+    if !(0.0..=1.0).contains(&cfg.sample_ratio) {
+        return Err(TelemetryError::SampleRatio);
+    }
+    let (filter, handle) = reload::Layer::new(parse_filter(&cfg.log_filter)?);
+    let log_filter = LogFilterHandle {
+        handle,
+        current: Arc::new(std::sync::Mutex::new(cfg.log_filter.clone())),
+    };
+    let fmt = tracing_subscriber::fmt::layer()
+        .with_target(false)
+        .with_level(true)
+        .compact();
+
+    #[cfg(feature = "otel")]
+    {
+        let (otel, provider) = match cfg.otlp_endpoint.as_deref() {
+            Some(endpoint) => {
+                let (layer, provider) = otlp_layer(cfg, endpoint)?;
+                (Some(layer), Some(provider))
+            }
+            None => (None, None),
+        };
+        let _ = tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt)
+            .with(otel)
+            .try_init();
+        Ok(TelemetryGuard {
+            log_filter,
+            provider,
+        })
+    }
+
+    #[cfg(not(feature = "otel"))]
+    {
+        if cfg.otlp_endpoint.is_some() {
+            return Err(TelemetryError::Unsupported);
+        }
+        let _ = tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt)
+            .try_init();
+        Ok(TelemetryGuard { log_filter })
+    }
+}
+
+#[cfg(feature = "otel")]
+fn otlp_layer<S>(
+    cfg: &TelemetryConfig,
+    endpoint: &str,
+) -> Result<
+    (
+        tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>,
+        opentelemetry_sdk::trace::SdkTracerProvider,
+    ),
+    TelemetryError,
+>
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+    use opentelemetry_sdk::Resource;
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()
+        .map_err(|e| TelemetryError::Exporter(e.to_string()))?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            cfg.sample_ratio,
+        ))))
+        .with_resource(
+            Resource::builder()
+                .with_service_name(cfg.service_name.clone())
+                .build(),
+        )
+        .build();
+    let tracer = provider.tracer("amunchain");
+    Ok((tracing_opentelemetry::layer().with_tracer(tracer), provider))
+}
@@ -0,0 +1,152 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Crash reporting for long-running tasks.
+//!
+//! `install_panic_hook` logs every panic as a structured `fatal` event (message, location,
+//! thread) instead of the default stderr dump. `Watchdog::watch` supervises a task (the p2p
+//! loop, the event bridge): when it panics or returns, the watchdog logs a fatal event,
+//! bumps `node_task_failures_total{task}` and records the failure, which turns the `tasks`
+//! health check (and so `/readyz`) unhealthy.
+//!
+//! With `exit_on_failure` the supervised handle then completes, the startup orchestrator
+//! tears the node down and the builder exits with `ExitCode::TaskFailure` for the supervisor
+//! to restart. Without it the node keeps running, not ready, for an operator to inspect.
+//!
+//! After `begin_shutdown` tasks are expected to end, and their exits are not reported.
+
+use crate::monitoring::metrics::Metrics;
+use std::panic::PanicHookInfo;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+use tracing::error;
+
+/// First recorded task failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskFailure {
+    pub task: &'static str,
+    pub reason: String,
+}
+
+/// Supervises tasks and remembers the first failure.
+#[derive(Clone)]
+pub struct Watchdog {
+    metrics: Arc<Metrics>,
+    exit_on_failure: bool,
+    failure: Arc<Mutex<Option<TaskFailure>>>,
+    stopping: Arc<AtomicBool>,
+}
+
+impl Watchdog {
+    pub fn new(metrics: Arc<Metrics>, exit_on_failure: bool) -> Self {
+        Self {
+            metrics,
+            exit_on_failure,
+            failure: Arc::new(Mutex::new(None)),
+            stopping: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Whether a failed task should bring the node down.
+    pub fn exit_on_failure(&self) -> bool {
+        self.exit_on_failure
+    }
+
+    /// The node is shutting down; supervised tasks may now exit.
+    pub fn begin_shutdown(&self) {
+        self.stopping.store(true, Ordering::SeqCst);
+    }
+
+    /// First failure recorded, if any.
+    pub fn failure(&self) -> Option<TaskFailure> {
+        self.failure.lock().ok().and_then(|f| f.clone())
+    }
+
+    /// Record that `task` died.
+    pub fn report(&self, task: &'static str, reason: impl Into<String>) {
+        let reason = reason.into();
+        error!(fatal = true, task, %reason, "supervised task failed");
+        self.metrics
+            .node_task_failures_total
+            .with_label_values(&[task])
+            .inc();
+        if let Ok(mut f) = self.failure.lock() {
+            f.get_or_insert(TaskFailure { task, reason });
+        }
+    }
+
+    /// Supervise `handle`. The returned handle completes after the failure is reported, or
+    /// never if `exit_on_failure` is off. Aborting the returned handle aborts `handle` too
+    /// and is not reported.
+    pub fn watch(&self, task: &'static str, handle: JoinHandle<()>) -> JoinHandle<()> {
+        let watchdog = self.clone();
+        let mut handle = AbortOnDrop(handle);
+        tokio::spawn(async move {
+            let res = (&mut handle.0).await;
+            if watchdog.stopping.load(Ordering::SeqCst) {
+                return;
+            }
+            let reason = match res {
+                Ok(()) => "exited".to_string(),
+                Err(e) if e.is_panic() => format!("panicked: {}", panic_message(e.into_panic())),
+                Err(_) => "cancelled".to_string(),
+            };
+            watchdog.report(task, reason);
+            if !watchdog.exit_on_failure {
+                std::future::pending::<()>().await;
+            }
+        })
+    }
+}
+
+// Aborts the supervised task when the watcher itself is aborted (teardown).
+struct AbortOnDrop(JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Log panics as structured `fatal` events. Replaces the default hook.
+pub fn install_panic_hook() {
+    std::panic::set_hook(Box::new(|info: &PanicHookInfo<'_>| {
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}", l.file(), l.line()))
+            .unwrap_or_default();
+        let thread = std::thread::current();
+        error!(
+            fatal = true,
+            thread = thread.name().unwrap_or("unnamed"),
+            %location,
+            message = %payload_message(info.payload()),
+            "panic"
+        );
+    }));
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload_message(payload.as_ref())
+}
+
+fn payload_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "non-string panic payload".to_string()
+    }
+}
@@ -0,0 +1,457 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Verified peer addresses, kept across restarts.
+//!
+//! Peers advertise their listen addresses in identify, and nothing stops a peer from listing
+//! addresses it does not own: a node that stored and later dialed them could be turned against
+//! third parties, or have its address book filled with dead entries. So an advertised address
+//! only enters the `AddrBook` after the node dialed it back and the handshake authenticated the
+//! advertising peer on the other end (noise binds the connection to the peer id; the connection
+//! is closed right after).
+//!
+//! `DialBack` holds the policy and the in-flight dials:
+//! - only addresses on the IP the peer is already connected from are dialed, so a peer cannot
+//!   point our dials at somebody else's host; other addresses are skipped, not penalized
+//! - at most `max_addrs_per_peer` verified addresses per peer and `max_pending` dials at once
+//! - a peer whose dial-backs fail `fail_threshold` times in a row is penalized on every further
+//!   failure, until one succeeds
+//!
+//! Each address carries when a dial to it last succeeded and its dial successes and failures.
+//! The p2p task saves the book to `PEERS_FILE` in its data directory and loads it on start,
+//! dialing the best-rated peers (`AddrBook::best`) next to the bootstrap list. Addresses not
+//! reached for `ADDR_TTL` are expired.
+
+use libp2p::{multiaddr::Protocol, swarm::ConnectionId, Multiaddr, PeerId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// Address book file in the p2p data directory.
+pub const PEERS_FILE: &str = "peers.json";
+/// Addresses whose last successful dial is older than this are dropped.
+pub const ADDR_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+const FILE_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum AddrBookError {
+    #[error("address book {path}: {reason}")]
+    Io { path: String, reason: String },
+    #[error("address book {path}: {reason}")]
+    Malformed { path: String, reason: String },
+}
+
+/// Dial history of one address.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddrStats {
+    /// Unix ms of the last successful dial.
+    pub last_seen_ms: u64,
+    pub successes: u32,
+    pub failures: u32,
+}
+
+impl AddrStats {
+    /// Dial success rate in [0, 1], smoothed so one dial does not decide it: an address
+    /// without history rates 0.5.
+    pub fn quality(&self) -> f64 {
+        let ok = f64::from(self.successes);
+        (ok + 1.0) / (ok + f64::from(self.failures) + 2.0)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct PeersFile {
+    version: u32,
+    peers: Vec<PeerEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PeerEntry {
+    peer_id: String,
+    addrs: Vec<AddrEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AddrEntry {
+    addr: String,
+    #[serde(flatten)]
+    stats: AddrStats,
+}
+
+type Book = BTreeMap<PeerId, BTreeMap<Multiaddr, AddrStats>>;
+
+/// Verified addresses per peer with their dial history. Cheap to clone; clones share the book.
+#[derive(Clone, Debug, Default)]
+pub struct AddrBook {
+    inner: Arc<RwLock<Book>>,
+}
+
+impl AddrBook {
+    /// Load a book saved with `save`; a missing file is an empty book. Entries that no longer
+    /// parse are skipped.
+    pub fn load(path: &Path) -> Result<Self, AddrBookError> {
+        let shown = path.display().to_string();
+        let raw = match std::fs::read(path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(AddrBookError::Io {
+                    path: shown,
+                    reason: e.to_string(),
+                })
+            }
+        };
+        let file: PeersFile =
+            serde_json::from_slice(&raw).map_err(|e| AddrBookError::Malformed {
+                path: shown.clone(),
+                reason: e.to_string(),
+            })?;
+        if file.version != FILE_VERSION {
+            return Err(AddrBookError::Malformed {
+                path: shown,
+                reason: format!("unsupported version {}", file.version),
+            });
+        }
+        let mut book = Book::new();
+        for entry in file.peers {
+            let Ok(peer) = entry.peer_id.parse::<PeerId>() else {
+                continue;
+            };
+            for a in entry.addrs {
+                if let Ok(addr) = a.addr.parse::<Multiaddr>() {
+                    book.entry(peer).or_default().insert(addr, a.stats);
+                }
+            }
+        }
+        Ok(Self {
+            inner: Arc::new(RwLock::new(book)),
+        })
+    }
+
+    /// Write the book to `path` (through a temporary file, so a crash leaves the old copy).
+    pub fn save(&self, path: &Path) -> Result<(), AddrBookError> {
+        let file = {
+            let g = self.inner.read().unwrap_or_else(|e| e.into_inner());
+            PeersFile {
+                version: FILE_VERSION,
+                peers: g
+                    .iter()
+                    .map(|(peer, addrs)| PeerEntry {
+                        peer_id: peer.to_string(),
+                        addrs: addrs
+                            .iter()
+                            .map(|(addr, stats)| AddrEntry {
+                                addr: addr.to_string(),
+                                stats: *stats,
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            }
+        };
+        let io_err = |e: std::io::Error| AddrBookError::Io {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        };
+        let json = serde_json::to_vec(&file).map_err(|e| AddrBookError::Malformed {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, json)
+            .and_then(|()| std::fs::rename(&tmp, path))
+            .map_err(io_err)
+    }
+
+    /// Record a verified address; false if it was known already.
+    pub fn add(&self, peer: PeerId, addr: Multiaddr) -> bool {
+        let known = self.contains(&peer, &addr);
+        self.record_success(peer, addr, now_ms());
+        !known
+    }
+
+    /// A dial to `addr` reached `peer` at `now_ms`. Adds the address if it is new.
+    pub fn record_success(&self, peer: PeerId, addr: Multiaddr, now_ms: u64) {
+        let mut g = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        let stats = g.entry(peer).or_default().entry(addr).or_default();
+        stats.last_seen_ms = stats.last_seen_ms.max(now_ms);
+        stats.successes = stats.successes.saturating_add(1);
+    }
+
+    /// A dial to a known address of `peer` failed. Unknown addresses are not added.
+    pub fn record_failure(&self, peer: &PeerId, addr: &Multiaddr) {
+        let mut g = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(stats) = g.get_mut(peer).and_then(|a| a.get_mut(addr)) {
+            stats.failures = stats.failures.saturating_add(1);
+        }
+    }
+
+    /// Drop addresses not reached within `ttl` of `now_ms`, and peers left without any.
+    /// Returns how many addresses went.
+    pub fn expire(&self, now_ms: u64, ttl: Duration) -> usize {
+        let cutoff = now_ms.saturating_sub(ttl.as_millis() as u64);
+        let mut g = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        let mut removed = 0;
+        g.retain(|_, addrs| {
+            let before = addrs.len();
+            addrs.retain(|_, s| s.last_seen_ms >= cutoff);
+            removed += before - addrs.len();
+            !addrs.is_empty()
+        });
+        removed
+    }
+
+    /// Up to `n` peers to reconnect to, with their best address: highest quality first, then
+    /// most recently reached.
+    pub fn best(&self, n: usize) -> Vec<(PeerId, Multiaddr)> {
+        let g = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        let mut ranked: Vec<(PeerId, &Multiaddr, &AddrStats)> = g
+            .iter()
+            .filter_map(|(peer, addrs)| {
+                addrs
+                    .iter()
+                    .max_by(|a, b| rank(a.1, b.1))
+                    .map(|(addr, stats)| (*peer, addr, stats))
+            })
+            .collect();
+        ranked.sort_by(|a, b| rank(b.2, a.2));
+        ranked
+            .into_iter()
+            .take(n)
+            .map(|(peer, addr, _)| (peer, addr.clone()))
+            .collect()
+    }
+
+    /// Whether `addr` was verified for `peer`.
+    pub fn contains(&self, peer: &PeerId, addr: &Multiaddr) -> bool {
+        let g = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        g.get(peer).is_some_and(|s| s.contains_key(addr))
+    }
+
+    /// Dial history of `addr`, if it is in the book.
+    pub fn stats(&self, peer: &PeerId, addr: &Multiaddr) -> Option<AddrStats> {
+        let g = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        g.get(peer).and_then(|s| s.get(addr)).copied()
+    }
+
+    /// Verified addresses of `peer`, sorted.
+    pub fn addresses(&self, peer: &PeerId) -> Vec<Multiaddr> {
+        let g = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        g.get(peer)
+            .map(|s| s.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Peers with at least one verified address.
+    pub fn peers(&self) -> Vec<PeerId> {
+        let g = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        g.keys().copied().collect()
+    }
+
+    /// Verified addresses over all peers.
+    pub fn len(&self) -> usize {
+        let g = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        g.values().map(BTreeMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn count(&self, peer: &PeerId) -> usize {
+        let g = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        g.get(peer).map_or(0, BTreeMap::len)
+    }
+}
+
+fn rank(a: &AddrStats, b: &AddrStats) -> std::cmp::Ordering {
+    a.quality()
+        .total_cmp(&b.quality())
+        .then(a.last_seen_ms.cmp(&b.last_seen_ms))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Limits of dial-back verification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DialBackConfig {
+    /// Verified addresses kept per peer; further advertisements are not dialed.
+    pub max_addrs_per_peer: usize,
+    /// Dial-backs in flight over all peers.
+    pub max_pending: usize,
+    /// Consecutive failed dial-backs after which each failure is penalized.
+    pub fail_threshold: u32,
+}
+
+impl Default for DialBackConfig {
+    fn default() -> Self {
+        Self {
+            max_addrs_per_peer: 4,
+            max_pending: 16,
+            fail_threshold: 3,
+        }
+    }
+}
+
+/// Why an advertised address was not dialed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Skip {
+    /// Verified already, or a dial-back to it is in flight.
+    Known,
+    /// Not a TCP address on the IP the peer is connected from.
+    Foreign,
+    /// The peer has `max_addrs_per_peer` addresses, or `max_pending` dials are in flight.
+    Full,
+}
+
+/// Result of a dial-back that failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Failure {
+    pub peer: PeerId,
+    /// Consecutive failures of the peer, this one included.
+    pub consecutive: u32,
+    /// The peer reached `fail_threshold`; score it as misbehaving.
+    pub penalize: bool,
+}
+
+/// Dial-back policy and the dials in flight.
+#[derive(Debug, Default)]
+pub struct DialBack {
+    cfg: DialBackConfig,
+    /// Remote IP of each connected peer's first connection.
+    remote_ips: HashMap<PeerId, IpAddr>,
+    pending: HashMap<ConnectionId, (PeerId, Multiaddr)>,
+    failures: HashMap<PeerId, u32>,
+}
+
+impl DialBack {
+    pub fn new(cfg: DialBackConfig) -> Self {
+        Self {
+            cfg,
+            ..Self::default()
+        }
+    }
+
+    /// A connection to `peer` came up from `ip`. The first one fixes where its advertised
+    /// addresses may point.
+    pub fn connected(&mut self, peer: PeerId, ip: Option<IpAddr>) {
+        if let Some(ip) = ip {
+            self.remote_ips.entry(peer).or_insert(ip);
+        }
+    }
+
+    /// The last connection to `peer` closed. Failure counts survive reconnects.
+    pub fn disconnected(&mut self, peer: &PeerId) {
+        self.remote_ips.remove(peer);
+    }
+
+    /// Split what `peer` advertised into addresses to dial back and skipped ones.
+    pub fn candidates(
+        &self,
+        book: &AddrBook,
+        peer: &PeerId,
+        advertised: &[Multiaddr],
+    ) -> (Vec<Multiaddr>, Vec<(Multiaddr, Skip)>) {
+        let remote = self.remote_ips.get(peer);
+        let pending_for_peer = self.pending.values().filter(|(p, _)| p == peer).count();
+        let mut room = self
+            .cfg
+            .max_addrs_per_peer
+            .saturating_sub(book.count(peer) + pending_for_peer);
+        let mut slots = self.cfg.max_pending.saturating_sub(self.pending.len());
+        let mut dial = Vec::new();
+        let mut skipped = Vec::new();
+        for addr in advertised {
+            let known = book.contains(peer, addr)
+                || dial.contains(addr)
+                || self.pending.values().any(|(p, a)| p == peer && a == addr);
+            let skip = if known {
+                Some(Skip::Known)
+            } else if !is_tcp(addr) || remote.is_none() || ip_of(addr).as_ref() != remote {
+                Some(Skip::Foreign)
+            } else if room == 0 || slots == 0 {
+                Some(Skip::Full)
+            } else {
+                None
+            };
+            match skip {
+                Some(s) => skipped.push((addr.clone(), s)),
+                None => {
+                    room -= 1;
+                    slots -= 1;
+                    dial.push(addr.clone());
+                }
+            }
+        }
+        (dial, skipped)
+    }
+
+    /// A dial-back to `addr` was started on `connection`.
+    pub fn started(&mut self, connection: ConnectionId, peer: PeerId, addr: Multiaddr) {
+        self.pending.insert(connection, (peer, addr));
+    }
+
+    /// Whether `connection` is a dial-back.
+    pub fn is_pending(&self, connection: &ConnectionId) -> bool {
+        self.pending.contains_key(connection)
+    }
+
+    /// `connection` reached `peer`. Returns the verified address, or `None` when the connection
+    /// was not a dial-back.
+    pub fn succeeded(&mut self, connection: &ConnectionId) -> Option<(PeerId, Multiaddr)> {
+        let (peer, addr) = self.pending.remove(connection)?;
+        self.failures.remove(&peer);
+        Some((peer, addr))
+    }
+
+    /// `connection` failed, or authenticated a different peer. `None` when it was not a
+    /// dial-back.
+    pub fn failed(&mut self, connection: &ConnectionId) -> Option<Failure> {
+        let (peer, _) = self.pending.remove(connection)?;
+        let consecutive = self.failures.entry(peer).or_default();
+        *consecutive = consecutive.saturating_add(1);
+        Some(Failure {
+            peer,
+            consecutive: *consecutive,
+            penalize: *consecutive >= self.cfg.fail_threshold,
+        })
+    }
+
+    /// Dial-backs in flight.
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+fn ip_of(addr: &Multiaddr) -> Option<IpAddr> {
+    addr.iter().find_map(|p| match p {
+        Protocol::Ip4(a) => Some(IpAddr::V4(a)),
+        Protocol::Ip6(a) => Some(IpAddr::V6(a)),
+        _ => None,
+    })
+}
+
+fn is_tcp(addr: &Multiaddr) -> bool {
+    addr.iter().any(|p| matches!(p, Protocol::Tcp(_)))
+}
@@ -0,0 +1,251 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! PeerId churn per IP, against ban evasion.
+//!
+//! Bans in `peer_score` are keyed by peer id, and a new id costs an attacker one key
+//! generation. `ChurnTracker` links ids by the address they connect from: it remembers the
+//! distinct peer ids seen inbound from each IP and each subnet (IPv4 /24, IPv6 /64) over a
+//! sliding `window`, and the IPs banned peers were connected from. A new id from such an IP is
+//! a suspected evasion. The IP is banned for `ip_ban_duration` once it shows more than
+//! `max_ids_per_ip` ids, or `max_suspected` suspected evasions; a subnet once it shows more
+//! than `max_ids_per_subnet` ids. While an address is banned every inbound connection from it
+//! is refused, whatever peer id it presents.
+
+use libp2p::PeerId;
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// Churn limits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChurnParams {
+    /// How long a peer id, or a ban of a peer, counts against its IP.
+    pub window: Duration,
+    /// Distinct peer ids from one IP within `window` before the IP is banned.
+    pub max_ids_per_ip: usize,
+    /// Distinct peer ids from one subnet within `window` before the subnet is banned.
+    pub max_ids_per_subnet: usize,
+    /// New peer ids from an IP whose peer was banned before the IP is banned.
+    pub max_suspected: usize,
+    pub ip_ban_duration: Duration,
+}
+
+impl Default for ChurnParams {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(600),
+            max_ids_per_ip: 8,
+            max_ids_per_subnet: 32,
+            max_suspected: 2,
+            ip_ban_duration: Duration::from_secs(1800),
+        }
+    }
+}
+
+/// An IP, or the subnet around one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AddrKey {
+    Ip(IpAddr),
+    /// Network address of the /24 (IPv4) or /64 (IPv6) the IP is in.
+    Subnet(IpAddr),
+}
+
+impl AddrKey {
+    pub fn subnet_of(ip: IpAddr) -> Self {
+        match ip {
+            IpAddr::V4(v4) => {
+                let [a, b, c, _] = v4.octets();
+                AddrKey::Subnet(IpAddr::from([a, b, c, 0]))
+            }
+            IpAddr::V6(v6) => {
+                let mut segs = v6.segments();
+                segs[4..].fill(0);
+                AddrKey::Subnet(IpAddr::from(segs))
+            }
+        }
+    }
+
+    /// Whether `ip` is this IP or in this subnet.
+    pub fn covers(&self, ip: IpAddr) -> bool {
+        match self {
+            AddrKey::Ip(own) => *own == ip,
+            AddrKey::Subnet(_) => Self::subnet_of(ip) == *self,
+        }
+    }
+}
+
+/// What to do with an inbound connection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChurnVerdict {
+    Allow,
+    /// A new id from an IP a banned peer used. Allowed until `max_suspected`.
+    Suspected,
+    /// The IP or subnet is banned. `escalated` holds the address this connection just got
+    /// banned, so connections already open from it can be closed.
+    Refused {
+        escalated: Option<AddrKey>,
+    },
+}
+
+#[derive(Default)]
+struct AddrHistory {
+    /// Peer ids and when each last connected, oldest first.
+    ids: VecDeque<(Instant, PeerId)>,
+    /// Suspected evasions.
+    suspected: VecDeque<Instant>,
+    /// When a peer connected from here was last banned.
+    tainted: Option<Instant>,
+}
+
+impl AddrHistory {
+    fn prune(&mut self, cutoff: Instant) {
+        while self.ids.front().is_some_and(|(t, _)| *t < cutoff) {
+            self.ids.pop_front();
+        }
+        while self.suspected.front().is_some_and(|t| *t < cutoff) {
+            self.suspected.pop_front();
+        }
+        if self.tainted.is_some_and(|t| t < cutoff) {
+            self.tainted = None;
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.ids.is_empty() && self.suspected.is_empty() && self.tainted.is_none()
+    }
+
+    /// Record `peer`; true if it was not seen within the window.
+    fn touch(&mut self, peer: PeerId, now: Instant) -> bool {
+        let known = match self.ids.iter().position(|(_, p)| *p == peer) {
+            Some(i) => {
+                self.ids.remove(i);
+                true
+            }
+            None => false,
+        };
+        self.ids.push_back((now, peer));
+        !known
+    }
+}
+
+/// Peer ids per IP and subnet, and the addresses banned for churning them.
+#[derive(Default)]
+pub struct ChurnTracker {
+    params: ChurnParams,
+    history: HashMap<AddrKey, AddrHistory>,
+    /// IP of every connected peer, to find where a banned peer came from.
+    peer_ips: HashMap<PeerId, IpAddr>,
+    banned: HashMap<AddrKey, Instant>,
+}
+
+impl ChurnTracker {
+    pub fn new(params: ChurnParams) -> Self {
+        Self {
+            params,
+            ..Self::default()
+        }
+    }
+
+    /// A peer connected to us from `ip`.
+    pub fn on_inbound(&mut self, peer: PeerId, ip: IpAddr, now: Instant) -> ChurnVerdict {
+        if self.banned_addr(ip).is_some() {
+            return ChurnVerdict::Refused { escalated: None };
+        }
+        let cutoff = now.checked_sub(self.params.window).unwrap_or(now);
+        let ip_key = AddrKey::Ip(ip);
+        let subnet_key = AddrKey::subnet_of(ip);
+
+        let subnet = self.history.entry(subnet_key).or_default();
+        subnet.prune(cutoff);
+        subnet.touch(peer, now);
+        let subnet_ids = subnet.ids.len();
+
+        let host = self.history.entry(ip_key).or_default();
+        host.prune(cutoff);
+        let new_id = host.touch(peer, now);
+        let suspected = new_id && host.tainted.is_some();
+        if suspected {
+            host.suspected.push_back(now);
+        }
+        let (ip_ids, suspected_count) = (host.ids.len(), host.suspected.len());
+
+        let escalated = if ip_ids > self.params.max_ids_per_ip
+            || suspected_count >= self.params.max_suspected.max(1)
+        {
+            Some(ip_key)
+        } else if subnet_ids > self.params.max_ids_per_subnet {
+            Some(subnet_key)
+        } else {
+            None
+        };
+        if let Some(key) = escalated {
+            let until = now.checked_add(self.params.ip_ban_duration).unwrap_or(now);
+            self.banned.insert(key, until);
+            self.history.remove(&key);
+            return ChurnVerdict::Refused { escalated };
+        }
+        if suspected {
+            ChurnVerdict::Suspected
+        } else {
+            ChurnVerdict::Allow
+        }
+    }
+
+    /// A connection to `peer` from `ip` was admitted (either direction).
+    pub fn connected(&mut self, peer: PeerId, ip: IpAddr) {
+        self.peer_ips.insert(peer, ip);
+    }
+
+    /// The last connection to `peer` closed.
+    pub fn disconnected(&mut self, peer: &PeerId) {
+        self.peer_ips.remove(peer);
+    }
+
+    /// `peer` was banned; new ids from its IP are now suspect for a window.
+    pub fn on_banned(&mut self, peer: &PeerId, now: Instant) {
+        if let Some(ip) = self.peer_ips.get(peer) {
+            self.history.entry(AddrKey::Ip(*ip)).or_default().tainted = Some(now);
+        }
+    }
+
+    /// The banned IP or subnet covering `ip`, if any.
+    pub fn banned_addr(&self, ip: IpAddr) -> Option<AddrKey> {
+        self.banned.keys().copied().find(|k| k.covers(ip))
+    }
+
+    /// Banned IPs and subnets.
+    pub fn banned_len(&self) -> usize {
+        self.banned.len()
+    }
+
+    /// Lift address bans that ended by `now` and forget history older than the window.
+    /// Returns the addresses let back in.
+    pub fn expire(&mut self, now: Instant) -> Vec<AddrKey> {
+        let expired: Vec<AddrKey> = self
+            .banned
+            .iter()
+            .filter(|(_, until)| **until <= now)
+            .map(|(k, _)| *k)
+            .collect();
+        for k in &expired {
+            self.banned.remove(k);
+        }
+        let cutoff = now.checked_sub(self.params.window).unwrap_or(now);
+        self.history.retain(|_, h| {
+            h.prune(cutoff);
+            !h.is_empty()
+        });
+        expired
+    }
+}
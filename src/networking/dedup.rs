@@ -0,0 +1,100 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Deduplication of decoded consensus messages.
+//!
+//! Gossipsub drops repeats of the same gossip message, but the same vote arriving under a
+//! different encoding (bincode v1 vs v2, protobuf) or republished by another peer is a new
+//! message to it. After decode, `content_key` hashes the canonical encoding, which is the
+//! same whatever codec carried it, and `SeenCache` drops keys seen recently.
+//!
+//! The key covers every field, signature included. A key of only type, voter, height, round
+//! and counter would let a forged copy with a bad signature get in first and shadow the real
+//! vote.
+
+use crate::core::types::{encode_canonical, CodecError, ConsensusMsg, H256};
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Keys remembered at most; the oldest are forgotten first.
+pub const DEFAULT_SEEN_CAPACITY: usize = 16 * 1024;
+/// How long a key is remembered.
+pub const DEFAULT_SEEN_TTL: Duration = Duration::from_secs(120);
+
+/// Canonical content key of a decoded message.
+pub fn content_key(msg: &ConsensusMsg) -> Result<H256, CodecError> {
+    let mut ctx = ring::digest::Context::new(&ring::digest::SHA256);
+    ctx.update(b"Amunchain-Content-v1");
+    ctx.update(&encode_canonical(msg)?);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(ctx.finish().as_ref());
+    Ok(H256::from_bytes(out))
+}
+
+/// Bounded set of recently seen content keys.
+pub struct SeenCache {
+    capacity: usize,
+    ttl: Duration,
+    keys: HashSet<H256>,
+    order: VecDeque<(Instant, H256)>,
+}
+
+impl SeenCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            ttl,
+            keys: HashSet::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Record `key`; false if it was already seen within the TTL.
+    pub fn insert(&mut self, key: H256, now: Instant) -> bool {
+        self.expire(now);
+        if !self.keys.insert(key) {
+            return false;
+        }
+        self.order.push_back((now, key));
+        while self.order.len() > self.capacity {
+            if let Some((_, old)) = self.order.pop_front() {
+                self.keys.remove(&old);
+            }
+        }
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while let Some((at, key)) = self.order.front().copied() {
+            if now.saturating_duration_since(at) < self.ttl {
+                break;
+            }
+            self.order.pop_front();
+            self.keys.remove(&key);
+        }
+    }
+}
+
+impl Default for SeenCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEEN_CAPACITY, DEFAULT_SEEN_TTL)
+    }
+}
@@ -0,0 +1,93 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Consensus TTL enforcement inside gossipsub.
+//!
+//! Tide rejects votes and commits whose TTL ran out, but gossipsub forwards a message before
+//! the event loop decodes it, so stale messages would still travel the whole mesh.
+//! `TtlFilter` is the gossipsub data transform: on the consensus topic it reads only the
+//! timing header (`WireCodec::peek_timing`) and fails the transform for an expired message,
+//! which gossipsub then neither delivers nor forwards. Other topics, legacy messages without a
+//! TTL, and messages whose header does not parse pass unchanged; the event loop decodes and
+//! judges those as before.
+
+use crate::networking::gossip_tuning::GossipTuning;
+use crate::networking::wire::WireCodec;
+use libp2p::gossipsub::{DataTransform, Message, RawMessage, TopicHash};
+use prometheus::IntCounter;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Gossipsub transform dropping expired consensus messages.
+#[derive(Clone)]
+pub struct TtlFilter {
+    topic: TopicHash,
+    codec: WireCodec,
+    /// `None` when enforcement is off.
+    grace_ms: Option<u64>,
+    dropped: IntCounter,
+}
+
+impl TtlFilter {
+    /// Filter for the consensus `topic`, counting drops in `dropped`.
+    pub fn new(
+        topic: TopicHash,
+        codec: WireCodec,
+        tuning: &GossipTuning,
+        dropped: IntCounter,
+    ) -> Self {
+        Self {
+            topic,
+            codec,
+            grace_ms: tuning.enforce_ttl.then_some(tuning.ttl_grace_ms),
+            dropped,
+        }
+    }
+
+    /// Whether `data` on `topic` is an expired consensus message at `now_ms`.
+    pub fn is_expired(&self, topic: &TopicHash, data: &[u8], now_ms: u64) -> bool {
+        let Some(grace_ms) = self.grace_ms else {
+            return false;
+        };
+        *topic == self.topic
+            && self
+                .codec
+                .peek_timing(data)
+                .is_some_and(|t| t.is_expired(now_ms, grace_ms))
+    }
+}
+
+impl DataTransform for TtlFilter {
+    fn inbound_transform(&self, raw: RawMessage) -> Result<Message, std::io::Error> {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+        if now_ms != 0 && self.is_expired(&raw.topic, &raw.data, now_ms) {
+            self.dropped.inc();
+            return Err(std::io::Error::other("consensus message expired"));
+        }
+        Ok(Message {
+            source: raw.source,
+            data: raw.data,
+            sequence_number: raw.sequence_number,
+            topic: raw.topic,
+        })
+    }
+
+    fn outbound_transform(
+        &self,
+        _topic: &TopicHash,
+        data: Vec<u8>,
+    ) -> Result<Vec<u8>, std::io::Error> {
+        Ok(data)
+    }
+}
@@ -0,0 +1,144 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Gossipsub mesh and heartbeat tuning (`[p2p.gossipsub]`).
+//!
+//! Small validator sets want a tight mesh and a fast heartbeat for latency; large ones want a
+//! sparser mesh and less gossip to save bandwidth. The defaults match the values the node used
+//! before they became configurable.
+
+use libp2p::gossipsub;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thiserror::Error;
+
+/// Shortest accepted heartbeat; below this the heartbeat itself dominates the CPU.
+pub const MIN_HEARTBEAT_MS: u64 = 100;
+/// Longest accepted heartbeat; beyond this the mesh repairs too slowly to be useful.
+pub const MAX_HEARTBEAT_MS: u64 = 60_000;
+
+/// Gossipsub parameters exposed in the node config.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GossipTuning {
+    /// Target number of mesh peers per topic.
+    pub mesh_n: usize,
+    /// Below this many mesh peers the heartbeat grafts more.
+    pub mesh_n_low: usize,
+    /// Above this many mesh peers the heartbeat prunes some.
+    pub mesh_n_high: usize,
+    /// Peers outside the mesh that get IHAVE gossip each heartbeat.
+    pub gossip_lazy: usize,
+    /// Heartbeats a message stays in the cache for IWANT replies.
+    pub history_length: usize,
+    /// Publish own messages to every subscribed peer, not just the mesh.
+    pub flood_publish: bool,
+    /// Heartbeat interval in milliseconds.
+    pub heartbeat_interval_ms: u64,
+    /// Drop consensus messages whose TTL ran out before forwarding them (`gossip_ttl`).
+    pub enforce_ttl: bool,
+    /// How long past `sent_ts_ms + ttl_ms` a message is still forwarded, for clock skew.
+    pub ttl_grace_ms: u64,
+}
+
+impl Default for GossipTuning {
+    fn default() -> Self {
+        Self {
+            mesh_n: 6,
+            mesh_n_low: 5,
+            mesh_n_high: 12,
+            gossip_lazy: 6,
+            history_length: 5,
+            flood_publish: true,
+            heartbeat_interval_ms: 1_000,
+            enforce_ttl: true,
+            // Tide's default clock skew allowance.
+            ttl_grace_ms: 10_000,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum GossipTuningError {
+    #[error("mesh_n_low ({low}) must be between 1 and mesh_n ({n})")]
+    MeshLow { low: usize, n: usize },
+    #[error("mesh_n_high ({high}) must be at least mesh_n ({n})")]
+    MeshHigh { high: usize, n: usize },
+    #[error("history_length must be at least 1")]
+    HistoryLength,
+    #[error("heartbeat_interval_ms ({0}) must be within {MIN_HEARTBEAT_MS}..={MAX_HEARTBEAT_MS}")]
+    Heartbeat(u64),
+    #[error("rejected by gossipsub: {0}")]
+    Rejected(String),
+}
+
+impl GossipTuningError {
+    /// The `[p2p.gossipsub]` key at fault.
+    pub fn field(&self) -> &'static str {
+        match self {
+            GossipTuningError::MeshLow { .. } => "mesh_n_low",
+            GossipTuningError::MeshHigh { .. } => "mesh_n_high",
+            GossipTuningError::HistoryLength => "history_length",
+            GossipTuningError::Heartbeat(_) => "heartbeat_interval_ms",
+            GossipTuningError::Rejected(_) => "mesh_n",
+        }
+    }
+}
+
+impl GossipTuning {
+    /// Every problem with these values; empty when they are usable.
+    pub fn validate(&self) -> Vec<GossipTuningError> {
+        let mut errors = Vec::new();
+        if self.mesh_n_low == 0 || self.mesh_n_low > self.mesh_n {
+            errors.push(GossipTuningError::MeshLow {
+                low: self.mesh_n_low,
+                n: self.mesh_n,
+            });
+        }
+        if self.mesh_n_high < self.mesh_n {
+            errors.push(GossipTuningError::MeshHigh {
+                high: self.mesh_n_high,
+                n: self.mesh_n,
+            });
+        }
+        if self.history_length == 0 {
+            errors.push(GossipTuningError::HistoryLength);
+        }
+        if !(MIN_HEARTBEAT_MS..=MAX_HEARTBEAT_MS).contains(&self.heartbeat_interval_ms) {
+            errors.push(GossipTuningError::Heartbeat(self.heartbeat_interval_ms));
+        }
+        errors
+    }
+
+    /// The gossipsub config for these values, or the first problem with them.
+    pub fn to_config(&self) -> Result<gossipsub::Config, GossipTuningError> {
+        if let Some(e) = self.validate().into_iter().next() {
+            return Err(e);
+        }
+        gossipsub::ConfigBuilder::default()
+            .validation_mode(gossipsub::ValidationMode::Permissive)
+            .heartbeat_interval(Duration::from_millis(self.heartbeat_interval_ms))
+            .mesh_n(self.mesh_n)
+            .mesh_n_low(self.mesh_n_low)
+            .mesh_n_high(self.mesh_n_high)
+            // Derived so small meshes stay valid: gossipsub wants at most half the mesh outbound
+            // and no more gossip windows than cached heartbeats.
+            .mesh_outbound_min(2.min(self.mesh_n_low).min(self.mesh_n / 2))
+            .gossip_lazy(self.gossip_lazy)
+            .history_length(self.history_length)
+            .history_gossip(3.min(self.history_length))
+            .flood_publish(self.flood_publish)
+            .build()
+            .map_err(|e| GossipTuningError::Rejected(e.to_string()))
+    }
+}
@@ -3,7 +3,17 @@
 
 //! Networking: libp2p transport and peer scoring.
 
+pub mod addr_book;
+pub mod churn;
+pub mod dedup;
+pub mod gossip_ttl;
+pub mod gossip_tuning;
 pub mod p2p;
 pub mod p2p_identity;
+pub mod peer_caps;
 pub mod peer_registry;
 pub mod peer_score;
+pub mod pex;
+pub mod proto;
+pub mod wire;
+pub mod wire_limits;
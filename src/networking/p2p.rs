@@ -5,28 +5,103 @@
 // P2P subsystem (libp2p): persistent identity + gossipsub consensus topic.
 //
 // This replaces the previous build-stub with a minimal but real networking loop.
-// - Outbound: ConsensusMsg -> gossipsub publish (bincode)
-// - Inbound: gossipsub message -> ConsensusMsg -> inbound channel
+// - Outbound: ConsensusMsg -> gossipsub publish, in the highest wire version every
+//   connected peer reads (see `wire`)
+// - Inbound: gossipsub message (any supported wire version) -> ConsensusMsg -> inbound channel
 // - Allowlist: if allow_peers non-empty, disconnect peers not in allowlist
+// - Limits: connections per remote IP and messages/sec per peer; both, and the allowlist,
+//   can be replaced at runtime through `P2pNode::tunables`
+// - Shutdown: `stop_intake` unsubscribes and closes the inbound channel (outbound publishing
+//   continues), `close` ends the task
+// - Scoring: peers sending invalid consensus messages lose score (`peer_score`); once banned
+//   they are disconnected and blacklisted in gossipsub, so their messages are dropped even when
+//   relayed by others, until the ban expires. Consensus can report peers whose decoded
+//   messages it rejected as invalid (`P2pNode::peer_reports`); reports score the same way
+// - Evidence: double-vote evidence travels on its own topic (`P2pConfig::evidence_topic`),
+//   always in the v2 envelope; undecodable or non-conflicting evidence scores like an invalid
+//   consensus message
+// - Commit sync: compact commits and vote requests travel on `wire::COMMIT_SYNC_TOPIC`;
+//   a vote request only reaches the consensus side of the peer it names
+// - Churn: inbound peer ids are tracked per IP and subnet (`churn`); an address cycling
+//   through too many ids, or new ids after one of its peers was banned, is banned as a whole
+// - Dial-back: listen addresses a peer advertises in identify are dialed back before they
+//   enter the address book (`addr_book`, `P2pNode::addresses`); peers whose advertisements
+//   keep failing are scored like invalid messages
+// - Address book: verified addresses and their dial history persist in `data_dir/peers.json`;
+//   on start the best-rated peers are dialed next to the bootstrap list
+// - Peer exchange: with an allowlist, the best-rated approved peers of the address book are
+//   shared on `pex::PEX_TOPIC`, and approved peers learned there are dialed
+// - Capabilities: identify results and per-peer gossip usage feed `peer_caps`
+//   (`P2pNode::capabilities`)
+// - TTL: expired consensus messages are dropped inside gossipsub before they are forwarded
+//   (`gossip_ttl`, `[p2p.gossipsub] enforce_ttl`)
+// - Read-only: observer nodes (`P2pConfig::read_only`) drop everything handed to them to
+//   publish, and only receive and relay
+// - Size limits: each message class has its own limit (`wire_limits`, `[p2p.limits]`), checked
+//   before decoding and before publishing; gossipsub frames are sized for the largest one
+// - Dedup: decoded consensus messages whose canonical content was seen recently are dropped
+//   (`dedup`), whichever codec or gossip message carried them
 // - Metrics: peer count gauge + banned counter + invalid msg counter
-use crate::{core::types::ConsensusMsg, monitoring::metrics::Metrics};
-use std::{collections::HashSet, path::Path, sync::Arc, time::Duration};
+//   + gossipsub mesh/topic/traffic metrics (exported via Metrics::gossipsub)
+use crate::core::consensus::compact::CommitSync;
+use crate::networking::addr_book::{
+    AddrBook, DialBack, DialBackConfig, Skip, ADDR_TTL, PEERS_FILE,
+};
+use crate::networking::churn::{ChurnParams, ChurnTracker, ChurnVerdict};
+use crate::networking::dedup::{content_key, SeenCache};
+use crate::networking::gossip_ttl::TtlFilter;
+use crate::networking::gossip_tuning::GossipTuning;
+use crate::networking::p2p_identity::IdentitySource;
+use crate::networking::peer_caps::{PeerCapabilityMap, TopicKind};
+use crate::networking::peer_score::{Decision, PeerScore, ScoreParams};
+use crate::networking::pex::{PexMessage, PEX_TOPIC};
+use crate::networking::wire::{self, PeerVersions, WireCodec, WireError, SUPPORTED_WIRE_VERSIONS};
+use crate::networking::wire_limits::{MsgClass, WireLimits};
+use crate::node::channel::{self, ChannelConfig, Receiver, Sender};
+use crate::node::extensions::{Extensions, GossipHandler};
+use crate::{
+    core::types::{ConsensusMsg, Evidence},
+    monitoring::metrics::Metrics,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    net::IpAddr,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 use thiserror::Error;
-use tokio::sync::mpsc;
-use tracing::{info, warn};
+use tokio::sync::watch;
+use tracing::{info, warn, Instrument};
 
 use libp2p::{
     core::upgrade,
     gossipsub::{self, IdentTopic, MessageAuthenticity},
-    identify, noise, ping,
-    swarm::{NetworkBehaviour, Swarm, SwarmEvent},
+    identify,
+    multiaddr::Protocol,
+    noise, ping,
+    swarm::{
+        dial_opts::{DialOpts, PeerCondition},
+        ConnectionId, NetworkBehaviour, Swarm, SwarmEvent,
+    },
     tcp, yamux, Multiaddr, PeerId, Transport,
 };
 
 use libp2p::futures::StreamExt;
 use libp2p::swarm::Config as SwarmConfig;
 
+/// How often expired peer bans are lifted (and the peers let back into the gossip mesh).
+const BAN_EXPIRY_INTERVAL: Duration = Duration::from_secs(30);
+/// How often the address book is expired and saved.
+const ADDR_BOOK_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+/// Peers from the address book dialed on start.
+const RECONNECT_PEERS: usize = 8;
+/// How often the address book is shared on the peer exchange topic.
+const PEX_INTERVAL: Duration = Duration::from_secs(60);
+/// Dials of known or exchanged peers in flight.
+const MAX_PENDING_REDIALS: usize = 16;
+
 /// Events emitted by the P2P node.
 #[derive(Clone, Debug)]
 pub enum P2pEvent {
@@ -37,7 +112,7 @@ pub enum P2pEvent {
 }
 
 /// Receiver of P2P events.
-pub type EventRx = mpsc::Receiver<P2pEvent>;
+pub type EventRx = Receiver<P2pEvent>;
 
 #[derive(Debug, Error)]
 pub enum P2pError {
@@ -45,6 +120,8 @@ pub enum P2pError {
     Io,
     #[error("config")]
     Config,
+    #[error("identity key")]
+    Identity,
 }
 
 /// Runtime configuration for the P2P subsystem.
@@ -54,40 +131,210 @@ pub struct P2pConfig {
     pub listen_addr: String,
     /// Gossipsub topic for consensus messages.
     pub consensus_topic: String,
-    /// Max messages/sec per peer. (best-effort placeholder)
+    /// Encoding used on `consensus_topic`.
+    pub consensus_codec: WireCodec,
+    /// Gossipsub topic for misbehaviour evidence.
+    pub evidence_topic: String,
+    /// Max messages/sec per peer; excess messages are dropped.
     pub max_msg_per_sec: u32,
-    /// Maximum peers allowed from the same IP. (best-effort placeholder)
+    /// Maximum connections accepted from the same remote IP.
     pub max_peers_per_ip: usize,
     /// Data directory used for persistent identity.
     pub data_dir: String,
+    /// Where the identity key comes from.
+    pub identity: IdentitySource,
     /// Bootstrap peers.
     pub bootstrap: Vec<String>,
     /// Optional allowlist of peer ids (empty => allow all).
     pub allow_peers: Vec<String>,
+    /// Extension gossip topics and their handlers.
+    pub extensions: Option<Arc<Extensions>>,
+    /// Channel capacities and overflow policies.
+    pub channels: P2pChannels,
+    /// Gossipsub mesh and heartbeat parameters.
+    pub gossipsub: GossipTuning,
+    /// Message size limits per class.
+    pub limits: WireLimits,
+    /// Publish nothing (observer nodes): whatever is handed to the outbound channels is
+    /// dropped. Gossip from peers is still received and relayed.
+    pub read_only: bool,
+}
+
+impl P2pConfig {
+    /// The runtime-tunable part of this config.
+    pub fn tunables(&self) -> P2pTunables {
+        P2pTunables {
+            max_msg_per_sec: self.max_msg_per_sec,
+            max_peers_per_ip: self.max_peers_per_ip,
+            allow_peers: self.allow_peers.clone(),
+        }
+    }
+}
+
+/// Limits the running P2P task picks up without a restart.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct P2pTunables {
+    /// Max messages/sec per peer.
+    pub max_msg_per_sec: u32,
+    /// Max connections per remote IP.
+    pub max_peers_per_ip: usize,
+    /// Allowlist of peer ids (empty => allow all). Connected peers that drop off it are
+    /// disconnected.
+    pub allow_peers: Vec<String>,
+}
+
+/// Channels between the P2P task and the rest of the node.
+#[derive(Clone, Copy, Debug)]
+pub struct P2pChannels {
+    /// Decoded consensus messages to the consensus pump (`p2p_inbound`).
+    pub inbound: ChannelConfig,
+    /// Consensus messages to publish (`p2p_outbound`).
+    pub outbound: ChannelConfig,
+    /// Peer connect/disconnect events (`p2p_events`).
+    pub events: ChannelConfig,
+    /// Extension topic payloads to publish (`p2p_extension_outbound`).
+    pub extension: ChannelConfig,
+    /// Peers whose messages consensus rejected (`p2p_peer_reports`).
+    pub reports: ChannelConfig,
+    /// Evidence both ways (`p2p_evidence_inbound`, `p2p_evidence_outbound`).
+    pub evidence: ChannelConfig,
+    /// Compact commits and vote requests both ways (`p2p_commit_sync_inbound`,
+    /// `p2p_commit_sync_outbound`).
+    pub commit_sync: ChannelConfig,
+}
+
+impl Default for P2pChannels {
+    fn default() -> Self {
+        Self {
+            // Consensus traffic is backpressured rather than dropped.
+            inbound: ChannelConfig::blocking(1024),
+            outbound: ChannelConfig::blocking(1024),
+            // Events are informational; never stall the swarm loop on them.
+            events: ChannelConfig::lossy(128),
+            extension: ChannelConfig::lossy(1024),
+            // Reports only feed scoring; losing some under a flood is harmless.
+            reports: ChannelConfig::lossy(256),
+            // Evidence is rare, and every node that saw the offense sends its own copy.
+            evidence: ChannelConfig::lossy(256),
+            // A lost compact commit is recovered from the votes, as without compaction.
+            commit_sync: ChannelConfig::lossy(256),
+        }
+    }
+}
+
+/// Shutdown phase of the P2P task.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Phase {
+    Running,
+    Draining,
+    Closed,
 }
 
 /// Handle to interact with P2P.
 pub struct P2pNode {
-    inbound_rx: mpsc::Receiver<(Vec<u8>, ConsensusMsg)>,
-    outbound_tx: mpsc::Sender<ConsensusMsg>,
+    inbound_rx: Option<Receiver<(Vec<u8>, ConsensusMsg)>>,
+    outbound_tx: Sender<ConsensusMsg>,
+    evidence_rx: Option<Receiver<(Vec<u8>, Evidence)>>,
+    evidence_tx: Sender<Evidence>,
+    sync_rx: Option<Receiver<(Vec<u8>, CommitSync)>>,
+    sync_tx: Sender<CommitSync>,
+    extension_tx: Sender<(String, Vec<u8>)>,
+    reports_tx: Sender<Vec<u8>>,
+    tunables_tx: watch::Sender<P2pTunables>,
+    phase_tx: watch::Sender<Phase>,
+    capabilities: PeerCapabilityMap,
+    addresses: AddrBook,
+    peers_rx: watch::Receiver<usize>,
 }
 
 impl P2pNode {
-    /// Inbound consensus messages (peer_id_bytes, msg).
-    pub fn inbound(&mut self) -> &mut mpsc::Receiver<(Vec<u8>, ConsensusMsg)> {
-        &mut self.inbound_rx
+    /// Inbound consensus messages (peer_id_bytes, msg), for the one consumer that takes them.
+    /// The channel ends after `stop_intake`.
+    pub fn take_inbound(&mut self) -> Option<Receiver<(Vec<u8>, ConsensusMsg)>> {
+        self.inbound_rx.take()
     }
 
     /// Outbound channel for broadcasting consensus messages.
-    pub fn outbound(&self) -> mpsc::Sender<ConsensusMsg> {
+    pub fn outbound(&self) -> Sender<ConsensusMsg> {
         self.outbound_tx.clone()
     }
+
+    /// Inbound evidence (peer_id_bytes, evidence), decoded and checked for conflicting votes
+    /// but with signatures unverified. Ends after `stop_intake`, like `take_inbound`.
+    pub fn take_evidence_inbound(&mut self) -> Option<Receiver<(Vec<u8>, Evidence)>> {
+        self.evidence_rx.take()
+    }
+
+    /// Outbound channel for evidence to gossip on the evidence topic.
+    pub fn evidence_outbound(&self) -> Sender<Evidence> {
+        self.evidence_tx.clone()
+    }
+
+    /// Inbound compact commits, and vote requests addressed to this node (peer_id_bytes,
+    /// msg). Ends after `stop_intake`, like `take_inbound`.
+    pub fn take_commit_sync_inbound(&mut self) -> Option<Receiver<(Vec<u8>, CommitSync)>> {
+        self.sync_rx.take()
+    }
+
+    /// Outbound channel for `wire::COMMIT_SYNC_TOPIC`.
+    pub fn commit_sync_outbound(&self) -> Sender<CommitSync> {
+        self.sync_tx.clone()
+    }
+
+    /// Outbound channel for extension topics: `(topic, payload)`. Payloads for topics no
+    /// extension registered are dropped.
+    pub fn extension_outbound(&self) -> Sender<(String, Vec<u8>)> {
+        self.extension_tx.clone()
+    }
+
+    /// Report a peer (peer id bytes) whose consensus message was rejected as invalid. Each
+    /// report lowers its score like an undecodable message; enough of them ban it.
+    pub fn peer_reports(&self) -> Sender<Vec<u8>> {
+        self.reports_tx.clone()
+    }
+
+    /// Stop taking gossip: unsubscribe every topic and close the inbound channel once
+    /// messages already received are queued. Publishing still works.
+    pub fn stop_intake(&self) {
+        self.phase_tx.send_if_modified(|p| {
+            let change = *p == Phase::Running;
+            if change {
+                *p = Phase::Draining;
+            }
+            change
+        });
+    }
+
+    /// End the P2P task, closing every connection.
+    pub fn close(&self) {
+        self.phase_tx.send_replace(Phase::Closed);
+    }
+
+    /// Connected peers, what they announced in identify and what they sent.
+    pub fn capabilities(&self) -> PeerCapabilityMap {
+        self.capabilities.clone()
+    }
+
+    /// Number of connected peers (admitted ones), updated as they come and go.
+    pub fn connected_peers(&self) -> watch::Receiver<usize> {
+        self.peers_rx.clone()
+    }
+
+    /// Peer addresses verified by dialing them back.
+    pub fn addresses(&self) -> AddrBook {
+        self.addresses.clone()
+    }
+
+    /// Replace the task's limits and allowlist; takes effect on the next swarm event.
+    pub fn tunables(&self) -> watch::Sender<P2pTunables> {
+        self.tunables_tx.clone()
+    }
 }
 
 #[derive(Debug)]
 enum BehaviourEvent {
     Gossipsub(Box<gossipsub::Event>),
-    Identify(()),
+    Identify(Box<identify::Event>),
     Ping(()),
 }
 
@@ -98,8 +345,8 @@ impl From<gossipsub::Event> for BehaviourEvent {
 }
 
 impl From<identify::Event> for BehaviourEvent {
-    fn from(_e: identify::Event) -> Self {
-        Self::Identify(())
+    fn from(e: identify::Event) -> Self {
+        Self::Identify(Box::new(e))
     }
 }
 
@@ -112,11 +359,176 @@ impl From<ping::Event> for BehaviourEvent {
 #[derive(NetworkBehaviour)]
 #[behaviour(to_swarm = "BehaviourEvent")]
 struct Behaviour {
-    gossipsub: gossipsub::Behaviour,
+    gossipsub: gossipsub::Behaviour<TtlFilter>,
     identify: identify::Behaviour,
     ping: ping::Behaviour,
 }
 
+// Allowlist, per-IP connection cap and per-peer message rate.
+struct PeerLimits {
+    tunables: P2pTunables,
+    allow: HashSet<PeerId>,
+    // Accepted connections and their remote IP.
+    conns: HashMap<ConnectionId, Option<IpAddr>>,
+    // Start of the current one-second window and messages seen in it.
+    windows: HashMap<PeerId, (Instant, u32)>,
+}
+
+impl PeerLimits {
+    fn new(tunables: P2pTunables) -> Self {
+        let mut limits = Self {
+            tunables: tunables.clone(),
+            allow: HashSet::new(),
+            conns: HashMap::new(),
+            windows: HashMap::new(),
+        };
+        limits.set(tunables);
+        limits
+    }
+
+    fn set(&mut self, tunables: P2pTunables) {
+        self.allow = parse_allowlist(&tunables.allow_peers);
+        self.tunables = tunables;
+    }
+
+    fn allowed(&self, peer: &PeerId) -> bool {
+        self.allow.is_empty() || self.allow.contains(peer)
+    }
+
+    // Track the connection unless its IP is already at the cap.
+    fn admit(&mut self, id: ConnectionId, ip: Option<IpAddr>) -> bool {
+        if let Some(ip) = ip {
+            let same_ip = self.conns.values().filter(|c| **c == Some(ip)).count();
+            if same_ip >= self.tunables.max_peers_per_ip {
+                return false;
+            }
+        }
+        self.conns.insert(id, ip);
+        true
+    }
+
+    // Whether `id` had been admitted.
+    fn release(&mut self, id: ConnectionId) -> bool {
+        self.conns.remove(&id).is_some()
+    }
+
+    fn allow_message(&mut self, peer: PeerId, now: Instant) -> bool {
+        let (start, count) = self.windows.entry(peer).or_insert((now, 0));
+        if now.duration_since(*start) >= Duration::from_secs(1) {
+            *start = now;
+            *count = 0;
+        }
+        *count += 1;
+        *count <= self.tunables.max_msg_per_sec
+    }
+}
+
+fn parse_allowlist(peers: &[String]) -> HashSet<PeerId> {
+    let mut allow_set = HashSet::new();
+    for s in peers.iter().map(|x| x.trim()).filter(|x| !x.is_empty()) {
+        match s.parse::<PeerId>() {
+            Ok(pid) => {
+                allow_set.insert(pid);
+            }
+            Err(_) => {
+                warn!(peer = %s, "invalid allow_peers entry; ignoring");
+            }
+        }
+    }
+    allow_set
+}
+
+fn remote_ip(addr: &Multiaddr) -> Option<IpAddr> {
+    addr.iter().find_map(|p| match p {
+        Protocol::Ip4(a) => Some(IpAddr::V4(a)),
+        Protocol::Ip6(a) => Some(IpAddr::V6(a)),
+        _ => None,
+    })
+}
+
+/// Blacklist and disconnect a peer that `scores` just banned. New ids from its IP become
+/// suspect (`churn`).
+fn ban(
+    swarm: &mut Swarm<Behaviour>,
+    scores: &PeerScore,
+    churn: &mut ChurnTracker,
+    metrics: &Metrics,
+    peer_id: PeerId,
+) {
+    warn!(%peer_id, "peer banned; blacklisting");
+    churn.on_banned(&peer_id, Instant::now());
+    metrics.p2p_banned_total.inc();
+    metrics.p2p_banned_peers.set(scores.banned_len() as i64);
+    swarm.behaviour_mut().gossipsub.blacklist_peer(&peer_id);
+    let _ = swarm.disconnect_peer_id(peer_id);
+}
+
+/// Dial `addr` of `peer_id` unless connected, tracking the dial in `redials` so its outcome
+/// reaches the address book.
+fn dial_peer(
+    swarm: &mut Swarm<Behaviour>,
+    redials: &mut HashMap<ConnectionId, (PeerId, Multiaddr)>,
+    peer_id: PeerId,
+    addr: Multiaddr,
+) -> bool {
+    let opts = DialOpts::peer_id(peer_id)
+        .addresses(vec![addr.clone()])
+        .condition(PeerCondition::Disconnected)
+        .build();
+    let connection_id = opts.connection_id();
+    match swarm.dial(opts) {
+        Ok(()) => {
+            info!(%peer_id, %addr, "dialing known peer");
+            redials.insert(connection_id, (peer_id, addr));
+            true
+        }
+        Err(e) => {
+            warn!(%peer_id, %addr, err = ?e, "dial known peer failed");
+            false
+        }
+    }
+}
+
+/// Dial back the addresses `peer_id` advertised that `dial_back` accepts as candidates.
+fn verify_addresses(
+    swarm: &mut Swarm<Behaviour>,
+    dial_back: &mut DialBack,
+    book: &AddrBook,
+    metrics: &Metrics,
+    peer_id: PeerId,
+    advertised: &[Multiaddr],
+) {
+    let (dial, skipped) = dial_back.candidates(book, &peer_id, advertised);
+    for (addr, reason) in skipped {
+        if reason == Skip::Known {
+            continue;
+        }
+        tracing::debug!(%peer_id, %addr, ?reason, "advertised address not dialed back");
+        metrics
+            .p2p_dialback_total
+            .with_label_values(&["skipped"])
+            .inc();
+    }
+    for addr in dial {
+        let opts = DialOpts::peer_id(peer_id)
+            .addresses(vec![addr.clone()])
+            .condition(PeerCondition::Always)
+            .build();
+        let connection_id = opts.connection_id();
+        match swarm.dial(opts) {
+            Ok(()) => dial_back.started(connection_id, peer_id, addr),
+            Err(e) => warn!(%peer_id, %addr, err = ?e, "dial-back not started"),
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
 fn ensure_dir(path: &str) -> Result<(), P2pError> {
     let p = Path::new(path);
     if !p.exists() {
@@ -131,38 +543,84 @@ pub fn spawn_p2p(
     metrics: Arc<Metrics>,
 ) -> Result<(P2pNode, EventRx, tokio::task::JoinHandle<()>), P2pError> {
     ensure_dir(&cfg.data_dir)?;
+    let gcfg = cfg.gossipsub.to_config().map_err(|e| {
+        warn!(error = %e, "invalid gossipsub tuning");
+        P2pError::Config
+    })?;
+    if let Some(e) = cfg.limits.validate().into_iter().next() {
+        warn!(error = %e, "invalid message size limits");
+        return Err(P2pError::Config);
+    }
+    let gcfg = gossipsub::ConfigBuilder::from(gcfg)
+        .max_transmit_size(cfg.limits.transmit_size())
+        .build()
+        .map_err(|e| {
+            warn!(error = %e, "invalid gossipsub config");
+            P2pError::Config
+        })?;
+    let wire_limits = cfg.limits.clone();
+    let read_only = cfg.read_only;
 
     // Persistent identity lives in networking::p2p_identity (already in your project).
     let (local_peer_id, id_keys) =
-        crate::networking::p2p_identity::load_or_create_identity(&cfg.data_dir)
-            .map_err(|_| P2pError::Io)?;
-
-    // Build allowlist set.
-    let mut allow_set: HashSet<PeerId> = HashSet::new();
-    for s in cfg
-        .allow_peers
-        .iter()
-        .map(|x| x.trim())
-        .filter(|x| !x.is_empty())
-    {
-        match s.parse::<PeerId>() {
-            Ok(pid) => {
-                allow_set.insert(pid);
-            }
-            Err(_) => {
-                warn!(peer = %s, "invalid allow_peers entry; ignoring");
-            }
-        }
-    }
+        crate::networking::p2p_identity::load_identity(&cfg.data_dir, cfg.identity).map_err(
+            |e| {
+                warn!(error = ?e, source = ?cfg.identity, "p2p identity");
+                P2pError::Identity
+            },
+        )?;
+
+    let mut limits = PeerLimits::new(cfg.tunables());
+    let mut versions = PeerVersions::new(SUPPORTED_WIRE_VERSIONS);
+    let mut scores = PeerScore::new(ScoreParams::default());
+    let mut churn = ChurnTracker::new(ChurnParams::default());
+    let mut seen = SeenCache::default();
+    let (tunables_tx, mut tunables_rx) = watch::channel(cfg.tunables());
+    let (phase_tx, mut phase_rx) = watch::channel(Phase::Running);
+    let (peers_tx, peers_rx) = watch::channel(0usize);
+    let capabilities = PeerCapabilityMap::default();
+    let caps = capabilities.clone();
+    let peers_path = Path::new(&cfg.data_dir).join(PEERS_FILE);
+    let addresses = AddrBook::load(&peers_path).unwrap_or_else(|e| {
+        warn!(error = %e, "address book unreadable; starting empty");
+        AddrBook::default()
+    });
+    addresses.expire(now_ms(), ADDR_TTL);
+    metrics.p2p_known_addrs.set(addresses.len() as i64);
+    let book = addresses.clone();
+    let mut dial_back = DialBack::new(DialBackConfig::default());
 
     // Channels
-    let (in_tx, in_rx) = mpsc::channel::<(Vec<u8>, ConsensusMsg)>(1024);
-    let (out_tx, mut out_rx) = mpsc::channel::<ConsensusMsg>(1024);
-    let (ev_tx, ev_rx) = mpsc::channel::<P2pEvent>(128);
+    let ch = cfg.channels;
+    let m = Some(metrics.as_ref());
+    let (in_tx, in_rx) = channel::channel::<(Vec<u8>, ConsensusMsg)>("p2p_inbound", ch.inbound, m);
+    let (out_tx, mut out_rx) = channel::channel::<ConsensusMsg>("p2p_outbound", ch.outbound, m);
+    let (ev_tx, ev_rx) = channel::channel::<P2pEvent>("p2p_events", ch.events, m);
+    let (ext_tx, mut ext_rx) =
+        channel::channel::<(String, Vec<u8>)>("p2p_extension_outbound", ch.extension, m);
+    let (reports_tx, mut reports_rx) =
+        channel::channel::<Vec<u8>>("p2p_peer_reports", ch.reports, m);
+    let (ev_in_tx, ev_in_rx) =
+        channel::channel::<(Vec<u8>, Evidence)>("p2p_evidence_inbound", ch.evidence, m);
+    let (ev_out_tx, mut ev_out_rx) =
+        channel::channel::<Evidence>("p2p_evidence_outbound", ch.evidence, m);
+    let (sync_in_tx, sync_in_rx) =
+        channel::channel::<(Vec<u8>, CommitSync)>("p2p_commit_sync_inbound", ch.commit_sync, m);
+    let (sync_out_tx, mut sync_out_rx) =
+        channel::channel::<CommitSync>("p2p_commit_sync_outbound", ch.commit_sync, m);
 
     let listen_addr = cfg.listen_addr.clone();
     let topic_name = cfg.consensus_topic.clone();
+    let evidence_topic_name = cfg.evidence_topic.clone();
+    let codec = cfg.consensus_codec;
+    let ttl_filter = TtlFilter::new(
+        IdentTopic::new(topic_name.clone()).hash(),
+        codec,
+        &cfg.gossipsub,
+        metrics.p2p_expired_dropped_total.clone(),
+    );
     let bootstrap = cfg.bootstrap.clone();
+    let extensions = cfg.extensions.clone();
 
     // Spawn swarm loop
     let join = tokio::spawn(async move {
@@ -182,29 +640,63 @@ pub fn spawn_p2p(
             .boxed();
 
         // --- Gossipsub ---
-        let gcfg = gossipsub::ConfigBuilder::default()
-            .validation_mode(gossipsub::ValidationMode::Permissive)
-            .heartbeat_interval(Duration::from_secs(1))
-            .build()
-            .unwrap_or_else(|_| gossipsub::Config::default());
-
-        let mut gossipsub =
-            match gossipsub::Behaviour::new(MessageAuthenticity::Signed(id_keys.clone()), gcfg) {
-                Ok(v) => v,
-                Err(_) => {
-                    warn!("failed to create gossipsub behaviour");
-                    return;
-                }
-            };
+        let mut gossip_registry =
+            prometheus_client::registry::Registry::with_prefix("amunchain_gossipsub");
+
+        let mut gossipsub = match gossipsub::Behaviour::new_with_transform(
+            MessageAuthenticity::Signed(id_keys.clone()),
+            gcfg,
+            Some((&mut gossip_registry, gossipsub::MetricsConfig::default())),
+            ttl_filter,
+        ) {
+            Ok(v) => v,
+            Err(_) => {
+                warn!("failed to create gossipsub behaviour");
+                return;
+            }
+        };
+
+        // Replaces the metrics of an earlier p2p task on the same `Metrics`.
+        if let Ok(mut registry) = metrics.gossipsub.lock() {
+            *registry = gossip_registry;
+        }
 
         let topic = IdentTopic::new(topic_name.clone());
         if let Err(e) = gossipsub.subscribe(&topic) {
             warn!(err = ?e, "failed to subscribe topic");
         }
+        let evidence_topic = IdentTopic::new(evidence_topic_name.clone());
+        if let Err(e) = gossipsub.subscribe(&evidence_topic) {
+            warn!(err = ?e, "failed to subscribe evidence topic");
+        }
+        let sync_topic = IdentTopic::new(wire::COMMIT_SYNC_TOPIC);
+        if let Err(e) = gossipsub.subscribe(&sync_topic) {
+            warn!(err = ?e, "failed to subscribe commit sync topic");
+        }
+        let pex_topic = IdentTopic::new(PEX_TOPIC);
+        if let Err(e) = gossipsub.subscribe(&pex_topic) {
+            warn!(err = ?e, "failed to subscribe pex topic");
+        }
+
+        // Extension topics.
+        let mut ext_handlers: HashMap<gossipsub::TopicHash, Arc<dyn GossipHandler>> =
+            HashMap::new();
+        let mut ext_topics: HashMap<String, IdentTopic> = HashMap::new();
+        if let Some(ext) = extensions.as_ref() {
+            for (name, handler) in ext.topics() {
+                let t = IdentTopic::new(name.clone());
+                if let Err(e) = gossipsub.subscribe(&t) {
+                    warn!(topic = %name, err = ?e, "failed to subscribe extension topic");
+                    continue;
+                }
+                ext_handlers.insert(t.hash(), handler.clone());
+                ext_topics.insert(name.clone(), t);
+            }
+        }
 
         // Identify + Ping
         let identify = identify::Behaviour::new(identify::Config::new(
-            "amunchain/1.0.0".to_string(),
+            wire::identify_protocol(SUPPORTED_WIRE_VERSIONS),
             id_keys.public(),
         ));
 
@@ -255,22 +747,72 @@ pub fn spawn_p2p(
             }
         }
 
-        info!(%local_peer_id, topic = %topic_name, "p2p loop started");
+        // Peers known from earlier runs, best-rated first. Outcomes feed their dial history.
+        let mut redials: HashMap<ConnectionId, (PeerId, Multiaddr)> = HashMap::new();
+        for (peer_id, addr) in book.best(RECONNECT_PEERS) {
+            if peer_id != local_peer_id {
+                dial_peer(&mut swarm, &mut redials, peer_id, addr);
+            }
+        }
+
+        info!(%local_peer_id, topic = %topic_name, ?codec, "p2p loop started");
 
         // Ensure gauge starts at 0
         metrics.p2p_peers.set(0);
+        metrics.p2p_listen_addrs.set(0);
+        metrics.p2p_wire_version.set(versions.send_version().into());
+
+        // Dropped when intake stops, which ends the consumer's inbound channel.
+        let mut in_tx = Some(in_tx);
+        let mut ev_in_tx = Some(ev_in_tx);
+        let mut sync_in_tx = Some(sync_in_tx);
+        let local_peer_bytes = local_peer_id.to_bytes();
+        let mut ban_expiry = tokio::time::interval(BAN_EXPIRY_INTERVAL);
+        let mut book_save = tokio::time::interval(ADDR_BOOK_SAVE_INTERVAL);
+        let mut pex_tick = tokio::time::interval(PEX_INTERVAL);
 
         loop {
             tokio::select! {
+                Ok(()) = phase_rx.changed() => {
+                    let phase = *phase_rx.borrow_and_update();
+                    match phase {
+                        Phase::Running => {}
+                        Phase::Draining => {
+                            info!("p2p intake stopped");
+                            let gossipsub = &mut swarm.behaviour_mut().gossipsub;
+                            let _ = gossipsub.unsubscribe(&topic);
+                            let _ = gossipsub.unsubscribe(&evidence_topic);
+                            let _ = gossipsub.unsubscribe(&sync_topic);
+                            let _ = gossipsub.unsubscribe(&pex_topic);
+                            for t in ext_topics.values() {
+                                let _ = gossipsub.unsubscribe(t);
+                            }
+                            in_tx = None;
+                            ev_in_tx = None;
+                            sync_in_tx = None;
+                        }
+                        Phase::Closed => {
+                            info!("p2p closed");
+                            break;
+                        }
+                    }
+                }
+
                 maybe_msg = out_rx.recv() => {
                     match maybe_msg {
+                        Some(_) if read_only => {}
                         Some(msg) => {
-                            match bincode::serialize(&msg) {
+                            match codec.encode_within(&msg, versions.send_version(), &wire_limits) {
                                 Ok(bytes) => {
+                                    let _timer = metrics.p2p_publish_seconds.start_timer();
                                     if let Err(e) = swarm.behaviour_mut().gossipsub.publish(topic.clone(), bytes) {
                                         warn!(err=?e, "gossipsub publish failed");
                                     }
                                 }
+                                Err(WireError::TooLarge) => {
+                                    warn!(class = ?MsgClass::of(&msg), "consensus message over its size limit; not sent");
+                                    metrics.p2p_oversize_total.with_label_values(&["consensus"]).inc();
+                                }
                                 Err(_) => {
                                     warn!("failed to serialize ConsensusMsg");
                                     metrics.p2p_invalid_msg_total.inc();
@@ -284,30 +826,253 @@ pub fn spawn_p2p(
                     }
                 }
 
+                Ok(()) = tunables_rx.changed() => {
+                    let tunables = tunables_rx.borrow_and_update().clone();
+                    info!(
+                        max_msg_per_sec = tunables.max_msg_per_sec,
+                        max_peers_per_ip = tunables.max_peers_per_ip,
+                        allow_peers = tunables.allow_peers.len(),
+                        "p2p limits updated"
+                    );
+                    limits.set(tunables);
+                    let dropped: Vec<PeerId> = swarm
+                        .connected_peers()
+                        .filter(|p| !limits.allowed(p))
+                        .copied()
+                        .collect();
+                    for peer_id in dropped {
+                        warn!(%peer_id, "peer no longer in allowlist; disconnecting");
+                        metrics.p2p_banned_total.inc();
+                        let _ = swarm.disconnect_peer_id(peer_id);
+                    }
+                }
+
+                _ = ban_expiry.tick() => {
+                    for peer in scores.expire_bans(Instant::now()) {
+                        let Ok(peer_id) = PeerId::from_bytes(&peer) else {
+                            continue;
+                        };
+                        swarm.behaviour_mut().gossipsub.remove_blacklisted_peer(&peer_id);
+                        info!(%peer_id, "peer ban expired");
+                    }
+                    metrics.p2p_banned_peers.set(scores.banned_len() as i64);
+                    for addr in churn.expire(Instant::now()) {
+                        info!(?addr, "address ban expired");
+                    }
+                    metrics.p2p_banned_ips.set(churn.banned_len() as i64);
+                }
+
+                _ = pex_tick.tick() => {
+                    if read_only || swarm.connected_peers().next().is_none() {
+                        continue;
+                    }
+                    let Some(msg) = PexMessage::from_book(&book, &limits.allow, &local_peer_id) else {
+                        continue;
+                    };
+                    match msg.encode() {
+                        Ok(bytes) => match swarm.behaviour_mut().gossipsub.publish(pex_topic.clone(), bytes) {
+                            Ok(_) => metrics.p2p_pex_total.with_label_values(&["sent"]).inc(),
+                            Err(e) => tracing::debug!(err = ?e, "pex publish failed"),
+                        },
+                        Err(_) => warn!("failed to serialize pex message"),
+                    }
+                }
+
+                _ = book_save.tick() => {
+                    book.expire(now_ms(), ADDR_TTL);
+                    metrics.p2p_known_addrs.set(book.len() as i64);
+                    if let Err(e) = book.save(&peers_path) {
+                        warn!(error = %e, "address book not saved");
+                    }
+                }
+
+                Some(peer) = reports_rx.recv() => {
+                    let Ok(peer_id) = PeerId::from_bytes(&peer) else {
+                        continue;
+                    };
+                    if scores.observe_bad(peer, Instant::now(), 1) == Decision::Ban {
+                        ban(&mut swarm, &scores, &mut churn, &metrics, peer_id);
+                    }
+                }
+
+                Some(ev) = ev_out_rx.recv() => {
+                    if read_only {
+                        continue;
+                    }
+                    match wire::encode_evidence(&ev) {
+                        Ok(bytes) if !wire_limits.admits(MsgClass::Evidence, bytes.len()) => {
+                            warn!(bytes = bytes.len(), "evidence over its size limit; not sent");
+                            metrics.p2p_oversize_total.with_label_values(&["evidence"]).inc();
+                        }
+                        Ok(bytes) => {
+                            let _timer = metrics.p2p_publish_seconds.start_timer();
+                            if let Err(e) = swarm.behaviour_mut().gossipsub.publish(evidence_topic.clone(), bytes) {
+                                warn!(err = ?e, "evidence publish failed");
+                            }
+                        }
+                        Err(_) => warn!("failed to serialize evidence"),
+                    }
+                }
+
+                Some(msg) = sync_out_rx.recv() => {
+                    if read_only {
+                        continue;
+                    }
+                    match wire::encode_commit_sync(&msg) {
+                        Ok(bytes) if !wire_limits.admits(MsgClass::CommitSync, bytes.len()) => {
+                            warn!(bytes = bytes.len(), "commit sync message over its size limit; not sent");
+                            metrics.p2p_oversize_total.with_label_values(&["commit_sync"]).inc();
+                        }
+                        Ok(bytes) => {
+                            let _timer = metrics.p2p_publish_seconds.start_timer();
+                            if let Err(e) = swarm.behaviour_mut().gossipsub.publish(sync_topic.clone(), bytes) {
+                                warn!(err = ?e, "commit sync publish failed");
+                            }
+                        }
+                        Err(_) => warn!("failed to serialize commit sync message"),
+                    }
+                }
+
+                Some((name, bytes)) = ext_rx.recv() => {
+                    if read_only {
+                        continue;
+                    }
+                    let Some(t) = ext_topics.get(&name) else {
+                        warn!(topic = %name, "publish to unregistered extension topic; dropping");
+                        continue;
+                    };
+                    if !wire_limits.admits(MsgClass::Other, bytes.len()) {
+                        warn!(topic = %name, bytes = bytes.len(), "extension payload over its size limit; not sent");
+                        metrics.p2p_oversize_total.with_label_values(&["other"]).inc();
+                        continue;
+                    }
+                    let _timer = metrics.p2p_publish_seconds.start_timer();
+                    if let Err(e) = swarm.behaviour_mut().gossipsub.publish(t.clone(), bytes) {
+                        warn!(topic = %name, err = ?e, "extension publish failed");
+                    }
+                }
+
                 ev = swarm.select_next_some() => {
                     match ev {
                         SwarmEvent::NewListenAddr { address, .. } => {
                             info!(addr=%address, "listening");
+                            metrics.p2p_listen_addrs.inc();
+                        }
+
+                        SwarmEvent::ExpiredListenAddr { address, .. } => {
+                            warn!(addr=%address, "listen address expired");
+                            metrics.p2p_listen_addrs.dec();
                         }
 
-                        SwarmEvent::ConnectionEstablished { peer_id, .. } => {
-                            if !allow_set.is_empty() && !allow_set.contains(&peer_id) {
+                        SwarmEvent::ConnectionEstablished { peer_id, connection_id, endpoint, .. } => {
+                            if let Some((peer_id, addr)) = dial_back.succeeded(&connection_id) {
+                                info!(%peer_id, %addr, "advertised address verified");
+                                metrics.p2p_dialback_total.with_label_values(&["verified"]).inc();
+                                book.add(peer_id, addr);
+                                metrics.p2p_known_addrs.set(book.len() as i64);
+                                swarm.close_connection(connection_id);
+                                continue;
+                            }
+                            if let Some((peer_id, addr)) = redials.remove(&connection_id) {
+                                book.add(peer_id, addr);
+                            }
+                            if scores.is_banned(&peer_id.to_bytes()) {
+                                warn!(%peer_id, "banned peer reconnected; disconnecting");
+                                let _ = swarm.disconnect_peer_id(peer_id);
+                                continue;
+                            }
+                            let ip = remote_ip(endpoint.get_remote_address());
+                            if let (true, Some(ip)) = (endpoint.is_listener(), ip) {
+                                match churn.on_inbound(peer_id, ip, Instant::now()) {
+                                    ChurnVerdict::Allow => {}
+                                    ChurnVerdict::Suspected => {
+                                        warn!(%peer_id, %ip, "new peer id from a banned peer's ip");
+                                        metrics.p2p_ban_evasion_suspected_total.inc();
+                                    }
+                                    ChurnVerdict::Refused { escalated } => {
+                                        if let Some(addr) = escalated {
+                                            warn!(%peer_id, ?addr, "peer id churn; banning address");
+                                            metrics.p2p_ban_evasion_suspected_total.inc();
+                                            metrics.p2p_banned_ips.set(churn.banned_len() as i64);
+                                            let open: Vec<ConnectionId> = limits
+                                                .conns
+                                                .iter()
+                                                .filter(|(_, c)| c.is_some_and(|c| addr.covers(c)))
+                                                .map(|(id, _)| *id)
+                                                .collect();
+                                            for id in open {
+                                                swarm.close_connection(id);
+                                            }
+                                        }
+                                        metrics.p2p_banned_total.inc();
+                                        swarm.close_connection(connection_id);
+                                        continue;
+                                    }
+                                }
+                            }
+                            if !limits.allowed(&peer_id) {
                                 warn!(%peer_id, "peer not in allowlist; disconnecting");
                                 metrics.p2p_banned_total.inc();
                                 let _ = swarm.disconnect_peer_id(peer_id);
                                 continue;
                             }
+                            if !limits.admit(connection_id, ip) {
+                                warn!(%peer_id, ?ip, "too many connections from this ip; closing");
+                                metrics.p2p_banned_total.inc();
+                                swarm.close_connection(connection_id);
+                                continue;
+                            }
                             metrics.p2p_peers.inc();
+                            caps.connected(&peer_id.to_string());
+                            dial_back.connected(peer_id, ip);
+                            if let Some(ip) = ip {
+                                churn.connected(peer_id, ip);
+                            }
+                            peers_tx.send_replace(swarm.connected_peers().count());
+                            versions.connected(peer_id.to_bytes());
+                            metrics.p2p_wire_version.set(versions.send_version().into());
                             let _ = ev_tx.send(P2pEvent::PeerConnected(peer_id.to_bytes())).await;
                             info!(%peer_id, "peer connected");
                         }
 
-                        SwarmEvent::ConnectionClosed { peer_id, .. } => {
+                        SwarmEvent::ConnectionClosed { peer_id, connection_id, num_established, .. } => {
+                            if num_established == 0 {
+                                caps.disconnected(&peer_id.to_string(), 0);
+                                dial_back.disconnected(&peer_id);
+                                churn.disconnected(&peer_id);
+                                peers_tx.send_replace(swarm.connected_peers().count());
+                                limits.windows.remove(&peer_id);
+                                versions.disconnected(&peer_id.to_bytes());
+                                metrics.p2p_wire_version.set(versions.send_version().into());
+                            }
+                            if !limits.release(connection_id) {
+                                continue;
+                            }
+                            caps.disconnected(&peer_id.to_string(), num_established as usize);
                             metrics.p2p_peers.dec();
                             let _ = ev_tx.send(P2pEvent::PeerDisconnected(peer_id.to_bytes())).await;
                             info!(%peer_id, "peer disconnected");
                         }
 
+                        SwarmEvent::OutgoingConnectionError { connection_id, error, .. } => {
+                            if let Some((peer_id, addr)) = redials.remove(&connection_id) {
+                                warn!(%peer_id, %addr, err = %error, "known peer unreachable");
+                                book.record_failure(&peer_id, &addr);
+                                continue;
+                            }
+                            let Some(failure) = dial_back.failed(&connection_id) else {
+                                continue;
+                            };
+                            let peer_id = failure.peer;
+                            warn!(%peer_id, consecutive = failure.consecutive, err = %error, "dial-back failed");
+                            metrics.p2p_dialback_total.with_label_values(&["failed"]).inc();
+                            if failure.penalize
+                                && scores.observe_bad(peer_id.to_bytes(), Instant::now(), 1) == Decision::Ban
+                            {
+                                ban(&mut swarm, &scores, &mut churn, &metrics, peer_id);
+                            }
+                        }
+
                         SwarmEvent::Behaviour(BehaviourEvent::Gossipsub(ev)) => {
                             if let gossipsub::Event::Message {
                                 propagation_source,
@@ -315,7 +1080,10 @@ pub fn spawn_p2p(
                                 ..
                             } = *ev
                             {
-                                if !allow_set.is_empty() && !allow_set.contains(&propagation_source) {
+                                let Some(in_tx) = in_tx.as_ref() else {
+                                    continue;
+                                };
+                                if !limits.allowed(&propagation_source) {
                                     warn!(
                                         %propagation_source,
                                         "message from non-allowlisted peer; dropping"
@@ -323,20 +1091,226 @@ pub fn spawn_p2p(
                                     metrics.p2p_banned_total.inc();
                                     continue;
                                 }
+                                let source = message.source.map(|p| p.to_bytes());
+                                if !scores.admits(source.as_deref(), &propagation_source.to_bytes()) {
+                                    metrics.p2p_banned_total.inc();
+                                    continue;
+                                }
+                                if !limits.allow_message(propagation_source, Instant::now()) {
+                                    metrics.p2p_rate_limited_total.inc();
+                                    continue;
+                                }
+
+                                // The consensus topic carries two classes; `decode_within`
+                                // tells them apart.
+                                let class = if message.topic == evidence_topic.hash() {
+                                    Some(MsgClass::Evidence)
+                                } else if message.topic == sync_topic.hash() {
+                                    Some(MsgClass::CommitSync)
+                                } else if message.topic == topic.hash() {
+                                    None
+                                } else {
+                                    Some(MsgClass::Other)
+                                };
+                                if let Some(class) = class.filter(|c| !wire_limits.admits(*c, message.data.len())) {
+                                    // Limits are local config, so this is not held against the peer.
+                                    tracing::debug!(%propagation_source, bytes = message.data.len(), ?class, "message over its size limit");
+                                    metrics.p2p_oversize_total.with_label_values(&[class.topic()]).inc();
+                                    continue;
+                                }
 
-                                match bincode::deserialize::<ConsensusMsg>(&message.data) {
+                                let source_id = propagation_source.to_string();
+                                if let Some(h) = ext_handlers.get(&message.topic) {
+                                    caps.record(&source_id, TopicKind::Extension, message.data.len(), true);
+                                    h.on_message(&propagation_source.to_bytes(), &message.data);
+                                    continue;
+                                }
+
+                                if message.topic == pex_topic.hash() {
+                                    let checked = PexMessage::decode(&message.data)
+                                        .and_then(|m| m.verify(&limits.allow));
+                                    match checked {
+                                        Ok(entries) => {
+                                            metrics.p2p_pex_total.with_label_values(&["accepted"]).inc();
+                                            let mut dialed = HashSet::new();
+                                            for (peer_id, addr) in entries {
+                                                if redials.len() >= MAX_PENDING_REDIALS {
+                                                    break;
+                                                }
+                                                if peer_id == local_peer_id
+                                                    || swarm.is_connected(&peer_id)
+                                                    || scores.is_banned(&peer_id.to_bytes())
+                                                    || !dialed.insert(peer_id)
+                                                {
+                                                    continue;
+                                                }
+                                                dial_peer(&mut swarm, &mut redials, peer_id, addr);
+                                            }
+                                        }
+                                        Err(e) if e.is_invalid() => {
+                                            warn!(%propagation_source, err = %e, "invalid pex message");
+                                            metrics.p2p_pex_total.with_label_values(&["rejected"]).inc();
+                                            let decision = scores.observe_bad(
+                                                propagation_source.to_bytes(),
+                                                Instant::now(),
+                                                1,
+                                            );
+                                            if decision == Decision::Ban {
+                                                ban(&mut swarm, &scores, &mut churn, &metrics, propagation_source);
+                                            }
+                                        }
+                                        Err(e) => {
+                                            tracing::debug!(%propagation_source, err = %e, "pex message ignored");
+                                            metrics.p2p_pex_total.with_label_values(&["ignored"]).inc();
+                                        }
+                                    }
+                                    continue;
+                                }
+
+                                if message.topic == sync_topic.hash() {
+                                    let Some(sync_in_tx) = sync_in_tx.as_ref() else {
+                                        continue;
+                                    };
+                                    match wire::decode_commit_sync(&message.data) {
+                                        // Requests are for the one peer named in them.
+                                        Ok(CommitSync::Request(r)) if r.responder != local_peer_bytes => {}
+                                        Ok(msg) => {
+                                            let _ = sync_in_tx.send((propagation_source.to_bytes(), msg)).await;
+                                        }
+                                        Err(_) => {
+                                            warn!(%propagation_source, "invalid commit sync decode");
+                                            metrics.p2p_invalid_msg_total.inc();
+                                            let decision = scores.observe_bad(
+                                                propagation_source.to_bytes(),
+                                                Instant::now(),
+                                                1,
+                                            );
+                                            if decision == Decision::Ban {
+                                                ban(&mut swarm, &scores, &mut churn, &metrics, propagation_source);
+                                            }
+                                        }
+                                    }
+                                    continue;
+                                }
+
+                                if message.topic == evidence_topic.hash() {
+                                    let Some(ev_in_tx) = ev_in_tx.as_ref() else {
+                                        continue;
+                                    };
+                                    let decoded = wire::decode_evidence(&message.data);
+                                    caps.record(&source_id, TopicKind::Evidence, message.data.len(), decoded.is_ok());
+                                    match decoded {
+                                        Ok(ev) => {
+                                            let _ = ev_in_tx.send((propagation_source.to_bytes(), ev)).await;
+                                        }
+                                        Err(_) => {
+                                            warn!(%propagation_source, "invalid evidence decode");
+                                            metrics.p2p_invalid_msg_total.inc();
+                                            let decision = scores.observe_bad(
+                                                propagation_source.to_bytes(),
+                                                Instant::now(),
+                                                1,
+                                            );
+                                            if decision == Decision::Ban {
+                                                ban(&mut swarm, &scores, &mut churn, &metrics, propagation_source);
+                                            }
+                                        }
+                                    }
+                                    continue;
+                                }
+
+                                let span = tracing::info_span!(
+                                    "p2p.receive",
+                                    peer = %propagation_source,
+                                    bytes = message.data.len()
+                                );
+                                let decoded = span.in_scope(|| {
+                                    let _decode = tracing::debug_span!("p2p.decode").entered();
+                                    codec.decode_within(&message.data, &wire_limits)
+                                });
+                                caps.record(&source_id, TopicKind::Consensus, message.data.len(), decoded.is_ok());
+                                match decoded {
                                     Ok(msg) => {
-                                        let _ = in_tx.send((propagation_source.to_bytes(), msg)).await;
+                                        // The same content under another encoding or gossip id.
+                                        let fresh = content_key(&msg)
+                                            .is_ok_and(|key| seen.insert(key, Instant::now()));
+                                        if !fresh {
+                                            metrics.p2p_replay_dropped_total.inc();
+                                            continue;
+                                        }
+                                        scores.observe_good(propagation_source.to_bytes(), Instant::now(), 1);
+                                        let sent = in_tx
+                                            .send((propagation_source.to_bytes(), msg))
+                                            .instrument(span)
+                                            .await;
+                                        if let Err(e) = sent {
+                                            warn!(err = %e, "consensus inbound unavailable");
+                                        }
+                                    }
+                                    Err(WireError::UnsupportedVersion(v)) => {
+                                        warn!(%propagation_source, version = v, "unsupported wire version");
+                                        metrics.p2p_wire_unsupported_total.inc();
+                                    }
+                                    Err(WireError::TooLarge) => {
+                                        tracing::debug!(%propagation_source, bytes = message.data.len(), "consensus message over its size limit");
+                                        metrics.p2p_oversize_total.with_label_values(&["consensus"]).inc();
                                     }
                                     Err(_) => {
                                         warn!(%propagation_source, "invalid consensus msg decode");
                                         metrics.p2p_invalid_msg_total.inc();
+                                        let decision = scores.observe_bad(
+                                            propagation_source.to_bytes(),
+                                            Instant::now(),
+                                            1,
+                                        );
+                                        if decision == Decision::Ban {
+                                            ban(&mut swarm, &scores, &mut churn, &metrics, propagation_source);
+                                        }
                                     }
                                 }
                             }
                         }
 
-                        SwarmEvent::Behaviour(BehaviourEvent::Identify(_)) => {}
+                        SwarmEvent::Behaviour(BehaviourEvent::Identify(ev)) => {
+                            let identify::Event::Received { peer_id, info } = *ev else {
+                                continue;
+                            };
+                            if !swarm.is_connected(&peer_id) {
+                                continue;
+                            }
+                            let peer = peer_id.to_string();
+                            caps.identified(
+                                &peer,
+                                &info.agent_version,
+                                &info.protocol_version,
+                                info.protocols.iter().map(|p| p.to_string()),
+                            );
+                            let negotiated = versions.identified(peer_id.to_bytes(), &info.protocol_version);
+                            caps.set_wire_version(&peer, negotiated);
+                            match negotiated {
+                                Some(v) => {
+                                    info!(%peer_id, protocol = %info.protocol_version, wire_version = v, "peer identified");
+                                    verify_addresses(
+                                        &mut swarm,
+                                        &mut dial_back,
+                                        &book,
+                                        &metrics,
+                                        peer_id,
+                                        &info.listen_addrs,
+                                    );
+                                }
+                                None => {
+                                    warn!(
+                                        %peer_id,
+                                        protocol = %info.protocol_version,
+                                        "no common wire version; disconnecting"
+                                    );
+                                    metrics.p2p_wire_unsupported_total.inc();
+                                    let _ = swarm.disconnect_peer_id(peer_id);
+                                }
+                            }
+                            metrics.p2p_wire_version.set(versions.send_version().into());
+                        }
                         SwarmEvent::Behaviour(BehaviourEvent::Ping(_)) => {}
 
                         _ => {}
@@ -344,12 +1318,27 @@ pub fn spawn_p2p(
                 }
             }
         }
+
+        if let Err(e) = book.save(&peers_path) {
+            warn!(error = %e, "address book not saved");
+        }
     });
 
     Ok((
         P2pNode {
-            inbound_rx: in_rx,
+            inbound_rx: Some(in_rx),
             outbound_tx: out_tx,
+            evidence_rx: Some(ev_in_rx),
+            evidence_tx: ev_out_tx,
+            sync_rx: Some(sync_in_rx),
+            sync_tx: sync_out_tx,
+            extension_tx: ext_tx,
+            reports_tx,
+            tunables_tx,
+            phase_tx,
+            capabilities,
+            addresses,
+            peers_rx,
         },
         ev_rx,
         join,
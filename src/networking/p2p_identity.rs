@@ -1,17 +1,54 @@
 #![forbid(unsafe_code)]
 
+//! Persistent libp2p identity and its relation to validator keys.
+//!
+//! Where the identity comes from is `p2p.identity` (`IdentitySource`):
+//! - `file`: a random Ed25519 key in `data_dir/p2p_identity.key` (protobuf encoding);
+//! - `encrypted`: the same key, stored with the keystore's passphrase encryption
+//!   (`AMUNCHAIN_KEY_PASSPHRASE`, see `core::security::keystore`); an existing plaintext
+//!   file is encrypted in place, keeping the PeerId;
+//! - `validator`: the validator key in `data_dir/validator.key` is also the identity, so the
+//!   PeerId names the validator.
+//!
+//! Noise needs the identity's secret key in process, so it cannot live behind a remote signer;
+//! nodes whose validator key does should use `encrypted` and bind the two identities instead.
+//!
+//! An Ed25519 PeerId embeds its public key, so `peer_public_key` recovers it and
+//! `ValidatorPeers` matches peers to validator ids (for `validator` identities, and for any
+//! pair bound explicitly with `insert`).
+
 use std::{
+    collections::HashMap,
     fs,
     io::{self, Write},
     path::{Path, PathBuf},
 };
 
 use libp2p::{identity, PeerId};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use crate::core::security::keystore::{
+    is_encrypted, passphrase_from_env, read_key_seed, read_secret_file, write_secret_file,
+    KeystoreError,
+};
+use crate::core::types::ValidatorId;
+
+/// Identity file inside the data directory.
+pub const IDENTITY_FILE: &str = "p2p_identity.key";
+/// Validator key inside the data directory (see `core::security::keystore`).
+const VALIDATOR_KEY_FILE: &str = "validator.key";
 
 #[derive(Debug)]
 pub enum IdentityError {
     Io,
     Decode,
+    /// The identity file is encrypted (or must be) and no passphrase is set.
+    MissingPassphrase,
+    /// Wrong passphrase or damaged file.
+    Crypto,
+    /// `validator` identity without a `validator.key`.
+    NoValidatorKey,
 }
 
 impl From<io::Error> for IdentityError {
@@ -20,31 +57,112 @@ impl From<io::Error> for IdentityError {
     }
 }
 
+impl From<KeystoreError> for IdentityError {
+    fn from(e: KeystoreError) -> Self {
+        match e {
+            KeystoreError::MissingPassphrase => IdentityError::MissingPassphrase,
+            KeystoreError::Crypto => IdentityError::Crypto,
+            KeystoreError::InvalidKey => IdentityError::Decode,
+            _ => IdentityError::Io,
+        }
+    }
+}
+
+/// Where the libp2p identity key comes from (`p2p.identity`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentitySource {
+    /// Plaintext `p2p_identity.key`.
+    #[default]
+    File,
+    /// Passphrase-encrypted `p2p_identity.key`.
+    Encrypted,
+    /// The validator key.
+    Validator,
+}
+
 /// Load an existing Ed25519 keypair from `data_dir/p2p_identity.key`,
 /// or create a new one and persist it.
 ///
 /// Returns (PeerId, Keypair).
 pub fn load_or_create_identity(
     data_dir: impl AsRef<Path>,
+) -> Result<(PeerId, identity::Keypair), IdentityError> {
+    load_identity(data_dir, IdentitySource::File)
+}
+
+/// Load the identity `source` names, creating a file identity if there is none yet.
+/// The passphrase comes from `AMUNCHAIN_KEY_PASSPHRASE` (see `keystore::passphrase_from_env`).
+pub fn load_identity(
+    data_dir: impl AsRef<Path>,
+    source: IdentitySource,
+) -> Result<(PeerId, identity::Keypair), IdentityError> {
+    load_identity_with(data_dir, source, passphrase_from_env()?.as_deref())
+}
+
+/// `load_identity` with an explicit passphrase.
+pub fn load_identity_with(
+    data_dir: impl AsRef<Path>,
+    source: IdentitySource,
+    passphrase: Option<&str>,
 ) -> Result<(PeerId, identity::Keypair), IdentityError> {
     let dir = data_dir.as_ref();
+    let kp = match source {
+        IdentitySource::File => file_identity(dir, None, passphrase)?,
+        IdentitySource::Encrypted => {
+            let p = passphrase.ok_or(IdentityError::MissingPassphrase)?;
+            file_identity(dir, Some(p), Some(p))?
+        }
+        IdentitySource::Validator => {
+            let path = dir.join(VALIDATOR_KEY_FILE);
+            if !path.exists() {
+                return Err(IdentityError::NoValidatorKey);
+            }
+            let mut seed = read_key_seed(&path, passphrase)?;
+            let kp =
+                identity::Keypair::ed25519_from_bytes(&mut seed).map_err(|_| IdentityError::Decode);
+            seed.zeroize();
+            kp?
+        }
+    };
+    Ok((PeerId::from(kp.public()), kp))
+}
+
+/// Read (or create) `p2p_identity.key`. With `encrypt_with`, a plaintext file is rewritten
+/// encrypted and new files are written encrypted.
+fn file_identity(
+    dir: &Path,
+    encrypt_with: Option<&str>,
+    passphrase: Option<&str>,
+) -> Result<identity::Keypair, IdentityError> {
     fs::create_dir_all(dir)?;
 
-    let path: PathBuf = dir.join("p2p_identity.key");
+    let path: PathBuf = dir.join(IDENTITY_FILE);
 
     if path.exists() {
-        let bytes = fs::read(&path)?;
+        let raw = fs::read(&path)?;
+        let encrypted = is_encrypted(&raw);
+        let mut bytes = read_secret_file(&path, passphrase)?;
         let kp =
-            identity::Keypair::from_protobuf_encoding(&bytes).map_err(|_| IdentityError::Decode)?;
-        let pid = PeerId::from(kp.public());
-        return Ok((pid, kp));
+            identity::Keypair::from_protobuf_encoding(&bytes).map_err(|_| IdentityError::Decode);
+        if let (Ok(_), false, Some(p)) = (&kp, encrypted, encrypt_with) {
+            write_secret_file(&path, &bytes, Some(p))?;
+        }
+        bytes.zeroize();
+        return kp;
     }
 
     let kp = identity::Keypair::generate_ed25519();
-    let bytes = kp
+    let mut bytes = kp
         .to_protobuf_encoding()
         .map_err(|_| IdentityError::Decode)?;
 
+    if encrypt_with.is_some() {
+        write_secret_file(&path, &bytes, encrypt_with)?;
+        bytes.zeroize();
+        return Ok(kp);
+    }
+
     // Atomic-ish write: write to tmp then rename.
     let tmp = dir.join("p2p_identity.key.tmp");
     {
@@ -52,8 +170,79 @@ pub fn load_or_create_identity(
         f.write_all(&bytes)?;
         f.sync_all()?;
     }
+    bytes.zeroize();
     fs::rename(&tmp, &path)?;
+    Ok(kp)
+}
+
+/// Ed25519 public key a PeerId embeds, if it is an Ed25519 identity.
+pub fn peer_public_key(peer: &PeerId) -> Option<[u8; 32]> {
+    // Ed25519 PeerIds use the identity multihash: the digest is the protobuf public key.
+    let mh = peer.as_ref();
+    if mh.code() != 0x00 {
+        return None;
+    }
+    let pk = identity::PublicKey::try_decode_protobuf(mh.digest()).ok()?;
+    Some(pk.try_into_ed25519().ok()?.to_bytes())
+}
+
+/// PeerId of the Ed25519 public key `pk`.
+pub fn peer_id_of_key(pk: &[u8; 32]) -> Option<PeerId> {
+    let pk = identity::ed25519::PublicKey::try_from_bytes(pk).ok()?;
+    Some(PeerId::from(identity::PublicKey::from(pk)))
+}
 
-    let pid = PeerId::from(kp.public());
-    Ok((pid, kp))
+/// Peers matched to validator ids, for allowlists, registries and peer scoring.
+#[derive(Clone, Debug, Default)]
+pub struct ValidatorPeers {
+    by_peer: HashMap<PeerId, ValidatorId>,
+    by_validator: HashMap<ValidatorId, PeerId>,
+}
+
+impl ValidatorPeers {
+    /// Map every validator to the PeerId of its own key (`validator` identities).
+    pub fn new<'a>(validators: impl IntoIterator<Item = &'a ValidatorId>) -> Self {
+        let mut out = Self::default();
+        for v in validators {
+            if let Some(peer) = peer_id_of_key(v.as_bytes()) {
+                out.insert(peer, v.clone());
+            }
+        }
+        out
+    }
+
+    /// Record that `peer` speaks for `validator`, replacing earlier entries of either.
+    pub fn insert(&mut self, peer: PeerId, validator: ValidatorId) {
+        if let Some(old) = self.by_validator.insert(validator.clone(), peer) {
+            self.by_peer.remove(&old);
+        }
+        if let Some(old) = self.by_peer.insert(peer, validator.clone()) {
+            if old != validator {
+                self.by_validator.remove(&old);
+            }
+        }
+    }
+
+    /// Forget `validator`'s peer.
+    pub fn remove(&mut self, validator: &ValidatorId) -> Option<PeerId> {
+        let peer = self.by_validator.remove(validator)?;
+        self.by_peer.remove(&peer);
+        Some(peer)
+    }
+
+    pub fn validator(&self, peer: &PeerId) -> Option<&ValidatorId> {
+        self.by_peer.get(peer)
+    }
+
+    pub fn peer(&self, validator: &ValidatorId) -> Option<&PeerId> {
+        self.by_validator.get(validator)
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_peer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_peer.is_empty()
+    }
 }
@@ -0,0 +1,204 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! What each connected peer supports, and what it sends us.
+//!
+//! The p2p task fills a `PeerCapabilityMap` from connection events and identify: agent and
+//! protocol version, the stream protocols the peer lists, and the wire version negotiated with
+//! it. Capabilities are derived from the protocol list (`SYNC_PROTOCOL`, `STATESYNC_PROTOCOL`),
+//! so a peer is only asked for what it announced. Alongside, each peer accumulates usage
+//! counters per gossip topic kind (messages and bytes received from it as propagation source).
+//!
+//! `PeerCapabilityMap::supporting` is how block and state sync choose whom to ask; operators
+//! read the map through `GET /p2p/peers`. Entries are dropped when the peer's last connection closes.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+/// Stream protocol of block sync.
+pub const SYNC_PROTOCOL: &str = "/amunchain/sync/1.0.0";
+/// Stream protocol of state sync (snapshots and chunks).
+pub const STATESYNC_PROTOCOL: &str = "/amunchain/statesync/1.0.0";
+
+/// Capability a scheduler can ask for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Capability {
+    Sync,
+    StateSync,
+}
+
+impl Capability {
+    /// Protocol a peer must list in identify.
+    pub fn protocol(self) -> &'static str {
+        match self {
+            Capability::Sync => SYNC_PROTOCOL,
+            Capability::StateSync => STATESYNC_PROTOCOL,
+        }
+    }
+}
+
+/// Kind of gossip topic a message arrived on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TopicKind {
+    Consensus,
+    Evidence,
+    Extension,
+}
+
+/// Gossip received from one peer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ProtocolUsage {
+    pub consensus_msgs: u64,
+    pub evidence_msgs: u64,
+    pub extension_msgs: u64,
+    /// Payload bytes over all topics.
+    pub bytes_received: u64,
+    /// Messages that failed to decode or validate.
+    pub invalid_msgs: u64,
+}
+
+/// One connected peer.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct PeerCapabilities {
+    /// Base58 peer id.
+    pub peer_id: String,
+    /// Open connections to the peer.
+    pub connections: usize,
+    /// From identify; empty until the peer identified.
+    pub agent_version: String,
+    pub protocol_version: String,
+    /// Stream protocols the peer listed, sorted.
+    pub protocols: Vec<String>,
+    /// Consensus wire version negotiated with the peer (see `wire`).
+    pub wire_version: Option<u16>,
+    pub supports_sync: bool,
+    pub supports_statesync: bool,
+    pub usage: ProtocolUsage,
+}
+
+impl PeerCapabilities {
+    /// Whether the peer announced `cap`.
+    pub fn supports(&self, cap: Capability) -> bool {
+        match cap {
+            Capability::Sync => self.supports_sync,
+            Capability::StateSync => self.supports_statesync,
+        }
+    }
+}
+
+/// Connected peers and their capabilities, shared between the p2p task and its readers.
+#[derive(Clone, Debug, Default)]
+pub struct PeerCapabilityMap {
+    peers: Arc<RwLock<BTreeMap<String, PeerCapabilities>>>,
+}
+
+impl PeerCapabilityMap {
+    fn update(&self, peer: &str, f: impl FnOnce(&mut PeerCapabilities)) {
+        if let Ok(mut peers) = self.peers.write() {
+            if let Some(entry) = peers.get_mut(peer) {
+                f(entry);
+            }
+        }
+    }
+
+    /// A connection to `peer` opened.
+    pub fn connected(&self, peer: &str) {
+        if let Ok(mut peers) = self.peers.write() {
+            let entry = peers
+                .entry(peer.to_string())
+                .or_insert_with(|| PeerCapabilities {
+                    peer_id: peer.to_string(),
+                    ..PeerCapabilities::default()
+                });
+            entry.connections += 1;
+        }
+    }
+
+    /// A connection to `peer` closed; `remaining` are still open. The entry goes with the
+    /// last one.
+    pub fn disconnected(&self, peer: &str, remaining: usize) {
+        if let Ok(mut peers) = self.peers.write() {
+            if remaining == 0 {
+                peers.remove(peer);
+            } else if let Some(entry) = peers.get_mut(peer) {
+                entry.connections = remaining;
+            }
+        }
+    }
+
+    /// `peer` identified with these protocols.
+    pub fn identified(
+        &self,
+        peer: &str,
+        agent_version: &str,
+        protocol_version: &str,
+        protocols: impl IntoIterator<Item = String>,
+    ) {
+        let mut protocols: Vec<String> = protocols.into_iter().collect();
+        protocols.sort_unstable();
+        protocols.dedup();
+        self.update(peer, |entry| {
+            entry.supports_sync = protocols.iter().any(|p| p == SYNC_PROTOCOL);
+            entry.supports_statesync = protocols.iter().any(|p| p == STATESYNC_PROTOCOL);
+            entry.agent_version = agent_version.to_string();
+            entry.protocol_version = protocol_version.to_string();
+            entry.protocols = protocols;
+        });
+    }
+
+    /// Consensus wire version negotiated with `peer` (`None` when none is shared).
+    pub fn set_wire_version(&self, peer: &str, version: Option<u16>) {
+        self.update(peer, |entry| entry.wire_version = version);
+    }
+
+    /// Count a gossip message of `bytes` from `peer`; `valid` is false if it was refused.
+    pub fn record(&self, peer: &str, kind: TopicKind, bytes: usize, valid: bool) {
+        self.update(peer, |entry| {
+            let usage = &mut entry.usage;
+            match kind {
+                TopicKind::Consensus => usage.consensus_msgs += 1,
+                TopicKind::Evidence => usage.evidence_msgs += 1,
+                TopicKind::Extension => usage.extension_msgs += 1,
+            }
+            usage.bytes_received = usage.bytes_received.saturating_add(bytes as u64);
+            if !valid {
+                usage.invalid_msgs += 1;
+            }
+        });
+    }
+
+    /// One peer's entry.
+    pub fn get(&self, peer: &str) -> Option<PeerCapabilities> {
+        self.peers.read().ok()?.get(peer).cloned()
+    }
+
+    /// Every connected peer, by peer id.
+    pub fn list(&self) -> Vec<PeerCapabilities> {
+        self.peers
+            .read()
+            .map(|peers| peers.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Peer ids supporting `cap`, those that sent the fewest invalid messages first.
+    pub fn supporting(&self, cap: Capability) -> Vec<String> {
+        let mut peers: Vec<PeerCapabilities> = self
+            .list()
+            .into_iter()
+            .filter(|p| p.supports(cap))
+            .collect();
+        peers.sort_by_key(|p| p.usage.invalid_msgs);
+        peers.into_iter().map(|p| p.peer_id).collect()
+    }
+}
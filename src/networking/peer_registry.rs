@@ -47,6 +47,37 @@
 //! - **Freshness:** enforced with `issued_at_ms`, `expires_at_ms`, and node policy.
 //! - **Rollback safety:** optional minimum version policy (and operationally, monotonically increasing
 //!   `issued_at_ms` via config management).
+//!
+//! ## Bundles
+//! One signed artifact can carry several networks:
+//!
+//! ```text
+//! version = 1
+//! signature_hex = "..."
+//!
+//! [[networks]]
+//! network = "amunchain/consensus/v2"
+//! issued_at_ms = 1730000000000
+//! expires_at_ms = 1730003600000
+//! peers = ["12D3KooW..."]
+//!
+//! [[networks]]
+//! network = "amunchain-testnet/consensus/v2"
+//! ...
+//! ```
+//!
+//! The signature covers every section, each in the single-network canonical form above (so
+//! each section stays bound to its `network=` line), sorted by network and length-prefixed:
+//!
+//! ```text
+//! bundle-v1
+//! sections=<n>
+//! section=<byte length>
+//! <section canonical bytes>
+//! ...
+//! ```
+//!
+//! A node verifies the whole bundle, then applies its policy to its own network's section only.
 
 use crate::core::security::keystore::verify_sig_bytes64;
 use libp2p::PeerId;
@@ -193,39 +224,62 @@ fn canonical_bytes(
     Ok(out)
 }
 
-fn now_ms() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
+#[derive(Debug, Deserialize)]
+struct PeerRegistryBundleFile {
+    /// Bundle format version.
+    version: u32,
+    /// One section per network.
+    #[serde(default)]
+    networks: Vec<PeerRegistrySection>,
+    /// Signature over the bundle canonical bytes (hex; Ed25519 64 bytes). Absent while the
+    /// bundle is being prepared for signing.
+    #[serde(default)]
+    signature_hex: String,
 }
 
-/// Load and verify a signed peer registry, returning a deduplicated allowlist.
-///
-/// Node policy can enforce:
-/// - freshness (issued_at / expires_at)
-/// - age limits
-/// - grace windows
-/// - topic binding
-///
-/// Parse a peer registry TOML document (syntax + schema only).
-///
-/// This does **not** verify signatures. It is intended for tooling and fuzzing.
-pub fn parse_peer_registry_toml(raw: &str) -> Result<(), PeerRegistryError> {
-    let _reg: PeerRegistryFile = toml::from_str(raw).map_err(|_| PeerRegistryError::Parse)?;
-    Ok(())
+#[derive(Debug, Deserialize)]
+struct PeerRegistrySection {
+    network: String,
+    #[serde(default)]
+    issued_at_ms: Option<u64>,
+    #[serde(default)]
+    expires_at_ms: Option<u64>,
+    #[serde(default)]
+    peers: Vec<String>,
 }
 
-pub fn load_and_verify_peer_registry(
-    path: &str,
-    pubkey_hex: &str,
-    policy: &PeerRegistryPolicy<'_>,
-) -> Result<Vec<String>, PeerRegistryError> {
-    // Public key must be a valid 32-byte Ed25519 pubkey.
-    let pk = parse_hex_32(pubkey_hex)?;
-    let raw = fs::read_to_string(path).map_err(|_| PeerRegistryError::Read)?;
-    let reg: PeerRegistryFile = toml::from_str(&raw).map_err(|_| PeerRegistryError::Parse)?;
+impl PeerRegistrySection {
+    fn as_file(&self, version: u32) -> PeerRegistryFile {
+        PeerRegistryFile {
+            version,
+            network: Some(self.network.clone()),
+            issued_at_ms: self.issued_at_ms,
+            expires_at_ms: self.expires_at_ms,
+            peers: self.peers.clone(),
+            signature_hex: String::new(),
+        }
+    }
+}
 
+fn parse_peers(raw: &[String]) -> Result<BTreeSet<PeerId>, PeerRegistryError> {
+    let mut peers = BTreeSet::new();
+    for s in raw.iter() {
+        let p = PeerId::from_bytes(
+            &bs58::decode(s)
+                .into_vec()
+                .map_err(|_| PeerRegistryError::InvalidPeer)?,
+        )
+        .map_err(|_| PeerRegistryError::InvalidPeer)?;
+        peers.insert(p);
+    }
+    Ok(peers)
+}
+
+/// Version, freshness and network checks shared by single-network files and bundle sections.
+fn check_policy(
+    reg: &PeerRegistryFile,
+    policy: &PeerRegistryPolicy<'_>,
+) -> Result<(), PeerRegistryError> {
     // Version gate.
     if reg.version != 1 {
         return Err(PeerRegistryError::UnsupportedVersion);
@@ -263,18 +317,64 @@ pub fn load_and_verify_peer_registry(
             _ => return Err(PeerRegistryError::NetworkMismatch),
         }
     }
+    Ok(())
+}
 
-    // Parse and dedupe peers.
-    let mut peers = BTreeSet::new();
-    for s in reg.peers.iter() {
-        let p = PeerId::from_bytes(
-            &bs58::decode(s)
-                .into_vec()
-                .map_err(|_| PeerRegistryError::InvalidPeer)?,
-        )
-        .map_err(|_| PeerRegistryError::InvalidPeer)?;
-        peers.insert(p);
+/// Canonical bytes of a bundle: every section in single-network canonical form, sorted by
+/// network and length-prefixed. Duplicate networks are rejected.
+fn bundle_canonical_bytes(bundle: &PeerRegistryBundleFile) -> Result<Vec<u8>, PeerRegistryError> {
+    let mut sections = std::collections::BTreeMap::new();
+    for sec in bundle.networks.iter() {
+        let bytes = canonical_bytes(&sec.as_file(bundle.version), &parse_peers(&sec.peers)?)?;
+        if sections.insert(sec.network.as_str(), bytes).is_some() {
+            return Err(PeerRegistryError::Parse);
+        }
+    }
+    let mut out = Vec::new();
+    out.extend_from_slice(b"bundle-v1\n");
+    out.extend_from_slice(format!("sections={}\n", sections.len()).as_bytes());
+    for bytes in sections.values() {
+        out.extend_from_slice(format!("section={}\n", bytes.len()).as_bytes());
+        out.extend_from_slice(bytes);
     }
+    Ok(out)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Load and verify a signed peer registry, returning a deduplicated allowlist.
+///
+/// Node policy can enforce:
+/// - freshness (issued_at / expires_at)
+/// - age limits
+/// - grace windows
+/// - topic binding
+///
+/// Parse a peer registry TOML document (syntax + schema only).
+///
+/// This does **not** verify signatures. It is intended for tooling and fuzzing.
+pub fn parse_peer_registry_toml(raw: &str) -> Result<(), PeerRegistryError> {
+    let _reg: PeerRegistryFile = toml::from_str(raw).map_err(|_| PeerRegistryError::Parse)?;
+    Ok(())
+}
+
+pub fn load_and_verify_peer_registry(
+    path: &str,
+    pubkey_hex: &str,
+    policy: &PeerRegistryPolicy<'_>,
+) -> Result<Vec<String>, PeerRegistryError> {
+    // Public key must be a valid 32-byte Ed25519 pubkey.
+    let pk = parse_hex_32(pubkey_hex)?;
+    let raw = fs::read_to_string(path).map_err(|_| PeerRegistryError::Read)?;
+    let reg: PeerRegistryFile = toml::from_str(&raw).map_err(|_| PeerRegistryError::Parse)?;
+
+    check_policy(&reg, policy)?;
+    let peers = parse_peers(&reg.peers)?;
 
     let sig = parse_sig_64(&reg.signature_hex)?;
     let msg = canonical_bytes(&reg, &peers)?;
@@ -285,6 +385,58 @@ pub fn load_and_verify_peer_registry(
     Ok(peers.into_iter().map(|p| p.to_base58()).collect())
 }
 
+/// Load a signed multi-network bundle and return the allowlist of `policy.expected_network`.
+///
+/// The signature is checked over all sections; freshness policy applies to the node's own
+/// section only. `expected_network` is required.
+pub fn load_and_verify_peer_registry_bundle(
+    path: &str,
+    pubkey_hex: &str,
+    policy: &PeerRegistryPolicy<'_>,
+) -> Result<Vec<String>, PeerRegistryError> {
+    let pk = parse_hex_32(pubkey_hex)?;
+    let expected = policy
+        .expected_network
+        .ok_or(PeerRegistryError::MissingField)?;
+    let raw = fs::read_to_string(path).map_err(|_| PeerRegistryError::Read)?;
+    let bundle: PeerRegistryBundleFile =
+        toml::from_str(&raw).map_err(|_| PeerRegistryError::Parse)?;
+    if bundle.version != 1 {
+        return Err(PeerRegistryError::UnsupportedVersion);
+    }
+
+    let sig = parse_sig_64(&bundle.signature_hex)?;
+    let msg = bundle_canonical_bytes(&bundle)?;
+    if verify_sig_bytes64(&pk, &msg, &sig).is_err() {
+        return Err(PeerRegistryError::BadSignature);
+    }
+
+    let section = bundle
+        .networks
+        .iter()
+        .find(|s| s.network == expected)
+        .ok_or(PeerRegistryError::NetworkMismatch)?;
+    check_policy(&section.as_file(bundle.version), policy)?;
+    let peers = parse_peers(&section.peers)?;
+    Ok(peers.into_iter().map(|p| p.to_base58()).collect())
+}
+
+/// Canonical bytes to sign for a single-network registry document (tooling helper).
+pub fn peer_registry_signing_bytes(raw: &str) -> Result<Vec<u8>, PeerRegistryError> {
+    let reg: PeerRegistryFile = toml::from_str(raw).map_err(|_| PeerRegistryError::Parse)?;
+    canonical_bytes(&reg, &parse_peers(&reg.peers)?)
+}
+
+/// Canonical bytes to sign for a bundle document (tooling helper).
+pub fn peer_registry_bundle_signing_bytes(raw: &str) -> Result<Vec<u8>, PeerRegistryError> {
+    let bundle: PeerRegistryBundleFile =
+        toml::from_str(raw).map_err(|_| PeerRegistryError::Parse)?;
+    if bundle.version != 1 {
+        return Err(PeerRegistryError::UnsupportedVersion);
+    }
+    bundle_canonical_bytes(&bundle)
+}
+
 /// Convenience helper using system time for `now_ms`.
 pub fn load_and_verify_peer_registry_now(
     path: &str,
@@ -297,3 +449,16 @@ pub fn load_and_verify_peer_registry_now(
     }
     load_and_verify_peer_registry(path, pubkey_hex, &p)
 }
+
+/// Bundle variant of [`load_and_verify_peer_registry_now`].
+pub fn load_and_verify_peer_registry_bundle_now(
+    path: &str,
+    pubkey_hex: &str,
+    policy: &PeerRegistryPolicy<'_>,
+) -> Result<Vec<String>, PeerRegistryError> {
+    let mut p = policy.clone();
+    if p.now_ms == 0 {
+        p.now_ms = now_ms();
+    }
+    load_and_verify_peer_registry_bundle(path, pubkey_hex, &p)
+}
@@ -18,6 +18,8 @@ pub struct ScoreParams {
     pub bad_inc: i32,
     pub decay_per_min: i32,
     pub ban_threshold: i32,
+    /// How long a ban lasts before the peer is let back into the mesh.
+    pub ban_duration: Duration,
 }
 
 impl Default for ScoreParams {
@@ -27,6 +29,7 @@ impl Default for ScoreParams {
             bad_inc: 5,
             decay_per_min: 1,
             ban_threshold: 200,
+            ban_duration: Duration::from_secs(600),
         }
     }
 }
@@ -47,6 +50,8 @@ struct PeerState {
 pub struct PeerScore {
     params: ScoreParams,
     peers: BTreeMap<Vec<u8>, PeerState>,
+    /// Banned peers and when their ban ends.
+    banned: BTreeMap<Vec<u8>, Instant>,
 }
 
 impl PeerScore {
@@ -54,6 +59,7 @@ impl PeerScore {
         Self {
             params,
             peers: BTreeMap::new(),
+            banned: BTreeMap::new(),
         }
     }
 
@@ -77,9 +83,11 @@ impl PeerScore {
         Self::decision_from_score(&params, st.score)
     }
 
+    /// Record misbehaviour. Reaching the ban threshold bans the peer for `ban_duration`
+    /// (see `is_banned`); a peer already banned keeps its original expiry.
     pub fn observe_bad(&mut self, peer: Vec<u8>, now: Instant, weight: i32) -> Decision {
         let params = self.params.clone(); // avoid borrow issues
-        let st = self.peers.entry(peer).or_insert(PeerState {
+        let st = self.peers.entry(peer.clone()).or_insert(PeerState {
             score: 0,
             last: now,
         });
@@ -90,7 +98,43 @@ impl PeerScore {
             .saturating_sub(params.bad_inc.saturating_mul(weight.max(1)));
         st.score = st.score.clamp(-1000, 1000);
 
-        Self::decision_from_score(&params, st.score)
+        let decision = Self::decision_from_score(&params, st.score);
+        if decision == Decision::Ban {
+            self.banned
+                .entry(peer)
+                .or_insert_with(|| now.checked_add(params.ban_duration).unwrap_or(now));
+        }
+        decision
+    }
+
+    pub fn is_banned(&self, peer: &[u8]) -> bool {
+        self.banned.contains_key(peer)
+    }
+
+    pub fn banned_len(&self) -> usize {
+        self.banned.len()
+    }
+
+    /// Whether a gossip message may be processed: neither the peer that relayed it nor the
+    /// peer that authored it (`source`, when signed) is banned. Relays keep forwarding a
+    /// banned author's messages, so checking only the direct sender is not enough.
+    pub fn admits(&self, source: Option<&[u8]>, propagation_source: &[u8]) -> bool {
+        !self.is_banned(propagation_source) && !source.is_some_and(|s| self.is_banned(s))
+    }
+
+    /// Lift bans that have ended by `now` and return those peers. Their score starts over.
+    pub fn expire_bans(&mut self, now: Instant) -> Vec<Vec<u8>> {
+        let expired: Vec<Vec<u8>> = self
+            .banned
+            .iter()
+            .filter(|(_, until)| **until <= now)
+            .map(|(peer, _)| peer.clone())
+            .collect();
+        for peer in &expired {
+            self.banned.remove(peer);
+            self.peers.remove(peer);
+        }
+        expired
     }
 
     fn decision_from_score(params: &ScoreParams, score: i32) -> Decision {
@@ -0,0 +1,172 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Peer exchange (PEX) among registry-approved peers.
+//!
+//! Every node publishes on `PEX_TOPIC`, now and then, the best-rated part of its address book
+//! (`addr_book`): up to `MAX_PEX_PEERS` peers with up to `MAX_PEX_ADDRS` verified addresses
+//! each. Receivers dial the peers they are not connected to, so the mesh forms without every
+//! node depending on the bootstrap list.
+//!
+//! Only nodes running with an allowlist (a signed peer registry or `allow_peers`) take part,
+//! and a message only lists peers on it. It carries `registry_hash`, the SHA-256 of the
+//! sender's sorted allowlist: a node ignores messages from senders on another registry
+//! version, and treats a matching message naming a peer off the list as invalid.
+
+use crate::core::types::{decode_canonical_limited, encode_canonical};
+use crate::networking::addr_book::AddrBook;
+use libp2p::{Multiaddr, PeerId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use thiserror::Error;
+
+/// Gossip topic for peer exchange.
+pub const PEX_TOPIC: &str = "amunchain/pex/v1";
+/// Peers in one message.
+pub const MAX_PEX_PEERS: usize = 16;
+/// Addresses per peer in one message.
+pub const MAX_PEX_ADDRS: usize = 4;
+
+/// Upper bound for one encoded message.
+const MAX_PEX_BYTES: usize = 16 * 1024;
+
+/// Why a message was not used.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PexError {
+    #[error("encoding")]
+    Codec,
+    #[error("more than {MAX_PEX_PEERS} peers or {MAX_PEX_ADDRS} addresses per peer")]
+    TooLarge,
+    #[error("peer {0} is not in the registry")]
+    NotApproved(String),
+    /// The sender's allowlist differs from ours, e.g. during a registry rollout.
+    #[error("registry hash differs")]
+    RegistryMismatch,
+    /// This node runs without an allowlist and does not exchange peers.
+    #[error("no registry")]
+    NoRegistry,
+}
+
+impl PexError {
+    /// Whether the sender broke the protocol (as opposed to running another registry).
+    pub fn is_invalid(&self) -> bool {
+        matches!(
+            self,
+            PexError::Codec | PexError::TooLarge | PexError::NotApproved(_)
+        )
+    }
+}
+
+/// One shared peer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PexEntry {
+    /// Base58 peer id.
+    pub peer_id: String,
+    pub addrs: Vec<String>,
+}
+
+/// A peer exchange message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PexMessage {
+    /// `registry_hash` of the sender's allowlist.
+    pub registry_hash: [u8; 32],
+    pub peers: Vec<PexEntry>,
+}
+
+/// SHA-256 over the sorted base58 peer ids of `allow`, one per line.
+pub fn registry_hash<'a>(allow: impl IntoIterator<Item = &'a PeerId>) -> [u8; 32] {
+    let mut ids: Vec<String> = allow.into_iter().map(|p| p.to_base58()).collect();
+    ids.sort();
+    ids.dedup();
+    let digest = ring::digest::digest(&ring::digest::SHA256, ids.join("\n").as_bytes());
+    let mut out = [0u8; 32];
+    out.copy_from_slice(digest.as_ref());
+    out
+}
+
+impl PexMessage {
+    /// What this node shares: the best-rated approved peers of `book` other than `local`.
+    /// `None` without an allowlist or with nothing to share.
+    pub fn from_book(book: &AddrBook, allow: &HashSet<PeerId>, local: &PeerId) -> Option<Self> {
+        if allow.is_empty() {
+            return None;
+        }
+        let peers: Vec<PexEntry> = book
+            .best(usize::MAX)
+            .into_iter()
+            .filter(|(peer, _)| peer != local && allow.contains(peer))
+            .take(MAX_PEX_PEERS)
+            .map(|(peer, best)| {
+                let mut addrs = vec![best.to_string()];
+                addrs.extend(
+                    book.addresses(&peer)
+                        .into_iter()
+                        .filter(|a| *a != best)
+                        .map(|a| a.to_string()),
+                );
+                addrs.truncate(MAX_PEX_ADDRS);
+                PexEntry {
+                    peer_id: peer.to_base58(),
+                    addrs,
+                }
+            })
+            .collect();
+        if peers.is_empty() {
+            return None;
+        }
+        Some(Self {
+            registry_hash: registry_hash(allow),
+            peers,
+        })
+    }
+
+    /// Canonical encoding, as published on `PEX_TOPIC`.
+    pub fn encode(&self) -> Result<Vec<u8>, PexError> {
+        encode_canonical(self).map_err(|_| PexError::Codec)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, PexError> {
+        decode_canonical_limited(bytes, MAX_PEX_BYTES).map_err(|_| PexError::Codec)
+    }
+
+    /// Check the message against this node's allowlist and return the addresses to dial.
+    pub fn verify(&self, allow: &HashSet<PeerId>) -> Result<Vec<(PeerId, Multiaddr)>, PexError> {
+        if allow.is_empty() {
+            return Err(PexError::NoRegistry);
+        }
+        if self.registry_hash != registry_hash(allow) {
+            return Err(PexError::RegistryMismatch);
+        }
+        if self.peers.len() > MAX_PEX_PEERS {
+            return Err(PexError::TooLarge);
+        }
+        let mut out = Vec::new();
+        for entry in &self.peers {
+            if entry.addrs.len() > MAX_PEX_ADDRS {
+                return Err(PexError::TooLarge);
+            }
+            let peer = entry
+                .peer_id
+                .parse::<PeerId>()
+                .map_err(|_| PexError::Codec)?;
+            if !allow.contains(&peer) {
+                return Err(PexError::NotApproved(entry.peer_id.clone()));
+            }
+            for a in &entry.addrs {
+                let addr = a.parse::<Multiaddr>().map_err(|_| PexError::Codec)?;
+                out.push((peer, addr));
+            }
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,297 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Protobuf encoding of consensus messages, for clients outside Rust.
+//!
+//! The messages mirror `proto/amunchain/consensus.proto`; keep the tags in sync. Encoding is
+//! canonical: fields in tag order, defaults omitted (proto3), commit signatures sorted by
+//! validator. `decode_msg` re-encodes and rejects anything else (unknown fields, duplicate
+//! or unsorted signers, non-minimal varints), so a message has one protobuf form just as it
+//! has one bincode form.
+
+use crate::core::types::{self, H256};
+use crate::networking::wire::{MsgTiming, WireError, MAX_WIRE_BYTES};
+use prost::Message;
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Vote {
+    #[prost(uint64, tag = "1")]
+    pub height: u64,
+    #[prost(uint64, tag = "2")]
+    pub round: u64,
+    #[prost(uint64, tag = "3")]
+    pub epoch: u64,
+    #[prost(uint64, tag = "4")]
+    pub msg_counter: u64,
+    #[prost(uint64, tag = "5")]
+    pub sent_ts_ms: u64,
+    #[prost(uint32, tag = "6")]
+    pub ttl_ms: u32,
+    #[prost(bytes = "vec", tag = "7")]
+    pub block_hash: Vec<u8>,
+    #[prost(bytes = "vec", tag = "8")]
+    pub voter: Vec<u8>,
+    #[prost(bytes = "vec", tag = "9")]
+    pub signature: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommitSignature {
+    #[prost(bytes = "vec", tag = "1")]
+    pub validator: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub signature: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Commit {
+    #[prost(uint64, tag = "1")]
+    pub height: u64,
+    #[prost(uint64, tag = "2")]
+    pub round: u64,
+    #[prost(uint64, tag = "3")]
+    pub epoch: u64,
+    #[prost(uint64, tag = "4")]
+    pub msg_counter: u64,
+    #[prost(uint64, tag = "5")]
+    pub sent_ts_ms: u64,
+    #[prost(uint32, tag = "6")]
+    pub ttl_ms: u32,
+    #[prost(bytes = "vec", tag = "7")]
+    pub block_hash: Vec<u8>,
+    /// Ascending by `validator`, no duplicates.
+    #[prost(message, repeated, tag = "8")]
+    pub signatures: Vec<CommitSignature>,
+    /// Low and high 64 bits of the u128 voting power.
+    #[prost(uint64, tag = "9")]
+    pub voting_power_lo: u64,
+    #[prost(uint64, tag = "10")]
+    pub voting_power_hi: u64,
+    /// 32 bytes, or empty for an unbound (zero) hash.
+    #[prost(bytes = "vec", tag = "11")]
+    pub validator_set_hash: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ConsensusMsg {
+    #[prost(oneof = "consensus_msg::Msg", tags = "1, 2")]
+    pub msg: Option<consensus_msg::Msg>,
+}
+
+pub mod consensus_msg {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Msg {
+        #[prost(message, tag = "1")]
+        Vote(super::Vote),
+        #[prost(message, tag = "2")]
+        Commit(super::Commit),
+    }
+}
+
+/// `sent_ts_ms` and `ttl_ms` of a `Vote` or `Commit`, which share the tags; decoding skips
+/// every other field.
+#[derive(Clone, PartialEq, Message)]
+struct Timing {
+    #[prost(uint64, tag = "5")]
+    sent_ts_ms: u64,
+    #[prost(uint32, tag = "6")]
+    ttl_ms: u32,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct TimingMsg {
+    #[prost(oneof = "timing_msg::Msg", tags = "1, 2")]
+    msg: Option<timing_msg::Msg>,
+}
+
+mod timing_msg {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Msg {
+        #[prost(message, tag = "1")]
+        Vote(super::Timing),
+        #[prost(message, tag = "2")]
+        Commit(super::Timing),
+    }
+}
+
+/// Timing fields of an encoded `ConsensusMsg`, without decoding the rest.
+pub fn peek_timing(bytes: &[u8]) -> Option<MsgTiming> {
+    if bytes.len() > MAX_WIRE_BYTES {
+        return None;
+    }
+    let (timing_msg::Msg::Vote(t) | timing_msg::Msg::Commit(t)) =
+        TimingMsg::decode(bytes).ok()?.msg?;
+    Some(MsgTiming {
+        sent_ts_ms: t.sent_ts_ms,
+        ttl_ms: t.ttl_ms,
+    })
+}
+
+fn hash(bytes: &[u8]) -> Result<H256, WireError> {
+    bytes
+        .try_into()
+        .map(H256::from_bytes)
+        .map_err(|_| WireError::Malformed)
+}
+
+impl From<&types::Vote> for Vote {
+    fn from(v: &types::Vote) -> Self {
+        Self {
+            height: v.height.get(),
+            round: v.round.get(),
+            epoch: v.epoch.get(),
+            msg_counter: v.msg_counter,
+            sent_ts_ms: v.sent_ts_ms,
+            ttl_ms: v.ttl_ms,
+            block_hash: v.block_hash.as_bytes().to_vec(),
+            voter: v.voter.as_bytes().to_vec(),
+            signature: v.signature.as_bytes().to_vec(),
+        }
+    }
+}
+
+impl TryFrom<Vote> for types::Vote {
+    type Error = WireError;
+
+    fn try_from(v: Vote) -> Result<Self, WireError> {
+        Ok(Self {
+            height: types::Height(v.height),
+            round: types::Round(v.round),
+            epoch: types::Epoch(v.epoch),
+            msg_counter: v.msg_counter,
+            sent_ts_ms: v.sent_ts_ms,
+            ttl_ms: v.ttl_ms,
+            block_hash: hash(&v.block_hash)?,
+            voter: v.voter.try_into().map_err(|_| WireError::Malformed)?,
+            signature: v.signature.try_into().map_err(|_| WireError::Malformed)?,
+        })
+    }
+}
+
+impl From<&types::Commit> for Commit {
+    fn from(c: &types::Commit) -> Self {
+        Self {
+            height: c.height.get(),
+            round: c.round.get(),
+            epoch: c.epoch.get(),
+            msg_counter: c.msg_counter,
+            sent_ts_ms: c.sent_ts_ms,
+            ttl_ms: c.ttl_ms,
+            block_hash: c.block_hash.as_bytes().to_vec(),
+            signatures: c
+                .signatures
+                .iter()
+                .map(|(v, s)| CommitSignature {
+                    validator: v.as_bytes().to_vec(),
+                    signature: s.as_bytes().to_vec(),
+                })
+                .collect(),
+            voting_power_lo: c.voting_power as u64,
+            voting_power_hi: (c.voting_power >> 64) as u64,
+            validator_set_hash: if c.validator_set_hash == H256::ZERO {
+                Vec::new()
+            } else {
+                c.validator_set_hash.as_bytes().to_vec()
+            },
+        }
+    }
+}
+
+impl TryFrom<Commit> for types::Commit {
+    type Error = WireError;
+
+    fn try_from(c: Commit) -> Result<Self, WireError> {
+        Ok(Self {
+            height: types::Height(c.height),
+            round: types::Round(c.round),
+            epoch: types::Epoch(c.epoch),
+            msg_counter: c.msg_counter,
+            sent_ts_ms: c.sent_ts_ms,
+            ttl_ms: c.ttl_ms,
+            block_hash: hash(&c.block_hash)?,
+            signatures: c
+                .signatures
+                .into_iter()
+                .map(|s| {
+                    Ok((
+                        s.validator.try_into().map_err(|_| WireError::Malformed)?,
+                        s.signature.try_into().map_err(|_| WireError::Malformed)?,
+                    ))
+                })
+                .collect::<Result<_, WireError>>()?,
+            voting_power: (u128::from(c.voting_power_hi) << 64) | u128::from(c.voting_power_lo),
+            validator_set_hash: if c.validator_set_hash.is_empty() {
+                H256::ZERO
+            } else {
+                hash(&c.validator_set_hash)?
+            },
+        })
+    }
+}
+
+impl From<&types::ConsensusMsg> for ConsensusMsg {
+    fn from(m: &types::ConsensusMsg) -> Self {
+        let msg = match m {
+            types::ConsensusMsg::Vote(v) => consensus_msg::Msg::Vote(v.into()),
+            types::ConsensusMsg::Commit(c) => consensus_msg::Msg::Commit(c.into()),
+        };
+        Self { msg: Some(msg) }
+    }
+}
+
+impl TryFrom<ConsensusMsg> for types::ConsensusMsg {
+    type Error = WireError;
+
+    fn try_from(m: ConsensusMsg) -> Result<Self, WireError> {
+        match m.msg.ok_or(WireError::Malformed)? {
+            consensus_msg::Msg::Vote(v) => Ok(Self::Vote(v.try_into()?)),
+            consensus_msg::Msg::Commit(c) => Ok(Self::Commit(c.try_into()?)),
+        }
+    }
+}
+
+/// Canonical protobuf bytes of `msg`.
+pub fn encode_msg(msg: &types::ConsensusMsg) -> Result<Vec<u8>, WireError> {
+    encode_msg_capped(msg, MAX_WIRE_BYTES)
+}
+
+pub(crate) fn encode_msg_capped(
+    msg: &types::ConsensusMsg,
+    max: usize,
+) -> Result<Vec<u8>, WireError> {
+    let bytes = ConsensusMsg::from(msg).encode_to_vec();
+    if bytes.len() > max {
+        return Err(WireError::TooLarge);
+    }
+    Ok(bytes)
+}
+
+/// Decode canonical protobuf; other encodings of the same message are rejected.
+pub fn decode_msg(bytes: &[u8]) -> Result<types::ConsensusMsg, WireError> {
+    decode_msg_capped(bytes, MAX_WIRE_BYTES)
+}
+
+pub(crate) fn decode_msg_capped(
+    bytes: &[u8],
+    max: usize,
+) -> Result<types::ConsensusMsg, WireError> {
+    if bytes.len() > max {
+        return Err(WireError::TooLarge);
+    }
+    let pb = ConsensusMsg::decode(bytes).map_err(|_| WireError::Malformed)?;
+    let msg = types::ConsensusMsg::try_from(pb)?;
+    if encode_msg_capped(&msg, max)? != bytes {
+        return Err(WireError::Malformed);
+    }
+    Ok(msg)
+}
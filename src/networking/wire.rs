@@ -0,0 +1,531 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Versioned gossip encoding for `ConsensusMsg`.
+//!
+//! - v1: the bare message, `bincode::serialize(&msg)`, as sent by nodes that predate the
+//!   envelope.
+//! - v2: `ENVELOPE_MAGIC` followed by a canonical `WireEnvelope { version, payload }` whose
+//!   payload is the canonically encoded message.
+//! - v3: the v2 envelope with a zstd-compressed payload. Only messages of at least
+//!   `COMPRESS_MIN_BYTES` that shrink are compressed (in practice commits with many
+//!   signatures); a node sending v3 puts the rest in v2 envelopes, which every v3 reader also
+//!   reads. Decompression stops at the size limit uncompressed messages have.
+//!
+//! A v1 message starts with its variant tag (a little-endian u32, so `0x00` or `0x01`), which
+//! never collides with the magic; `decode_msg` therefore reads both forms. Nodes advertise
+//! the versions they read in their identify protocol string (`amunchain/1.0.0 wire=1,2`; a
+//! string without `wire=` means v1 only) and publish with the highest version every connected
+//! peer reads. During an upgrade the network keeps sending v1 until the last old node leaves.
+//!
+//! That applies to topics using the default `WireCodec::Bincode`. A topic configured with
+//! `WireCodec::Protobuf` carries bare protobuf messages (`networking::proto`) instead, for
+//! clients outside Rust; every node on such a topic must use that codec.
+//!
+//! `Evidence` has its own topic and is always sent enveloped (`encode_evidence`): it is
+//! newer than v1, so no peer that could read it needs the bare form. The same holds for
+//! compact commits and vote requests on `COMMIT_SYNC_TOPIC` (`encode_commit_sync`).
+
+use crate::core::consensus::compact::{CommitSync, MAX_REQUESTED_VOTES};
+use crate::core::consensus::signing::SigningDomain;
+use crate::core::types::{
+    decode_canonical_limited, encode_canonical, ConsensusMsg, Evidence, Vote, H256,
+};
+use crate::networking::wire_limits::{MsgClass, WireLimits};
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use thiserror::Error;
+
+/// Bare legacy encoding.
+pub const WIRE_V1: u16 = 1;
+/// Enveloped canonical encoding.
+pub const WIRE_V2: u16 = 2;
+/// Enveloped canonical encoding, zstd-compressed.
+pub const WIRE_V3: u16 = 3;
+/// Versions this node reads and may send, ascending.
+pub const SUPPORTED_WIRE_VERSIONS: &[u16] = &[WIRE_V1, WIRE_V2, WIRE_V3];
+
+/// Smallest canonical payload a v3 sender compresses; below this zstd's frame overhead
+/// eats the gain.
+pub const COMPRESS_MIN_BYTES: usize = 1024;
+
+const ZSTD_LEVEL: i32 = 3;
+
+/// Upper bound for one consensus gossip message, and the default commit limit. The p2p layer
+/// applies the per-class limits of `wire_limits` instead (`WireCodec::decode_within`).
+pub const MAX_WIRE_BYTES: usize = 256 * 1024;
+
+/// Leading bytes of an enveloped message.
+pub const ENVELOPE_MAGIC: [u8; 2] = [0xa3, 0x57];
+
+/// Gossip topic for compact commits and vote requests (`core::consensus::compact`).
+pub const COMMIT_SYNC_TOPIC: &str = "amunchain/commit-sync/v1";
+
+/// Identify protocol string prefix; the wire versions follow as ` wire=1,2`.
+const PROTOCOL_PREFIX: &str = "amunchain/1.0.0";
+
+/// Encoding of a consensus gossip topic.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WireCodec {
+    /// Versioned bincode (see the module docs).
+    #[default]
+    Bincode,
+    /// Canonical protobuf, `proto/amunchain/consensus.proto`.
+    Protobuf,
+}
+
+impl WireCodec {
+    /// Encode `msg`; `version` is the negotiated bincode wire version and is ignored for
+    /// protobuf.
+    pub fn encode(self, msg: &ConsensusMsg, version: u16) -> Result<Vec<u8>, WireError> {
+        match self {
+            WireCodec::Bincode => encode_msg(msg, version),
+            WireCodec::Protobuf => crate::networking::proto::encode_msg(msg),
+        }
+    }
+
+    /// Decode a message received on a topic using this codec.
+    pub fn decode(self, bytes: &[u8]) -> Result<ConsensusMsg, WireError> {
+        match self {
+            WireCodec::Bincode => decode_msg(bytes).map(|(_, msg)| msg),
+            WireCodec::Protobuf => crate::networking::proto::decode_msg(bytes),
+        }
+    }
+
+    /// Sender timestamp and TTL of an encoded message, read without decoding the rest (no
+    /// signatures, no allocation). `None` when the header does not parse; the full decode
+    /// then reports why.
+    pub fn peek_timing(self, bytes: &[u8]) -> Option<MsgTiming> {
+        match self {
+            WireCodec::Bincode => peek_bincode_timing(bytes),
+            WireCodec::Protobuf => crate::networking::proto::peek_timing(bytes),
+        }
+    }
+
+    /// `decode` plus the checks that need no chain state (see `validate_msg`).
+    pub fn decode_validated(self, bytes: &[u8]) -> Result<ConsensusMsg, WireError> {
+        let msg = self.decode(bytes)?;
+        validate_msg(&msg)?;
+        Ok(msg)
+    }
+
+    /// `encode` within the limit for the class of `msg` rather than `MAX_WIRE_BYTES`.
+    pub fn encode_within(
+        self,
+        msg: &ConsensusMsg,
+        version: u16,
+        limits: &WireLimits,
+    ) -> Result<Vec<u8>, WireError> {
+        let max = limits.bytes(MsgClass::of(msg));
+        match self {
+            WireCodec::Bincode => encode_capped(msg, version, max),
+            WireCodec::Protobuf => crate::networking::proto::encode_msg_capped(msg, max),
+        }
+    }
+
+    /// `decode_validated` within `limits` rather than `MAX_WIRE_BYTES`: the encoded message
+    /// may not exceed the limit for its class. This is what the p2p layer runs on every
+    /// inbound consensus message.
+    pub fn decode_within(
+        self,
+        bytes: &[u8],
+        limits: &WireLimits,
+    ) -> Result<ConsensusMsg, WireError> {
+        let max = limits.consensus_bytes();
+        let msg = match self {
+            WireCodec::Bincode => decode_capped(bytes, max)?.1,
+            WireCodec::Protobuf => crate::networking::proto::decode_msg_capped(bytes, max)?,
+        };
+        if !limits.admits(MsgClass::of(&msg), bytes.len()) {
+            return Err(WireError::TooLarge);
+        }
+        validate_msg(&msg)?;
+        Ok(msg)
+    }
+}
+
+/// Freshness fields shared by `Vote` and `Commit`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MsgTiming {
+    pub sent_ts_ms: u64,
+    pub ttl_ms: u32,
+}
+
+impl MsgTiming {
+    /// Whether the TTL ran out more than `grace_ms` before `now_ms`, the bound Tide applies
+    /// with its clock skew. Legacy messages (no timestamp or no TTL) never expire.
+    pub fn is_expired(&self, now_ms: u64, grace_ms: u64) -> bool {
+        if self.sent_ts_ms == 0 || self.ttl_ms == 0 {
+            return false;
+        }
+        let expiry = self.sent_ts_ms.saturating_add(u64::from(self.ttl_ms));
+        now_ms > expiry.saturating_add(grace_ms)
+    }
+}
+
+/// Variant tag, then the leading `Vote`/`Commit` fields up to `ttl_ms`; both bincode forms
+/// use fixed-width little-endian integers.
+#[derive(Deserialize)]
+struct TimingHeader {
+    tag: u32,
+    /// height, round, epoch, msg_counter
+    _position: [u64; 4],
+    sent_ts_ms: u64,
+    ttl_ms: u32,
+}
+
+/// Encoded size of `TimingHeader`.
+const TIMING_HEADER_LEN: usize = 4 + 5 * 8 + 4;
+
+fn peek_bincode_timing(bytes: &[u8]) -> Option<MsgTiming> {
+    let mut head = [0u8; TIMING_HEADER_LEN];
+    let body = match bytes.strip_prefix(&ENVELOPE_MAGIC) {
+        // `WireEnvelope`: version (u16), payload length (u64), payload.
+        Some(framed) if framed.get(..2)? == WIRE_V3.to_le_bytes() => {
+            // Inflate only as far as the header.
+            let mut decoder = zstd::stream::read::Decoder::with_buffer(framed.get(10..)?).ok()?;
+            decoder.read_exact(&mut head).ok()?;
+            &head[..]
+        }
+        Some(framed) => framed.get(10..)?,
+        None => bytes,
+    };
+    let header: TimingHeader = bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .deserialize(body)
+        .ok()?;
+    (header.tag <= 1).then_some(MsgTiming {
+        sent_ts_ms: header.sent_ts_ms,
+        ttl_ms: header.ttl_ms,
+    })
+}
+
+/// Untrusted-input path of a default (bincode) consensus topic, as one call for fuzzing.
+pub fn decode_and_validate_consensus_msg(bytes: &[u8]) -> Result<ConsensusMsg, WireError> {
+    WireCodec::Bincode.decode_validated(bytes)
+}
+
+/// Stateless checks on a decoded message: the height is not zero, a commit has signers, and
+/// the signing payload can be rebuilt for every signer (with the legacy window open, so both
+/// payload versions are exercised). Signatures themselves are verified later, by Tide.
+pub fn validate_msg(msg: &ConsensusMsg) -> Result<(), WireError> {
+    let domain = SigningDomain::LEGACY;
+    match msg {
+        ConsensusMsg::Vote(v) => validate_vote(v)?,
+        ConsensusMsg::Commit(c) => {
+            if c.height.is_zero() || c.signatures.is_empty() {
+                return Err(WireError::Invalid);
+            }
+            for signer in c.signatures.keys() {
+                domain
+                    .candidates(
+                        c.height,
+                        c.round,
+                        c.epoch,
+                        c.msg_counter,
+                        c.sent_ts_ms,
+                        c.ttl_ms,
+                        c.block_hash,
+                        c.validator_set_hash,
+                        signer,
+                    )
+                    .map_err(|_| WireError::Invalid)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_vote(v: &Vote) -> Result<(), WireError> {
+    if v.height.is_zero() {
+        return Err(WireError::Invalid);
+    }
+    SigningDomain::LEGACY
+        .candidates(
+            v.height,
+            v.round,
+            v.epoch,
+            v.msg_counter,
+            v.sent_ts_ms,
+            v.ttl_ms,
+            v.block_hash,
+            H256::ZERO,
+            &v.voter,
+        )
+        .map_err(|_| WireError::Invalid)?;
+    Ok(())
+}
+
+/// Stateless checks on decoded evidence: both votes pass `validate_msg` and conflict.
+pub fn validate_evidence(ev: &Evidence) -> Result<(), WireError> {
+    match ev {
+        Evidence::DoubleVote(dv) => {
+            validate_vote(&dv.first)?;
+            validate_vote(&dv.second)?;
+            if !dv.is_conflicting() {
+                return Err(WireError::Invalid);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Encode evidence for the evidence topic, as a v2 envelope.
+pub fn encode_evidence(ev: &Evidence) -> Result<Vec<u8>, WireError> {
+    encode_v2(ev)
+}
+
+/// Decode evidence from the evidence topic and `validate_evidence` it. The vote signatures
+/// are verified later, by Tide.
+pub fn decode_evidence(bytes: &[u8]) -> Result<Evidence, WireError> {
+    let ev = decode_v2(bytes)?;
+    validate_evidence(&ev)?;
+    Ok(ev)
+}
+
+/// Encode a commit sync message for `COMMIT_SYNC_TOPIC`, as a v2 envelope.
+pub fn encode_commit_sync(msg: &CommitSync) -> Result<Vec<u8>, WireError> {
+    encode_v2(msg)
+}
+
+/// Decode a commit sync message. Only stateless checks apply; the signer bitmap is checked
+/// against the validator set by the driver.
+pub fn decode_commit_sync(bytes: &[u8]) -> Result<CommitSync, WireError> {
+    let msg = decode_v2(bytes)?;
+    let valid = match &msg {
+        CommitSync::Compact(c) => !c.height.is_zero() && !c.vote_hashes.is_empty(),
+        CommitSync::Request(r) => {
+            !r.height.is_zero() && !r.voters.is_empty() && r.voters.len() <= MAX_REQUESTED_VOTES
+        }
+    };
+    if !valid {
+        return Err(WireError::Invalid);
+    }
+    Ok(msg)
+}
+
+fn encode_v2<T: Serialize>(v: &T) -> Result<Vec<u8>, WireError> {
+    let envelope = WireEnvelope {
+        version: WIRE_V2,
+        payload: encode_canonical(v).map_err(|_| WireError::Malformed)?,
+    };
+    let mut out = ENVELOPE_MAGIC.to_vec();
+    out.extend(encode_canonical(&envelope).map_err(|_| WireError::Malformed)?);
+    if out.len() > MAX_WIRE_BYTES {
+        return Err(WireError::TooLarge);
+    }
+    Ok(out)
+}
+
+fn decode_v2<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T, WireError> {
+    if bytes.len() > MAX_WIRE_BYTES {
+        return Err(WireError::TooLarge);
+    }
+    let framed = bytes
+        .strip_prefix(&ENVELOPE_MAGIC)
+        .ok_or(WireError::Malformed)?;
+    let envelope: WireEnvelope =
+        decode_canonical_limited(framed, MAX_WIRE_BYTES).map_err(|_| WireError::Malformed)?;
+    if envelope.version != WIRE_V2 {
+        return Err(WireError::UnsupportedVersion(envelope.version));
+    }
+    decode_canonical_limited(&envelope.payload, MAX_WIRE_BYTES).map_err(|_| WireError::Malformed)
+}
+
+/// Versioned message frame (v2 and later).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WireEnvelope {
+    /// Encoding of `payload`.
+    pub version: u16,
+    /// The encoded message.
+    pub payload: Vec<u8>,
+}
+
+#[derive(Debug, Error)]
+pub enum WireError {
+    #[error("unsupported wire version {0}")]
+    UnsupportedVersion(u16),
+    #[error("message exceeds its size limit")]
+    TooLarge,
+    #[error("malformed message")]
+    Malformed,
+    #[error("invalid message")]
+    Invalid,
+}
+
+/// Encode `msg` for gossip in wire format `version`. With v3 the result is a v2 envelope when
+/// compression does not pay.
+pub fn encode_msg(msg: &ConsensusMsg, version: u16) -> Result<Vec<u8>, WireError> {
+    encode_capped(msg, version, MAX_WIRE_BYTES)
+}
+
+fn encode_capped(msg: &ConsensusMsg, version: u16, max: usize) -> Result<Vec<u8>, WireError> {
+    let bytes = match version {
+        WIRE_V1 => bincode::serialize(msg).map_err(|_| WireError::Malformed)?,
+        WIRE_V2 | WIRE_V3 => {
+            let payload = encode_canonical(msg).map_err(|_| WireError::Malformed)?;
+            let compressed = if version == WIRE_V3 && payload.len() >= COMPRESS_MIN_BYTES {
+                zstd::bulk::compress(&payload, ZSTD_LEVEL)
+                    .ok()
+                    .filter(|c| c.len() < payload.len())
+            } else {
+                None
+            };
+            let envelope = match compressed {
+                Some(payload) => WireEnvelope {
+                    version: WIRE_V3,
+                    payload,
+                },
+                None => WireEnvelope {
+                    version: WIRE_V2,
+                    payload,
+                },
+            };
+            let mut out = ENVELOPE_MAGIC.to_vec();
+            out.extend(encode_canonical(&envelope).map_err(|_| WireError::Malformed)?);
+            out
+        }
+        v => return Err(WireError::UnsupportedVersion(v)),
+    };
+    if bytes.len() > max {
+        return Err(WireError::TooLarge);
+    }
+    Ok(bytes)
+}
+
+/// Decode a gossip message in any supported format; returns the version it arrived in.
+pub fn decode_msg(bytes: &[u8]) -> Result<(u16, ConsensusMsg), WireError> {
+    decode_capped(bytes, MAX_WIRE_BYTES)
+}
+
+fn decode_capped(bytes: &[u8], max: usize) -> Result<(u16, ConsensusMsg), WireError> {
+    if bytes.len() > max {
+        return Err(WireError::TooLarge);
+    }
+    let Some(framed) = bytes.strip_prefix(&ENVELOPE_MAGIC) else {
+        let msg = bincode::deserialize(bytes).map_err(|_| WireError::Malformed)?;
+        return Ok((WIRE_V1, msg));
+    };
+    let envelope: WireEnvelope =
+        decode_canonical_limited(framed, max).map_err(|_| WireError::Malformed)?;
+    let msg = match envelope.version {
+        WIRE_V1 => bincode::deserialize(&envelope.payload).map_err(|_| WireError::Malformed)?,
+        WIRE_V2 => {
+            decode_canonical_limited(&envelope.payload, max).map_err(|_| WireError::Malformed)?
+        }
+        WIRE_V3 => decode_canonical_limited(&inflate(&envelope.payload, max)?, max)
+            .map_err(|_| WireError::Malformed)?,
+        v => return Err(WireError::UnsupportedVersion(v)),
+    };
+    Ok((envelope.version, msg))
+}
+
+/// Decompress a v3 payload, reading at most one byte past `max` so a small bomb cannot
+/// inflate.
+fn inflate(payload: &[u8], max: usize) -> Result<Vec<u8>, WireError> {
+    let decoder =
+        zstd::stream::read::Decoder::with_buffer(payload).map_err(|_| WireError::Malformed)?;
+    let mut out = Vec::new();
+    decoder
+        .take(max as u64 + 1)
+        .read_to_end(&mut out)
+        .map_err(|_| WireError::Malformed)?;
+    if out.len() > max {
+        return Err(WireError::TooLarge);
+    }
+    Ok(out)
+}
+
+/// Identify protocol string advertising `versions`.
+pub fn identify_protocol(versions: &[u16]) -> String {
+    let list: Vec<String> = versions.iter().map(u16::to_string).collect();
+    format!("{PROTOCOL_PREFIX} wire={}", list.join(","))
+}
+
+/// Wire versions a peer advertises; v1 only when it names none. Unparseable entries are
+/// skipped.
+pub fn parse_identify_protocol(protocol: &str) -> Vec<u16> {
+    let mut versions: Vec<u16> = protocol
+        .split_whitespace()
+        .find_map(|part| part.strip_prefix("wire="))
+        .map(|list| list.split(',').filter_map(|v| v.parse().ok()).collect())
+        .unwrap_or_default();
+    versions.sort_unstable();
+    versions.dedup();
+    if versions.is_empty() {
+        versions.push(WIRE_V1);
+    }
+    versions
+}
+
+/// Highest version both sides read.
+pub fn negotiate(local: &[u16], remote: &[u16]) -> Option<u16> {
+    local.iter().filter(|v| remote.contains(v)).max().copied()
+}
+
+/// Negotiated version per connected peer, and the version to publish with.
+#[derive(Debug)]
+pub struct PeerVersions {
+    local: Vec<u16>,
+    peers: HashMap<Vec<u8>, u16>,
+}
+
+impl PeerVersions {
+    /// Track peers against the versions this node supports.
+    pub fn new(local: &[u16]) -> Self {
+        Self {
+            local: local.to_vec(),
+            peers: HashMap::new(),
+        }
+    }
+
+    /// A peer connected; until it identifies, assume it reads v1 only.
+    pub fn connected(&mut self, peer: Vec<u8>) {
+        self.peers.entry(peer).or_insert(WIRE_V1);
+    }
+
+    /// A peer identified with `protocol`. Returns the negotiated version, or `None` when the
+    /// two nodes share none (the peer is then no longer counted).
+    pub fn identified(&mut self, peer: Vec<u8>, protocol: &str) -> Option<u16> {
+        match negotiate(&self.local, &parse_identify_protocol(protocol)) {
+            Some(v) => {
+                self.peers.insert(peer, v);
+                Some(v)
+            }
+            None => {
+                self.peers.remove(&peer);
+                None
+            }
+        }
+    }
+
+    /// The peer's last connection closed.
+    pub fn disconnected(&mut self, peer: &[u8]) {
+        self.peers.remove(peer);
+    }
+
+    /// Version every connected peer reads: the lowest negotiated one, or the newest local
+    /// version when no peer is connected.
+    pub fn send_version(&self) -> u16 {
+        self.peers
+            .values()
+            .min()
+            .copied()
+            .or_else(|| self.local.iter().max().copied())
+            .unwrap_or(WIRE_V1)
+    }
+}
@@ -0,0 +1,165 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Gossip message size limits per message class (`[p2p.limits]`).
+//!
+//! A vote is a few hundred bytes and a commit grows with the validator set, so one cap for
+//! both either lets any peer send 256 KiB votes or caps the validator set. Each class has its
+//! own limit instead, checked on the encoded message before it is decoded and when it is
+//! published. Gossipsub's `max_transmit_size` follows the largest limit plus framing, so only
+//! that class needs a large frame.
+
+use crate::core::types::ConsensusMsg;
+use crate::networking::wire::MAX_WIRE_BYTES;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Smallest accepted limit; an encoded vote alone is close to this.
+pub const MIN_LIMIT_BYTES: usize = 512;
+/// Largest accepted vote or commit limit.
+pub const MAX_LIMIT_BYTES: usize = 16 * 1024 * 1024;
+
+/// Room in a gossipsub frame for the message envelope (source, sequence number, signature,
+/// topic) and the RPC framing around it.
+const FRAME_OVERHEAD_BYTES: usize = 4 * 1024;
+
+/// What a gossip message carries, for its size limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MsgClass {
+    Vote,
+    Commit,
+    Evidence,
+    CommitSync,
+    /// Peer exchange and extension topics.
+    Other,
+}
+
+impl MsgClass {
+    /// Class of a consensus topic message.
+    pub fn of(msg: &ConsensusMsg) -> Self {
+        match msg {
+            ConsensusMsg::Vote(_) => MsgClass::Vote,
+            ConsensusMsg::Commit(_) => MsgClass::Commit,
+        }
+    }
+
+    /// Metric label: the kind of topic the class travels on.
+    pub fn topic(self) -> &'static str {
+        match self {
+            MsgClass::Vote | MsgClass::Commit => "consensus",
+            MsgClass::Evidence => "evidence",
+            MsgClass::CommitSync => "commit_sync",
+            MsgClass::Other => "other",
+        }
+    }
+}
+
+/// Largest encoded message accepted or sent per class, in bytes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WireLimits {
+    pub vote_bytes: usize,
+    /// Commits carry one signature per signer; raise this for large validator sets.
+    pub commit_bytes: usize,
+    /// Double-vote evidence (two votes).
+    pub evidence_bytes: usize,
+    /// Compact commits and vote requests.
+    pub commit_sync_bytes: usize,
+    /// Peer exchange and extension topics.
+    pub other_bytes: usize,
+}
+
+impl Default for WireLimits {
+    fn default() -> Self {
+        Self {
+            vote_bytes: 4 * 1024,
+            commit_bytes: MAX_WIRE_BYTES,
+            evidence_bytes: 16 * 1024,
+            commit_sync_bytes: 64 * 1024,
+            // The gossipsub default every topic had before the limits.
+            other_bytes: 64 * 1024,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum WireLimitsError {
+    #[error("{field} ({bytes}) must be within {MIN_LIMIT_BYTES}..={max}")]
+    OutOfRange {
+        field: &'static str,
+        bytes: usize,
+        max: usize,
+    },
+}
+
+impl WireLimitsError {
+    /// The `[p2p.limits]` key at fault.
+    pub fn field(&self) -> &'static str {
+        match self {
+            WireLimitsError::OutOfRange { field, .. } => field,
+        }
+    }
+}
+
+impl WireLimits {
+    /// The limit for `class`.
+    pub fn bytes(&self, class: MsgClass) -> usize {
+        match class {
+            MsgClass::Vote => self.vote_bytes,
+            MsgClass::Commit => self.commit_bytes,
+            MsgClass::Evidence => self.evidence_bytes,
+            MsgClass::CommitSync => self.commit_sync_bytes,
+            MsgClass::Other => self.other_bytes,
+        }
+    }
+
+    /// Whether an encoded `class` message of `len` bytes is within its limit.
+    pub fn admits(&self, class: MsgClass, len: usize) -> bool {
+        len <= self.bytes(class)
+    }
+
+    /// Largest message on the consensus topic, which carries both votes and commits.
+    pub fn consensus_bytes(&self) -> usize {
+        self.vote_bytes.max(self.commit_bytes)
+    }
+
+    /// Gossipsub `max_transmit_size`: the largest limit plus framing.
+    pub fn transmit_size(&self) -> usize {
+        let largest = [
+            self.consensus_bytes(),
+            self.evidence_bytes,
+            self.commit_sync_bytes,
+            self.other_bytes,
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(MAX_WIRE_BYTES);
+        largest.saturating_add(FRAME_OVERHEAD_BYTES)
+    }
+
+    /// Every problem with these values; empty when they are usable. Votes and commits may go
+    /// up to `MAX_LIMIT_BYTES`; the other classes are decoded within `MAX_WIRE_BYTES`.
+    pub fn validate(&self) -> Vec<WireLimitsError> {
+        [
+            ("vote_bytes", self.vote_bytes, MAX_LIMIT_BYTES),
+            ("commit_bytes", self.commit_bytes, MAX_LIMIT_BYTES),
+            ("evidence_bytes", self.evidence_bytes, MAX_WIRE_BYTES),
+            ("commit_sync_bytes", self.commit_sync_bytes, MAX_WIRE_BYTES),
+            ("other_bytes", self.other_bytes, MAX_WIRE_BYTES),
+        ]
+        .into_iter()
+        .filter(|(_, bytes, max)| !(MIN_LIMIT_BYTES..=*max).contains(bytes))
+        .map(|(field, bytes, max)| WireLimitsError::OutOfRange { field, bytes, max })
+        .collect()
+    }
+}
@@ -0,0 +1,276 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Anti-entropy: periodic finalized-head announcements.
+//!
+//! A node that misses a commit gets no further gossip about that height, so it cannot tell
+//! that it is behind. Every `p2p.status_interval_secs` each node publishes its finalized
+//! height, block hash and state root on `STATUS_TOPIC`. When a peer reports a head more than
+//! `p2p.sync_lag_threshold` heights ahead of ours, the highest such report becomes the current
+//! `SyncTarget` for the sync protocol to fetch from.
+//!
+//! Announcements are unauthenticated hints: a target only says where to look, and whatever
+//! is fetched from it must still carry valid commit certificates.
+
+use crate::core::state::commit_store::CommitStore;
+use crate::core::state::persistent_state::PersistentState;
+use crate::core::types::{decode_canonical_limited, encode_canonical, CodecError, Height, H256};
+use crate::node::channel::Sender;
+use crate::node::extensions::{ExtensionError, ExtensionRegistry, GossipHandler, NodeExtension};
+use crate::rpc::server::SharedDriver;
+use prometheus::{IntCounter, IntGauge};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{debug, info, warn};
+
+/// Gossip topic for head announcements.
+pub const STATUS_TOPIC: &str = "amunchain/status/v1";
+
+/// Upper bound for one encoded announcement.
+const MAX_STATUS_BYTES: usize = 256;
+
+/// A node's finalized head.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeadStatus {
+    /// Highest finalized height.
+    pub height: Height,
+    /// Block finalized at `height` (zero before the first commit).
+    pub block_hash: H256,
+    /// State root after applying `height`.
+    pub state_root: H256,
+}
+
+impl HeadStatus {
+    /// Head of a node that has finalized nothing yet.
+    pub const GENESIS: Self = Self {
+        height: Height::ZERO,
+        block_hash: H256::ZERO,
+        state_root: H256::ZERO,
+    };
+
+    /// Canonical encoding, as published on `STATUS_TOPIC`.
+    pub fn encode(&self) -> Result<Vec<u8>, CodecError> {
+        encode_canonical(self)
+    }
+
+    /// Decode an announcement received from a peer.
+    pub fn decode(bytes: &[u8]) -> Result<Self, CodecError> {
+        decode_canonical_limited(bytes, MAX_STATUS_BYTES)
+    }
+}
+
+/// A peer whose finalized head is far enough ahead of ours to sync from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyncTarget {
+    /// Peer id bytes of the announcer.
+    pub peer: Vec<u8>,
+    /// Our finalized height when the announcement arrived.
+    pub local: Height,
+    /// The announced head.
+    pub remote: HeadStatus,
+}
+
+/// Tracks the local head against peer announcements. Cheap to clone; clones share state.
+#[derive(Clone)]
+pub struct AntiEntropy {
+    local: watch::Sender<HeadStatus>,
+    target: watch::Sender<Option<SyncTarget>>,
+    lag_threshold: u64,
+    lag: IntGauge,
+    triggers: IntCounter,
+}
+
+impl AntiEntropy {
+    /// Heads more than `lag_threshold` heights above ours trigger a sync.
+    pub fn new(lag_threshold: u64) -> Result<Self, prometheus::Error> {
+        Ok(Self {
+            local: watch::Sender::new(HeadStatus::GENESIS),
+            target: watch::Sender::new(None),
+            lag_threshold,
+            lag: IntGauge::new(
+                "amunchain_sync_lag_heights",
+                "Heights between the local finalized head and the current sync target",
+            )?,
+            triggers: IntCounter::new(
+                "amunchain_sync_triggers_total",
+                "Peer head announcements that raised the sync target",
+            )?,
+        })
+    }
+
+    /// The head we announce and compare against.
+    pub fn local(&self) -> HeadStatus {
+        *self.local.borrow()
+    }
+
+    /// Record a new local head; drops the sync target once we are within the threshold.
+    pub fn set_local(&self, head: HeadStatus) {
+        self.local.send_replace(head);
+        self.target.send_if_modified(|t| match t {
+            Some(target) if !self.lagging(head.height, target.remote.height) => {
+                *t = None;
+                true
+            }
+            _ => false,
+        });
+        self.update_lag();
+    }
+
+    /// The current sync target; changes whenever a higher one is seen or we catch up.
+    pub fn sync_target(&self) -> watch::Receiver<Option<SyncTarget>> {
+        self.target.subscribe()
+    }
+
+    /// Compare a peer's announced head with ours. Returns the new sync target if this
+    /// announcement raised it.
+    pub fn observe(&self, peer: &[u8], remote: HeadStatus) -> Option<SyncTarget> {
+        let local = self.local();
+        if remote.height == local.height
+            && !local.height.is_zero()
+            && remote.block_hash != local.block_hash
+        {
+            warn!(
+                peer = %hex::encode(peer),
+                height = %remote.height,
+                "peer finalized a different block at our head"
+            );
+            return None;
+        }
+        if !self.lagging(local.height, remote.height) {
+            return None;
+        }
+        let next = SyncTarget {
+            peer: peer.to_vec(),
+            local: local.height,
+            remote,
+        };
+        let raised = self.target.send_if_modified(|t| {
+            let higher = t
+                .as_ref()
+                .is_none_or(|cur| remote.height > cur.remote.height);
+            if higher {
+                *t = Some(next.clone());
+            }
+            higher
+        });
+        if !raised {
+            return None;
+        }
+        self.triggers.inc();
+        self.update_lag();
+        info!(
+            peer = %hex::encode(peer),
+            local = %local.height,
+            remote = %remote.height,
+            "behind a peer's finalized head; sync needed"
+        );
+        Some(next)
+    }
+
+    fn lagging(&self, local: Height, remote: Height) -> bool {
+        remote.since(local).is_some_and(|d| d > self.lag_threshold)
+    }
+
+    fn update_lag(&self) {
+        let local = self.local().height;
+        let lag = self
+            .target
+            .borrow()
+            .as_ref()
+            .and_then(|t| t.remote.height.since(local))
+            .unwrap_or(0);
+        self.lag.set(i64::try_from(lag).unwrap_or(i64::MAX));
+    }
+}
+
+impl GossipHandler for AntiEntropy {
+    fn on_message(&self, peer: &[u8], data: &[u8]) {
+        match HeadStatus::decode(data) {
+            Ok(remote) => {
+                self.observe(peer, remote);
+            }
+            Err(_) => debug!(peer = %hex::encode(peer), "undecodable head announcement"),
+        }
+    }
+}
+
+impl NodeExtension for AntiEntropy {
+    fn name(&self) -> &'static str {
+        "anti-entropy"
+    }
+
+    fn register(&self, reg: &mut ExtensionRegistry) -> Result<(), ExtensionError> {
+        reg.gossip_topic(STATUS_TOPIC, std::sync::Arc::new(self.clone()))?
+            .metric(Box::new(self.lag.clone()))
+            .metric(Box::new(self.triggers.clone()));
+        Ok(())
+    }
+}
+
+/// Every `interval`, refresh the local head from the driver and publish it on `STATUS_TOPIC`.
+/// The state root is only recomputed when the finalized height moved.
+pub fn spawn_announcer(
+    anti_entropy: AntiEntropy,
+    driver: SharedDriver,
+    commits: CommitStore,
+    state: PersistentState,
+    publish: Sender<(String, Vec<u8>)>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        let mut announced = None;
+        loop {
+            ticker.tick().await;
+            let Ok(height) = driver.lock().map(|d| d.tide.finalized_height()) else {
+                warn!("consensus driver poisoned; stopping head announcements");
+                return;
+            };
+            if announced != Some(height) {
+                let block_hash = match commits.get(height.get()) {
+                    Ok(proof) => proof.map_or(H256::ZERO, |p| p.commit.block_hash),
+                    Err(e) => {
+                        warn!(err = ?e, %height, "cannot read finalized commit");
+                        continue;
+                    }
+                };
+                let state = state.clone();
+                let root = match tokio::task::spawn_blocking(move || state.state_root()).await {
+                    Ok(Ok(root)) => root,
+                    _ => {
+                        warn!(%height, "cannot compute state root for head announcement");
+                        continue;
+                    }
+                };
+                anti_entropy.set_local(HeadStatus {
+                    height,
+                    block_hash,
+                    state_root: H256::from_bytes(root),
+                });
+                announced = Some(height);
+            }
+            let Ok(bytes) = anti_entropy.local().encode() else {
+                continue;
+            };
+            if publish
+                .send((STATUS_TOPIC.to_string(), bytes))
+                .await
+                .is_err()
+            {
+                info!("p2p gone; stopping head announcements");
+                return;
+            }
+        }
+    })
+}
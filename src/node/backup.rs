@@ -0,0 +1,438 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Offline backup and restore of a data directory (`amunchain backup` / `restore`).
+//!
+//! A backup is a directory holding a copy of the data directory — the state database,
+//! which also holds the block store, plus anything else the node keeps there — and a
+//! `MANIFEST.json` with the size and SHA-256 of every file and the state root at backup
+//! time. Key files (`validator.key`, its rotated `.old` copies, `p2p_identity.key`) are
+//! left out unless `--include-keys` is given.
+//!
+//! If the data directory has a validator key, the manifest is signed with it
+//! (`Amunchain-Backup-v1` domain). `restore` checks the signature against
+//! `consensus.validators_hex`, every file against the manifest, and the root of the
+//! restored state, all in a staging directory; only then is anything moved into the data
+//! directory. Unsigned backups are refused unless `--allow-unsigned` is given.
+//!
+//! Both commands open the state database, so they fail while a node runs on it. An
+//! encrypted state needs its secret (see `core::state::encryption`) for both.
+
+use crate::core::security::keystore::{self, verify_pubkey_bytes, KeystoreError};
+use crate::core::state::persistent_state::{PersistentState, StateError};
+use crate::core::types::Signature;
+use crate::node::cli::{P2P_IDENTITY_FILE, STATE_DIR, VALIDATOR_KEY_FILE};
+use ring::digest::{Context, SHA256};
+use ring::signature::KeyPair;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// Manifest file at the top of a backup directory.
+pub const MANIFEST_FILE: &str = "MANIFEST.json";
+/// Domain tag of signed manifests.
+pub const BACKUP_DOMAIN: &[u8] = b"Amunchain-Backup-v1";
+
+const MANIFEST_VERSION: u32 = 1;
+const MAX_MANIFEST_BYTES: u64 = 64 * 1024 * 1024;
+// Restore assembles the data directory here before moving it into place.
+const STAGING_DIR: &str = ".restore";
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("{path}: {reason}")]
+    Io { path: String, reason: String },
+    #[error("state: {0}")]
+    State(#[from] StateError),
+    #[error("validator key: {0}")]
+    Keystore(#[from] KeystoreError),
+    #[error("{0} already exists")]
+    Exists(String),
+    #[error("{0} is not empty")]
+    NotEmpty(String),
+    #[error("destination {0} is inside the data directory")]
+    InsideDataDir(String),
+    #[error("invalid manifest: {0}")]
+    Manifest(String),
+    #[error("backup is not signed (pass --allow-unsigned to restore it anyway)")]
+    Unsigned,
+    #[error("backup signed by {0}, which is not a configured validator")]
+    UntrustedSigner(String),
+    #[error("manifest signature does not verify")]
+    BadSignature,
+    #[error("{path}: {reason}")]
+    Corrupt { path: String, reason: String },
+    #[error("restored state root {actual} does not match the manifest ({expected})")]
+    RootMismatch { expected: String, actual: String },
+}
+
+fn io_err(path: &Path) -> impl FnOnce(std::io::Error) -> BackupError + '_ {
+    move |e| BackupError::Io {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    }
+}
+
+/// One file of a backup.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEntry {
+    /// Relative to the backup directory, `/`-separated.
+    pub path: String,
+    pub size: u64,
+    /// SHA-256 of the contents, hex.
+    pub sha256: String,
+}
+
+/// Signed part of a manifest.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestBody {
+    pub version: u32,
+    pub created_at_ms: u64,
+    /// State root at backup time, hex.
+    pub state_root: String,
+    pub keys_included: bool,
+    /// Sorted by path.
+    pub files: Vec<FileEntry>,
+}
+
+/// Validator signature over `manifest_signing_bytes`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestSignature {
+    /// Ed25519 public key, hex.
+    pub validator: String,
+    pub signature: String,
+}
+
+/// Contents of `MANIFEST.json`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub body: ManifestBody,
+    pub signature: Option<ManifestSignature>,
+}
+
+/// Summary of a backup or restore.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BackupInfo {
+    pub files: usize,
+    pub bytes: u64,
+    pub state_root: String,
+    /// Validator that signed the manifest, hex.
+    pub signed_by: Option<String>,
+}
+
+impl Manifest {
+    fn info(&self) -> BackupInfo {
+        BackupInfo {
+            files: self.body.files.len(),
+            bytes: self.body.files.iter().map(|f| f.size).sum(),
+            state_root: self.body.state_root.clone(),
+            signed_by: self.signature.as_ref().map(|s| s.validator.clone()),
+        }
+    }
+}
+
+/// `BACKUP_DOMAIN || sha256(json(body))`.
+pub fn manifest_signing_bytes(body: &ManifestBody) -> Result<Vec<u8>, BackupError> {
+    let json = serde_json::to_vec(body).map_err(|e| BackupError::Manifest(e.to_string()))?;
+    let mut out = BACKUP_DOMAIN.to_vec();
+    out.extend_from_slice(ring::digest::digest(&SHA256, &json).as_ref());
+    Ok(out)
+}
+
+/// Copy `data_dir` to the new directory `dest` and write its manifest.
+pub fn backup(data_dir: &Path, dest: &Path, include_keys: bool) -> Result<BackupInfo, BackupError> {
+    if dest.exists() && fs::read_dir(dest).map_err(io_err(dest))?.next().is_some() {
+        return Err(BackupError::NotEmpty(dest.display().to_string()));
+    }
+    fs::create_dir_all(dest).map_err(io_err(dest))?;
+    let data_abs = fs::canonicalize(data_dir).map_err(io_err(data_dir))?;
+    if fs::canonicalize(dest)
+        .map_err(io_err(dest))?
+        .starts_with(&data_abs)
+    {
+        return Err(BackupError::InsideDataDir(dest.display().to_string()));
+    }
+
+    // Held until every file is copied: nothing writes to the database meanwhile.
+    let state = PersistentState::open(&data_dir.join(STATE_DIR).to_string_lossy())?;
+    state.flush()?;
+    let state_root = hex::encode(state.state_root()?);
+
+    let mut paths = Vec::new();
+    collect_files(data_dir, Path::new(""), &mut paths)?;
+    paths.retain(|p| !skipped(p, include_keys));
+    let mut files = Vec::with_capacity(paths.len());
+    for rel in paths {
+        let path = manifest_path(&rel)?;
+        let (size, sha256) = copy_hashed(&data_dir.join(&rel), &dest.join(&rel))?;
+        files.push(FileEntry { path, size, sha256 });
+    }
+    drop(state);
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let body = ManifestBody {
+        version: MANIFEST_VERSION,
+        created_at_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+        state_root,
+        keys_included: include_keys,
+        files,
+    };
+    let key_path = data_dir.join(VALIDATOR_KEY_FILE);
+    let signature = if key_path.exists() {
+        let passphrase = keystore::passphrase_from_env()?;
+        let kp = keystore::read_key_file(&key_path, passphrase.as_deref())?;
+        let msg = manifest_signing_bytes(&body)?;
+        Some(ManifestSignature {
+            validator: hex::encode(kp.public_key().as_ref()),
+            signature: hex::encode(kp.sign(&msg).as_ref()),
+        })
+    } else {
+        None
+    };
+    let manifest = Manifest { body, signature };
+    let json =
+        serde_json::to_vec_pretty(&manifest).map_err(|e| BackupError::Manifest(e.to_string()))?;
+    let manifest_path = dest.join(MANIFEST_FILE);
+    fs::write(&manifest_path, json).map_err(io_err(&manifest_path))?;
+    Ok(manifest.info())
+}
+
+/// Verify the backup at `src` and restore it into `data_dir`, whose state database must
+/// be empty. `validators_hex` are the keys trusted to sign backups.
+pub fn restore(
+    data_dir: &Path,
+    src: &Path,
+    validators_hex: &[String],
+    allow_unsigned: bool,
+) -> Result<BackupInfo, BackupError> {
+    let manifest = read_manifest(src)?;
+    match &manifest.signature {
+        Some(sig) => verify_signature(&manifest.body, sig, validators_hex)?,
+        None if allow_unsigned => {}
+        None => return Err(BackupError::Unsigned),
+    }
+    let mut targets = Vec::with_capacity(manifest.body.files.len());
+    for f in &manifest.body.files {
+        let rel = safe_relative(&f.path)
+            .ok_or_else(|| BackupError::Manifest(format!("bad path {:?}", f.path)))?;
+        targets.push((rel, f));
+    }
+    if targets.windows(2).any(|w| w[0].1.path >= w[1].1.path) {
+        return Err(BackupError::Manifest(
+            "files not sorted or duplicated".into(),
+        ));
+    }
+
+    let state_dir = data_dir.join(STATE_DIR);
+    if state_dir.exists()
+        && fs::read_dir(&state_dir)
+            .map_err(io_err(&state_dir))?
+            .next()
+            .is_some()
+    {
+        return Err(BackupError::NotEmpty(state_dir.display().to_string()));
+    }
+    if let Some((rel, _)) = targets.iter().find(|(rel, _)| data_dir.join(rel).exists()) {
+        return Err(BackupError::Exists(
+            data_dir.join(rel).display().to_string(),
+        ));
+    }
+
+    let staging = data_dir.join(STAGING_DIR);
+    if staging.exists() {
+        // Left over from an interrupted restore.
+        fs::remove_dir_all(&staging).map_err(io_err(&staging))?;
+    }
+    fs::create_dir_all(&staging).map_err(io_err(&staging))?;
+    let staged = stage(src, &staging, &targets, &manifest.body.state_root);
+    if let Err(e) = staged {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+    move_tree(&staging, data_dir)?;
+    Ok(manifest.info())
+}
+
+/// Parsed `MANIFEST.json` of the backup at `src`.
+pub fn read_manifest(src: &Path) -> Result<Manifest, BackupError> {
+    let path = src.join(MANIFEST_FILE);
+    let len = fs::metadata(&path).map_err(io_err(&path))?.len();
+    if len > MAX_MANIFEST_BYTES {
+        return Err(BackupError::Manifest("too large".into()));
+    }
+    let raw = fs::read(&path).map_err(io_err(&path))?;
+    let manifest: Manifest =
+        serde_json::from_slice(&raw).map_err(|e| BackupError::Manifest(e.to_string()))?;
+    if manifest.body.version != MANIFEST_VERSION {
+        return Err(BackupError::Manifest(format!(
+            "unsupported version {}",
+            manifest.body.version
+        )));
+    }
+    Ok(manifest)
+}
+
+fn verify_signature(
+    body: &ManifestBody,
+    sig: &ManifestSignature,
+    validators_hex: &[String],
+) -> Result<(), BackupError> {
+    let signer: [u8; 32] = hex::decode(&sig.validator)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or(BackupError::BadSignature)?;
+    let trusted = validators_hex
+        .iter()
+        .any(|v| hex::decode(v.trim()).is_ok_and(|b| b == signer));
+    if !trusted {
+        return Err(BackupError::UntrustedSigner(sig.validator.clone()));
+    }
+    let signature = hex::decode(&sig.signature)
+        .ok()
+        .and_then(|b| Signature::from_slice(&b).ok())
+        .ok_or(BackupError::BadSignature)?;
+    verify_pubkey_bytes(&signer, &manifest_signing_bytes(body)?, &signature)
+        .map_err(|_| BackupError::BadSignature)
+}
+
+// Copy every listed file into `staging`, checking it against the manifest, then check
+// the state root of the staged database.
+fn stage(
+    src: &Path,
+    staging: &Path,
+    targets: &[(PathBuf, &FileEntry)],
+    state_root: &str,
+) -> Result<(), BackupError> {
+    for (rel, entry) in targets {
+        let from = src.join(rel);
+        let corrupt = |reason: String| BackupError::Corrupt {
+            path: from.display().to_string(),
+            reason,
+        };
+        let len = fs::metadata(&from).map_err(io_err(&from))?.len();
+        if len != entry.size {
+            return Err(corrupt(format!("size {len}, manifest says {}", entry.size)));
+        }
+        let (_, sha256) = copy_hashed(&from, &staging.join(rel))?;
+        if sha256 != entry.sha256 {
+            return Err(corrupt("hash does not match the manifest".into()));
+        }
+    }
+    let state = PersistentState::open(&staging.join(STATE_DIR).to_string_lossy())?;
+    let actual = hex::encode(state.state_root()?);
+    if actual != state_root {
+        return Err(BackupError::RootMismatch {
+            expected: state_root.to_string(),
+            actual,
+        });
+    }
+    Ok(())
+}
+
+// Regular files under `root.join(rel)`, relative to `root`. Symlinks are not followed.
+fn collect_files(root: &Path, rel: &Path, out: &mut Vec<PathBuf>) -> Result<(), BackupError> {
+    let dir = root.join(rel);
+    for entry in fs::read_dir(&dir).map_err(io_err(&dir))? {
+        let entry = entry.map_err(io_err(&dir))?;
+        let path = rel.join(entry.file_name());
+        let kind = entry.file_type().map_err(io_err(&entry.path()))?;
+        if kind.is_dir() {
+            collect_files(root, &path, out)?;
+        } else if kind.is_file() {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+// Files of the data directory a backup leaves out.
+fn skipped(rel: &Path, include_keys: bool) -> bool {
+    let name = rel.to_string_lossy();
+    let is_key = name == P2P_IDENTITY_FILE || name.starts_with(VALIDATOR_KEY_FILE);
+    rel.starts_with(STAGING_DIR) || name.ends_with(".tmp") || (is_key && !include_keys)
+}
+
+fn manifest_path(rel: &Path) -> Result<String, BackupError> {
+    let parts = rel
+        .components()
+        .map(|c| c.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| BackupError::Manifest(format!("{} is not UTF-8", rel.display())))?;
+    Ok(parts.join("/"))
+}
+
+// `path` as a relative path that cannot leave the directory it is joined to.
+fn safe_relative(path: &str) -> Option<PathBuf> {
+    let parts: Vec<&str> = path.split('/').collect();
+    let mut out = PathBuf::new();
+    for part in parts {
+        let mut components = Path::new(part).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(c)), None) if c == part && !part.contains('\\') => {
+                out.push(part)
+            }
+            _ => return None,
+        }
+    }
+    (path != MANIFEST_FILE).then_some(out)
+}
+
+// Copy `from` to the new file `to`, returning its size and SHA-256 (hex).
+fn copy_hashed(from: &Path, to: &Path) -> Result<(u64, String), BackupError> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).map_err(io_err(parent))?;
+    }
+    let mut input = fs::File::open(from).map_err(io_err(from))?;
+    let mut output = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(to)
+        .map_err(io_err(to))?;
+    let mut hasher = Context::new(&SHA256);
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut size = 0u64;
+    loop {
+        let n = input.read(&mut buf).map_err(io_err(from))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        output.write_all(&buf[..n]).map_err(io_err(to))?;
+        size += n as u64;
+    }
+    output.sync_all().map_err(io_err(to))?;
+    Ok((size, hex::encode(hasher.finish().as_ref())))
+}
+
+// Move the contents of `from` into `to`, merging directories; `from` is removed.
+fn move_tree(from: &Path, to: &Path) -> Result<(), BackupError> {
+    fs::create_dir_all(to).map_err(io_err(to))?;
+    for entry in fs::read_dir(from).map_err(io_err(from))? {
+        let entry = entry.map_err(io_err(from))?;
+        let (src, dst) = (entry.path(), to.join(entry.file_name()));
+        if dst.is_dir() && src.is_dir() {
+            move_tree(&src, &dst)?;
+        } else if dst.exists() {
+            return Err(BackupError::Exists(dst.display().to_string()));
+        } else {
+            fs::rename(&src, &dst).map_err(io_err(&src))?;
+        }
+    }
+    fs::remove_dir(from).map_err(io_err(from))
+}
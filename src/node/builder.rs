@@ -0,0 +1,1151 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Node assembly.
+//!
+//! Downstream crates embed the node by building it with their own extensions:
+//!
+//! ```ignore
+//! let extensions = NodeBuilder::new()
+//!     .extension(MyBridge::new())
+//!     .build()?;
+//! ```
+//!
+//! The resulting `Extensions` is handed to the RPC router, the P2P task and the metrics
+//! stage. `NodeBuilder::run` does all of that and runs the node until a subsystem exits, so
+//! a downstream binary is just `NodeBuilder::new().extension(..).run()`.
+//!
+//! On ctrl-c or SIGTERM the node shuts down in order: stop taking gossip, let the consensus
+//! pump finish the votes already received, flush the state and commit store, write the
+//! shutdown checkpoint (see `core::state::checkpoint`), close p2p, then stop the rest.
+
+use crate::config::ConfigLoader;
+use crate::core::consensus::beacon::{BeaconStore, RandomnessBeacon};
+use crate::core::consensus::driver::{ConsensusDriver, MsgOutcome};
+use crate::core::consensus::events::{ChainEvents, DEFAULT_EVENT_CAPACITY};
+use crate::core::consensus::liveness::LivenessTracker;
+use crate::core::consensus::tide::DEFAULT_CHAIN_ID;
+use crate::core::economics::epoch::EpochPolicy;
+use crate::core::economics::staking::StakingLedger;
+use crate::core::runtime::executor::{EvmExecutor, DEFAULT_BLOCK_GAS_LIMIT};
+use crate::core::security::keystore::Keystore;
+use crate::core::security::secrets::{default_chain, SecretProvider};
+use crate::core::state::checkpoint::{self, Resume};
+use crate::core::state::commit_store::CommitStore;
+use crate::core::state::encryption::StateEncryption;
+use crate::core::state::header_store::HeaderStore;
+use crate::core::state::integrity::{self, StateRepair};
+use crate::core::state::migration;
+use crate::core::state::persistent_state::PersistentState;
+use crate::core::state::receipt_store::ReceiptStore;
+use crate::core::types::{BeaconConfig, Epoch, Height, NodeConfig, RuntimeSettings, ValidatorId};
+use crate::errors::{Classify, ExitCode};
+use crate::monitoring::health::{HealthMonitor, ReadinessCriteria};
+use crate::monitoring::metrics::Metrics;
+use crate::monitoring::telemetry::LogFilterHandle;
+use crate::monitoring::watchdog::{install_panic_hook, Watchdog};
+use crate::networking::p2p::P2pNode;
+use crate::networking::peer_caps::PeerCapabilityMap;
+use crate::node::anti_entropy::{spawn_announcer, AntiEntropy};
+use crate::node::cli::load_snapshot;
+use crate::node::extensions::{ExtensionError, ExtensionRegistry, Extensions, NodeExtension};
+use crate::node::info::NodeIdentity;
+use crate::node::provenance::{config_digest, spawn_provenance, LocalInputs, ProvenanceBook};
+use crate::node::reload::{resolve_allowlist, Reloader, TESTNET_PEERS};
+use crate::node::role;
+use crate::node::runtimes::{spawn_metrics_sampler, NodeRuntimes};
+use crate::node::startup::{
+    Resources, RunningNode, StageFailure, StageHandle, StartupOrchestrator, Stop,
+};
+use crate::node::validator_binding::{spawn_binding_announcer, BindingRegistry, PeerBinding};
+use crate::rpc::server::{RpcState, SharedDriver};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// Collects extensions and freezes them into an `Extensions` set.
+#[derive(Default)]
+pub struct NodeBuilder {
+    extensions: Vec<Box<dyn NodeExtension>>,
+    log_filter: Option<LogFilterHandle>,
+    config: Option<NodeConfig>,
+    data_dir: Option<String>,
+    reload: Option<ConfigLoader>,
+    repair: StateRepair,
+}
+
+impl NodeBuilder {
+    /// Builder with no extensions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an extension. Extensions register in the order they are added.
+    pub fn extension<E: NodeExtension>(mut self, ext: E) -> Self {
+        self.extensions.push(Box::new(ext));
+        self
+    }
+
+    /// Make the log filter reconfigurable at runtime: `PUT /admin/loglevel` (when
+    /// `AMUN_ADMIN_TOKEN` is set) and SIGHUP (re-reads `AMUN_LOG_FILTER_FILE`).
+    pub fn log_filter(mut self, handle: LogFilterHandle) -> Self {
+        self.log_filter = Some(handle);
+        self
+    }
+
+    /// Run with this config, as assembled by `crate::config::ConfigLoader`. Without one,
+    /// `run` loads the built-in defaults and the environment layer.
+    pub fn config(mut self, config: NodeConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Re-run `loader` on SIGHUP and apply runtime-tunable changes (see `node::reload`).
+    /// Needs `config`, which should be what `loader` produced at startup.
+    pub fn reload_from(mut self, loader: ConfigLoader) -> Self {
+        self.reload = Some(loader);
+        self
+    }
+
+    /// What to do when the state database fails its startup integrity check (see
+    /// `core::state::integrity`). By default the node refuses to start.
+    pub fn repair(mut self, repair: StateRepair) -> Self {
+        self.repair = repair;
+        self
+    }
+
+    /// Data directory, overriding `node.data_dir` of the config.
+    pub fn data_dir(mut self, data_dir: impl Into<String>) -> Self {
+        self.data_dir = Some(data_dir.into());
+        self
+    }
+
+    /// Run every extension's registration and validate the combined result.
+    pub fn build(self) -> Result<Arc<Extensions>, ExtensionError> {
+        let mut names = BTreeSet::new();
+        let mut reg = ExtensionRegistry::default();
+        for ext in self.extensions.iter() {
+            let name = ext.name();
+            if !names.insert(name) {
+                return Err(ExtensionError::Duplicate(format!("extension {name}")));
+            }
+            reg.enter(name);
+            ext.register(&mut reg)?;
+            info!(extension = name, "extension registered");
+        }
+        Ok(Arc::new(reg.finish()))
+    }
+
+    /// Build extensions and runtimes from the config and environment, start every subsystem, and
+    /// block until the node stops. Returns the process exit code.
+    pub fn run(mut self) -> ExitCode {
+        install_panic_hook();
+        if self.config.is_none() {
+            match ConfigLoader::new().with_env().load() {
+                Ok(c) => self.config = Some(c),
+                Err(e) => {
+                    eprintln!("{e}");
+                    return ExitCode::Config;
+                }
+            }
+        }
+        let log_filter = self.log_filter.clone();
+        let config = self.config.clone();
+        let reload = self.reload.clone();
+        let repair = self.repair.clone();
+        let anti_entropy = match anti_entropy(config.as_ref()) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("anti-entropy setup failed: {e}");
+                return ExitCode::Internal;
+            }
+        };
+        if let Some((ae, _)) = anti_entropy.as_ref() {
+            self.extensions.push(Box::new(ae.clone()));
+        }
+        let bindings = match validator_bindings(config.as_ref()) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("validator binding setup failed: {e}");
+                return ExitCode::Config;
+            }
+        };
+        if let Some((registry, _)) = bindings.as_ref() {
+            self.extensions.push(Box::new(registry.clone()));
+        }
+        let provenance = match provenance(config.as_ref()) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("provenance setup failed: {e}");
+                return ExitCode::Config;
+            }
+        };
+        if let Some((book, _, _)) = provenance.as_ref() {
+            self.extensions.push(Box::new(book.clone()));
+        }
+        let data_dir = self.data_dir.clone().unwrap_or_else(|| {
+            config
+                .as_ref()
+                .map_or("./data", |c| c.node.data_dir.as_str())
+                .to_string()
+        });
+        let extensions = match self.build() {
+            Ok(v) => v,
+            Err(e) => {
+                error!(err = %e, "extension registration failed");
+                eprintln!("extension registration failed: {e}");
+                return ExitCode::Config;
+            }
+        };
+
+        let settings: RuntimeSettings = config
+            .as_ref()
+            .map(|c| c.runtime.clone())
+            .unwrap_or_default();
+        let runtimes = match NodeRuntimes::build(&settings) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("runtime start failed: {e}");
+                return ExitCode::Internal;
+            }
+        };
+        runtimes.consensus.block_on(run(
+            &runtimes,
+            settings.rpc_max_in_flight,
+            extensions,
+            log_filter,
+            data_dir,
+            config,
+            reload,
+            anti_entropy,
+            bindings,
+            provenance,
+            repair,
+        ))
+    }
+}
+
+/// Head announcements and lag detection, unless `p2p.status_interval_secs` is 0.
+fn anti_entropy(
+    config: Option<&NodeConfig>,
+) -> Result<Option<(AntiEntropy, Duration)>, prometheus::Error> {
+    let (secs, threshold) = config.map_or((10, 2), |c| {
+        (c.p2p.status_interval_secs, c.p2p.sync_lag_threshold)
+    });
+    if secs == 0 {
+        return Ok(None);
+    }
+    Ok(Some((
+        AntiEntropy::new(threshold)?,
+        Duration::from_secs(secs),
+    )))
+}
+
+/// Validator peer bindings, unless `p2p.binding_interval_secs` is 0 (the default).
+fn validator_bindings(
+    config: Option<&NodeConfig>,
+) -> Result<Option<(BindingRegistry, Duration)>, String> {
+    let Some(c) = config.filter(|c| c.p2p.binding_interval_secs > 0) else {
+        return Ok(None);
+    };
+    let registry = BindingRegistry::new(
+        &chain_id(config),
+        validator_set(config)?,
+        c.p2p.binding_max_epoch_age,
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(Some((
+        registry,
+        Duration::from_secs(c.p2p.binding_interval_secs),
+    )))
+}
+
+/// Provenance book and the shared-config digest, unless `p2p.provenance_interval_secs` is 0.
+fn provenance(
+    config: Option<&NodeConfig>,
+) -> Result<Option<(ProvenanceBook, String, Duration)>, String> {
+    let Some(c) = config.filter(|c| c.p2p.provenance_interval_secs > 0) else {
+        return Ok(None);
+    };
+    let chain_id = chain_id(config);
+    let book = ProvenanceBook::new(&chain_id, validator_set(config)?).map_err(|e| e.to_string())?;
+    Ok(Some((
+        book,
+        config_digest(c, &chain_id),
+        Duration::from_secs(c.p2p.provenance_interval_secs),
+    )))
+}
+
+/// Open the state database and check it (see `core::state::integrity`). A database that
+/// fails the check is moved aside and rebuilt as `repair` says.
+fn open_checked_state(
+    dir: &Path,
+    encryption: StateEncryption,
+    repair: &StateRepair,
+    metrics: &Metrics,
+) -> Result<PersistentState, StageFailure> {
+    let err = match integrity::open_checked(dir, encryption) {
+        Ok((state, report)) => {
+            info!(
+                entries = report.entries,
+                state_root = %hex::encode(report.state_root),
+                "state integrity check passed"
+            );
+            return Ok(state);
+        }
+        Err(e) => e,
+    };
+    let Some(kind) = err.kind() else {
+        return Err(StageFailure::classified(err));
+    };
+    metrics
+        .state_corruption_detected_total
+        .with_label_values(&[kind])
+        .inc();
+    error!(err = %err, path = %dir.display(), "state database failed its integrity check");
+    let source = match repair {
+        StateRepair::Off => {
+            return Err(StageFailure::msg(format!(
+                "{err}; restart with `--repair` or `--repair-from <snapshot>` to rebuild it"
+            )))
+        }
+        StateRepair::Resync => "resync",
+        StateRepair::Snapshot(_) => "snapshot",
+    };
+    let moved = integrity::quarantine(dir).map_err(StageFailure::msg)?;
+    warn!(moved_to = %moved.display(), "corrupt state database moved aside");
+    let state = PersistentState::open_with(&dir.to_string_lossy(), encryption)
+        .map_err(StageFailure::classified)?;
+    match repair {
+        StateRepair::Snapshot(file) => {
+            let info = load_snapshot(&state, file).map_err(StageFailure::classified)?;
+            info!(
+                entries = info.entries,
+                state_root = %info.state_root,
+                "state rebuilt from snapshot"
+            );
+        }
+        _ => warn!("starting from an empty state; the node catches up from peers"),
+    }
+    metrics
+        .state_repairs_total
+        .with_label_values(&[source])
+        .inc();
+    Ok(state)
+}
+
+fn chain_id(config: Option<&NodeConfig>) -> String {
+    config
+        .map_or(DEFAULT_CHAIN_ID, |c| c.node.chain_id.as_str())
+        .to_string()
+}
+
+fn env(key: &str, default: &str) -> String {
+    std::env::var(key).unwrap_or_else(|_| default.to_string())
+}
+
+fn env_usize(key: &str, default: usize) -> usize {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run(
+    runtimes: &NodeRuntimes,
+    rpc_max_in_flight: usize,
+    extensions: Arc<Extensions>,
+    log_filter: Option<LogFilterHandle>,
+    data_dir: String,
+    config: Option<NodeConfig>,
+    reload: Option<ConfigLoader>,
+    anti_entropy: Option<(AntiEntropy, Duration)>,
+    bindings: Option<(BindingRegistry, Duration)>,
+    provenance: Option<(ProvenanceBook, String, Duration)>,
+    repair: StateRepair,
+) -> ExitCode {
+    let role = config.as_ref().map(|c| c.node.role).unwrap_or_default();
+    if let Some(c) = config.as_ref() {
+        match role::check_role(c, Path::new(&data_dir)) {
+            Ok(warnings) => {
+                for w in warnings {
+                    warn!(role = role.as_str(), "{w}");
+                }
+            }
+            Err(e) => {
+                error!(role = role.as_str(), err = %e, "role check failed");
+                eprintln!("{}: {e}", e.field());
+                return ExitCode::Config;
+            }
+        }
+    }
+
+    let evidence_topic = env("AMUN_P2P_EVIDENCE_TOPIC", "amunchain-evidence");
+
+    let allow_peers = match config.as_ref().map(|c| resolve_allowlist(&c.p2p, role)) {
+        Some(Ok(peers)) => peers,
+        Some(Err(e)) => {
+            error!(err = %e, "peer registry rejected");
+            eprintln!("peer registry rejected: {e}");
+            return e.exit_code();
+        }
+        None => TESTNET_PEERS.iter().map(ToString::to_string).collect(),
+    };
+    let identity = config.as_ref().map(|c| c.p2p.identity).unwrap_or_default();
+    let cfg = crate::networking::p2p::P2pConfig {
+        data_dir: data_dir.clone(),
+        identity,
+        listen_addr: config
+            .as_ref()
+            .map_or("/ip4/0.0.0.0/tcp/30333", |c| c.p2p.listen_addr.as_str())
+            .to_string(),
+        consensus_topic: config
+            .as_ref()
+            .map_or("amunchain/consensus/v2", |c| c.p2p.topic.as_str())
+            .to_string(),
+        consensus_codec: config.as_ref().map(|c| c.p2p.codec).unwrap_or_default(),
+        evidence_topic,
+        max_msg_per_sec: config.as_ref().map_or(200, |c| c.p2p.max_msg_per_sec),
+        max_peers_per_ip: config.as_ref().map_or(4, |c| c.p2p.max_peers_per_ip),
+        bootstrap: config
+            .as_ref()
+            .map(|c| c.p2p.bootstrap.clone())
+            .unwrap_or_default(),
+        extensions: Some(extensions.clone()),
+        channels: Default::default(),
+        gossipsub: config
+            .as_ref()
+            .map(|c| c.p2p.gossipsub.clone())
+            .unwrap_or_default(),
+        limits: config
+            .as_ref()
+            .map(|c| c.p2p.limits.clone())
+            .unwrap_or_default(),
+        read_only: !role.publishes(),
+        allow_peers,
+    };
+
+    info!(
+        listen_addr = %cfg.listen_addr,
+        data_dir = %data_dir,
+        role = role.as_str(),
+        "amunchain node starting"
+    );
+
+    let http_addr = config
+        .as_ref()
+        .map_or("127.0.0.1:9090", |c| c.http.listen_addr.as_str())
+        .to_string();
+    let runtime_handles = runtimes.handles();
+    let rpc = runtimes.rpc.handle().clone();
+    let metrics_extensions = extensions.clone();
+    let peer_id = crate::networking::p2p_identity::load_identity(&data_dir, identity)
+        .map(|(id, _)| id.to_string())
+        .unwrap_or_default();
+    let chain_id = chain_id(config.as_ref());
+    let local_peer = peer_id.clone();
+    let binding_chain_id = chain_id.clone();
+    let binding_data_dir = data_dir.clone();
+    let provenance_peer = peer_id.clone();
+    let provenance_chain_id = chain_id.clone();
+    let provenance_data_dir = data_dir.clone();
+    let identity = NodeIdentity::new(chain_id.clone(), peer_id).with_role(role);
+    let validators = validator_set(config.as_ref());
+    let state_dir = Path::new(&data_dir).join(crate::node::cli::STATE_DIR);
+    let state_encryption = config
+        .as_ref()
+        .map(|c| c.state.encryption)
+        .unwrap_or_default();
+    let state_tree = config
+        .as_ref()
+        .map(|c| c.consensus.state_tree)
+        .unwrap_or_default();
+    let quorum = config
+        .as_ref()
+        .map(|c| c.consensus.quorum)
+        .unwrap_or_default();
+    let min_consensus_peers = config
+        .as_ref()
+        .filter(|_| role.votes())
+        .map_or(0, |c| c.consensus.min_consensus_peers);
+    let compact_commits = config.as_ref().is_some_and(|c| c.consensus.compact_commits);
+    let beacon = config.as_ref().and_then(|c| c.consensus.beacon.clone());
+    let block_gas_limit = config
+        .as_ref()
+        .map_or(DEFAULT_BLOCK_GAS_LIMIT, |c| c.consensus.block_gas_limit);
+    let marker_dir = state_dir.clone();
+    let shutdown_timeout = Duration::from_secs(env_usize("AMUN_SHUTDOWN_TIMEOUT_SECS", 10) as u64);
+    // Env, `AMUN_ADMIN_TOKEN_FILE` or `AMUNCHAIN_SECRETS_DIR` (see `core::security::secrets`).
+    let admin_token = match default_chain().get("AMUN_ADMIN_TOKEN") {
+        Ok(token) => token,
+        Err(e) => {
+            error!(err = %e, "admin token unavailable");
+            eprintln!("admin token unavailable: {e}");
+            return ExitCode::Config;
+        }
+    };
+    let admin_filter = log_filter.clone();
+    let http_tls = config.as_ref().map(|c| c.http.clone());
+    let events = ChainEvents::new(
+        config
+            .as_ref()
+            .map_or(DEFAULT_EVENT_CAPACITY, |c| c.http.ws_buffer),
+    );
+    let ws_max_subscribers = config.as_ref().map_or(64, |c| c.http.ws_max_subscribers);
+    // Config reload needs both the loader and the config it produced.
+    let reload = reload.zip(config.clone());
+    let exit_on_task_failure = env("AMUN_EXIT_ON_TASK_FAILURE", "true") != "false";
+    let readiness: ReadinessCriteria = config.map(|c| c.health).unwrap_or_default();
+
+    let orchestrator = StartupOrchestrator::new()
+        .stage("metrics", &[], ExitCode::Internal, move |res| {
+            let metrics = Metrics::new().map_err(StageFailure::msg)?;
+            metrics.node_role.with_label_values(&[role.as_str()]).set(1);
+            metrics_extensions
+                .register_metrics(&metrics)
+                .map_err(StageFailure::msg)?;
+            res.insert(Arc::new(metrics));
+            Ok(StageHandle::empty())
+        })
+        .stage("watchdog", &["metrics"], ExitCode::Internal, move |res| {
+            let metrics = shared_metrics(res)?;
+            res.insert(Watchdog::new(metrics, exit_on_task_failure));
+            Ok(StageHandle::empty())
+        })
+        .stage(
+            "runtime-metrics",
+            &["metrics"],
+            ExitCode::Internal,
+            move |res| {
+                let metrics = shared_metrics(res)?;
+                Ok(StageHandle::empty().with_task(spawn_metrics_sampler(runtime_handles, metrics)))
+            },
+        )
+        .stage("state", &["metrics"], ExitCode::DbCorruption, move |res| {
+            let metrics = shared_metrics(res)?;
+            let mut state = open_checked_state(&state_dir, state_encryption, &repair, &metrics)?
+                .with_metrics(metrics);
+            // Before anything else writes: a newer schema must be left as it is. A migration
+            // that changes the state root also retires the shutdown checkpoint.
+            let migrated = migration::builtin()
+                .run(&state)
+                .map_err(StageFailure::classified)?;
+            if !migrated.applied.is_empty() {
+                info!(
+                    from = migrated.from,
+                    to = migrated.to,
+                    applied = ?migrated.applied,
+                    "state schema migrated"
+                );
+            }
+            // A fresh database is built with the network's tree; others must already use it.
+            state
+                .require_state_tree(state_tree)
+                .map_err(StageFailure::classified)?;
+            let commits = CommitStore::open(&state).map_err(StageFailure::classified)?;
+            let resume = checkpoint::restore(&state, &commits).map_err(StageFailure::classified)?;
+            integrity::clear_marker(&state_dir).map_err(StageFailure::classified)?;
+            match resume {
+                Resume::Checkpoint(cp) => {
+                    info!(
+                        height = cp.finalized_height,
+                        "resuming from shutdown checkpoint"
+                    )
+                }
+                Resume::CommitStore(height) => {
+                    warn!(
+                        height,
+                        "no clean shutdown checkpoint; resuming from commit store"
+                    )
+                }
+            }
+            res.insert(state);
+            res.insert(commits);
+            res.insert(resume);
+            Ok(StageHandle::empty())
+        })
+        .stage(
+            "consensus",
+            &["metrics", "watchdog", "state", "p2p"],
+            ExitCode::Config,
+            move |res| {
+                let metrics = shared_metrics(res)?;
+                let watchdog = shared_watchdog(res)?;
+                let commits = res
+                    .get::<CommitStore>()
+                    .cloned()
+                    .ok_or_else(|| StageFailure::msg("state not initialized"))?;
+                let height = res.get::<Resume>().map_or(0, Resume::height);
+                let state = res
+                    .get::<PersistentState>()
+                    .cloned()
+                    .ok_or_else(|| StageFailure::msg("state not initialized"))?;
+                let p2p = res
+                    .get_mut::<P2pNode>()
+                    .ok_or_else(|| StageFailure::msg("p2p inbound not available"))?;
+                let (outbound, reports) = (p2p.outbound(), p2p.peer_reports());
+                let mut inbound = p2p
+                    .take_inbound()
+                    .ok_or_else(|| StageFailure::msg("p2p inbound not available"))?;
+                let evidence_outbound = p2p.evidence_outbound();
+                let mut connected_peers = p2p.connected_peers();
+                let mut evidence_inbound = p2p
+                    .take_evidence_inbound()
+                    .ok_or_else(|| StageFailure::msg("p2p evidence inbound not available"))?;
+                let sync_outbound = p2p.commit_sync_outbound();
+                let mut sync_inbound = p2p
+                    .take_commit_sync_inbound()
+                    .ok_or_else(|| StageFailure::msg("p2p commit sync inbound not available"))?;
+                quorum.validate().map_err(StageFailure::msg)?;
+                let validators = validators.map_err(StageFailure::msg)?;
+                // Uptime and miss streaks carry over from the last run.
+                let liveness =
+                    LivenessTracker::load(&state, &validators).map_err(StageFailure::classified)?;
+                let beacon = beacon
+                    .as_ref()
+                    .map(|cfg| open_beacon(&state, cfg))
+                    .transpose()?;
+                let driver = ConsensusDriver::new(validators)
+                    .map_err(StageFailure::msg)?
+                    .with_liveness(liveness)
+                    .with_liveness_state(state)
+                    .with_chain_id(&chain_id, None)
+                    .with_quorum(Arc::new(quorum))
+                    .with_metrics(metrics)
+                    .with_commit_store(commits)
+                    .with_events(events.clone())
+                    .with_outbound(outbound)
+                    .with_evidence_outbound(evidence_outbound)
+                    .with_commit_sync_outbound(sync_outbound)
+                    .with_compact_commits(compact_commits)
+                    .with_finalized_height(Height(height))
+                    .with_local_votes(role.votes())
+                    .with_min_consensus_peers(min_consensus_peers);
+                // Block import reads each epoch's randomness from the beacon store and records
+                // the headers the beacon absorbs, so both go into the resources.
+                let driver = match beacon {
+                    Some((b, store, headers)) => {
+                        info!(epoch = b.epoch.get(), "randomness beacon attached");
+                        res.insert(store.clone());
+                        res.insert(headers.clone());
+                        driver.with_beacon(b, Box::new(headers), Some(store))
+                    }
+                    None => driver,
+                };
+                let driver: SharedDriver = Arc::new(Mutex::new(driver));
+                res.insert(driver.clone());
+                res.insert(events);
+
+                // Ends with the p2p task.
+                let gate_driver = driver.clone();
+                let peer_gate = tokio::spawn(async move {
+                    loop {
+                        let peers = *connected_peers.borrow_and_update();
+                        match gate_driver.lock() {
+                            Ok(mut d) => d.set_connected_peers(peers),
+                            Err(_) => {
+                                warn!("consensus driver poisoned; stopping peer gate");
+                                return;
+                            }
+                        }
+                        if connected_peers.changed().await.is_err() {
+                            break;
+                        }
+                    }
+                    info!("peer count closed");
+                });
+
+                // Ends once p2p stops intake and everything already received is processed.
+                let evidence_driver = driver.clone();
+                let evidence_reports = reports.clone();
+                let evidence_pump = tokio::spawn(async move {
+                    while let Some((peer, ev)) = evidence_inbound.recv().await {
+                        let Ok(mut d) = evidence_driver.lock() else {
+                            warn!("consensus driver poisoned; stopping evidence pump");
+                            return;
+                        };
+                        let outcome = d.on_evidence(&peer, ev);
+                        drop(d);
+                        // Forged evidence counts against the relaying peer, as forged votes do.
+                        if let MsgOutcome::Rejected(e) = outcome {
+                            if e.is_invalid() && !peer.is_empty() {
+                                let _ = evidence_reports.try_send(peer);
+                            }
+                        }
+                    }
+                    info!("evidence inbound closed");
+                });
+                let sync_driver = driver.clone();
+                let sync_reports = reports.clone();
+                let sync_pump = tokio::spawn(async move {
+                    while let Some((peer, msg)) = sync_inbound.recv().await {
+                        let Ok(mut d) = sync_driver.lock() else {
+                            warn!("consensus driver poisoned; stopping commit sync pump");
+                            return;
+                        };
+                        let outcome = d.on_commit_sync(&peer, msg);
+                        drop(d);
+                        if let MsgOutcome::Rejected(e) = outcome {
+                            if e.is_invalid() && !peer.is_empty() {
+                                let _ = sync_reports.try_send(peer);
+                            }
+                        }
+                    }
+                    info!("commit sync inbound closed");
+                });
+                let pump = tokio::spawn(async move {
+                    while let Some(((peer, msg), origin)) = inbound.recv_with_span().await {
+                        let Ok(mut d) = driver.lock() else {
+                            warn!("consensus driver poisoned; stopping pump");
+                            return;
+                        };
+                        let outcome = d.on_traced_msg(&peer, msg, &origin);
+                        drop(d);
+                        // Forged or malformed content counts against the relaying peer.
+                        if let MsgOutcome::Rejected(e) = outcome {
+                            if e.is_invalid() && !peer.is_empty() {
+                                let _ = reports.try_send(peer);
+                            }
+                        }
+                    }
+                    info!("consensus inbound closed");
+                });
+                Ok(StageHandle::empty()
+                    .with_task(watchdog.watch("consensus", pump))
+                    .with_task(watchdog.watch("evidence", evidence_pump))
+                    .with_task(watchdog.watch("commit-sync", sync_pump))
+                    .with_task(watchdog.watch("peer-gate", peer_gate)))
+            },
+        )
+        .stage(
+            "http",
+            &["metrics", "watchdog", "state", "consensus"],
+            ExitCode::PortBind,
+            move |res| {
+                let metrics = shared_metrics(res)?;
+                let watchdog = shared_watchdog(res)?;
+                let driver = res.get::<SharedDriver>().cloned();
+                let events = res.get::<ChainEvents>().cloned();
+                let state = res.get::<PersistentState>().cloned();
+                let peers = res.get::<PeerCapabilityMap>().cloned();
+                let tls = match &http_tls {
+                    Some(http) => {
+                        crate::rpc::tls::server_config(http).map_err(StageFailure::classified)?
+                    }
+                    None => None,
+                };
+                let listener =
+                    crate::rpc::server::bind(&http_addr).map_err(StageFailure::classified)?;
+                let health = HealthMonitor::new(readiness, metrics.clone()).with_watchdog(watchdog);
+                let mut rpc_state = RpcState::new(metrics)
+                    .with_identity(identity)
+                    .with_health(health)
+                    .with_max_in_flight(rpc_max_in_flight)
+                    .with_extensions(extensions);
+                if let Some(driver) = driver {
+                    rpc_state = rpc_state.with_driver(driver);
+                }
+                if let Some(state) = state {
+                    let receipts = ReceiptStore::open(&state).map_err(StageFailure::classified)?;
+                    // Nothing in the node writes the ledger while it runs, so the copy loaded
+                    // here stays current.
+                    let ledger = StakingLedger::load(&state).map_err(StageFailure::classified)?;
+                    let executor = EvmExecutor::new().with_block_gas_limit(block_gas_limit);
+                    rpc_state = rpc_state
+                        .with_state(state.clone())
+                        .with_receipts(receipts)
+                        .with_staking(Arc::new(Mutex::new(ledger)), EpochPolicy::default())
+                        .with_call(state, executor);
+                }
+                if let Some(peers) = peers {
+                    rpc_state = rpc_state.with_peers(peers);
+                }
+                if let Some(events) = events {
+                    rpc_state = rpc_state.with_events(events, ws_max_subscribers);
+                }
+                if let (Some(token), Some(filter)) = (admin_token, admin_filter) {
+                    rpc_state = rpc_state.with_admin(token, filter);
+                }
+                if http_tls
+                    .as_ref()
+                    .is_some_and(|h| h.tls_client_ca_path.is_some())
+                {
+                    rpc_state = rpc_state.with_admin_client_cert();
+                }
+                if tls.is_some() {
+                    info!(addr = %http_addr, "serving the API over https");
+                }
+                let task = rpc.spawn(async move {
+                    let served = match tls {
+                        Some(tls) => {
+                            crate::rpc::tls::serve_listener_tls(listener, rpc_state, tls).await
+                        }
+                        None => crate::rpc::server::serve_listener(listener, rpc_state).await,
+                    };
+                    if let Err(e) = served {
+                        warn!(?e, "http server stopped");
+                    }
+                });
+                Ok(StageHandle::empty().with_task(task))
+            },
+        )
+        .stage(
+            "p2p",
+            &["metrics", "watchdog"],
+            ExitCode::Internal,
+            move |res| {
+                let metrics = shared_metrics(res)?;
+                let watchdog = shared_watchdog(res)?;
+                let (node, mut ev_rx, p2p_handle) = crate::networking::p2p::spawn_p2p(cfg, metrics)
+                    .map_err(StageFailure::classified)?;
+                res.insert(node.capabilities());
+                // The node handle owns the outbound channel; keep it alive with the node.
+                res.insert(node);
+
+                // keep alive + log events
+                let ev_task = tokio::spawn(async move {
+                    while let Some(ev) = ev_rx.recv().await {
+                        info!(?ev, "p2p event");
+                    }
+                    warn!("p2p event channel closed");
+                });
+                Ok(StageHandle::empty()
+                    .with_task(watchdog.watch("p2p", p2p_handle))
+                    .with_task(watchdog.watch("p2p-events", ev_task)))
+            },
+        )
+        .stage(
+            "anti-entropy",
+            &["watchdog", "state", "consensus", "p2p"],
+            ExitCode::Internal,
+            move |res| {
+                let Some((anti_entropy, interval)) = anti_entropy else {
+                    return Ok(StageHandle::empty());
+                };
+                let watchdog = shared_watchdog(res)?;
+                let (Some(driver), Some(commits), Some(state), Some(p2p)) = (
+                    res.get::<SharedDriver>().cloned(),
+                    res.get::<CommitStore>().cloned(),
+                    res.get::<PersistentState>().cloned(),
+                    res.get::<P2pNode>().map(P2pNode::extension_outbound),
+                ) else {
+                    return Err(StageFailure::msg("consensus or p2p not initialized"));
+                };
+                let task = spawn_announcer(anti_entropy, driver, commits, state, p2p, interval);
+                Ok(StageHandle::empty().with_task(watchdog.watch("anti-entropy", task)))
+            },
+        )
+        .stage(
+            "validator-binding",
+            &["watchdog", "p2p"],
+            ExitCode::Internal,
+            move |res| {
+                let Some((_, interval)) = bindings else {
+                    return Ok(StageHandle::empty());
+                };
+                // Only validators announce; everyone else just listens (the registry extension).
+                if !role.uses_validator_key()
+                    || !Path::new(&binding_data_dir).join("validator.key").exists()
+                {
+                    return Ok(StageHandle::empty());
+                }
+                let watchdog = shared_watchdog(res)?;
+                let publish = res
+                    .get::<P2pNode>()
+                    .map(P2pNode::extension_outbound)
+                    .ok_or_else(|| StageFailure::msg("p2p not initialized"))?;
+                let peer = local_peer
+                    .parse()
+                    .map_err(|_| StageFailure::msg("local peer id unavailable"))?;
+                let ks = Keystore::open(&binding_data_dir).map_err(StageFailure::msg)?;
+                let validator = ValidatorId::from_bytes(ks.public_key());
+                // The node tracks no validator-set epochs yet; epoch 0 bindings never expire.
+                let binding =
+                    PeerBinding::sign(&binding_chain_id, validator, &peer, Epoch::ZERO, |m| {
+                        ks.sign(m)
+                    })
+                    .map_err(StageFailure::msg)?;
+                let task = spawn_binding_announcer(binding, publish, interval);
+                Ok(StageHandle::empty().with_task(watchdog.watch("validator-binding", task)))
+            },
+        )
+        .stage(
+            "provenance",
+            &["watchdog", "state", "p2p"],
+            ExitCode::Internal,
+            move |res| {
+                let Some((book, config_digest, interval)) = provenance else {
+                    return Ok(StageHandle::empty());
+                };
+                let watchdog = shared_watchdog(res)?;
+                let (Some(state), Some(publish)) = (
+                    res.get::<PersistentState>().cloned(),
+                    res.get::<P2pNode>().map(P2pNode::extension_outbound),
+                ) else {
+                    return Err(StageFailure::msg("state or p2p not initialized"));
+                };
+                // Only validators sign and publish; other nodes keep an unsigned local record.
+                let keystore = if role.uses_validator_key()
+                    && Path::new(&provenance_data_dir)
+                        .join("validator.key")
+                        .exists()
+                {
+                    Some(Keystore::open(&provenance_data_dir).map_err(StageFailure::msg)?)
+                } else {
+                    None
+                };
+                let local = LocalInputs {
+                    chain_id: provenance_chain_id,
+                    peer_id: provenance_peer,
+                    config_digest,
+                    state,
+                    height: res.get::<Resume>().map_or(0, Resume::height),
+                };
+                let task = spawn_provenance(book, local, keystore, publish, interval);
+                Ok(StageHandle::empty().with_task(watchdog.watch("provenance", task)))
+            },
+        )
+        .stage(
+            "reload",
+            &["metrics", "p2p"],
+            ExitCode::Internal,
+            move |res| {
+                let reloader = match reload {
+                    Some((loader, config)) => {
+                        let mut r = Reloader::new(loader, config, shared_metrics(res)?);
+                        if let Some(filter) = log_filter.clone() {
+                            r = r.with_log_filter(filter);
+                        }
+                        if let Some(node) = res.get::<P2pNode>() {
+                            r = r.with_p2p(node.tunables());
+                        }
+                        Some(r)
+                    }
+                    None => None,
+                };
+                if reloader.is_none() && log_filter.is_none() {
+                    return Ok(StageHandle::empty());
+                }
+                Ok(StageHandle::empty().with_task(spawn_sighup_reload(reloader, log_filter)?))
+            },
+        );
+
+    let mut running = match orchestrator.start() {
+        Ok(v) => v,
+        Err(e) => {
+            let code = e.exit_code();
+            error!(stage = e.stage(), class = code.label(), err = %e, "startup failed");
+            eprintln!("startup failed: {e}");
+            return code;
+        }
+    };
+
+    // Run until a shutdown signal, or until any subsystem task exits (or crashes).
+    let watchdog = running.resources().get::<Watchdog>().cloned();
+    match running.wait(shutdown_signal()).await {
+        Stop::Signal => graceful_shutdown(running, watchdog, &marker_dir, shutdown_timeout).await,
+        Stop::TaskExited(stage) => {
+            running.shutdown();
+            if watchdog.as_ref().and_then(Watchdog::failure).is_some() {
+                error!(stage, "supervised task failed; exiting for restart");
+                ExitCode::TaskFailure
+            } else {
+                error!(stage, "subsystem exited unexpectedly");
+                ExitCode::Internal
+            }
+        }
+    }
+}
+
+/// Configured validator set (the built-in defaults without a config).
+// Beacon saved by the last run, or the configured genesis one, with its stores.
+fn open_beacon(
+    state: &PersistentState,
+    cfg: &BeaconConfig,
+) -> Result<(RandomnessBeacon, BeaconStore, HeaderStore), StageFailure> {
+    let store = BeaconStore::open(state).map_err(StageFailure::msg)?;
+    let headers = HeaderStore::open(state).map_err(StageFailure::classified)?;
+    let beacon = match store.load().map_err(StageFailure::msg)? {
+        Some(b) if b.epoch_length != cfg.epoch_length => {
+            return Err(StageFailure::msg(format!(
+                "beacon epoch length {} does not match consensus.beacon.epoch_length {}",
+                b.epoch_length, cfg.epoch_length
+            )))
+        }
+        Some(b) => b,
+        None => {
+            let randomness = hex::decode(&cfg.genesis_randomness_hex)
+                .ok()
+                .and_then(|b| <[u8; 32]>::try_from(b).ok())
+                .ok_or_else(|| {
+                    StageFailure::msg("invalid consensus.beacon.genesis_randomness_hex")
+                })?;
+            RandomnessBeacon::genesis(cfg.epoch_length, randomness).map_err(StageFailure::msg)?
+        }
+    };
+    Ok((beacon, store, headers))
+}
+
+fn validator_set(config: Option<&NodeConfig>) -> Result<BTreeSet<ValidatorId>, String> {
+    let defaults;
+    let config = match config {
+        Some(c) => c,
+        None => {
+            defaults = ConfigLoader::new().load().map_err(|e| e.to_string())?;
+            &defaults
+        }
+    };
+    config
+        .consensus
+        .validators_hex
+        .iter()
+        .map(|v| {
+            hex::decode(v)
+                .ok()
+                .and_then(|b| ValidatorId::try_from(b).ok())
+                .ok_or_else(|| format!("invalid validator key {v:?}"))
+        })
+        .collect()
+}
+
+/// Ordered shutdown; each step gets at most `timeout`.
+async fn graceful_shutdown(
+    mut running: RunningNode,
+    watchdog: Option<Watchdog>,
+    state_dir: &Path,
+    timeout: Duration,
+) -> ExitCode {
+    info!("shutdown requested");
+    if let Some(w) = watchdog.as_ref() {
+        w.begin_shutdown();
+    }
+    let mut code = ExitCode::Success;
+
+    if let Some(p2p) = running.resources().get::<P2pNode>() {
+        p2p.stop_intake();
+    }
+    if !running.stop_stage("consensus", timeout).await {
+        warn!("consensus did not drain in time; remaining votes dropped");
+    }
+
+    let height = running
+        .resources()
+        .get::<SharedDriver>()
+        .and_then(|d| d.lock().ok().map(|d| d.tide.finalized_height().get()));
+    if let (Some(state), Some(height)) = (running.resources().get::<PersistentState>(), height) {
+        match checkpoint::write(state, height) {
+            Ok(cp) => {
+                info!(
+                    height,
+                    state_root = %hex::encode(cp.state_root),
+                    "state flushed; shutdown checkpoint written"
+                );
+                if let Err(e) = integrity::write_marker(state_dir, &cp) {
+                    warn!(err = %e, "integrity marker not written");
+                }
+            }
+            Err(e) => {
+                error!(err = %e, "shutdown checkpoint failed");
+                code = e.exit_code();
+            }
+        }
+    }
+
+    if let Some(p2p) = running.resources().get::<P2pNode>() {
+        p2p.close();
+    }
+    running.stop_stage("p2p", timeout).await;
+    running.shutdown();
+    info!("shutdown complete");
+    code
+}
+
+/// Completes on ctrl-c, or SIGTERM on unix.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut term) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = term.recv() => {}
+                }
+                return;
+            }
+            Err(e) => warn!(err = %e, "cannot listen for SIGTERM; ctrl-c only"),
+        }
+    }
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        warn!(err = %e, "cannot listen for ctrl-c");
+        std::future::pending::<()>().await;
+    }
+}
+
+/// On SIGHUP, reload the config (if reloadable), then apply the filter in
+/// `AMUN_LOG_FILTER_FILE` (if set), which wins over `telemetry.log_filter`.
+#[cfg(unix)]
+fn spawn_sighup_reload(
+    mut reloader: Option<Reloader>,
+    filter: Option<LogFilterHandle>,
+) -> Result<tokio::task::JoinHandle<()>, StageFailure> {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut hup = signal(SignalKind::hangup()).map_err(StageFailure::msg)?;
+    Ok(tokio::spawn(async move {
+        while hup.recv().await.is_some() {
+            if let Some(r) = reloader.as_mut() {
+                r.reload();
+            }
+            let Some(filter) = filter.as_ref() else {
+                continue;
+            };
+            let Ok(path) = std::env::var("AMUN_LOG_FILTER_FILE") else {
+                if reloader.is_none() {
+                    warn!("SIGHUP without AMUN_LOG_FILTER_FILE; log filter unchanged");
+                }
+                continue;
+            };
+            match std::fs::read_to_string(&path) {
+                Ok(directives) => {
+                    if let Err(e) = filter.set(&directives) {
+                        warn!(err = %e, %path, "log filter reload failed");
+                    }
+                }
+                Err(e) => warn!(err = %e, %path, "cannot read log filter file"),
+            }
+        }
+    }))
+}
+
+#[cfg(not(unix))]
+fn spawn_sighup_reload(
+    _reloader: Option<Reloader>,
+    _filter: Option<LogFilterHandle>,
+) -> Result<tokio::task::JoinHandle<()>, StageFailure> {
+    Ok(tokio::spawn(std::future::pending()))
+}
+
+fn shared_watchdog(res: &Resources) -> Result<Watchdog, StageFailure> {
+    res.get::<Watchdog>()
+        .cloned()
+        .ok_or_else(|| StageFailure::msg("watchdog not initialized"))
+}
+
+fn shared_metrics(res: &Resources) -> Result<Arc<Metrics>, StageFailure> {
+    res.get::<Arc<Metrics>>()
+        .cloned()
+        .ok_or_else(|| StageFailure::msg("metrics not initialized"))
+}
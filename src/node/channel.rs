@@ -0,0 +1,211 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Named, bounded channels for plumbing between subsystems.
+//!
+//! A thin layer over `tokio::sync::mpsc` that gives every channel a name, an explicit
+//! capacity and overflow policy, and two metrics labelled by that name:
+//! `amunchain_channel_depth` (queued messages) and `amunchain_channel_dropped_total`
+//! (messages discarded by a lossy channel). Nothing is dropped without being counted.
+//!
+//! Each message carries the sender's current tracing span, so the receiving side can link
+//! its own span to the one that produced the message (`Receiver::recv_with_span`).
+
+use crate::monitoring::metrics::Metrics;
+use prometheus::{IntCounter, IntGauge};
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tracing::Span;
+
+/// What a full channel does with a new message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    /// Wait for room (backpressure onto the sender).
+    Block,
+    /// Discard the new message and count it.
+    DropNewest,
+}
+
+/// Channel capacity and overflow policy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChannelConfig {
+    pub capacity: usize,
+    pub overflow: Overflow,
+}
+
+impl ChannelConfig {
+    /// Backpressured channel.
+    pub const fn blocking(capacity: usize) -> Self {
+        Self {
+            capacity,
+            overflow: Overflow::Block,
+        }
+    }
+
+    /// Channel that drops new messages when full.
+    pub const fn lossy(capacity: usize) -> Self {
+        Self {
+            capacity,
+            overflow: Overflow::DropNewest,
+        }
+    }
+}
+
+/// Send failures.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SendError {
+    #[error("channel `{0}` closed")]
+    Closed(&'static str),
+    #[error("channel `{0}` full; message dropped")]
+    Dropped(&'static str),
+}
+
+struct Shared {
+    name: &'static str,
+    cfg: ChannelConfig,
+    depth: Option<IntGauge>,
+    dropped: Option<IntCounter>,
+}
+
+impl Shared {
+    fn set_depth(&self, depth: usize) {
+        if let Some(g) = self.depth.as_ref() {
+            g.set(depth as i64);
+        }
+    }
+
+    fn dropped(&self) -> SendError {
+        if let Some(c) = self.dropped.as_ref() {
+            c.inc();
+        }
+        SendError::Dropped(self.name)
+    }
+}
+
+/// Sending half.
+pub struct Sender<T> {
+    tx: mpsc::Sender<(T, Span)>,
+    shared: Arc<Shared>,
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Sender<T> {
+    /// Channel name.
+    pub fn name(&self) -> &'static str {
+        self.shared.name
+    }
+
+    /// Messages currently queued.
+    pub fn len(&self) -> usize {
+        self.tx.max_capacity() - self.tx.capacity()
+    }
+
+    /// True if nothing is queued.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Send according to the channel's overflow policy.
+    pub async fn send(&self, value: T) -> Result<(), SendError> {
+        match self.shared.cfg.overflow {
+            Overflow::Block => self
+                .tx
+                .send((value, Span::current()))
+                .await
+                .map_err(|_| SendError::Closed(self.shared.name))?,
+            Overflow::DropNewest => return self.try_send(value),
+        }
+        self.shared.set_depth(self.len());
+        Ok(())
+    }
+
+    /// Send without waiting; a full channel drops (and counts) the message regardless of
+    /// policy. For use outside async contexts.
+    pub fn try_send(&self, value: T) -> Result<(), SendError> {
+        match self.tx.try_send((value, Span::current())) {
+            Ok(()) => {
+                self.shared.set_depth(self.len());
+                Ok(())
+            }
+            Err(mpsc::error::TrySendError::Full(_)) => Err(self.shared.dropped()),
+            Err(mpsc::error::TrySendError::Closed(_)) => Err(SendError::Closed(self.shared.name)),
+        }
+    }
+}
+
+/// Receiving half.
+pub struct Receiver<T> {
+    rx: mpsc::Receiver<(T, Span)>,
+    shared: Arc<Shared>,
+}
+
+impl<T> Receiver<T> {
+    /// Channel name.
+    pub fn name(&self) -> &'static str {
+        self.shared.name
+    }
+
+    /// Messages currently queued.
+    pub fn len(&self) -> usize {
+        self.rx.len()
+    }
+
+    /// True if nothing is queued.
+    pub fn is_empty(&self) -> bool {
+        self.rx.is_empty()
+    }
+
+    /// Receive the next message; `None` once every sender is gone.
+    pub async fn recv(&mut self) -> Option<T> {
+        self.recv_with_span().await.map(|(v, _)| v)
+    }
+
+    /// Like `recv`, also returning the span that was current when the message was sent.
+    pub async fn recv_with_span(&mut self) -> Option<(T, Span)> {
+        let v = self.rx.recv().await;
+        self.shared.set_depth(self.rx.len());
+        v
+    }
+}
+
+/// Create a named channel. With `metrics`, depth and drops are exported under `name`.
+pub fn channel<T>(
+    name: &'static str,
+    cfg: ChannelConfig,
+    metrics: Option<&Metrics>,
+) -> (Sender<T>, Receiver<T>) {
+    let (tx, rx) = mpsc::channel(cfg.capacity.max(1));
+    let shared = Arc::new(Shared {
+        name,
+        cfg,
+        depth: metrics.map(|m| m.channel_depth.with_label_values(&[name])),
+        dropped: metrics.map(|m| m.channel_dropped_total.with_label_values(&[name])),
+    });
+    shared.set_depth(0);
+    (
+        Sender {
+            tx,
+            shared: shared.clone(),
+        },
+        Receiver { rx, shared },
+    )
+}
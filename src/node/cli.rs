@@ -0,0 +1,513 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Command line of the node binary.
+//!
+//! ```text
+//! amunchain [--config <FILE>] [--data-dir <DIR>] <COMMAND>
+//!
+//!   run              start the node (default when no command is given)
+//!   init             write a config file and create the p2p identity
+//!   keygen           create the validator signing key
+//!   print-peer-id    print the p2p peer id
+//!   check-config     validate a config file
+//!   export-state     write the state tree to a snapshot file
+//!   import-state     load a snapshot into an empty state tree
+//!   migrate-state-tree  rebuild the state root with another tree (sorted, sparse)
+//!   backup           copy the data directory with a signed manifest
+//!   restore          verify a backup and restore it into the data directory
+//! ```
+//!
+//! The config is layered as described in `crate::config`; `--set key=value` (repeatable)
+//! overrides single values. The data directory is `--data-dir`, else `node.data_dir` of the
+//! layered config (where `AMUN_DATA_DIR` sits in the environment layer). Subcommands print
+//! their result on stdout and fail with an `ExitCode` class, so scripts can rely on both.
+
+use crate::config::{ConfigLoader, DEFAULT_CONFIG};
+use crate::core::security::keystore::{self, KeystoreError};
+use crate::core::state::checkpoint;
+use crate::core::state::integrity::{self, IntegrityError};
+use crate::core::state::merkle::{merkle_root_sorted, Hash32};
+use crate::core::state::persistent_state::{KvOp, PersistentState, StateError};
+use crate::core::state::smt::{sparse_root, StateTree};
+use crate::core::types::{decode_canonical_limited, encode_canonical, ConfigError, NodeConfig};
+use crate::networking::p2p_identity::IdentitySource;
+use crate::node::backup::BackupError;
+use crate::node::config_check::{self, ConfigIssue};
+use clap::{Parser, Subcommand};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// State database directory inside the data directory.
+pub const STATE_DIR: &str = "state";
+/// Validator signing key inside the data directory (PKCS#8, encrypted when
+/// `AMUNCHAIN_KEY_PASSPHRASE` is set; see `core::security::keystore`).
+pub const VALIDATOR_KEY_FILE: &str = "validator.key";
+/// p2p identity inside the data directory (see `networking::p2p_identity`).
+pub const P2P_IDENTITY_FILE: &str = "p2p_identity.key";
+
+const SNAPSHOT_MAGIC: &[u8; 8] = b"AMUNST01";
+const MAX_SNAPSHOT_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("state: {0}")]
+    State(#[from] StateError),
+    #[error("{path}: {reason}")]
+    Io { path: String, reason: String },
+    #[error("p2p identity unreadable")]
+    Identity,
+    #[error("key generation failed")]
+    Key,
+    #[error("validator key: {0}")]
+    Keystore(#[from] KeystoreError),
+    #[error("{0} already exists (pass --force to overwrite)")]
+    Exists(String),
+    #[error("{0} not found; run `amunchain init` first")]
+    NotInitialized(String),
+    #[error("state database {0} is not empty")]
+    NotEmpty(String),
+    #[error("{path}: {} problem(s)\n{}", .issues.len(), list_issues(.issues))]
+    InvalidConfig {
+        path: String,
+        issues: Vec<ConfigIssue>,
+    },
+    #[error("{0} is required")]
+    MissingArg(&'static str),
+    #[error("invalid snapshot: {0}")]
+    Snapshot(&'static str),
+    #[error("backup: {0}")]
+    Backup(#[from] BackupError),
+    #[error(transparent)]
+    Integrity(#[from] IntegrityError),
+}
+
+fn list_issues(issues: &[ConfigIssue]) -> String {
+    issues
+        .iter()
+        .map(|i| format!("  - {i}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn io_err(path: &Path) -> impl FnOnce(std::io::Error) -> CliError + '_ {
+    move |e| CliError::Io {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    }
+}
+
+/// Amunchain node.
+#[derive(Debug, Parser)]
+#[command(name = "amunchain", version, about, propagate_version = true)]
+pub struct Cli {
+    /// Node config file (TOML).
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+    /// Data directory (db and keys).
+    #[arg(long, global = true, value_name = "DIR")]
+    pub data_dir: Option<PathBuf>,
+    /// Override one config value, e.g. `--set p2p.max_peers_per_ip=8`. Repeatable.
+    #[arg(long = "set", global = true, value_name = "KEY=VALUE")]
+    pub overrides: Vec<String>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Start the node and run until it stops.
+    Run {
+        /// If the state database fails its startup integrity check, move it aside and
+        /// start from an empty state, catching up from peers.
+        #[arg(long)]
+        repair: bool,
+        /// Like `--repair`, but rebuild the state from a snapshot written by `export-state`.
+        #[arg(long, value_name = "FILE")]
+        repair_from: Option<PathBuf>,
+    },
+    /// Write a config file and create the p2p identity.
+    ///
+    /// The config goes to `--config`, default `<data-dir>/node.toml`. Prints the peer id.
+    Init {
+        /// Overwrite an existing config file.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Create the validator signing key. Prints the public key (hex).
+    Keygen {
+        /// Replace an existing key.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print the p2p peer id.
+    PrintPeerId,
+    /// Validate a config file: schema, addresses, keys, port conflicts.
+    CheckConfig {
+        /// Config file to check (default: `--config`).
+        #[arg(value_name = "PATH")]
+        path: Option<PathBuf>,
+    },
+    /// Write the state tree to a snapshot file.
+    ExportState {
+        /// Snapshot file to create.
+        #[arg(value_name = "FILE")]
+        out: PathBuf,
+    },
+    /// Load a snapshot into an empty state tree.
+    ImportState {
+        /// Snapshot file to read.
+        #[arg(value_name = "FILE")]
+        input: PathBuf,
+    },
+    /// Rebuild the state root with another Merkle tree.
+    ///
+    /// The node must be stopped, and restarted with `consensus.state_tree` set to match. Every
+    /// node of a network has to use the same tree.
+    MigrateStateTree {
+        /// Tree to convert to: `sorted` or `sparse`.
+        #[arg(long, value_name = "TREE")]
+        to: StateTree,
+    },
+    /// Copy the data directory to a new backup directory with a signed manifest.
+    ///
+    /// The node must be stopped. Key files are left out unless `--include-keys` is given.
+    Backup {
+        /// Backup directory to create (must be empty or missing).
+        #[arg(value_name = "DIR")]
+        dest: PathBuf,
+        /// Also copy the validator key and p2p identity.
+        #[arg(long)]
+        include_keys: bool,
+    },
+    /// Verify a backup against its manifest and restore it into the data directory.
+    ///
+    /// The state database of the data directory must be empty.
+    Restore {
+        /// Backup directory to read.
+        #[arg(value_name = "DIR")]
+        src: PathBuf,
+        /// Accept a backup whose manifest is not signed by a configured validator key.
+        #[arg(long)]
+        allow_unsigned: bool,
+    },
+}
+
+impl Command {
+    /// The command run without a subcommand.
+    pub const DEFAULT: Command = Command::Run {
+        repair: false,
+        repair_from: None,
+    };
+}
+
+impl Cli {
+    /// Environment and command-line layers, without a config file.
+    pub fn loader(&self) -> Result<ConfigLoader, CliError> {
+        let mut loader = ConfigLoader::new().with_env();
+        for arg in self.overrides.iter() {
+            loader = loader.set_arg(arg)?;
+        }
+        if let Some(dir) = self.data_dir.as_ref() {
+            let dir = toml::Value::String(dir.display().to_string()).to_string();
+            loader = loader.set("node.data_dir", dir);
+        }
+        Ok(loader)
+    }
+
+    /// Effective data directory.
+    pub fn resolve_data_dir(&self, config: &NodeConfig) -> PathBuf {
+        match self.data_dir.as_ref() {
+            Some(dir) => dir.clone(),
+            None => PathBuf::from(&config.node.data_dir),
+        }
+    }
+}
+
+/// Load the layered config and run the semantic checks of `config_check`.
+pub fn check_config(loader: &ConfigLoader) -> Result<NodeConfig, CliError> {
+    let config = loader.load()?;
+    let issues = config_check::check(&config);
+    if !issues.is_empty() {
+        let path = loader
+            .file_path()
+            .map_or("config".to_string(), |p| p.display().to_string());
+        return Err(CliError::InvalidConfig { path, issues });
+    }
+    Ok(config)
+}
+
+/// Write the config template to `config_path` with `data_dir` filled in, and create the p2p
+/// identity. Returns the peer id.
+pub fn init(data_dir: &Path, config_path: &Path, force: bool) -> Result<String, CliError> {
+    if config_path.exists() && !force {
+        return Err(CliError::Exists(config_path.display().to_string()));
+    }
+    std::fs::create_dir_all(data_dir).map_err(io_err(data_dir))?;
+    let dir_value = toml::Value::String(data_dir.display().to_string()).to_string();
+    let config =
+        DEFAULT_CONFIG.replacen("data_dir = \"data\"", &format!("data_dir = {dir_value}"), 1);
+    if let Some(parent) = config_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(io_err(parent))?;
+    }
+    std::fs::write(config_path, config).map_err(io_err(config_path))?;
+    let (peer_id, _) = crate::networking::p2p_identity::load_or_create_identity(data_dir)
+        .map_err(|_| CliError::Identity)?;
+    Ok(peer_id.to_string())
+}
+
+/// Create `<data_dir>/validator.key`, encrypted when `AMUNCHAIN_KEY_PASSPHRASE` is set.
+/// Returns the hex public key.
+pub fn keygen(data_dir: &Path, force: bool) -> Result<String, CliError> {
+    let key_path = data_dir.join(VALIDATOR_KEY_FILE);
+    if key_path.exists() && !force {
+        return Err(CliError::Exists(key_path.display().to_string()));
+    }
+    let passphrase = keystore::passphrase_from_env()?;
+    let kp = keystore::generate_key_file(&key_path, passphrase.as_deref())?;
+    Ok(hex::encode(kp.public_key().as_ref()))
+}
+
+/// A validator key file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct KeyInfo {
+    pub path: PathBuf,
+    /// Ed25519 public key, hex.
+    pub public_key: String,
+    /// Stored in the passphrase-encrypted format.
+    pub encrypted: bool,
+}
+
+// The key at `path` and whether it is stored encrypted.
+fn open_key(path: &Path) -> Result<(Ed25519KeyPair, bool), CliError> {
+    if !path.exists() {
+        return Err(CliError::NotInitialized(path.display().to_string()));
+    }
+    let bytes = std::fs::read(path).map_err(io_err(path))?;
+    let passphrase = keystore::passphrase_from_env()?;
+    let kp = keystore::read_key_file(path, passphrase.as_deref())?;
+    Ok((kp, keystore::is_encrypted(&bytes)))
+}
+
+fn read_key(path: &Path) -> Result<KeyInfo, CliError> {
+    let (kp, encrypted) = open_key(path)?;
+    Ok(KeyInfo {
+        path: path.to_path_buf(),
+        public_key: hex::encode(kp.public_key().as_ref()),
+        encrypted,
+    })
+}
+
+/// The existing validator key. Encrypted keys need `AMUNCHAIN_KEY_PASSPHRASE`.
+pub fn key_info(data_dir: &Path) -> Result<KeyInfo, CliError> {
+    read_key(&data_dir.join(VALIDATOR_KEY_FILE))
+}
+
+/// The validator public key as PEM (see `keystore::public_key_pem`).
+pub fn public_key_pem(data_dir: &Path) -> Result<String, CliError> {
+    let (kp, _) = open_key(&data_dir.join(VALIDATOR_KEY_FILE))?;
+    let pk: [u8; 32] = kp
+        .public_key()
+        .as_ref()
+        .try_into()
+        .map_err(|_| CliError::Key)?;
+    Ok(keystore::public_key_pem(&pk))
+}
+
+/// Result of `rotate_key`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct KeyRotation {
+    /// The replaced key, at its backup path.
+    pub previous: KeyInfo,
+    pub current: KeyInfo,
+}
+
+/// Replace the validator key with a new one. The old key file is kept as
+/// `validator.key.<unix-ms>.old` next to it (never deleted) and must be readable, so a
+/// wrong passphrase cannot orphan it.
+pub fn rotate_key(data_dir: &Path) -> Result<KeyRotation, CliError> {
+    let key_path = data_dir.join(VALIDATOR_KEY_FILE);
+    let previous = read_key(&key_path)?;
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis());
+    let backup = data_dir.join(format!("{VALIDATOR_KEY_FILE}.{now_ms}.old"));
+    if backup.exists() {
+        return Err(CliError::Exists(backup.display().to_string()));
+    }
+    std::fs::rename(&key_path, &backup).map_err(io_err(&key_path))?;
+    if let Err(e) = keygen(data_dir, false) {
+        // Put the old key back rather than leave the node without one.
+        let _ = std::fs::rename(&backup, &key_path);
+        return Err(e);
+    }
+    Ok(KeyRotation {
+        previous: KeyInfo {
+            path: backup,
+            ..previous
+        },
+        current: read_key(&key_path)?,
+    })
+}
+
+/// Operator guidance after creating `key` (printed by `keygen`).
+pub fn key_guidance(key: &KeyInfo) -> String {
+    let storage = if key.encrypted {
+        "encrypted with AMUNCHAIN_KEY_PASSPHRASE; the node needs the same passphrase to start"
+    } else {
+        "NOT encrypted; set AMUNCHAIN_KEY_PASSPHRASE before keygen to encrypt it at rest"
+    };
+    format!(
+        "wrote {} ({storage}).\nAdd the public key to consensus.validators_hex on every node.",
+        key.path.display()
+    )
+}
+
+/// Peer id of the existing p2p identity. Does not create one.
+pub fn peer_id(data_dir: &Path, source: IdentitySource) -> Result<String, CliError> {
+    let file = match source {
+        IdentitySource::Validator => VALIDATOR_KEY_FILE,
+        IdentitySource::File | IdentitySource::Encrypted => P2P_IDENTITY_FILE,
+    };
+    let path = data_dir.join(file);
+    if !path.exists() {
+        return Err(CliError::NotInitialized(path.display().to_string()));
+    }
+    let (peer_id, _) = crate::networking::p2p_identity::load_identity(data_dir, source)
+        .map_err(|_| CliError::Identity)?;
+    Ok(peer_id.to_string())
+}
+
+/// Summary of an exported or imported snapshot.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SnapshotInfo {
+    pub entries: usize,
+    pub state_root: String,
+}
+
+// File body after `SNAPSHOT_MAGIC`. Only the main tree (the one covered by the state
+// root) is exported; auxiliary trees are rebuilt by the node.
+#[derive(Serialize, Deserialize)]
+struct StateSnapshot {
+    state_root: Hash32,
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Open the state database of `data_dir`.
+pub fn open_state(data_dir: &Path) -> Result<PersistentState, CliError> {
+    let path = data_dir.join(STATE_DIR);
+    Ok(PersistentState::open(&path.to_string_lossy())?)
+}
+
+/// Write every key of the state tree to `out`.
+pub fn export_state(data_dir: &Path, out: &Path) -> Result<SnapshotInfo, CliError> {
+    let state = open_state(data_dir)?;
+    let snapshot = StateSnapshot {
+        state_root: state.state_root()?,
+        entries: state.scan_prefix(b"")?,
+    };
+    let mut bytes = SNAPSHOT_MAGIC.to_vec();
+    bytes.extend(encode_canonical(&snapshot).map_err(|_| CliError::Snapshot("encode"))?);
+    std::fs::write(out, bytes).map_err(io_err(out))?;
+    Ok(SnapshotInfo {
+        entries: snapshot.entries.len(),
+        state_root: hex::encode(snapshot.state_root),
+    })
+}
+
+/// Check the snapshot at `input` against its state root and load it into the (empty)
+/// state tree.
+pub fn import_state(data_dir: &Path, input: &Path) -> Result<SnapshotInfo, CliError> {
+    let state = open_state(data_dir)?;
+    if !state.scan_prefix(b"")?.is_empty() {
+        return Err(CliError::NotEmpty(
+            data_dir.join(STATE_DIR).display().to_string(),
+        ));
+    }
+    let info = load_snapshot(&state, input)?;
+    integrity::clear_marker(&data_dir.join(STATE_DIR))?;
+    state.flush()?;
+    Ok(info)
+}
+
+/// Result of `migrate_state_tree`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TreeMigration {
+    pub from: StateTree,
+    pub to: StateTree,
+    pub state_root: String,
+}
+
+/// Convert the state database of `data_dir` to tree `to` (see
+/// `PersistentState::convert_state_tree`). Drops the shutdown checkpoint and its copy, which
+/// hold the root of the old tree.
+pub fn migrate_state_tree(data_dir: &Path, to: StateTree) -> Result<TreeMigration, CliError> {
+    let mut state = open_state(data_dir)?;
+    let from = state.state_tree();
+    if from != to {
+        checkpoint::clear(&state)?;
+        integrity::clear_marker(&data_dir.join(STATE_DIR))?;
+        state.convert_state_tree(to)?;
+        state.flush()?;
+    }
+    Ok(TreeMigration {
+        from,
+        to,
+        state_root: hex::encode(state.state_root()?),
+    })
+}
+
+/// `import_state` into an open database with an empty state tree. Drops the shutdown
+/// checkpoint, which no longer describes the state.
+pub fn load_snapshot(state: &PersistentState, input: &Path) -> Result<SnapshotInfo, CliError> {
+    let len = std::fs::metadata(input).map_err(io_err(input))?.len();
+    if len > MAX_SNAPSHOT_BYTES {
+        return Err(CliError::Snapshot("too large"));
+    }
+    let raw = std::fs::read(input).map_err(io_err(input))?;
+    let body = raw
+        .strip_prefix(SNAPSHOT_MAGIC.as_slice())
+        .ok_or(CliError::Snapshot("bad magic"))?;
+    let mut snapshot: StateSnapshot =
+        decode_canonical_limited(body, body.len()).map_err(|_| CliError::Snapshot("decode"))?;
+    snapshot.entries.sort_by(|a, b| a.0.cmp(&b.0));
+    if snapshot.entries.windows(2).any(|w| w[0].0 == w[1].0) {
+        return Err(CliError::Snapshot("duplicate key"));
+    }
+    // The root is of the exporting database's tree, either one.
+    if merkle_root_sorted(&snapshot.entries) != snapshot.state_root
+        && sparse_root(&snapshot.entries) != snapshot.state_root
+    {
+        return Err(CliError::Snapshot("state root mismatch"));
+    }
+
+    if !state.scan_prefix(b"")?.is_empty() {
+        return Err(CliError::NotEmpty(STATE_DIR.into()));
+    }
+    let entries = snapshot.entries.len();
+    let ops = snapshot
+        .entries
+        .into_iter()
+        .map(|(key, value)| KvOp::Put { key, value })
+        .collect();
+    state.commit_atomic(ops)?;
+    checkpoint::clear(state)?;
+    Ok(SnapshotInfo {
+        entries,
+        state_root: hex::encode(snapshot.state_root),
+    })
+}
@@ -0,0 +1,362 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Semantic checks on a parsed `NodeConfig`.
+//!
+//! Deserialization only proves the TOML has the right shape. `check` looks at the values:
+//! addresses must parse, validator keys must be 32-byte hex, the HTTP and p2p listeners (the
+//! `http.listen_addr` and `p2p.listen_addr` the node binds) must not claim the same port, a peer registry needs its verification key, and only validators
+//! may use the validator key as their p2p identity. Every problem is
+//! reported (not just the first) with the field it belongs to.
+
+use crate::core::economics::fees::FeeParams;
+use crate::core::security::audit_sink::HttpSink;
+use crate::core::types::{AuditSinkConfig, NodeConfig};
+use crate::node::role;
+use libp2p::multiaddr::Protocol;
+use libp2p::{Multiaddr, PeerId};
+use std::collections::BTreeSet;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+/// One problem found in a config.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigIssue {
+    /// Dotted path of the offending field, e.g. `p2p.bootstrap[1]`.
+    pub field: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+#[derive(Default)]
+struct Issues(Vec<ConfigIssue>);
+
+impl Issues {
+    fn push(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.0.push(ConfigIssue {
+            field: field.into(),
+            message: message.into(),
+        });
+    }
+}
+
+/// All problems in `cfg`; empty when it is usable.
+pub fn check(cfg: &NodeConfig) -> Vec<ConfigIssue> {
+    let mut issues = Issues::default();
+    if cfg.node.data_dir.trim().is_empty() {
+        issues.push("node.data_dir", "must not be empty");
+    }
+    if let Err(e) = role::validate(cfg) {
+        issues.push(e.field(), e.to_string());
+    }
+    let http = check_http(cfg, &mut issues);
+    let p2p = check_p2p(cfg, &mut issues);
+    if let (Some(http), Some((p2p_ip, p2p_port))) = (http, p2p) {
+        if http.port() == p2p_port && ips_overlap(http.ip(), p2p_ip) {
+            issues.push(
+                "p2p.listen_addr",
+                format!("tcp port {p2p_port} is also used by http.listen_addr; pick another port"),
+            );
+        }
+    }
+    check_registry(cfg, &mut issues);
+    check_consensus(cfg, &mut issues);
+    check_runtime(cfg, &mut issues);
+    check_keystore(cfg, &mut issues);
+    issues.0
+}
+
+fn check_http(cfg: &NodeConfig, issues: &mut Issues) -> Option<SocketAddr> {
+    let http = &cfg.http;
+    match (&http.tls_cert_path, &http.tls_key_path) {
+        (Some(_), None) => issues.push("http.tls_key_path", "required when tls_cert_path is set"),
+        (None, Some(_)) => issues.push("http.tls_cert_path", "required when tls_key_path is set"),
+        (None, None) if http.tls_client_ca_path.is_some() => issues.push(
+            "http.tls_client_ca_path",
+            "needs tls_cert_path and tls_key_path; client certificates only work over https",
+        ),
+        _ => {}
+    }
+    for (field, path) in [
+        ("http.tls_cert_path", &http.tls_cert_path),
+        ("http.tls_key_path", &http.tls_key_path),
+        ("http.tls_client_ca_path", &http.tls_client_ca_path),
+    ] {
+        if let Some(path) = path {
+            if !std::path::Path::new(path).exists() {
+                issues.push(field, format!("{path} does not exist"));
+            }
+        }
+    }
+    match cfg.http.listen_addr.parse::<SocketAddr>() {
+        Ok(addr) => Some(addr),
+        Err(_) => {
+            issues.push(
+                "http.listen_addr",
+                format!(
+                    "{:?} is not an ip:port address (e.g. \"127.0.0.1:9090\")",
+                    cfg.http.listen_addr
+                ),
+            );
+            None
+        }
+    }
+}
+
+// Returns the ip and tcp port of the p2p listener.
+fn check_p2p(cfg: &NodeConfig, issues: &mut Issues) -> Option<(IpAddr, u16)> {
+    let p2p = &cfg.p2p;
+    let listen = match p2p.listen_addr.parse::<Multiaddr>() {
+        Ok(addr) => {
+            let listen = listen_ip_port(&addr);
+            if listen.is_none() {
+                issues.push(
+                    "p2p.listen_addr",
+                    "must have an /ip4 or /ip6 and a /tcp component",
+                );
+            }
+            listen
+        }
+        Err(e) => {
+            issues.push(
+                "p2p.listen_addr",
+                format!(
+                    "{:?} is not a multiaddr ({e}); expected e.g. \"/ip4/0.0.0.0/tcp/30333\"",
+                    p2p.listen_addr
+                ),
+            );
+            None
+        }
+    };
+    if p2p.topic.trim().is_empty() {
+        issues.push("p2p.topic", "must not be empty");
+    }
+    if p2p.max_msg_per_sec == 0 {
+        issues.push("p2p.max_msg_per_sec", "must be at least 1");
+    }
+    if p2p.max_peers_per_ip == 0 {
+        issues.push("p2p.max_peers_per_ip", "must be at least 1");
+    }
+    for (i, b) in p2p.bootstrap.iter().enumerate() {
+        match b.parse::<Multiaddr>() {
+            Ok(addr) if !addr.iter().any(|p| matches!(p, Protocol::P2p(_))) => issues.push(
+                format!("p2p.bootstrap[{i}]"),
+                format!("{b:?} has no /p2p/<peer id> suffix"),
+            ),
+            Ok(_) => {}
+            Err(e) => issues.push(
+                format!("p2p.bootstrap[{i}]"),
+                format!("{b:?} is not a multiaddr ({e})"),
+            ),
+        }
+    }
+    for e in p2p.gossipsub.validate() {
+        issues.push(format!("p2p.gossipsub.{}", e.field()), e.to_string());
+    }
+    for e in p2p.limits.validate() {
+        issues.push(format!("p2p.limits.{}", e.field()), e.to_string());
+    }
+    let mut seen = BTreeSet::new();
+    for (i, p) in p2p.allow_peers.iter().enumerate() {
+        if p.parse::<PeerId>().is_err() {
+            issues.push(
+                format!("p2p.allow_peers[{i}]"),
+                format!("{p:?} is not a peer id"),
+            );
+        } else if !seen.insert(p) {
+            issues.push(
+                format!("p2p.allow_peers[{i}]"),
+                format!("{p} is listed twice"),
+            );
+        }
+    }
+    listen
+}
+
+fn check_registry(cfg: &NodeConfig, issues: &mut Issues) {
+    let p2p = &cfg.p2p;
+    match (&p2p.peer_registry_path, &p2p.peer_registry_pubkey_hex) {
+        (Some(path), key) => {
+            if !std::path::Path::new(path).exists() {
+                issues.push("p2p.peer_registry_path", format!("{path} does not exist"));
+            }
+            match key {
+                None => issues.push(
+                    "p2p.peer_registry_pubkey_hex",
+                    "required when peer_registry_path is set",
+                ),
+                Some(k) if !is_hex32(k) => issues.push(
+                    "p2p.peer_registry_pubkey_hex",
+                    "must be a 32-byte ed25519 key in hex (64 characters)",
+                ),
+                Some(_) => {}
+            }
+        }
+        (None, Some(_)) => issues.push(
+            "p2p.peer_registry_pubkey_hex",
+            "set without peer_registry_path; it has no effect",
+        ),
+        (None, None) => {
+            if p2p.require_allow_peers
+                && p2p.allow_peers.is_empty()
+                && cfg.node.role.requires_allowlist()
+            {
+                issues.push(
+                    "p2p.require_allow_peers",
+                    "allow_peers is empty and no peer_registry_path is set; the node would refuse to start",
+                );
+            }
+        }
+    }
+}
+
+fn check_consensus(cfg: &NodeConfig, issues: &mut Issues) {
+    let validators = &cfg.consensus.validators_hex;
+    if validators.is_empty() {
+        issues.push("consensus.validators_hex", "needs at least one validator");
+    }
+    let mut seen = BTreeSet::new();
+    for (i, v) in validators.iter().enumerate() {
+        if !is_hex32(v) {
+            issues.push(
+                format!("consensus.validators_hex[{i}]"),
+                format!("{v:?} is not a 32-byte ed25519 key in hex (64 characters)"),
+            );
+        } else if !seen.insert(v.to_ascii_lowercase()) {
+            issues.push(
+                format!("consensus.validators_hex[{i}]"),
+                "duplicate validator",
+            );
+        }
+    }
+    if let Err(e) = cfg.consensus.quorum.validate() {
+        issues.push("consensus.quorum", e.to_string());
+    }
+    if let Some(beacon) = cfg.consensus.beacon.as_ref() {
+        if beacon.epoch_length == 0 {
+            issues.push("consensus.beacon.epoch_length", "must be at least 1");
+        }
+        if !is_hex32(&beacon.genesis_randomness_hex) {
+            issues.push(
+                "consensus.beacon.genesis_randomness_hex",
+                "must be 32 bytes in hex (64 characters)",
+            );
+        }
+    }
+    if FeeParams::from_config(&cfg.consensus).validate().is_err() {
+        issues.push(
+            "consensus.block_gas_limit",
+            "too small for the fee market (must be at least 2)",
+        );
+    }
+}
+
+fn check_runtime(cfg: &NodeConfig, issues: &mut Issues) {
+    if cfg.runtime.rpc_max_in_flight == 0 {
+        issues.push(
+            "runtime.rpc_max_in_flight",
+            "must be at least 1 (0 rejects every request)",
+        );
+    }
+    if cfg.http.ws_max_subscribers == 0 {
+        issues.push("http.ws_max_subscribers", "must be at least 1");
+    }
+    if cfg.http.ws_buffer == 0 {
+        issues.push("http.ws_buffer", "must be at least 1");
+    }
+    if !(0.0..=1.0).contains(&cfg.telemetry.sample_ratio) {
+        issues.push("telemetry.sample_ratio", "must be within 0..=1");
+    }
+    if let Err(e) = tracing_subscriber::EnvFilter::builder().parse(&cfg.telemetry.log_filter) {
+        issues.push("telemetry.log_filter", format!("invalid filter: {e}"));
+    }
+}
+
+fn check_keystore(cfg: &NodeConfig, issues: &mut Issues) {
+    if cfg.keystore.audit_queue_len == 0 {
+        issues.push("keystore.audit_queue_len", "must be at least 1");
+    }
+    if cfg.keystore.policy.allowed_domains.is_empty() {
+        issues.push(
+            "keystore.policy.allowed_domains",
+            "must not be empty (the key could sign nothing)",
+        );
+    }
+    if cfg.keystore.policy.max_signs_per_sec == 0 {
+        issues.push("keystore.policy.max_signs_per_sec", "must be at least 1");
+    }
+    for (i, sink) in cfg.keystore.audit_sinks.iter().enumerate() {
+        let field = format!("keystore.audit_sinks[{i}]");
+        match sink {
+            AuditSinkConfig::File { path } => {
+                if path.trim().is_empty() {
+                    issues.push(format!("{field}.path"), "must not be empty");
+                }
+            }
+            AuditSinkConfig::Syslog { addr } => {
+                let ok = match (addr.strip_prefix("udp://"), addr.strip_prefix("unix://")) {
+                    (Some(hostport), _) => hostport
+                        .rsplit_once(':')
+                        .is_some_and(|(h, p)| !h.is_empty() && p.parse::<u16>().is_ok()),
+                    (_, Some(path)) => path.starts_with('/'),
+                    _ => false,
+                };
+                if !ok {
+                    issues.push(
+                        format!("{field}.addr"),
+                        format!("{addr:?}: expected udp://host:port or unix:///path"),
+                    );
+                }
+            }
+            AuditSinkConfig::Http { url, timeout_ms } => {
+                if let Err(e) = HttpSink::new(url, Duration::from_millis((*timeout_ms).max(1))) {
+                    issues.push(
+                        format!("{field}.url"),
+                        format!("{url:?}: expected http://host[:port][/path] ({e})"),
+                    );
+                }
+                if *timeout_ms == 0 {
+                    issues.push(format!("{field}.timeout_ms"), "must be at least 1");
+                }
+            }
+        }
+    }
+}
+
+fn listen_ip_port(addr: &Multiaddr) -> Option<(IpAddr, u16)> {
+    let mut ip = None;
+    let mut port = None;
+    for p in addr.iter() {
+        match p {
+            Protocol::Ip4(a) => ip = Some(IpAddr::V4(a)),
+            Protocol::Ip6(a) => ip = Some(IpAddr::V6(a)),
+            Protocol::Tcp(p) => port = Some(p),
+            _ => {}
+        }
+    }
+    Some((ip?, port?))
+}
+
+// Two listeners collide if they bind the same ip, or either binds all interfaces.
+fn ips_overlap(a: IpAddr, b: IpAddr) -> bool {
+    a == b || a.is_unspecified() || b.is_unspecified()
+}
+
+fn is_hex32(s: &str) -> bool {
+    hex::decode(s).is_ok_and(|b| b.len() == 32)
+}
@@ -0,0 +1,236 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Extension points for downstream crates.
+//!
+//! A `NodeExtension` registers, at `NodeBuilder` time:
+//! - RPC namespaces, served under `/ext/<namespace>/...` behind the same in-flight limit as
+//!   built-in routes;
+//! - gossip topics, subscribed by the P2P task, with a handler for inbound messages;
+//! - block-validity checks, run in registration order after the built-in checks;
+//! - Prometheus collectors, registered into the node's metrics registry.
+//!
+//! Names are validated and deduplicated up front, so a conflicting extension fails startup
+//! instead of silently shadowing another.
+
+use crate::core::types::H256;
+use crate::monitoring::metrics::Metrics;
+use axum::Router;
+use prometheus::core::Collector;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Extension errors.
+#[derive(Debug, Error)]
+pub enum ExtensionError {
+    #[error("invalid name `{0}`")]
+    InvalidName(String),
+    #[error("`{0}` registered twice")]
+    Duplicate(String),
+    #[error("metrics registration failed for extension `{0}`")]
+    Metrics(&'static str),
+    #[error("block rejected by `{check}`: {reason}")]
+    BlockRejected { check: String, reason: String },
+}
+
+/// Handler for an extension gossip topic. Called on the P2P task; must not block.
+pub trait GossipHandler: Send + Sync + 'static {
+    fn on_message(&self, peer: &[u8], data: &[u8]);
+}
+
+/// Block being validated.
+#[derive(Clone, Copy, Debug)]
+pub struct BlockContext<'a> {
+    pub height: u64,
+    pub block_hash: H256,
+    /// Opaque block payload.
+    pub payload: &'a [u8],
+}
+
+/// Additional block-validity rule.
+pub trait BlockCheck: Send + Sync + 'static {
+    fn check(&self, block: &BlockContext<'_>) -> Result<(), String>;
+}
+
+/// A pluggable node module.
+pub trait NodeExtension: Send + Sync + 'static {
+    /// Unique extension name (used in logs and errors).
+    fn name(&self) -> &'static str;
+    /// Register this extension's RPC routes, topics, checks and metrics.
+    fn register(&self, reg: &mut ExtensionRegistry) -> Result<(), ExtensionError>;
+}
+
+/// Registration sink handed to `NodeExtension::register`.
+#[derive(Default)]
+pub struct ExtensionRegistry {
+    current: &'static str,
+    rpc: BTreeMap<String, Router>,
+    topics: BTreeMap<String, Arc<dyn GossipHandler>>,
+    checks: Vec<(String, Arc<dyn BlockCheck>)>,
+    collectors: Vec<(&'static str, Box<dyn Collector>)>,
+}
+
+/// Names: 1-64 chars of `[a-z0-9_-]` (`/` and `.` also allowed for topics).
+fn valid_name(s: &str, extra: &[char]) -> bool {
+    !s.is_empty()
+        && s.len() <= 64
+        && s.chars().all(|c| {
+            c.is_ascii_lowercase()
+                || c.is_ascii_digit()
+                || c == '-'
+                || c == '_'
+                || extra.contains(&c)
+        })
+}
+
+impl ExtensionRegistry {
+    /// Serve `router` under `/ext/<namespace>`.
+    pub fn rpc_namespace(
+        &mut self,
+        namespace: &str,
+        router: Router,
+    ) -> Result<&mut Self, ExtensionError> {
+        if !valid_name(namespace, &[]) {
+            return Err(ExtensionError::InvalidName(namespace.to_string()));
+        }
+        if self.rpc.contains_key(namespace) {
+            return Err(ExtensionError::Duplicate(format!(
+                "rpc namespace {namespace}"
+            )));
+        }
+        self.rpc.insert(namespace.to_string(), router);
+        Ok(self)
+    }
+
+    /// Subscribe to `topic` and route its inbound messages to `handler`.
+    pub fn gossip_topic(
+        &mut self,
+        topic: &str,
+        handler: Arc<dyn GossipHandler>,
+    ) -> Result<&mut Self, ExtensionError> {
+        if !valid_name(topic, &['/', '.']) {
+            return Err(ExtensionError::InvalidName(topic.to_string()));
+        }
+        if self.topics.contains_key(topic) {
+            return Err(ExtensionError::Duplicate(format!("gossip topic {topic}")));
+        }
+        self.topics.insert(topic.to_string(), handler);
+        Ok(self)
+    }
+
+    /// Add a block-validity check; it is reported as `<extension>/<name>` on rejection.
+    pub fn block_check(
+        &mut self,
+        name: &str,
+        check: Arc<dyn BlockCheck>,
+    ) -> Result<&mut Self, ExtensionError> {
+        if !valid_name(name, &[]) {
+            return Err(ExtensionError::InvalidName(name.to_string()));
+        }
+        let full = format!("{}/{}", self.current, name);
+        if self.checks.iter().any(|(n, _)| *n == full) {
+            return Err(ExtensionError::Duplicate(format!("block check {full}")));
+        }
+        self.checks.push((full, check));
+        Ok(self)
+    }
+
+    /// Register a Prometheus collector into the node registry.
+    pub fn metric(&mut self, collector: Box<dyn Collector>) -> &mut Self {
+        self.collectors.push((self.current, collector));
+        self
+    }
+
+    pub(crate) fn enter(&mut self, extension: &'static str) {
+        self.current = extension;
+    }
+
+    pub(crate) fn finish(self) -> Extensions {
+        Extensions {
+            rpc: self.rpc.into_iter().collect(),
+            topics: self.topics,
+            checks: self.checks,
+            collectors: std::sync::Mutex::new(self.collectors),
+        }
+    }
+}
+
+/// Frozen set of registered extensions, consumed by the node's subsystems.
+#[derive(Default)]
+pub struct Extensions {
+    rpc: Vec<(String, Router)>,
+    topics: BTreeMap<String, Arc<dyn GossipHandler>>,
+    checks: Vec<(String, Arc<dyn BlockCheck>)>,
+    // Taken once, when the metrics stage starts.
+    collectors: std::sync::Mutex<Vec<(&'static str, Box<dyn Collector>)>>,
+}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Extensions")
+            .field("rpc", &self.rpc.iter().map(|(n, _)| n).collect::<Vec<_>>())
+            .field("topics", &self.topics.keys().collect::<Vec<_>>())
+            .field(
+                "checks",
+                &self.checks.iter().map(|(n, _)| n).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl Extensions {
+    /// RPC namespaces and their routers.
+    pub fn rpc_routes(&self) -> &[(String, Router)] {
+        &self.rpc
+    }
+
+    /// Gossip topics and handlers.
+    pub fn topics(&self) -> &BTreeMap<String, Arc<dyn GossipHandler>> {
+        &self.topics
+    }
+
+    /// Names of registered block checks, in execution order.
+    pub fn block_checks(&self) -> impl Iterator<Item = &str> {
+        self.checks.iter().map(|(n, _)| n.as_str())
+    }
+
+    /// Run all extension block checks; the first rejection wins.
+    pub fn validate_block(&self, block: &BlockContext<'_>) -> Result<(), ExtensionError> {
+        for (name, check) in self.checks.iter() {
+            check
+                .check(block)
+                .map_err(|reason| ExtensionError::BlockRejected {
+                    check: name.clone(),
+                    reason,
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Register extension collectors into the node registry. Subsequent calls are no-ops.
+    pub fn register_metrics(&self, metrics: &Metrics) -> Result<(), ExtensionError> {
+        let collectors = match self.collectors.lock() {
+            Ok(mut g) => std::mem::take(&mut *g),
+            Err(_) => return Ok(()),
+        };
+        for (ext, c) in collectors {
+            metrics
+                .registry
+                .register(c)
+                .map_err(|_| ExtensionError::Metrics(ext))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Build metadata (emitted by `build.rs` through vergen) and node identity for
+//! `GET /system_info`.
+
+use crate::node::role::NodeRole;
+use serde::Serialize;
+use std::time::Instant;
+
+/// Stand-in for metadata vergen could not determine (e.g. building outside a git checkout).
+const UNKNOWN: &str = "unknown";
+
+/// Compile-time build metadata.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_sha: &'static str,
+    pub build_timestamp: &'static str,
+    pub rustc: &'static str,
+    pub target: &'static str,
+}
+
+impl BuildInfo {
+    /// Metadata of this binary.
+    pub const fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            git_sha: or_unknown(option_env!("VERGEN_GIT_SHA")),
+            build_timestamp: or_unknown(option_env!("VERGEN_BUILD_TIMESTAMP")),
+            rustc: or_unknown(option_env!("VERGEN_RUSTC_SEMVER")),
+            target: or_unknown(option_env!("VERGEN_CARGO_TARGET_TRIPLE")),
+        }
+    }
+}
+
+const fn or_unknown(v: Option<&'static str>) -> &'static str {
+    match v {
+        Some(v) => v,
+        None => UNKNOWN,
+    }
+}
+
+/// Identity of a running node.
+#[derive(Clone, Debug)]
+pub struct NodeIdentity {
+    pub chain_id: String,
+    pub peer_id: String,
+    pub role: NodeRole,
+    pub started: Instant,
+}
+
+impl NodeIdentity {
+    pub fn new(chain_id: impl Into<String>, peer_id: impl Into<String>) -> Self {
+        Self {
+            chain_id: chain_id.into(),
+            peer_id: peer_id.into(),
+            role: NodeRole::default(),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn with_role(mut self, role: NodeRole) -> Self {
+        self.role = role;
+        self
+    }
+
+    /// Snapshot for `/system_info`.
+    pub fn system_info(&self, finalized_height: u64) -> SystemInfo {
+        SystemInfo {
+            build: BuildInfo::current(),
+            chain_id: self.chain_id.clone(),
+            peer_id: self.peer_id.clone(),
+            role: self.role,
+            finalized_height,
+            uptime_secs: self.started.elapsed().as_secs(),
+        }
+    }
+}
+
+/// `GET /system_info` response.
+#[derive(Clone, Debug, Serialize)]
+pub struct SystemInfo {
+    pub build: BuildInfo,
+    pub chain_id: String,
+    pub peer_id: String,
+    pub role: NodeRole,
+    pub finalized_height: u64,
+    pub uptime_secs: u64,
+}
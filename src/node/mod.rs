@@ -0,0 +1,45 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+#![allow(missing_docs)]
+
+//! Node process wiring: async runtimes and subsystem lifecycle.
+
+/// Node assembly from built-in subsystems plus downstream extensions.
+pub mod anti_entropy;
+/// Offline backup and restore with a signed manifest.
+pub mod backup;
+pub mod builder;
+/// Named, bounded, metered channels between subsystems.
+pub mod channel;
+/// Command line of the node binary.
+pub mod cli;
+/// Semantic validation of the node config.
+pub mod config_check;
+/// Extension points: RPC namespaces, gossip topics, block checks, metrics.
+pub mod extensions;
+/// Build metadata and node identity (`/system_info`).
+pub mod info;
+/// Signed startup provenance records.
+pub mod provenance;
+/// Config reload (SIGHUP) for runtime-tunable settings.
+pub mod reload;
+/// Node roles: validator, full and observer.
+pub mod role;
+/// Dedicated tokio runtimes for consensus and the HTTP API.
+pub mod runtimes;
+/// Dependency-ordered subsystem startup with teardown on failure.
+pub mod startup;
+/// Signed PeerId to validator bindings.
+pub mod validator_binding;
+/// Byte-compatibility test vectors for third-party implementations.
+pub mod vectors;
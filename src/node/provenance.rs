@@ -0,0 +1,509 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Startup provenance: what binary, config and state a node runs with.
+//!
+//! On startup the node builds a `ProvenanceRecord` (version, git commit, SHA-256 of its own
+//! executable, digest of the network-wide config and the state root it resumes from). A
+//! validator signs it with its consensus key and republishes it on `PROVENANCE_TOPIC` every
+//! `p2p.provenance_interval_secs`; other nodes verify it against the active validator set.
+//! `GET /ext/provenance` shows the local record, every validator's latest one, and which
+//! fields differ from ours, so a tampered binary or a divergent config stands out.
+//!
+//! The config digest covers only what every node of a network must agree on (chain id,
+//! `[consensus]`, the consensus topic and codec), not node names, paths or addresses.
+//!
+//! Signing payload: `domain || canonical(record)`. A record is self-reported: it shows what
+//! the node claims to run, which catches drift and accidents, not a compromised host.
+
+use crate::core::security::keystore::{
+    verify_pubkey_bytes, FileEd25519Backend, Keystore, KeystoreError,
+};
+use crate::core::state::persistent_state::PersistentState;
+use crate::core::types::{
+    decode_canonical_limited, encode_canonical, CodecError, NodeConfig, Signature, ValidatorId,
+};
+use crate::node::channel::Sender;
+use crate::node::extensions::{ExtensionError, ExtensionRegistry, GossipHandler, NodeExtension};
+use crate::node::info::BuildInfo;
+use axum::{routing::get, Json, Router};
+use prometheus::{IntCounter, IntGauge};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use tracing::{debug, info, warn};
+
+/// Gossip topic for provenance records.
+pub const PROVENANCE_TOPIC: &str = "amunchain/provenance/v1";
+
+/// Domain tag of the signing payload.
+pub const PROVENANCE_DOMAIN: &[u8] = b"Amunchain-Provenance-v1";
+
+/// Upper bound for one encoded record.
+const MAX_PROVENANCE_BYTES: usize = 2048;
+
+/// Why a record was refused.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ProvenanceError {
+    #[error("encoding")]
+    Codec,
+    #[error("unsigned record")]
+    Unsigned,
+    #[error("record for chain {0:?}")]
+    WrongChain(String),
+    #[error("not an active validator")]
+    NotValidator,
+    #[error("bad signature")]
+    BadSignature,
+    #[error("older than the recorded one")]
+    Stale,
+}
+
+/// What a node reports about itself; hashes are hex.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvenanceRecord {
+    pub chain_id: String,
+    pub peer_id: String,
+    pub version: String,
+    pub git_sha: String,
+    /// SHA-256 of the running executable ("unknown" if it cannot be read).
+    pub build_hash: String,
+    /// See `config_digest`.
+    pub config_digest: String,
+    /// State root and finalized height the node started from.
+    pub state_root: String,
+    pub height: u64,
+    pub issued_at_ms: u64,
+}
+
+impl ProvenanceRecord {
+    /// Record of this binary with `config_digest`, the resumed state and `peer_id`.
+    pub fn local(
+        chain_id: &str,
+        peer_id: &str,
+        config_digest: String,
+        state_root: [u8; 32],
+        height: u64,
+    ) -> ProvenanceRecord {
+        let build = BuildInfo::current();
+        ProvenanceRecord {
+            chain_id: chain_id.to_string(),
+            peer_id: peer_id.to_string(),
+            version: build.version.to_string(),
+            git_sha: build.git_sha.to_string(),
+            build_hash: executable_hash().unwrap_or_else(|| "unknown".to_string()),
+            config_digest,
+            state_root: hex::encode(state_root),
+            height,
+            issued_at_ms: now_ms(),
+        }
+    }
+
+    /// Names of the fields that identify binary and config and differ from `other`'s.
+    pub fn differences(&self, other: &Self) -> Vec<&'static str> {
+        [
+            ("version", self.version == other.version),
+            ("git_sha", self.git_sha == other.git_sha),
+            ("build_hash", self.build_hash == other.build_hash),
+            ("config_digest", self.config_digest == other.config_digest),
+        ]
+        .into_iter()
+        .filter_map(|(name, same)| (!same).then_some(name))
+        .collect()
+    }
+}
+
+/// A record and, from validators, its signature.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub record: ProvenanceRecord,
+    pub validator: Option<ValidatorId>,
+    pub signature: Option<Signature>,
+}
+
+impl Provenance {
+    /// An unsigned record, for nodes without a validator key.
+    pub fn unsigned(record: ProvenanceRecord) -> Self {
+        Self {
+            record,
+            validator: None,
+            signature: None,
+        }
+    }
+
+    /// Sign `record` as `validator` with `sign` (the validator's keystore).
+    pub fn sign(
+        record: ProvenanceRecord,
+        validator: ValidatorId,
+        sign: impl FnOnce(&[u8]) -> Result<Signature, KeystoreError>,
+    ) -> Result<Self, KeystoreError> {
+        let msg = provenance_signing_bytes(&record).map_err(|_| KeystoreError::Crypto)?;
+        Ok(Self {
+            signature: Some(sign(&msg)?),
+            validator: Some(validator),
+            record,
+        })
+    }
+
+    /// Check the signature and return the signer. Set membership is the book's concern.
+    pub fn verify(&self) -> Result<&ValidatorId, ProvenanceError> {
+        let (Some(validator), Some(signature)) = (&self.validator, &self.signature) else {
+            return Err(ProvenanceError::Unsigned);
+        };
+        let msg = provenance_signing_bytes(&self.record)?;
+        verify_pubkey_bytes(validator.as_bytes(), &msg, signature)
+            .map_err(|_| ProvenanceError::BadSignature)?;
+        Ok(validator)
+    }
+
+    /// Canonical encoding, as published on `PROVENANCE_TOPIC`.
+    pub fn encode(&self) -> Result<Vec<u8>, CodecError> {
+        encode_canonical(self)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, CodecError> {
+        decode_canonical_limited(bytes, MAX_PROVENANCE_BYTES)
+    }
+}
+
+/// Payload a validator signs for `record`; see the module docs.
+pub fn provenance_signing_bytes(record: &ProvenanceRecord) -> Result<Vec<u8>, ProvenanceError> {
+    let body = encode_canonical(record).map_err(|_| ProvenanceError::Codec)?;
+    let mut out = Vec::with_capacity(PROVENANCE_DOMAIN.len() + body.len());
+    out.extend_from_slice(PROVENANCE_DOMAIN);
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+/// Hex SHA-256 of the settings every node of `chain_id` must share.
+pub fn config_digest(config: &NodeConfig, chain_id: &str) -> String {
+    #[derive(Serialize)]
+    struct Shared<'a> {
+        chain_id: &'a str,
+        consensus: &'a crate::core::types::ConsensusConfig,
+        topic: &'a str,
+        codec: crate::networking::wire::WireCodec,
+    }
+    let shared = Shared {
+        chain_id,
+        consensus: &config.consensus,
+        topic: &config.p2p.topic,
+        codec: config.p2p.codec,
+    };
+    // Field order is fixed and there are no maps, so the JSON is canonical.
+    let bytes = serde_json::to_vec(&shared).unwrap_or_default();
+    sha256_hex(&bytes)
+}
+
+/// Hex SHA-256 of the running executable.
+pub fn executable_hash() -> Option<String> {
+    let bytes = std::fs::read(std::env::current_exe().ok()?).ok()?;
+    Some(sha256_hex(&bytes))
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(ring::digest::digest(&ring::digest::SHA256, bytes).as_ref())
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    validators: BTreeSet<ValidatorId>,
+    local: Option<Provenance>,
+    peers: BTreeMap<ValidatorId, Provenance>,
+}
+
+impl Inner {
+    fn mismatched(&self) -> usize {
+        let Some(local) = &self.local else {
+            return 0;
+        };
+        self.peers
+            .values()
+            .filter(|p| !local.record.differences(&p.record).is_empty())
+            .count()
+    }
+}
+
+/// Peer record with the fields that differ from the local one.
+#[derive(Clone, Debug, Serialize)]
+pub struct PeerProvenance {
+    pub validator: String,
+    pub provenance: Provenance,
+    pub differs: Vec<&'static str>,
+}
+
+/// `GET /ext/provenance` response.
+#[derive(Clone, Debug, Serialize)]
+pub struct ProvenanceReport {
+    pub local: Option<Provenance>,
+    pub peers: Vec<PeerProvenance>,
+}
+
+/// Latest verified record per active validator, plus our own. Cheap to clone; clones share
+/// state.
+#[derive(Clone)]
+pub struct ProvenanceBook {
+    chain_id: Arc<str>,
+    inner: Arc<Mutex<Inner>>,
+    accepted: IntCounter,
+    rejected: IntCounter,
+    mismatched: IntGauge,
+}
+
+impl ProvenanceBook {
+    pub fn new(
+        chain_id: &str,
+        validators: BTreeSet<ValidatorId>,
+    ) -> Result<Self, prometheus::Error> {
+        Ok(Self {
+            chain_id: Arc::from(chain_id),
+            inner: Arc::new(Mutex::new(Inner {
+                validators,
+                ..Inner::default()
+            })),
+            accepted: IntCounter::new(
+                "amunchain_provenance_records_accepted_total",
+                "Validator provenance records verified and recorded",
+            )?,
+            rejected: IntCounter::new(
+                "amunchain_provenance_records_rejected_total",
+                "Provenance records refused (unsigned, bad signature, unknown validator, stale)",
+            )?,
+            mismatched: IntGauge::new(
+                "amunchain_provenance_mismatched_validators",
+                "Validators whose version, build or config digest differs from ours",
+            )?,
+        })
+    }
+
+    /// Record this node's own provenance.
+    pub fn set_local(&self, provenance: Provenance) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        inner.local = Some(provenance);
+        self.mismatched.set(inner.mismatched() as i64);
+    }
+
+    pub fn local(&self) -> Option<Provenance> {
+        self.inner.lock().ok()?.local.clone()
+    }
+
+    /// Replace the active validator set; records of removed validators are dropped.
+    pub fn set_validators(&self, validators: BTreeSet<ValidatorId>) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        inner.peers.retain(|v, _| validators.contains(v));
+        inner.validators = validators;
+        self.mismatched.set(inner.mismatched() as i64);
+    }
+
+    /// Verify `provenance` and record it if it is the validator's newest.
+    pub fn observe(&self, provenance: &Provenance) -> Result<(), ProvenanceError> {
+        let res = self.try_observe(provenance);
+        match &res {
+            Ok(()) => self.accepted.inc(),
+            Err(_) => self.rejected.inc(),
+        }
+        res
+    }
+
+    fn try_observe(&self, provenance: &Provenance) -> Result<(), ProvenanceError> {
+        if provenance.record.chain_id != *self.chain_id {
+            return Err(ProvenanceError::WrongChain(
+                provenance.record.chain_id.clone(),
+            ));
+        }
+        let validator = provenance.verify()?;
+        let mut inner = self.inner.lock().map_err(|_| ProvenanceError::Codec)?;
+        if !inner.validators.contains(validator) {
+            return Err(ProvenanceError::NotValidator);
+        }
+        if let Some(known) = inner.peers.get(validator) {
+            if provenance.record.issued_at_ms < known.record.issued_at_ms {
+                return Err(ProvenanceError::Stale);
+            }
+        }
+        if let Some(local) = &inner.local {
+            let differs = local.record.differences(&provenance.record);
+            if !differs.is_empty() {
+                warn!(
+                    validator = %hex::encode(validator.as_bytes()),
+                    peer = %provenance.record.peer_id,
+                    ?differs,
+                    "validator runs a different build or config"
+                );
+            }
+        }
+        inner.peers.insert(validator.clone(), provenance.clone());
+        self.mismatched.set(inner.mismatched() as i64);
+        Ok(())
+    }
+
+    /// Latest record of `validator`.
+    pub fn get(&self, validator: &ValidatorId) -> Option<Provenance> {
+        self.inner.lock().ok()?.peers.get(validator).cloned()
+    }
+
+    /// Local record and every validator's, with the fields that differ from ours.
+    pub fn report(&self) -> ProvenanceReport {
+        let Ok(inner) = self.inner.lock() else {
+            return ProvenanceReport {
+                local: None,
+                peers: Vec::new(),
+            };
+        };
+        let peers = inner
+            .peers
+            .iter()
+            .map(|(v, p)| PeerProvenance {
+                validator: hex::encode(v.as_bytes()),
+                differs: inner
+                    .local
+                    .as_ref()
+                    .map(|l| l.record.differences(&p.record))
+                    .unwrap_or_default(),
+                provenance: p.clone(),
+            })
+            .collect();
+        ProvenanceReport {
+            local: inner.local.clone(),
+            peers,
+        }
+    }
+}
+
+impl GossipHandler for ProvenanceBook {
+    fn on_message(&self, peer: &[u8], data: &[u8]) {
+        let Ok(provenance) = Provenance::decode(data) else {
+            self.rejected.inc();
+            debug!(peer = %hex::encode(peer), "undecodable provenance record");
+            return;
+        };
+        if let Err(e) = self.observe(&provenance) {
+            debug!(peer = %hex::encode(peer), err = %e, "provenance record refused");
+        }
+    }
+}
+
+impl NodeExtension for ProvenanceBook {
+    fn name(&self) -> &'static str {
+        "provenance"
+    }
+
+    fn register(&self, reg: &mut ExtensionRegistry) -> Result<(), ExtensionError> {
+        let book = self.clone();
+        let routes = Router::new().route(
+            "/",
+            get(move || {
+                let book = book.clone();
+                async move { Json(book.report()) }
+            }),
+        );
+        reg.rpc_namespace("provenance", routes)?
+            .gossip_topic(PROVENANCE_TOPIC, Arc::new(self.clone()))?
+            .metric(Box::new(self.accepted.clone()))
+            .metric(Box::new(self.rejected.clone()))
+            .metric(Box::new(self.mismatched.clone()));
+        Ok(())
+    }
+}
+
+/// What the local record is built from.
+pub struct LocalInputs {
+    pub chain_id: String,
+    pub peer_id: String,
+    pub config_digest: String,
+    pub state: PersistentState,
+    pub height: u64,
+}
+
+/// Build the local record once the state root is computed, sign it with `keystore` if given,
+/// record it in `book` and, if signed, publish it every `interval`.
+pub fn spawn_provenance(
+    book: ProvenanceBook,
+    local: LocalInputs,
+    keystore: Option<Keystore<FileEd25519Backend>>,
+    publish: Sender<(String, Vec<u8>)>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let state = local.state;
+        let root = match tokio::task::spawn_blocking(move || state.state_root()).await {
+            Ok(Ok(r)) => r,
+            _ => {
+                warn!("state root unavailable; reporting provenance with a zero root");
+                [0; 32]
+            }
+        };
+        let record = ProvenanceRecord::local(
+            &local.chain_id,
+            &local.peer_id,
+            local.config_digest,
+            root,
+            local.height,
+        );
+        let provenance = match keystore {
+            Some(ks) => {
+                let validator = ValidatorId::from_bytes(ks.public_key());
+                Provenance::sign(record.clone(), validator, |m| ks.sign(m)).unwrap_or_else(|e| {
+                    warn!(err = %e, "cannot sign provenance record; keeping it local");
+                    Provenance::unsigned(record)
+                })
+            }
+            None => Provenance::unsigned(record),
+        };
+        info!(
+            git_sha = %provenance.record.git_sha,
+            build_hash = %provenance.record.build_hash,
+            config_digest = %provenance.record.config_digest,
+            state_root = %provenance.record.state_root,
+            signed = provenance.signature.is_some(),
+            "provenance recorded"
+        );
+        book.set_local(provenance.clone());
+        if provenance.signature.is_none() {
+            // Nothing to publish; stay up like the other subsystem tasks.
+            std::future::pending::<()>().await;
+        }
+        let bytes = match provenance.encode() {
+            Ok(b) => b,
+            Err(e) => {
+                warn!(err = ?e, "cannot encode provenance record");
+                return;
+            }
+        };
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if publish
+                .send((PROVENANCE_TOPIC.to_string(), bytes.clone()))
+                .await
+                .is_err()
+            {
+                info!("p2p gone; stopping provenance announcements");
+                return;
+            }
+        }
+    })
+}
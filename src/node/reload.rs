@@ -0,0 +1,277 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Config reload without a restart.
+//!
+//! `Reloader::reload` re-runs the `ConfigLoader` the node started with, validates the result
+//! and diffs it against the running config:
+//!
+//! - runtime-tunable fields (p2p rate and per-IP limits, the allowlist and peer registry
+//!   policy, `telemetry.log_filter`) are applied in place;
+//! - immutable fields (`node.data_dir`, `node.chain_id`) reject the whole reload;
+//! - anything else is logged as needing a restart and otherwise ignored.
+//!
+//! A rejected reload changes nothing. Every outcome is logged and counted in
+//! `config_reloads_total{result}`.
+
+use crate::config::ConfigLoader;
+use crate::core::types::{NodeConfig, NodeP2pConfig};
+use crate::monitoring::metrics::Metrics;
+use crate::monitoring::telemetry::LogFilterHandle;
+use crate::networking::p2p::P2pTunables;
+use crate::networking::peer_registry::{
+    load_and_verify_peer_registry_bundle_now, load_and_verify_peer_registry_now, PeerRegistryError,
+    PeerRegistryPolicy,
+};
+use crate::node::config_check;
+use crate::node::role::NodeRole;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::watch;
+use toml::Value;
+use tracing::{info, warn};
+
+/// Fields that cannot change while the node runs.
+pub const IMMUTABLE_FIELDS: &[&str] = &["node.data_dir", "node.chain_id"];
+
+/// Fields a reload applies in place.
+pub const TUNABLE_FIELDS: &[&str] = &[
+    "p2p.max_msg_per_sec",
+    "p2p.max_peers_per_ip",
+    "p2p.allow_peers",
+    "p2p.peer_registry_path",
+    "p2p.peer_registry_pubkey_hex",
+    "p2p.peer_registry_min_version",
+    "p2p.peer_registry_max_age_ms",
+    "p2p.peer_registry_grace_ms",
+    "p2p.peer_registry_require_fresh",
+    "telemetry.log_filter",
+];
+
+/// Result of one reload.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReloadOutcome {
+    /// These tunable fields changed and were applied.
+    Applied(Vec<String>),
+    /// No tunable field changed.
+    Unchanged,
+    /// Nothing was applied.
+    Rejected(String),
+}
+
+impl ReloadOutcome {
+    /// `result` label of `config_reloads_total`.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReloadOutcome::Applied(_) => "applied",
+            ReloadOutcome::Unchanged => "unchanged",
+            ReloadOutcome::Rejected(_) => "rejected",
+        }
+    }
+}
+
+/// Re-reads the config and pushes tunable changes to the running subsystems.
+pub struct Reloader {
+    loader: ConfigLoader,
+    current: NodeConfig,
+    metrics: Arc<Metrics>,
+    log_filter: Option<LogFilterHandle>,
+    p2p: Option<watch::Sender<P2pTunables>>,
+}
+
+impl Reloader {
+    /// `current` is the config the node is running with, as produced by `loader`.
+    pub fn new(loader: ConfigLoader, current: NodeConfig, metrics: Arc<Metrics>) -> Self {
+        Self {
+            loader,
+            current,
+            metrics,
+            log_filter: None,
+            p2p: None,
+        }
+    }
+
+    /// Apply `telemetry.log_filter` changes to this handle.
+    pub fn with_log_filter(mut self, handle: LogFilterHandle) -> Self {
+        self.log_filter = Some(handle);
+        self
+    }
+
+    /// Send p2p limit and allowlist changes here (see `P2pNode::tunables`).
+    pub fn with_p2p(mut self, tunables: watch::Sender<P2pTunables>) -> Self {
+        self.p2p = Some(tunables);
+        self
+    }
+
+    /// The config in effect, including applied reloads.
+    pub fn current(&self) -> &NodeConfig {
+        &self.current
+    }
+
+    /// Reload once; logs the outcome and counts it.
+    pub fn reload(&mut self) -> ReloadOutcome {
+        let outcome = self.try_reload();
+        match &outcome {
+            ReloadOutcome::Applied(fields) => info!(?fields, "config reloaded"),
+            ReloadOutcome::Unchanged => info!("config reloaded; nothing to apply"),
+            ReloadOutcome::Rejected(reason) => {
+                warn!(%reason, "config reload rejected; running config unchanged")
+            }
+        }
+        self.metrics
+            .config_reloads_total
+            .with_label_values(&[outcome.label()])
+            .inc();
+        outcome
+    }
+
+    fn try_reload(&mut self) -> ReloadOutcome {
+        let next = match self.loader.load() {
+            Ok(c) => c,
+            Err(e) => return ReloadOutcome::Rejected(e.to_string()),
+        };
+        let issues = config_check::check(&next);
+        if !issues.is_empty() {
+            let issues: Vec<String> = issues.iter().map(ToString::to_string).collect();
+            return ReloadOutcome::Rejected(issues.join("; "));
+        }
+
+        let changed = changed_fields(&self.current, &next);
+        if let Some(field) = changed
+            .iter()
+            .find(|f| IMMUTABLE_FIELDS.contains(&f.as_str()))
+        {
+            return ReloadOutcome::Rejected(format!(
+                "{field} cannot change while the node runs; restart to apply"
+            ));
+        }
+        let (tunable, restart): (Vec<String>, Vec<String>) = changed
+            .into_iter()
+            .partition(|f| TUNABLE_FIELDS.contains(&f.as_str()));
+        if !restart.is_empty() {
+            warn!(fields = ?restart, "config changes take effect after a restart");
+        }
+        if tunable.is_empty() {
+            return ReloadOutcome::Unchanged;
+        }
+
+        // Resolve everything fallible before touching a subsystem.
+        let p2p_changed = tunable.iter().any(|f| f.starts_with("p2p."));
+        let allow_peers = if p2p_changed && self.p2p.is_some() {
+            // The role only changes with a restart: resolve for the running one.
+            match resolve_allowlist(&next.p2p, self.current.node.role) {
+                Ok(peers) => Some(peers),
+                Err(e) => return ReloadOutcome::Rejected(format!("p2p.peer_registry_path: {e}")),
+            }
+        } else {
+            None
+        };
+        let old_filter = self.current.telemetry.log_filter.clone();
+        if next.telemetry.log_filter != old_filter {
+            if let Some(handle) = &self.log_filter {
+                if let Err(e) = handle.set(&next.telemetry.log_filter) {
+                    return ReloadOutcome::Rejected(format!("telemetry.log_filter: {e}"));
+                }
+            }
+        }
+        if let (Some(tx), Some(allow_peers)) = (&self.p2p, allow_peers) {
+            tx.send_replace(P2pTunables {
+                max_msg_per_sec: next.p2p.max_msg_per_sec,
+                max_peers_per_ip: next.p2p.max_peers_per_ip,
+                allow_peers,
+            });
+        }
+        self.current = next;
+        ReloadOutcome::Applied(tunable)
+    }
+}
+
+/// Allowlist of a node whose role needs one (see `NodeRole::requires_allowlist`) when its
+/// config names neither `allow_peers` nor a peer registry: the four-node testnet.
+pub const TESTNET_PEERS: &[&str] = &[
+    "12D3KooWLh9S2QyVMgQgHmuzu2tCA6KsL9mpNgnvoiA1SQ9nHnMA",
+    "12D3KooWLCZAkdjaD7FAT65FvUhwHVC5RBvYRTDZ6QXCbks7ZTaJ",
+    "12D3KooWS9xDuptBksMQs7hAvKAJQhW5G9wYYVg7yemgGSZkQxWX",
+    "12D3KooWEdXmay5QGhLnJnuDD9Wt2M3v2ADEjmEHFsN33XkTaTN4",
+];
+
+/// The allowlist `p2p` describes for a node running as `role`: `allow_peers` when set, else
+/// the verified peer registry, else `TESTNET_PEERS`, or empty (allow all) for observers.
+/// Startup and reload both resolve it here, so a reload cannot open the node up further
+/// than the same config would at startup.
+pub fn resolve_allowlist(
+    p2p: &NodeP2pConfig,
+    role: NodeRole,
+) -> Result<Vec<String>, PeerRegistryError> {
+    if !p2p.allow_peers.is_empty() {
+        return Ok(p2p.allow_peers.clone());
+    }
+    let Some(path) = p2p.peer_registry_path.as_deref() else {
+        // Observers need no allowlist; they take whichever peers will have them.
+        if !role.requires_allowlist() {
+            return Ok(Vec::new());
+        }
+        return Ok(TESTNET_PEERS.iter().map(ToString::to_string).collect());
+    };
+    let pubkey = p2p
+        .peer_registry_pubkey_hex
+        .as_deref()
+        .ok_or(PeerRegistryError::MissingField)?;
+    let policy = PeerRegistryPolicy {
+        max_age_ms: p2p.peer_registry_max_age_ms,
+        grace_ms: p2p.peer_registry_grace_ms,
+        min_version: p2p.peer_registry_min_version,
+        expected_network: Some(&p2p.topic),
+        require_freshness_fields: p2p.peer_registry_require_fresh || cfg!(feature = "production"),
+        ..PeerRegistryPolicy::default_with_now(0)
+    };
+    // A file that does not parse as a single registry may be a multi-network bundle.
+    match load_and_verify_peer_registry_now(path, pubkey, &policy) {
+        Err(PeerRegistryError::Parse) => {
+            load_and_verify_peer_registry_bundle_now(path, pubkey, &policy)
+        }
+        other => other,
+    }
+}
+
+/// Dotted paths (`section.key`) whose values differ between `a` and `b`.
+pub fn changed_fields(a: &NodeConfig, b: &NodeConfig) -> Vec<String> {
+    let (a, b) = (flatten(a), flatten(b));
+    let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|k| a.get(*k) != b.get(*k))
+        .cloned()
+        .collect()
+}
+
+fn flatten(cfg: &NodeConfig) -> BTreeMap<String, Value> {
+    let mut out = BTreeMap::new();
+    let Ok(Value::Table(root)) = Value::try_from(cfg) else {
+        return out;
+    };
+    for (section, v) in root {
+        match v {
+            Value::Table(t) => {
+                for (k, v) in t {
+                    out.insert(format!("{section}.{k}"), v);
+                }
+            }
+            v => {
+                out.insert(section, v);
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,117 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! What a node does in the network (`[node] role`).
+//!
+//! - `validator` (default): signs and casts votes with `validator.key`, announces its peer
+//!   binding and signed provenance.
+//! - `full`: verifies and stores everything, relays gossip and serves vote requests and head
+//!   announcements, but never signs.
+//! - `observer`: for RPC and indexing. Subscribes read-only (publishes nothing, relaying
+//!   aside), never loads a validator key, and may run without an allowlist even where
+//!   `require_allow_peers` is set.
+
+use crate::core::types::NodeConfig;
+use crate::networking::p2p_identity::IdentitySource;
+use crate::node::cli::VALIDATOR_KEY_FILE;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+
+/// Node role.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeRole {
+    #[default]
+    Validator,
+    Full,
+    Observer,
+}
+
+impl NodeRole {
+    /// Config value and metric label.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NodeRole::Validator => "validator",
+            NodeRole::Full => "full",
+            NodeRole::Observer => "observer",
+        }
+    }
+
+    /// Whether the node casts its own votes.
+    pub fn votes(self) -> bool {
+        self == NodeRole::Validator
+    }
+
+    /// Whether the node may open `validator.key`, for signing or as its p2p identity.
+    pub fn uses_validator_key(self) -> bool {
+        self == NodeRole::Validator
+    }
+
+    /// Whether the node publishes gossip of its own.
+    pub fn publishes(self) -> bool {
+        self != NodeRole::Observer
+    }
+
+    /// Whether `p2p.require_allow_peers` applies.
+    pub fn requires_allowlist(self) -> bool {
+        self != NodeRole::Observer
+    }
+}
+
+/// A role the rest of the config contradicts.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum RoleError {
+    #[error("\"validator\" loads the validator key, which a {0} node never does")]
+    ValidatorIdentity(&'static str),
+}
+
+impl RoleError {
+    /// The config key at fault.
+    pub fn field(&self) -> &'static str {
+        match self {
+            RoleError::ValidatorIdentity(_) => "p2p.identity",
+        }
+    }
+}
+
+/// Settings that contradict `config.node.role`.
+pub fn validate(config: &NodeConfig) -> Result<(), RoleError> {
+    let role = config.node.role;
+    if !role.uses_validator_key() && config.p2p.identity == IdentitySource::Validator {
+        return Err(RoleError::ValidatorIdentity(role.as_str()));
+    }
+    Ok(())
+}
+
+/// Startup checks for `config.node.role`: `validate`, plus warnings about the validator key
+/// in `data_dir`.
+pub fn check_role(config: &NodeConfig, data_dir: &Path) -> Result<Vec<String>, RoleError> {
+    validate(config)?;
+    let role = config.node.role;
+    let has_key = data_dir.join(VALIDATOR_KEY_FILE).exists();
+    let mut warnings = Vec::new();
+    match role {
+        NodeRole::Validator if !has_key => warnings.push(format!(
+            "no {VALIDATOR_KEY_FILE} in {}; this validator cannot sign",
+            data_dir.display()
+        )),
+        NodeRole::Full | NodeRole::Observer if has_key => warnings.push(format!(
+            "{VALIDATOR_KEY_FILE} in {} is not loaded by a {} node",
+            data_dir.display(),
+            role.as_str()
+        )),
+        _ => {}
+    }
+    Ok(warnings)
+}
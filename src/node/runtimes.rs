@@ -0,0 +1,108 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Runtime separation.
+//!
+//! Consensus and P2P share the `consensus` runtime; the HTTP API gets its own, smaller
+//! `rpc` runtime. A request flood can then only saturate RPC workers, never the threads
+//! driving gossip and vote processing.
+
+use crate::core::types::RuntimeSettings;
+use crate::monitoring::metrics::Metrics;
+use std::{sync::Arc, time::Duration};
+use thiserror::Error;
+use tokio::runtime::{Builder, Handle, Runtime};
+use tokio::task::JoinHandle;
+
+/// Runtime errors.
+#[derive(Debug, Error)]
+pub enum RuntimeError {
+    #[error("runtime build")]
+    Build,
+}
+
+/// Interval between runtime metric samples.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The node's runtimes.
+pub struct NodeRuntimes {
+    /// Consensus + P2P runtime.
+    pub consensus: Runtime,
+    /// HTTP API runtime.
+    pub rpc: Runtime,
+}
+
+impl NodeRuntimes {
+    /// Build both runtimes from settings.
+    pub fn build(settings: &RuntimeSettings) -> Result<Self, RuntimeError> {
+        let mut consensus = Builder::new_multi_thread();
+        if settings.consensus_worker_threads > 0 {
+            consensus.worker_threads(settings.consensus_worker_threads);
+        }
+        let consensus = consensus
+            .thread_name("amun-consensus")
+            .enable_all()
+            .build()
+            .map_err(|_| RuntimeError::Build)?;
+
+        let rpc = Builder::new_multi_thread()
+            .worker_threads(settings.rpc_worker_threads.max(1))
+            .thread_name("amun-rpc")
+            .enable_all()
+            .build()
+            .map_err(|_| RuntimeError::Build)?;
+
+        Ok(Self { consensus, rpc })
+    }
+
+    /// Named handles of both runtimes, for metric sampling.
+    pub fn handles(&self) -> Vec<(&'static str, Handle)> {
+        vec![
+            ("consensus", self.consensus.handle().clone()),
+            ("rpc", self.rpc.handle().clone()),
+        ]
+    }
+}
+
+/// Periodically export queue depth and task counts for the given runtimes.
+/// The sampler is spawned on the current runtime.
+pub fn spawn_metrics_sampler(
+    runtimes: Vec<(&'static str, Handle)>,
+    metrics: Arc<Metrics>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(SAMPLE_INTERVAL);
+        loop {
+            tick.tick().await;
+            for (name, handle) in runtimes.iter() {
+                sample(&metrics, name, handle);
+            }
+        }
+    })
+}
+
+fn sample(metrics: &Metrics, name: &str, handle: &Handle) {
+    let m = handle.metrics();
+    metrics
+        .runtime_workers
+        .with_label_values(&[name])
+        .set(m.num_workers() as i64);
+    metrics
+        .runtime_alive_tasks
+        .with_label_values(&[name])
+        .set(m.num_alive_tasks() as i64);
+    metrics
+        .runtime_global_queue_depth
+        .with_label_values(&[name])
+        .set(m.global_queue_depth() as i64);
+}
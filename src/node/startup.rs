@@ -0,0 +1,388 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Startup orchestration.
+//!
+//! Subsystems register as named stages with explicit dependencies. The orchestrator starts
+//! them in dependency order (ties broken by registration order, so startup is reproducible),
+//! and if any stage fails it tears down every stage already running, in reverse order, before
+//! reporting which stage failed. Stages hand values to their dependents through `Resources`.
+//!
+//! A running node either stops everything at once (`run_until_exit`, `shutdown`) or is shut
+//! down step by step: `wait` for a signal, then `stop_stage` in whatever order the subsystems
+//! need, then `shutdown` for the rest.
+
+use crate::errors::{Classify, ExitCode};
+use std::any::{Any, TypeId};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// Type-keyed bag of values produced by stages (metrics, channels, handles).
+///
+/// Resources live as long as the running node, so values that must stay alive for their
+/// subsystem to keep running (e.g. the P2P channel handle) can simply be inserted here.
+#[derive(Default)]
+pub struct Resources {
+    items: HashMap<TypeId, Box<dyn Any + Send>>,
+}
+
+impl Resources {
+    /// Insert a value, replacing any previous value of the same type.
+    pub fn insert<T: Any + Send>(&mut self, v: T) {
+        self.items.insert(TypeId::of::<T>(), Box::new(v));
+    }
+
+    /// Borrow a value.
+    pub fn get<T: Any + Send>(&self) -> Option<&T> {
+        self.items
+            .get(&TypeId::of::<T>())
+            .and_then(|b| b.downcast_ref::<T>())
+    }
+
+    /// Mutably borrow a value.
+    pub fn get_mut<T: Any + Send>(&mut self) -> Option<&mut T> {
+        self.items
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|b| b.downcast_mut::<T>())
+    }
+
+    /// Remove and return a value.
+    pub fn take<T: Any + Send>(&mut self) -> Option<T> {
+        self.items
+            .remove(&TypeId::of::<T>())
+            .and_then(|b| b.downcast::<T>().ok())
+            .map(|b| *b)
+    }
+}
+
+/// Tasks owned by a started stage.
+#[derive(Default)]
+pub struct StageHandle {
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl StageHandle {
+    /// Handle with no background tasks.
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Track a background task; it is aborted on teardown.
+    pub fn with_task(mut self, task: JoinHandle<()>) -> Self {
+        self.tasks.push(task);
+        self
+    }
+
+    fn abort(self) {
+        for t in self.tasks.into_iter().rev() {
+            t.abort();
+        }
+    }
+}
+
+/// Stage start failure (returned by a stage's start function).
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub struct StageFailure {
+    pub message: String,
+    /// Overrides the stage's exit code when the cause is known.
+    pub exit_code: Option<ExitCode>,
+}
+
+impl StageFailure {
+    /// Build from any displayable error; the stage's exit code applies.
+    pub fn msg(e: impl std::fmt::Display) -> Self {
+        Self {
+            message: e.to_string(),
+            exit_code: None,
+        }
+    }
+
+    /// Build from a classified error; its failure class becomes the exit code.
+    pub fn classified<E: Classify + std::fmt::Display>(e: E) -> Self {
+        Self {
+            message: e.to_string(),
+            exit_code: Some(e.exit_code()),
+        }
+    }
+}
+
+/// Startup error, always naming the stage at fault.
+#[derive(Debug, Error)]
+pub enum StartupError {
+    #[error("stage `{stage}` depends on unknown stage `{dependency}`")]
+    UnknownDependency {
+        stage: &'static str,
+        dependency: &'static str,
+    },
+    #[error("dependency cycle involving stage `{stage}`")]
+    Cycle { stage: &'static str },
+    #[error("stage `{stage}` failed: {source}")]
+    Failed {
+        stage: &'static str,
+        exit_code: ExitCode,
+        source: StageFailure,
+    },
+}
+
+impl StartupError {
+    /// Name of the failing stage.
+    pub fn stage(&self) -> &'static str {
+        match self {
+            StartupError::UnknownDependency { stage, .. } => stage,
+            StartupError::Cycle { stage } => stage,
+            StartupError::Failed { stage, .. } => stage,
+        }
+    }
+
+    /// Process exit code for this failure.
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            StartupError::Failed { exit_code, .. } => *exit_code,
+            // Graph errors are programming mistakes.
+            _ => ExitCode::Internal,
+        }
+    }
+}
+
+type StartFn = Box<dyn FnOnce(&mut Resources) -> Result<StageHandle, StageFailure> + Send>;
+
+struct Stage {
+    name: &'static str,
+    deps: Vec<&'static str>,
+    exit_code: ExitCode,
+    start: StartFn,
+}
+
+/// Dependency-ordered startup orchestrator.
+#[derive(Default)]
+pub struct StartupOrchestrator {
+    stages: Vec<Stage>,
+}
+
+impl StartupOrchestrator {
+    /// Empty orchestrator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a stage. `exit_code` is used if this stage fails to start with an
+    /// unclassified failure.
+    ///
+    /// `start` runs inside the caller's tokio runtime, so it may spawn tasks.
+    pub fn stage<F>(
+        mut self,
+        name: &'static str,
+        deps: &[&'static str],
+        exit_code: ExitCode,
+        start: F,
+    ) -> Self
+    where
+        F: FnOnce(&mut Resources) -> Result<StageHandle, StageFailure> + Send + 'static,
+    {
+        self.stages.push(Stage {
+            name,
+            deps: deps.to_vec(),
+            exit_code,
+            start: Box::new(start),
+        });
+        self
+    }
+
+    /// Start order (Kahn's algorithm, registration order breaks ties).
+    pub fn plan(&self) -> Result<Vec<&'static str>, StartupError> {
+        self.order()
+            .map(|o| o.into_iter().map(|i| self.stages[i].name).collect())
+    }
+
+    fn order(&self) -> Result<Vec<usize>, StartupError> {
+        let index: BTreeMap<&'static str, usize> = self
+            .stages
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (s.name, i))
+            .collect();
+
+        let mut indegree = vec![0usize; self.stages.len()];
+        let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); self.stages.len()];
+        for (i, s) in self.stages.iter().enumerate() {
+            for d in s.deps.iter() {
+                let Some(&j) = index.get(d) else {
+                    return Err(StartupError::UnknownDependency {
+                        stage: s.name,
+                        dependency: d,
+                    });
+                };
+                indegree[i] += 1;
+                dependents[j].push(i);
+            }
+        }
+
+        let mut order = Vec::with_capacity(self.stages.len());
+        let mut done = vec![false; self.stages.len()];
+        while order.len() < self.stages.len() {
+            let Some(next) = (0..self.stages.len()).find(|&i| !done[i] && indegree[i] == 0) else {
+                let stuck = (0..self.stages.len()).find(|&i| !done[i]).unwrap_or(0);
+                return Err(StartupError::Cycle {
+                    stage: self.stages[stuck].name,
+                });
+            };
+            done[next] = true;
+            order.push(next);
+            for &d in dependents[next].iter() {
+                indegree[d] -= 1;
+            }
+        }
+        Ok(order)
+    }
+
+    /// Start all stages. On failure every started stage is torn down (reverse order).
+    pub fn start(self) -> Result<RunningNode, StartupError> {
+        let order = self.order()?;
+        let mut slots: Vec<Option<Stage>> = self.stages.into_iter().map(Some).collect();
+        let mut resources = Resources::default();
+        let mut running: Vec<(&'static str, StageHandle)> = Vec::with_capacity(order.len());
+
+        for i in order {
+            let Some(stage) = slots[i].take() else {
+                continue;
+            };
+            info!(stage = stage.name, "starting stage");
+            match (stage.start)(&mut resources) {
+                Ok(h) => running.push((stage.name, h)),
+                Err(source) => {
+                    error!(stage = stage.name, err = %source, "stage failed; tearing down");
+                    teardown(running);
+                    return Err(StartupError::Failed {
+                        stage: stage.name,
+                        exit_code: source.exit_code.unwrap_or(stage.exit_code),
+                        source,
+                    });
+                }
+            }
+        }
+
+        Ok(RunningNode { running, resources })
+    }
+}
+
+fn teardown(running: Vec<(&'static str, StageHandle)>) {
+    for (name, h) in running.into_iter().rev() {
+        info!(stage = name, "stopping stage");
+        h.abort();
+    }
+}
+
+/// Why `RunningNode::wait` returned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stop {
+    /// The shutdown signal completed.
+    Signal,
+    /// A task of this stage exited.
+    TaskExited(&'static str),
+}
+
+/// All stages started successfully.
+pub struct RunningNode {
+    running: Vec<(&'static str, StageHandle)>,
+    resources: Resources,
+}
+
+impl RunningNode {
+    /// Shared resources produced during startup.
+    pub fn resources(&mut self) -> &mut Resources {
+        &mut self.resources
+    }
+
+    /// Wait until any stage task exits, then tear everything down.
+    /// Returns the name of the stage whose task exited first.
+    pub async fn run_until_exit(mut self) -> Option<&'static str> {
+        let mut owners = Vec::new();
+        let mut tasks = Vec::new();
+        for (name, h) in self.running.iter_mut() {
+            for t in h.tasks.drain(..) {
+                owners.push(*name);
+                tasks.push(t);
+            }
+        }
+        if tasks.is_empty() {
+            return None;
+        }
+        let (_res, idx, rest) = futures::future::select_all(tasks).await;
+        let stage = owners[idx];
+        warn!(stage, "stage task exited; shutting down");
+        for t in rest.into_iter().rev() {
+            t.abort();
+        }
+        teardown(self.running);
+        Some(stage)
+    }
+
+    /// Wait until `signal` completes or a stage task exits. Nothing is torn down.
+    pub async fn wait<F: Future<Output = ()>>(&mut self, signal: F) -> Stop {
+        let tasks: Vec<_> = self
+            .running
+            .iter_mut()
+            .flat_map(|(name, h)| h.tasks.iter_mut().map(move |t| (*name, t)))
+            .filter(|(_, t)| !t.is_finished())
+            .map(|(name, t)| {
+                Box::pin(async move {
+                    let _ = t.await;
+                    name
+                })
+            })
+            .collect();
+        if tasks.is_empty() {
+            signal.await;
+            return Stop::Signal;
+        }
+        tokio::select! {
+            _ = signal => Stop::Signal,
+            (stage, _, _) = futures::future::select_all(tasks) => {
+                warn!(stage, "stage task exited");
+                Stop::TaskExited(stage)
+            }
+        }
+    }
+
+    /// Give the tasks of `stage` up to `timeout` to end on their own, abort the rest and drop
+    /// the stage. Returns false if a task had to be aborted.
+    pub async fn stop_stage(&mut self, stage: &str, timeout: Duration) -> bool {
+        let Some(pos) = self.running.iter().position(|(n, _)| *n == stage) else {
+            return true;
+        };
+        let (name, h) = self.running.remove(pos);
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut clean = true;
+        for mut t in h.tasks {
+            if t.is_finished() {
+                continue;
+            }
+            if tokio::time::timeout_at(deadline, &mut t).await.is_err() {
+                t.abort();
+                clean = false;
+            }
+        }
+        info!(stage = name, clean, "stage stopped");
+        clean
+    }
+
+    /// Tear down all stages in reverse start order.
+    pub fn shutdown(self) {
+        teardown(self.running);
+    }
+}
@@ -0,0 +1,349 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Validator identity binding: which gossip peer speaks for which validator.
+//!
+//! A validator signs, with its consensus key, the PeerId its node gossips under and the
+//! validator-set epoch, and publishes the result on `BINDING_TOPIC` every
+//! `p2p.binding_interval_secs`. Receivers verify the signature against the active validator
+//! set and keep the newest binding per validator in a `BindingRegistry`, which peer scoring,
+//! liveness tracking and penalties use to attribute a peer to a validator.
+//!
+//! Signing payload: `domain || len(chain_id) u32 || chain_id || len(peer_id) u32 || peer_id ||
+//! epoch u64 || validator`, integers big-endian. A binding for an epoch more than
+//! `max_epoch_age` below the registry's current epoch is stale. Epoch 0 is the legacy epoch
+//! of nodes that do not track validator-set epochs; such bindings do not expire.
+//!
+//! A binding proves the validator authorized the PeerId, not that the sender is that peer;
+//! anyone may relay it.
+
+use crate::core::security::keystore::{verify_pubkey_bytes, KeystoreError};
+use crate::core::types::{
+    decode_canonical_limited, encode_canonical, CodecError, Epoch, Signature, ValidatorId,
+};
+use crate::networking::p2p_identity::ValidatorPeers;
+use crate::node::channel::Sender;
+use crate::node::extensions::{ExtensionError, ExtensionRegistry, GossipHandler, NodeExtension};
+use libp2p::PeerId;
+use prometheus::{IntCounter, IntGauge};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use thiserror::Error;
+use tracing::{debug, info, warn};
+
+/// Gossip topic for bindings.
+pub const BINDING_TOPIC: &str = "amunchain/binding/v1";
+
+/// Domain tag of the signing payload.
+pub const BINDING_DOMAIN: &[u8] = b"Amunchain-PeerBinding-v1";
+
+/// Upper bound for one encoded binding.
+const MAX_BINDING_BYTES: usize = 512;
+
+/// Why a binding was refused.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BindingError {
+    #[error("codec")]
+    Codec,
+    #[error("invalid peer id")]
+    PeerId,
+    #[error("not an active validator")]
+    NotValidator,
+    #[error("bad signature")]
+    BadSignature,
+    #[error("stale epoch {0}")]
+    Stale(u64),
+}
+
+/// `validator` authorizes `peer_id` (PeerId bytes) to gossip for it during `epoch`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerBinding {
+    pub validator: ValidatorId,
+    pub peer_id: Vec<u8>,
+    pub epoch: Epoch,
+    pub signature: Signature,
+}
+
+/// Payload a validator signs to bind `peer_id`; see the module docs.
+pub fn binding_signing_bytes(
+    chain_id: &str,
+    validator: &ValidatorId,
+    peer_id: &[u8],
+    epoch: Epoch,
+) -> Result<Vec<u8>, BindingError> {
+    let chain = chain_id.as_bytes();
+    let chain_len = u32::try_from(chain.len()).map_err(|_| BindingError::Codec)?;
+    let peer_len = u32::try_from(peer_id.len()).map_err(|_| BindingError::Codec)?;
+    let vb = encode_canonical(validator).map_err(|_| BindingError::Codec)?;
+    let mut out =
+        Vec::with_capacity(BINDING_DOMAIN.len() + 8 + chain.len() + peer_id.len() + 8 + vb.len());
+    out.extend_from_slice(BINDING_DOMAIN);
+    out.extend_from_slice(&chain_len.to_be_bytes());
+    out.extend_from_slice(chain);
+    out.extend_from_slice(&peer_len.to_be_bytes());
+    out.extend_from_slice(peer_id);
+    out.extend_from_slice(&epoch.to_be_bytes());
+    out.extend_from_slice(&vb);
+    Ok(out)
+}
+
+impl PeerBinding {
+    /// Bind `peer` to `validator`, signing with `sign` (the validator's keystore).
+    pub fn sign(
+        chain_id: &str,
+        validator: ValidatorId,
+        peer: &PeerId,
+        epoch: Epoch,
+        sign: impl FnOnce(&[u8]) -> Result<Signature, KeystoreError>,
+    ) -> Result<Self, KeystoreError> {
+        let peer_id = peer.to_bytes();
+        let msg = binding_signing_bytes(chain_id, &validator, &peer_id, epoch)
+            .map_err(|_| KeystoreError::Crypto)?;
+        Ok(Self {
+            signature: sign(&msg)?,
+            validator,
+            peer_id,
+            epoch,
+        })
+    }
+
+    /// Check the signature and return the bound peer. Set membership and epoch are the
+    /// registry's concern.
+    pub fn verify(&self, chain_id: &str) -> Result<PeerId, BindingError> {
+        let peer = PeerId::from_bytes(&self.peer_id).map_err(|_| BindingError::PeerId)?;
+        let msg = binding_signing_bytes(chain_id, &self.validator, &self.peer_id, self.epoch)?;
+        verify_pubkey_bytes(self.validator.as_bytes(), &msg, &self.signature)
+            .map_err(|_| BindingError::BadSignature)?;
+        Ok(peer)
+    }
+
+    /// Canonical encoding, as published on `BINDING_TOPIC`.
+    pub fn encode(&self) -> Result<Vec<u8>, CodecError> {
+        encode_canonical(self)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, CodecError> {
+        decode_canonical_limited(bytes, MAX_BINDING_BYTES)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    validators: BTreeSet<ValidatorId>,
+    current_epoch: Epoch,
+    peers: ValidatorPeers,
+    epochs: HashMap<ValidatorId, Epoch>,
+}
+
+impl Inner {
+    /// Forget the bindings of validators matching `drop(validator, epoch)`.
+    fn drop_where(&mut self, drop: impl Fn(&ValidatorId, Epoch) -> bool) {
+        let gone: Vec<ValidatorId> = self
+            .epochs
+            .iter()
+            .filter(|(v, e)| drop(v, **e))
+            .map(|(v, _)| v.clone())
+            .collect();
+        for v in gone {
+            self.epochs.remove(&v);
+            self.peers.remove(&v);
+        }
+    }
+}
+
+/// Verified bindings of the active validator set. Cheap to clone; clones share state.
+#[derive(Clone)]
+pub struct BindingRegistry {
+    chain_id: Arc<str>,
+    max_epoch_age: u64,
+    inner: Arc<Mutex<Inner>>,
+    accepted: IntCounter,
+    rejected: IntCounter,
+    bound: IntGauge,
+}
+
+impl std::fmt::Debug for BindingRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BindingRegistry")
+            .field("chain_id", &self.chain_id)
+            .field("max_epoch_age", &self.max_epoch_age)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BindingRegistry {
+    pub fn new(
+        chain_id: &str,
+        validators: BTreeSet<ValidatorId>,
+        max_epoch_age: u64,
+    ) -> Result<Self, prometheus::Error> {
+        Ok(Self {
+            chain_id: Arc::from(chain_id),
+            max_epoch_age,
+            inner: Arc::new(Mutex::new(Inner {
+                validators,
+                ..Inner::default()
+            })),
+            accepted: IntCounter::new(
+                "amunchain_peer_bindings_accepted_total",
+                "Validator peer bindings verified and recorded",
+            )?,
+            rejected: IntCounter::new(
+                "amunchain_peer_bindings_rejected_total",
+                "Validator peer bindings refused (bad signature, unknown validator, stale)",
+            )?,
+            bound: IntGauge::new(
+                "amunchain_peer_bindings_validators",
+                "Active validators with a known peer",
+            )?,
+        })
+    }
+
+    /// Replace the active validator set; bindings of removed validators are dropped.
+    pub fn set_validators(&self, validators: BTreeSet<ValidatorId>) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        inner.drop_where(|v, _| !validators.contains(v));
+        inner.validators = validators;
+        self.bound.set(inner.peers.len() as i64);
+    }
+
+    /// Advance the current epoch; bindings older than `max_epoch_age` epochs are dropped.
+    pub fn set_epoch(&self, epoch: Epoch) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        inner.current_epoch = epoch;
+        inner.drop_where(|_, e| self.is_stale(e, epoch));
+        self.bound.set(inner.peers.len() as i64);
+    }
+
+    fn is_stale(&self, epoch: Epoch, current: Epoch) -> bool {
+        !epoch.is_zero() && epoch.get().saturating_add(self.max_epoch_age) < current.get()
+    }
+
+    /// Verify `binding` and record it if it is the validator's newest.
+    pub fn observe(&self, binding: &PeerBinding) -> Result<PeerId, BindingError> {
+        let res = self.try_observe(binding);
+        match &res {
+            Ok(_) => self.accepted.inc(),
+            Err(_) => self.rejected.inc(),
+        }
+        res
+    }
+
+    fn try_observe(&self, binding: &PeerBinding) -> Result<PeerId, BindingError> {
+        let mut inner = self.inner.lock().map_err(|_| BindingError::Codec)?;
+        if !inner.validators.contains(&binding.validator) {
+            return Err(BindingError::NotValidator);
+        }
+        let peer = binding.verify(&self.chain_id)?;
+        let newest = inner.epochs.get(&binding.validator).copied();
+        if self.is_stale(binding.epoch, inner.current_epoch)
+            || newest.is_some_and(|e| binding.epoch < e)
+        {
+            return Err(BindingError::Stale(binding.epoch.get()));
+        }
+        if inner.peers.peer(&binding.validator) != Some(&peer) {
+            info!(validator = %hex::encode(binding.validator.as_bytes()), %peer, "validator bound to peer");
+        }
+        inner.peers.insert(peer, binding.validator.clone());
+        inner
+            .epochs
+            .insert(binding.validator.clone(), binding.epoch);
+        self.bound.set(inner.peers.len() as i64);
+        Ok(peer)
+    }
+
+    /// Validator `peer` speaks for, if bound.
+    pub fn validator_of(&self, peer: &PeerId) -> Option<ValidatorId> {
+        self.inner.lock().ok()?.peers.validator(peer).cloned()
+    }
+
+    /// Like `validator_of`, for peer id bytes as gossip handlers and peer scoring see them.
+    pub fn validator_of_bytes(&self, peer: &[u8]) -> Option<ValidatorId> {
+        self.validator_of(&PeerId::from_bytes(peer).ok()?)
+    }
+
+    pub fn peer_of(&self, validator: &ValidatorId) -> Option<PeerId> {
+        self.inner.lock().ok()?.peers.peer(validator).copied()
+    }
+
+    /// Number of validators with a bound peer.
+    pub fn len(&self) -> usize {
+        self.inner.lock().map_or(0, |i| i.peers.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl GossipHandler for BindingRegistry {
+    fn on_message(&self, peer: &[u8], data: &[u8]) {
+        let Ok(binding) = PeerBinding::decode(data) else {
+            self.rejected.inc();
+            debug!(peer = %hex::encode(peer), "undecodable peer binding");
+            return;
+        };
+        if let Err(e) = self.observe(&binding) {
+            debug!(peer = %hex::encode(peer), err = %e, "peer binding refused");
+        }
+    }
+}
+
+impl NodeExtension for BindingRegistry {
+    fn name(&self) -> &'static str {
+        "validator-binding"
+    }
+
+    fn register(&self, reg: &mut ExtensionRegistry) -> Result<(), ExtensionError> {
+        reg.gossip_topic(BINDING_TOPIC, Arc::new(self.clone()))?
+            .metric(Box::new(self.accepted.clone()))
+            .metric(Box::new(self.rejected.clone()))
+            .metric(Box::new(self.bound.clone()));
+        Ok(())
+    }
+}
+
+/// Publish `binding` on `BINDING_TOPIC` every `interval` so late joiners learn it.
+pub fn spawn_binding_announcer(
+    binding: PeerBinding,
+    publish: Sender<(String, Vec<u8>)>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let bytes = match binding.encode() {
+            Ok(b) => b,
+            Err(e) => {
+                warn!(err = ?e, "cannot encode peer binding");
+                return;
+            }
+        };
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if publish
+                .send((BINDING_TOPIC.to_string(), bytes.clone()))
+                .await
+                .is_err()
+            {
+                info!("p2p gone; stopping binding announcements");
+                return;
+            }
+        }
+    })
+}
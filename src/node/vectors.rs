@@ -0,0 +1,295 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! Test vectors for signing payloads and canonical encodings.
+//!
+//! `generate` returns one JSON document with the exact bytes this node produces for vote
+//! signing payloads (v1 and v2), commit certificates, state Merkle hashing and signed peer
+//! registries. Every input is fixed and keys come from the public `TEST_SEED`, so output
+//! is identical on every run and platform; light clients and HSM signers diff against it.
+//! Byte fields are lowercase hex.
+
+use crate::core::consensus::signing::{vote_signing_bytes_v1, vote_signing_bytes_v2};
+use crate::core::state::merkle::{hash_leaf, hash_node, merkle_root_sorted};
+use crate::core::types::{
+    encode_canonical, CanonicalMap, Commit, Epoch, Height, Round, Signature, ValidatorId, H256,
+};
+use crate::networking::peer_registry::peer_registry_signing_bytes;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+/// Format version of the vectors document.
+pub const VECTORS_VERSION: u32 = 1;
+
+/// Seed of the signing keys used in the vectors. Public: never use it for a real key.
+pub const TEST_SEED: [u8; 32] = [0x11; 32];
+
+#[derive(Debug, Error)]
+pub enum VectorsError {
+    #[error("key")]
+    Key,
+    #[error("codec")]
+    Codec,
+}
+
+/// Test key `n`: seed `TEST_SEED` with the last byte replaced by `n`.
+fn test_key(n: u8) -> Result<Ed25519KeyPair, VectorsError> {
+    let mut seed = TEST_SEED;
+    seed[31] = n;
+    Ed25519KeyPair::from_seed_unchecked(&seed).map_err(|_| VectorsError::Key)
+}
+
+fn voter(kp: &Ed25519KeyPair) -> Result<ValidatorId, VectorsError> {
+    ValidatorId::from_slice(kp.public_key().as_ref()).map_err(|_| VectorsError::Key)
+}
+
+fn sign(kp: &Ed25519KeyPair, msg: &[u8]) -> String {
+    hex::encode(kp.sign(msg).as_ref())
+}
+
+fn sha256(b: &[u8]) -> String {
+    hex::encode(ring::digest::digest(&ring::digest::SHA256, b).as_ref())
+}
+
+/// The full vectors document.
+pub fn generate() -> Result<Value, VectorsError> {
+    Ok(json!({
+        "version": VECTORS_VERSION,
+        "test_seed": hex::encode(TEST_SEED),
+        "vote_signing_bytes_v1": vote_v1()?,
+        "vote_signing_bytes_v2": vote_v2()?,
+        "commit_canonical_bytes": commits()?,
+        "merkle": merkle(),
+        "peer_registry": registry()?,
+    }))
+}
+
+fn vote_v1() -> Result<Vec<Value>, VectorsError> {
+    let kp = test_key(0)?;
+    let cases = [
+        ("zero", 0, 0, [0u8; 32]),
+        ("typical", 42, 1, [0xab; 32]),
+        ("max", u64::MAX, u64::MAX, [0xff; 32]),
+    ];
+    cases
+        .into_iter()
+        .map(|(name, height, round, hash)| {
+            let v = voter(&kp)?;
+            let bytes =
+                vote_signing_bytes_v1(Height(height), Round(round), H256::from_bytes(hash), &v)
+                    .map_err(|_| VectorsError::Codec)?;
+            Ok(json!({
+                "name": name,
+                "height": height,
+                "round": round,
+                "block_hash": hex::encode(hash),
+                "voter": hex::encode(v.as_bytes()),
+                "bytes": hex::encode(&bytes),
+                "signature": sign(&kp, &bytes),
+            }))
+        })
+        .collect()
+}
+
+fn vote_v2() -> Result<Vec<Value>, VectorsError> {
+    let kp = test_key(0)?;
+    let cases = [
+        (
+            "typical",
+            42,
+            1,
+            3,
+            7,
+            1_700_000_000_000,
+            30_000,
+            [0xab; 32],
+        ),
+        ("counter_only", 1, 0, 0, 1, 0, 0, [0x01; 32]),
+        (
+            "max",
+            u64::MAX,
+            u64::MAX,
+            u64::MAX,
+            u64::MAX,
+            u64::MAX,
+            u32::MAX,
+            [0xff; 32],
+        ),
+    ];
+    cases
+        .into_iter()
+        .map(
+            |(name, height, round, epoch, counter, sent_ts_ms, ttl_ms, hash)| {
+                let v = voter(&kp)?;
+                let bytes = vote_signing_bytes_v2(
+                    Height(height),
+                    Round(round),
+                    Epoch(epoch),
+                    counter,
+                    sent_ts_ms,
+                    ttl_ms,
+                    H256::from_bytes(hash),
+                    &v,
+                )
+                .map_err(|_| VectorsError::Codec)?;
+                Ok(json!({
+                    "name": name,
+                    "height": height,
+                    "round": round,
+                    "epoch": epoch,
+                    "msg_counter": counter,
+                    "sent_ts_ms": sent_ts_ms,
+                    "ttl_ms": ttl_ms,
+                    "block_hash": hex::encode(hash),
+                    "voter": hex::encode(v.as_bytes()),
+                    "bytes": hex::encode(&bytes),
+                    "signature": sign(&kp, &bytes),
+                }))
+            },
+        )
+        .collect()
+}
+
+fn commits() -> Result<Vec<Value>, VectorsError> {
+    let keys = [test_key(0)?, test_key(1)?, test_key(2)?];
+    let block_hash = H256::from_bytes([0xab; 32]);
+    let mut out = Vec::new();
+    for (name, signers, epoch, validator_set_hash) in
+        [("legacy", 1, 0, [0u8; 32]), ("bound", 3, 3, [0xcd; 32])]
+    {
+        let mut signatures = CanonicalMap::new();
+        for kp in &keys[..signers] {
+            let v = voter(kp)?;
+            let msg = vote_signing_bytes_v1(Height(42), Round(1), block_hash, &v)
+                .map_err(|_| VectorsError::Codec)?;
+            let sig =
+                Signature::from_slice(kp.sign(&msg).as_ref()).map_err(|_| VectorsError::Key)?;
+            signatures.insert(v, sig);
+        }
+        let commit = Commit {
+            height: Height(42),
+            round: Round(1),
+            epoch: Epoch(epoch),
+            msg_counter: epoch,
+            sent_ts_ms: epoch * 1_000,
+            ttl_ms: 0,
+            block_hash,
+            voting_power: signers as u128,
+            validator_set_hash: H256::from_bytes(validator_set_hash),
+            signatures,
+        };
+        let bytes = encode_canonical(&commit).map_err(|_| VectorsError::Codec)?;
+        out.push(json!({
+            "name": name,
+            "height": commit.height.get(),
+            "round": commit.round.get(),
+            "epoch": commit.epoch.get(),
+            "msg_counter": commit.msg_counter,
+            "sent_ts_ms": commit.sent_ts_ms,
+            "ttl_ms": commit.ttl_ms,
+            "block_hash": hex::encode(commit.block_hash.as_bytes()),
+            // Canonical order: by validator id.
+            "signatures": commit.signatures.iter().map(|(v, s)| json!({
+                "validator": hex::encode(v.as_bytes()),
+                "signature": hex::encode(s.as_bytes()),
+            })).collect::<Vec<_>>(),
+            "voting_power": commit.voting_power,
+            "validator_set_hash": hex::encode(commit.validator_set_hash.as_bytes()),
+            "bytes": hex::encode(&bytes),
+            "sha256": sha256(&bytes),
+        }));
+    }
+    Ok(out)
+}
+
+fn merkle() -> Value {
+    let leaves: Vec<(Vec<u8>, Vec<u8>)> = vec![
+        (Vec::new(), Vec::new()),
+        (
+            b"bank/v1/balance/alice".to_vec(),
+            100u128.to_be_bytes().to_vec(),
+        ),
+        (b"nonce/v1/alice".to_vec(), 7u64.to_be_bytes().to_vec()),
+    ];
+    let leaf_vectors: Vec<Value> = leaves
+        .iter()
+        .map(|(k, v)| {
+            json!({
+                "key": hex::encode(k),
+                "value": hex::encode(v),
+                "hash": hex::encode(hash_leaf(k, v)),
+            })
+        })
+        .collect();
+    let node_vectors: Vec<Value> = [([0u8; 32], [0u8; 32]), ([0x01; 32], [0x02; 32])]
+        .into_iter()
+        .map(|(l, r)| {
+            json!({
+                "left": hex::encode(l),
+                "right": hex::encode(r),
+                "hash": hex::encode(hash_node(l, r)),
+            })
+        })
+        .collect();
+    // Sorted pairs; odd levels pair the last node with itself.
+    let mut sorted = leaves[1..].to_vec();
+    sorted.push((b"staking/v1/params".to_vec(), vec![0x01]));
+    sorted.sort();
+    let roots: Vec<Value> = (0..=sorted.len())
+        .map(|n| {
+            json!({
+                "pairs": sorted[..n].iter().map(|(k, v)| json!({
+                    "key": hex::encode(k),
+                    "value": hex::encode(v),
+                })).collect::<Vec<_>>(),
+                "root": hex::encode(merkle_root_sorted(&sorted[..n])),
+            })
+        })
+        .collect();
+    json!({ "leaves": leaf_vectors, "nodes": node_vectors, "roots": roots })
+}
+
+fn registry() -> Result<Vec<Value>, VectorsError> {
+    let kp = test_key(9)?;
+    let peers = (1u8..=3)
+        .map(|n| {
+            let mut seed = TEST_SEED;
+            seed[0] = n;
+            libp2p::identity::Keypair::ed25519_from_bytes(seed)
+                .map(|k| k.public().to_peer_id().to_base58())
+                .map_err(|_| VectorsError::Key)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let toml_doc = |signature: &str| {
+        let list = peers
+            .iter()
+            .rev()
+            .map(|p| format!("\"{p}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "version = 1\nnetwork = \"amun-testnet\"\nissued_at_ms = 1700000000000\n\
+             expires_at_ms = 1700086400000\npeers = [{list}]\nsignature_hex = \"{signature}\"\n"
+        )
+    };
+    let bytes = peer_registry_signing_bytes(&toml_doc("")).map_err(|_| VectorsError::Codec)?;
+    let signature = sign(&kp, &bytes);
+    Ok(vec![json!({
+        "name": "single_network",
+        "pubkey": hex::encode(kp.public_key().as_ref()),
+        "toml": toml_doc(&signature),
+        "bytes": hex::encode(&bytes),
+        "signature": signature,
+    })])
+}
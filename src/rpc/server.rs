@@ -0,0 +1,627 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! HTTP server.
+//!
+//! Routes:
+//! - `GET /metrics`: Prometheus text exposition
+//! - `GET /system_info`: build metadata, chain id, peer id, finalized height, uptime
+//! - `GET /status`: finalized height, peers, state root, db size, commit age, participation
+//! - `GET /state/proof/:key`: value and inclusion proof of a state key (hex)
+//! - `GET /state/keys`: paged state entries under a key prefix, optionally proven (see `state`)
+//! - `GET /healthz`: per-subsystem health report (always 200)
+//! - `GET /readyz`: same report; 503 when unhealthy
+//! - `GET /consensus/liveness`: per-validator uptime report
+//! - `GET /consensus/finality/:height`: stored commit + validator set hash
+//! - `GET /consensus/performance`: vote delay percentiles per validator
+//! - `GET /consensus/timings/:height`: vote arrival times behind a finalized commit
+//! - `GET /chain/receipt/:tx_hash`: receipt and block position of a transaction (hex hash)
+//! - `GET /p2p/peers`: connected peers with identify info, capabilities and gossip usage
+//! - `GET /p2p/peers/:peer_id`: one of them
+//! - `POST /chain/call`: dry-run a contract call against current state (gas estimate, queries)
+//! - `GET|POST /staking/epoch/preview`: next-epoch preview; POST takes hypothetical ops
+//! - `GET /ws`: chain event subscriptions over WebSocket (see `ws`)
+//! - `GET|PUT /admin/loglevel`: read or replace the log filter (bearer token required)
+//! - `/ext/<namespace>/...`: routes registered by node extensions
+
+use crate::core::consensus::driver::{ConsensusDriver, ConsensusSnapshot};
+use crate::core::consensus::events::ChainEvents;
+use crate::core::economics::epoch::{preview_next_epoch, EpochPolicy, PendingOp};
+use crate::core::economics::staking::StakingLedger;
+use crate::core::runtime::executor::{EvmExecutor, ExecError};
+use crate::core::runtime::receipt::{Log, TxStatus};
+use crate::core::state::commit_store::CommitStore;
+use crate::core::state::persistent_state::PersistentState;
+use crate::core::state::receipt_store::ReceiptStore;
+use crate::core::types::{AccountKey, Signature, Transaction, TxCall, H256};
+use crate::monitoring::health::{HealthMonitor, HealthStatus};
+use crate::monitoring::metrics::Metrics;
+use crate::monitoring::telemetry::LogFilterHandle;
+use crate::networking::peer_caps::PeerCapabilityMap;
+use crate::node::extensions::Extensions;
+use crate::node::info::NodeIdentity;
+use crate::rpc::state::{state_keys_handler, state_proof_handler};
+use crate::rpc::status::{status_handler, StateStatus};
+use crate::rpc::tls::ClientCert;
+use crate::rpc::ws::{ws_handler, WsConfig};
+use axum::{
+    extract::Request,
+    middleware::{self, Next},
+    response::Response,
+};
+use axum::{
+    extract::{Extension, Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, PoisonError};
+use subtle::ConstantTimeEq;
+use thiserror::Error;
+use tokio::sync::{watch, Semaphore};
+use tracing::info;
+
+/// Server errors.
+#[derive(Debug, Error)]
+pub enum RpcError {
+    #[error("invalid listen address")]
+    Addr,
+    #[error("bind")]
+    Bind,
+    #[error("serve")]
+    Serve,
+}
+
+/// Consensus driver shared between the network pump and the HTTP API.
+pub type SharedDriver = Arc<Mutex<ConsensusDriver>>;
+
+/// What the HTTP API reads of consensus: the driver's published snapshots and its commit
+/// store. Handlers never lock the driver, so RPC load cannot stall the consensus pumps.
+#[derive(Clone)]
+pub(crate) struct ConsensusView {
+    pub(crate) snapshot: watch::Receiver<Arc<ConsensusSnapshot>>,
+    pub(crate) commits: Option<CommitStore>,
+}
+
+impl ConsensusView {
+    /// The latest snapshot.
+    pub(crate) fn current(&self) -> Arc<ConsensusSnapshot> {
+        self.snapshot.borrow().clone()
+    }
+}
+
+/// Staking ledger shared with the HTTP API.
+pub type SharedLedger = Arc<Mutex<StakingLedger>>;
+
+/// Shared handler state.
+#[derive(Clone)]
+pub struct RpcState {
+    /// Metrics registry to expose.
+    pub metrics: Arc<Metrics>,
+    /// Consensus snapshots and commits (absent on nodes that do not run consensus).
+    pub(crate) consensus: Option<ConsensusView>,
+    /// Staking ledger and epoch policy for previews (absent if not wired).
+    pub staking: Option<(SharedLedger, EpochPolicy)>,
+    /// Receipt store for `/chain/receipt` (absent => 503).
+    pub receipts: Option<ReceiptStore>,
+    /// State and executor for `/chain/call` (absent => 503).
+    pub(crate) call: Option<(PersistentState, Arc<EvmExecutor>)>,
+    /// Extension routes served under `/ext/<namespace>`.
+    pub extensions: Option<Arc<Extensions>>,
+    /// Node identity for `/system_info` (absent => 503).
+    pub identity: Option<NodeIdentity>,
+    /// Health checks (absent => `/healthz` and `/readyz` return 503).
+    pub health: Option<HealthMonitor>,
+    /// State database for `/status` and `/state` routes (absent => root and size are null,
+    /// `/state` routes return 503).
+    pub(crate) state: Option<StateStatus>,
+    /// Event source for `/ws` (absent => 503).
+    pub(crate) ws: Option<WsConfig>,
+    /// Peer capabilities for `/p2p/peers` (absent => 503).
+    pub peers: Option<PeerCapabilityMap>,
+    /// Bearer token and log filter for `/admin` routes (absent => admin routes return 404).
+    admin: Option<(Arc<str>, LogFilterHandle)>,
+    /// `/admin` routes also need a verified client certificate (mTLS).
+    admin_client_cert: bool,
+    /// In-flight request permits; requests beyond the limit are rejected, not queued.
+    in_flight: Arc<Semaphore>,
+}
+
+/// Default in-flight request limit.
+const DEFAULT_MAX_IN_FLIGHT: usize = 256;
+
+impl RpcState {
+    /// State exposing metrics only.
+    pub fn new(metrics: Arc<Metrics>) -> Self {
+        Self {
+            metrics,
+            consensus: None,
+            staking: None,
+            receipts: None,
+            call: None,
+            extensions: None,
+            identity: None,
+            health: None,
+            state: None,
+            ws: None,
+            peers: None,
+            admin: None,
+            admin_client_cert: false,
+            in_flight: Arc::new(Semaphore::new(DEFAULT_MAX_IN_FLIGHT)),
+        }
+    }
+
+    /// Set the in-flight request limit.
+    pub fn with_max_in_flight(mut self, max: usize) -> Self {
+        self.in_flight = Arc::new(Semaphore::new(max.max(1)));
+        self
+    }
+
+    /// Serve extension RPC namespaces.
+    pub fn with_extensions(mut self, extensions: Arc<Extensions>) -> Self {
+        self.extensions = Some(extensions);
+        self
+    }
+
+    /// Serve `/system_info` for `identity`.
+    pub fn with_identity(mut self, identity: NodeIdentity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Serve `/healthz` and `/readyz` from `health`.
+    pub fn with_health(mut self, health: HealthMonitor) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// Report the state root and database size in `/status`; serve `/state` routes.
+    pub fn with_state(mut self, state: PersistentState) -> Self {
+        self.state = Some(StateStatus::new(state));
+        self
+    }
+
+    /// Serve `/ws` from `events`, with at most `max_subscribers` open connections.
+    pub fn with_events(mut self, events: ChainEvents, max_subscribers: usize) -> Self {
+        self.ws = Some(WsConfig::new(events, max_subscribers));
+        self
+    }
+
+    /// Enable `/admin` routes, authenticated with `Authorization: Bearer <token>`.
+    pub fn with_admin(mut self, token: String, log_filter: LogFilterHandle) -> Self {
+        self.admin = Some((token.into(), log_filter));
+        self
+    }
+
+    /// Also require a verified client certificate on `/admin` routes. Only satisfiable when
+    /// served by `tls::serve_listener_tls` with a client CA; plain HTTP admin requests get 403.
+    pub fn with_admin_client_cert(mut self) -> Self {
+        self.admin_client_cert = true;
+        self
+    }
+
+    /// Serve `/p2p/peers` from the p2p task's capability map.
+    pub fn with_peers(mut self, peers: PeerCapabilityMap) -> Self {
+        self.peers = Some(peers);
+        self
+    }
+
+    /// Serve consensus routes from `driver`'s snapshots and commit store. The driver is only
+    /// locked here, once.
+    pub fn with_driver(mut self, driver: SharedDriver) -> Self {
+        let d = driver.lock().unwrap_or_else(PoisonError::into_inner);
+        self.consensus = Some(ConsensusView {
+            snapshot: d.snapshots(),
+            commits: d.commit_store().cloned(),
+        });
+        drop(d);
+        self
+    }
+
+    /// Attach the staking ledger used by epoch previews.
+    pub fn with_staking(mut self, ledger: SharedLedger, policy: EpochPolicy) -> Self {
+        self.staking = Some((ledger, policy));
+        self
+    }
+
+    /// Serve receipts from `store`.
+    pub fn with_receipts(mut self, store: ReceiptStore) -> Self {
+        self.receipts = Some(store);
+        self
+    }
+
+    /// Serve read-only calls against `state` with `executor`.
+    pub fn with_call(mut self, state: PersistentState, executor: EvmExecutor) -> Self {
+        self.call = Some((state, Arc::new(executor)));
+        self
+    }
+}
+
+/// Build the HTTP router.
+pub fn router(state: RpcState) -> Router {
+    let mut r = Router::new()
+        .route("/metrics", get(metrics_handler))
+        .route("/system_info", get(system_info_handler))
+        .route("/status", get(status_handler))
+        .route("/state/keys", get(state_keys_handler))
+        .route("/state/proof/:key", get(state_proof_handler))
+        .route("/healthz", get(healthz_handler))
+        .route("/readyz", get(readyz_handler))
+        .route("/consensus/liveness", get(liveness_handler))
+        .route("/consensus/finality/:height", get(finality_handler))
+        .route("/consensus/performance", get(performance_handler))
+        .route("/consensus/timings/:height", get(timings_handler))
+        .route("/p2p/peers", get(peers_handler))
+        .route("/p2p/peers/:peer_id", get(peer_handler))
+        .route("/chain/receipt/:tx_hash", get(receipt_handler))
+        .route("/chain/call", post(call_handler))
+        .route(
+            "/staking/epoch/preview",
+            get(epoch_preview_handler).post(epoch_what_if_handler),
+        )
+        .route("/ws", get(ws_handler))
+        .route(
+            "/admin/loglevel",
+            get(loglevel_get_handler).put(loglevel_put_handler),
+        );
+    if let Some(ext) = state.extensions.as_ref() {
+        for (ns, routes) in ext.rpc_routes() {
+            r = r.nest(&format!("/ext/{ns}"), routes.clone().with_state(()));
+        }
+    }
+    r.layer(middleware::from_fn_with_state(
+        state.clone(),
+        limit_in_flight,
+    ))
+    .with_state(state)
+}
+
+async fn limit_in_flight(State(st): State<RpcState>, req: Request, next: Next) -> Response {
+    let Ok(_permit) = st.in_flight.clone().try_acquire_owned() else {
+        st.metrics.rpc_rejected_total.inc();
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    };
+    next.run(req).await
+}
+
+/// Bind `listen_addr` and serve until the task is dropped.
+pub async fn serve(listen_addr: &str, state: RpcState) -> Result<(), RpcError> {
+    serve_listener(bind(listen_addr)?, state).await
+}
+
+/// Bind the listen socket synchronously, so bind failures surface during startup
+/// rather than inside a spawned task.
+pub fn bind(listen_addr: &str) -> Result<std::net::TcpListener, RpcError> {
+    let addr: std::net::SocketAddr = listen_addr.parse().map_err(|_| RpcError::Addr)?;
+    let listener = std::net::TcpListener::bind(addr).map_err(|_| RpcError::Bind)?;
+    listener.set_nonblocking(true).map_err(|_| RpcError::Bind)?;
+    info!(addr = %listen_addr, "http listening");
+    Ok(listener)
+}
+
+/// Serve on an already bound listener.
+pub async fn serve_listener(
+    listener: std::net::TcpListener,
+    state: RpcState,
+) -> Result<(), RpcError> {
+    let listener = tokio::net::TcpListener::from_std(listener).map_err(|_| RpcError::Bind)?;
+    axum::serve(listener, router(state))
+        .await
+        .map_err(|_| RpcError::Serve)
+}
+
+async fn metrics_handler(State(st): State<RpcState>) -> impl IntoResponse {
+    match st.metrics.encode_text() {
+        Ok(text) => (StatusCode::OK, text),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, String::new()),
+    }
+}
+
+async fn system_info_handler(State(st): State<RpcState>) -> impl IntoResponse {
+    let Some(identity) = st.identity.as_ref() else {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    };
+    let height = st.metrics.block_height.get().max(0) as u64;
+    Ok(Json(identity.system_info(height)))
+}
+
+async fn healthz_handler(State(st): State<RpcState>) -> impl IntoResponse {
+    let Some(health) = st.health.as_ref() else {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    };
+    Ok(Json(health.report()))
+}
+
+async fn readyz_handler(State(st): State<RpcState>) -> impl IntoResponse {
+    let Some(health) = st.health.as_ref() else {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    };
+    let report = health.report();
+    let code = if report.status == HealthStatus::Unhealthy {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    Ok((code, Json(report)))
+}
+
+/// Log filter handle if admin routes are enabled, `headers` carry the token and, when
+/// required, the connection presented a client certificate.
+fn admin_auth(
+    st: &RpcState,
+    headers: &HeaderMap,
+    cert: Option<Extension<ClientCert>>,
+) -> Result<LogFilterHandle, StatusCode> {
+    let (token, filter) = st.admin.as_ref().ok_or(StatusCode::NOT_FOUND)?;
+    if st.admin_client_cert && cert.is_none() {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let presented = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or("");
+    if !bool::from(presented.as_bytes().ct_eq(token.as_bytes())) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(filter.clone())
+}
+
+async fn loglevel_get_handler(
+    State(st): State<RpcState>,
+    cert: Option<Extension<ClientCert>>,
+    headers: HeaderMap,
+) -> Result<String, StatusCode> {
+    Ok(admin_auth(&st, &headers, cert)?.current())
+}
+
+/// Body: filter directives, e.g. `info,amunchain::networking::p2p=debug`.
+async fn loglevel_put_handler(
+    State(st): State<RpcState>,
+    cert: Option<Extension<ClientCert>>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<String, (StatusCode, String)> {
+    let filter = admin_auth(&st, &headers, cert).map_err(|s| (s, String::new()))?;
+    filter
+        .set(&body)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    Ok(filter.current())
+}
+
+async fn liveness_handler(State(st): State<RpcState>) -> impl IntoResponse {
+    let Some(consensus) = st.consensus.as_ref() else {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    };
+    Ok(Json(consensus.current().liveness.clone()))
+}
+
+fn commit_store(st: &RpcState) -> Result<&CommitStore, StatusCode> {
+    st.consensus
+        .as_ref()
+        .and_then(|c| c.commits.as_ref())
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)
+}
+
+async fn finality_handler(
+    State(st): State<RpcState>,
+    Path(height): Path<u64>,
+) -> impl IntoResponse {
+    match commit_store(&st)?.get(height) {
+        Ok(Some(proof)) => Ok(Json(proof)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn performance_handler(State(st): State<RpcState>) -> impl IntoResponse {
+    let Some(consensus) = st.consensus.as_ref() else {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    };
+    Ok(Json(consensus.current().performance.clone()))
+}
+
+async fn timings_handler(State(st): State<RpcState>, Path(height): Path<u64>) -> impl IntoResponse {
+    match commit_store(&st)?.timings(height) {
+        Ok(Some(timings)) => Ok(Json(timings)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn peers_handler(State(st): State<RpcState>) -> impl IntoResponse {
+    let peers = st.peers.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    Ok::<_, StatusCode>(Json(peers.list()))
+}
+
+async fn peer_handler(
+    State(st): State<RpcState>,
+    Path(peer_id): Path<String>,
+) -> impl IntoResponse {
+    let peers = st.peers.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    peers.get(&peer_id).map(Json).ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn receipt_handler(
+    State(st): State<RpcState>,
+    Path(tx_hash): Path<String>,
+) -> impl IntoResponse {
+    let Some(store) = st.receipts.as_ref() else {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    };
+    let hash: [u8; 32] = hex::decode(tx_hash.trim_start_matches("0x"))
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or(StatusCode::BAD_REQUEST)?;
+    match store.get(&H256::from_bytes(hash)) {
+        Ok(Some(receipt)) => Ok(Json(receipt)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Body of `POST /chain/call`; byte fields are hex.
+#[derive(Debug, Deserialize)]
+pub struct CallRequest {
+    /// Sender account (32 bytes).
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub value: u128,
+    #[serde(default)]
+    pub input: String,
+    /// Zero or absent: the block gas limit.
+    #[serde(default)]
+    pub gas_limit: u64,
+    /// State root to run against; absent: the current state.
+    #[serde(default)]
+    pub at_state_root: Option<String>,
+}
+
+impl CallRequest {
+    fn decode(&self) -> Option<(Transaction, Option<[u8; 32]>)> {
+        let unhex = |s: &str| hex::decode(s.trim_start_matches("0x")).ok();
+        let tx = Transaction {
+            chain_id: String::new(),
+            sender: AccountKey::from_slice(&unhex(&self.from)?).ok()?,
+            nonce: 0,
+            call: TxCall::Call {
+                to: unhex(&self.to)?,
+                value: self.value,
+                input: unhex(&self.input)?,
+            },
+            gas_limit: self.gas_limit,
+            max_fee_per_gas: 0,
+            max_priority_fee_per_gas: 0,
+            signature: Signature::from_bytes([0; 64]),
+        };
+        let root = match self.at_state_root.as_deref() {
+            Some(r) => Some(unhex(r)?.try_into().ok()?),
+            None => None,
+        };
+        Some((tx, root))
+    }
+}
+
+/// Result of `POST /chain/call`.
+#[derive(Debug, Serialize)]
+pub struct CallResponse {
+    pub status: TxStatus,
+    pub gas_used: u64,
+    /// Hex return data.
+    pub output: String,
+    pub logs: Vec<Log>,
+}
+
+async fn call_handler(
+    State(st): State<RpcState>,
+    Json(req): Json<CallRequest>,
+) -> impl IntoResponse {
+    let Some((state, executor)) = st.call.clone() else {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    };
+    let (tx, root) = req.decode().ok_or(StatusCode::BAD_REQUEST)?;
+    let receipt = tokio::task::spawn_blocking(move || executor.call_readonly(&state, &tx, root))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map_err(|e| match e {
+            ExecError::UnknownStateRoot => StatusCode::NOT_FOUND,
+            ExecError::State | ExecError::Codec => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        })?;
+    Ok(Json(CallResponse {
+        status: receipt.status,
+        gas_used: receipt.gas_used,
+        output: hex::encode(&receipt.output),
+        logs: receipt.logs,
+    }))
+}
+
+/// Hypothetical op in a what-if request; ids are hex.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum WhatIfOp {
+    Bond {
+        delegator: String,
+        validator: String,
+        amount: u128,
+    },
+    Unbond {
+        delegator: String,
+        validator: String,
+        amount: u128,
+    },
+}
+
+/// Body of `POST /staking/epoch/preview`.
+#[derive(Debug, Default, Deserialize)]
+pub struct WhatIfRequest {
+    #[serde(default)]
+    pub ops: Vec<WhatIfOp>,
+}
+
+impl WhatIfOp {
+    fn decode(&self) -> Option<PendingOp> {
+        Some(match self {
+            WhatIfOp::Bond {
+                delegator,
+                validator,
+                amount,
+            } => PendingOp::Bond {
+                delegator: hex::decode(delegator).ok()?,
+                validator: hex::decode(validator).ok()?,
+                amount: *amount,
+            },
+            WhatIfOp::Unbond {
+                delegator,
+                validator,
+                amount,
+            } => PendingOp::Unbond {
+                delegator: hex::decode(delegator).ok()?,
+                validator: hex::decode(validator).ok()?,
+                amount: *amount,
+            },
+        })
+    }
+}
+
+async fn epoch_preview_handler(State(st): State<RpcState>) -> impl IntoResponse {
+    epoch_preview(&st, WhatIfRequest::default())
+}
+
+async fn epoch_what_if_handler(
+    State(st): State<RpcState>,
+    Json(req): Json<WhatIfRequest>,
+) -> impl IntoResponse {
+    epoch_preview(&st, req)
+}
+
+fn epoch_preview(st: &RpcState, req: WhatIfRequest) -> impl IntoResponse {
+    let Some((ledger, policy)) = st.staking.as_ref() else {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    };
+    let ops = req
+        .ops
+        .iter()
+        .map(WhatIfOp::decode)
+        .collect::<Option<Vec<_>>>()
+        .ok_or(StatusCode::BAD_REQUEST)?;
+    let guard = ledger
+        .lock()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    preview_next_epoch(&guard, policy, &ops)
+        .map(Json)
+        .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)
+}
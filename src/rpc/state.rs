@@ -0,0 +1,201 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! State queries.
+//!
+//! `GET /state/proof/:key` (`state_getProof`): the value of a key (hex) with its inclusion
+//! proof against the current state root; 404 when the key is absent. On the sparse state
+//! tree an absent key is answered too, with a `null` value and a proof of its absence; the
+//! `tree` field names the kind of proof.
+//!
+//! `GET /state/keys` (`state_getKeysPaged`): paged listing of state entries under a key
+//! prefix. Query parameters, byte strings in hex: `prefix` (default: all keys), `start_after` (the
+//! `next_start_after` of the previous page), `limit` (default 100, at most 1000) and
+//! `proof=true` for inclusion proofs of the page's first and last entries. A proven page is
+//! read from one snapshot of the state and walks the whole database, like the state root.
+//! The sparse state tree has no page proofs: `proof=true` is answered with 400.
+//!
+//! Proofs are in the canonical hex encoding of `MerkleProof`, or of `SparseProof` on the
+//! sparse tree.
+
+use crate::core::state::merkle::MerkleProof;
+use crate::core::state::persistent_state::{PageProof, StateError, StatePage};
+use crate::core::state::smt::{SparseProof, StateTree};
+use crate::rpc::server::RpcState;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+/// Page size when the request does not set one.
+pub const DEFAULT_PAGE_LIMIT: usize = 100;
+/// Largest page served.
+pub const MAX_PAGE_LIMIT: usize = 1_000;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct KeysQuery {
+    #[serde(default)]
+    prefix: String,
+    start_after: Option<String>,
+    limit: Option<usize>,
+    #[serde(default)]
+    proof: bool,
+}
+
+/// Body of `GET /state/keys`; byte fields are hex.
+#[derive(Debug, Serialize)]
+pub struct KeysPage {
+    pub entries: Vec<KeyEntry>,
+    /// Cursor for the next page; `null` on the last page.
+    pub next_start_after: Option<String>,
+    /// Present when requested and the page is not empty.
+    pub proof: Option<KeysProof>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeyEntry {
+    pub key: String,
+    pub value: String,
+}
+
+/// `PageProof` with a hex state root.
+#[derive(Debug, Serialize)]
+pub struct KeysProof {
+    pub state_root: String,
+    pub first: MerkleProof,
+    pub last: MerkleProof,
+}
+
+/// Body of `GET /state/proof/:key`; byte fields are hex.
+#[derive(Debug, Serialize)]
+pub struct KeyProof {
+    /// Always `sorted`.
+    pub tree: StateTree,
+    pub key: String,
+    pub value: String,
+    pub state_root: String,
+    pub proof: MerkleProof,
+}
+
+/// Body of `GET /state/proof/:key` on the sparse state tree; byte fields are hex.
+#[derive(Debug, Serialize)]
+pub struct SparseKeyProof {
+    /// Always `sparse`.
+    pub tree: StateTree,
+    pub key: String,
+    /// `null` when the key is absent.
+    pub value: Option<String>,
+    pub state_root: String,
+    pub proof: SparseProof,
+}
+
+impl KeysPage {
+    fn new(page: StatePage, proof: Option<PageProof>) -> Self {
+        Self {
+            entries: page
+                .entries
+                .iter()
+                .map(|(k, v)| KeyEntry {
+                    key: hex::encode(k),
+                    value: hex::encode(v),
+                })
+                .collect(),
+            next_start_after: page.next_start_after.map(hex::encode),
+            proof: proof.map(|p| KeysProof {
+                state_root: hex::encode(p.state_root),
+                first: p.first,
+                last: p.last,
+            }),
+        }
+    }
+}
+
+pub(crate) async fn state_keys_handler(
+    State(st): State<RpcState>,
+    Query(q): Query<KeysQuery>,
+) -> Result<Json<KeysPage>, StatusCode> {
+    let Some(status) = st.state.as_ref() else {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    };
+    let prefix = unhex(&q.prefix)?;
+    let start_after = q.start_after.as_deref().map(unhex).transpose()?;
+    let limit = q
+        .limit
+        .unwrap_or(DEFAULT_PAGE_LIMIT)
+        .clamp(1, MAX_PAGE_LIMIT);
+    let state = status.state().clone();
+    let page = tokio::task::spawn_blocking(move || {
+        let after = start_after.as_deref();
+        if q.proof {
+            state.scan_prefix_page_proven(&prefix, after, limit)
+        } else {
+            state
+                .scan_prefix_page(&prefix, after, limit)
+                .map(|page| (page, None))
+        }
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .map_err(status_of)?;
+    Ok(Json(KeysPage::new(page.0, page.1)))
+}
+
+pub(crate) async fn state_proof_handler(
+    State(st): State<RpcState>,
+    Path(key): Path<String>,
+) -> Result<Response, StatusCode> {
+    let Some(status) = st.state.as_ref() else {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    };
+    let key = unhex(&key)?;
+    let state = status.state().clone();
+    let proven = tokio::task::spawn_blocking(move || match state.state_tree() {
+        StateTree::Sorted => Ok(state.prove_key(&key)?.map(|(key, value, root, proof)| {
+            Json(KeyProof {
+                tree: StateTree::Sorted,
+                key: hex::encode(key),
+                value: hex::encode(value),
+                state_root: hex::encode(root),
+                proof,
+            })
+            .into_response()
+        })),
+        StateTree::Sparse => {
+            let (value, root, proof) = state.prove_sparse(&key)?;
+            let body = SparseKeyProof {
+                tree: StateTree::Sparse,
+                key: hex::encode(key),
+                value: value.map(hex::encode),
+                state_root: hex::encode(root),
+                proof,
+            };
+            Ok::<_, StateError>(Some(Json(body).into_response()))
+        }
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .map_err(status_of)?;
+    proven.ok_or(StatusCode::NOT_FOUND)
+}
+
+fn status_of(e: StateError) -> StatusCode {
+    match e {
+        StateError::UnsupportedTree(_) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn unhex(s: &str) -> Result<Vec<u8>, StatusCode> {
+    hex::decode(s.trim_start_matches("0x")).map_err(|_| StatusCode::BAD_REQUEST)
+}
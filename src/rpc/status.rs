@@ -0,0 +1,137 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! `GET /status`: one JSON document with the numbers an operator checks first, for scripts
+//! and quick debugging without Prometheus.
+//!
+//! Fields the node cannot provide (no consensus driver or state attached, a legacy commit
+//! without a timestamp) are `null` rather than failing the request. The state root is
+//! recomputed only when the finalized height moved, since it walks the whole database.
+
+use crate::core::state::merkle::Hash32;
+use crate::core::state::persistent_state::PersistentState;
+use crate::rpc::server::RpcState;
+use axum::extract::State;
+use axum::Json;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+
+/// Body of `GET /status`.
+#[derive(Clone, Debug, Serialize)]
+pub struct NodeStatus {
+    /// Highest finalized height.
+    pub finalized_height: u64,
+    /// Connected peers.
+    pub peers: u64,
+    /// State root (hex) at `finalized_height`.
+    pub state_root: Option<String>,
+    /// Database size on disk, in bytes.
+    pub db_size_bytes: Option<u64>,
+    /// Seconds since the last finalized commit was sent.
+    pub last_commit_age_secs: Option<u64>,
+    /// Validator participation in finalized rounds.
+    pub participation: Option<Participation>,
+}
+
+/// Validator participation summary.
+#[derive(Clone, Debug, Serialize)]
+pub struct Participation {
+    /// Validators currently in the active set.
+    pub active_validators: usize,
+    /// Signatures in the last finalized commit.
+    pub last_commit_signers: usize,
+    /// Mean uptime over tracked validators, in basis points.
+    pub mean_uptime_bps: u16,
+}
+
+/// State database handle for `/status`, with the last computed root.
+#[derive(Clone)]
+pub struct StateStatus {
+    state: PersistentState,
+    root: Arc<Mutex<Option<(u64, Hash32)>>>,
+}
+
+impl StateStatus {
+    pub fn new(state: PersistentState) -> Self {
+        Self {
+            state,
+            root: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub(crate) fn state(&self) -> &PersistentState {
+        &self.state
+    }
+
+    async fn root_at(&self, height: u64) -> Option<Hash32> {
+        if let Some((h, root)) = self.root.lock().ok().and_then(|r| *r) {
+            if h == height {
+                return Some(root);
+            }
+        }
+        let state = self.state.clone();
+        let root = tokio::task::spawn_blocking(move || state.state_root())
+            .await
+            .ok()?
+            .ok()?;
+        if let Ok(mut cached) = self.root.lock() {
+            *cached = Some((height, root));
+        }
+        Some(root)
+    }
+}
+
+pub(crate) async fn status_handler(State(st): State<RpcState>) -> Json<NodeStatus> {
+    let mut height = st.metrics.block_height.get().max(0) as u64;
+    let mut last_commit = None;
+    let mut participation = None;
+    if let Some(consensus) = st.consensus.as_ref() {
+        let snapshot = consensus.current();
+        height = snapshot.finalized_height.get();
+        last_commit = snapshot.last_commit.clone();
+        let uptimes = &snapshot.liveness;
+        let mean = match uptimes.len() {
+            0 => 10_000,
+            n => uptimes.iter().map(|u| u64::from(u.uptime_bps)).sum::<u64>() / n as u64,
+        };
+        participation = Some(Participation {
+            active_validators: snapshot.active_validators,
+            last_commit_signers: last_commit.as_ref().map_or(0, |c| c.signatures.len()),
+            mean_uptime_bps: u16::try_from(mean).unwrap_or(10_000),
+        });
+    }
+
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64);
+    let last_commit_age_secs = last_commit
+        .filter(|c| c.sent_ts_ms != 0)
+        .map(|c| now_ms.saturating_sub(c.sent_ts_ms) / 1_000);
+
+    let (state_root, db_size_bytes) = match st.state.as_ref() {
+        Some(s) => (
+            s.root_at(height).await.map(hex::encode),
+            s.state.size_on_disk().ok(),
+        ),
+        None => (None, None),
+    };
+
+    Json(NodeStatus {
+        finalized_height: height,
+        peers: st.metrics.p2p_peers.get().max(0) as u64,
+        state_root,
+        db_size_bytes,
+        last_commit_age_secs,
+        participation,
+    })
+}
@@ -0,0 +1,175 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! HTTPS for the API server.
+//!
+//! `server_config` builds a rustls config from PEM files. With a client CA, clients *may*
+//! present a certificate; one that does not chain to the CA fails the handshake, and a
+//! connection that presented a valid one carries `ClientCert` in every request's
+//! extensions. Routes decide whether they need it (only `/admin` does, see
+//! `RpcState::with_admin_client_cert`), so scrapers without certificates keep working.
+
+use crate::core::types::HttpConfig;
+use crate::monitoring::metrics::Metrics;
+use crate::rpc::server::{router, RpcError, RpcState};
+use axum::extract::Request;
+use hyper::body::Incoming;
+use hyper_util::rt::TokioIo;
+use hyper_util::service::TowerToHyperService;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio_rustls::rustls::crypto::ring::default_provider;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+use tower::ServiceExt;
+use tracing::{debug, warn};
+
+/// Handshakes slower than this are dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// TLS setup errors.
+#[derive(Debug, Error)]
+pub enum TlsError {
+    #[error("cannot read {path}: {reason}")]
+    Read { path: String, reason: String },
+    #[error("{path}: no certificate found")]
+    NoCertificate { path: String },
+    #[error("tls_cert_path and tls_key_path must be set together")]
+    Incomplete,
+    #[error("tls_client_ca_path needs tls_cert_path and tls_key_path")]
+    ClientCaWithoutTls,
+    #[error("tls config: {0}")]
+    Config(String),
+}
+
+/// A verified client certificate was presented on this connection.
+#[derive(Clone, Copy, Debug)]
+pub struct ClientCert;
+
+/// Server TLS config for `http`, or `None` when TLS is not configured.
+pub fn server_config(http: &HttpConfig) -> Result<Option<Arc<ServerConfig>>, TlsError> {
+    let (cert, key) = match (&http.tls_cert_path, &http.tls_key_path) {
+        (Some(c), Some(k)) => (c, k),
+        (None, None) if http.tls_client_ca_path.is_some() => {
+            return Err(TlsError::ClientCaWithoutTls)
+        }
+        (None, None) => return Ok(None),
+        _ => return Err(TlsError::Incomplete),
+    };
+    let chain = load_certs(cert)?;
+    let key = PrivateKeyDer::from_pem_file(key).map_err(|e| TlsError::Read {
+        path: key.clone(),
+        reason: e.to_string(),
+    })?;
+
+    let provider = Arc::new(default_provider());
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| TlsError::Config(e.to_string()))?;
+    let builder = match http.tls_client_ca_path.as_ref() {
+        Some(ca) => {
+            let mut roots = RootCertStore::empty();
+            for c in load_certs(ca)? {
+                roots
+                    .add(c)
+                    .map_err(|e| TlsError::Config(format!("{ca}: {e}")))?;
+            }
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+                .allow_unauthenticated()
+                .build()
+                .map_err(|e| TlsError::Config(e.to_string()))?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+    let mut config = builder
+        .with_single_cert(chain, key)
+        .map_err(|e| TlsError::Config(e.to_string()))?;
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(Some(Arc::new(config)))
+}
+
+fn load_certs(path: &str) -> Result<Vec<CertificateDer<'static>>, TlsError> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| TlsError::Read {
+            path: path.to_string(),
+            reason: e.to_string(),
+        })?;
+    if certs.is_empty() {
+        return Err(TlsError::NoCertificate {
+            path: path.to_string(),
+        });
+    }
+    Ok(certs)
+}
+
+/// Serve HTTPS on an already bound listener. Failed handshakes are counted in
+/// `rpc_tls_handshake_failures_total` and dropped.
+pub async fn serve_listener_tls(
+    listener: std::net::TcpListener,
+    state: RpcState,
+    tls: Arc<ServerConfig>,
+) -> Result<(), RpcError> {
+    let listener = tokio::net::TcpListener::from_std(listener).map_err(|_| RpcError::Bind)?;
+    let acceptor = TlsAcceptor::from(tls);
+    let metrics = state.metrics.clone();
+    let app = router(state);
+    loop {
+        let (tcp, peer) = match listener.accept().await {
+            Ok(v) => v,
+            Err(e) => {
+                // Typically fd exhaustion; back off instead of spinning.
+                warn!(err = %e, "accept failed");
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let acceptor = acceptor.clone();
+        let app = app.clone();
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            let stream = match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(tcp)).await {
+                Ok(Ok(s)) => s,
+                Ok(Err(e)) => return handshake_failed(&metrics, peer, &e.to_string()),
+                Err(_) => return handshake_failed(&metrics, peer, "timeout"),
+            };
+            let client_cert = stream
+                .get_ref()
+                .1
+                .peer_certificates()
+                .is_some_and(|c| !c.is_empty());
+            let svc = app.map_request(move |mut req: Request<Incoming>| {
+                if client_cert {
+                    req.extensions_mut().insert(ClientCert);
+                }
+                req
+            });
+            let conn = hyper::server::conn::http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), TowerToHyperService::new(svc))
+                .with_upgrades();
+            if let Err(e) = conn.await {
+                debug!(%peer, err = %e, "https connection error");
+            }
+        });
+    }
+}
+
+fn handshake_failed(metrics: &Metrics, peer: std::net::SocketAddr, reason: &str) {
+    metrics.rpc_tls_handshake_failures_total.inc();
+    debug!(%peer, %reason, "tls handshake failed");
+}
@@ -0,0 +1,231 @@
+#![allow(missing_docs)]
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![forbid(unsafe_code)]
+
+//! `GET /ws`: chain events over WebSocket.
+//!
+//! Each text frame is one `ChainEvent` as JSON. `?events=finalized,new_head` limits the
+//! stream to those kinds (default: all); a client may replace the filter later by sending
+//! `{"events": [...]}`, which is acknowledged with `{"type":"subscribed","events":[...]}`.
+//!
+//! On nodes serving state, `{"storage": {"keys": [hex], "prefixes": [hex]}}` subscribes to
+//! changes of those keys (acknowledged with `{"type":"storage_subscribed",...}`; empty lists
+//! unsubscribe). Each change arrives as `{"type":"storage_changed","key","old","new","height"}`
+//! with hex values, `null` where the key is absent (see `core::state::watch`).
+//!
+//! Backpressure is per connection. Events queue in the subscriber's slot of the broadcast
+//! channel (`http.ws_buffer` deep); a client that falls further behind loses the oldest events
+//! and receives `{"type":"lagged","skipped":n}`. Storage changes queue as deep; those that
+//! do not fit are dropped and reported the same way. A client that stops reading entirely is
+//! disconnected once a frame cannot be written within `SEND_TIMEOUT`. Neither consensus nor
+//! state commits wait for subscribers.
+
+use crate::core::consensus::events::{ChainEvent, ChainEvents};
+use crate::core::state::persistent_state::PersistentState;
+use crate::core::state::watch::{KeyChange, StateWatch, WatchError, WatchFilter};
+use crate::monitoring::metrics::Metrics;
+use crate::rpc::server::RpcState;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::debug;
+
+/// A frame that cannot be written for this long disconnects the subscriber.
+pub const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Event source and subscriber limit for `/ws`.
+#[derive(Clone)]
+pub struct WsConfig {
+    pub events: ChainEvents,
+    slots: Arc<Semaphore>,
+}
+
+impl WsConfig {
+    /// Allow up to `max_subscribers` (at least 1) concurrent connections.
+    pub fn new(events: ChainEvents, max_subscribers: usize) -> Self {
+        Self {
+            events,
+            slots: Arc::new(Semaphore::new(max_subscribers.max(1))),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct WsQuery {
+    events: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ClientMsg {
+    Events { events: Vec<String> },
+    Storage { storage: StorageFilter },
+}
+
+#[derive(Deserialize)]
+struct StorageFilter {
+    #[serde(default)]
+    keys: Vec<String>,
+    #[serde(default)]
+    prefixes: Vec<String>,
+}
+
+impl StorageFilter {
+    /// Watch filters; `None` when a key or prefix is not hex.
+    fn parse(&self) -> Option<Vec<WatchFilter>> {
+        let unhex = |s: &String| hex::decode(s.trim_start_matches("0x")).ok();
+        let keys = self.keys.iter().map(|k| unhex(k).map(WatchFilter::Key));
+        let prefixes = self
+            .prefixes
+            .iter()
+            .map(|p| unhex(p).map(WatchFilter::Prefix));
+        keys.chain(prefixes).collect()
+    }
+}
+
+// Wait for the next storage change; never resolves without a watch.
+async fn next_change(watch: &mut Option<StateWatch>) -> Result<KeyChange, WatchError> {
+    match watch {
+        Some(w) => w.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Event kinds to forward; `None` when one is unknown.
+fn parse_filter<'a>(kinds: impl IntoIterator<Item = &'a str>) -> Option<BTreeSet<&'static str>> {
+    let mut out = BTreeSet::new();
+    for k in kinds {
+        let k = k.trim();
+        if k.is_empty() {
+            continue;
+        }
+        out.insert(*ChainEvent::KINDS.iter().find(|known| **known == k)?);
+    }
+    if out.is_empty() {
+        out.extend(ChainEvent::KINDS);
+    }
+    Some(out)
+}
+
+pub(crate) async fn ws_handler(
+    State(st): State<RpcState>,
+    Query(q): Query<WsQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    let Some(ws) = st.ws.as_ref() else {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    };
+    let Some(filter) = parse_filter(q.events.as_deref().unwrap_or("").split(',')) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("events: expected any of {}", ChainEvent::KINDS.join(", ")),
+        )
+            .into_response();
+    };
+    let Ok(permit) = ws.slots.clone().try_acquire_owned() else {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    };
+    let events = ws.events.clone();
+    let metrics = st.metrics.clone();
+    let state = st.state.as_ref().map(|s| s.state().clone());
+    upgrade.on_upgrade(move |socket| stream(socket, events, state, filter, metrics, permit))
+}
+
+async fn stream(
+    mut socket: WebSocket,
+    events: ChainEvents,
+    state: Option<PersistentState>,
+    mut filter: BTreeSet<&'static str>,
+    metrics: Arc<Metrics>,
+    _permit: OwnedSemaphorePermit,
+) {
+    let mut rx = events.subscribe();
+    let mut watch: Option<StateWatch> = None;
+    metrics.rpc_ws_subscribers.inc();
+    loop {
+        let frame = tokio::select! {
+            ev = rx.recv() => match ev {
+                Ok(ev) if filter.contains(ev.kind()) => json!(ev),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(n)) => {
+                    metrics.rpc_ws_events_dropped_total.inc_by(n);
+                    json!({"type": "lagged", "skipped": n})
+                }
+                Err(RecvError::Closed) => break,
+            },
+            change = next_change(&mut watch) => match change {
+                Ok(c) => json!({
+                    "type": "storage_changed",
+                    "key": hex::encode(&c.key),
+                    "old": c.old.map(hex::encode),
+                    "new": c.new.map(hex::encode),
+                    "height": c.height,
+                }),
+                Err(WatchError::Lagged(n)) => {
+                    metrics.rpc_ws_events_dropped_total.inc_by(n);
+                    json!({"type": "lagged", "skipped": n})
+                }
+                Err(_) => {
+                    watch = None;
+                    continue;
+                }
+            },
+            msg = socket.recv() => match msg {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<ClientMsg>(&text) {
+                    Ok(ClientMsg::Events { events: kinds }) => {
+                        match parse_filter(kinds.iter().map(String::as_str)) {
+                            Some(f) => {
+                                filter = f;
+                                json!({"type": "subscribed", "events": filter})
+                            }
+                            None => json!({"type": "error", "message": "expected {\"events\": [kinds]}"}),
+                        }
+                    }
+                    Ok(ClientMsg::Storage { storage }) => match (&state, storage.parse()) {
+                        (None, _) => json!({"type": "error", "message": "state is not served"}),
+                        (_, None) => json!({"type": "error", "message": "storage keys and prefixes are hex"}),
+                        (Some(state), Some(filters)) => {
+                            watch = (!filters.is_empty())
+                                .then(|| state.watch(filters, events.capacity()));
+                            json!({
+                                "type": "storage_subscribed",
+                                "keys": storage.keys,
+                                "prefixes": storage.prefixes,
+                            })
+                        }
+                    },
+                    Err(_) => json!({"type": "error", "message": "expected {\"events\": [kinds]} or {\"storage\": {...}}"}),
+                },
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+        let send = socket.send(Message::Text(frame.to_string()));
+        match tokio::time::timeout(SEND_TIMEOUT, send).await {
+            Ok(Ok(())) => {}
+            Ok(Err(_)) => break,
+            Err(_) => {
+                debug!("ws subscriber too slow; disconnecting");
+                break;
+            }
+        }
+    }
+    metrics.rpc_ws_subscribers.dec();
+}
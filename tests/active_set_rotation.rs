@@ -0,0 +1,72 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::driver::{ConsensusDriver, EpochRotation};
+use amunchain::core::economics::staking::StakingLedger;
+use amunchain::core::types::{Height, ValidatorId};
+use common::{id, keypairs, vote_msg};
+use std::collections::BTreeSet;
+
+#[test]
+fn active_set_ranks_by_stake_with_id_tiebreak() {
+    let mut l = StakingLedger::default();
+    l.register_validator(b"a".to_vec(), 0, 10).unwrap();
+    l.register_validator(b"b".to_vec(), 0, 30).unwrap();
+    l.register_validator(b"c".to_vec(), 0, 10).unwrap();
+    l.bond(b"d".to_vec(), b"c".to_vec(), 5).unwrap();
+    l.register_validator(b"e".to_vec(), 0, 10).unwrap();
+    l.jail(b"b", 0);
+
+    let ids: Vec<Vec<u8>> = l.active_set(3).into_iter().map(|(id, _)| id).collect();
+    assert_eq!(ids, vec![b"c".to_vec(), b"a".to_vec(), b"e".to_vec()]);
+    assert_eq!(l.active_set(1).len(), 1);
+}
+
+#[test]
+fn driver_applies_active_set_at_epoch_boundaries() {
+    let kps = keypairs(4);
+    let all: BTreeSet<ValidatorId> = kps.iter().map(id).collect();
+    let mut l = StakingLedger::default();
+    for (kp, stake) in kps.iter().zip([40u128, 30, 20, 10]) {
+        l.register_validator(id(kp).as_bytes().to_vec(), 0, stake)
+            .unwrap();
+    }
+    let mut driver = ConsensusDriver::new(all.clone())
+        .unwrap()
+        .with_epoch_rotation(EpochRotation {
+            epoch_length: 2,
+            max_validators: 3,
+        });
+
+    let finalize = |driver: &mut ConsensusDriver, h: u64| {
+        for kp in kps.iter().take(3) {
+            driver.on_msg(vote_msg(kp, &all, h));
+        }
+        assert_eq!(driver.tide.finalized_height(), Height(h));
+    };
+
+    finalize(&mut driver, 1);
+    assert!(!driver.on_epoch_boundary(&l).unwrap());
+    assert_eq!(driver.tide.validators().len(), 4);
+
+    finalize(&mut driver, 2);
+    assert!(driver.on_epoch_boundary(&l).unwrap());
+    let set = driver.tide.validators();
+    assert_eq!(set.len(), 3);
+    assert!(!set.contains(&id(&kps[3])));
+
+    // Same epoch: no second rotation.
+    assert!(!driver.on_epoch_boundary(&l).unwrap());
+}
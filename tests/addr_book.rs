@@ -0,0 +1,174 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::networking::addr_book::{AddrBook, AddrBookError, ADDR_TTL, PEERS_FILE};
+use amunchain::networking::p2p::{spawn_p2p, P2pConfig};
+use amunchain::networking::p2p_identity::{load_identity_with, IdentitySource};
+use libp2p::{Multiaddr, PeerId};
+use std::sync::Arc;
+use std::time::Duration;
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+fn config(dir: &tempfile::TempDir, port: u16, bootstrap: Vec<String>) -> P2pConfig {
+    P2pConfig {
+        listen_addr: format!("/ip4/127.0.0.1/tcp/{port}"),
+        consensus_topic: "addrbook-test".to_string(),
+        consensus_codec: Default::default(),
+        evidence_topic: "addrbook-test-evidence".to_string(),
+        max_msg_per_sec: 100,
+        max_peers_per_ip: 4,
+        data_dir: dir.path().to_string_lossy().to_string(),
+        identity: Default::default(),
+        bootstrap,
+        allow_peers: Vec::new(),
+        extensions: None,
+        channels: Default::default(),
+        gossipsub: Default::default(),
+        limits: Default::default(),
+        read_only: false,
+    }
+}
+
+fn addr(s: &str) -> Multiaddr {
+    s.parse().unwrap()
+}
+
+#[test]
+fn book_round_trips_and_ranks_by_dial_history() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(PEERS_FILE);
+    assert!(AddrBook::load(&path).unwrap().is_empty());
+
+    let (flaky, steady, fresh) = (PeerId::random(), PeerId::random(), PeerId::random());
+    let now = 30 * DAY_MS;
+    let book = AddrBook::default();
+    let flaky_addr = addr("/ip4/10.0.0.1/tcp/4001");
+    for _ in 0..3 {
+        book.record_success(flaky, flaky_addr.clone(), now);
+        book.record_failure(&flaky, &flaky_addr);
+        book.record_failure(&flaky, &flaky_addr);
+    }
+    book.record_success(steady, addr("/ip4/10.0.0.2/tcp/4001"), now - DAY_MS);
+    book.record_success(steady, addr("/ip4/10.0.0.2/tcp/4001"), now - DAY_MS);
+    book.record_success(steady, addr("/ip4/10.0.0.2/tcp/4002"), now);
+    book.record_success(fresh, addr("/ip4/10.0.0.3/tcp/4001"), now);
+    // Failures of addresses never verified are not recorded.
+    book.record_failure(&fresh, &addr("/ip4/10.0.0.3/tcp/9"));
+    book.save(&path).unwrap();
+
+    let loaded = AddrBook::load(&path).unwrap();
+    assert_eq!(loaded.len(), 4);
+    let stats = loaded.stats(&flaky, &flaky_addr).unwrap();
+    assert_eq!(
+        (stats.successes, stats.failures, stats.last_seen_ms),
+        (3, 6, now)
+    );
+    assert!(stats.quality() < 0.5);
+    assert_eq!(
+        loaded.best(2),
+        vec![
+            (steady, addr("/ip4/10.0.0.2/tcp/4001")),
+            (fresh, addr("/ip4/10.0.0.3/tcp/4001")),
+        ]
+    );
+    assert_eq!(loaded.best(10).len(), 3);
+
+    // Addresses not reached within the TTL go, and peers left without any with them.
+    let later = now - DAY_MS + ADDR_TTL.as_millis() as u64 + 1;
+    assert_eq!(loaded.expire(later, ADDR_TTL), 1);
+    assert_eq!(
+        loaded.addresses(&steady),
+        vec![addr("/ip4/10.0.0.2/tcp/4002")]
+    );
+    assert_eq!(loaded.expire(later + DAY_MS, ADDR_TTL), 3);
+    assert!(loaded.peers().is_empty());
+}
+
+#[test]
+fn unreadable_books_are_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(PEERS_FILE);
+    std::fs::write(&path, b"{not json").unwrap();
+    assert!(matches!(
+        AddrBook::load(&path),
+        Err(AddrBookError::Malformed { .. })
+    ));
+    std::fs::write(&path, br#"{"version":9,"peers":[]}"#).unwrap();
+    assert!(matches!(
+        AddrBook::load(&path),
+        Err(AddrBookError::Malformed { .. })
+    ));
+    // Entries that do not parse are skipped, the rest loads.
+    let peer = PeerId::random();
+    let body = format!(
+        r#"{{"version":1,"peers":[
+            {{"peer_id":"nope","addrs":[{{"addr":"/ip4/1.2.3.4/tcp/1","last_seen_ms":1,"successes":1,"failures":0}}]}},
+            {{"peer_id":"{peer}","addrs":[
+                {{"addr":"garbage","last_seen_ms":1,"successes":1,"failures":0}},
+                {{"addr":"/ip4/1.2.3.4/tcp/1","last_seen_ms":1,"successes":1,"failures":0}}]}}]}}"#
+    );
+    std::fs::write(&path, body).unwrap();
+    let book = AddrBook::load(&path).unwrap();
+    assert_eq!(book.peers(), vec![peer]);
+    assert_eq!(book.len(), 1);
+}
+
+#[tokio::test]
+async fn known_peers_are_redialed_after_restart() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let (dir_a, dir_b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    let (peer_a, _) = load_identity_with(dir_a.path(), IdentitySource::File, None).unwrap();
+    let listen = addr(&format!("/ip4/127.0.0.1/tcp/{port}"));
+
+    // b remembers a from an earlier run and has no bootstrap peers.
+    let saved = AddrBook::default();
+    let seen = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    saved.record_success(peer_a, listen.clone(), seen);
+    saved.save(&dir_b.path().join(PEERS_FILE)).unwrap();
+
+    let (_a, _events_a, _join_a) = spawn_p2p(
+        config(&dir_a, port, Vec::new()),
+        Arc::new(Metrics::new().unwrap()),
+    )
+    .unwrap();
+    let metrics_b = Arc::new(Metrics::new().unwrap());
+    let (b, _events_b, join_b) =
+        spawn_p2p(config(&dir_b, 0, Vec::new()), metrics_b.clone()).unwrap();
+    assert_eq!(metrics_b.p2p_known_addrs.get(), 1);
+
+    let caps = b.capabilities();
+    tokio::time::timeout(Duration::from_secs(30), async {
+        while caps.get(&peer_a.to_string()).is_none() {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("b reconnected to a");
+    let book = b.addresses();
+    assert_eq!(book.stats(&peer_a, &listen).unwrap().successes, 2);
+
+    // The book is written back when the task ends.
+    b.close();
+    join_b.await.unwrap();
+    let reloaded = AddrBook::load(&dir_b.path().join(PEERS_FILE)).unwrap();
+    assert_eq!(reloaded.stats(&peer_a, &listen).unwrap().successes, 2);
+}
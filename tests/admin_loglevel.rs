@@ -0,0 +1,82 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::types::TelemetryConfig;
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::monitoring::telemetry;
+use amunchain::rpc::server::{bind, serve_listener, RpcState};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+async fn request(addr: SocketAddr, method: &str, token: Option<&str>, body: &str) -> String {
+    let auth = token
+        .map(|t| format!("Authorization: Bearer {t}\r\n"))
+        .unwrap_or_default();
+    let mut s = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let req = format!(
+        "{method} /admin/loglevel HTTP/1.1\r\nHost: x\r\n{auth}Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    );
+    s.write_all(req.as_bytes()).await.unwrap();
+    let mut resp = String::new();
+    s.read_to_string(&mut resp).await.unwrap();
+    resp
+}
+
+#[tokio::test]
+async fn log_filter_is_reconfigurable_over_http() {
+    let guard = telemetry::init(&TelemetryConfig::default()).unwrap();
+    let filter = guard.log_filter();
+    assert!(!tracing::enabled!(target: "amunchain::networking::p2p", tracing::Level::DEBUG));
+
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let listener = bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let state = RpcState::new(metrics).with_admin("s3cret".to_string(), filter.clone());
+    tokio::spawn(serve_listener(listener, state));
+
+    let directives = "info,amunchain::networking::p2p=debug";
+    assert!(request(addr, "PUT", None, directives)
+        .await
+        .starts_with("HTTP/1.1 401"));
+    assert!(request(addr, "PUT", Some("wrong"), directives)
+        .await
+        .starts_with("HTTP/1.1 401"));
+    assert!(request(addr, "PUT", Some("s3cret"), "info,=[")
+        .await
+        .starts_with("HTTP/1.1 400"));
+    assert_eq!(filter.current(), "info");
+
+    let resp = request(addr, "PUT", Some("s3cret"), directives).await;
+    assert!(resp.starts_with("HTTP/1.1 200"));
+    assert!(resp.ends_with(directives));
+    assert_eq!(filter.current(), directives);
+    assert!(tracing::enabled!(target: "amunchain::networking::p2p", tracing::Level::DEBUG));
+    assert!(!tracing::enabled!(target: "amunchain::rpc", tracing::Level::DEBUG));
+
+    let resp = request(addr, "GET", Some("s3cret"), "").await;
+    assert!(resp.ends_with(directives));
+}
+
+#[tokio::test]
+async fn admin_routes_disabled_without_token() {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let listener = bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_listener(listener, RpcState::new(metrics)));
+    assert!(request(addr, "GET", Some("anything"), "")
+        .await
+        .starts_with("HTTP/1.1 404"));
+}
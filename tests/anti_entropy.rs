@@ -0,0 +1,109 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::config::ConfigLoader;
+use amunchain::core::types::{Height, H256};
+use amunchain::node::anti_entropy::{AntiEntropy, HeadStatus, STATUS_TOPIC};
+use amunchain::node::builder::NodeBuilder;
+use amunchain::node::extensions::GossipHandler;
+
+const PEER: &[u8] = b"peer";
+
+fn head(height: u64) -> HeadStatus {
+    HeadStatus {
+        height: Height(height),
+        block_hash: H256::from_bytes([height as u8; 32]),
+        state_root: H256::from_bytes([0xaa; 32]),
+    }
+}
+
+#[test]
+fn announcements_round_trip_and_are_bounded() {
+    let bytes = head(7).encode().unwrap();
+    assert_eq!(HeadStatus::decode(&bytes).unwrap(), head(7));
+    assert!(HeadStatus::decode(&bytes[..bytes.len() - 1]).is_err());
+    assert!(HeadStatus::decode(&[0u8; 1024]).is_err());
+}
+
+#[test]
+fn only_lag_beyond_the_threshold_triggers_a_sync() {
+    let ae = AntiEntropy::new(2).unwrap();
+    ae.set_local(head(10));
+    let target = ae.sync_target();
+
+    assert_eq!(ae.observe(PEER, head(12)), None);
+    assert_eq!(ae.observe(PEER, head(9)), None);
+    assert!(target.borrow().is_none());
+
+    let t = ae.observe(PEER, head(13)).unwrap();
+    assert_eq!(
+        (t.peer.as_slice(), t.local, t.remote),
+        (PEER, Height(10), head(13))
+    );
+    assert_eq!(
+        target.borrow().as_ref().map(|t| t.remote.height),
+        Some(Height(13))
+    );
+
+    // Only a higher head replaces the target.
+    assert_eq!(ae.observe(b"other", head(13)), None);
+    assert!(ae.observe(b"other", head(20)).is_some());
+    assert_eq!(target.borrow().as_ref().unwrap().peer, b"other");
+}
+
+#[test]
+fn catching_up_clears_the_target() {
+    let ae = AntiEntropy::new(0).unwrap();
+    ae.set_local(head(1));
+    assert!(ae.observe(PEER, head(5)).is_some());
+
+    ae.set_local(head(4));
+    assert!(ae.sync_target().borrow().is_some());
+    ae.set_local(head(5));
+    assert!(ae.sync_target().borrow().is_none());
+}
+
+#[test]
+fn a_diverging_head_at_our_height_is_not_a_target() {
+    let ae = AntiEntropy::new(0).unwrap();
+    ae.set_local(head(3));
+    let fork = HeadStatus {
+        block_hash: H256::from_bytes([0xff; 32]),
+        ..head(3)
+    };
+    assert_eq!(ae.observe(PEER, fork), None);
+}
+
+#[test]
+fn gossip_handler_decodes_and_ignores_garbage() {
+    let ae = AntiEntropy::new(1).unwrap();
+    ae.on_message(PEER, b"not an announcement");
+    assert!(ae.sync_target().borrow().is_none());
+    ae.on_message(PEER, &head(5).encode().unwrap());
+    assert!(ae.sync_target().borrow().is_some());
+}
+
+#[test]
+fn registers_the_status_topic_and_defaults_from_config() {
+    let ext = NodeBuilder::new()
+        .extension(AntiEntropy::new(2).unwrap())
+        .build()
+        .unwrap();
+    assert!(ext.topics().contains_key(STATUS_TOPIC));
+
+    let cfg = ConfigLoader::new().load().unwrap();
+    assert_eq!(
+        (cfg.p2p.status_interval_secs, cfg.p2p.sync_lag_threshold),
+        (10, 2)
+    );
+}
@@ -0,0 +1,89 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::security::audit::{self, AuditError, AuditLog};
+use std::fs;
+
+#[test]
+fn export_spans_rotation_and_detects_tampering() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = AuditLog::open(dir.path());
+    for i in 0..10u8 {
+        log.append("sign", &[i]).unwrap();
+    }
+    // Rotate as the writer would once the file is full.
+    fs::rename(dir.path().join("audit.log"), dir.path().join("audit.log.1")).unwrap();
+    for i in 10..20u8 {
+        log.append("sign", &[i]).unwrap();
+    }
+
+    // A fresh writer resumes the chain from disk.
+    AuditLog::open(dir.path()).append("sign", b"x").unwrap();
+    let chain = audit::read_chain(dir.path()).unwrap();
+    assert_eq!(chain.len(), 21);
+    assert_eq!(chain.last().unwrap().seq, 20);
+
+    let out = dir.path().join("export.amunaudit");
+    let manifest = audit::export(dir.path(), 0, u64::MAX, &out).unwrap();
+    assert_eq!(manifest.entries, 21);
+    let (_, entries) = audit::verify_archive(&out).unwrap();
+    assert_eq!(entries, chain);
+
+    // Flip one byte in the last chunk.
+    let mut bytes = fs::read(&out).unwrap();
+    let n = bytes.len();
+    bytes[n - 1] ^= 0xff;
+    fs::write(&out, bytes).unwrap();
+    assert!(matches!(
+        audit::verify_archive(&out),
+        Err(AuditError::ChunkHash(_))
+    ));
+
+    // Dropping a line from the rotated file breaks the chain across the boundary.
+    let rotated = dir.path().join("audit.log.1");
+    let raw = fs::read_to_string(&rotated).unwrap();
+    let trimmed: Vec<&str> = raw.lines().take(9).collect();
+    fs::write(&rotated, trimmed.join("\n") + "\n").unwrap();
+    assert!(matches!(
+        audit::export(dir.path(), 0, u64::MAX, &out),
+        Err(AuditError::ChainBroken(10))
+    ));
+}
+
+#[test]
+fn manifest_entry_count_is_not_trusted() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = AuditLog::open(dir.path());
+    for i in 0..3u8 {
+        log.append("sign", &[i]).unwrap();
+    }
+    let out = dir.path().join("export.amunaudit");
+    audit::export(dir.path(), 0, u64::MAX, &out).unwrap();
+
+    // Rewrite the manifest (magic, u32 length, JSON) to claim u64::MAX entries.
+    let bytes = fs::read(&out).unwrap();
+    let len = u32::from_be_bytes(bytes[8..12].try_into().unwrap()) as usize;
+    let mut manifest: serde_json::Value = serde_json::from_slice(&bytes[12..12 + len]).unwrap();
+    manifest["entries"] = serde_json::json!(u64::MAX);
+    let manifest = serde_json::to_vec(&manifest).unwrap();
+    let mut forged = bytes[..8].to_vec();
+    forged.extend_from_slice(&(manifest.len() as u32).to_be_bytes());
+    forged.extend_from_slice(&manifest);
+    forged.extend_from_slice(&bytes[12 + len..]);
+    fs::write(&out, forged).unwrap();
+
+    assert!(matches!(
+        audit::verify_archive(&out),
+        Err(AuditError::Archive)
+    ));
+}
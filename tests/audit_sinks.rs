@@ -0,0 +1,254 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::security::audit::{AuditEntry, AuditError, AuditLog};
+use amunchain::core::security::audit_sink::{
+    AuditForwarder, AuditSink, FileSink, HttpSink, SyslogSink,
+};
+use amunchain::core::security::keystore::Keystore;
+use amunchain::core::types::{AuditSinkConfig, KeystoreConfig, NodeConfig};
+use amunchain::node::config_check::check;
+use std::io::{Read, Write};
+use std::net::{TcpListener, UdpSocket};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+fn entries(n: u8) -> Vec<AuditEntry> {
+    let dir = tempfile::tempdir().unwrap();
+    let log = AuditLog::open(dir.path());
+    (0..n).map(|i| log.append("sign", &[i]).unwrap()).collect()
+}
+
+fn wait_for(mut done: impl FnMut() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !done() {
+        assert!(Instant::now() < deadline, "timed out");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn file_sink_appends_json_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("siem.jsonl");
+    let sink = FileSink::open(&path).unwrap();
+    let es = entries(3);
+    for e in &es {
+        sink.deliver(e).unwrap();
+    }
+    let got: Vec<AuditEntry> = std::fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(got, es);
+}
+
+#[test]
+fn syslog_sink_sends_rfc5424_over_udp() {
+    let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+    server
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let sink = SyslogSink::connect(&format!("udp://{}", server.local_addr().unwrap())).unwrap();
+    let mut e = entries(1).remove(0);
+    e.ts_ms = 1_700_000_000_123;
+    sink.deliver(&e).unwrap();
+
+    let mut buf = [0u8; 4096];
+    let n = server.recv(&mut buf).unwrap();
+    let msg = std::str::from_utf8(&buf[..n]).unwrap();
+    assert!(msg.starts_with("<85>1 2023-11-14T22:13:20.123Z "), "{msg}");
+    let json = msg.split(" audit - ").nth(1).unwrap();
+    assert_eq!(serde_json::from_str::<AuditEntry>(json).unwrap(), e);
+}
+
+/// Accepts `n` requests, answering each with `status`, and sends their bodies to the channel.
+fn http_collector(n: usize, status: u16) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/ingest", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().take(n) {
+            let mut s = stream.unwrap();
+            let mut req = Vec::new();
+            let mut buf = [0u8; 4096];
+            // Headers, then Content-Length bytes of body.
+            let body = loop {
+                let k = s.read(&mut buf).unwrap();
+                req.extend_from_slice(&buf[..k]);
+                let text = String::from_utf8_lossy(&req).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let len: usize = head
+                        .lines()
+                        .find_map(|l| l.strip_prefix("Content-Length: "))
+                        .unwrap()
+                        .parse()
+                        .unwrap();
+                    if body.len() >= len {
+                        assert!(head.starts_with("POST /ingest HTTP/1.1"));
+                        break body.to_string();
+                    }
+                }
+            };
+            s.write_all(format!("HTTP/1.1 {status} X\r\nContent-Length: 0\r\n\r\n").as_bytes())
+                .unwrap();
+            tx.send(body).unwrap();
+        }
+    });
+    (url, rx)
+}
+
+#[test]
+fn http_sink_posts_entries_and_requires_2xx() {
+    let e = entries(1).remove(0);
+    let (url, rx) = http_collector(1, 204);
+    HttpSink::new(&url, Duration::from_secs(5))
+        .unwrap()
+        .deliver(&e)
+        .unwrap();
+    let body = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(serde_json::from_str::<AuditEntry>(&body).unwrap(), e);
+
+    let (url, _rx) = http_collector(1, 500);
+    assert!(matches!(
+        HttpSink::new(&url, Duration::from_secs(5))
+            .unwrap()
+            .deliver(&e),
+        Err(AuditError::Io)
+    ));
+    assert!(HttpSink::new("https://siem.example", Duration::from_secs(1)).is_err());
+}
+
+/// Blocks every delivery until released.
+struct Stuck {
+    gate: Mutex<mpsc::Receiver<()>>,
+}
+
+impl AuditSink for Stuck {
+    fn name(&self) -> &str {
+        "stuck"
+    }
+
+    fn deliver(&self, _entry: &AuditEntry) -> Result<(), AuditError> {
+        let _ = self.gate.lock().unwrap().recv();
+        Ok(())
+    }
+}
+
+#[test]
+fn slow_sink_drops_without_blocking_the_caller() {
+    let (release, gate) = mpsc::channel();
+    let stuck: Arc<dyn AuditSink> = Arc::new(Stuck {
+        gate: Mutex::new(gate),
+    });
+    let dir = tempfile::tempdir().unwrap();
+    let file: Arc<dyn AuditSink> = Arc::new(FileSink::open(dir.path().join("a.jsonl")).unwrap());
+    let fwd = AuditForwarder::new(vec![stuck, file], 4).unwrap();
+
+    let start = Instant::now();
+    for e in entries(50) {
+        fwd.forward(&e);
+    }
+    assert!(start.elapsed() < Duration::from_secs(2));
+
+    // The stuck worker holds one entry and its queue four more: the rest are dropped.
+    let stats = fwd.stats();
+    assert_eq!(stats[0].name, "stuck");
+    assert!(stats[0].dropped >= 45, "{stats:?}");
+    // The healthy sink is unaffected by its neighbour.
+    wait_for(|| {
+        let s = &fwd.stats()[1];
+        s.delivered + s.dropped == 50
+    });
+
+    for _ in 0..50 {
+        let _ = release.send(());
+    }
+    wait_for(|| {
+        let s = &fwd.stats()[0];
+        s.delivered + s.dropped == 50
+    });
+}
+
+#[test]
+fn keystore_forwards_signing_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("siem.jsonl");
+    let fwd = AuditForwarder::from_config(&KeystoreConfig {
+        audit_sinks: vec![AuditSinkConfig::File {
+            path: out.to_str().unwrap().to_string(),
+        }],
+        audit_queue_len: 16,
+        ..KeystoreConfig::default()
+    })
+    .unwrap()
+    .unwrap();
+    let ks = Keystore::open(dir.path().to_str().unwrap())
+        .unwrap()
+        .with_audit_forwarder(fwd);
+    ks.sign(b"block 1").unwrap();
+    ks.sign(b"block 2").unwrap();
+    wait_for(|| ks.audit_sink_stats()[0].delivered == 2);
+
+    let local = std::fs::read_to_string(dir.path().join("audit.log")).unwrap();
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), local);
+    assert!(AuditForwarder::from_config(&KeystoreConfig::default())
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn config_check_rejects_bad_sinks() {
+    let mut cfg: NodeConfig = toml::from_str(
+        r#"
+[node]
+name = "t"
+data_dir = "data"
+[http]
+listen_addr = "127.0.0.1:9090"
+[p2p]
+listen_addr = "/ip4/0.0.0.0/tcp/30333"
+topic = "t"
+max_msg_per_sec = 200
+max_peers_per_ip = 3
+bootstrap = []
+allow_peers = []
+[consensus]
+validators_hex = ["1111111111111111111111111111111111111111111111111111111111111111"]
+[keystore]
+audit_queue_len = 0
+[[keystore.audit_sinks]]
+kind = "syslog"
+addr = "tcp://127.0.0.1:514"
+[[keystore.audit_sinks]]
+kind = "http"
+url = "https://siem.example/ingest"
+[[keystore.audit_sinks]]
+kind = "syslog"
+addr = "udp://127.0.0.1:514"
+"#,
+    )
+    .unwrap();
+    let fields: Vec<String> = check(&cfg).into_iter().map(|i| i.field).collect();
+    assert_eq!(
+        fields,
+        vec![
+            "keystore.audit_queue_len",
+            "keystore.audit_sinks[0].addr",
+            "keystore.audit_sinks[1].url",
+        ]
+    );
+    cfg.keystore = KeystoreConfig::default();
+    assert!(check(&cfg).is_empty());
+}
@@ -0,0 +1,221 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use std::path::Path;
+
+use amunchain::core::security::keystore;
+use amunchain::core::security::sign_policy::SignDomain;
+use amunchain::core::state::persistent_state::{KvOp, PersistentState};
+use amunchain::node::backup::{
+    backup, manifest_signing_bytes, read_manifest, restore, BackupError, FileEntry, MANIFEST_FILE,
+};
+use common::fresh_copy;
+use ring::signature::KeyPair;
+
+// A stopped node's data directory with some state; returns the validator key (hex) if
+// `with_key`. Tests back up a `fresh_copy` of it, as the dropped state may still hold its lock.
+fn data_dir(dir: &Path, with_key: bool) -> Option<String> {
+    let state = PersistentState::open(&dir.join("state").to_string_lossy()).unwrap();
+    let ops = (0u8..16)
+        .map(|i| KvOp::Put {
+            key: vec![b'k', i],
+            value: vec![i; 64],
+        })
+        .collect();
+    state.commit_atomic(ops).unwrap();
+    state.flush().unwrap();
+    drop(state);
+    std::fs::write(dir.join("p2p_identity.key"), b"identity").unwrap();
+    std::fs::write(dir.join("peers.json"), b"{}").unwrap();
+    with_key.then(|| {
+        let kp = keystore::generate_key_file(&dir.join("validator.key"), None).unwrap();
+        hex::encode(kp.public_key().as_ref())
+    })
+}
+
+fn root_of(dir: &Path) -> String {
+    let copy = fresh_copy(&dir.join("state"));
+    let state = PersistentState::open(&copy.path().to_string_lossy()).unwrap();
+    hex::encode(state.state_root().unwrap())
+}
+
+fn rewrite_manifest(dir: &Path, edit: impl FnOnce(&mut serde_json::Value)) {
+    let path = dir.join(MANIFEST_FILE);
+    let mut manifest: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    edit(&mut manifest);
+    std::fs::write(&path, serde_json::to_vec(&manifest).unwrap()).unwrap();
+}
+
+#[test]
+fn signed_backup_restores_without_keys() {
+    let (src, out, dst) = (
+        tempfile::tempdir().unwrap(),
+        tempfile::tempdir().unwrap(),
+        tempfile::tempdir().unwrap(),
+    );
+    let validator = data_dir(src.path(), true).unwrap();
+    let src = fresh_copy(src.path());
+    let dest = out.path().join("backup");
+
+    let info = backup(src.path(), &dest, false).unwrap();
+    assert_eq!(info.state_root, root_of(src.path()));
+    assert_eq!(info.signed_by.as_deref(), Some(validator.as_str()));
+    let manifest = read_manifest(&dest).unwrap();
+    let paths: Vec<&str> = manifest
+        .body
+        .files
+        .iter()
+        .map(|f| f.path.as_str())
+        .collect();
+    assert!(paths.contains(&"peers.json"));
+    assert!(paths.iter().any(|p| p.starts_with("state/")));
+    assert!(!paths.contains(&"validator.key") && !paths.contains(&"p2p_identity.key"));
+    assert!(!manifest.body.keys_included);
+    assert!(!dest.join("validator.key").exists());
+    let msg = manifest_signing_bytes(&manifest.body).unwrap();
+    assert_eq!(SignDomain::of(&msg), Some(SignDomain::Backup));
+
+    let trusted = std::slice::from_ref(&validator);
+    let restored = restore(dst.path(), &dest, trusted, false).unwrap();
+    assert_eq!(restored, info);
+    assert_eq!(root_of(dst.path()), info.state_root);
+    assert!(dst.path().join("peers.json").exists());
+    assert!(!dst.path().join("validator.key").exists());
+    assert!(!dst.path().join(".restore").exists());
+
+    // The restored state is no longer empty.
+    assert!(matches!(
+        restore(dst.path(), &dest, trusted, false),
+        Err(BackupError::NotEmpty(_))
+    ));
+}
+
+#[test]
+fn keys_are_copied_only_on_request() {
+    let (src, out) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    data_dir(src.path(), true);
+    let src = fresh_copy(src.path());
+    let dest = out.path().join("backup");
+    backup(src.path(), &dest, true).unwrap();
+    assert!(read_manifest(&dest).unwrap().body.keys_included);
+    assert_eq!(
+        std::fs::read(dest.join("validator.key")).unwrap(),
+        std::fs::read(src.path().join("validator.key")).unwrap()
+    );
+    assert!(dest.join("p2p_identity.key").exists());
+
+    assert!(matches!(
+        backup(src.path(), &dest, false),
+        Err(BackupError::NotEmpty(_))
+    ));
+    assert!(matches!(
+        backup(src.path(), &src.path().join("inner"), false),
+        Err(BackupError::InsideDataDir(_))
+    ));
+}
+
+#[test]
+fn tampered_backups_never_reach_the_data_dir() {
+    let (src, out) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    let validator = data_dir(src.path(), true).unwrap();
+    let src = fresh_copy(src.path());
+    let dest = out.path().join("backup");
+    backup(src.path(), &dest, false).unwrap();
+
+    let restore_into = |trusted: &[String], allow_unsigned: bool| {
+        let dst = tempfile::tempdir().unwrap();
+        let res = restore(dst.path(), &dest, trusted, allow_unsigned);
+        if res.is_err() {
+            let left: Vec<_> = std::fs::read_dir(dst.path()).unwrap().collect();
+            assert!(left.is_empty(), "restore left files behind");
+        }
+        res
+    };
+    let trusted = std::slice::from_ref(&validator);
+
+    assert!(matches!(
+        restore_into(&["00".repeat(32)], false),
+        Err(BackupError::UntrustedSigner(_))
+    ));
+
+    // A file that no longer matches its hash.
+    let peers = dest.join("peers.json");
+    std::fs::write(&peers, b"[]").unwrap();
+    assert!(matches!(
+        restore_into(trusted, false),
+        Err(BackupError::Corrupt { .. })
+    ));
+    std::fs::write(&peers, b"{}").unwrap();
+    restore_into(trusted, false).unwrap();
+
+    // Editing the manifest to match breaks the signature.
+    let manifest = read_manifest(&dest).unwrap();
+    rewrite_manifest(&dest, |m| m["body"]["state_root"] = "00".repeat(32).into());
+    assert!(matches!(
+        restore_into(trusted, false),
+        Err(BackupError::BadSignature)
+    ));
+
+    // Without a signature it needs --allow-unsigned, and still checks the state root.
+    rewrite_manifest(&dest, |m| m["signature"] = serde_json::Value::Null);
+    assert!(matches!(
+        restore_into(trusted, false),
+        Err(BackupError::Unsigned)
+    ));
+    assert!(matches!(
+        restore_into(trusted, true),
+        Err(BackupError::RootMismatch { .. })
+    ));
+    rewrite_manifest(&dest, |m| {
+        m["body"]["state_root"] = manifest.body.state_root.clone().into()
+    });
+    restore_into(trusted, true).unwrap();
+}
+
+#[test]
+fn manifest_paths_cannot_leave_the_data_dir() {
+    let (src, out) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    data_dir(src.path(), false);
+    let src = fresh_copy(src.path());
+    let dest = out.path().join("backup");
+    let info = backup(src.path(), &dest, false).unwrap();
+    assert_eq!(info.signed_by, None);
+
+    for bad in [
+        "../escape",
+        "/etc/passwd",
+        "state/../../x",
+        "a//b",
+        "MANIFEST.json",
+    ] {
+        rewrite_manifest(&dest, |m| {
+            let entry = FileEntry {
+                path: bad.into(),
+                size: 0,
+                sha256: String::new(),
+            };
+            m["body"]["files"] = serde_json::to_value(vec![entry]).unwrap();
+        });
+        let dst = tempfile::tempdir().unwrap();
+        assert!(
+            matches!(
+                restore(dst.path(), &dest, &[], true),
+                Err(BackupError::Manifest(_))
+            ),
+            "{bad}"
+        );
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::networking::churn::{AddrKey, ChurnParams, ChurnTracker, ChurnVerdict};
+use libp2p::PeerId;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+fn params() -> ChurnParams {
+    ChurnParams {
+        window: Duration::from_secs(60),
+        max_ids_per_ip: 3,
+        max_ids_per_subnet: 5,
+        max_suspected: 2,
+        ip_ban_duration: Duration::from_secs(120),
+    }
+}
+
+#[test]
+fn cycling_peer_ids_from_one_ip_bans_the_ip() {
+    let now = Instant::now();
+    let mut churn = ChurnTracker::new(params());
+    let host = ip("198.51.100.7");
+    let ids: Vec<PeerId> = (0..4).map(|_| PeerId::random()).collect();
+    for id in &ids[..3] {
+        assert_eq!(churn.on_inbound(*id, host, now), ChurnVerdict::Allow);
+    }
+    // Reconnecting with a known id is not churn.
+    assert_eq!(churn.on_inbound(ids[0], host, now), ChurnVerdict::Allow);
+    assert_eq!(
+        churn.on_inbound(ids[3], host, now),
+        ChurnVerdict::Refused {
+            escalated: Some(AddrKey::Ip(host))
+        }
+    );
+    assert_eq!(churn.banned_addr(host), Some(AddrKey::Ip(host)));
+    // Any id from the banned IP is refused; its neighbours are not.
+    assert_eq!(
+        churn.on_inbound(ids[0], host, now),
+        ChurnVerdict::Refused { escalated: None }
+    );
+    assert_eq!(
+        churn.on_inbound(PeerId::random(), ip("198.51.100.8"), now),
+        ChurnVerdict::Allow
+    );
+
+    assert!(churn.expire(now + Duration::from_secs(119)).is_empty());
+    assert_eq!(
+        churn.expire(now + Duration::from_secs(120)),
+        vec![AddrKey::Ip(host)]
+    );
+    assert_eq!(churn.banned_len(), 0);
+    assert_eq!(
+        churn.on_inbound(PeerId::random(), host, now + Duration::from_secs(121)),
+        ChurnVerdict::Allow
+    );
+}
+
+#[test]
+fn ids_older_than_the_window_do_not_count() {
+    let now = Instant::now();
+    let mut churn = ChurnTracker::new(params());
+    let host = ip("198.51.100.7");
+    for i in 0..10u64 {
+        let at = now + Duration::from_secs(30 * i);
+        assert_eq!(
+            churn.on_inbound(PeerId::random(), host, at),
+            ChurnVerdict::Allow
+        );
+    }
+}
+
+#[test]
+fn subnet_churn_bans_the_subnet() {
+    let now = Instant::now();
+    let mut churn = ChurnTracker::new(params());
+    for last in 1..=5 {
+        let host = ip(&format!("203.0.113.{last}"));
+        assert_eq!(
+            churn.on_inbound(PeerId::random(), host, now),
+            ChurnVerdict::Allow
+        );
+    }
+    let subnet = AddrKey::subnet_of(ip("203.0.113.200"));
+    assert_eq!(subnet, AddrKey::Subnet(ip("203.0.113.0")));
+    assert_eq!(
+        churn.on_inbound(PeerId::random(), ip("203.0.113.9"), now),
+        ChurnVerdict::Refused {
+            escalated: Some(subnet)
+        }
+    );
+    assert_eq!(churn.banned_addr(ip("203.0.113.250")), Some(subnet));
+    assert!(subnet.covers(ip("203.0.113.1")));
+    assert!(!subnet.covers(ip("203.0.114.1")));
+    assert_eq!(
+        AddrKey::subnet_of(ip("2001:db8:1:2:3:4:5:6")),
+        AddrKey::Subnet(ip("2001:db8:1:2::"))
+    );
+}
+
+#[test]
+fn new_ids_after_a_ban_are_suspected_then_refused() {
+    let now = Instant::now();
+    let mut churn = ChurnTracker::new(params());
+    let host = ip("192.0.2.10");
+    let offender = PeerId::random();
+    assert_eq!(churn.on_inbound(offender, host, now), ChurnVerdict::Allow);
+    churn.connected(offender, host);
+    churn.on_banned(&offender, now);
+    churn.disconnected(&offender);
+
+    assert_eq!(
+        churn.on_inbound(PeerId::random(), host, now),
+        ChurnVerdict::Suspected
+    );
+    assert_eq!(
+        churn.on_inbound(PeerId::random(), host, now),
+        ChurnVerdict::Refused {
+            escalated: Some(AddrKey::Ip(host))
+        }
+    );
+
+    // Once the window passes, the IP is no longer tainted.
+    let mut churn = ChurnTracker::new(params());
+    churn.connected(offender, host);
+    churn.on_banned(&offender, now);
+    churn.expire(now + Duration::from_secs(61));
+    assert_eq!(
+        churn.on_inbound(PeerId::random(), host, now + Duration::from_secs(61)),
+        ChurnVerdict::Allow
+    );
+}
@@ -0,0 +1,197 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::economics::bank::{Bank, BankError, BONDED_POOL, REWARDS_POOL};
+use amunchain::core::economics::staking::{Offense, StakingError, StakingLedger, StakingParams};
+use amunchain::core::state::persistent_state::PersistentState;
+
+fn supply_matches(bank: &Bank) -> bool {
+    bank.balances.values().sum::<u128>() == bank.total_supply
+}
+
+#[test]
+fn transfer_mint_burn() {
+    let mut bank = Bank::default();
+    bank.mint(b"alice", 100).unwrap();
+    bank.transfer(b"alice", b"bob", 40).unwrap();
+    assert_eq!(bank.balance(b"alice"), 60);
+    assert_eq!(bank.balance(b"bob"), 40);
+    assert!(matches!(
+        bank.transfer(b"bob", b"alice", 41),
+        Err(BankError::InsufficientFunds)
+    ));
+    assert!(bank.transfer(b"bob", b"alice", 0).is_err());
+
+    bank.burn(b"bob", 40).unwrap();
+    assert_eq!(bank.total_supply, 60);
+    assert!(!bank.balances.contains_key(b"bob".as_slice()));
+    assert!(supply_matches(&bank));
+
+    assert!(matches!(
+        bank.mint(b"alice", u128::MAX),
+        Err(BankError::Overflow)
+    ));
+
+    // A supply that disagrees with the balances is refused, not wrapped.
+    let mut skewed = bank.clone();
+    skewed.total_supply = 10;
+    let before = skewed.clone();
+    assert!(matches!(
+        skewed.burn(b"alice", 60),
+        Err(BankError::SupplyUnderflow)
+    ));
+    assert_eq!(skewed, before);
+}
+
+#[test]
+fn bonding_moves_balances() {
+    let mut bank = Bank::default();
+    bank.mint(b"d", 100).unwrap();
+    let mut l = StakingLedger::with_params(StakingParams {
+        unbonding_period_secs: 10,
+        ..StakingParams::default()
+    })
+    .unwrap();
+    l.register_validator(b"v".to_vec(), 0, 0).unwrap();
+
+    assert!(matches!(
+        l.bond_from(&mut bank, b"d".to_vec(), b"v".to_vec(), 101),
+        Err(StakingError::Bank(BankError::InsufficientFunds))
+    ));
+    assert!(l.delegations.is_empty());
+
+    l.bond_from(&mut bank, b"d".to_vec(), b"v".to_vec(), 70)
+        .unwrap();
+    assert_eq!(bank.balance(b"d"), 30);
+    assert_eq!(bank.balance(BONDED_POOL), 70);
+
+    l.begin_unbond(b"d".to_vec(), b"v".to_vec(), 70, 0, 1)
+        .unwrap();
+    assert_eq!(
+        l.finalize_unbond_to(&mut bank, b"d".to_vec(), b"v".to_vec(), 9)
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        l.finalize_unbond_to(&mut bank, b"d".to_vec(), b"v".to_vec(), 10)
+            .unwrap(),
+        70
+    );
+    assert_eq!(bank.balance(b"d"), 100);
+    assert_eq!(bank.balance(BONDED_POOL), 0);
+    assert!(supply_matches(&bank));
+}
+
+#[test]
+fn slashing_burns_bonded_stake() {
+    let mut bank = Bank::default();
+    bank.mint(b"d", 1_000).unwrap();
+    let mut l = StakingLedger::default();
+    l.register_validator(b"v".to_vec(), 0, 0).unwrap();
+    l.bond_from(&mut bank, b"d".to_vec(), b"v".to_vec(), 1_000)
+        .unwrap();
+
+    let slashed = l
+        .slash_from(&mut bank, b"v", Offense::DoubleSign, 1, 0)
+        .unwrap();
+    assert!(slashed > 0);
+    assert_eq!(bank.total_supply, 1_000 - slashed);
+    assert_eq!(bank.balance(BONDED_POOL), 1_000 - slashed);
+    assert!(supply_matches(&bank));
+
+    // Stake bonded without the bank is not in the pool and cannot be burned.
+    l.bond(b"e".to_vec(), b"v".to_vec(), 1_000_000).unwrap();
+    let before = bank.clone();
+    let ledger_before = l.clone();
+    assert!(matches!(
+        l.slash_from(&mut bank, b"v", Offense::DoubleSign, 1, 0),
+        Err(StakingError::Bank(BankError::InsufficientFunds))
+    ));
+    assert_eq!(bank, before);
+    assert_eq!(l, ledger_before);
+}
+
+#[test]
+fn staking_conserves_supply() {
+    let mut bank = Bank::default();
+    bank.mint(b"v", 500).unwrap();
+    bank.mint(b"d", 1_000).unwrap();
+    let mut l = StakingLedger::with_params(StakingParams {
+        min_self_stake: 100,
+        ..StakingParams::default()
+    })
+    .unwrap();
+
+    assert!(matches!(
+        l.register_validator_from(&mut bank, b"v".to_vec(), 1_000, 501),
+        Err(StakingError::Bank(BankError::InsufficientFunds))
+    ));
+    l.register_validator_from(&mut bank, b"v".to_vec(), 1_000, 200)
+        .unwrap();
+    l.register_validator_from(&mut bank, b"v".to_vec(), 1_000, 300)
+        .unwrap();
+    assert!(matches!(
+        l.register_validator_from(&mut bank, b"v".to_vec(), 1_000, 250),
+        Err(StakingError::InvalidAmount)
+    ));
+    assert_eq!(bank.balance(b"v"), 200);
+    l.bond_from(&mut bank, b"d".to_vec(), b"v".to_vec(), 1_000)
+        .unwrap();
+    assert_eq!(bank.balance(BONDED_POOL), 1_300);
+
+    // Rewards are the only new tokens.
+    assert_eq!(l.distribute_rewards_from(&mut bank, b"v", 100).unwrap(), 10);
+    assert_eq!(bank.total_supply, 1_500 + 100);
+    assert_eq!(l.claim_rewards_to(&mut bank, b"d", b"v").unwrap(), 90);
+    assert_eq!(l.claim_commission_to(&mut bank, b"v").unwrap(), 10);
+    assert_eq!(bank.balance(b"d"), 90);
+    assert_eq!(bank.balance(b"v"), 210);
+    assert_eq!(bank.balance(REWARDS_POOL), 0);
+    assert_eq!(l.claim_rewards_to(&mut bank, b"d", b"v").unwrap(), 0);
+
+    // Slashing burns self stake and delegations alike.
+    let slashed = l
+        .slash_from(&mut bank, b"v", Offense::DoubleSign, 1, 0)
+        .unwrap();
+    assert_eq!(slashed, 15 + 50);
+    assert_eq!(bank.balance(BONDED_POOL), 1_300 - slashed);
+    assert_eq!(bank.total_supply, 1_600 - slashed);
+    assert!(supply_matches(&bank));
+
+    // Rewards minted without the bank cannot be paid out.
+    l.distribute_rewards(b"v", 100);
+    let before = bank.clone();
+    assert!(matches!(
+        l.claim_rewards_to(&mut bank, b"d", b"v"),
+        Err(StakingError::Bank(BankError::InsufficientFunds))
+    ));
+    assert!(l.pending_rewards(b"d", b"v") > 0);
+    assert_eq!(bank, before);
+}
+
+#[test]
+fn balances_persist() {
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    let mut bank = Bank::default();
+    bank.mint(b"a", 5).unwrap();
+    bank.mint(b"b", 7).unwrap();
+    bank.commit(&st).unwrap();
+    assert_eq!(Bank::load(&st).unwrap(), bank);
+
+    // Emptied accounts are removed from state.
+    bank.transfer(b"a", b"b", 5).unwrap();
+    bank.commit(&st).unwrap();
+    assert_eq!(Bank::load(&st).unwrap(), bank);
+    assert_eq!(st.scan_prefix(b"bank/v1/bal/").unwrap().len(), 1);
+}
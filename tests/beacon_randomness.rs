@@ -0,0 +1,188 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::beacon::{BeaconError, BeaconStore, RandomnessBeacon};
+use amunchain::core::consensus::driver::{ConsensusDriver, MsgOutcome};
+use amunchain::core::consensus::hydro::HydroConfig;
+use amunchain::core::state::commit_store::CommitStore;
+use amunchain::core::state::header_store::HeaderStore;
+use amunchain::core::state::persistent_state::PersistentState;
+use amunchain::core::types::{Block, BlockHeader, ConsensusMsg, Epoch, Height, ValidatorId, H256};
+use common::{keypairs, validators};
+use proptest::prelude::*;
+
+fn header(height: u64, vrf_output: u8) -> BlockHeader {
+    BlockHeader {
+        height: Height(height),
+        parent_hash: H256::ZERO,
+        slot: height,
+        timestamp_ms: 0,
+        producer: ValidatorId::from_bytes([0; 32]),
+        vrf_output: H256::from_bytes([vrf_output; 32]),
+        vrf_proof: Vec::new(),
+        tx_root: Block::tx_root(&[]),
+        state_root: H256::ZERO,
+        pow_nonce: 0,
+    }
+}
+
+proptest! {
+    #[test]
+    fn prop_beacon_deterministic_and_input_sensitive(
+        outputs in prop::collection::vec(any::<[u8; 32]>(), 4..4 * 3),
+        flip in any::<usize>(),
+    ) {
+        let mut a = RandomnessBeacon::genesis(4, [9u8; 32]).unwrap();
+        let mut b = a.clone();
+        let mut c = a.clone();
+        let flip = flip % outputs.len();
+
+        let mut rotations = 0;
+        for (i, o) in outputs.iter().enumerate() {
+            let h = Height(i as u64 + 1);
+            let ra = a.absorb(h, o).unwrap();
+            let rb = b.absorb(h, o).unwrap();
+            prop_assert_eq!(ra, rb);
+            if ra.is_some() {
+                rotations += 1;
+            }
+            let mut oc = *o;
+            if i == flip {
+                oc[0] ^= 1;
+            }
+            c.absorb(h, &oc).unwrap();
+        }
+        prop_assert_eq!(rotations, outputs.len() / 4);
+        prop_assert_eq!(a.epoch, Epoch(1 + rotations as u64));
+        // Changing any VRF output in a closed epoch changes the derived randomness.
+        if flip < rotations * 4 {
+            prop_assert_ne!(a.randomness, c.randomness);
+        }
+    }
+}
+
+#[test]
+fn beacon_persists_and_feeds_hydro() {
+    let mut beacon = RandomnessBeacon::genesis(2, [1u8; 32]).unwrap();
+    assert!(beacon.absorb(Height(1), &[3u8; 32]).unwrap().is_none());
+    let r = beacon
+        .absorb(Height(2), &[4u8; 32])
+        .unwrap()
+        .expect("epoch closed");
+    assert_ne!(r, [1u8; 32]);
+    assert!(beacon.absorb(Height(2), &[4u8; 32]).is_err());
+
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    let root = st.state_root().unwrap();
+    let store = BeaconStore::open(&st).unwrap();
+    assert_eq!(store.load().unwrap(), None);
+    store.save(&beacon).unwrap();
+    let loaded = store.load().unwrap().unwrap();
+    assert_eq!(loaded, beacon);
+    assert_eq!(store.randomness(Epoch(2)).unwrap(), Some(r));
+    assert_eq!(store.randomness(Epoch(3)).unwrap(), None);
+    // Nodes absorb headers as they see them: the beacon stays out of the state root.
+    assert_eq!(st.state_root().unwrap(), root);
+
+    let mut hydro = HydroConfig {
+        genesis_time_ms: 0,
+        slot_ms: 1000,
+        skew_ms: 100,
+        epoch_randomness: [1u8; 32],
+        chain_id: "amunchain-devnet".to_string(),
+    };
+    loaded.apply(&mut hydro);
+    assert_eq!(hydro.epoch_randomness, r);
+}
+
+#[test]
+fn beacon_rejects_skipped_heights() {
+    let mut beacon = RandomnessBeacon::genesis(2, [1u8; 32]).unwrap();
+    beacon.absorb(Height(1), &[3u8; 32]).unwrap();
+    let before = beacon.clone();
+
+    // Skipping height 2 would also skip the epoch rollover.
+    assert!(matches!(
+        beacon.absorb(Height(3), &[4u8; 32]),
+        Err(BeaconError::NonContiguousHeight {
+            expected: Height(2),
+            got: Height(3),
+        })
+    ));
+    assert_eq!(beacon, before);
+
+    assert!(beacon.absorb(Height(2), &[4u8; 32]).unwrap().is_some());
+    assert_eq!(beacon.epoch, Epoch(2));
+}
+
+#[test]
+fn driver_feeds_the_beacon_from_finalized_headers() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    let root = st.state_root().unwrap();
+    let headers = HeaderStore::open(&st).unwrap();
+    let store = BeaconStore::open(&st).unwrap();
+    let genesis = RandomnessBeacon::genesis(2, [1u8; 32]).unwrap();
+    let mut d = ConsensusDriver::new(set.clone())
+        .unwrap()
+        .with_commit_store(CommitStore::open(&st).unwrap())
+        .with_beacon(
+            genesis.clone(),
+            Box::new(headers.clone()),
+            Some(store.clone()),
+        );
+    let snapshots = d.snapshots();
+
+    let (h1, h2) = (header(1, 3), header(2, 4));
+    let finalize = |d: &mut ConsensusDriver, h: &BlockHeader| {
+        for kp in &kps[..3] {
+            let v = common::vote(kp, &set, h.height.get(), h.hash().unwrap());
+            assert_eq!(
+                d.on_peer_msg(b"peer", ConsensusMsg::Vote(v)),
+                MsgOutcome::Accepted
+            );
+        }
+        assert_eq!(d.tide.finalized_height(), h.height);
+    };
+
+    // Height 1 is final but its header has not arrived: the beacon waits for it.
+    finalize(&mut d, &h1);
+    assert_eq!(d.beacon().unwrap().last_height, Height::ZERO);
+
+    // Once it has, the next finalization absorbs it from the commit store, then height 2,
+    // which closes the epoch.
+    for h in [&h1, &h2] {
+        assert_eq!(headers.put(h).unwrap(), h.hash().unwrap());
+    }
+    finalize(&mut d, &h2);
+    let mut expected = genesis;
+    expected.absorb(Height(1), &[3u8; 32]).unwrap();
+    expected.absorb(Height(2), &[4u8; 32]).unwrap();
+    assert_eq!(d.beacon(), Some(&expected));
+    assert_eq!(d.beacon().unwrap().epoch, Epoch(2));
+
+    // The store keeps the beacon and every epoch's randomness, outside the state root.
+    assert_eq!(store.load().unwrap(), Some(expected.clone()));
+    assert_eq!(store.randomness(Epoch(1)).unwrap(), Some([1u8; 32]));
+    assert_eq!(
+        store.randomness(Epoch(2)).unwrap(),
+        Some(expected.randomness)
+    );
+    assert_eq!(st.state_root().unwrap(), root);
+    assert_eq!(snapshots.borrow().beacon, Some(expected));
+}
@@ -0,0 +1,447 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::consensus::beacon::{BeaconStore, RandomnessBeacon};
+use amunchain::core::consensus::hydro::HydroConfig;
+use amunchain::core::consensus::import::{
+    BlockExecutor, BlockImporter, BlockSource, ChainView, ForkChoice, ImportError, ImportOutcome,
+    ImportStage, VrfVerifier,
+};
+use amunchain::core::consensus::orphans::{OrphanConfig, OrphanPool};
+use amunchain::core::state::persistent_state::PersistentState;
+use amunchain::core::types::{Block, BlockHeader, Height, ValidatorId, H256};
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::node::channel::{channel, ChannelConfig};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+const SLOT_MS: u64 = 1_000;
+
+type Store = Arc<Mutex<HashMap<H256, BlockHeader>>>;
+
+struct Chain(Store);
+
+impl ChainView for Chain {
+    fn header(&self, hash: &H256) -> Option<BlockHeader> {
+        self.0.lock().unwrap().get(hash).cloned()
+    }
+}
+
+impl ForkChoice for Chain {
+    fn on_block(&mut self, hash: H256, block: Block) -> Result<(), String> {
+        self.0.lock().unwrap().insert(hash, block.header);
+        Ok(())
+    }
+}
+
+/// Accepts a proof equal to the output followed by the transcript.
+struct EchoVrf;
+
+impl VrfVerifier for EchoVrf {
+    fn verify(&self, _: &ValidatorId, transcript: &[u8], output: &H256, proof: &[u8]) -> bool {
+        proof.len() == 32 + transcript.len()
+            && &proof[..32] == output.as_bytes()
+            && &proof[32..] == transcript
+    }
+}
+
+/// State root = number of transactions executed so far on this branch.
+struct Counter;
+
+impl BlockExecutor for Counter {
+    fn execute(&mut self, parent: &BlockHeader, block: &Block) -> Result<H256, String> {
+        if block.transactions.iter().any(|t| t == b"fail") {
+            return Err("bad tx".into());
+        }
+        Ok(root(
+            root_count(&parent.state_root) + block.transactions.len() as u64,
+        ))
+    }
+}
+
+fn root(n: u64) -> H256 {
+    let mut b = [0u8; 32];
+    b[24..].copy_from_slice(&n.to_be_bytes());
+    H256::from_bytes(b)
+}
+
+fn root_count(r: &H256) -> u64 {
+    let mut b = [0u8; 8];
+    b.copy_from_slice(&r.as_bytes()[24..]);
+    u64::from_be_bytes(b)
+}
+
+fn hydro() -> HydroConfig {
+    HydroConfig {
+        genesis_time_ms: 1_000_000,
+        slot_ms: SLOT_MS,
+        skew_ms: 200,
+        epoch_randomness: [7; 32],
+        chain_id: "amun-testnet".to_string(),
+    }
+}
+
+fn genesis() -> BlockHeader {
+    BlockHeader {
+        height: Height::ZERO,
+        parent_hash: H256::ZERO,
+        slot: 0,
+        timestamp_ms: 1_000_000,
+        producer: ValidatorId::from_bytes([0; 32]),
+        vrf_output: H256::ZERO,
+        vrf_proof: Vec::new(),
+        tx_root: Block::tx_root(&[]),
+        state_root: root(0),
+        pow_nonce: 0,
+    }
+}
+
+/// Valid child of `parent` at `slot`.
+fn child(parent: &BlockHeader, slot: u64, txs: Vec<Vec<u8>>) -> Block {
+    child_with(&hydro(), parent, slot, txs)
+}
+
+/// Child of `parent` at `slot` whose VRF proof is over `h`'s transcript.
+fn child_with(h: &HydroConfig, parent: &BlockHeader, slot: u64, txs: Vec<Vec<u8>>) -> Block {
+    let parent_hash = parent.hash().unwrap();
+    let output = H256::from_bytes([slot as u8; 32]);
+    let mut proof = output.as_bytes().to_vec();
+    proof.extend(h.build_vrf_transcript(slot, parent_hash));
+    Block {
+        header: BlockHeader {
+            height: parent.height.checked_next().unwrap(),
+            parent_hash,
+            slot,
+            timestamp_ms: h.genesis_time_ms + slot * SLOT_MS + 10,
+            producer: ValidatorId::from_bytes([1; 32]),
+            vrf_output: output,
+            vrf_proof: proof,
+            tx_root: Block::tx_root(&txs),
+            state_root: root(root_count(&parent.state_root) + txs.len() as u64),
+            pow_nonce: 0,
+        },
+        transactions: txs,
+    }
+}
+
+fn importer(store: &Store) -> BlockImporter {
+    let g = genesis();
+    store.lock().unwrap().insert(g.hash().unwrap(), g);
+    BlockImporter::new(
+        hydro(),
+        [0xff; 32],
+        Box::new(Chain(store.clone())),
+        Box::new(EchoVrf),
+        Box::new(Counter),
+        Box::new(Chain(store.clone())),
+    )
+}
+
+fn now(slot: u64) -> u64 {
+    hydro().genesis_time_ms + slot * SLOT_MS + 500
+}
+
+#[test]
+fn valid_chain_imports_in_order() {
+    let store = Store::default();
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let mut imp = importer(&store).with_metrics(metrics.clone());
+
+    let b1 = child(&genesis(), 1, vec![b"a".to_vec()]);
+    let b2 = child(&b1.header, 3, vec![b"b".to_vec(), b"c".to_vec()]);
+    let h1 = b1.header.hash().unwrap();
+    assert_eq!(
+        imp.import_at(b1.clone(), BlockSource::Local, now(1)),
+        Ok(ImportOutcome::Imported(h1))
+    );
+    assert!(matches!(
+        imp.import_at(b2, BlockSource::Local, now(3)),
+        Ok(ImportOutcome::Imported(_))
+    ));
+    assert_eq!(
+        imp.import_at(b1, BlockSource::Local, now(4)),
+        Ok(ImportOutcome::Known(h1))
+    );
+    assert_eq!(store.lock().unwrap().len(), 3);
+    assert_eq!(metrics.block_imports_total.get(), 2);
+}
+
+#[test]
+fn each_stage_rejects_with_its_own_error() {
+    let store = Store::default();
+    let mut imp = importer(&store);
+    let g = genesis();
+    let base = child(&g, 1, vec![b"a".to_vec()]);
+
+    let mut bad_root = base.clone();
+    bad_root.transactions.push(b"x".to_vec());
+    let mut early = base.clone();
+    early.header.timestamp_ms -= SLOT_MS;
+    let mut bad_vrf = base.clone();
+    bad_vrf.header.vrf_output = H256::from_bytes([9; 32]);
+    let mut orphan = base.clone();
+    orphan.header.parent_hash = H256::from_bytes([3; 32]);
+    let mut skipped = base.clone();
+    skipped.header.height = Height(2);
+    let mut state = base.clone();
+    state.header.state_root = root(42);
+    let failing = child(&g, 1, vec![b"fail".to_vec()]);
+
+    let cases = [
+        (bad_root, ImportStage::Header),
+        (early, ImportStage::SlotWindow),
+        (bad_vrf, ImportStage::Vrf),
+        (skipped, ImportStage::Parent),
+        (failing, ImportStage::Execution),
+        (state, ImportStage::StateRoot),
+    ];
+    for (block, stage) in cases {
+        let err = imp
+            .import_at(block, BlockSource::Local, now(1))
+            .unwrap_err();
+        assert_eq!(err.stage(), stage, "{err}");
+        assert!(err.is_invalid());
+    }
+    // The VRF transcript binds the parent, so a forged parent fails there first.
+    assert_eq!(
+        imp.import_at(orphan, BlockSource::Local, now(1)),
+        Err(ImportError::BadVrf)
+    );
+    assert_eq!(
+        imp.import_at(base.clone(), BlockSource::Local, now(0) - 600),
+        Err(ImportError::FutureSlot)
+    );
+
+    imp.set_target([0; 32]);
+    assert_eq!(
+        imp.import_at(base, BlockSource::Local, now(1)),
+        Err(ImportError::Difficulty)
+    );
+    assert_eq!(store.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn unknown_parent_is_not_the_peers_fault() {
+    let store = Store::default();
+    let (tx, rx) = channel("peer_reports", ChannelConfig::blocking(8), None);
+    let mut imp = importer(&store).with_peer_reports(tx);
+
+    let b1 = child(&genesis(), 1, Vec::new());
+    let b2 = child(&b1.header, 2, Vec::new());
+    assert_eq!(
+        imp.import_at(b2.clone(), BlockSource::Gossip(b"p1".to_vec()), now(2)),
+        Err(ImportError::UnknownParent(b1.header.hash().unwrap()))
+    );
+    assert!(rx.is_empty());
+
+    imp.import_at(b1, BlockSource::Sync(b"p1".to_vec()), now(2))
+        .unwrap();
+    imp.import_at(b2, BlockSource::Gossip(b"p1".to_vec()), now(2))
+        .unwrap();
+    assert!(rx.is_empty());
+}
+
+#[tokio::test]
+async fn invalid_blocks_from_peers_are_reported() {
+    let store = Store::default();
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let (tx, mut rx) = channel("peer_reports", ChannelConfig::blocking(8), None);
+    let mut imp = importer(&store)
+        .with_peer_reports(tx)
+        .with_metrics(metrics.clone());
+
+    let mut bad = child(&genesis(), 1, Vec::new());
+    bad.header.state_root = root(5);
+    assert!(imp
+        .import_at(bad.clone(), BlockSource::Local, now(1))
+        .is_err());
+    assert!(rx.is_empty());
+
+    assert_eq!(
+        imp.import_at(bad, BlockSource::Gossip(b"evil".to_vec()), now(1)),
+        Err(ImportError::StateRootMismatch)
+    );
+    assert_eq!(rx.recv().await.unwrap(), b"evil".to_vec());
+    assert_eq!(
+        metrics
+            .block_import_rejected_total
+            .with_label_values(&["state_root"])
+            .get(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn orphans_connect_when_the_parent_lands() {
+    let store = Store::default();
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let (tx, rx) = channel("peer_reports", ChannelConfig::blocking(8), None);
+    let mut imp = importer(&store)
+        .with_orphan_pool(OrphanConfig::default())
+        .with_peer_reports(tx)
+        .with_metrics(metrics.clone());
+
+    let b1 = child(&genesis(), 1, vec![b"a".to_vec()]);
+    let b2 = child(&b1.header, 2, Vec::new());
+    let b3 = child(&b2.header, 3, vec![b"b".to_vec()]);
+    let sibling = child(&b1.header, 3, Vec::new());
+    let peer = || BlockSource::Gossip(b"p1".to_vec());
+
+    for b in [&b3, &b2, &sibling] {
+        assert_eq!(
+            imp.import_at(b.clone(), peer(), now(3)),
+            Ok(ImportOutcome::Orphaned(b.header.hash().unwrap()))
+        );
+    }
+    assert_eq!(imp.orphan_count(), 3);
+    assert_eq!(metrics.block_orphans.get(), 3);
+
+    assert!(matches!(
+        imp.import_at(b1, peer(), now(3)),
+        Ok(ImportOutcome::Imported(_))
+    ));
+    assert_eq!(imp.orphan_count(), 0);
+    assert_eq!(store.lock().unwrap().len(), 5);
+    assert_eq!(metrics.block_imports_total.get(), 4);
+    assert_eq!(metrics.block_orphans.get(), 0);
+    assert!(rx.is_empty());
+}
+
+#[tokio::test]
+async fn unconnectable_orphans_strike_the_sender() {
+    let store = Store::default();
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let (tx, mut rx) = channel("peer_reports", ChannelConfig::blocking(8), None);
+    let cfg = OrphanConfig {
+        max_age_ms: 5_000,
+        max_strikes: 2,
+        ..OrphanConfig::default()
+    };
+    let mut imp = importer(&store)
+        .with_orphan_pool(cfg)
+        .with_peer_reports(tx)
+        .with_metrics(metrics.clone());
+
+    // Children of parents nobody will ever produce.
+    let mut fake = genesis();
+    for slot in [1, 2] {
+        fake.pow_nonce = slot;
+        imp.import_at(
+            child(&fake, slot, Vec::new()),
+            BlockSource::Gossip(b"spam".to_vec()),
+            now(2),
+        )
+        .unwrap();
+    }
+    assert!(rx.is_empty());
+
+    // Both expire on the next import.
+    imp.import_at(child(&genesis(), 8, Vec::new()), BlockSource::Local, now(8))
+        .unwrap();
+    assert_eq!(imp.orphan_count(), 0);
+    assert_eq!(metrics.block_orphans_evicted_total.get(), 2);
+    assert_eq!(rx.recv().await.unwrap(), b"spam".to_vec());
+}
+
+#[test]
+fn orphan_pool_is_bounded() {
+    let mut pool = OrphanPool::new(OrphanConfig {
+        max_blocks: 2,
+        max_per_peer: 1,
+        max_strikes: 2,
+        ..OrphanConfig::default()
+    });
+    let g = genesis();
+    let blocks: Vec<Block> = (1..=3).map(|s| child(&g, s, Vec::new())).collect();
+    let hashes: Vec<H256> = blocks.iter().map(|b| b.header.hash().unwrap()).collect();
+    let from = |p: &[u8]| BlockSource::Sync(p.to_vec());
+
+    assert!(pool.insert(hashes[0], blocks[0].clone(), from(b"a"), 0));
+    // One per peer: the second block from `a` is refused and counts as a strike.
+    assert!(!pool.insert(hashes[1], blocks[1].clone(), from(b"a"), 0));
+    assert!(pool.insert(hashes[1], blocks[1].clone(), from(b"b"), 0));
+    // Full: the oldest (from `a`) makes room, its second strike.
+    assert!(pool.insert(hashes[2], blocks[2].clone(), BlockSource::Local, 0));
+    assert!(!pool.contains(&hashes[0]));
+    assert_eq!(pool.take_evicted(), 1);
+    assert_eq!(pool.take_penalized(), vec![b"a".to_vec()]);
+
+    let children = pool.take_children(&g.hash().unwrap());
+    let got: Vec<H256> = children
+        .iter()
+        .map(|o| o.block.header.hash().unwrap())
+        .collect();
+    assert_eq!(got, hashes[1..].to_vec());
+    assert!(pool.is_empty());
+}
+
+#[test]
+fn vrf_proofs_use_the_beacon_randomness_of_the_block_epoch() {
+    let store = Store::default();
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    let beacons = BeaconStore::open(&st).unwrap();
+    let mut imp = importer(&store).with_beacon(beacons.clone());
+
+    // Nothing saved yet: no epoch can be checked.
+    let b1 = child(&genesis(), 1, vec![]);
+    assert_eq!(
+        imp.import_at(b1.clone(), BlockSource::Local, now(1)),
+        Err(ImportError::UnknownEpoch)
+    );
+
+    // The genesis beacon holds the configured randomness for epoch 1.
+    let mut beacon = RandomnessBeacon::genesis(2, [7; 32]).unwrap();
+    beacons.save(&beacon).unwrap();
+    let b2 = child(&b1.header, 2, vec![]);
+    for (b, slot) in [(b1.clone(), 1), (b2.clone(), 2)] {
+        assert!(matches!(
+            imp.import_at(b, BlockSource::Local, now(slot)),
+            Ok(ImportOutcome::Imported(_))
+        ));
+    }
+
+    // Heights 1 and 2 are final and close epoch 1.
+    beacon
+        .absorb(Height(1), b1.header.vrf_output.as_bytes())
+        .unwrap();
+    beacon
+        .absorb(Height(2), b2.header.vrf_output.as_bytes())
+        .unwrap();
+    beacons.save(&beacon).unwrap();
+    let mut epoch2 = hydro();
+    beacon.apply(&mut epoch2);
+
+    // A proof over the old randomness no longer verifies in epoch 2.
+    assert_eq!(
+        imp.import_at(child(&b2.header, 3, vec![]), BlockSource::Local, now(3)),
+        Err(ImportError::BadVrf)
+    );
+    let b3 = child_with(&epoch2, &b2.header, 3, vec![]);
+    assert!(matches!(
+        imp.import_at(b3.clone(), BlockSource::Local, now(3)),
+        Ok(ImportOutcome::Imported(_))
+    ));
+    // Past epochs keep their randomness: a competing block in epoch 1 still imports.
+    assert!(matches!(
+        imp.import_at(child(&b1.header, 4, vec![]), BlockSource::Local, now(4)),
+        Ok(ImportOutcome::Imported(_))
+    ));
+    // Epoch 3 has no randomness yet.
+    let b4 = child_with(&epoch2, &b3.header, 5, vec![]);
+    let b5 = child_with(&epoch2, &b4.header, 6, vec![]);
+    imp.import_at(b4, BlockSource::Local, now(5)).unwrap();
+    let err = imp.import_at(b5, BlockSource::Local, now(6)).unwrap_err();
+    assert_eq!(err, ImportError::UnknownEpoch);
+    assert!(!err.is_invalid());
+}
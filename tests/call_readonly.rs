@@ -0,0 +1,188 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::economics::staking::StakingLedger;
+use amunchain::core::runtime::executor::{EvmExecutor, ExecError, ExecState, TRANSFER_GAS};
+use amunchain::core::runtime::precompiles::VOTING_POWER;
+use amunchain::core::runtime::receipt::TxStatus;
+use amunchain::core::state::persistent_state::PersistentState;
+use amunchain::core::types::{AccountKey, Signature, Transaction, TxCall};
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::rpc::server::{bind, serve_listener, RpcState};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+const ALICE: [u8; 32] = [0xa1; 32];
+
+fn staked_db() -> (tempfile::TempDir, PersistentState) {
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    let mut ledger = StakingLedger::default();
+    ledger
+        .register_validator(b"val".to_vec(), 500, 1_000)
+        .unwrap();
+    ledger.commit(&st).unwrap();
+    (dir, st)
+}
+
+fn query(nonce: u64, gas_limit: u64) -> Transaction {
+    Transaction {
+        chain_id: String::new(),
+        sender: AccountKey::from_bytes(ALICE),
+        nonce,
+        call: TxCall::Call {
+            to: VOTING_POWER.to_vec(),
+            value: 0,
+            input: b"val".to_vec(),
+        },
+        gas_limit,
+        max_fee_per_gas: 0,
+        max_priority_fee_per_gas: 0,
+        signature: Signature::from_bytes([0; 64]),
+    }
+}
+
+fn word(v: u128) -> Vec<u8> {
+    let mut out = vec![0u8; 32];
+    out[16..].copy_from_slice(&v.to_be_bytes());
+    out
+}
+
+#[test]
+fn dry_runs_do_not_mutate_state() {
+    let (_dir, st) = staked_db();
+    let exec = EvmExecutor::new();
+    let root = st.state_root().unwrap();
+    let before = ExecState::load(&st).unwrap();
+
+    // Any nonce; zero gas limit means the block gas limit.
+    let receipt = exec.call_readonly(&st, &query(99, 0), None).unwrap();
+    assert_eq!(receipt.status, TxStatus::Success);
+    assert_eq!(receipt.output, word(1_000));
+    let cost = exec.precompiles().gas(&VOTING_POWER, b"val").unwrap();
+    assert_eq!(receipt.gas_used, TRANSFER_GAS + cost);
+
+    // The estimate is enough to run it for real.
+    let again = exec
+        .call_readonly(&st, &query(0, receipt.gas_used), Some(root))
+        .unwrap();
+    assert_eq!(
+        (again.status, again.gas_used, again.output),
+        (receipt.status, receipt.gas_used, receipt.output)
+    );
+    let starved = exec
+        .call_readonly(&st, &query(0, receipt.gas_used - 1), None)
+        .unwrap();
+    assert_eq!(starved.status, TxStatus::OutOfGas);
+
+    assert_eq!(st.state_root().unwrap(), root);
+    assert_eq!(ExecState::load(&st).unwrap(), before);
+    assert_eq!(before.nonces.next(&ALICE), 0);
+}
+
+#[test]
+fn dry_runs_follow_the_state_root() {
+    let (_dir, st) = staked_db();
+    let exec = EvmExecutor::new();
+    let old_root = st.state_root().unwrap();
+    let receipt = exec.call_readonly(&st, &query(0, 0), None).unwrap();
+    assert_eq!(receipt.output, word(1_000));
+
+    let mut ledger = StakingLedger::load(&st).unwrap();
+    ledger.bond(b"del".to_vec(), b"val".to_vec(), 250).unwrap();
+    ledger.commit(&st).unwrap();
+
+    // The state loaded for the old root is not reused.
+    let receipt = exec.call_readonly(&st, &query(0, 0), None).unwrap();
+    assert_eq!(receipt.output, word(1_250));
+    assert_eq!(
+        exec.call_readonly(&st, &query(0, 0), Some(old_root)),
+        Err(ExecError::UnknownStateRoot)
+    );
+}
+
+#[test]
+fn dry_runs_are_checked() {
+    let (_dir, st) = staked_db();
+    let exec = EvmExecutor::new().with_block_gas_limit(100_000);
+    assert_eq!(
+        exec.call_readonly(&st, &query(0, 0), Some([7; 32])),
+        Err(ExecError::UnknownStateRoot)
+    );
+    assert_eq!(
+        exec.call_readonly(&st, &query(0, 100_001), None),
+        Err(ExecError::BlockGasLimit)
+    );
+    assert_eq!(
+        exec.call_readonly(&st, &query(0, TRANSFER_GAS - 1), None),
+        Err(ExecError::IntrinsicGas)
+    );
+}
+
+async fn post(addr: std::net::SocketAddr, path: &str, body: &str) -> (u16, String) {
+    let mut s = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let req = format!(
+        "POST {path} HTTP/1.1\r\nHost: x\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    s.write_all(req.as_bytes()).await.unwrap();
+    let mut resp = String::new();
+    s.read_to_string(&mut resp).await.unwrap();
+    let code = resp[9..12].parse().unwrap();
+    (
+        code,
+        resp.split("\r\n\r\n").nth(1).unwrap_or("").to_string(),
+    )
+}
+
+#[tokio::test]
+async fn call_rpc() {
+    let (_dir, st) = staked_db();
+    let root = hex::encode(st.state_root().unwrap());
+    let metrics = Arc::new(Metrics::new().unwrap());
+
+    let listener = bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_listener(
+        listener,
+        RpcState::new(metrics.clone()).with_call(st, EvmExecutor::new()),
+    ));
+
+    let body = |root: &str| {
+        serde_json::json!({
+            "from": hex::encode(ALICE),
+            "to": format!("0x{}", hex::encode(VOTING_POWER)),
+            "input": hex::encode(b"val"),
+            "at_state_root": root,
+        })
+        .to_string()
+    };
+    let (code, resp) = post(addr, "/chain/call", &body(&root)).await;
+    assert_eq!(code, 200, "{resp}");
+    let v: serde_json::Value = serde_json::from_str(&resp).unwrap();
+    assert_eq!(v["status"], "Success");
+    assert_eq!(v["output"], hex::encode(word(1_000)));
+    assert!(v["gas_used"].as_u64().unwrap() > TRANSFER_GAS);
+
+    let (code, _) = post(addr, "/chain/call", &body(&"00".repeat(32))).await;
+    assert_eq!(code, 404);
+    let (code, _) = post(addr, "/chain/call", &body("zz")).await;
+    assert_eq!(code, 400);
+
+    // Not wired: unavailable.
+    let listener = bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_listener(listener, RpcState::new(metrics)));
+    let (code, _) = post(addr, "/chain/call", &body(&root)).await;
+    assert_eq!(code, 503);
+}
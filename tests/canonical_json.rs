@@ -0,0 +1,174 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::state::commit_store::FinalityProof;
+use amunchain::core::types::{
+    decode_canonical_json, encode_canonical, encode_canonical_json, CodecError, Commit,
+    ConsensusMsg, Epoch, Height, Round, Signature, ValidatorId, Vote, H256,
+};
+use proptest::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const MAX: usize = 64 * 1024;
+
+fn vote(height: u64, seed: u8) -> Vote {
+    Vote {
+        height: Height(height),
+        round: Round(1),
+        epoch: Epoch(2),
+        msg_counter: 3,
+        sent_ts_ms: 4,
+        ttl_ms: 5,
+        block_hash: H256::from_bytes([seed; 32]),
+        voter: ValidatorId::from_bytes([seed; 32]),
+        signature: Signature::from_bytes([seed.wrapping_add(1); 64]),
+    }
+}
+
+fn commit(height: u64, signers: &[u8], voting_power: u128) -> Commit {
+    Commit {
+        height: Height(height),
+        round: Round::ZERO,
+        epoch: Epoch(1),
+        msg_counter: 0,
+        sent_ts_ms: 0,
+        ttl_ms: 0,
+        block_hash: H256::from_bytes([9; 32]),
+        signatures: signers
+            .iter()
+            .map(|s| {
+                (
+                    ValidatorId::from_bytes([*s; 32]),
+                    Signature::from_bytes([*s; 64]),
+                )
+            })
+            .collect(),
+        voting_power,
+        validator_set_hash: H256::ZERO,
+    }
+}
+
+/// JSON and bincode describe the same value: decoding the JSON yields the bincode bytes.
+fn same_as_bincode<T: Serialize + serde::de::DeserializeOwned>(v: &T) {
+    let json = encode_canonical_json(v).unwrap();
+    let back: T = decode_canonical_json(&json, MAX).unwrap();
+    assert_eq!(
+        encode_canonical(&back).unwrap(),
+        encode_canonical(v).unwrap()
+    );
+    assert_eq!(encode_canonical_json(&back).unwrap(), json);
+}
+
+#[test]
+fn consensus_types_round_trip() {
+    same_as_bincode(&vote(7, 1));
+    same_as_bincode(&commit(7, &[3, 1, 2], u128::MAX));
+    same_as_bincode(&ConsensusMsg::Vote(vote(1, 0)));
+    same_as_bincode(&ConsensusMsg::Commit(commit(1, &[], 0)));
+    same_as_bincode(&FinalityProof {
+        commit: commit(8, &[4, 5], 2),
+        validator_set_hash: H256::from_bytes([1; 32]),
+    });
+}
+
+#[test]
+fn keys_are_sorted_and_output_is_compact() {
+    #[derive(Serialize, Deserialize)]
+    struct S {
+        zeta: u8,
+        alpha: Option<u8>,
+        mid: BTreeMap<String, i64>,
+    }
+    let v = S {
+        zeta: 1,
+        alpha: None,
+        mid: BTreeMap::from([("b".into(), -2), ("a".into(), 0)]),
+    };
+    assert_eq!(
+        encode_canonical_json(&v).unwrap(),
+        br#"{"alpha":null,"mid":{"a":0,"b":-2},"zeta":1}"#
+    );
+
+    // Non-string keys become [key, value] pairs in key order.
+    let sigs = commit(1, &[2, 1], 0).signatures;
+    let json = String::from_utf8(encode_canonical_json(&sigs).unwrap()).unwrap();
+    assert!(json.starts_with("[[[1,1,"), "{json}");
+}
+
+#[test]
+fn wide_integers_and_strings_are_exact() {
+    for n in [0u128, 1, u64::MAX as u128 + 1, u128::MAX] {
+        let json = encode_canonical_json(&n).unwrap();
+        assert_eq!(json, n.to_string().as_bytes());
+        assert_eq!(decode_canonical_json::<u128>(&json, MAX).unwrap(), n);
+    }
+    let json = encode_canonical_json(&i128::MIN).unwrap();
+    assert_eq!(
+        decode_canonical_json::<i128>(&json, MAX).unwrap(),
+        i128::MIN
+    );
+
+    let s = "tab\t\"quote\" \u{1} é".to_string();
+    let json = encode_canonical_json(&s).unwrap();
+    assert_eq!(json, "\"tab\\t\\\"quote\\\" \\u0001 é\"".as_bytes());
+    assert_eq!(decode_canonical_json::<String>(&json, MAX).unwrap(), s);
+}
+
+#[test]
+fn floats_and_non_canonical_input_are_rejected() {
+    assert!(matches!(
+        encode_canonical_json(&1.5f64),
+        Err(CodecError::Serialize)
+    ));
+
+    let good = encode_canonical_json(&vote(1, 1)).unwrap();
+    assert!(decode_canonical_json::<Vote>(&good, MAX).is_ok());
+    assert!(matches!(
+        decode_canonical_json::<Vote>(&good, good.len() - 1),
+        Err(CodecError::TooLarge)
+    ));
+
+    for bad in [
+        r#"{"b":1, "a":2}"#,
+        r#"{"b":1,"a":2}"#,
+        "01",
+        "-0",
+        "1.0",
+        "1e3",
+        "\"\\u0041\"",
+        "\"a\" ",
+        r#"{"a":1,"a":1}"#,
+    ] {
+        assert!(
+            decode_canonical_json::<BTreeMap<String, u8>>(bad.as_bytes(), MAX).is_err()
+                && decode_canonical_json::<u64>(bad.as_bytes(), MAX).is_err()
+                && decode_canonical_json::<String>(bad.as_bytes(), MAX).is_err(),
+            "{bad}"
+        );
+    }
+
+    let deep = format!("{}{}", "[".repeat(200), "]".repeat(200));
+    assert!(decode_canonical_json::<serde_json::Value>(deep.as_bytes(), MAX).is_err());
+}
+
+proptest! {
+    #[test]
+    fn prop_commit_json_matches_bincode(
+        height in any::<u64>(),
+        power in any::<u128>(),
+        signers in prop::collection::vec(any::<u8>(), 0..8),
+    ) {
+        same_as_bincode(&commit(height, &signers, power));
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::hydro::HydroConfig;
+use amunchain::core::consensus::signing::SigningDomain;
+use amunchain::core::consensus::tide::{
+    expected_set_hash, verify_commit_certificate_for_chain, NoopSlashing, TideConfig, TideError,
+    TideFinalizer,
+};
+use amunchain::core::types::{Height, Vote, H256};
+use common::{keypairs, sign_legacy, sign_v3, unsigned_vote, validators};
+use ring::signature::Ed25519KeyPair;
+
+/// `chain = None` signs the legacy v2 payload; v3 votes are bound to the set of `kps`.
+fn signed_vote(kps: &[Ed25519KeyPair], i: usize, height: u64, chain: Option<&str>) -> Vote {
+    let v = unsigned_vote(&kps[i], height, H256::from_bytes([height as u8; 32]));
+    match chain {
+        Some(id) => sign_v3(
+            &kps[i],
+            id,
+            expected_set_hash(&validators(kps), None).unwrap(),
+            v,
+        ),
+        None => sign_legacy(&kps[i], v),
+    }
+}
+
+#[test]
+fn v3_votes_are_bound_to_chain_id() {
+    let kps = keypairs(4);
+    let cfg = TideConfig::new(validators(&kps)).with_chain_id("amun-testnet", None);
+    let mut tide = TideFinalizer::new(cfg, NoopSlashing);
+
+    // Signed for another chain: rejected.
+    assert!(matches!(
+        tide.process_vote_verified(signed_vote(&kps, 0, 1, Some("amun-mainnet"))),
+        Err(TideError::BadSignature)
+    ));
+    // Legacy payload with the compatibility window closed: rejected.
+    assert!(matches!(
+        tide.process_vote_verified(signed_vote(&kps, 0, 1, None)),
+        Err(TideError::BadSignature)
+    ));
+
+    let mut commit = None;
+    for i in 0..3 {
+        commit = tide
+            .process_vote_verified(signed_vote(&kps, i, 1, Some("amun-testnet")))
+            .unwrap();
+    }
+    let commit = commit.expect("quorum reached");
+
+    let set = validators(&kps);
+    let testnet = SigningDomain {
+        chain_id: "amun-testnet",
+        legacy_until_height: None,
+    };
+    let mainnet = SigningDomain {
+        chain_id: "amun-mainnet",
+        legacy_until_height: None,
+    };
+    verify_commit_certificate_for_chain(&commit, &set, None, testnet).unwrap();
+    assert!(matches!(
+        verify_commit_certificate_for_chain(&commit, &set, None, mainnet),
+        Err(TideError::BadSignature)
+    ));
+    // Even with the legacy window open, a v3 certificate does not verify for another chain.
+    let open_window = SigningDomain {
+        chain_id: "",
+        legacy_until_height: Some(Height::MAX),
+    };
+    assert!(verify_commit_certificate_for_chain(&commit, &set, None, open_window).is_err());
+}
+
+#[test]
+fn legacy_votes_accepted_within_window() {
+    let kps = keypairs(4);
+    let cfg = TideConfig::new(validators(&kps)).with_chain_id("amun-testnet", Some(Height(5)));
+    let mut tide = TideFinalizer::new(cfg, NoopSlashing);
+
+    // Mixed v2 and v3 votes finalize a height inside the window.
+    tide.process_vote_verified(signed_vote(&kps, 0, 5, None))
+        .unwrap();
+    tide.process_vote_verified(signed_vote(&kps, 1, 5, Some("amun-testnet")))
+        .unwrap();
+    let commit = tide
+        .process_vote_verified(signed_vote(&kps, 2, 5, None))
+        .unwrap();
+    assert_eq!(commit.map(|c| c.height), Some(Height(5)));
+
+    // Past the window only v3 verifies.
+    assert!(matches!(
+        tide.process_vote_verified(signed_vote(&kps, 0, 6, None)),
+        Err(TideError::BadSignature)
+    ));
+    tide.process_vote_verified(signed_vote(&kps, 0, 6, Some("amun-testnet")))
+        .unwrap();
+}
+
+#[test]
+fn vrf_transcript_includes_chain_id() {
+    let mut hydro = HydroConfig {
+        genesis_time_ms: 0,
+        slot_ms: 1000,
+        skew_ms: 100,
+        epoch_randomness: [7u8; 32],
+        chain_id: "amun-testnet".to_string(),
+    };
+    let parent = H256::from_bytes([1u8; 32]);
+    let testnet = hydro.build_vrf_transcript(3, parent);
+    hydro.chain_id = "amun-mainnet".to_string();
+    assert_ne!(testnet, hydro.build_vrf_transcript(3, parent));
+}
@@ -0,0 +1,57 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::node::channel::{channel, ChannelConfig, SendError};
+use std::time::Duration;
+
+#[tokio::test]
+async fn lossy_channel_counts_drops_and_depth() {
+    let metrics = Metrics::new().unwrap();
+    let (tx, mut rx) = channel::<u32>("test_lossy", ChannelConfig::lossy(2), Some(&metrics));
+
+    tx.send(1).await.unwrap();
+    tx.send(2).await.unwrap();
+    assert_eq!(tx.send(3).await, Err(SendError::Dropped("test_lossy")));
+
+    let depth = metrics.channel_depth.with_label_values(&["test_lossy"]);
+    let dropped = metrics
+        .channel_dropped_total
+        .with_label_values(&["test_lossy"]);
+    assert_eq!(depth.get(), 2);
+    assert_eq!(dropped.get(), 1);
+
+    assert_eq!(rx.recv().await, Some(1));
+    assert_eq!(depth.get(), 1);
+    assert_eq!(rx.recv().await, Some(2));
+    assert_eq!(depth.get(), 0);
+}
+
+#[tokio::test]
+async fn blocking_channel_applies_backpressure() {
+    let (tx, mut rx) = channel::<u32>("test_blocking", ChannelConfig::blocking(1), None);
+    tx.send(1).await.unwrap();
+
+    // Full: the second send waits instead of dropping.
+    let pending = tokio::time::timeout(Duration::from_millis(50), tx.send(2)).await;
+    assert!(pending.is_err());
+
+    let tx2 = tx.clone();
+    let waiter = tokio::spawn(async move { tx2.send(2).await });
+    assert_eq!(rx.recv().await, Some(1));
+    waiter.await.unwrap().unwrap();
+    assert_eq!(rx.recv().await, Some(2));
+
+    drop(tx);
+    assert_eq!(rx.recv().await, None);
+}
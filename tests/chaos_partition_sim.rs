@@ -12,7 +12,9 @@
 #![forbid(unsafe_code)]
 
 use amunchain::core::consensus::driver::ConsensusDriver;
-use amunchain::core::types::{ConsensusMsg, Signature, ValidatorId, Vote, H256};
+use amunchain::core::types::{
+    ConsensusMsg, Epoch, Height, Round, Signature, ValidatorId, Vote, H256,
+};
 use std::collections::BTreeSet;
 
 fn make_validators(n: usize) -> BTreeSet<ValidatorId> {
@@ -20,13 +22,13 @@ fn make_validators(n: usize) -> BTreeSet<ValidatorId> {
     for i in 0..n {
         let mut b = [0u8; 32];
         b[0] = i as u8;
-        s.insert(ValidatorId(b.to_vec()));
+        s.insert(ValidatorId::from_bytes(b));
     }
     s
 }
 
 fn dummy_sig() -> Signature {
-    Signature(vec![0u8; 64])
+    Signature::from_bytes([0u8; 64])
 }
 
 #[test]
@@ -45,9 +47,9 @@ fn chaos_partition_does_not_panic() {
 
     for v in group1 {
         let vote = Vote {
-            height,
-            round,
-            epoch: 0,
+            height: Height(height),
+            round: Round(round),
+            epoch: Epoch::ZERO,
             msg_counter: 0,
             sent_ts_ms: 0,
             ttl_ms: 0,
@@ -59,9 +61,9 @@ fn chaos_partition_does_not_panic() {
     }
     for v in group2 {
         let vote = Vote {
-            height,
-            round,
-            epoch: 0,
+            height: Height(height),
+            round: Round(round),
+            epoch: Epoch::ZERO,
             msg_counter: 0,
             sent_ts_ms: 0,
             ttl_ms: 0,
@@ -0,0 +1,186 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::security::keystore::{self, KeystoreError};
+use amunchain::core::state::persistent_state::KvOp;
+use amunchain::core::types::NodeConfig;
+use amunchain::networking::p2p_identity::IdentitySource;
+use amunchain::node::cli::{self, Cli, CliError, Command};
+use clap::Parser;
+use common::fresh_copy;
+use std::path::PathBuf;
+
+#[test]
+fn global_flags_apply_to_every_subcommand() {
+    let args = Cli::try_parse_from([
+        "amunchain",
+        "export-state",
+        "out.snap",
+        "--data-dir",
+        "/var/lib/amun",
+    ])
+    .unwrap();
+    assert_eq!(args.data_dir, Some(PathBuf::from("/var/lib/amun")));
+    assert!(
+        matches!(args.command, Some(Command::ExportState { out }) if out == std::path::Path::new("out.snap"))
+    );
+
+    let args = Cli::try_parse_from(["amunchain"]).unwrap();
+    assert!(args.command.is_none());
+    assert!(Cli::try_parse_from(["amunchain", "frobnicate"]).is_err());
+}
+
+#[test]
+fn init_writes_loadable_config_and_identity() {
+    let dir = tempfile::tempdir().unwrap();
+    let data_dir = dir.path().join("data");
+    let config_path = dir.path().join("node.toml");
+
+    let peer_id = cli::init(&data_dir, &config_path, false).unwrap();
+    assert_eq!(
+        cli::peer_id(&data_dir, IdentitySource::File).unwrap(),
+        peer_id
+    );
+    let config = NodeConfig::load(&config_path).unwrap();
+    assert_eq!(PathBuf::from(&config.node.data_dir), data_dir);
+
+    assert!(matches!(
+        cli::init(&data_dir, &config_path, false),
+        Err(CliError::Exists(_))
+    ));
+    // The identity survives a forced re-init.
+    assert_eq!(cli::init(&data_dir, &config_path, true).unwrap(), peer_id);
+}
+
+#[test]
+fn print_peer_id_does_not_create_identity() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(matches!(
+        cli::peer_id(dir.path(), IdentitySource::File),
+        Err(CliError::NotInitialized(_))
+    ));
+}
+
+#[test]
+fn keygen_refuses_to_overwrite() {
+    let dir = tempfile::tempdir().unwrap();
+    let pk = cli::keygen(dir.path(), false).unwrap();
+    assert_eq!(pk.len(), 64);
+    assert!(matches!(
+        cli::keygen(dir.path(), false),
+        Err(CliError::Exists(_))
+    ));
+    assert_ne!(cli::keygen(dir.path(), true).unwrap(), pk);
+}
+
+#[test]
+fn rotation_keeps_the_old_key() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(matches!(
+        cli::rotate_key(dir.path()),
+        Err(CliError::NotInitialized(_))
+    ));
+    let old = cli::keygen(dir.path(), false).unwrap();
+    let r = cli::rotate_key(dir.path()).unwrap();
+    assert_eq!(r.previous.public_key, old);
+    assert_ne!(r.current.public_key, old);
+    assert_eq!(cli::key_info(dir.path()).unwrap(), r.current);
+    assert!(!r.current.encrypted);
+    // The backup is still a usable key.
+    let backup = keystore::read_key_file(&r.previous.path, None).unwrap();
+    assert_eq!(
+        hex::encode(ring::signature::KeyPair::public_key(&backup)),
+        old
+    );
+
+    let pem = cli::public_key_pem(dir.path()).unwrap();
+    assert!(pem.starts_with("-----BEGIN PUBLIC KEY-----\nMCowBQYDK2VwAyEA"));
+    assert!(pem.ends_with("-----END PUBLIC KEY-----\n"));
+}
+
+#[test]
+fn key_files_encrypt_with_a_passphrase() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("validator.key");
+    let kp = keystore::generate_key_file(&path, Some("hunter2")).unwrap();
+    assert!(keystore::is_encrypted(&std::fs::read(&path).unwrap()));
+
+    let pk = |k: &ring::signature::Ed25519KeyPair| {
+        ring::signature::KeyPair::public_key(k).as_ref().to_vec()
+    };
+    let read = keystore::read_key_file(&path, Some("hunter2")).unwrap();
+    assert_eq!(pk(&read), pk(&kp));
+    assert!(matches!(
+        keystore::read_key_file(&path, None),
+        Err(KeystoreError::MissingPassphrase)
+    ));
+    assert!(matches!(
+        keystore::read_key_file(&path, Some("wrong")),
+        Err(KeystoreError::Crypto)
+    ));
+}
+
+#[test]
+fn state_snapshot_round_trips() {
+    let src = tempfile::tempdir().unwrap();
+    let dst = tempfile::tempdir().unwrap();
+    let snap = src.path().join("state.snap");
+    {
+        let st = cli::open_state(src.path()).unwrap();
+        st.commit_atomic(vec![
+            KvOp::Put {
+                key: b"a".to_vec(),
+                value: b"1".to_vec(),
+            },
+            KvOp::Put {
+                key: b"b".to_vec(),
+                value: b"2".to_vec(),
+            },
+        ])
+        .unwrap();
+        st.flush().unwrap();
+    }
+    let exported = cli::export_state(fresh_copy(src.path()).path(), &snap).unwrap();
+    assert_eq!(exported.entries, 2);
+
+    let imported = cli::import_state(dst.path(), &snap).unwrap();
+    assert_eq!(imported, exported);
+    let dst = fresh_copy(dst.path());
+    let st = cli::open_state(dst.path()).unwrap();
+    assert_eq!(st.get(b"b").unwrap(), Some(b"2".to_vec()));
+    drop(st);
+
+    // A second import would mix two states.
+    assert!(matches!(
+        cli::import_state(fresh_copy(dst.path()).path(), &snap),
+        Err(CliError::NotEmpty(_))
+    ));
+
+    // Any change to the entries breaks the root check.
+    let mut raw = std::fs::read(&snap).unwrap();
+    let last = raw.len() - 1;
+    raw[last] ^= 1;
+    std::fs::write(&snap, raw).unwrap();
+    let fresh = tempfile::tempdir().unwrap();
+    assert!(matches!(
+        cli::import_state(fresh.path(), &snap),
+        Err(CliError::Snapshot(_))
+    ));
+    assert!(cli::open_state(fresh_copy(fresh.path()).path())
+        .unwrap()
+        .scan_prefix(b"")
+        .unwrap()
+        .is_empty());
+}
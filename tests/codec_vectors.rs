@@ -0,0 +1,185 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+//! Cross-codec vectors in `tests/vectors/consensus_msgs.json`: one message per entry, in every
+//! wire encoding. Non-Rust clients can test against the same file. Regenerate (only when an
+//! encoding changes on purpose) with `AMUN_WRITE_VECTORS=1 cargo test --test codec_vectors`.
+
+use amunchain::core::types::{
+    decode_canonical_json, encode_canonical, encode_canonical_json, Commit, ConsensusMsg, Epoch,
+    Height, Round, Signature, ValidatorId, Vote, H256,
+};
+use amunchain::networking::proto;
+use amunchain::networking::wire::{self, WireCodec, WireError, WIRE_V1, WIRE_V2};
+use serde::{Deserialize, Serialize};
+
+const PATH: &str = "tests/vectors/consensus_msgs.json";
+const MAX: usize = 256 * 1024;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Vector {
+    name: String,
+    /// `ConsensusMsg` in canonical JSON, the readable reference.
+    canonical_json: String,
+    bincode_v1: String,
+    bincode_v2: String,
+    protobuf: String,
+}
+
+fn vote(seed: u8, zeros: bool) -> Vote {
+    let n = |v: u64| if zeros { 0 } else { v };
+    Vote {
+        height: Height(n(1_000_000)),
+        round: Round(n(3)),
+        epoch: Epoch(n(7)),
+        msg_counter: n(42),
+        sent_ts_ms: n(1_760_000_000_000),
+        ttl_ms: if zeros { 0 } else { 30_000 },
+        block_hash: H256::from_bytes([seed; 32]),
+        voter: ValidatorId::from_bytes([seed.wrapping_add(1); 32]),
+        signature: Signature::from_bytes([seed.wrapping_add(2); 64]),
+    }
+}
+
+fn commit(signers: &[u8], voting_power: u128) -> Commit {
+    Commit {
+        height: Height(1_000_000),
+        round: Round(3),
+        epoch: Epoch(7),
+        msg_counter: 43,
+        sent_ts_ms: 1_760_000_000_500,
+        ttl_ms: 30_000,
+        block_hash: H256::from_bytes([0xab; 32]),
+        signatures: signers
+            .iter()
+            .map(|s| {
+                (
+                    ValidatorId::from_bytes([*s; 32]),
+                    Signature::from_bytes([s ^ 0xff; 64]),
+                )
+            })
+            .collect(),
+        voting_power,
+        validator_set_hash: if voting_power == 0 {
+            H256::ZERO
+        } else {
+            H256::from_bytes([0x5e; 32])
+        },
+    }
+}
+
+fn cases() -> Vec<(&'static str, ConsensusMsg)> {
+    vec![
+        ("vote", ConsensusMsg::Vote(vote(0x11, false))),
+        ("vote_default_fields", ConsensusMsg::Vote(vote(0, true))),
+        (
+            "commit",
+            ConsensusMsg::Commit(commit(&[0x30, 0x10, 0x20], 3)),
+        ),
+        (
+            "commit_wide_power",
+            ConsensusMsg::Commit(commit(&[0x01], u128::MAX - 1)),
+        ),
+        ("commit_no_signers", ConsensusMsg::Commit(commit(&[], 0))),
+    ]
+}
+
+fn vector(name: &str, msg: &ConsensusMsg) -> Vector {
+    Vector {
+        name: name.to_string(),
+        canonical_json: String::from_utf8(encode_canonical_json(msg).unwrap()).unwrap(),
+        bincode_v1: hex::encode(wire::encode_msg(msg, WIRE_V1).unwrap()),
+        bincode_v2: hex::encode(wire::encode_msg(msg, WIRE_V2).unwrap()),
+        protobuf: hex::encode(proto::encode_msg(msg).unwrap()),
+    }
+}
+
+fn load() -> Vec<Vector> {
+    serde_json::from_str(&std::fs::read_to_string(PATH).unwrap()).unwrap()
+}
+
+#[test]
+fn encodings_match_committed_vectors() {
+    let fresh: Vec<Vector> = cases().iter().map(|(n, m)| vector(n, m)).collect();
+    if std::env::var_os("AMUN_WRITE_VECTORS").is_some() {
+        let mut out = serde_json::to_string_pretty(&fresh).unwrap();
+        out.push('\n');
+        std::fs::write(PATH, out).unwrap();
+    }
+    assert_eq!(load(), fresh);
+}
+
+#[test]
+fn every_encoding_decodes_to_the_same_message() {
+    for v in load() {
+        let reference: ConsensusMsg =
+            decode_canonical_json(v.canonical_json.as_bytes(), MAX).unwrap();
+        let expected = encode_canonical(&reference).unwrap();
+        let decoded = [
+            wire::decode_msg(&hex::decode(&v.bincode_v1).unwrap()).unwrap(),
+            wire::decode_msg(&hex::decode(&v.bincode_v2).unwrap()).unwrap(),
+        ];
+        assert_eq!(
+            [decoded[0].0, decoded[1].0],
+            [WIRE_V1, WIRE_V2],
+            "{}",
+            v.name
+        );
+        let from_proto = WireCodec::Protobuf
+            .decode(&hex::decode(&v.protobuf).unwrap())
+            .unwrap();
+        for msg in [&decoded[0].1, &decoded[1].1, &from_proto] {
+            assert_eq!(encode_canonical(msg).unwrap(), expected, "{}", v.name);
+        }
+    }
+}
+
+#[test]
+fn protobuf_rejects_non_canonical_forms() {
+    let msg = ConsensusMsg::Commit(commit(&[0x10, 0x20], 3));
+    let good = proto::encode_msg(&msg).unwrap();
+    assert!(proto::decode_msg(&good).is_ok());
+
+    // Unknown field 15 (varint 1) appended.
+    let mut unknown = good.clone();
+    unknown.extend([0x78, 0x01]);
+    assert!(matches!(
+        proto::decode_msg(&unknown),
+        Err(WireError::Malformed)
+    ));
+
+    // Signers out of order.
+    let ConsensusMsg::Commit(c) = &msg else {
+        unreachable!()
+    };
+    let mut pb = proto::Commit::from(c);
+    pb.signatures.reverse();
+    let unsorted = prost::Message::encode_to_vec(&proto::ConsensusMsg {
+        msg: Some(proto::consensus_msg::Msg::Commit(pb)),
+    });
+    assert!(proto::decode_msg(&unsorted).is_err());
+
+    // Short block hash.
+    let mut pb = proto::Vote::from(&vote(1, false));
+    pb.block_hash.pop();
+    let short = prost::Message::encode_to_vec(&proto::ConsensusMsg {
+        msg: Some(proto::consensus_msg::Msg::Vote(pb)),
+    });
+    assert!(proto::decode_msg(&short).is_err());
+
+    // Bincode bytes on a protobuf topic and vice versa.
+    assert!(WireCodec::Protobuf
+        .decode(&wire::encode_msg(&msg, WIRE_V2).unwrap())
+        .is_err());
+    assert!(WireCodec::Bincode.decode(&good).is_err());
+}
@@ -0,0 +1,109 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::commit_cache::{commit_key, VerifiedCommits};
+use amunchain::core::consensus::driver::MsgOutcome;
+use amunchain::core::types::{Commit, ConsensusMsg, Height, H256};
+use common::{driver, id, keypairs, validators, vote_for, vote_msg};
+use ring::signature::Ed25519KeyPair;
+
+/// Commit for `height` assembled from the first `signers` validators.
+fn commit(kps: &[Ed25519KeyPair], height: u64, signers: usize) -> Commit {
+    let set = validators(kps);
+    let (mut d, _) = driver(kps);
+    d.tide.mark_finalized(Height(height - 1));
+    let mut c = None;
+    for kp in kps.iter().take(signers) {
+        c = d
+            .tide
+            .process_vote_verified(vote_for(kp, &set, height, height as u8))
+            .unwrap();
+    }
+    c.unwrap()
+}
+
+fn key(n: u8) -> H256 {
+    H256::from_bytes([n; 32])
+}
+
+#[test]
+fn least_recently_used_key_is_evicted() {
+    let mut cache = VerifiedCommits::new(2);
+    cache.insert(key(1));
+    cache.insert(key(2));
+    assert!(cache.touch(&key(1)));
+    cache.insert(key(3));
+    assert_eq!(cache.len(), 2);
+    assert!(cache.touch(&key(1)));
+    assert!(!cache.touch(&key(2)));
+    assert!(cache.touch(&key(3)));
+}
+
+#[test]
+fn key_covers_the_whole_certificate() {
+    let kps = keypairs(4);
+    let c = commit(&kps, 1, 3);
+    let mut other = c.clone();
+    other.signatures.pop_first();
+    assert_eq!(commit_key(&c).unwrap(), commit_key(&c.clone()).unwrap());
+    assert_ne!(commit_key(&c).unwrap(), commit_key(&other).unwrap());
+}
+
+#[test]
+fn repeated_certificates_hit_the_cache() {
+    let kps = keypairs(4);
+    let (mut d, m) = driver(&kps);
+    let c = commit(&kps, 1, 3);
+
+    assert_eq!(
+        d.on_msg(ConsensusMsg::Commit(c.clone())),
+        MsgOutcome::Accepted
+    );
+    for _ in 0..3 {
+        assert_eq!(
+            d.on_msg(ConsensusMsg::Commit(c.clone())),
+            MsgOutcome::Duplicate
+        );
+    }
+    assert_eq!(m.consensus_commit_cache_hits_total.get(), 3);
+    assert_eq!(m.consensus_msgs_duplicate_total.get(), 3);
+    assert_eq!(m.consensus_commits_total.get(), 1);
+}
+
+#[test]
+fn own_certificate_echoed_back_hits_the_cache() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let (mut d, m) = driver(&kps);
+    for kp in kps.iter().take(3) {
+        d.on_msg(vote_msg(kp, &set, 1));
+    }
+    assert_eq!(d.tide.finalized_height(), Height(1));
+
+    assert_eq!(
+        d.on_msg(ConsensusMsg::Commit(commit(&kps, 1, 3))),
+        MsgOutcome::Duplicate
+    );
+    assert_eq!(m.consensus_commit_cache_hits_total.get(), 1);
+
+    // A different signer subset is a different certificate: verified, then a duplicate.
+    let mut alt = commit(&kps, 1, 4);
+    let first = id(&kps[0]);
+    alt.signatures.remove(&first);
+    alt.voting_power = 3;
+    let before = m.consensus_commit_cache_hits_total.get();
+    assert_eq!(d.on_msg(ConsensusMsg::Commit(alt)), MsgOutcome::Duplicate);
+    assert_eq!(m.consensus_commit_cache_hits_total.get(), before);
+}
@@ -0,0 +1,177 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fixtures shared by the integration tests.
+
+// Each test binary compiles this module and uses only part of it.
+#![allow(dead_code)]
+
+use amunchain::core::consensus::driver::ConsensusDriver;
+use amunchain::core::consensus::signing::{vote_signing_bytes_auto, vote_signing_bytes_v3};
+use amunchain::core::consensus::tide::{expected_set_hash, DEFAULT_CHAIN_ID};
+use amunchain::core::types::{
+    ConsensusMsg, Epoch, Height, Round, Signature, ValidatorId, Vote, H256,
+};
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::node::channel::Receiver;
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub fn keypairs(n: usize) -> Vec<Ed25519KeyPair> {
+    let rng = SystemRandom::new();
+    (0..n)
+        .map(|_| {
+            let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+            Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+        })
+        .collect()
+}
+
+pub fn id(kp: &Ed25519KeyPair) -> ValidatorId {
+    ValidatorId::from_slice(kp.public_key().as_ref()).unwrap()
+}
+
+pub fn validators(kps: &[Ed25519KeyPair]) -> BTreeSet<ValidatorId> {
+    kps.iter().map(id).collect()
+}
+
+/// Epoch of fixture votes: production builds reject the legacy epoch 0.
+pub const EPOCH: Epoch = Epoch(1);
+
+/// Unsigned round-0 vote by `kp` for `block_hash` at `height` in [`EPOCH`].
+pub fn unsigned_vote(kp: &Ed25519KeyPair, height: u64, block_hash: H256) -> Vote {
+    Vote {
+        height: Height(height),
+        round: Round::ZERO,
+        epoch: EPOCH,
+        msg_counter: 0,
+        sent_ts_ms: 0,
+        ttl_ms: 0,
+        block_hash,
+        voter: id(kp),
+        signature: Signature::from_bytes([0; 64]),
+    }
+}
+
+/// [`unsigned_vote`] signed with the v3 payload for the default chain, bound to `set` in
+/// count mode.
+pub fn vote(
+    kp: &Ed25519KeyPair,
+    set: &BTreeSet<ValidatorId>,
+    height: u64,
+    block_hash: H256,
+) -> Vote {
+    let set_hash = expected_set_hash(set, None).unwrap();
+    sign_v3(
+        kp,
+        DEFAULT_CHAIN_ID,
+        set_hash,
+        unsigned_vote(kp, height, block_hash),
+    )
+}
+
+/// [`vote`] for the block `[hash; 32]`.
+pub fn vote_for(kp: &Ed25519KeyPair, set: &BTreeSet<ValidatorId>, height: u64, hash: u8) -> Vote {
+    vote(kp, set, height, H256::from_bytes([hash; 32]))
+}
+
+/// [`vote_for`] the block `[height; 32]`, as a consensus message.
+pub fn vote_msg(kp: &Ed25519KeyPair, set: &BTreeSet<ValidatorId>, height: u64) -> ConsensusMsg {
+    ConsensusMsg::Vote(vote_for(kp, set, height, height as u8))
+}
+
+/// Driver over the validators of `kps` on the default chain, with its metrics.
+pub fn driver(kps: &[Ed25519KeyPair]) -> (ConsensusDriver, Arc<Metrics>) {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let d = ConsensusDriver::new(validators(kps))
+        .unwrap()
+        .with_metrics(metrics.clone());
+    (d, metrics)
+}
+
+/// `v` re-signed by `kp` with the chain-less v1/v2 payload its fields select.
+pub fn sign_legacy(kp: &Ed25519KeyPair, v: Vote) -> Vote {
+    let voter = id(kp);
+    let msg = vote_signing_bytes_auto(
+        v.height,
+        v.round,
+        v.epoch,
+        v.msg_counter,
+        v.sent_ts_ms,
+        v.ttl_ms,
+        v.block_hash,
+        &voter,
+    )
+    .unwrap();
+    Vote {
+        voter,
+        signature: Signature::from_slice(kp.sign(&msg).as_ref()).unwrap(),
+        ..v
+    }
+}
+
+/// `v` re-signed by `kp` with the v3 payload for `chain_id`, bound to `set_hash`.
+pub fn sign_v3(kp: &Ed25519KeyPair, chain_id: &str, set_hash: H256, v: Vote) -> Vote {
+    let voter = id(kp);
+    let msg = vote_signing_bytes_v3(
+        chain_id,
+        v.height,
+        v.round,
+        v.epoch,
+        v.msg_counter,
+        v.sent_ts_ms,
+        v.ttl_ms,
+        v.block_hash,
+        set_hash,
+        &voter,
+    )
+    .unwrap();
+    Vote {
+        voter,
+        signature: Signature::from_slice(kp.sign(&msg).as_ref()).unwrap(),
+        ..v
+    }
+}
+
+/// Next message on `rx`. The driver sends synchronously, so anything expected is already
+/// queued; a missing message fails the test instead of hanging it.
+pub async fn queued<T>(rx: &mut Receiver<T>) -> Option<T> {
+    tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Copy of the directory `dir` in a fresh tempdir. sled releases the file lock of a dropped
+/// database a moment after its last handle goes, so tests reopen a copy rather than the
+/// original. Flush before dropping: the copy only holds what reached the disk.
+pub fn fresh_copy(dir: &Path) -> tempfile::TempDir {
+    let copy = tempfile::tempdir().unwrap();
+    copy_dir(dir, copy.path());
+    copy
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    std::fs::create_dir_all(to).unwrap();
+    for entry in std::fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            std::fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::compact::{vote_hash, CommitSync, CompactCommit, CompactError};
+use amunchain::core::consensus::driver::{ConsensusDriver, MsgOutcome};
+use amunchain::core::consensus::tide::{expected_set_hash, TideError};
+use amunchain::core::types::{
+    Commit, ConsensusMsg, Height, Round, Signature, ValidatorId, Vote, H256,
+};
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::networking::wire;
+use amunchain::node::channel::{channel, ChannelConfig, Receiver};
+use common::{keypairs, sign_v3, unsigned_vote, validators, EPOCH};
+use ring::signature::Ed25519KeyPair;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+const CHAIN: &str = "amun-testnet";
+
+fn vote(kp: &Ed25519KeyPair, set: &BTreeSet<ValidatorId>) -> Vote {
+    let v = unsigned_vote(kp, 1, H256::from_bytes([9; 32]));
+    sign_v3(kp, CHAIN, expected_set_hash(set, None).unwrap(), v)
+}
+
+struct Node {
+    driver: ConsensusDriver,
+    metrics: Arc<Metrics>,
+    outbound: Receiver<ConsensusMsg>,
+    sync: Receiver<CommitSync>,
+}
+
+fn node(set: &BTreeSet<ValidatorId>, compact: bool) -> Node {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let (out_tx, outbound) = channel("outbound", ChannelConfig::blocking(16), None);
+    let (sync_tx, sync) = channel("commit_sync", ChannelConfig::blocking(16), None);
+    let driver = ConsensusDriver::new(set.clone())
+        .unwrap()
+        .with_chain_id(CHAIN, None)
+        .with_metrics(metrics.clone())
+        .with_outbound(out_tx)
+        .with_commit_sync_outbound(sync_tx)
+        .with_compact_commits(compact);
+    Node {
+        driver,
+        metrics,
+        outbound,
+        sync,
+    }
+}
+
+fn sync_count(m: &Metrics, event: &str) -> u64 {
+    m.consensus_commit_sync_total
+        .with_label_values(&[event])
+        .get()
+}
+
+#[tokio::test]
+async fn missing_votes_are_fetched_from_the_relaying_peer() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let votes: Vec<Vote> = kps[..3].iter().map(|k| vote(k, &set)).collect();
+
+    // The node that finalizes gossips the compact form only.
+    let mut a = node(&set, true);
+    for v in &votes {
+        assert_eq!(
+            a.driver.on_peer_msg(b"x", ConsensusMsg::Vote(v.clone())),
+            MsgOutcome::Accepted
+        );
+    }
+    assert_eq!(a.driver.tide.finalized_height(), Height(1));
+    assert!(a.outbound.is_empty());
+    let Some(CommitSync::Compact(cc)) = common::queued(&mut a.sync).await else {
+        panic!("expected a compact commit");
+    };
+    assert_eq!(cc.vote_hashes.len(), 3);
+    assert_eq!(sync_count(&a.metrics, "compact_sent"), 1);
+    let bytes = wire::encode_commit_sync(&CommitSync::Compact(cc.clone())).unwrap();
+    assert_eq!(
+        wire::decode_commit_sync(&bytes).unwrap(),
+        CommitSync::Compact(cc.clone())
+    );
+
+    // b missed one vote and asks a for it.
+    let mut b = node(&set, true);
+    for v in &votes[..2] {
+        b.driver.on_peer_msg(b"x", ConsensusMsg::Vote(v.clone()));
+    }
+    assert_eq!(
+        b.driver
+            .on_commit_sync(b"peer-a", CommitSync::Compact(cc.clone())),
+        MsgOutcome::Accepted
+    );
+    let Some(CommitSync::Request(req)) = common::queued(&mut b.sync).await else {
+        panic!("expected a vote request");
+    };
+    assert_eq!(req.responder, b"peer-a");
+    assert_eq!(req.voters, vec![votes[2].voter.clone()]);
+    assert_eq!(sync_count(&b.metrics, "votes_requested"), 1);
+
+    assert_eq!(
+        a.driver.on_commit_sync(b"peer-b", CommitSync::Request(req)),
+        MsgOutcome::Accepted
+    );
+    let Some(ConsensusMsg::Vote(served)) = common::queued(&mut a.outbound).await else {
+        panic!("expected the requested vote");
+    };
+    assert_eq!(vote_hash(&served).unwrap(), vote_hash(&votes[2]).unwrap());
+    assert_eq!(sync_count(&a.metrics, "votes_served"), 1);
+
+    b.driver.on_peer_msg(b"peer-a", ConsensusMsg::Vote(served));
+    assert_eq!(b.driver.tide.finalized_height(), Height(1));
+    assert_eq!(
+        b.driver.on_commit_sync(b"peer-a", CommitSync::Compact(cc)),
+        MsgOutcome::Duplicate
+    );
+}
+
+#[test]
+fn compact_commits_expand_to_the_full_commit() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let votes: Vec<Vote> = kps[..3].iter().map(|k| vote(k, &set)).collect();
+    let commit = Commit {
+        height: Height(1),
+        round: Round::ZERO,
+        epoch: EPOCH,
+        msg_counter: 0,
+        sent_ts_ms: 0,
+        ttl_ms: 0,
+        block_hash: H256::from_bytes([9; 32]),
+        signatures: votes
+            .iter()
+            .map(|v| (v.voter.clone(), v.signature.clone()))
+            .collect(),
+        voting_power: 3,
+        validator_set_hash: expected_set_hash(&set, None).unwrap(),
+    };
+    let cc = CompactCommit::from_commit(&commit, &set).unwrap();
+    let signers = cc.signers(&set).unwrap();
+    assert_eq!(signers.len(), 3);
+    assert!(signers.iter().all(|s| commit.signatures.contains_key(s)));
+    let hashes: Vec<H256> = signers
+        .iter()
+        .map(|s| vote_hash(votes.iter().find(|v| &v.voter == s).unwrap()).unwrap())
+        .collect();
+    assert_eq!(cc.vote_hashes, hashes);
+
+    let held = |vid: &ValidatorId| votes.iter().find(|v| &v.voter == vid).cloned();
+    let rebuilt = cc.expand(&set, held).unwrap();
+    assert_eq!(rebuilt.signatures, commit.signatures);
+
+    // A missing vote, or one that differs from the committed one, has to be fetched.
+    let partial = |vid: &ValidatorId| held(vid).filter(|v| v.voter != votes[0].voter);
+    assert_eq!(
+        cc.expand(&set, partial).unwrap_err(),
+        CompactError::Missing(vec![votes[0].voter.clone()])
+    );
+    let forged = |vid: &ValidatorId| {
+        held(vid).map(|mut v| {
+            if v.voter == votes[1].voter {
+                v.signature = Signature::from_bytes([0; 64]);
+            }
+            v
+        })
+    };
+    assert_eq!(
+        cc.expand(&set, forged).unwrap_err(),
+        CompactError::Missing(vec![votes[1].voter.clone()])
+    );
+
+    let mut short = cc.clone();
+    short.vote_hashes.pop();
+    assert_eq!(short.signers(&set), Err(CompactError::Bitmap));
+    let outsider = validators(&keypairs(1));
+    assert_eq!(
+        CompactCommit::from_commit(&commit, &outsider),
+        Err(CompactError::UnknownSigner)
+    );
+
+    // A node on another validator set cannot read the bitmap.
+    let mut other = node(&validators(&keypairs(4)), false);
+    assert_eq!(
+        other.driver.on_commit_sync(b"x", CommitSync::Compact(cc)),
+        MsgOutcome::Rejected(TideError::ValidatorSetMismatch)
+    );
+    // The rebuilt commit verifies like the original.
+    let mut fresh = node(&set, false);
+    assert_eq!(
+        fresh
+            .driver
+            .on_peer_msg(b"x", ConsensusMsg::Commit(rebuilt)),
+        MsgOutcome::Accepted
+    );
+}
@@ -0,0 +1,169 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::config::ConfigLoader;
+use amunchain::core::types::{BeaconConfig, NodeConfig};
+use amunchain::node::cli::{self, CliError};
+use amunchain::node::config_check::check;
+
+const BASE: &str = r#"
+[node]
+name = "t"
+data_dir = "data"
+
+[http]
+listen_addr = "127.0.0.1:9090"
+
+[p2p]
+listen_addr = "/ip4/0.0.0.0/tcp/30333"
+topic = "t"
+max_msg_per_sec = 200
+max_peers_per_ip = 3
+bootstrap = ["/ip4/10.0.0.1/tcp/30333/p2p/12D3KooWLh9S2QyVMgQgHmuzu2tCA6KsL9mpNgnvoiA1SQ9nHnMA"]
+allow_peers = ["12D3KooWLh9S2QyVMgQgHmuzu2tCA6KsL9mpNgnvoiA1SQ9nHnMA"]
+
+[consensus]
+validators_hex = ["1111111111111111111111111111111111111111111111111111111111111111"]
+"#;
+
+fn config(edit: impl FnOnce(&mut NodeConfig)) -> NodeConfig {
+    let mut cfg: NodeConfig = toml::from_str(BASE).unwrap();
+    edit(&mut cfg);
+    cfg
+}
+
+fn fields(cfg: &NodeConfig) -> Vec<String> {
+    check(cfg).into_iter().map(|i| i.field).collect()
+}
+
+#[test]
+fn shipped_config_is_valid() {
+    assert!(check(&config(|_| {})).is_empty());
+    cli::check_config(&ConfigLoader::new().file("configs/node.toml")).unwrap();
+}
+
+#[test]
+fn reports_every_problem_with_its_field() {
+    let cfg = config(|c| {
+        c.http.listen_addr = "localhost".into();
+        c.p2p.listen_addr = "/ip4/0.0.0.0/udp/1".into();
+        c.p2p.bootstrap.push("/ip4/10.0.0.2/tcp/30333".into());
+        c.p2p.allow_peers.push("not-a-peer".into());
+        c.consensus.validators_hex.push("abcd".into());
+        c.consensus
+            .validators_hex
+            .push(c.consensus.validators_hex[0].to_uppercase());
+        c.consensus.beacon = Some(BeaconConfig {
+            epoch_length: 0,
+            genesis_randomness_hex: "abcd".into(),
+        });
+    });
+    assert_eq!(
+        fields(&cfg),
+        [
+            "http.listen_addr",
+            "p2p.listen_addr",
+            "p2p.bootstrap[1]",
+            "p2p.allow_peers[1]",
+            "consensus.validators_hex[1]",
+            "consensus.validators_hex[2]",
+            "consensus.beacon.epoch_length",
+            "consensus.beacon.genesis_randomness_hex",
+        ]
+    );
+}
+
+#[test]
+fn listeners_must_not_share_a_port() {
+    let cfg = config(|c| c.http.listen_addr = "127.0.0.1:30333".into());
+    assert_eq!(fields(&cfg), ["p2p.listen_addr"]);
+    // Different interfaces may reuse the port number.
+    let cfg = config(|c| {
+        c.http.listen_addr = "127.0.0.1:30333".into();
+        c.p2p.listen_addr = "/ip4/10.0.0.1/tcp/30333".into();
+    });
+    assert!(check(&cfg).is_empty());
+
+    // The node binds the layered values, so that is what gets checked.
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("node.toml");
+    std::fs::write(&path, BASE).unwrap();
+    let loader = ConfigLoader::new().file(&path).env_vars([
+        (
+            "AMUNCHAIN__P2P__LISTEN_ADDR".to_string(),
+            "/ip4/0.0.0.0/tcp/9090".to_string(),
+        ),
+        ("AMUN_HTTP_ADDR".to_string(), "0.0.0.0:9090".to_string()),
+    ]);
+    let err = cli::check_config(&loader).unwrap_err();
+    assert!(err.to_string().contains("p2p.listen_addr"), "{err}");
+}
+
+#[test]
+fn registry_needs_its_key() {
+    let dir = tempfile::tempdir().unwrap();
+    let registry = dir.path().join("registry.toml");
+    std::fs::write(&registry, "").unwrap();
+    let cfg = config(|c| c.p2p.peer_registry_path = Some(registry.display().to_string()));
+    assert_eq!(fields(&cfg), ["p2p.peer_registry_pubkey_hex"]);
+
+    let cfg = config(|c| {
+        c.p2p.peer_registry_path = Some(registry.display().to_string());
+        c.p2p.peer_registry_pubkey_hex = Some("22".repeat(32));
+    });
+    assert!(check(&cfg).is_empty());
+
+    let cfg = config(|c| {
+        c.p2p.allow_peers.clear();
+        c.p2p.require_allow_peers = true;
+    });
+    assert_eq!(fields(&cfg), ["p2p.require_allow_peers"]);
+}
+
+#[test]
+fn tls_paths_come_in_pairs_and_must_exist() {
+    let cfg = config(|c| c.http.tls_cert_path = Some("tests/fixtures/tls/server.pem".into()));
+    assert_eq!(fields(&cfg), ["http.tls_key_path"]);
+
+    let cfg = config(|c| c.http.tls_client_ca_path = Some("tests/fixtures/tls/ca.pem".into()));
+    assert_eq!(fields(&cfg), ["http.tls_client_ca_path"]);
+
+    let cfg = config(|c| {
+        c.http.tls_cert_path = Some("tests/fixtures/tls/server.pem".into());
+        c.http.tls_key_path = Some("tests/fixtures/tls/missing.key".into());
+    });
+    assert_eq!(fields(&cfg), ["http.tls_key_path"]);
+
+    let cfg = config(|c| {
+        c.http.tls_cert_path = Some("tests/fixtures/tls/server.pem".into());
+        c.http.tls_key_path = Some("tests/fixtures/tls/server.key".into());
+        c.http.tls_client_ca_path = Some("tests/fixtures/tls/ca.pem".into());
+    });
+    assert!(check(&cfg).is_empty());
+}
+
+#[test]
+fn check_config_lists_issues() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("node.toml");
+    std::fs::write(&path, BASE.replace("127.0.0.1:9090", "nowhere")).unwrap();
+    let err = cli::check_config(&ConfigLoader::new().file(&path)).unwrap_err();
+    assert!(matches!(&err, CliError::InvalidConfig { issues, .. } if issues.len() == 1));
+    assert!(err.to_string().contains("http.listen_addr"));
+
+    std::fs::write(&path, "[node]\nname = 1\n").unwrap();
+    assert!(matches!(
+        cli::check_config(&ConfigLoader::new().file(&path)),
+        Err(CliError::Config(_))
+    ));
+}
@@ -0,0 +1,174 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::config::ConfigLoader;
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::networking::p2p::P2pTunables;
+use amunchain::node::reload::{changed_fields, ReloadOutcome, Reloader, TESTNET_PEERS};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+const PEER: &str = "12D3KooWLh9S2QyVMgQgHmuzu2tCA6KsL9mpNgnvoiA1SQ9nHnMA";
+
+struct Fixture {
+    _dir: tempfile::TempDir,
+    path: std::path::PathBuf,
+    metrics: Arc<Metrics>,
+    reloader: Reloader,
+    p2p: watch::Receiver<P2pTunables>,
+}
+
+fn fixture(initial: &str) -> Fixture {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("node.toml");
+    std::fs::write(&path, initial).unwrap();
+    let loader = ConfigLoader::new().file(&path);
+    let config = loader.load().unwrap();
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let (tx, rx) = watch::channel(P2pTunables {
+        max_msg_per_sec: config.p2p.max_msg_per_sec,
+        max_peers_per_ip: config.p2p.max_peers_per_ip,
+        allow_peers: config.p2p.allow_peers.clone(),
+    });
+    let reloader = Reloader::new(loader, config, metrics.clone()).with_p2p(tx);
+    Fixture {
+        _dir: dir,
+        path,
+        metrics,
+        reloader,
+        p2p: rx,
+    }
+}
+
+fn write(path: &Path, raw: &str) {
+    std::fs::write(path, raw).unwrap();
+}
+
+fn reloads(m: &Metrics, result: &str) -> u64 {
+    m.config_reloads_total.with_label_values(&[result]).get()
+}
+
+#[test]
+fn unchanged_file_applies_nothing() {
+    let mut f = fixture("[p2p]\nmax_peers_per_ip = 5\n");
+    assert_eq!(f.reloader.reload(), ReloadOutcome::Unchanged);
+    assert!(!f.p2p.has_changed().unwrap());
+    assert_eq!(reloads(&f.metrics, "unchanged"), 1);
+}
+
+#[test]
+fn tunables_are_pushed_to_p2p() {
+    let mut f = fixture("[p2p]\nmax_peers_per_ip = 5\n");
+    write(
+        &f.path,
+        &format!("[p2p]\nmax_peers_per_ip = 2\nmax_msg_per_sec = 50\nallow_peers = [\"{PEER}\"]\n"),
+    );
+    let ReloadOutcome::Applied(fields) = f.reloader.reload() else {
+        panic!("expected applied");
+    };
+    assert_eq!(
+        fields,
+        [
+            "p2p.allow_peers",
+            "p2p.max_msg_per_sec",
+            "p2p.max_peers_per_ip"
+        ]
+    );
+    assert!(f.p2p.has_changed().unwrap());
+    let t = f.p2p.borrow_and_update().clone();
+    assert_eq!(t.max_peers_per_ip, 2);
+    assert_eq!(t.max_msg_per_sec, 50);
+    assert_eq!(t.allow_peers, [PEER]);
+    assert_eq!(f.reloader.current().p2p.max_peers_per_ip, 2);
+    assert_eq!(reloads(&f.metrics, "applied"), 1);
+}
+
+#[test]
+fn reloading_a_default_validator_keeps_its_allowlist() {
+    // No allow_peers and no registry: a validator starts locked to the testnet peers.
+    let mut f = fixture("[p2p]\nmax_msg_per_sec = 200\n");
+    write(&f.path, "[p2p]\nmax_msg_per_sec = 100\n");
+    assert_eq!(
+        f.reloader.reload(),
+        ReloadOutcome::Applied(vec!["p2p.max_msg_per_sec".to_string()])
+    );
+    let t = f.p2p.borrow_and_update().clone();
+    assert_eq!(t.max_msg_per_sec, 100);
+    assert_eq!(t.allow_peers, TESTNET_PEERS);
+
+    // Observers allow everyone, before and after.
+    let mut f = fixture("[node]\nrole = \"observer\"\n[p2p]\nmax_msg_per_sec = 200\n");
+    write(
+        &f.path,
+        "[node]\nrole = \"observer\"\n[p2p]\nmax_msg_per_sec = 100\n",
+    );
+    assert!(matches!(f.reloader.reload(), ReloadOutcome::Applied(_)));
+    assert!(f.p2p.borrow_and_update().allow_peers.is_empty());
+}
+
+#[test]
+fn immutable_fields_reject_the_whole_reload() {
+    for change in [
+        "[node]\ndata_dir = \"elsewhere\"\n",
+        "[node]\nchain_id = \"other\"\n",
+    ] {
+        let mut f = fixture("");
+        write(&f.path, &format!("{change}[p2p]\nmax_peers_per_ip = 9\n"));
+        let ReloadOutcome::Rejected(reason) = f.reloader.reload() else {
+            panic!("expected rejected for {change}");
+        };
+        assert!(reason.contains("cannot change"), "{reason}");
+        assert!(!f.p2p.has_changed().unwrap());
+        assert_eq!(f.reloader.current().p2p.max_peers_per_ip, 3);
+        assert_eq!(reloads(&f.metrics, "rejected"), 1);
+    }
+}
+
+#[test]
+fn invalid_or_unreadable_config_is_rejected() {
+    let mut f = fixture("");
+    write(&f.path, "[p2p]\nmax_msg_per_sec = 0\n");
+    let ReloadOutcome::Rejected(reason) = f.reloader.reload() else {
+        panic!("expected rejected");
+    };
+    assert!(reason.contains("p2p.max_msg_per_sec"), "{reason}");
+
+    write(&f.path, "[p2p\n");
+    assert_eq!(f.reloader.reload().label(), "rejected");
+    assert!(!f.p2p.has_changed().unwrap());
+    assert_eq!(reloads(&f.metrics, "rejected"), 2);
+}
+
+#[test]
+fn restart_only_changes_are_not_applied() {
+    let mut f = fixture("");
+    write(&f.path, "[http]\nlisten_addr = \"127.0.0.1:9191\"\n");
+    assert_eq!(f.reloader.reload(), ReloadOutcome::Unchanged);
+    assert_eq!(f.reloader.current().http.listen_addr, "127.0.0.1:9090");
+}
+
+#[test]
+fn changed_fields_lists_dotted_paths() {
+    let a = ConfigLoader::new().load().unwrap();
+    let b = ConfigLoader::new()
+        .set("telemetry.log_filter", "debug")
+        .set("p2p.peer_registry_path", "/tmp/registry.toml")
+        .load()
+        .unwrap();
+    assert_eq!(
+        changed_fields(&a, &b),
+        ["p2p.peer_registry_path", "telemetry.log_filter"]
+    );
+    assert!(changed_fields(&a, &a).is_empty());
+}
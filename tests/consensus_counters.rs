@@ -0,0 +1,45 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::types::{encode_canonical, encode_canonical_json, Epoch, Height, Round};
+
+#[test]
+fn steps_are_guarded_at_both_ends() {
+    assert_eq!(Height::MAX.checked_next(), None);
+    assert_eq!(Height::ZERO.checked_prev(), None);
+    assert_eq!(Height(7).checked_next(), Some(Height(8)));
+    assert_eq!(Round(u64::MAX - 1).checked_add(2), None);
+    assert_eq!(Round(3).saturating_add(u64::MAX), Round::MAX);
+    assert_eq!(Epoch(2).saturating_sub(5), Epoch::ZERO);
+
+    assert_eq!(Height(10).since(Height(4)), Some(6));
+    assert_eq!(Height(4).since(Height(10)), None);
+    assert!(Epoch::default().is_zero());
+}
+
+#[test]
+fn encodings_match_bare_u64() {
+    for n in [0, 1, 1_000_000, u64::MAX] {
+        assert_eq!(
+            encode_canonical(&Height(n)).unwrap(),
+            encode_canonical(&n).unwrap()
+        );
+        assert_eq!(
+            encode_canonical_json(&Round(n)).unwrap(),
+            encode_canonical_json(&n).unwrap()
+        );
+        assert_eq!(Epoch(n).to_be_bytes(), n.to_be_bytes());
+        assert_eq!(Height::from(n).to_string(), n.to_string());
+        assert_eq!(u64::from(Height(n)), n);
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::driver::MsgOutcome;
+use amunchain::core::types::Height;
+use amunchain::monitoring::health::{HealthMonitor, HealthStatus, ReadinessCriteria};
+use common::{driver, keypairs, validators, vote_for, vote_msg};
+
+fn participation(health: &HealthMonitor) -> HealthStatus {
+    health
+        .report()
+        .checks
+        .into_iter()
+        .find(|c| c.name == "consensus_participation")
+        .unwrap()
+        .status
+}
+
+#[test]
+fn local_votes_wait_for_enough_peers() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let (d, m) = driver(&kps);
+    let mut d = d.with_min_consensus_peers(2);
+    let health = HealthMonitor::new(ReadinessCriteria::default(), m.clone());
+
+    // Until a peer count arrives the node counts as isolated.
+    assert!(!d.is_participating());
+    assert_eq!(m.consensus_votes_gated.get(), 1);
+    assert_eq!(participation(&health), HealthStatus::Unhealthy);
+    d.set_connected_peers(1);
+    assert_eq!(d.on_msg(vote_msg(&kps[0], &set, 1)), MsgOutcome::Withheld);
+    assert_eq!(m.consensus_votes_withheld_total.get(), 1);
+    assert!(!d.tide.has_vote(&vote_for(&kps[0], &set, 1, 1)));
+
+    // Peers' votes are still verified and counted toward finality.
+    for kp in &kps[1..] {
+        assert_eq!(
+            d.on_peer_msg(b"peer", vote_msg(kp, &set, 1)),
+            MsgOutcome::Accepted
+        );
+    }
+    assert_eq!(d.tide.finalized_height(), Height(1));
+
+    d.set_connected_peers(2);
+    assert!(d.is_participating());
+    assert_eq!(m.consensus_votes_gated.get(), 0);
+    assert_eq!(participation(&health), HealthStatus::Healthy);
+    assert_eq!(d.on_msg(vote_msg(&kps[0], &set, 2)), MsgOutcome::Accepted);
+
+    d.set_connected_peers(0);
+    assert_eq!(d.on_msg(vote_msg(&kps[1], &set, 2)), MsgOutcome::Withheld);
+    assert_eq!(m.consensus_votes_withheld_total.get(), 2);
+}
+
+#[test]
+fn gate_is_off_by_default() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let (mut d, m) = driver(&kps);
+    assert!(d.is_participating());
+    assert_eq!(d.on_msg(vote_msg(&kps[0], &set, 1)), MsgOutcome::Accepted);
+    assert_eq!(m.consensus_votes_gated.get(), 0);
+
+    let cfg: amunchain::core::types::ConsensusConfig =
+        toml::from_str("validators_hex = []").unwrap();
+    assert_eq!(cfg.min_consensus_peers, 0);
+}
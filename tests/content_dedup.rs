@@ -0,0 +1,102 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::types::{
+    CanonicalMap, Commit, ConsensusMsg, Epoch, Height, Round, Signature, ValidatorId, Vote, H256,
+};
+use amunchain::networking::dedup::{content_key, SeenCache};
+use amunchain::networking::wire::{WireCodec, WIRE_V1, WIRE_V2};
+use std::time::{Duration, Instant};
+
+fn vote(sig: u8) -> ConsensusMsg {
+    ConsensusMsg::Vote(Vote {
+        height: Height(4),
+        round: Round(1),
+        epoch: Epoch(1),
+        msg_counter: 9,
+        sent_ts_ms: 1_000,
+        ttl_ms: 5_000,
+        block_hash: H256::from_bytes([1; 32]),
+        voter: ValidatorId::from_bytes([2; 32]),
+        signature: Signature::from_bytes([sig; 64]),
+    })
+}
+
+#[test]
+fn key_is_independent_of_the_encoding() {
+    let msg = vote(3);
+    let key = content_key(&msg).unwrap();
+    for (codec, version) in [
+        (WireCodec::Bincode, WIRE_V1),
+        (WireCodec::Bincode, WIRE_V2),
+        (WireCodec::Protobuf, WIRE_V2),
+    ] {
+        let bytes = codec.encode(&msg, version).unwrap();
+        let back = codec.decode_validated(&bytes).unwrap();
+        assert_eq!(content_key(&back).unwrap(), key, "{codec:?} v{version}");
+    }
+}
+
+#[test]
+fn forged_copies_do_not_share_the_key() {
+    // Same type, voter, height, round and counter; only the signature differs.
+    assert_ne!(
+        content_key(&vote(3)).unwrap(),
+        content_key(&vote(4)).unwrap()
+    );
+
+    let commit = |n: u8| {
+        ConsensusMsg::Commit(Commit {
+            height: Height(4),
+            round: Round(1),
+            epoch: Epoch(1),
+            msg_counter: 0,
+            sent_ts_ms: 0,
+            ttl_ms: 0,
+            block_hash: H256::from_bytes([1; 32]),
+            signatures: CanonicalMap::from([(
+                ValidatorId::from_bytes([2; 32]),
+                Signature::from_bytes([n; 64]),
+            )]),
+            voting_power: 1,
+            validator_set_hash: H256::ZERO,
+        })
+    };
+    assert_eq!(
+        content_key(&commit(5)).unwrap(),
+        content_key(&commit(5)).unwrap()
+    );
+    assert_ne!(
+        content_key(&commit(5)).unwrap(),
+        content_key(&commit(6)).unwrap()
+    );
+}
+
+#[test]
+fn seen_cache_forgets_by_age_and_capacity() {
+    let now = Instant::now();
+    let key = |n: u8| H256::from_bytes([n; 32]);
+    let mut seen = SeenCache::new(2, Duration::from_secs(10));
+
+    assert!(seen.insert(key(1), now));
+    assert!(!seen.insert(key(1), now + Duration::from_secs(9)));
+    assert!(seen.insert(key(1), now + Duration::from_secs(10)));
+
+    let later = now + Duration::from_secs(11);
+    assert!(seen.insert(key(2), later));
+    assert!(seen.insert(key(3), later));
+    assert_eq!(seen.len(), 2);
+    // Key 1 was the oldest and made room for key 3.
+    assert!(seen.insert(key(1), later));
+    assert!(!seen.insert(key(3), later));
+}
@@ -0,0 +1,175 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::networking::addr_book::{AddrBook, DialBack, DialBackConfig, Skip};
+use amunchain::networking::p2p::{spawn_p2p, P2pConfig};
+use libp2p::{swarm::ConnectionId, Multiaddr, PeerId};
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn config(dir: &tempfile::TempDir, port: u16, bootstrap: Vec<String>) -> P2pConfig {
+    P2pConfig {
+        listen_addr: format!("/ip4/127.0.0.1/tcp/{port}"),
+        consensus_topic: "dialback-test".to_string(),
+        consensus_codec: Default::default(),
+        evidence_topic: "dialback-test-evidence".to_string(),
+        max_msg_per_sec: 100,
+        max_peers_per_ip: 4,
+        data_dir: dir.path().to_string_lossy().to_string(),
+        identity: Default::default(),
+        bootstrap,
+        allow_peers: Vec::new(),
+        extensions: None,
+        channels: Default::default(),
+        gossipsub: Default::default(),
+        limits: Default::default(),
+        read_only: false,
+    }
+}
+
+fn addr(s: &str) -> Multiaddr {
+    s.parse().unwrap()
+}
+
+#[test]
+fn only_addresses_on_the_connected_ip_are_dialed() {
+    let cfg = DialBackConfig {
+        max_addrs_per_peer: 2,
+        ..DialBackConfig::default()
+    };
+    let mut dial_back = DialBack::new(cfg);
+    let book = AddrBook::default();
+    let peer = PeerId::random();
+    let advertised = [
+        addr("/ip4/10.0.0.1/tcp/4001"),
+        addr("/ip4/10.0.0.1/tcp/4001"),
+        addr("/ip4/192.0.2.7/tcp/4001"),
+        addr("/ip4/10.0.0.1/udp/4001"),
+        addr("/ip4/10.0.0.1/tcp/4002"),
+        addr("/ip4/10.0.0.1/tcp/4003"),
+    ];
+
+    // Nothing is dialed for a peer whose connection address is unknown.
+    let (dial, _) = dial_back.candidates(&book, &peer, &advertised);
+    assert!(dial.is_empty());
+
+    dial_back.connected(peer, Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))));
+    // A later connection from elsewhere does not move the peer.
+    dial_back.connected(peer, Some(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 7))));
+    let (dial, skipped) = dial_back.candidates(&book, &peer, &advertised);
+    assert_eq!(
+        dial,
+        vec![
+            addr("/ip4/10.0.0.1/tcp/4001"),
+            addr("/ip4/10.0.0.1/tcp/4002")
+        ]
+    );
+    let reasons: Vec<Skip> = skipped.into_iter().map(|(_, s)| s).collect();
+    assert_eq!(
+        reasons,
+        vec![Skip::Known, Skip::Foreign, Skip::Foreign, Skip::Full]
+    );
+
+    // In-flight and verified addresses are not dialed again, and count against the limit.
+    dial_back.started(ConnectionId::new_unchecked(1), peer, dial[0].clone());
+    assert!(dial_back.is_pending(&ConnectionId::new_unchecked(1)));
+    let verified = dial_back
+        .succeeded(&ConnectionId::new_unchecked(1))
+        .unwrap();
+    assert!(book.add(verified.0, verified.1));
+    let (dial, _) = dial_back.candidates(&book, &peer, &advertised);
+    assert_eq!(dial, vec![addr("/ip4/10.0.0.1/tcp/4002")]);
+    assert_eq!(book.addresses(&peer), vec![addr("/ip4/10.0.0.1/tcp/4001")]);
+    assert_eq!(book.peers(), vec![peer]);
+
+    dial_back.disconnected(&peer);
+    let (dial, _) = dial_back.candidates(&book, &peer, &advertised);
+    assert!(dial.is_empty());
+}
+
+#[test]
+fn consecutive_failures_penalize_until_a_dial_back_succeeds() {
+    let mut dial_back = DialBack::new(DialBackConfig::default());
+    let peer = PeerId::random();
+    let bogus = addr("/ip4/10.0.0.1/tcp/1");
+    let fail = |dial_back: &mut DialBack, id: usize| {
+        dial_back.started(ConnectionId::new_unchecked(id), peer, bogus.clone());
+        dial_back.failed(&ConnectionId::new_unchecked(id)).unwrap()
+    };
+
+    let penalized: Vec<bool> = (0..4).map(|i| fail(&mut dial_back, i).penalize).collect();
+    assert_eq!(penalized, vec![false, false, true, true]);
+    assert_eq!(dial_back.pending_len(), 0);
+    // Unknown connections are not dial-backs.
+    assert!(dial_back.failed(&ConnectionId::new_unchecked(99)).is_none());
+    assert!(dial_back
+        .succeeded(&ConnectionId::new_unchecked(99))
+        .is_none());
+
+    dial_back.started(ConnectionId::new_unchecked(10), peer, bogus.clone());
+    assert!(dial_back
+        .succeeded(&ConnectionId::new_unchecked(10))
+        .is_some());
+    let again = fail(&mut dial_back, 11);
+    assert_eq!((again.consecutive, again.penalize), (1, false));
+}
+
+#[tokio::test]
+async fn advertised_listen_address_is_verified_by_dialing_back() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let (dir_a, dir_b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    let metrics_b = Arc::new(Metrics::new().unwrap());
+    let (_a, _events_a, _join_a) = spawn_p2p(
+        config(&dir_a, port, Vec::new()),
+        Arc::new(Metrics::new().unwrap()),
+    )
+    .unwrap();
+    let (b, _events_b, _join_b) = spawn_p2p(
+        config(&dir_b, 0, vec![format!("/ip4/127.0.0.1/tcp/{port}")]),
+        metrics_b.clone(),
+    )
+    .unwrap();
+
+    // b dialed a's listen address itself, but only the dial-back puts it in the book.
+    let book = b.addresses();
+    let listen = addr(&format!("/ip4/127.0.0.1/tcp/{port}"));
+    let peer = tokio::time::timeout(Duration::from_secs(30), async {
+        loop {
+            if let Some(p) = book.peers().into_iter().find(|p| book.contains(p, &listen)) {
+                return p;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("a's address verified");
+    assert_eq!(book.addresses(&peer), vec![listen]);
+    let verified = metrics_b
+        .p2p_dialback_total
+        .with_label_values(&["verified"])
+        .get();
+    assert!(verified >= 1);
+    assert_eq!(
+        metrics_b
+            .p2p_dialback_total
+            .with_label_values(&["failed"])
+            .get(),
+        0
+    );
+}
@@ -0,0 +1,140 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::consensus::difficulty::{
+    retarget, DifficultyError, DifficultyState, RetargetParams,
+};
+use amunchain::core::state::persistent_state::PersistentState;
+use amunchain::core::types::Height;
+use proptest::prelude::*;
+
+/// Target with `v` in the low 8 bytes.
+fn target(v: u64) -> [u8; 32] {
+    let mut t = [0u8; 32];
+    t[24..].copy_from_slice(&v.to_be_bytes());
+    t
+}
+
+fn params() -> RetargetParams {
+    RetargetParams {
+        window: 10,
+        block_time_ms: 1_000,
+        max_adjustment: 4,
+        pow_limit: [0x0f; 32],
+    }
+}
+
+#[test]
+fn retarget_scales_with_the_observed_window() {
+    let p = params();
+    assert_eq!(retarget(target(1_000), 10_000, &p), target(1_000));
+    // Blocks twice as slow: twice as easy.
+    assert_eq!(retarget(target(1_000), 20_000, &p), target(2_000));
+    // Twice as fast: twice as hard.
+    assert_eq!(retarget(target(1_000), 5_000, &p), target(500));
+}
+
+#[test]
+fn adjustment_is_bounded() {
+    let p = params();
+    assert_eq!(retarget(target(1_000), 1_000_000, &p), target(4_000));
+    assert_eq!(retarget(target(1_000), 0, &p), target(250));
+    assert_eq!(retarget(p.pow_limit, 40_000, &p), p.pow_limit);
+    assert_eq!(retarget(target(1), 0, &p), target(1));
+}
+
+#[test]
+fn full_width_targets_do_not_overflow() {
+    let p = RetargetParams {
+        pow_limit: [0xff; 32],
+        ..params()
+    };
+    let mut high = [0u8; 32];
+    high[0] = 0x80;
+    assert_eq!(retarget(high, 40_000, &p), [0xff; 32]);
+    let mut half = [0u8; 32];
+    half[0] = 0x40;
+    assert_eq!(retarget(high, 5_000, &p), half);
+}
+
+#[test]
+fn retargets_only_at_window_boundaries() {
+    let p = params();
+    let mut d = DifficultyState::genesis(target(1_000), 0, &p).unwrap();
+    for h in 1..10 {
+        assert_eq!(d.on_block(Height(h), h * 2_000, &p).unwrap(), None);
+    }
+    assert_eq!(
+        d.on_block(Height(10), 20_000, &p).unwrap(),
+        Some(target(2_000))
+    );
+    // The next window is measured from the block that closed this one.
+    for h in 11..20 {
+        d.on_block(Height(h), 20_000 + (h - 10) * 1_000, &p)
+            .unwrap();
+    }
+    assert_eq!(
+        d.on_block(Height(20), 30_000, &p).unwrap(),
+        Some(target(2_000))
+    );
+    assert!(matches!(
+        d.on_block(Height(20), 31_000, &p),
+        Err(DifficultyError::NonMonotonicHeight)
+    ));
+}
+
+#[test]
+fn genesis_rejects_bad_inputs() {
+    let p = params();
+    assert!(matches!(
+        DifficultyState::genesis([0u8; 32], 0, &p),
+        Err(DifficultyError::BadTarget)
+    ));
+    assert!(matches!(
+        DifficultyState::genesis([0xff; 32], 0, &p),
+        Err(DifficultyError::BadTarget)
+    ));
+    assert!(matches!(
+        DifficultyState::genesis(target(1), 0, &RetargetParams { window: 0, ..p }),
+        Err(DifficultyError::BadParams)
+    ));
+}
+
+#[test]
+fn difficulty_persists() {
+    let p = params();
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    assert_eq!(DifficultyState::load(&st).unwrap(), None);
+
+    let mut d = DifficultyState::genesis(target(1_000), 5, &p).unwrap();
+    d.on_block(Height(10), 7_000, &p).unwrap();
+    d.commit(&st).unwrap();
+    assert_eq!(DifficultyState::load(&st).unwrap(), Some(d));
+}
+
+proptest! {
+    #[test]
+    fn prop_retarget_is_bounded_and_monotonic(
+        t in 1u64..u64::MAX / 8,
+        a in any::<u64>(),
+        b in any::<u64>(),
+    ) {
+        let p = RetargetParams { pow_limit: [0xff; 32], ..params() };
+        let (lo, hi) = (a.min(b), a.max(b));
+        let (rl, rh) = (retarget(target(t), lo, &p), retarget(target(t), hi, &p));
+        prop_assert!(rl <= rh);
+        prop_assert!(rh <= target(t.saturating_mul(4)));
+        prop_assert!(rl >= target((t / 4).max(1)));
+    }
+}
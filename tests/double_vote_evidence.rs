@@ -0,0 +1,222 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::driver::{DriverError, MsgOutcome};
+use amunchain::core::consensus::evidence::EvidencePool;
+use amunchain::core::consensus::tide::{Slashing, TideConfig, TideError, TideFinalizer};
+use amunchain::core::economics::bank::{Bank, BONDED_POOL};
+use amunchain::core::economics::staking::StakingLedger;
+use amunchain::core::types::{
+    ConsensusMsg, DoubleVoteEvidence, Evidence, Height, Round, ValidatorId, H256,
+};
+use amunchain::networking::wire::{self, WireError};
+use amunchain::node::channel::{channel, ChannelConfig};
+use common::{driver, keypairs, validators, vote_for};
+use ring::signature::Ed25519KeyPair;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn double_vote(kps: &[Ed25519KeyPair], height: u64) -> Evidence {
+    let set = validators(kps);
+    let ev = DoubleVoteEvidence::new(
+        vote_for(&kps[0], &set, height, 1),
+        vote_for(&kps[0], &set, height, 2),
+    );
+    Evidence::DoubleVote(ev.unwrap())
+}
+
+#[derive(Clone, Default)]
+struct Counting(Arc<AtomicUsize>);
+
+impl Slashing for Counting {
+    fn on_double_vote(&self, _offender: &ValidatorId) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[tokio::test]
+async fn detected_double_votes_are_broadcast_as_evidence() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let (tx, mut rx) = channel::<Evidence>("evidence", ChannelConfig::lossy(8), None);
+    let (d, metrics) = driver(&kps);
+    let mut d = d.with_evidence_outbound(tx);
+
+    let first = vote_for(&kps[0], &set, 1, 1);
+    assert_eq!(
+        d.on_msg(ConsensusMsg::Vote(first.clone())),
+        MsgOutcome::Accepted
+    );
+    assert_eq!(
+        d.on_msg(ConsensusMsg::Vote(vote_for(&kps[0], &set, 1, 2))),
+        MsgOutcome::Rejected(TideError::DoubleVote)
+    );
+
+    let Evidence::DoubleVote(sent) = common::queued(&mut rx).await.unwrap();
+    assert!(sent.is_conflicting());
+    assert_eq!(sent.first.voter, first.voter);
+    assert_eq!(d.evidence().len(), 1);
+    let local = metrics
+        .consensus_evidence_total
+        .with_label_values(&["local"]);
+    assert_eq!(local.get(), 1);
+
+    // Gossip hands the same offense back: recorded once, not re-sent.
+    let echoed = Evidence::DoubleVote(sent);
+    assert_eq!(d.on_evidence(b"peer", echoed), MsgOutcome::Duplicate);
+    assert!(rx.is_empty());
+}
+
+#[test]
+fn peer_evidence_needs_both_signatures() {
+    let kps = keypairs(4);
+    let ev = double_vote(&kps, 3);
+    let (mut d, metrics) = driver(&kps);
+
+    let Evidence::DoubleVote(mut forged) = ev.clone();
+    forged.second.signature = forged.first.signature.clone();
+    assert_eq!(
+        d.on_evidence(b"peer", Evidence::DoubleVote(forged)),
+        MsgOutcome::Rejected(TideError::BadSignature)
+    );
+    let Evidence::DoubleVote(mut same) = ev.clone();
+    same.second = same.first.clone();
+    assert_eq!(
+        d.on_evidence(b"peer", Evidence::DoubleVote(same)),
+        MsgOutcome::Rejected(TideError::InvalidEvidence)
+    );
+    let (mut outsider, _) = driver(&keypairs(4));
+    assert_eq!(
+        outsider.on_evidence(b"peer", ev.clone()),
+        MsgOutcome::Rejected(TideError::UnknownValidator)
+    );
+    assert!(d.evidence().is_empty());
+
+    assert_eq!(d.on_evidence(b"peer", ev.clone()), MsgOutcome::Accepted);
+    assert_eq!(d.on_evidence(b"peer", ev), MsgOutcome::Duplicate);
+    assert_eq!(d.evidence().len(), 1);
+    let peer = metrics
+        .consensus_evidence_total
+        .with_label_values(&["peer"]);
+    assert_eq!(peer.get(), 1);
+    assert_eq!(metrics.consensus_evidence_pool.get(), 1);
+}
+
+#[test]
+fn verified_evidence_reaches_slashing() {
+    let kps = keypairs(4);
+    let slashing = Counting::default();
+    let cfg = TideConfig::new(validators(&kps));
+    let tide = TideFinalizer::new(cfg, slashing.clone());
+
+    let Evidence::DoubleVote(mut forged) = double_vote(&kps, 2);
+    forged.first.block_hash = H256::from_bytes([9; 32]);
+    assert!(tide
+        .process_evidence(&Evidence::DoubleVote(forged))
+        .is_err());
+    assert_eq!(slashing.0.load(Ordering::SeqCst), 0);
+
+    tide.process_evidence(&double_vote(&kps, 2)).unwrap();
+    assert_eq!(slashing.0.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn included_evidence_slashes_the_ledger_all_or_nothing() {
+    let kps = keypairs(4);
+    let (d, _) = driver(&kps);
+    let (mut ledger, mut bank) = (StakingLedger::default(), Bank::default());
+    for kp in &kps {
+        let v = common::id(kp).as_bytes().to_vec();
+        bank.mint(&v, 10_000).unwrap();
+        ledger
+            .register_validator_from(&mut bank, v, 0, 10_000)
+            .unwrap();
+    }
+    let offender = common::id(&kps[0]).as_bytes().to_vec();
+
+    let Evidence::DoubleVote(mut forged) = double_vote(&kps, 2);
+    forged.first.block_hash = H256::from_bytes([9; 32]);
+    let batch = [double_vote(&kps, 2), Evidence::DoubleVote(forged)];
+    assert!(matches!(
+        d.apply_included_evidence(&mut ledger, &mut bank, &batch, 1_000),
+        Err(DriverError::Evidence(TideError::BadSignature))
+    ));
+    assert_eq!(ledger.validators[&offender].self_stake, 10_000);
+    assert_eq!(bank.total_supply, 40_000);
+
+    // A pool short of the slashed stake fails the batch: neither ledger nor bank moves.
+    let mut short = Bank::default();
+    assert!(matches!(
+        d.apply_included_evidence(&mut ledger, &mut short, &[double_vote(&kps, 2)], 1_000),
+        Err(DriverError::Staking(_))
+    ));
+    assert_eq!(ledger.validators[&offender].self_stake, 10_000);
+    assert_eq!(short, Bank::default());
+
+    let slashed = d
+        .apply_included_evidence(&mut ledger, &mut bank, &[double_vote(&kps, 2)], 1_000)
+        .unwrap();
+    assert_eq!(slashed, 500);
+    assert_eq!(ledger.validators[&offender].self_stake, 9_500);
+    assert!(ledger.validators[&offender].jailed);
+    // The slashed stake is burned: the bonded pool and the supply follow the ledger.
+    assert_eq!(bank.balance(BONDED_POOL), 39_500);
+    assert_eq!(bank.total_supply, 39_500);
+}
+
+#[test]
+fn evidence_travels_enveloped_and_checked() {
+    let kps = keypairs(4);
+    let ev = double_vote(&kps, 5);
+    let bytes = wire::encode_evidence(&ev).unwrap();
+    assert_eq!(&bytes[..2], &wire::ENVELOPE_MAGIC);
+    let decoded = wire::decode_evidence(&bytes).unwrap();
+    assert_eq!(decoded.offender(), ev.offender());
+    assert_eq!(decoded.position(), (Height(5), Round::ZERO));
+
+    // Order does not matter to the encoding.
+    let Evidence::DoubleVote(dv) = &ev;
+    let swapped = DoubleVoteEvidence::new(dv.second.clone(), dv.first.clone()).unwrap();
+    assert_eq!(
+        wire::encode_evidence(&Evidence::DoubleVote(swapped)).unwrap(),
+        bytes
+    );
+
+    let Evidence::DoubleVote(mut same) = ev;
+    same.second = same.first.clone();
+    assert!(DoubleVoteEvidence::new(same.first.clone(), same.second.clone()).is_none());
+    let bytes = wire::encode_evidence(&Evidence::DoubleVote(same)).unwrap();
+    assert!(matches!(
+        wire::decode_evidence(&bytes),
+        Err(WireError::Invalid)
+    ));
+    assert!(matches!(
+        wire::decode_evidence(&bytes[2..]),
+        Err(WireError::Malformed)
+    ));
+}
+
+#[test]
+fn pool_keeps_the_highest_offenses() {
+    let kps = keypairs(4);
+    let mut pool = EvidencePool::new(2);
+    assert!(pool.insert(double_vote(&kps, 4)));
+    assert!(pool.insert(double_vote(&kps, 6)));
+    assert!(!pool.insert(double_vote(&kps, 6)));
+    assert!(!pool.insert(double_vote(&kps, 2)));
+    assert!(pool.insert(double_vote(&kps, 8)));
+    let heights: Vec<u64> = pool.iter().map(|e| e.position().0.get()).collect();
+    assert_eq!(heights, vec![6, 8]);
+}
@@ -0,0 +1,149 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::driver::MsgOutcome;
+use amunchain::core::consensus::tide::TideError;
+use amunchain::core::types::{Commit, ConsensusMsg, Height, H256};
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::node::channel::{channel, ChannelConfig};
+use common::{driver, keypairs, validators, vote_for};
+use ring::signature::Ed25519KeyPair;
+
+fn rejected(m: &Metrics, reason: &str) -> u64 {
+    m.consensus_msgs_rejected_total
+        .with_label_values(&[reason])
+        .get()
+}
+
+fn commit_for(kps: &[Ed25519KeyPair], height: u64) -> Commit {
+    let set = validators(kps);
+    let (mut d, _) = driver(kps);
+    kps.iter()
+        .find_map(|kp| {
+            d.tide
+                .process_vote_verified(vote_for(kp, &set, height, 1))
+                .unwrap()
+        })
+        .unwrap()
+}
+
+#[test]
+fn repeated_vote_is_a_duplicate() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let (mut d, m) = driver(&kps);
+    let v = vote_for(&kps[0], &set, 1, 1);
+
+    assert_eq!(
+        d.on_peer_msg(b"peer", ConsensusMsg::Vote(v.clone())),
+        MsgOutcome::Accepted
+    );
+    assert_eq!(
+        d.on_peer_msg(b"peer", ConsensusMsg::Vote(v)),
+        MsgOutcome::Duplicate
+    );
+    assert_eq!(m.consensus_msgs_duplicate_total.get(), 1);
+}
+
+#[test]
+fn rejections_carry_and_count_their_reason() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let (mut d, m) = driver(&kps);
+
+    let mut forged = vote_for(&kps[0], &set, 1, 1);
+    forged.block_hash = H256::from_bytes([9; 32]);
+    assert_eq!(
+        d.on_msg(ConsensusMsg::Vote(forged)),
+        MsgOutcome::Rejected(TideError::BadSignature)
+    );
+
+    let outsider = keypairs(1);
+    assert_eq!(
+        d.on_msg(ConsensusMsg::Vote(vote_for(&outsider[0], &set, 1, 1))),
+        MsgOutcome::Rejected(TideError::UnknownValidator)
+    );
+
+    d.on_msg(ConsensusMsg::Vote(vote_for(&kps[1], &set, 1, 1)));
+    assert_eq!(
+        d.on_msg(ConsensusMsg::Vote(vote_for(&kps[1], &set, 1, 2))),
+        MsgOutcome::Rejected(TideError::DoubleVote)
+    );
+
+    assert_eq!(rejected(&m, "bad_signature"), 1);
+    assert_eq!(rejected(&m, "unknown_validator"), 1);
+    assert_eq!(rejected(&m, "double_vote"), 1);
+    assert_eq!(m.consensus_double_votes_total.get(), 1);
+}
+
+#[test]
+fn only_forged_content_counts_against_the_peer() {
+    assert!(TideError::BadSignature.is_invalid());
+    assert!(TideError::NotEnoughVotes.is_invalid());
+    assert!(!TideError::Replay.is_invalid());
+    assert!(!TideError::UnknownValidator.is_invalid());
+    assert!(!TideError::OutOfWindow.is_invalid());
+}
+
+#[test]
+fn commits_are_accepted_once_and_verified_first() {
+    let kps = keypairs(4);
+    let (mut d, m) = driver(&kps);
+    let commit = commit_for(&kps, 1);
+
+    let mut stripped = commit.clone();
+    let first = stripped.signatures.keys().next().unwrap().clone();
+    stripped.signatures.remove(&first);
+    assert!(matches!(
+        d.on_msg(ConsensusMsg::Commit(stripped)),
+        MsgOutcome::Rejected(e) if e.is_invalid()
+    ));
+
+    assert_eq!(
+        d.on_msg(ConsensusMsg::Commit(commit.clone())),
+        MsgOutcome::Accepted
+    );
+    assert_eq!(d.tide.finalized_height(), Height(1));
+    assert_eq!(
+        d.on_msg(ConsensusMsg::Commit(commit)),
+        MsgOutcome::Duplicate
+    );
+    assert_eq!(m.consensus_commits_total.get(), 1);
+}
+
+#[tokio::test]
+async fn only_locally_assembled_commits_are_broadcast() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let (tx, mut rx) = channel("test_outbound", ChannelConfig::blocking(8), None);
+    let (d, _) = driver(&kps);
+    let mut d = d.with_outbound(tx.clone());
+
+    for kp in kps.iter().take(3) {
+        d.on_peer_msg(b"peer", ConsensusMsg::Vote(vote_for(kp, &set, 1, 1)));
+    }
+    let Some(ConsensusMsg::Commit(c)) = common::queued(&mut rx).await else {
+        panic!("no commit broadcast");
+    };
+    assert_eq!(c.height, Height(1));
+
+    let (other, _) = driver(&kps);
+    let mut other = other.with_outbound(tx);
+    assert_eq!(
+        other.on_peer_msg(b"peer", ConsensusMsg::Commit(c)),
+        MsgOutcome::Accepted
+    );
+    assert!(rx.is_empty());
+}
@@ -0,0 +1,80 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::economics::epoch::{preview_next_epoch, EpochPolicy, PendingOp};
+use amunchain::core::economics::staking::StakingLedger;
+
+fn ledger() -> StakingLedger {
+    let mut l = StakingLedger::default();
+    l.register_validator(b"a".to_vec(), 1_000, 100).unwrap();
+    l.register_validator(b"b".to_vec(), 0, 200).unwrap();
+    l.register_validator(b"c".to_vec(), 0, 50).unwrap();
+    l.bond(b"d1".to_vec(), b"a".to_vec(), 300).unwrap();
+    l
+}
+
+#[test]
+fn preview_ranks_by_power_and_splits_rewards() {
+    let l = ledger();
+    let policy = EpochPolicy {
+        max_validators: 2,
+        epoch_reward: 1_000,
+    };
+    let p = preview_next_epoch(&l, &policy, &[]).unwrap();
+
+    let ids: Vec<&str> = p.validators.iter().map(|v| v.validator.as_str()).collect();
+    assert_eq!(ids, vec![hex::encode("a"), hex::encode("b")]);
+    assert_eq!(p.total_power, 600);
+    assert_eq!(p.validators[0].power_bps, 6_666);
+
+    // a: 400/600 of 1000 = 666, 10% commission, rest to its only delegator.
+    assert_eq!(p.validators[0].expected_reward, 666);
+    assert_eq!(p.validators[0].expected_commission, 66);
+    assert_eq!(p.delegations.len(), 1);
+    assert_eq!(p.delegations[0].expected_reward, 600);
+}
+
+#[test]
+fn what_if_ops_do_not_touch_the_ledger() {
+    let l = ledger();
+    let policy = EpochPolicy {
+        max_validators: 2,
+        epoch_reward: 0,
+    };
+    let ops = vec![
+        PendingOp::Bond {
+            delegator: b"d2".to_vec(),
+            validator: b"c".to_vec(),
+            amount: 500,
+        },
+        PendingOp::Unbond {
+            delegator: b"d1".to_vec(),
+            validator: b"a".to_vec(),
+            amount: 300,
+        },
+    ];
+    let p = preview_next_epoch(&l, &policy, &ops).unwrap();
+    let ids: Vec<&str> = p.validators.iter().map(|v| v.validator.as_str()).collect();
+    assert_eq!(ids, vec![hex::encode("c"), hex::encode("b")]);
+
+    assert_eq!(l.voting_power(b"a"), 400);
+    assert_eq!(l.voting_power(b"c"), 50);
+
+    // Unbonding more than is delegated is rejected, not clamped.
+    let bad = [PendingOp::Unbond {
+        delegator: b"d1".to_vec(),
+        validator: b"a".to_vec(),
+        amount: 301,
+    }];
+    assert!(preview_next_epoch(&l, &policy, &bad).is_err());
+}
@@ -0,0 +1,94 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::economics::bank::Bank;
+use amunchain::core::economics::fees::{
+    FeeError, FeeLimits, FeeMarket, FeeParams, FEE_COLLECTOR, TREASURY,
+};
+
+fn params() -> FeeParams {
+    FeeParams {
+        block_gas_limit: 1_000,
+        ..FeeParams::default()
+    }
+}
+
+fn tx(gas_limit: u64, max_fee: u128, tip: u128) -> FeeLimits {
+    FeeLimits {
+        gas_limit,
+        max_fee_per_gas: max_fee,
+        max_priority_fee_per_gas: tip,
+    }
+}
+
+#[test]
+fn base_fee_tracks_block_fullness() {
+    let p = params();
+    assert_eq!(p.next_base_fee(800, 500), 800);
+    // Full block: +1/8.
+    assert_eq!(p.next_base_fee(800, 1_000), 900);
+    // Empty block: -1/8, floored at the minimum.
+    assert_eq!(p.next_base_fee(800, 0), 700);
+    assert_eq!(p.next_base_fee(1, 0), 1);
+    // Growth never stalls at small fees.
+    assert_eq!(p.next_base_fee(1, 501), 2);
+}
+
+#[test]
+fn admission_and_distribution() {
+    let mut bank = Bank::default();
+    bank.mint(b"alice", 1_000_000).unwrap();
+    let mut market = FeeMarket::new(params(), 100).unwrap();
+
+    assert!(matches!(
+        market.admit(&mut bank, b"alice", tx(10, 99, 5)),
+        Err(FeeError::FeeCapTooLow)
+    ));
+    // Tip is capped by max_fee - base_fee.
+    assert_eq!(
+        market.admit(&mut bank, b"alice", tx(600, 103, 5)).unwrap(),
+        600 * 103
+    );
+    assert!(matches!(
+        market.admit(&mut bank, b"alice", tx(401, 200, 0)),
+        Err(FeeError::BlockGasLimitExceeded)
+    ));
+    assert!(matches!(
+        market.admit(&mut bank, b"bob", tx(10, 200, 0)),
+        Err(FeeError::Bank(_))
+    ));
+    assert_eq!(market.gas_used(), 600);
+
+    // A collector short of what the block owes fails before burning or paying anything.
+    bank.transfer(FEE_COLLECTOR, b"elsewhere", 1).unwrap();
+    let before = bank.clone();
+    assert!(matches!(
+        market.finish_block(&mut bank, b"producer"),
+        Err(FeeError::Bank(_))
+    ));
+    assert_eq!(bank, before);
+    assert_eq!(market.gas_used(), 600);
+    bank.transfer(b"elsewhere", FEE_COLLECTOR, 1).unwrap();
+
+    let fees = market.finish_block(&mut bank, b"producer").unwrap();
+    assert_eq!(fees.producer, 600 * 3);
+    assert_eq!(fees.treasury, 6_000);
+    assert_eq!(fees.burned, 54_000);
+    assert_eq!(bank.balance(b"producer"), 1_800);
+    assert_eq!(bank.balance(TREASURY), 6_000);
+    assert_eq!(bank.balance(FEE_COLLECTOR), 0);
+    assert_eq!(bank.total_supply, 1_000_000 - 54_000);
+    // 600 of a 500 target: base fee rises.
+    assert_eq!(market.base_fee(), 102);
+    assert_eq!(market.gas_used(), 0);
+}
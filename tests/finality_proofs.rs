@@ -0,0 +1,108 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::driver::ConsensusDriver;
+use amunchain::core::consensus::signing::SigningDomain;
+use amunchain::core::consensus::tide::{
+    expected_set_hash, TideError, VotingPower, DEFAULT_CHAIN_ID,
+};
+use amunchain::core::state::commit_store::CommitStore;
+use amunchain::core::state::persistent_state::PersistentState;
+use amunchain::core::types::{ConsensusMsg, H256};
+use common::{id, keypairs, sign_v3, unsigned_vote, validators, vote};
+
+#[test]
+fn finalized_commit_is_persisted_and_provable() {
+    let kps = keypairs(4);
+    let validators = validators(&kps);
+
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    let store = CommitStore::open(&st).unwrap();
+    let root_before = st.state_root().unwrap();
+
+    let mut driver = ConsensusDriver::new(validators.clone())
+        .unwrap()
+        .with_commit_store(store.clone());
+
+    let block_hash = H256::from_bytes([7u8; 32]);
+    for kp in kps.iter().take(3) {
+        driver.on_msg(ConsensusMsg::Vote(vote(kp, &validators, 1, block_hash)));
+    }
+
+    let proof = driver
+        .finality_proof(1)
+        .unwrap()
+        .expect("height 1 finalized");
+    assert_eq!(proof.commit.block_hash, block_hash);
+    assert_eq!(proof.commit.signatures.len(), 3);
+    let domain = SigningDomain {
+        chain_id: DEFAULT_CHAIN_ID,
+        legacy_until_height: None,
+    };
+    proof.verify_for_chain(&validators, None, domain).unwrap();
+
+    // A different validator set must not match the recorded hash.
+    let mut other = validators.clone();
+    other.pop_first();
+    assert!(proof.verify_for_chain(&other, None, domain).is_err());
+
+    assert!(driver.finality_proof(2).unwrap().is_none());
+    assert_eq!(store.latest_height().unwrap(), Some(1));
+    // Certificates live outside the state root.
+    assert_eq!(st.state_root().unwrap(), root_before);
+}
+
+#[test]
+fn stake_weighted_proofs_carry_the_weighted_set_hash() {
+    let kps = keypairs(4);
+    let validators = validators(&kps);
+    let power: VotingPower = kps.iter().map(id).zip([70u128, 10, 10, 10]).collect();
+    let set_hash = expected_set_hash(&validators, Some(&power)).unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    let mut driver = ConsensusDriver::new(validators.clone())
+        .unwrap()
+        .with_commit_store(CommitStore::open(&st).unwrap());
+    driver.tide.set_voting_power(Some(power.clone()));
+
+    // The whale alone finalizes.
+    let v = unsigned_vote(&kps[0], 1, H256::from_bytes([7u8; 32]));
+    driver.on_msg(ConsensusMsg::Vote(sign_v3(
+        &kps[0],
+        DEFAULT_CHAIN_ID,
+        set_hash,
+        v,
+    )));
+    let proof = driver
+        .finality_proof(1)
+        .unwrap()
+        .expect("height 1 finalized");
+    assert_eq!(proof.validator_set_hash, set_hash);
+    assert_eq!(proof.validator_set_hash, proof.commit.validator_set_hash);
+
+    let domain = SigningDomain {
+        chain_id: DEFAULT_CHAIN_ID,
+        legacy_until_height: None,
+    };
+    proof
+        .verify_for_chain(&validators, Some(&power), domain)
+        .unwrap();
+    assert!(matches!(
+        proof.verify_for_chain(&validators, None, domain),
+        Err(TideError::ValidatorSetMismatch)
+    ));
+}
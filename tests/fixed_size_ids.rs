@@ -0,0 +1,104 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::types::{
+    decode_canonical_json, decode_canonical_limited, encode_canonical, encode_canonical_json,
+    ConsensusMsg, Epoch, Height, LengthError, Round, Signature, ValidatorId, Vote, H256,
+};
+use amunchain::networking::{proto, wire};
+
+const MAX: usize = 64 * 1024;
+
+#[test]
+fn constructors_check_length() {
+    assert!(ValidatorId::from_slice(&[1; 32]).is_ok());
+    assert_eq!(
+        ValidatorId::from_slice(&[1; 31]),
+        Err(LengthError {
+            expected: 32,
+            got: 31
+        })
+    );
+    assert!(Signature::try_from(vec![0; 65]).is_err());
+    assert_eq!(
+        Signature::try_from(vec![7; 64]).unwrap(),
+        Signature::from_bytes([7; 64])
+    );
+}
+
+#[test]
+fn encoding_matches_the_old_byte_vectors() {
+    let id = ValidatorId::from_bytes([3; 32]);
+    assert_eq!(
+        encode_canonical(&id).unwrap(),
+        encode_canonical(&vec![3u8; 32]).unwrap()
+    );
+    assert_eq!(
+        encode_canonical_json(&id).unwrap(),
+        encode_canonical_json(&vec![3u8; 32]).unwrap()
+    );
+    let back: ValidatorId =
+        decode_canonical_limited(&encode_canonical(&vec![3u8; 32]).unwrap(), MAX).unwrap();
+    assert_eq!(back, id);
+}
+
+#[test]
+fn wrong_lengths_are_rejected_at_decode() {
+    for len in [0, 31, 33] {
+        let bytes = encode_canonical(&vec![1u8; len]).unwrap();
+        assert!(decode_canonical_limited::<ValidatorId>(&bytes, MAX).is_err());
+        let json = encode_canonical_json(&vec![1u8; len]).unwrap();
+        assert!(decode_canonical_json::<ValidatorId>(&json, MAX).is_err());
+    }
+    assert!(
+        decode_canonical_limited::<Signature>(&encode_canonical(&vec![1u8; 63]).unwrap(), MAX)
+            .is_err()
+    );
+    // A huge length prefix fails before anything is buffered.
+    assert!(decode_canonical_limited::<Signature>(&u64::MAX.to_le_bytes(), MAX).is_err());
+}
+
+#[test]
+fn short_keys_never_reach_consensus() {
+    let vote = Vote {
+        height: Height(1),
+        round: Round::ZERO,
+        epoch: Epoch::ZERO,
+        msg_counter: 0,
+        sent_ts_ms: 0,
+        ttl_ms: 0,
+        block_hash: H256::from_bytes([5; 32]),
+        voter: ValidatorId::from_bytes([1; 32]),
+        signature: Signature::from_bytes([2; 64]),
+    };
+    let msg = ConsensusMsg::Vote(vote.clone());
+    assert!(wire::decode_msg(&wire::encode_msg(&msg, wire::WIRE_V2).unwrap()).is_ok());
+
+    // Bincode: the voter is a u64 length then the bytes; shorten it by one byte.
+    let good = wire::encode_msg(&msg, wire::WIRE_V1).unwrap();
+    let at = good
+        .windows(8)
+        .position(|w| w == 32u64.to_le_bytes())
+        .unwrap();
+    let mut short = good[..at].to_vec();
+    short.extend(31u64.to_le_bytes());
+    short.extend(&good[at + 9..]);
+    assert!(wire::decode_msg(&short).is_err());
+
+    let mut pb = proto::Vote::from(&vote);
+    pb.voter.pop();
+    let bytes = prost::Message::encode_to_vec(&proto::ConsensusMsg {
+        msg: Some(proto::consensus_msg::Msg::Vote(pb)),
+    });
+    assert!(proto::decode_msg(&bytes).is_err());
+}
@@ -0,0 +1,27 @@
+#!/bin/sh
+# Regenerates the TLS test fixtures used by tests/http_tls.rs. Valid for 100 years.
+set -eu
+cd "$(dirname "$0")"
+tmp=$(mktemp -d)
+trap 'rm -rf "$tmp"' EXIT
+
+ca() { # name
+    openssl req -x509 -newkey ec -pkeyopt ec_paramgen_curve:prime256v1 -nodes -days 36500 \
+        -subj "/CN=amunchain test $1" -keyout "$tmp/$1.key" -out "$1.pem" 2>/dev/null
+}
+
+leaf() { # name ca ext
+    openssl req -newkey ec -pkeyopt ec_paramgen_curve:prime256v1 -nodes \
+        -subj "/CN=amunchain test $1" -keyout "$1.key" -out "$tmp/$1.csr" 2>/dev/null
+    printf '%s\n' "$3" > "$tmp/$1.ext"
+    openssl x509 -req -in "$tmp/$1.csr" -CA "$2.pem" -CAkey "$tmp/$2.key" -CAcreateserial \
+        -days 36500 -extfile "$tmp/$1.ext" -out "$1.pem" 2>/dev/null
+}
+
+ca ca
+ca rogue-ca
+leaf server ca "subjectAltName=IP:127.0.0.1,DNS:localhost
+extendedKeyUsage=serverAuth"
+leaf client ca "extendedKeyUsage=clientAuth"
+leaf rogue-client rogue-ca "extendedKeyUsage=clientAuth"
+rm -f rogue-ca.pem ./*.srl
@@ -0,0 +1,151 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::runtime::executor::{
+    EvmExecutor, ExecError, ExecState, DEFAULT_BLOCK_GAS_LIMIT, TRANSFER_GAS,
+};
+use amunchain::core::runtime::gas::{GasMeter, OutOfGas};
+use amunchain::core::runtime::precompiles::VOTING_POWER;
+use amunchain::core::runtime::receipt::TxStatus;
+use amunchain::core::types::{AccountKey, Signature, Transaction, TxCall};
+use amunchain::monitoring::metrics::Metrics;
+use std::sync::Arc;
+
+const ALICE: [u8; 32] = [0xa1; 32];
+
+fn tx(nonce: u64, call: TxCall, gas_limit: u64) -> Transaction {
+    Transaction {
+        chain_id: "amun-testnet".to_string(),
+        sender: AccountKey::from_bytes(ALICE),
+        nonce,
+        call,
+        gas_limit,
+        max_fee_per_gas: 1,
+        max_priority_fee_per_gas: 0,
+        signature: Signature::from_bytes([0; 64]),
+    }
+}
+
+fn pay() -> TxCall {
+    TxCall::Transfer {
+        to: b"bob".to_vec(),
+        amount: 1,
+    }
+}
+
+fn power() -> TxCall {
+    TxCall::Call {
+        to: VOTING_POWER.to_vec(),
+        value: 0,
+        input: b"val".to_vec(),
+    }
+}
+
+fn funded() -> ExecState {
+    let mut state = ExecState::default();
+    state.bank.mint(&ALICE, 100).unwrap();
+    state
+}
+
+#[test]
+fn meter_charges_up_to_its_limit() {
+    let mut m = GasMeter::new(100);
+    m.charge(60).unwrap();
+    assert_eq!((m.used(), m.remaining()), (60, 40));
+    assert_eq!(m.charge(41), Err(OutOfGas));
+    // Running out uses everything.
+    assert_eq!((m.used(), m.remaining()), (100, 0));
+    assert_eq!(m.charge(1), Err(OutOfGas));
+
+    let mut m = GasMeter::new(10);
+    m.charge(10).unwrap();
+    assert_eq!(m.charge(0), Ok(()));
+    m.exhaust();
+    assert_eq!(m.used(), m.limit());
+}
+
+#[test]
+fn out_of_gas_is_a_failed_receipt() {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let exec = EvmExecutor::new().with_metrics(metrics.clone());
+    let cost = exec.precompiles().gas(&VOTING_POWER, b"val").unwrap();
+    let mut state = funded();
+    let txs = [
+        tx(0, power(), TRANSFER_GAS + cost - 1),
+        tx(1, pay(), TRANSFER_GAS),
+        tx(2, power(), TRANSFER_GAS + cost),
+    ];
+    let out = exec.execute_block(&mut state, &txs).unwrap();
+
+    let got: Vec<(TxStatus, u64)> = out
+        .receipts
+        .iter()
+        .map(|r| (r.status, r.gas_used))
+        .collect();
+    assert_eq!(
+        got,
+        vec![
+            (TxStatus::OutOfGas, TRANSFER_GAS + cost - 1),
+            (TxStatus::Success, TRANSFER_GAS),
+            (TxStatus::Success, TRANSFER_GAS + cost),
+        ]
+    );
+    assert_eq!(out.gas_used, 3 * TRANSFER_GAS + 2 * cost - 1);
+    // The out-of-gas transaction still consumed its nonce.
+    assert_eq!(state.nonces.next(&ALICE), 3);
+
+    // Same block on another node: same receipts.
+    let again = EvmExecutor::new()
+        .execute_block(&mut funded(), &txs)
+        .unwrap();
+    assert_eq!(again, out);
+
+    assert_eq!(metrics.tx_out_of_gas_total.get(), 1);
+    assert_eq!(metrics.block_gas_used.get_sample_count(), 1);
+    assert_eq!(metrics.block_gas_used.get_sample_sum(), out.gas_used as f64);
+}
+
+#[test]
+fn block_gas_limit_is_enforced() {
+    assert_eq!(
+        EvmExecutor::new().block_gas_limit(),
+        DEFAULT_BLOCK_GAS_LIMIT
+    );
+    let exec = EvmExecutor::new().with_block_gas_limit(3 * TRANSFER_GAS);
+
+    let fits = [
+        tx(0, pay(), TRANSFER_GAS),
+        tx(1, pay(), TRANSFER_GAS),
+        tx(2, pay(), TRANSFER_GAS),
+    ];
+    assert_eq!(
+        exec.execute_block(&mut funded(), &fits).unwrap().gas_used,
+        3 * TRANSFER_GAS
+    );
+
+    let over = [
+        tx(0, pay(), TRANSFER_GAS),
+        tx(1, pay(), TRANSFER_GAS),
+        tx(2, pay(), TRANSFER_GAS + 1),
+    ];
+    assert_eq!(
+        exec.execute_block(&mut funded(), &over),
+        Err(ExecError::BlockGasLimit)
+    );
+
+    // The check uses the gas limit, not the gas the transaction would use.
+    assert_eq!(
+        exec.execute_block(&mut funded(), &[tx(0, pay(), 3 * TRANSFER_GAS + 1)]),
+        Err(ExecError::BlockGasLimit)
+    );
+}
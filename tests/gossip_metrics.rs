@@ -0,0 +1,120 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::types::{
+    ConsensusMsg, Epoch, Height, Round, Signature, ValidatorId, Vote, H256,
+};
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::networking::p2p::{spawn_p2p, P2pConfig, P2pEvent};
+use std::sync::Arc;
+use std::time::Duration;
+
+const TOPIC: &str = "gossip-metrics-test";
+
+fn config(dir: &tempfile::TempDir, port: u16, bootstrap: Vec<String>) -> P2pConfig {
+    P2pConfig {
+        listen_addr: format!("/ip4/127.0.0.1/tcp/{port}"),
+        consensus_topic: TOPIC.to_string(),
+        consensus_codec: Default::default(),
+        evidence_topic: "evidence-test".to_string(),
+        max_msg_per_sec: 100,
+        max_peers_per_ip: 4,
+        data_dir: dir.path().to_string_lossy().to_string(),
+        identity: Default::default(),
+        bootstrap,
+        allow_peers: Vec::new(),
+        extensions: None,
+        channels: Default::default(),
+        gossipsub: Default::default(),
+        limits: Default::default(),
+        read_only: false,
+    }
+}
+
+fn vote(counter: u64) -> ConsensusMsg {
+    ConsensusMsg::Vote(Vote {
+        height: Height(1),
+        round: Round::ZERO,
+        epoch: Epoch::ZERO,
+        msg_counter: counter,
+        sent_ts_ms: 0,
+        ttl_ms: 0,
+        block_hash: H256::from_bytes([1; 32]),
+        voter: ValidatorId::from_bytes([2; 32]),
+        signature: Signature::from_bytes([3; 64]),
+    })
+}
+
+/// Value of the first sample of `metric` for this test's topic.
+fn sample(text: &str, metric: &str) -> Option<f64> {
+    let prefix = format!("{metric}{{hash=\"{TOPIC}\"}} ");
+    text.lines()
+        .find_map(|l| l.strip_prefix(prefix.as_str()))
+        .and_then(|v| v.parse().ok())
+}
+
+#[tokio::test]
+async fn gossip_traffic_is_exported_per_topic() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let (dir_a, dir_b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    let metrics_a = Arc::new(Metrics::new().unwrap());
+    let metrics_b = Arc::new(Metrics::new().unwrap());
+    let (mut a, _events_a, _join_a) =
+        spawn_p2p(config(&dir_a, port, Vec::new()), metrics_a.clone()).unwrap();
+    let (b, mut events_b, _join_b) = spawn_p2p(
+        config(&dir_b, 0, vec![format!("/ip4/127.0.0.1/tcp/{port}")]),
+        metrics_b.clone(),
+    )
+    .unwrap();
+    let mut inbound = a.take_inbound().unwrap();
+
+    let connected = tokio::time::timeout(Duration::from_secs(20), events_b.recv())
+        .await
+        .expect("peers connect");
+    assert!(matches!(connected, Some(P2pEvent::PeerConnected(_))));
+
+    // Publish until the mesh has formed and a message gets through.
+    let out = b.outbound();
+    let received = tokio::time::timeout(Duration::from_secs(30), async {
+        let mut counter = 1;
+        loop {
+            out.send(vote(counter)).await.unwrap();
+            counter += 1;
+            if let Ok(Some(msg)) =
+                tokio::time::timeout(Duration::from_millis(500), inbound.recv()).await
+            {
+                return msg;
+            }
+        }
+    })
+    .await
+    .expect("a message is delivered");
+    assert!(matches!(received.1, ConsensusMsg::Vote(_)));
+
+    let text_a = metrics_a.encode_text().unwrap();
+    let text_b = metrics_b.encode_text().unwrap();
+    assert!(text_a.contains("amunchain_p2p_peers "));
+    assert!(!text_a.contains("# EOF"));
+    assert!(sample(&text_a, "amunchain_gossipsub_topic_msg_recv_counts_total") >= Some(1.0));
+    assert!(sample(&text_b, "amunchain_gossipsub_topic_msg_published_total") >= Some(1.0));
+    // The mesh gauge is refreshed on the heartbeat, so only its presence is stable here.
+    assert!(sample(&text_a, "amunchain_gossipsub_mesh_peer_counts").is_some());
+    assert_eq!(
+        sample(&text_a, "amunchain_gossipsub_topic_peers_counts"),
+        Some(1.0)
+    );
+}
@@ -0,0 +1,128 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::types::{
+    ConsensusMsg, Epoch, Height, Round, Signature, ValidatorId, Vote, H256,
+};
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::networking::gossip_ttl::TtlFilter;
+use amunchain::networking::gossip_tuning::GossipTuning;
+use amunchain::networking::wire::{MsgTiming, WireCodec, WIRE_V1, WIRE_V2};
+use libp2p::gossipsub::{DataTransform, IdentTopic, RawMessage, TopicHash};
+
+const SENT: u64 = 1_700_000_000_000;
+
+fn vote(sent_ts_ms: u64, ttl_ms: u32) -> ConsensusMsg {
+    ConsensusMsg::Vote(Vote {
+        height: Height(5),
+        round: Round(1),
+        epoch: Epoch(1),
+        msg_counter: 2,
+        sent_ts_ms,
+        ttl_ms,
+        block_hash: H256::from_bytes([7; 32]),
+        voter: ValidatorId::from_bytes([1; 32]),
+        signature: Signature::from_bytes([2; 64]),
+    })
+}
+
+fn raw(topic: TopicHash, data: Vec<u8>) -> RawMessage {
+    RawMessage {
+        source: None,
+        data,
+        sequence_number: None,
+        topic,
+        signature: None,
+        key: None,
+        validated: false,
+    }
+}
+
+#[test]
+fn timing_is_read_from_every_encoding() {
+    let timing = MsgTiming {
+        sent_ts_ms: SENT,
+        ttl_ms: 4_000,
+    };
+    let msg = vote(SENT, 4_000);
+    for bytes in [
+        WireCodec::Bincode.encode(&msg, WIRE_V1).unwrap(),
+        WireCodec::Bincode.encode(&msg, WIRE_V2).unwrap(),
+    ] {
+        assert_eq!(WireCodec::Bincode.peek_timing(&bytes), Some(timing));
+    }
+    let proto = WireCodec::Protobuf.encode(&msg, WIRE_V2).unwrap();
+    assert_eq!(WireCodec::Protobuf.peek_timing(&proto), Some(timing));
+    assert_eq!(WireCodec::Bincode.peek_timing(&[0, 0]), None);
+    assert_eq!(WireCodec::Bincode.peek_timing(&[9; 64]), None);
+}
+
+#[test]
+fn expiry_allows_the_grace_and_ignores_legacy_messages() {
+    let timing = MsgTiming {
+        sent_ts_ms: SENT,
+        ttl_ms: 4_000,
+    };
+    assert!(!timing.is_expired(SENT + 4_000 + 500, 500));
+    assert!(timing.is_expired(SENT + 4_000 + 501, 500));
+    assert!(!MsgTiming {
+        sent_ts_ms: SENT,
+        ttl_ms: 0
+    }
+    .is_expired(u64::MAX, 0));
+    assert!(!MsgTiming {
+        sent_ts_ms: 0,
+        ttl_ms: 4_000
+    }
+    .is_expired(u64::MAX, 0));
+}
+
+#[test]
+fn expired_consensus_messages_are_dropped_before_delivery() {
+    let metrics = Metrics::new().unwrap();
+    let consensus = IdentTopic::new("ttl-test").hash();
+    let filter = TtlFilter::new(
+        consensus.clone(),
+        WireCodec::Bincode,
+        &GossipTuning::default(),
+        metrics.p2p_expired_dropped_total.clone(),
+    );
+    let stale = WireCodec::Bincode
+        .encode(&vote(1_000, 4_000), WIRE_V2)
+        .unwrap();
+    let legacy = WireCodec::Bincode.encode(&vote(0, 0), WIRE_V1).unwrap();
+
+    assert!(filter
+        .inbound_transform(raw(consensus.clone(), stale.clone()))
+        .is_err());
+    assert_eq!(metrics.p2p_expired_dropped_total.get(), 1);
+    let passed = filter
+        .inbound_transform(raw(consensus.clone(), legacy.clone()))
+        .unwrap();
+    assert_eq!(passed.data, legacy);
+    // Only the consensus topic is inspected.
+    let other = IdentTopic::new("other").hash();
+    assert!(filter.inbound_transform(raw(other, stale.clone())).is_ok());
+    assert_eq!(metrics.p2p_expired_dropped_total.get(), 1);
+
+    let off = TtlFilter::new(
+        consensus.clone(),
+        WireCodec::Bincode,
+        &GossipTuning {
+            enforce_ttl: false,
+            ..GossipTuning::default()
+        },
+        metrics.p2p_expired_dropped_total.clone(),
+    );
+    assert!(off.inbound_transform(raw(consensus, stale)).is_ok());
+}
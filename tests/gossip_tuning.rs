@@ -0,0 +1,95 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::config::ConfigLoader;
+use amunchain::networking::gossip_tuning::{GossipTuning, GossipTuningError};
+use amunchain::node::config_check::check;
+use std::time::Duration;
+
+#[test]
+fn defaults_match_the_previous_hardcoded_config() {
+    let cfg = GossipTuning::default().to_config().unwrap();
+    assert_eq!(cfg.heartbeat_interval(), Duration::from_secs(1));
+    assert_eq!(
+        (cfg.mesh_n(), cfg.mesh_n_low(), cfg.mesh_n_high()),
+        (6, 5, 12)
+    );
+    assert_eq!(cfg.gossip_lazy(), 6);
+    assert_eq!(cfg.history_length(), 5);
+    assert!(cfg.flood_publish());
+}
+
+#[test]
+fn small_meshes_are_accepted() {
+    let tuning = GossipTuning {
+        mesh_n: 2,
+        mesh_n_low: 1,
+        mesh_n_high: 3,
+        gossip_lazy: 0,
+        history_length: 1,
+        flood_publish: false,
+        heartbeat_interval_ms: 200,
+        enforce_ttl: true,
+        ttl_grace_ms: 0,
+    };
+    let cfg = tuning.to_config().unwrap();
+    assert_eq!(cfg.heartbeat_interval(), Duration::from_millis(200));
+    assert_eq!(cfg.history_gossip(), 1);
+    assert!(!cfg.flood_publish());
+}
+
+#[test]
+fn invalid_values_are_all_reported() {
+    let tuning = GossipTuning {
+        mesh_n: 4,
+        mesh_n_low: 5,
+        mesh_n_high: 3,
+        history_length: 0,
+        heartbeat_interval_ms: 10,
+        ..GossipTuning::default()
+    };
+    assert_eq!(
+        tuning.validate(),
+        [
+            GossipTuningError::MeshLow { low: 5, n: 4 },
+            GossipTuningError::MeshHigh { high: 3, n: 4 },
+            GossipTuningError::HistoryLength,
+            GossipTuningError::Heartbeat(10),
+        ]
+    );
+    assert_eq!(
+        tuning.to_config().unwrap_err(),
+        GossipTuningError::MeshLow { low: 5, n: 4 }
+    );
+}
+
+#[test]
+fn config_section_is_parsed_and_checked() {
+    let cfg = ConfigLoader::new()
+        .set("p2p.gossipsub.mesh_n", "3")
+        .set("p2p.gossipsub.heartbeat_interval_ms", "250")
+        .load()
+        .unwrap();
+    assert_eq!(cfg.p2p.gossipsub.mesh_n, 3);
+    assert_eq!(cfg.p2p.gossipsub.heartbeat_interval_ms, 250);
+    // Untouched keys keep their defaults, so mesh_n_low (5) is now above mesh_n.
+    assert_eq!(cfg.p2p.gossipsub.mesh_n_high, 12);
+    let issues: Vec<String> = check(&cfg).into_iter().map(|i| i.field).collect();
+    assert!(issues.contains(&"p2p.gossipsub.mesh_n_low".to_string()));
+
+    let err = ConfigLoader::new()
+        .set("p2p.gossipsub.mesh_size", "3")
+        .load()
+        .unwrap_err();
+    assert!(err.to_string().contains("mesh_size"), "{err}");
+}
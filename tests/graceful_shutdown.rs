@@ -0,0 +1,167 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::driver::ConsensusDriver;
+use amunchain::core::state::checkpoint::{self, Resume};
+use amunchain::core::state::commit_store::CommitStore;
+use amunchain::core::state::persistent_state::{KvOp, PersistentState};
+use amunchain::core::types::{Height, ValidatorId};
+use amunchain::errors::ExitCode;
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::networking::p2p::{spawn_p2p, P2pConfig};
+use amunchain::node::startup::{StageHandle, StartupOrchestrator, Stop};
+use common::fresh_copy;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn put(state: &PersistentState, key: &[u8], value: &[u8]) {
+    state
+        .commit_atomic(vec![KvOp::Put {
+            key: key.to_vec(),
+            value: value.to_vec(),
+        }])
+        .unwrap();
+}
+
+#[test]
+fn checkpoint_restores_once_and_only_on_matching_root() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("state");
+    {
+        let state = PersistentState::open(&path.to_string_lossy()).unwrap();
+        put(&state, b"a", b"1");
+        let cp = checkpoint::write(&state, 42).unwrap();
+        assert_eq!(cp.state_root, state.state_root().unwrap());
+        state.flush().unwrap();
+    }
+
+    // Reopen, as after a restart.
+    let copy = fresh_copy(dir.path());
+    let state = PersistentState::open(&copy.path().join("state").to_string_lossy()).unwrap();
+    let commits = CommitStore::open(&state).unwrap();
+    let resume = checkpoint::restore(&state, &commits).unwrap();
+    assert!(matches!(resume, Resume::Checkpoint(cp) if cp.finalized_height == 42));
+    assert_eq!(resume.height(), 42);
+
+    // Consumed: a crash after this start is not a clean shutdown.
+    assert_eq!(checkpoint::read(&state).unwrap(), None);
+    assert_eq!(
+        checkpoint::restore(&state, &commits).unwrap(),
+        Resume::CommitStore(0)
+    );
+
+    // State written after the checkpoint invalidates it.
+    checkpoint::write(&state, 50).unwrap();
+    put(&state, b"b", b"2");
+    assert_eq!(
+        checkpoint::restore(&state, &commits).unwrap(),
+        Resume::CommitStore(0)
+    );
+}
+
+#[test]
+fn driver_resumes_at_finalized_height() {
+    let validators: BTreeSet<ValidatorId> = [ValidatorId::from_bytes([1; 32])].into();
+    let driver = ConsensusDriver::new(validators)
+        .unwrap()
+        .with_finalized_height(Height(42));
+    assert_eq!(driver.tide.finalized_height(), Height(42));
+    assert_eq!(driver.view(), Height(43));
+}
+
+#[tokio::test]
+async fn stages_stop_one_by_one_after_the_signal() {
+    let (done_tx, done_rx) = tokio::sync::oneshot::channel::<()>();
+    let mut running = StartupOrchestrator::new()
+        .stage("drains", &[], ExitCode::Internal, move |_| {
+            // Finishes on its own once told to.
+            let task = tokio::spawn(async move {
+                let _ = done_rx.await;
+            });
+            Ok(StageHandle::empty().with_task(task))
+        })
+        .stage("stuck", &[], ExitCode::Internal, |_| {
+            let task = tokio::spawn(std::future::pending());
+            Ok(StageHandle::empty().with_task(task))
+        })
+        .start()
+        .unwrap();
+
+    assert_eq!(running.wait(async {}).await, Stop::Signal);
+
+    done_tx.send(()).unwrap();
+    assert!(running.stop_stage("drains", Duration::from_secs(5)).await);
+    assert!(!running.stop_stage("stuck", Duration::from_millis(50)).await);
+    // Unknown or already stopped stages are a no-op.
+    assert!(
+        running
+            .stop_stage("drains", Duration::from_millis(50))
+            .await
+    );
+    running.shutdown();
+}
+
+#[tokio::test]
+async fn task_exit_ends_wait() {
+    let mut running = StartupOrchestrator::new()
+        .stage("short", &[], ExitCode::Internal, |_| {
+            Ok(StageHandle::empty().with_task(tokio::spawn(async {})))
+        })
+        .start()
+        .unwrap();
+    let stop = running.wait(std::future::pending()).await;
+    assert_eq!(stop, Stop::TaskExited("short"));
+    running.shutdown();
+}
+
+#[tokio::test]
+async fn p2p_stops_intake_then_closes() {
+    let dir = tempfile::tempdir().unwrap();
+    let cfg = P2pConfig {
+        listen_addr: "/ip4/127.0.0.1/tcp/0".to_string(),
+        consensus_topic: "shutdown-test".to_string(),
+        consensus_codec: Default::default(),
+        evidence_topic: "evidence-test".to_string(),
+        max_msg_per_sec: 10,
+        max_peers_per_ip: 4,
+        data_dir: dir.path().to_string_lossy().to_string(),
+        identity: Default::default(),
+        bootstrap: Vec::new(),
+        allow_peers: Vec::new(),
+        extensions: None,
+        channels: Default::default(),
+        gossipsub: Default::default(),
+        limits: Default::default(),
+        read_only: false,
+    };
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let (mut node, _events, join) = spawn_p2p(cfg, metrics).unwrap();
+    let mut inbound = node.take_inbound().unwrap();
+    assert!(node.take_inbound().is_none());
+
+    node.stop_intake();
+    let closed = tokio::time::timeout(Duration::from_secs(10), inbound.recv())
+        .await
+        .expect("inbound closes after stop_intake");
+    assert!(closed.is_none());
+    assert!(!join.is_finished());
+
+    node.close();
+    tokio::time::timeout(Duration::from_secs(10), join)
+        .await
+        .expect("p2p task ends after close")
+        .unwrap();
+}
@@ -0,0 +1,103 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::state::persistent_state::PersistentState;
+use amunchain::monitoring::health::{HealthMonitor, HealthReport, HealthStatus, ReadinessCriteria};
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::rpc::server::{bind, serve_listener, RpcState};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+fn status_of(report: &HealthReport, name: &str) -> HealthStatus {
+    report
+        .checks
+        .iter()
+        .find(|c| c.name == name)
+        .map(|c| c.status)
+        .unwrap()
+}
+
+#[test]
+fn status_rolls_up_worst_check() {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    let criteria = ReadinessCriteria {
+        min_peers: 3,
+        max_stall_secs: 10,
+        max_clock_skew_ms: 500,
+    };
+    let health = HealthMonitor::new(criteria, metrics.clone()).with_state(st);
+    let t0 = Instant::now();
+
+    // Not listening, no peers.
+    let r = health.report_at(t0);
+    assert_eq!(r.status, HealthStatus::Unhealthy);
+    assert_eq!(status_of(&r, "p2p_listening"), HealthStatus::Unhealthy);
+    assert_eq!(status_of(&r, "db"), HealthStatus::Healthy);
+
+    metrics.p2p_listen_addrs.set(1);
+    metrics.p2p_peers.set(1);
+    let r = health.report_at(t0);
+    assert_eq!(r.status, HealthStatus::Degraded);
+    assert_eq!(status_of(&r, "peers"), HealthStatus::Degraded);
+
+    metrics.p2p_peers.set(3);
+    metrics.consensus_clock_skew_ms.set(-800);
+    assert_eq!(health.report_at(t0).status, HealthStatus::Degraded);
+    metrics.consensus_clock_skew_ms.set(100);
+    assert_eq!(health.report_at(t0).status, HealthStatus::Healthy);
+
+    // Finality stalls, then resumes.
+    let later = t0 + Duration::from_secs(11);
+    let r = health.report_at(later);
+    assert_eq!(status_of(&r, "consensus"), HealthStatus::Unhealthy);
+    metrics.block_height.set(5);
+    assert_eq!(health.report_at(later).status, HealthStatus::Healthy);
+}
+
+#[tokio::test]
+async fn readyz_is_503_when_unhealthy() {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let health = HealthMonitor::new(ReadinessCriteria::default(), metrics.clone());
+    let listener = bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_listener(
+        listener,
+        RpcState::new(metrics.clone()).with_health(health),
+    ));
+
+    let get = |path: &'static str| async move {
+        let mut s = tokio::net::TcpStream::connect(addr).await.unwrap();
+        s.write_all(
+            format!("GET {path} HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n").as_bytes(),
+        )
+        .await
+        .unwrap();
+        let mut resp = String::new();
+        s.read_to_string(&mut resp).await.unwrap();
+        resp
+    };
+
+    let resp = get("/readyz").await;
+    assert!(resp.starts_with("HTTP/1.1 503"));
+    assert!(resp.contains("\"status\":\"unhealthy\""));
+    assert!(get("/healthz").await.starts_with("HTTP/1.1 200"));
+
+    metrics.p2p_listen_addrs.set(1);
+    metrics.p2p_peers.set(4);
+    let resp = get("/readyz").await;
+    assert!(resp.starts_with("HTTP/1.1 200"));
+    assert!(resp.contains("\"status\":\"healthy\""));
+}
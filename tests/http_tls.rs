@@ -0,0 +1,164 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+//! Fixtures in `tests/fixtures/tls` come from `gen.sh` there.
+
+use amunchain::core::types::{HttpConfig, TelemetryConfig};
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::monitoring::telemetry;
+use amunchain::rpc::server::{bind, RpcState};
+use amunchain::rpc::tls::{serve_listener_tls, server_config, TlsError};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_rustls::rustls::crypto::ring::default_provider;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+const DIR: &str = "tests/fixtures/tls";
+
+fn fixture(name: &str) -> Option<String> {
+    Some(format!("{DIR}/{name}"))
+}
+
+fn plain() -> HttpConfig {
+    toml::from_str("listen_addr = \"127.0.0.1:0\"").unwrap()
+}
+
+fn http_config(client_ca: bool) -> HttpConfig {
+    HttpConfig {
+        tls_cert_path: fixture("server.pem"),
+        tls_key_path: fixture("server.key"),
+        tls_client_ca_path: if client_ca { fixture("ca.pem") } else { None },
+        ..plain()
+    }
+}
+
+fn connector(client: Option<&str>) -> TlsConnector {
+    let mut roots = RootCertStore::empty();
+    for c in CertificateDer::pem_file_iter(format!("{DIR}/ca.pem")).unwrap() {
+        roots.add(c.unwrap()).unwrap();
+    }
+    let builder = ClientConfig::builder_with_provider(Arc::new(default_provider()))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots);
+    let config = match client {
+        Some(name) => {
+            let chain = CertificateDer::pem_file_iter(format!("{DIR}/{name}.pem"))
+                .unwrap()
+                .map(Result::unwrap)
+                .collect();
+            let key = PrivateKeyDer::from_pem_file(format!("{DIR}/{name}.key")).unwrap();
+            builder.with_client_auth_cert(chain, key).unwrap()
+        }
+        None => builder.with_no_client_auth(),
+    };
+    TlsConnector::from(Arc::new(config))
+}
+
+/// One request over TLS; `Err` when the handshake or the exchange fails.
+async fn request(
+    addr: SocketAddr,
+    client: Option<&str>,
+    head: &str,
+) -> Result<String, std::io::Error> {
+    let tcp = tokio::net::TcpStream::connect(addr).await?;
+    let name = ServerName::try_from("127.0.0.1").unwrap();
+    let mut s = connector(client).connect(name, tcp).await?;
+    s.write_all(format!("{head}Host: x\r\nConnection: close\r\n\r\n").as_bytes())
+        .await?;
+    let mut resp = String::new();
+    s.read_to_string(&mut resp).await?;
+    Ok(resp)
+}
+
+fn serve(state: RpcState, http: &HttpConfig) -> SocketAddr {
+    let tls = server_config(http).unwrap().unwrap();
+    let listener = bind(&http.listen_addr).unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_listener_tls(listener, state, tls));
+    addr
+}
+
+#[test]
+fn config_needs_cert_and_key_together() {
+    assert!(server_config(&plain()).unwrap().is_none());
+    let half = HttpConfig {
+        tls_key_path: None,
+        ..http_config(false)
+    };
+    assert!(matches!(server_config(&half), Err(TlsError::Incomplete)));
+    let ca_only = HttpConfig {
+        tls_client_ca_path: fixture("ca.pem"),
+        ..plain()
+    };
+    assert!(matches!(
+        server_config(&ca_only),
+        Err(TlsError::ClientCaWithoutTls)
+    ));
+    let missing = HttpConfig {
+        tls_cert_path: fixture("missing.pem"),
+        ..http_config(false)
+    };
+    assert!(matches!(
+        server_config(&missing),
+        Err(TlsError::Read { .. })
+    ));
+}
+
+#[tokio::test]
+async fn metrics_over_https() {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let addr = serve(RpcState::new(metrics), &http_config(false));
+    let resp = request(addr, None, "GET /metrics HTTP/1.1\r\n")
+        .await
+        .unwrap();
+    assert!(resp.starts_with("HTTP/1.1 200"), "{resp}");
+    assert!(resp.contains("amunchain_"));
+}
+
+#[tokio::test]
+async fn admin_requires_a_trusted_client_cert() {
+    let guard = telemetry::init(&TelemetryConfig::default()).unwrap();
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let state = RpcState::new(metrics.clone())
+        .with_admin("s3cret".to_string(), guard.log_filter())
+        .with_admin_client_cert();
+    let addr = serve(state, &http_config(true));
+    let admin = "GET /admin/loglevel HTTP/1.1\r\nAuthorization: Bearer s3cret\r\n";
+
+    // Certificates are optional for everything but /admin.
+    let resp = request(addr, None, "GET /metrics HTTP/1.1\r\n")
+        .await
+        .unwrap();
+    assert!(resp.starts_with("HTTP/1.1 200"), "{resp}");
+    let resp = request(addr, None, admin).await.unwrap();
+    assert!(resp.starts_with("HTTP/1.1 403"), "{resp}");
+
+    // A certificate does not replace the token.
+    let resp = request(addr, Some("client"), "GET /admin/loglevel HTTP/1.1\r\n")
+        .await
+        .unwrap();
+    assert!(resp.starts_with("HTTP/1.1 401"), "{resp}");
+    let resp = request(addr, Some("client"), admin).await.unwrap();
+    assert!(resp.starts_with("HTTP/1.1 200"), "{resp}");
+    assert!(resp.ends_with("info"), "{resp}");
+
+    // A certificate from another CA is refused during the handshake.
+    assert!(request(addr, Some("rogue-client"), admin).await.is_err());
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert_eq!(metrics.rpc_tls_handshake_failures_total.get(), 1);
+}
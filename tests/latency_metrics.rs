@@ -0,0 +1,66 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::driver::ConsensusDriver;
+use amunchain::core::state::persistent_state::{KvOp, PersistentState};
+use amunchain::core::types::{ConsensusMsg, Height, H256};
+use amunchain::monitoring::metrics::Metrics;
+use common::{keypairs, validators, vote};
+use std::sync::Arc;
+
+#[test]
+fn state_latencies_are_recorded() {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap())
+        .unwrap()
+        .with_metrics(metrics.clone());
+    st.commit_atomic(vec![KvOp::Put {
+        key: b"k".to_vec(),
+        value: b"v".to_vec(),
+    }])
+    .unwrap();
+    st.state_root().unwrap();
+    assert_eq!(metrics.state_commit_seconds.get_sample_count(), 1);
+    assert_eq!(metrics.state_root_seconds.get_sample_count(), 1);
+}
+
+#[test]
+fn consensus_latencies_are_recorded() {
+    let kps = keypairs(3);
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let set = validators(&kps);
+    let mut driver = ConsensusDriver::new(set.clone())
+        .unwrap()
+        .with_metrics(metrics.clone());
+
+    for kp in kps.iter() {
+        driver.on_msg(ConsensusMsg::Vote(vote(
+            kp,
+            &set,
+            1,
+            H256::from_bytes([1u8; 32]),
+        )));
+    }
+    assert_eq!(driver.tide.finalized_height(), Height(1));
+    assert_eq!(metrics.consensus_vote_verify_seconds.get_sample_count(), 3);
+    assert_eq!(metrics.consensus_commit_build_seconds.get_sample_count(), 3);
+
+    assert_eq!(metrics.block_height.get(), 1);
+    assert_eq!(metrics.consensus_votes_received_total.get(), 3);
+    assert_eq!(metrics.consensus_commits_total.get(), 1);
+    assert_eq!(metrics.consensus_double_votes_total.get(), 0);
+    assert_eq!(metrics.consensus_round_votes.get(), 0);
+}
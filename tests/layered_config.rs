@@ -0,0 +1,152 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::config::ConfigLoader;
+use amunchain::core::types::ConfigError;
+use amunchain::node::cli::Cli;
+use clap::Parser;
+
+fn env(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn defaults_alone_are_a_complete_config() {
+    let cfg = ConfigLoader::new().load().unwrap();
+    assert_eq!(cfg.http.listen_addr, "127.0.0.1:9090");
+    assert_eq!(cfg.runtime.rpc_max_in_flight, 256);
+}
+
+#[test]
+fn file_then_env_then_overrides() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("node.toml");
+    // A partial file: everything else comes from the defaults.
+    std::fs::write(
+        &path,
+        "[p2p]\nlisten_addr = \"/ip4/0.0.0.0/tcp/4001\"\nmax_peers_per_ip = 5\n",
+    )
+    .unwrap();
+
+    let cfg = ConfigLoader::new().file(&path).load().unwrap();
+    assert_eq!(cfg.p2p.listen_addr, "/ip4/0.0.0.0/tcp/4001");
+    assert_eq!(cfg.p2p.max_peers_per_ip, 5);
+    assert_eq!(cfg.p2p.max_msg_per_sec, 200);
+
+    let loader = ConfigLoader::new().file(&path).env_vars(env(&[
+        ("AMUNCHAIN__P2P__MAX_PEERS_PER_IP", "7"),
+        ("AMUNCHAIN__P2P__BOOTSTRAP", r#"["/ip4/10.0.0.1/tcp/4001"]"#),
+        ("AMUNCHAIN__HTTP__LISTEN_ADDR", "0.0.0.0:8080"),
+        ("AMUN_HTTP_ADDR", "ignored"),
+    ]));
+    let cfg = loader.load().unwrap();
+    assert_eq!(cfg.p2p.max_peers_per_ip, 7);
+    assert_eq!(cfg.p2p.bootstrap, ["/ip4/10.0.0.1/tcp/4001"]);
+    assert_eq!(cfg.http.listen_addr, "0.0.0.0:8080");
+
+    let cfg = loader
+        .set_arg("p2p.max_peers_per_ip=9")
+        .unwrap()
+        .load()
+        .unwrap();
+    assert_eq!(cfg.p2p.max_peers_per_ip, 9);
+}
+
+#[test]
+fn legacy_env_vars_sit_below_overrides() {
+    let loader = ConfigLoader::new().env_vars(env(&[
+        ("AMUN_HTTP_ADDR", "0.0.0.0:7070"),
+        ("AMUN_CHAIN_ID", "123"),
+        ("AMUN_P2P_TOPIC", "amunchain/consensus/test"),
+        ("AMUN_RPC_WORKERS", "3"),
+    ]));
+    let cfg = loader.load().unwrap();
+    assert_eq!(cfg.http.listen_addr, "0.0.0.0:7070");
+    assert_eq!(cfg.node.chain_id, "123");
+    assert_eq!(cfg.p2p.topic, "amunchain/consensus/test");
+    assert_eq!(cfg.runtime.rpc_worker_threads, 3);
+
+    let cfg = loader
+        .set_arg("http.listen_addr=127.0.0.1:6060")
+        .unwrap()
+        .load()
+        .unwrap();
+    assert_eq!(cfg.http.listen_addr, "127.0.0.1:6060");
+}
+
+#[test]
+fn telemetry_env_vars_are_part_of_the_env_layer() {
+    let loader = ConfigLoader::new().env_vars(env(&[
+        ("RUST_LOG", "debug,libp2p=warn"),
+        ("AMUN_OTLP_ENDPOINT", "http://127.0.0.1:4318/v1/traces"),
+        ("AMUN_OTLP_SAMPLE_RATIO", "0.25"),
+    ]));
+    let cfg = loader.load().unwrap();
+    assert_eq!(cfg.telemetry.log_filter, "debug,libp2p=warn");
+    assert_eq!(
+        cfg.telemetry.otlp_endpoint.as_deref(),
+        Some("http://127.0.0.1:4318/v1/traces")
+    );
+    assert_eq!(cfg.telemetry.sample_ratio, 0.25);
+
+    let cfg = loader
+        .set_arg("telemetry.log_filter=warn")
+        .unwrap()
+        .set_arg("telemetry.sample_ratio=1.0")
+        .unwrap()
+        .load()
+        .unwrap();
+    assert_eq!(cfg.telemetry.log_filter, "warn");
+    assert_eq!(cfg.telemetry.sample_ratio, 1.0);
+}
+
+#[test]
+fn errors_name_the_key() {
+    let err = ConfigLoader::new()
+        .env_vars(env(&[("AMUNCHAIN__P2P__MAX_PEERS_PER_IP", "many")]))
+        .load()
+        .unwrap_err();
+    assert!(err.to_string().contains("p2p.max_peers_per_ip"), "{err}");
+
+    assert!(matches!(
+        ConfigLoader::new().set_arg("p2p.max_peers_per_ip"),
+        Err(ConfigError::Override { .. })
+    ));
+    assert!(matches!(
+        ConfigLoader::new().set("p2p..x", "1").load(),
+        Err(ConfigError::Override { .. })
+    ));
+    assert!(matches!(
+        ConfigLoader::new().set("http.listen_addr.port", "1").load(),
+        Err(ConfigError::Override { .. })
+    ));
+}
+
+#[test]
+fn cli_flags_are_the_top_layer() {
+    let args = Cli::try_parse_from([
+        "amunchain",
+        "--set",
+        "p2p.max_peers_per_ip=11",
+        "--data-dir",
+        "/srv/node1",
+        "run",
+    ])
+    .unwrap();
+    let cfg = args.loader().unwrap().load().unwrap();
+    assert_eq!(cfg.p2p.max_peers_per_ip, 11);
+    assert_eq!(cfg.node.data_dir, "/srv/node1");
+}
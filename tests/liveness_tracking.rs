@@ -0,0 +1,329 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::driver::{ConsensusDriver, DriverError, MsgOutcome};
+use amunchain::core::consensus::liveness::{ChainLiveness, LivenessPolicy, LivenessTracker};
+use amunchain::core::consensus::tide::expected_set_hash;
+use amunchain::core::economics::bank::{Bank, BONDED_POOL};
+use amunchain::core::economics::staking::{StakingLedger, StakingParams};
+use amunchain::core::state::persistent_state::PersistentState;
+use amunchain::core::types::{
+    CanonicalMap, Commit, Epoch, Height, Round, Signature, ValidatorId, H256,
+};
+use common::{id, keypairs, validators, vote_msg, EPOCH};
+use ring::signature::Ed25519KeyPair;
+use std::collections::BTreeSet;
+
+fn make_validators(n: usize) -> BTreeSet<ValidatorId> {
+    let mut s = BTreeSet::new();
+    for i in 0..n {
+        let mut b = [0u8; 32];
+        b[0] = i as u8;
+        s.insert(ValidatorId::from_bytes(b));
+    }
+    s
+}
+
+fn commit_signed_by(height: u64, signers: &[ValidatorId]) -> Commit {
+    let mut signatures = CanonicalMap::new();
+    for v in signers {
+        signatures.insert(v.clone(), Signature::from_bytes([0u8; 64]));
+    }
+    Commit {
+        height: Height(height),
+        round: Round::ZERO,
+        epoch: Epoch::ZERO,
+        msg_counter: 0,
+        sent_ts_ms: 0,
+        ttl_ms: 0,
+        block_hash: H256::from_bytes([height as u8; 32]),
+        signatures,
+        voting_power: 0,
+        validator_set_hash: H256::ZERO,
+    }
+}
+
+// A commit for `height` signed by `signers`, valid against `set`.
+fn included_commit(signers: &[Ed25519KeyPair], set: &BTreeSet<ValidatorId>, height: u64) -> Commit {
+    let block_hash = H256::from_bytes([height as u8; 32]);
+    let mut signatures = CanonicalMap::new();
+    for kp in signers {
+        signatures.insert(id(kp), common::vote(kp, set, height, block_hash).signature);
+    }
+    Commit {
+        height: Height(height),
+        round: Round::ZERO,
+        epoch: EPOCH,
+        msg_counter: 0,
+        sent_ts_ms: 0,
+        ttl_ms: 0,
+        block_hash,
+        signatures,
+        voting_power: 0,
+        validator_set_hash: expected_set_hash(set, None).unwrap(),
+    }
+}
+
+#[test]
+fn liveness_counts_and_persists() {
+    let validators = make_validators(4);
+    let all: Vec<ValidatorId> = validators.iter().cloned().collect();
+    let offline = all[3].clone();
+
+    let mut tracker = LivenessTracker::new();
+    for h in 1..=3u64 {
+        assert!(tracker.record_commit(&commit_signed_by(h, &all[..3]), &validators));
+    }
+    // Duplicate certificate for an already recorded height is ignored.
+    assert!(!tracker.record_commit(&commit_signed_by(3, &all[..3]), &validators));
+
+    let rec = *tracker.record(&offline).unwrap();
+    assert_eq!((rec.missed, rec.consecutive_missed), (3, 3));
+    assert_eq!(rec.uptime_bps(), 0);
+    assert_eq!(tracker.record(&all[0]).unwrap().uptime_bps(), 10_000);
+
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    let root = st.state_root().unwrap();
+    tracker.commit(&st).unwrap();
+    // Counters are local observation and stay out of the state root.
+    assert_eq!(st.state_root().unwrap(), root);
+
+    let loaded = LivenessTracker::load(&st, &validators).unwrap();
+    assert_eq!(loaded.last_height(), 3);
+    assert_eq!(loaded.record(&offline), Some(&rec));
+}
+
+#[test]
+fn included_commits_jail_offline_validators() {
+    let kps = keypairs(4);
+    let validators = validators(&kps);
+    let offline = id(&kps[3]);
+    let mut ledger = StakingLedger::with_params(StakingParams {
+        min_self_stake: 10,
+        min_jail_secs: 100,
+        ..StakingParams::default()
+    })
+    .unwrap();
+    let mut bank = Bank::default();
+    for v in validators.iter() {
+        bank.mint(v.as_bytes(), 100).unwrap();
+        ledger
+            .register_validator_from(&mut bank, v.as_bytes().to_vec(), 0, 100)
+            .unwrap();
+    }
+    let mut d = ConsensusDriver::new(validators.clone())
+        .unwrap()
+        .with_liveness_policy(LivenessPolicy {
+            auto_jail: true,
+            jail_after_missed: 3,
+        });
+    let mut chain = ChainLiveness::default();
+
+    // Fewer than a quorum of signatures: nothing is recorded.
+    assert!(matches!(
+        d.apply_included_commit(
+            &mut ledger,
+            &mut bank,
+            &mut chain,
+            &included_commit(&kps[..2], &validators, 1),
+            1_000
+        ),
+        Err(DriverError::Commit(_))
+    ));
+    assert_eq!(chain, ChainLiveness::default());
+
+    for h in 1..=2u64 {
+        let c = included_commit(&kps[..3], &validators, h);
+        assert!(d
+            .apply_included_commit(&mut ledger, &mut bank, &mut chain, &c, 1_000)
+            .unwrap()
+            .is_empty());
+    }
+    assert_eq!(chain.missed(&offline), 2);
+    assert_eq!(chain.missed(&id(&kps[0])), 0);
+    // The same commit included twice counts once.
+    let c = included_commit(&kps[..3], &validators, 2);
+    assert!(d
+        .apply_included_commit(&mut ledger, &mut bank, &mut chain, &c, 1_000)
+        .unwrap()
+        .is_empty());
+    assert_eq!(chain.missed(&offline), 2);
+
+    let c = included_commit(&kps[..3], &validators, 3);
+    assert_eq!(
+        d.apply_included_commit(&mut ledger, &mut bank, &mut chain, &c, 1_000)
+            .unwrap(),
+        vec![offline.clone()]
+    );
+    assert!(ledger.validators[offline.as_bytes().as_slice()].jailed);
+    assert_eq!(chain.missed(&offline), 0);
+
+    // The streaks are chain state: they change the state root and load back as they were.
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    let root = st.state_root().unwrap();
+    chain.commit(&st).unwrap();
+    assert_ne!(st.state_root().unwrap(), root);
+    assert_eq!(ChainLiveness::load(&st).unwrap(), chain);
+
+    d.sync_staking(&ledger).unwrap();
+    assert!(!d.tide.validators().contains(&offline));
+    assert_eq!(d.tide.validators().len(), 3);
+}
+
+#[test]
+fn validator_taken_offline_is_slashed_for_downtime() {
+    let kps = keypairs(4);
+    let validators = validators(&kps);
+    let offline = id(&kps[3]);
+    let mut ledger = StakingLedger::with_params(StakingParams {
+        min_self_stake: 10,
+        slash_downtime_bps: 100,
+        min_jail_secs: 100,
+        ..StakingParams::default()
+    })
+    .unwrap();
+    let mut bank = Bank::default();
+    for v in validators.iter() {
+        bank.mint(v.as_bytes(), 1_000).unwrap();
+        ledger
+            .register_validator_from(&mut bank, v.as_bytes().to_vec(), 0, 1_000)
+            .unwrap();
+    }
+    let mut d = ConsensusDriver::new(validators.clone())
+        .unwrap()
+        .with_liveness_policy(LivenessPolicy {
+            auto_jail: true,
+            jail_after_missed: 3,
+        });
+    let mut chain = ChainLiveness::default();
+
+    let c = included_commit(&kps, &validators, 1);
+    assert!(d
+        .apply_included_commit(&mut ledger, &mut bank, &mut chain, &c, 1_000)
+        .unwrap()
+        .is_empty());
+    // The validator goes offline: the next commits are signed by the other three only.
+    for h in 2..=3u64 {
+        let c = included_commit(&kps[..3], &validators, h);
+        d.apply_included_commit(&mut ledger, &mut bank, &mut chain, &c, 1_000)
+            .unwrap();
+    }
+    assert!(!ledger.validators[offline.as_bytes().as_slice()].jailed);
+
+    let c = included_commit(&kps[..3], &validators, 4);
+    // A failed slash leaves the streaks where they were, so the commit can be applied again.
+    let before = chain.clone();
+    assert!(matches!(
+        d.apply_included_commit(&mut ledger, &mut Bank::default(), &mut chain, &c, 1_000),
+        Err(DriverError::Staking(_))
+    ));
+    assert_eq!(chain, before);
+    assert!(!ledger.validators[offline.as_bytes().as_slice()].jailed);
+
+    d.apply_included_commit(&mut ledger, &mut bank, &mut chain, &c, 1_000)
+        .unwrap();
+    let val = &ledger.validators[offline.as_bytes().as_slice()];
+    assert!(val.jailed);
+    assert_eq!(val.jailed_until, 1_100);
+    assert_eq!((val.self_stake, val.slashed), (990, 10));
+    // The slashed stake is burned, not left in the bonded pool.
+    assert_eq!(bank.balance(BONDED_POOL), 3_990);
+    assert_eq!(bank.total_supply, 3_990);
+
+    d.sync_staking(&ledger).unwrap();
+    assert_eq!(d.tide.validators(), &common::validators(&kps[..3]));
+}
+
+#[test]
+fn driver_persists_liveness_across_restarts() {
+    let kps = keypairs(4);
+    let validators = validators(&kps);
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+
+    let mut d = ConsensusDriver::new(validators.clone())
+        .unwrap()
+        .with_liveness_state(st.clone());
+    for kp in &kps[..3] {
+        assert_eq!(
+            d.on_peer_msg(b"peer", vote_msg(kp, &validators, 1)),
+            MsgOutcome::Accepted
+        );
+    }
+    assert_eq!(d.tide.finalized_height(), Height(1));
+    drop(d);
+
+    // A restarted node picks the counters up where the last run left them.
+    let loaded = LivenessTracker::load(&st, &validators).unwrap();
+    assert_eq!(loaded.last_height(), 1);
+    assert_eq!(loaded.record(&id(&kps[0])).unwrap().signed, 1);
+    assert_eq!(loaded.record(&id(&kps[3])).unwrap().consecutive_missed, 1);
+    let d = ConsensusDriver::new(validators)
+        .unwrap()
+        .with_liveness(loaded);
+    assert_eq!(d.liveness.record(&id(&kps[3])).unwrap().missed, 1);
+}
+
+#[test]
+fn late_votes_are_credited_for_the_last_height() {
+    let validators = make_validators(4);
+    let all: Vec<ValidatorId> = validators.iter().cloned().collect();
+    let slow = all[3].clone();
+    let mut tracker = LivenessTracker::new();
+
+    assert!(tracker.record_commit(&commit_signed_by(1, &all[..3]), &validators));
+    assert_eq!(tracker.record(&slow).unwrap().missed, 1);
+
+    // The slow validator's vote shows up after the first-quorum certificate.
+    assert!(tracker.record_late_vote(Height(1), &slow));
+    let rec = *tracker.record(&slow).unwrap();
+    assert_eq!((rec.signed, rec.missed, rec.consecutive_missed), (1, 0, 0));
+    assert!(!tracker.record_late_vote(Height(1), &slow));
+
+    // Votes verified before finality count like certificate signers for uptime.
+    let voters: BTreeSet<ValidatorId> = all.iter().cloned().collect();
+    assert!(tracker.record_votes(&commit_signed_by(2, &all[..3]), &voters, &validators));
+    let rec = *tracker.record(&slow).unwrap();
+    assert_eq!((rec.signed, rec.uptime_bps()), (2, 10_000));
+
+    // Only the last recorded height can still be corrected.
+    assert!(tracker.record_commit(&commit_signed_by(3, &all[..3]), &validators));
+    assert!(!tracker.record_late_vote(Height(2), &slow));
+    assert_eq!(tracker.record(&slow).unwrap().missed, 1);
+}
+
+#[test]
+fn driver_counts_votes_left_out_of_the_certificate() {
+    let kps = keypairs(4);
+    let validators = validators(&kps);
+    let mut d = ConsensusDriver::new(validators.clone()).unwrap();
+    for kp in &kps[..3] {
+        assert_eq!(
+            d.on_peer_msg(b"peer", vote_msg(kp, &validators, 1)),
+            MsgOutcome::Accepted
+        );
+    }
+    assert_eq!(d.liveness.record(&id(&kps[3])).unwrap().missed, 1);
+
+    assert_eq!(
+        d.on_peer_msg(b"peer", vote_msg(&kps[3], &validators, 1)),
+        MsgOutcome::Accepted
+    );
+    let rec = d.liveness.record(&id(&kps[3])).unwrap();
+    assert_eq!((rec.signed, rec.missed), (1, 0));
+    assert_eq!(d.liveness.last_height(), 1);
+}
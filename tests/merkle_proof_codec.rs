@@ -0,0 +1,133 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::state::merkle::{
+    merkle_proof_sorted, merkle_root_sorted, verify_proof, MerkleProof, ProofCodecError, ProofItem,
+    Side, MAX_PROOF_DEPTH,
+};
+use amunchain::core::types::{decode_canonical_limited, encode_canonical};
+use proptest::prelude::*;
+
+fn arb_proof(max_depth: usize) -> impl Strategy<Value = MerkleProof> {
+    (
+        any::<[u8; 32]>(),
+        proptest::collection::vec((any::<bool>(), any::<[u8; 32]>()), 0..=max_depth),
+    )
+        .prop_map(|(leaf, path)| MerkleProof {
+            leaf,
+            path: path
+                .into_iter()
+                .map(|(left, sibling)| ProofItem {
+                    side: if left { Side::Left } else { Side::Right },
+                    sibling,
+                })
+                .collect(),
+        })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(128))]
+
+    #[test]
+    fn prop_bytes_round_trip(proof in arb_proof(MAX_PROOF_DEPTH)) {
+        let raw = proof.to_bytes().unwrap();
+        prop_assert_eq!(raw.len(), MerkleProof::encoded_len(proof.path.len()));
+        prop_assert_eq!(MerkleProof::from_bytes(&raw).unwrap(), proof);
+    }
+
+    #[test]
+    fn prop_serde_round_trip(proof in arb_proof(40)) {
+        let json = serde_json::to_string(&proof).unwrap();
+        prop_assert_eq!(serde_json::from_str::<MerkleProof>(&json).unwrap(), proof.clone());
+        let bin = encode_canonical(&proof).unwrap();
+        let limit = MerkleProof::encoded_len(MAX_PROOF_DEPTH) + 8;
+        prop_assert_eq!(decode_canonical_limited::<MerkleProof>(&bin, limit).unwrap(), proof);
+    }
+
+    #[test]
+    fn prop_decoded_proofs_still_verify(
+        n in 1usize..200,
+        pick in any::<prop::sample::Index>(),
+    ) {
+        let pairs: Vec<(Vec<u8>, Vec<u8>)> = (0..n as u32)
+            .map(|i| (i.to_be_bytes().to_vec(), vec![i as u8; 4]))
+            .collect();
+        let root = merkle_root_sorted(&pairs);
+        let proof = merkle_proof_sorted(&pairs, pick.index(n)).unwrap();
+        let decoded = MerkleProof::from_bytes(&proof.to_bytes().unwrap()).unwrap();
+        prop_assert!(verify_proof(root, &decoded));
+    }
+
+    #[test]
+    fn prop_arbitrary_bytes_never_panic(raw in proptest::collection::vec(any::<u8>(), 0..300)) {
+        if let Ok(proof) = MerkleProof::from_bytes(&raw) {
+            prop_assert_eq!(proof.to_bytes().unwrap(), raw);
+        }
+    }
+}
+
+#[test]
+fn encoding_is_compact_and_canonical() {
+    let proof = MerkleProof {
+        leaf: [1; 32],
+        path: (0..10)
+            .map(|i| ProofItem {
+                side: if i % 3 == 0 { Side::Left } else { Side::Right },
+                sibling: [i; 32],
+            })
+            .collect(),
+    };
+    let raw = proof.to_bytes().unwrap();
+    // Header, two bytes of sides, ten siblings.
+    assert_eq!(raw.len(), 2 + 32 + 2 + 10 * 32);
+    assert_eq!(&raw[34..36], &[0b0100_1001, 0b0000_0010]);
+
+    let mut padded = raw.clone();
+    padded[35] |= 0x80;
+    assert_eq!(
+        MerkleProof::from_bytes(&padded),
+        Err(ProofCodecError::Padding)
+    );
+    let mut versioned = raw.clone();
+    versioned[0] = 2;
+    assert_eq!(
+        MerkleProof::from_bytes(&versioned),
+        Err(ProofCodecError::Version(2))
+    );
+    assert!(matches!(
+        MerkleProof::from_bytes(&raw[..raw.len() - 1]),
+        Err(ProofCodecError::Length { .. })
+    ));
+    assert_eq!(
+        MerkleProof::from_bytes(&[1]),
+        Err(ProofCodecError::Truncated)
+    );
+    let mut deep = raw.clone();
+    deep[1] = MAX_PROOF_DEPTH as u8 + 1;
+    assert_eq!(
+        MerkleProof::from_bytes(&deep),
+        Err(ProofCodecError::TooDeep(MAX_PROOF_DEPTH + 1))
+    );
+
+    let too_deep = MerkleProof {
+        leaf: [0; 32],
+        path: vec![proof.path[0].clone(); MAX_PROOF_DEPTH + 1],
+    };
+    assert!(too_deep.to_bytes().is_err());
+    assert!(serde_json::to_string(&too_deep).is_err());
+    let oversized = format!(
+        "\"{}\"",
+        "00".repeat(MerkleProof::encoded_len(MAX_PROOF_DEPTH) + 1)
+    );
+    assert!(serde_json::from_str::<MerkleProof>(&oversized).is_err());
+}
@@ -0,0 +1,112 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::types::H256;
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::node::builder::NodeBuilder;
+use amunchain::node::extensions::{
+    BlockCheck, BlockContext, ExtensionError, ExtensionRegistry, GossipHandler, NodeExtension,
+};
+use amunchain::rpc::server::{bind, serve_listener, RpcState};
+use axum::{routing::get, Router};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+struct Bridge;
+
+struct NonEmpty;
+impl BlockCheck for NonEmpty {
+    fn check(&self, block: &BlockContext<'_>) -> Result<(), String> {
+        if block.payload.is_empty() {
+            return Err("empty payload".into());
+        }
+        Ok(())
+    }
+}
+
+struct Sink;
+impl GossipHandler for Sink {
+    fn on_message(&self, _peer: &[u8], _data: &[u8]) {}
+}
+
+impl NodeExtension for Bridge {
+    fn name(&self) -> &'static str {
+        "bridge"
+    }
+
+    fn register(&self, reg: &mut ExtensionRegistry) -> Result<(), ExtensionError> {
+        reg.rpc_namespace(
+            "bridge",
+            Router::new().route("/ping", get(|| async { "pong" })),
+        )?
+        .gossip_topic("bridge/relay/v1", Arc::new(Sink))?
+        .block_check("non-empty", Arc::new(NonEmpty))?
+        .metric(Box::new(
+            prometheus::IntCounter::new("bridge_relayed_total", "Relayed messages").unwrap(),
+        ));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn extension_routes_checks_and_metrics_are_wired() {
+    let ext = NodeBuilder::new().extension(Bridge).build().unwrap();
+    assert!(ext.topics().contains_key("bridge/relay/v1"));
+    assert_eq!(
+        ext.block_checks().collect::<Vec<_>>(),
+        vec!["bridge/non-empty"]
+    );
+
+    let block = |payload: &'static [u8]| BlockContext {
+        height: 1,
+        block_hash: H256::from_bytes([0u8; 32]),
+        payload,
+    };
+    ext.validate_block(&block(b"tx")).unwrap();
+    assert!(matches!(
+        ext.validate_block(&block(b"")),
+        Err(ExtensionError::BlockRejected { .. })
+    ));
+
+    let metrics = Arc::new(Metrics::new().unwrap());
+    ext.register_metrics(&metrics).unwrap();
+    assert!(metrics
+        .registry
+        .gather()
+        .iter()
+        .any(|f| f.get_name() == "bridge_relayed_total"));
+
+    let listener = bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let state = RpcState::new(metrics).with_extensions(ext);
+    tokio::spawn(serve_listener(listener, state));
+
+    let mut s = tokio::net::TcpStream::connect(addr).await.unwrap();
+    s.write_all(b"GET /ext/bridge/ping HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n")
+        .await
+        .unwrap();
+    let mut resp = String::new();
+    s.read_to_string(&mut resp).await.unwrap();
+    assert!(resp.starts_with("HTTP/1.1 200"));
+    assert!(resp.ends_with("pong"));
+}
+
+#[test]
+fn conflicting_extensions_fail_the_build() {
+    let err = NodeBuilder::new()
+        .extension(Bridge)
+        .extension(Bridge)
+        .build()
+        .unwrap_err();
+    assert!(matches!(err, ExtensionError::Duplicate(_)));
+}
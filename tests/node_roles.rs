@@ -0,0 +1,98 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::config::ConfigLoader;
+use amunchain::core::consensus::driver::{ConsensusDriver, MsgOutcome};
+use amunchain::core::types::Height;
+use amunchain::node::config_check::check;
+use amunchain::node::role::{check_role, NodeRole, RoleError};
+use common::{keypairs, validators, vote_msg};
+
+fn fields(loader: ConfigLoader) -> Vec<String> {
+    check(&loader.load().unwrap())
+        .into_iter()
+        .map(|i| i.field)
+        .collect()
+}
+
+#[test]
+fn non_voting_nodes_withhold_local_votes_but_finalize() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let mut d = ConsensusDriver::new(set.clone())
+        .unwrap()
+        .with_local_votes(NodeRole::Full.votes());
+    assert!(d.is_participating());
+    assert_eq!(d.on_msg(vote_msg(&kps[0], &set, 1)), MsgOutcome::Withheld);
+    for kp in &kps[1..] {
+        assert_eq!(
+            d.on_peer_msg(b"peer", vote_msg(kp, &set, 1)),
+            MsgOutcome::Accepted
+        );
+    }
+    assert_eq!(d.tide.finalized_height(), Height(1));
+}
+
+#[test]
+fn role_is_configured_and_checked() {
+    let cfg = ConfigLoader::new().load().unwrap();
+    assert_eq!(cfg.node.role, NodeRole::Validator);
+
+    // Observers may run without the allowlist other roles are required to have.
+    let strict = |role: &str| {
+        ConfigLoader::new()
+            .set("node.role", role)
+            .set("p2p.require_allow_peers", "true")
+    };
+    assert!(fields(strict("full")).contains(&"p2p.require_allow_peers".to_string()));
+    assert!(!fields(strict("observer")).contains(&"p2p.require_allow_peers".to_string()));
+
+    // Only validators may take the validator key as their p2p identity.
+    let keyed = |role: &str| {
+        ConfigLoader::new()
+            .set("node.role", role)
+            .set("p2p.identity", "validator")
+    };
+    assert!(!fields(keyed("validator")).contains(&"p2p.identity".to_string()));
+    assert!(fields(keyed("observer")).contains(&"p2p.identity".to_string()));
+    let dir = tempfile::tempdir().unwrap();
+    assert_eq!(
+        check_role(&keyed("full").load().unwrap(), dir.path()),
+        Err(RoleError::ValidatorIdentity("full"))
+    );
+
+    let err = ConfigLoader::new()
+        .set("node.role", "archive")
+        .load()
+        .unwrap_err();
+    assert!(err.to_string().contains("archive"), "{err}");
+}
+
+#[test]
+fn startup_warns_about_the_validator_key() {
+    let dir = tempfile::tempdir().unwrap();
+    let load = |role: &str| ConfigLoader::new().set("node.role", role).load().unwrap();
+    assert_eq!(check_role(&load("validator"), dir.path()).unwrap().len(), 1);
+    assert!(check_role(&load("observer"), dir.path())
+        .unwrap()
+        .is_empty());
+
+    std::fs::write(dir.path().join("validator.key"), b"key").unwrap();
+    assert!(check_role(&load("validator"), dir.path())
+        .unwrap()
+        .is_empty());
+    let warnings = check_role(&load("observer"), dir.path()).unwrap();
+    assert!(warnings[0].contains("not loaded"), "{warnings:?}");
+}
@@ -0,0 +1,153 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::driver::ConsensusDriver;
+use amunchain::core::state::commit_store::CommitStore;
+use amunchain::core::state::persistent_state::{KvOp, PersistentState};
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::rpc::server::{bind, serve_listener, RpcState};
+use common::{keypairs, validators, vote_msg};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+async fn get(addr: std::net::SocketAddr, path: &str) -> serde_json::Value {
+    let mut s = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let req = format!("GET {path} HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n");
+    s.write_all(req.as_bytes()).await.unwrap();
+    let mut resp = String::new();
+    s.read_to_string(&mut resp).await.unwrap();
+    assert!(resp.starts_with("HTTP/1.1 200"), "{resp}");
+    serde_json::from_str(resp.split("\r\n\r\n").nth(1).unwrap()).unwrap()
+}
+
+async fn status(addr: std::net::SocketAddr) -> serde_json::Value {
+    get(addr, "/status").await
+}
+
+fn serve(state: RpcState) -> std::net::SocketAddr {
+    let listener = bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_listener(listener, state));
+    addr
+}
+
+#[tokio::test]
+async fn metrics_only_node_reports_what_it_has() {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    metrics.block_height.set(9);
+    metrics.p2p_peers.set(3);
+    let body = status(serve(RpcState::new(metrics))).await;
+
+    assert_eq!(body["finalized_height"], 9);
+    assert_eq!(body["peers"], 3);
+    for field in [
+        "state_root",
+        "db_size_bytes",
+        "last_commit_age_secs",
+        "participation",
+    ] {
+        assert!(body[field].is_null(), "{field}: {body}");
+    }
+}
+
+#[tokio::test]
+async fn full_node_reports_chain_state_and_participation() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    st.commit_atomic(vec![KvOp::Put {
+        key: b"k".to_vec(),
+        value: b"v".to_vec(),
+    }])
+    .unwrap();
+    let mut driver = ConsensusDriver::new(set.clone())
+        .unwrap()
+        .with_commit_store(CommitStore::open(&st).unwrap());
+    for kp in kps.iter().take(3) {
+        driver.on_msg(vote_msg(kp, &set, 1));
+    }
+
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let addr = serve(
+        RpcState::new(metrics)
+            .with_driver(Arc::new(Mutex::new(driver)))
+            .with_state(st.clone()),
+    );
+    let body = status(addr).await;
+
+    let root = hex::encode(st.state_root().unwrap());
+    assert_eq!(body["finalized_height"], 1);
+    assert_eq!(body["state_root"], root.as_str());
+    assert!(body["db_size_bytes"].as_u64().unwrap() > 0);
+    // v1 votes carry no timestamp, so the commit's age is unknown.
+    assert!(body["last_commit_age_secs"].is_null());
+    assert_eq!(body["participation"]["active_validators"], 4);
+    assert_eq!(body["participation"]["last_commit_signers"], 3);
+    assert_eq!(body["participation"]["mean_uptime_bps"], 7_500);
+
+    // The root is only recomputed once the finalized height moves.
+    st.commit_atomic(vec![KvOp::Del { key: b"k".to_vec() }])
+        .unwrap();
+    assert_eq!(status(addr).await["state_root"], root.as_str());
+}
+
+#[tokio::test]
+async fn consensus_routes_do_not_wait_for_the_driver() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    let driver = Arc::new(Mutex::new(
+        ConsensusDriver::new(set.clone())
+            .unwrap()
+            .with_commit_store(CommitStore::open(&st).unwrap()),
+    ));
+    let addr = serve(RpcState::new(Arc::new(Metrics::new().unwrap())).with_driver(driver.clone()));
+
+    for kp in kps.iter().take(3) {
+        driver.lock().unwrap().on_msg(vote_msg(kp, &set, 1));
+    }
+
+    // Keep the driver locked, as it is while the pumps process a burst of messages.
+    let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+    let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+    let holder = driver.clone();
+    let holder = std::thread::spawn(move || {
+        let _guard = holder.lock().unwrap();
+        locked_tx.send(()).unwrap();
+        let _ = release_rx.recv();
+    });
+    locked_rx.recv().unwrap();
+
+    let requests = async {
+        assert_eq!(status(addr).await["finalized_height"], 1);
+        assert_eq!(
+            get(addr, "/consensus/liveness")
+                .await
+                .as_array()
+                .unwrap()
+                .len(),
+            4
+        );
+        assert!(get(addr, "/consensus/performance").await.is_array());
+        assert_eq!(get(addr, "/consensus/timings/1").await["height"], 1);
+    };
+    tokio::time::timeout(std::time::Duration::from_secs(10), requests)
+        .await
+        .expect("rpc waited for the driver lock");
+    release_tx.send(()).unwrap();
+    holder.join().unwrap();
+}
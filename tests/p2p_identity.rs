@@ -0,0 +1,90 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::security::keystore::{self, generate_key_file};
+use amunchain::core::types::ValidatorId;
+use amunchain::networking::p2p_identity::{
+    load_identity_with, load_or_create_identity, peer_id_of_key, peer_public_key, IdentityError,
+    IdentitySource, ValidatorPeers, IDENTITY_FILE,
+};
+use ring::signature::KeyPair;
+
+#[test]
+fn encrypted_identity_keeps_peer_id_and_needs_passphrase() {
+    let dir = tempfile::tempdir().unwrap();
+    let (plain_id, _) = load_or_create_identity(dir.path()).unwrap();
+    let path = dir.path().join(IDENTITY_FILE);
+    assert!(!keystore::is_encrypted(&std::fs::read(&path).unwrap()));
+
+    // Switching to `encrypted` encrypts the existing key in place.
+    let (id, _) = load_identity_with(dir.path(), IdentitySource::Encrypted, Some("pw")).unwrap();
+    assert_eq!(id, plain_id);
+    assert!(keystore::is_encrypted(&std::fs::read(&path).unwrap()));
+
+    assert!(matches!(
+        load_identity_with(dir.path(), IdentitySource::Encrypted, None),
+        Err(IdentityError::MissingPassphrase)
+    ));
+    assert!(matches!(
+        load_identity_with(dir.path(), IdentitySource::File, None),
+        Err(IdentityError::MissingPassphrase)
+    ));
+    assert!(matches!(
+        load_identity_with(dir.path(), IdentitySource::Encrypted, Some("wrong")),
+        Err(IdentityError::Crypto)
+    ));
+    let (id, _) = load_identity_with(dir.path(), IdentitySource::File, Some("pw")).unwrap();
+    assert_eq!(id, plain_id);
+
+    // A fresh encrypted identity is never written in plaintext.
+    let fresh = tempfile::tempdir().unwrap();
+    load_identity_with(fresh.path(), IdentitySource::Encrypted, Some("pw")).unwrap();
+    assert!(keystore::is_encrypted(
+        &std::fs::read(fresh.path().join(IDENTITY_FILE)).unwrap()
+    ));
+}
+
+#[test]
+fn validator_identity_is_the_validator_key() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(matches!(
+        load_identity_with(dir.path(), IdentitySource::Validator, None),
+        Err(IdentityError::NoValidatorKey)
+    ));
+    let kp = generate_key_file(&dir.path().join("validator.key"), Some("pw")).unwrap();
+    let pk: [u8; 32] = kp.public_key().as_ref().try_into().unwrap();
+
+    let (peer, _) = load_identity_with(dir.path(), IdentitySource::Validator, Some("pw")).unwrap();
+    assert_eq!(peer_public_key(&peer), Some(pk));
+    assert_eq!(peer_id_of_key(&pk), Some(peer));
+    assert!(!dir.path().join(IDENTITY_FILE).exists());
+}
+
+#[test]
+fn maps_peers_to_validators() {
+    let dir = tempfile::tempdir().unwrap();
+    let kp = generate_key_file(&dir.path().join("validator.key"), None).unwrap();
+    let v = ValidatorId::from_slice(kp.public_key().as_ref()).unwrap();
+    let (peer, _) = load_identity_with(dir.path(), IdentitySource::Validator, None).unwrap();
+
+    let mut peers = ValidatorPeers::new([&v]);
+    assert_eq!(peers.validator(&peer), Some(&v));
+    assert_eq!(peers.peer(&v), Some(&peer));
+
+    // A separate p2p key bound explicitly replaces the derived one.
+    let (other, _) = load_or_create_identity(dir.path()).unwrap();
+    peers.insert(other, v.clone());
+    assert_eq!(peers.validator(&other), Some(&v));
+    assert_eq!(peers.validator(&peer), None);
+    assert_eq!(peers.len(), 1);
+}
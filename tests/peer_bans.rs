@@ -0,0 +1,76 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::networking::peer_score::{Decision, PeerScore, ScoreParams};
+use std::time::{Duration, Instant};
+
+const BAD: &[u8] = b"bad";
+const RELAY: &[u8] = b"relay";
+
+fn params() -> ScoreParams {
+    ScoreParams {
+        bad_inc: 10,
+        ban_threshold: 30,
+        ban_duration: Duration::from_secs(60),
+        ..ScoreParams::default()
+    }
+}
+
+fn ban(scores: &mut PeerScore, now: Instant) {
+    let decisions: Vec<Decision> = (0..3)
+        .map(|_| scores.observe_bad(BAD.to_vec(), now, 1))
+        .collect();
+    assert_eq!(
+        decisions,
+        [Decision::Throttle, Decision::Throttle, Decision::Ban]
+    );
+}
+
+#[test]
+fn banned_author_is_dropped_when_relayed() {
+    let now = Instant::now();
+    let mut scores = PeerScore::new(params());
+    assert!(scores.admits(Some(BAD), RELAY));
+    ban(&mut scores, now);
+
+    assert!(scores.is_banned(BAD));
+    // Direct, and relayed by an honest peer: both dropped.
+    assert!(!scores.admits(Some(BAD), BAD));
+    assert!(!scores.admits(Some(BAD), RELAY));
+    assert!(!scores.admits(None, BAD));
+    // The relay's own messages still pass.
+    assert!(scores.admits(Some(RELAY), RELAY));
+    assert!(scores.admits(None, RELAY));
+}
+
+#[test]
+fn bans_expire_and_the_score_starts_over() {
+    let now = Instant::now();
+    let mut scores = PeerScore::new(params());
+    ban(&mut scores, now);
+    // Further offences do not extend the ban.
+    scores.observe_bad(BAD.to_vec(), now + Duration::from_secs(30), 1);
+
+    assert!(scores.expire_bans(now + Duration::from_secs(59)).is_empty());
+    assert_eq!(
+        scores.expire_bans(now + Duration::from_secs(60)),
+        [BAD.to_vec()]
+    );
+    assert_eq!(scores.banned_len(), 0);
+    assert!(scores.admits(Some(BAD), RELAY));
+    assert_eq!(scores.score_of(BAD), 0);
+
+    // Misbehaving again bans again.
+    ban(&mut scores, now + Duration::from_secs(61));
+    assert!(scores.is_banned(BAD));
+}
@@ -0,0 +1,186 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::types::{
+    ConsensusMsg, Epoch, Height, Round, Signature, ValidatorId, Vote, H256,
+};
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::networking::p2p::{spawn_p2p, P2pConfig};
+use amunchain::networking::peer_caps::{
+    Capability, PeerCapabilityMap, TopicKind, STATESYNC_PROTOCOL, SYNC_PROTOCOL,
+};
+use amunchain::rpc::server::{bind, serve_listener, RpcState};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+fn config(dir: &tempfile::TempDir, port: u16, bootstrap: Vec<String>) -> P2pConfig {
+    P2pConfig {
+        listen_addr: format!("/ip4/127.0.0.1/tcp/{port}"),
+        consensus_topic: "caps-test".to_string(),
+        consensus_codec: Default::default(),
+        evidence_topic: "caps-test-evidence".to_string(),
+        max_msg_per_sec: 100,
+        max_peers_per_ip: 4,
+        data_dir: dir.path().to_string_lossy().to_string(),
+        identity: Default::default(),
+        bootstrap,
+        allow_peers: Vec::new(),
+        extensions: None,
+        channels: Default::default(),
+        gossipsub: Default::default(),
+        limits: Default::default(),
+        read_only: false,
+    }
+}
+
+fn vote(counter: u64) -> ConsensusMsg {
+    ConsensusMsg::Vote(Vote {
+        height: Height(1),
+        round: Round::ZERO,
+        epoch: Epoch::ZERO,
+        msg_counter: counter,
+        sent_ts_ms: 0,
+        ttl_ms: 0,
+        block_hash: H256::from_bytes([1; 32]),
+        voter: ValidatorId::from_bytes([2; 32]),
+        signature: Signature::from_bytes([3; 64]),
+    })
+}
+
+async fn get(addr: SocketAddr, path: &str) -> String {
+    let mut s = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let req = format!("GET {path} HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n");
+    s.write_all(req.as_bytes()).await.unwrap();
+    let mut resp = String::new();
+    s.read_to_string(&mut resp).await.unwrap();
+    resp
+}
+
+#[test]
+fn capabilities_follow_identify_and_connections() {
+    let map = PeerCapabilityMap::default();
+    for peer in ["a", "b", "c"] {
+        map.connected(peer);
+    }
+    map.identified(
+        "a",
+        "amunchain/1",
+        "amunchain/1.0.0 wire=1,2",
+        [
+            SYNC_PROTOCOL.to_string(),
+            "/ipfs/id/1.0.0".to_string(),
+            SYNC_PROTOCOL.to_string(),
+        ],
+    );
+    map.identified(
+        "b",
+        "amunchain/1",
+        "amunchain/1.0.0 wire=2",
+        [SYNC_PROTOCOL.to_string(), STATESYNC_PROTOCOL.to_string()],
+    );
+    map.set_wire_version("b", Some(2));
+    // Identify from a peer that is no longer tracked is ignored.
+    map.identified("gone", "x", "y", [SYNC_PROTOCOL.to_string()]);
+
+    let a = map.get("a").unwrap();
+    assert!(a.supports_sync && !a.supports_statesync);
+    assert_eq!(a.protocols, vec!["/amunchain/sync/1.0.0", "/ipfs/id/1.0.0"]);
+    assert_eq!(map.get("b").unwrap().wire_version, Some(2));
+    assert!(!map.get("c").unwrap().supports(Capability::Sync));
+    assert!(map.get("gone").is_none());
+
+    map.record("a", TopicKind::Consensus, 100, false);
+    map.record("a", TopicKind::Evidence, 20, true);
+    let usage = map.get("a").unwrap().usage;
+    assert_eq!(
+        (
+            usage.consensus_msgs,
+            usage.evidence_msgs,
+            usage.bytes_received,
+            usage.invalid_msgs
+        ),
+        (1, 1, 120, 1)
+    );
+    // Peers that misbehaved less come first.
+    assert_eq!(map.supporting(Capability::Sync), vec!["b", "a"]);
+    assert_eq!(map.supporting(Capability::StateSync), vec!["b"]);
+
+    map.connected("b");
+    map.disconnected("b", 1);
+    assert_eq!(map.get("b").unwrap().connections, 1);
+    map.disconnected("b", 0);
+    assert!(map.get("b").is_none());
+    assert_eq!(map.list().len(), 2);
+}
+
+#[tokio::test]
+async fn connected_peers_are_listed_with_their_identify_info() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let (dir_a, dir_b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let (a, _events_a, _join_a) =
+        spawn_p2p(config(&dir_a, port, Vec::new()), metrics.clone()).unwrap();
+    let (b, _events_b, _join_b) = spawn_p2p(
+        config(&dir_b, 0, vec![format!("/ip4/127.0.0.1/tcp/{port}")]),
+        Arc::new(Metrics::new().unwrap()),
+    )
+    .unwrap();
+
+    // Publish until a's view of b shows identify results and consensus traffic, and b's
+    // dial-back of a's address has come and gone.
+    let caps = a.capabilities();
+    let out = b.outbound();
+    let peer = tokio::time::timeout(Duration::from_secs(30), async {
+        let mut counter = 1;
+        loop {
+            let _ = out.send(vote(counter)).await;
+            counter += 1;
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            if let Some(p) = caps.list().into_iter().find(|p| {
+                !p.agent_version.is_empty() && p.usage.consensus_msgs > 0 && p.connections == 1
+            }) {
+                return p;
+            }
+        }
+    })
+    .await
+    .expect("peer identified and sending");
+    assert_eq!(peer.connections, 1);
+    assert!(peer.protocol_version.contains("wire="));
+    assert_eq!(peer.wire_version, Some(3));
+    assert!(peer.protocols.iter().any(|p| p == "/ipfs/id/1.0.0"));
+    assert!(!peer.supports_sync && !peer.supports_statesync);
+    assert!(caps.supporting(Capability::Sync).is_empty());
+
+    let listener = bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_listener(
+        listener,
+        RpcState::new(metrics).with_peers(caps),
+    ));
+    let resp = get(addr, "/p2p/peers").await;
+    assert!(resp.starts_with("HTTP/1.1 200"), "{resp}");
+    assert!(resp.contains(&format!("\"peer_id\":\"{}\"", peer.peer_id)));
+    assert!(resp.contains("\"supports_sync\":false"));
+    let resp = get(addr, &format!("/p2p/peers/{}", peer.peer_id)).await;
+    assert!(resp.starts_with("HTTP/1.1 200"), "{resp}");
+    assert!(get(addr, "/p2p/peers/unknown")
+        .await
+        .starts_with("HTTP/1.1 404"));
+}
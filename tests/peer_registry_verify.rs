@@ -2,7 +2,10 @@
 
 use amunchain::{
     core::security::keystore::Keystore,
-    networking::peer_registry::{load_and_verify_peer_registry_now, PeerRegistryPolicy},
+    networking::peer_registry::{
+        load_and_verify_peer_registry_bundle_now, load_and_verify_peer_registry_now,
+        peer_registry_bundle_signing_bytes, PeerRegistryError, PeerRegistryPolicy,
+    },
 };
 use std::fs;
 
@@ -32,7 +35,7 @@ fn peer_registry_loads_and_verifies() {
         issued_at_ms,
         expires_at_ms,
         peer,
-        hex::encode(sig.0)
+        hex::encode(sig.as_bytes())
     );
 
     let path = dir.path().join("peer_registry.toml");
@@ -46,3 +49,61 @@ fn peer_registry_loads_and_verifies() {
         .expect("load and verify");
     assert_eq!(allow, vec![peer.to_string()]);
 }
+
+#[test]
+fn peer_registry_bundle_yields_own_section_only() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let ks = Keystore::open(dir.path().to_str().unwrap()).expect("keystore open");
+    let pk = hex::encode(ks.public_key());
+
+    let main_peer = "12D3KooWPYkNZrwQo5yESaXbBQ64f3GyFaUPFynPUoE7PfJ4xL4u";
+    let test_peer = "12D3KooWLh9S2QyVMgQgHmuzu2tCA6KsL9mpNgnvoiA1SQ9nHnMA";
+    let issued_at_ms: u64 = 1768336425892;
+    let expires_at_ms: u64 = 1768336485892;
+
+    let body = |test_peers: &str| {
+        format!(
+            "version = 1\n\n\
+             [[networks]]\nnetwork = \"amunchain-testnet/consensus/v2\"\n\
+             issued_at_ms = {issued_at_ms}\nexpires_at_ms = {expires_at_ms}\npeers = [{test_peers}]\n\n\
+             [[networks]]\nnetwork = \"amunchain/consensus/v2\"\n\
+             issued_at_ms = {issued_at_ms}\nexpires_at_ms = {expires_at_ms}\npeers = [\"{main_peer}\"]\n"
+        )
+    };
+    let signed = body(&format!("\"{test_peer}\""));
+    let msg = peer_registry_bundle_signing_bytes(&signed).expect("signing bytes");
+    let sig = hex::encode(ks.sign(&msg).expect("sign").as_bytes());
+    // `signature_hex` must precede the `[[networks]]` tables.
+    let with_sig = |b: String| {
+        b.replacen(
+            "version = 1\n",
+            &format!("version = 1\nsignature_hex = \"{sig}\"\n"),
+            1,
+        )
+    };
+
+    let path = dir.path().join("bundle.toml");
+    fs::write(&path, with_sig(signed)).expect("write");
+    let path = path.to_str().unwrap();
+
+    let mut pol = PeerRegistryPolicy::default_with_now(issued_at_ms + 1);
+    pol.expected_network = Some("amunchain/consensus/v2");
+    let allow = load_and_verify_peer_registry_bundle_now(path, &pk, &pol).expect("verify");
+    assert_eq!(allow, vec![main_peer.to_string()]);
+
+    // A network without a section is rejected.
+    pol.expected_network = Some("other/consensus/v2");
+    assert!(matches!(
+        load_and_verify_peer_registry_bundle_now(path, &pk, &pol),
+        Err(PeerRegistryError::NetworkMismatch)
+    ));
+
+    // Editing another network's section invalidates the whole bundle.
+    let tampered = dir.path().join("tampered.toml");
+    fs::write(&tampered, with_sig(body(""))).expect("write");
+    pol.expected_network = Some("amunchain/consensus/v2");
+    assert!(matches!(
+        load_and_verify_peer_registry_bundle_now(tampered.to_str().unwrap(), &pk, &pol),
+        Err(PeerRegistryError::BadSignature)
+    ));
+}
@@ -0,0 +1,68 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::pending::PendingConfig;
+use amunchain::core::types::Height;
+use common::{driver, keypairs, validators, vote_msg};
+
+#[test]
+fn early_votes_are_replayed_when_view_advances() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let (mut d, _) = driver(&kps);
+
+    // Quorum for heights 2 and 3 arrives before height 1 is final.
+    for h in [3, 2] {
+        for kp in kps.iter().take(3) {
+            d.on_peer_msg(b"peer-a", vote_msg(kp, &set, h));
+        }
+    }
+    assert_eq!(d.view(), Height(1));
+    assert_eq!(d.pending_len(), 6);
+
+    for kp in kps.iter().take(3) {
+        d.on_peer_msg(b"peer-b", vote_msg(kp, &set, 1));
+    }
+
+    // Finalizing 1 replays 2, which finalizes and replays 3.
+    assert_eq!(d.view(), Height(4));
+    assert_eq!(d.pending_len(), 0);
+}
+
+#[test]
+fn buffer_enforces_lookahead_and_per_peer_caps() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let (d, _) = driver(&kps);
+    let mut d = d.with_pending(PendingConfig {
+        max_heights_ahead: 2,
+        max_per_peer: 2,
+        max_total: 16,
+    });
+
+    // Beyond view + 2: dropped.
+    d.on_peer_msg(b"peer-a", vote_msg(&kps[0], &set, 4));
+    assert_eq!(d.pending_len(), 0);
+
+    // Per-peer cap of 2.
+    for kp in kps.iter().take(3) {
+        d.on_peer_msg(b"peer-a", vote_msg(kp, &set, 2));
+    }
+    assert_eq!(d.pending_len(), 2);
+
+    // Another peer still has room.
+    d.on_peer_msg(b"peer-b", vote_msg(&kps[2], &set, 2));
+    assert_eq!(d.pending_len(), 3);
+}
@@ -0,0 +1,76 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::networking::addr_book::AddrBook;
+use amunchain::networking::pex::{
+    registry_hash, PexError, PexMessage, MAX_PEX_ADDRS, MAX_PEX_PEERS,
+};
+use libp2p::{Multiaddr, PeerId};
+use std::collections::HashSet;
+
+fn addr(s: &str) -> Multiaddr {
+    s.parse().unwrap()
+}
+
+#[test]
+fn only_approved_peers_are_shared() {
+    let (local, approved, stranger) = (PeerId::random(), PeerId::random(), PeerId::random());
+    let allow: HashSet<PeerId> = [local, approved].into_iter().collect();
+    let book = AddrBook::default();
+    book.add(approved, addr("/ip4/10.0.0.1/tcp/4001"));
+    book.add(stranger, addr("/ip4/10.0.0.2/tcp/4001"));
+    book.add(local, addr("/ip4/10.0.0.3/tcp/4001"));
+
+    assert!(PexMessage::from_book(&book, &HashSet::new(), &local).is_none());
+    let msg = PexMessage::from_book(&book, &allow, &local).unwrap();
+    assert_eq!(msg.registry_hash, registry_hash(&allow));
+    let decoded = PexMessage::decode(&msg.encode().unwrap()).unwrap();
+    assert_eq!(decoded, msg);
+    assert_eq!(
+        decoded.verify(&allow).unwrap(),
+        vec![(approved, addr("/ip4/10.0.0.1/tcp/4001"))]
+    );
+}
+
+#[test]
+fn messages_are_checked_against_the_local_registry() {
+    let (a, b, c) = (PeerId::random(), PeerId::random(), PeerId::random());
+    let allow: HashSet<PeerId> = [a, b].into_iter().collect();
+    let book = AddrBook::default();
+    book.add(b, addr("/ip4/10.0.0.1/tcp/4001"));
+    let msg = PexMessage::from_book(&book, &allow, &a).unwrap();
+
+    // Another registry version is not an offence; an unlisted peer under ours is.
+    let other: HashSet<PeerId> = [a, b, c].into_iter().collect();
+    assert_eq!(msg.verify(&other), Err(PexError::RegistryMismatch));
+    assert_eq!(msg.verify(&HashSet::new()), Err(PexError::NoRegistry));
+    let mut forged = msg.clone();
+    forged.peers[0].peer_id = c.to_base58();
+    assert_eq!(
+        forged.verify(&allow),
+        Err(PexError::NotApproved(c.to_base58()))
+    );
+    assert!(forged.verify(&allow).unwrap_err().is_invalid());
+    assert!(!PexError::RegistryMismatch.is_invalid());
+
+    let mut bloated = msg.clone();
+    bloated.peers[0].addrs = vec!["/ip4/10.0.0.1/tcp/4001".to_string(); MAX_PEX_ADDRS + 1];
+    assert_eq!(bloated.verify(&allow), Err(PexError::TooLarge));
+    let mut crowded = msg.clone();
+    crowded.peers = vec![msg.peers[0].clone(); MAX_PEX_PEERS + 1];
+    assert_eq!(crowded.verify(&allow), Err(PexError::TooLarge));
+    let mut garbled = msg;
+    garbled.peers[0].addrs = vec!["nope".to_string()];
+    assert_eq!(garbled.verify(&allow), Err(PexError::Codec));
+    assert_eq!(PexMessage::decode(b"\xff\x00"), Err(PexError::Codec));
+}
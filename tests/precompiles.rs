@@ -0,0 +1,272 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::runtime::executor::{EvmExecutor, ExecState, TRANSFER_GAS};
+use amunchain::core::runtime::precompiles::{
+    GasSchedule, PrecompileError, PrecompileRegistry, ED25519_VERIFY, MERKLE_VERIFY,
+    PENDING_REWARDS, VOTING_POWER,
+};
+use amunchain::core::runtime::receipt::TxStatus;
+use amunchain::core::state::merkle::{merkle_proof_sorted, merkle_root_sorted, Side};
+use amunchain::core::types::{AccountKey, Signature, Transaction, TxCall};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+
+fn word(v: u128) -> Vec<u8> {
+    let mut out = vec![0u8; 32];
+    out[16..].copy_from_slice(&v.to_be_bytes());
+    out
+}
+
+fn ed25519_input(msg: &[u8], tamper: bool) -> Vec<u8> {
+    let kp = Ed25519KeyPair::from_seed_unchecked(&[7; 32]).unwrap();
+    let mut input = kp.public_key().as_ref().to_vec();
+    input.extend_from_slice(kp.sign(msg).as_ref());
+    input.extend_from_slice(msg);
+    if tamper {
+        *input.last_mut().unwrap() ^= 1;
+    }
+    input
+}
+
+fn merkle_input(index: usize) -> Vec<u8> {
+    let pairs: Vec<(Vec<u8>, Vec<u8>)> = (0u8..5).map(|i| (vec![i], vec![i; 3])).collect();
+    let proof = merkle_proof_sorted(&pairs, index).unwrap();
+    let mut input = merkle_root_sorted(&pairs).to_vec();
+    input.extend_from_slice(&proof.leaf);
+    for item in &proof.path {
+        input.push(match item.side {
+            Side::Left => 0,
+            Side::Right => 1,
+        });
+        input.extend_from_slice(&item.sibling);
+    }
+    input
+}
+
+fn staked() -> ExecState {
+    let mut state = ExecState::default();
+    state
+        .staking
+        .register_validator(b"val".to_vec(), 500, 1_000)
+        .unwrap();
+    state
+        .staking
+        .bond(b"del".to_vec(), b"val".to_vec(), 250)
+        .unwrap();
+    state
+}
+
+#[test]
+fn builtins_compute_expected_outputs() {
+    let r = PrecompileRegistry::default();
+    let state = staked();
+    let call = |to: [u8; 20], input: &[u8]| r.call(&to, input, u64::MAX, &state).map(|o| o.output);
+
+    assert_eq!(
+        call(ED25519_VERIFY, &ed25519_input(b"hi", false)),
+        Ok(word(1))
+    );
+    assert_eq!(
+        call(ED25519_VERIFY, &ed25519_input(b"hi", true)),
+        Ok(word(0))
+    );
+    assert_eq!(
+        call(ED25519_VERIFY, &[0; 95]),
+        Err(PrecompileError::BadInput)
+    );
+
+    let good = merkle_input(3);
+    assert_eq!(call(MERKLE_VERIFY, &good), Ok(word(1)));
+    let mut bad = good.clone();
+    bad[40] ^= 1;
+    assert_eq!(call(MERKLE_VERIFY, &bad), Ok(word(0)));
+    assert_eq!(
+        call(MERKLE_VERIFY, &good[..good.len() - 1]),
+        Err(PrecompileError::BadInput)
+    );
+
+    assert_eq!(
+        call(VOTING_POWER, b"val"),
+        Ok(word(state.staking.voting_power(b"val")))
+    );
+    assert_eq!(call(VOTING_POWER, b"nobody"), Ok(word(0)));
+    let mut input = 3u32.to_be_bytes().to_vec();
+    input.extend_from_slice(b"delval");
+    assert_eq!(
+        call(PENDING_REWARDS, &input),
+        Ok(word(state.staking.pending_rewards(b"del", b"val")))
+    );
+    assert_eq!(
+        call(PENDING_REWARDS, &9u32.to_be_bytes()),
+        Err(PrecompileError::BadInput)
+    );
+    assert_eq!(
+        r.call(&[0; 20], &[], u64::MAX, &state),
+        Err(PrecompileError::Unknown)
+    );
+}
+
+#[test]
+fn gas_comes_from_the_schedule() {
+    let s = GasSchedule::DEFAULT;
+    let r = PrecompileRegistry::with_defaults(s);
+    let input = ed25519_input(b"hello", false);
+    let words = (input.len() as u64).div_ceil(32);
+    let expected = s.call_base + s.per_input_word * words + s.ed25519_verify;
+    assert_eq!(r.gas(&ED25519_VERIFY, &input), Ok(expected));
+
+    let items = (merkle_input(0).len() as u64 - 64) / 33;
+    assert!(items > 0);
+    let input = merkle_input(0);
+    assert_eq!(
+        r.gas(&MERKLE_VERIFY, &input),
+        Ok(s.call_base
+            + s.per_input_word * (input.len() as u64).div_ceil(32)
+            + s.merkle_per_item * items)
+    );
+
+    let state = ExecState::default();
+    let input = ed25519_input(b"hello", false);
+    assert_eq!(
+        r.call(&ED25519_VERIFY, &input, expected - 1, &state),
+        Err(PrecompileError::OutOfGas)
+    );
+    assert_eq!(
+        r.call(&ED25519_VERIFY, &input, expected, &state)
+            .unwrap()
+            .gas_used,
+        expected
+    );
+
+    let pricier = PrecompileRegistry::with_defaults(GasSchedule {
+        ed25519_verify: s.ed25519_verify * 2,
+        ..s
+    });
+    assert_eq!(
+        pricier.gas(&ED25519_VERIFY, &input),
+        Ok(expected + s.ed25519_verify)
+    );
+}
+
+#[test]
+fn registry_is_configurable() {
+    let mut r = PrecompileRegistry::default();
+    assert_eq!(r.addresses().count(), 4);
+    assert!(r.remove(&VOTING_POWER));
+    assert!(!r.remove(&VOTING_POWER));
+    assert!(r.get(&VOTING_POWER).is_none());
+    assert!(r.get(&VOTING_POWER[..19]).is_none());
+
+    let empty = PrecompileRegistry::new(GasSchedule::DEFAULT);
+    assert_eq!(empty.addresses().count(), 0);
+    let merkle = PrecompileRegistry::default()
+        .get(&MERKLE_VERIFY)
+        .unwrap()
+        .clone();
+    assert_eq!(
+        r.register(MERKLE_VERIFY, merkle.clone()),
+        Err(PrecompileError::Duplicate)
+    );
+    r.register(VOTING_POWER, merkle).unwrap();
+    assert_eq!(r.get(&VOTING_POWER).unwrap().name(), "merkle_verify");
+}
+
+fn call_tx(nonce: u64, to: [u8; 20], value: u128, input: Vec<u8>, gas_limit: u64) -> Transaction {
+    Transaction {
+        chain_id: "amun-testnet".to_string(),
+        sender: AccountKey::from_bytes([0xa1; 32]),
+        nonce,
+        call: TxCall::Call {
+            to: to.to_vec(),
+            value,
+            input,
+        },
+        gas_limit,
+        max_fee_per_gas: 1,
+        max_priority_fee_per_gas: 0,
+        signature: Signature::from_bytes([0; 64]),
+    }
+}
+
+#[test]
+fn executor_runs_precompiles() {
+    let exec = EvmExecutor::new();
+    let mut state = staked();
+    let cost = exec.precompiles().gas(&VOTING_POWER, b"val").unwrap();
+    let txs = [
+        call_tx(0, VOTING_POWER, 0, b"val".to_vec(), TRANSFER_GAS + cost),
+        call_tx(1, VOTING_POWER, 0, b"val".to_vec(), TRANSFER_GAS + cost - 1),
+        call_tx(2, VOTING_POWER, 1, b"val".to_vec(), 100_000),
+        call_tx(3, PENDING_REWARDS, 0, vec![0xff; 4], 100_000),
+    ];
+    let out = exec.execute_block(&mut state, &txs).unwrap();
+    let got: Vec<(TxStatus, u64)> = out
+        .receipts
+        .iter()
+        .map(|r| (r.status, r.gas_used))
+        .collect();
+    assert_eq!(
+        got,
+        vec![
+            (TxStatus::Success, TRANSFER_GAS + cost),
+            (TxStatus::OutOfGas, TRANSFER_GAS + cost - 1),
+            // Value sent to a precompile: reverted like any unknown call.
+            (TxStatus::Reverted, TRANSFER_GAS),
+            (TxStatus::Reverted, 100_000),
+        ]
+    );
+    assert_eq!(out.receipts[0].output, word(1_250));
+    assert!(out.receipts[1].output.is_empty());
+
+    // Without the precompile the call reverts.
+    let mut r = PrecompileRegistry::default();
+    r.remove(&VOTING_POWER);
+    let exec = EvmExecutor::new().with_precompiles(r);
+    let mut state = staked();
+    let receipt = exec.execute(&mut state, &txs[0]).unwrap();
+    assert_eq!(receipt.status, TxStatus::Reverted);
+}
+
+#[test]
+fn execution_is_deterministic_across_nodes() {
+    let txs: Vec<Transaction> = [
+        (ED25519_VERIFY, ed25519_input(b"block", false)),
+        (ED25519_VERIFY, ed25519_input(b"block", true)),
+        (MERKLE_VERIFY, merkle_input(1)),
+        (VOTING_POWER, b"val".to_vec()),
+        (
+            PENDING_REWARDS,
+            [&3u32.to_be_bytes()[..], b"delval"].concat(),
+        ),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(n, (to, input))| call_tx(n as u64, to, 0, input, 1_000_000))
+    .collect();
+
+    // Two independently built nodes.
+    let run = || {
+        let exec = EvmExecutor::new()
+            .with_precompiles(PrecompileRegistry::with_defaults(GasSchedule::DEFAULT));
+        let mut state = staked();
+        let out = exec.execute_block(&mut state, &txs).unwrap();
+        (out, state)
+    };
+    let (a, state_a) = run();
+    let (b, state_b) = run();
+    assert_eq!(a, b);
+    assert_eq!(state_a, state_b);
+    assert!(a.receipts.iter().all(|r| r.status == TxStatus::Success));
+    assert_eq!(a.receipts[0].output, word(1));
+    assert_eq!(a.receipts[1].output, word(0));
+}
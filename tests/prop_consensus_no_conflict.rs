@@ -12,7 +12,7 @@
 #![forbid(unsafe_code)]
 
 use amunchain::core::consensus::tide::{NoopSlashing, TideConfig, TideFinalizer};
-use amunchain::core::types::{Commit, Signature, ValidatorId, Vote, H256};
+use amunchain::core::types::{Commit, Epoch, Height, Round, Signature, ValidatorId, Vote, H256};
 use proptest::prelude::*;
 use std::collections::BTreeSet;
 
@@ -21,13 +21,13 @@ fn make_validators(n: usize) -> BTreeSet<ValidatorId> {
     for i in 0..n {
         let mut b = [0u8; 32];
         b[0] = i as u8;
-        s.insert(ValidatorId(b.to_vec()));
+        s.insert(ValidatorId::from_bytes(b));
     }
     s
 }
 
 fn dummy_sig() -> Signature {
-    Signature(vec![0u8; 64])
+    Signature::from_bytes([0u8; 64])
 }
 
 fn arb_hash() -> impl Strategy<Value = H256> {
@@ -53,9 +53,9 @@ proptest! {
         for v in validators.iter().take(5) {
 
 let vote = Vote {
-            height,
-            round,
-            epoch: 0,
+            height: Height(height),
+            round: Round(round),
+            epoch: Epoch::ZERO,
             msg_counter: 0,
             sent_ts_ms: 0,
             ttl_ms: 0,
@@ -71,9 +71,9 @@ let vote = Vote {
         for v in validators.iter().skip(5) {
 
 let vote = Vote {
-            height,
-            round,
-            epoch: 0,
+            height: Height(height),
+            round: Round(round),
+            epoch: Epoch::ZERO,
             msg_counter: 0,
             sent_ts_ms: 0,
             ttl_ms: 0,
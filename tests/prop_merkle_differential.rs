@@ -0,0 +1,32 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+//! Run with `cargo test --features testing --test prop_merkle_differential`.
+
+use amunchain::core::state::persistent_state::PersistentState;
+use amunchain::core::state::testing::{arb_batches, check_against_rebuild};
+use proptest::prelude::*;
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn prop_state_root_matches_full_rebuild(batches in arb_batches(8, 16)) {
+        let dir = tempfile::tempdir().unwrap();
+        let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+        check_against_rebuild(&batches, |batch| {
+            st.commit_atomic(batch.to_vec()).unwrap();
+            st.state_root().unwrap()
+        })?;
+    }
+}
@@ -11,7 +11,7 @@ proptest! {
     #[test]
     fn merkle_proof_verifies_for_any_nonempty_set(mut pairs in proptest::collection::vec((any::<u64>(), any::<[u8;32]>()), 1..64)) {
         // Canonical ordering requirement
-        pairs.sort_by(|a,b| a.0.cmp(&b.0));
+        pairs.sort_by_key(|a| a.0);
 
         let kv_pairs: Vec<(Vec<u8>, Vec<u8>)> = pairs.iter().map(|(k,v)| (k.to_be_bytes().to_vec(), v.to_vec())).collect();
 
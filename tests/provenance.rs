@@ -0,0 +1,190 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use amunchain::config::ConfigLoader;
+use amunchain::core::security::sign_policy::SignDomain;
+use amunchain::core::types::{Signature, ValidatorId};
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::node::builder::NodeBuilder;
+use amunchain::node::extensions::GossipHandler;
+use amunchain::node::provenance::{
+    config_digest, provenance_signing_bytes, Provenance, ProvenanceBook, ProvenanceError,
+    ProvenanceRecord,
+};
+use amunchain::rpc::server::{bind, serve_listener, RpcState};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+const CHAIN: &str = "amunchain-test";
+
+fn keypair() -> Ed25519KeyPair {
+    let rng = ring::rand::SystemRandom::new();
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+    Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+}
+
+fn validator(kp: &Ed25519KeyPair) -> ValidatorId {
+    ValidatorId::from_slice(kp.public_key().as_ref()).unwrap()
+}
+
+fn record(issued_at_ms: u64) -> ProvenanceRecord {
+    let mut r = ProvenanceRecord::local(CHAIN, "peer", "c0ffee".into(), [7; 32], 12);
+    r.issued_at_ms = issued_at_ms;
+    r
+}
+
+fn signed(kp: &Ed25519KeyPair, record: ProvenanceRecord) -> Provenance {
+    Provenance::sign(record, validator(kp), |m| {
+        Ok(Signature::from_slice(kp.sign(m).as_ref()).unwrap())
+    })
+    .unwrap()
+}
+
+fn book(kps: &[&Ed25519KeyPair]) -> ProvenanceBook {
+    let set: BTreeSet<ValidatorId> = kps.iter().map(|kp| validator(kp)).collect();
+    ProvenanceBook::new(CHAIN, set).unwrap()
+}
+
+#[test]
+fn records_describe_this_binary_and_verify() {
+    let r = record(1);
+    assert_eq!(r.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(r.state_root, hex::encode([7u8; 32]));
+    assert_eq!(r.build_hash.len(), 64);
+    let msg = provenance_signing_bytes(&r).unwrap();
+    assert_eq!(SignDomain::of(&msg), Some(SignDomain::Provenance));
+
+    let kp = keypair();
+    let p = signed(&kp, r.clone());
+    assert_eq!(Provenance::decode(&p.encode().unwrap()).unwrap(), p);
+    assert_eq!(p.verify().unwrap(), &validator(&kp));
+
+    let mut tampered = p.clone();
+    tampered.record.build_hash = "00".repeat(32);
+    assert_eq!(tampered.verify(), Err(ProvenanceError::BadSignature));
+    assert_eq!(
+        Provenance::unsigned(r).verify(),
+        Err(ProvenanceError::Unsigned)
+    );
+}
+
+#[test]
+fn config_digest_ignores_node_local_settings() {
+    let a = ConfigLoader::new().load().unwrap();
+    let mut b = a.clone();
+    b.node.name = "other".into();
+    b.node.data_dir = "/elsewhere".into();
+    b.p2p.listen_addr = "/ip4/0.0.0.0/tcp/1".into();
+    assert_eq!(config_digest(&a, CHAIN), config_digest(&b, CHAIN));
+
+    b.consensus.block_gas_limit += 1;
+    assert_ne!(config_digest(&a, CHAIN), config_digest(&b, CHAIN));
+    assert_ne!(config_digest(&a, CHAIN), config_digest(&a, "other-chain"));
+}
+
+#[test]
+fn book_keeps_the_newest_record_of_active_validators() {
+    let (kp, outsider) = (keypair(), keypair());
+    let book = book(&[&kp]);
+
+    assert_eq!(
+        book.observe(&signed(&outsider, record(1))),
+        Err(ProvenanceError::NotValidator)
+    );
+    assert_eq!(
+        book.observe(&Provenance::unsigned(record(1))),
+        Err(ProvenanceError::Unsigned)
+    );
+    let mut foreign = record(1);
+    foreign.chain_id = "other-chain".into();
+    assert!(matches!(
+        book.observe(&signed(&kp, foreign)),
+        Err(ProvenanceError::WrongChain(_))
+    ));
+
+    book.observe(&signed(&kp, record(5))).unwrap();
+    assert_eq!(
+        book.observe(&signed(&kp, record(4))),
+        Err(ProvenanceError::Stale)
+    );
+    assert_eq!(book.get(&validator(&kp)).unwrap().record.issued_at_ms, 5);
+
+    book.set_validators(BTreeSet::new());
+    assert!(book.get(&validator(&kp)).is_none());
+}
+
+#[test]
+fn report_flags_divergent_builds_and_configs() {
+    let (same, drifted) = (keypair(), keypair());
+    let book = book(&[&same, &drifted]);
+    book.set_local(Provenance::unsigned(record(1)));
+
+    book.observe(&signed(&same, record(2))).unwrap();
+    let mut r = record(2);
+    r.git_sha = "deadbeef".into();
+    r.config_digest = "feed".into();
+    book.observe(&signed(&drifted, r)).unwrap();
+
+    book.on_message(b"peer", &[0xff; 64]);
+
+    let report = book.report();
+    assert!(report.local.is_some());
+    let differs = |kp: &Ed25519KeyPair| {
+        let id = hex::encode(validator(kp).as_bytes());
+        report
+            .peers
+            .iter()
+            .find(|p| p.validator == id)
+            .unwrap()
+            .differs
+            .clone()
+    };
+    assert!(differs(&same).is_empty());
+    assert_eq!(differs(&drifted), vec!["git_sha", "config_digest"]);
+}
+
+#[tokio::test]
+async fn report_is_served_under_ext_provenance() {
+    let kp = keypair();
+    let book = book(&[&kp]);
+    book.set_local(Provenance::unsigned(record(1)));
+    book.on_message(b"peer", &signed(&kp, record(2)).encode().unwrap());
+
+    let ext = NodeBuilder::new().extension(book).build().unwrap();
+    let metrics = Arc::new(Metrics::new().unwrap());
+    ext.register_metrics(&metrics).unwrap();
+    let listener = bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_listener(
+        listener,
+        RpcState::new(metrics).with_extensions(ext),
+    ));
+
+    let mut s = tokio::net::TcpStream::connect(addr).await.unwrap();
+    s.write_all(b"GET /ext/provenance HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n")
+        .await
+        .unwrap();
+    let mut resp = String::new();
+    s.read_to_string(&mut resp).await.unwrap();
+    assert!(resp.starts_with("HTTP/1.1 200"), "{resp}");
+    let body: serde_json::Value =
+        serde_json::from_str(resp.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+    assert_eq!(body["local"]["record"]["height"], 12);
+    assert_eq!(
+        body["peers"][0]["validator"],
+        hex::encode(validator(&kp).as_bytes())
+    );
+}
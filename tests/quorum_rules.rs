@@ -0,0 +1,178 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use amunchain::core::consensus::quorum::{QuorumConfig, QuorumError, QuorumRule};
+use amunchain::core::consensus::signing::SigningDomain;
+use amunchain::core::consensus::tide::{
+    verify_commit_certificate_for_chain, verify_commit_certificate_with_rule, NoopSlashing,
+    TideConfig, TideError, TideFinalizer, DEFAULT_CHAIN_ID,
+};
+use amunchain::core::types::{ConsensusConfig, ValidatorId, Vote, H256};
+use common::{id, validators};
+use proptest::prelude::*;
+use ring::signature::Ed25519KeyPair;
+
+fn keypairs(n: usize) -> Vec<Ed25519KeyPair> {
+    (0..n)
+        .map(|i| Ed25519KeyPair::from_seed_unchecked(&[i as u8 + 1; 32]).unwrap())
+        .collect()
+}
+
+fn vote(kp: &Ed25519KeyPair, set: &BTreeSet<ValidatorId>, block: u8) -> Vote {
+    common::vote(kp, set, 1, H256::from_bytes([block; 32]))
+}
+
+fn rule() -> impl Strategy<Value = QuorumConfig> {
+    prop_oneof![
+        Just(QuorumConfig::TwoThirds),
+        Just(QuorumConfig::All),
+        (1u64..1000, 1u64..1000).prop_filter_map("valid fraction", |(a, b)| {
+            let rule = QuorumConfig::Fraction {
+                numerator: a.min(b),
+                denominator: a.max(b),
+            };
+            rule.validate().is_ok().then_some(rule)
+        }),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn any_two_quorums_share_voting_power(
+        rule in rule(),
+        power in proptest::collection::vec(1u128..1_000, 1..16),
+        a in any::<u16>(),
+        b in any::<u16>(),
+    ) {
+        let total: u128 = power.iter().sum();
+        let weigh = |set: u16| -> u128 {
+            power.iter().enumerate().filter(|(i, _)| set & (1 << i) != 0).map(|(_, p)| p).sum()
+        };
+        if rule.reached(weigh(a), total) && rule.reached(weigh(b), total) {
+            prop_assert!(weigh(a & b) > 0, "{rule:?}: disjoint quorums {a:#x} {b:#x}");
+        }
+    }
+
+    #[test]
+    fn more_power_never_loses_a_quorum(
+        rule in rule(),
+        total in 1u128..u128::from(u64::MAX),
+        signed in any::<u64>(),
+        extra in any::<u64>(),
+    ) {
+        let signed = u128::from(signed) % (total + 1);
+        let more = (signed + u128::from(extra)).min(total);
+        prop_assert!(!rule.reached(signed, total) || rule.reached(more, total));
+        prop_assert!(rule.reached(total, total));
+        prop_assert!(!rule.reached(total / 2, total));
+    }
+}
+
+proptest! {
+    // Every case signs and verifies real votes.
+    #![proptest_config(ProptestConfig::with_cases(24))]
+
+    #[test]
+    fn split_votes_never_commit_two_blocks(
+        rule in rule(),
+        choices in proptest::collection::vec(0u8..3, 7),
+    ) {
+        let kps = keypairs(7);
+        let validators = validators(&kps);
+        let cfg = TideConfig::new(validators.clone()).with_quorum(Arc::new(rule));
+        let mut tide = TideFinalizer::new(cfg, NoopSlashing);
+        let mut committed = BTreeSet::new();
+        // 0 abstains; honest validators vote once, for block 1 or 2.
+        for (kp, choice) in kps.iter().zip(choices) {
+            if choice == 0 {
+                continue;
+            }
+            if let Ok(Some(commit)) = tide.process_vote_verified(vote(kp, &validators, choice)) {
+                committed.insert(commit.block_hash);
+            }
+        }
+        prop_assert!(committed.len() <= 1);
+    }
+}
+
+#[test]
+fn all_sign_rule_needs_every_validator() {
+    let kps = keypairs(4);
+    let validators = validators(&kps);
+    let rule = QuorumConfig::All;
+    let cfg = TideConfig::new(validators.clone()).with_quorum(Arc::new(rule));
+    let mut tide = TideFinalizer::new(cfg, NoopSlashing);
+    for kp in &kps[..3] {
+        assert!(tide
+            .process_vote_verified(vote(kp, &validators, 1))
+            .unwrap()
+            .is_none());
+    }
+    let commit = tide
+        .process_vote_verified(vote(&kps[3], &validators, 1))
+        .unwrap()
+        .expect("all signed");
+    let domain = SigningDomain {
+        chain_id: DEFAULT_CHAIN_ID,
+        legacy_until_height: None,
+    };
+    verify_commit_certificate_with_rule(&commit, &validators, None, domain, &rule).unwrap();
+
+    // 3 of 4 is a two-thirds commit but not an all-sign one.
+    let mut partial = commit.clone();
+    partial.signatures.remove(&id(&kps[0]));
+    partial.voting_power = 0;
+    verify_commit_certificate_for_chain(&partial, &validators, None, domain).unwrap();
+    assert!(matches!(
+        verify_commit_certificate_with_rule(&partial, &validators, None, domain, &rule),
+        Err(TideError::NotEnoughVotes)
+    ));
+}
+
+#[test]
+fn rules_come_from_the_consensus_config() {
+    let cfg: ConsensusConfig = toml::from_str("validators_hex = []").unwrap();
+    assert_eq!(cfg.quorum, QuorumConfig::TwoThirds);
+    let cfg: ConsensusConfig = toml::from_str(
+        "validators_hex = []\n[quorum]\nrule = \"fraction\"\nnumerator = 3\ndenominator = 4\n",
+    )
+    .unwrap();
+    assert_eq!(
+        cfg.quorum,
+        QuorumConfig::Fraction {
+            numerator: 3,
+            denominator: 4
+        }
+    );
+    assert!(cfg.quorum.reached(76, 100) && !cfg.quorum.reached(75, 100));
+
+    let fraction = |numerator, denominator| QuorumConfig::Fraction {
+        numerator,
+        denominator,
+    };
+    assert_eq!(fraction(1, 2).validate(), Ok(()));
+    assert!(matches!(
+        fraction(1, 3).validate(),
+        Err(QuorumError::BelowHalf { .. })
+    ));
+    assert!(matches!(
+        fraction(5, 5).validate(),
+        Err(QuorumError::Unreachable { .. })
+    ));
+    assert_eq!(fraction(0, 0).validate(), Err(QuorumError::ZeroDenominator));
+}
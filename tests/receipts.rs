@@ -0,0 +1,230 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::runtime::executor::{
+    account_topic, transfer_topic, EvmExecutor, ExecError, ExecState, BANK_LOG_ADDRESS,
+    TRANSFER_GAS,
+};
+use amunchain::core::runtime::receipt::{LogsBloom, TxStatus};
+use amunchain::core::runtime::tx::TxError;
+use amunchain::core::state::persistent_state::PersistentState;
+use amunchain::core::state::receipt_store::ReceiptStore;
+use amunchain::core::types::{AccountKey, Height, Signature, Transaction, TxCall, H256};
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::rpc::server::{bind, serve_listener, RpcState};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+const ALICE: [u8; 32] = [0xa1; 32];
+
+fn tx(nonce: u64, call: TxCall) -> Transaction {
+    Transaction {
+        chain_id: "amun-testnet".to_string(),
+        sender: AccountKey::from_bytes(ALICE),
+        nonce,
+        call,
+        gas_limit: TRANSFER_GAS,
+        max_fee_per_gas: 1,
+        max_priority_fee_per_gas: 0,
+        signature: Signature::from_bytes([0; 64]),
+    }
+}
+
+fn pay(to: &[u8], amount: u128) -> TxCall {
+    TxCall::Transfer {
+        to: to.to_vec(),
+        amount,
+    }
+}
+
+fn funded() -> ExecState {
+    let mut state = ExecState::default();
+    state.bank.mint(&ALICE, 100).unwrap();
+    state
+}
+
+#[test]
+fn every_included_transaction_gets_a_receipt() {
+    let mut state = funded();
+    let txs = vec![
+        tx(0, pay(b"bob", 60)),
+        // Insufficient funds: reverted, but included and its nonce used.
+        tx(1, pay(b"bob", 60)),
+        // No EVM yet: contract calls revert.
+        tx(
+            2,
+            TxCall::Call {
+                to: b"contract".to_vec(),
+                value: 0,
+                input: vec![1, 2, 3],
+            },
+        ),
+    ];
+    let out = EvmExecutor::new().execute_block(&mut state, &txs).unwrap();
+
+    let statuses: Vec<TxStatus> = out.receipts.iter().map(|r| r.status).collect();
+    assert_eq!(
+        statuses,
+        vec![TxStatus::Success, TxStatus::Reverted, TxStatus::Reverted]
+    );
+    for (r, t) in out.receipts.iter().zip(&txs) {
+        assert_eq!(r.tx_hash, t.hash().unwrap());
+    }
+    assert_eq!(out.gas_used, 3 * TRANSFER_GAS);
+    assert_eq!(state.bank.balance(b"bob"), 60);
+    assert_eq!(state.nonces.next(&ALICE), 3);
+
+    let log = &out.receipts[0].logs[0];
+    assert_eq!(log.address, BANK_LOG_ADDRESS.to_vec());
+    assert_eq!(
+        log.topics,
+        vec![
+            transfer_topic(),
+            account_topic(&ALICE),
+            account_topic(b"bob")
+        ]
+    );
+    assert_eq!(log.data, 60u128.to_be_bytes().to_vec());
+    assert!(out.receipts[1].logs.is_empty());
+}
+
+#[test]
+fn unincludable_transactions_fail_the_block() {
+    let exec = EvmExecutor::new();
+    let mut state = funded();
+    assert_eq!(
+        exec.execute(&mut state, &tx(1, pay(b"bob", 1))),
+        Err(ExecError::Tx(TxError::NonceGap {
+            expected: 0,
+            got: 1
+        }))
+    );
+    let mut cheap = tx(0, pay(b"bob", 1));
+    cheap.gas_limit = TRANSFER_GAS - 1;
+    assert_eq!(
+        exec.execute(&mut state, &cheap),
+        Err(ExecError::IntrinsicGas)
+    );
+    assert_eq!(state, funded());
+}
+
+#[test]
+fn bloom_has_no_false_negatives() {
+    let mut state = funded();
+    let out = EvmExecutor::new()
+        .execute_block(&mut state, &[tx(0, pay(b"bob", 1))])
+        .unwrap();
+    for input in [
+        BANK_LOG_ADDRESS,
+        transfer_topic().as_bytes(),
+        account_topic(b"bob").as_bytes(),
+    ] {
+        assert!(out.bloom.may_contain(input));
+    }
+    assert!(!out.bloom.may_contain(account_topic(b"carol").as_bytes()));
+    assert!(LogsBloom::default().is_empty());
+
+    let mut union = LogsBloom::default();
+    union.union(&out.bloom);
+    assert_eq!(union, out.bloom);
+}
+
+#[test]
+fn receipts_are_stored_by_tx_hash() {
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    let store = ReceiptStore::open(&st).unwrap();
+    let exec = EvmExecutor::new();
+    let mut state = funded();
+
+    let b1 = exec
+        .execute_block(
+            &mut state,
+            &[tx(0, pay(b"bob", 1)), tx(1, pay(b"carol", 1))],
+        )
+        .unwrap();
+    let b2 = exec
+        .execute_block(&mut state, &[tx(2, pay(b"bob", 1_000))])
+        .unwrap();
+    store
+        .put_block(Height(1), H256::from_bytes([1; 32]), &b1.receipts)
+        .unwrap();
+    store
+        .put_block(Height(2), H256::from_bytes([2; 32]), &b2.receipts)
+        .unwrap();
+
+    let got = store.get(&b1.receipts[1].tx_hash).unwrap().unwrap();
+    assert_eq!(got.receipt, b1.receipts[1]);
+    assert_eq!((got.height, got.index), (Height(1), 1));
+    assert_eq!(got.block_hash, H256::from_bytes([1; 32]));
+    assert_eq!(store.get(&H256::ZERO).unwrap(), None);
+
+    assert_eq!(store.bloom(Height(1)).unwrap(), Some(b1.bloom));
+    // Block 2's only transfer reverted: no logs.
+    assert!(store.bloom(Height(2)).unwrap().unwrap().is_empty());
+    assert_eq!(
+        store
+            .blocks_matching(Height(0), Height(9), account_topic(b"carol").as_bytes())
+            .unwrap(),
+        vec![Height(1)]
+    );
+}
+
+async fn get(addr: std::net::SocketAddr, path: &str) -> (u16, String) {
+    let mut s = tokio::net::TcpStream::connect(addr).await.unwrap();
+    s.write_all(format!("GET {path} HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n").as_bytes())
+        .await
+        .unwrap();
+    let mut resp = String::new();
+    s.read_to_string(&mut resp).await.unwrap();
+    let code = resp[9..12].parse().unwrap();
+    (
+        code,
+        resp.split("\r\n\r\n").nth(1).unwrap_or("").to_string(),
+    )
+}
+
+#[tokio::test]
+async fn receipt_rpc() {
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    let store = ReceiptStore::open(&st).unwrap();
+    let mut state = funded();
+    let out = EvmExecutor::new()
+        .execute_block(&mut state, &[tx(0, pay(b"bob", 1))])
+        .unwrap();
+    store
+        .put_block(Height(4), H256::from_bytes([4; 32]), &out.receipts)
+        .unwrap();
+
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let listener = bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_listener(
+        listener,
+        RpcState::new(metrics).with_receipts(store),
+    ));
+
+    let hash = hex::encode(out.receipts[0].tx_hash.as_bytes());
+    let (code, body) = get(addr, &format!("/chain/receipt/0x{hash}")).await;
+    assert_eq!(code, 200, "{body}");
+    let v: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(v["height"], 4);
+    assert_eq!(v["receipt"]["status"], "Success");
+    assert_eq!(v["receipt"]["gas_used"], TRANSFER_GAS);
+
+    let (code, _) = get(addr, &format!("/chain/receipt/{}", "00".repeat(32))).await;
+    assert_eq!(code, 404);
+    let (code, _) = get(addr, "/chain/receipt/xyz").await;
+    assert_eq!(code, 400);
+}
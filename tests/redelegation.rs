@@ -0,0 +1,105 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::economics::staking::{StakingError, StakingLedger, StakingParams};
+use amunchain::core::state::persistent_state::PersistentState;
+
+fn ledger() -> StakingLedger {
+    let mut l = StakingLedger::with_params(StakingParams {
+        unbonding_period_secs: 100,
+        ..StakingParams::default()
+    })
+    .unwrap();
+    for v in [b"a", b"b", b"c"] {
+        l.register_validator(v.to_vec(), 0, 0).unwrap();
+    }
+    l.bond(b"d".to_vec(), b"a".to_vec(), 1_000).unwrap();
+    l
+}
+
+fn stake(l: &StakingLedger, v: &[u8]) -> u128 {
+    l.delegations
+        .get(&(b"d".to_vec(), v.to_vec()))
+        .map_or(0, |d| d.amount)
+}
+
+#[test]
+fn redelegate_moves_stake() {
+    let mut l = ledger();
+    l.redelegate(b"d".to_vec(), b"a".to_vec(), b"b".to_vec(), 400, 1_000, 10)
+        .unwrap();
+    assert_eq!(stake(&l, b"a"), 600);
+    assert_eq!(stake(&l, b"b"), 400);
+    assert_eq!(l.pools[b"b".as_slice()].total_delegated, 400);
+
+    assert!(matches!(
+        l.redelegate(b"d".to_vec(), b"a".to_vec(), b"b".to_vec(), 601, 1_000, 10),
+        Err(StakingError::InsufficientStake)
+    ));
+    assert!(l
+        .redelegate(b"d".to_vec(), b"a".to_vec(), b"b".to_vec(), 0, 1_000, 10)
+        .is_err());
+}
+
+#[test]
+fn redelegated_stake_is_slashed_for_source_offense() {
+    let mut l = ledger();
+    l.redelegate(b"d".to_vec(), b"a".to_vec(), b"b".to_vec(), 400, 1_000, 10)
+        .unwrap();
+
+    // Offense at height 9, before the redelegation: the moved stake answers for it.
+    let slashed = l.slash_validator(b"a", 1_000, 9);
+    assert_eq!(slashed, 60 + 40);
+    assert_eq!(stake(&l, b"a"), 540);
+    assert_eq!(stake(&l, b"b"), 360);
+    assert_eq!(l.pools[b"b".as_slice()].total_delegated, 360);
+
+    // Offense after the redelegation: only stake still at the source is hit.
+    let slashed = l.slash_validator(b"a", 1_000, 11);
+    assert_eq!(slashed, 54);
+    assert_eq!(stake(&l, b"b"), 360);
+}
+
+#[test]
+fn hopping_is_blocked_until_maturity() {
+    let mut l = ledger();
+    l.redelegate(b"d".to_vec(), b"a".to_vec(), b"b".to_vec(), 400, 1_000, 10)
+        .unwrap();
+    assert!(matches!(
+        l.redelegate(b"d".to_vec(), b"b".to_vec(), b"c".to_vec(), 100, 1_050, 11),
+        Err(StakingError::RedelegationInProgress)
+    ));
+    // Moving stake that did not arrive by redelegation is fine.
+    l.redelegate(b"d".to_vec(), b"a".to_vec(), b"c".to_vec(), 100, 1_050, 11)
+        .unwrap();
+
+    // Once the entry matures it is no longer slashable and the stake may move again.
+    l.redelegate(b"d".to_vec(), b"b".to_vec(), b"c".to_vec(), 100, 1_100, 12)
+        .unwrap();
+    l.prune_redelegations(1_100);
+    assert!(!l
+        .redelegations
+        .contains_key(&(b"d".to_vec(), b"a".to_vec(), b"b".to_vec())));
+    assert_eq!(stake(&l, b"c"), 200);
+}
+
+#[test]
+fn redelegations_persist() {
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    let mut l = ledger();
+    l.redelegate(b"d".to_vec(), b"a".to_vec(), b"b".to_vec(), 400, 1_000, 10)
+        .unwrap();
+    l.commit(&st).unwrap();
+    assert_eq!(StakingLedger::load(&st).unwrap(), l);
+}
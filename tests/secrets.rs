@@ -0,0 +1,112 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::security::keystore::{self, passphrase_from, Keystore, KeystoreError};
+use amunchain::core::security::secrets::{
+    read_secret_path, DirProvider, EnvProvider, SecretChain, SecretError, SecretProvider,
+};
+
+// Each test uses its own variable names; the test binary runs them in parallel.
+
+#[test]
+fn env_value_wins_over_its_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("token");
+    std::fs::write(&file, "from-file\n").unwrap();
+
+    std::env::set_var("AMUN_TEST_SECRET_A_FILE", &file);
+    assert_eq!(
+        EnvProvider.get("AMUN_TEST_SECRET_A").unwrap().as_deref(),
+        Some("from-file")
+    );
+    std::env::set_var("AMUN_TEST_SECRET_A", "from-env");
+    assert_eq!(
+        EnvProvider.get("AMUN_TEST_SECRET_A").unwrap().as_deref(),
+        Some("from-env")
+    );
+
+    std::env::set_var("AMUN_TEST_SECRET_B_FILE", dir.path().join("missing"));
+    assert!(matches!(
+        EnvProvider.get("AMUN_TEST_SECRET_B"),
+        Err(SecretError::Read { .. })
+    ));
+    assert_eq!(EnvProvider.get("AMUN_TEST_SECRET_UNSET").unwrap(), None);
+}
+
+#[test]
+fn secret_files_lose_one_trailing_newline() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("s");
+    for (raw, want) in [
+        ("pw\n", Some("pw")),
+        ("pw\r\n", Some("pw")),
+        ("pw\n\n", Some("pw\n")),
+        (" pw ", Some(" pw ")),
+        ("\n", None),
+    ] {
+        std::fs::write(&path, raw).unwrap();
+        assert_eq!(read_secret_path(&path).unwrap().as_deref(), want, "{raw:?}");
+    }
+}
+
+#[test]
+fn directory_provider_and_chain_order() {
+    let (first, second) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    std::fs::write(first.path().join("AMUN_ADMIN_TOKEN"), "one").unwrap();
+    std::fs::write(second.path().join("AMUN_ADMIN_TOKEN"), "two").unwrap();
+    std::fs::write(second.path().join("OTHER"), "other").unwrap();
+
+    let dir = DirProvider::new(first.path());
+    assert_eq!(dir.get("AMUN_ADMIN_TOKEN").unwrap().as_deref(), Some("one"));
+    assert_eq!(dir.get("OTHER").unwrap(), None);
+    assert_eq!(dir.get("../AMUN_ADMIN_TOKEN").unwrap(), None);
+
+    let chain = SecretChain::new()
+        .with(DirProvider::new(first.path()))
+        .with(DirProvider::new(second.path()));
+    assert_eq!(
+        chain.get("AMUN_ADMIN_TOKEN").unwrap().as_deref(),
+        Some("one")
+    );
+    assert_eq!(chain.get("OTHER").unwrap().as_deref(), Some("other"));
+    assert_eq!(
+        chain.get_any(&["MISSING", "OTHER"]).unwrap().as_deref(),
+        Some("other")
+    );
+}
+
+#[test]
+fn keystore_takes_its_passphrase_from_a_provider() {
+    let secrets = tempfile::tempdir().unwrap();
+    std::fs::write(secrets.path().join("AMUNCHAIN_KEY_PASSPHRASE"), "pw\n").unwrap();
+    let provider = DirProvider::new(secrets.path());
+    assert_eq!(passphrase_from(&provider).unwrap().as_deref(), Some("pw"));
+
+    let data = tempfile::tempdir().unwrap();
+    let ks = Keystore::open_with(data.path().to_str().unwrap(), &provider).unwrap();
+    let key = data.path().join("validator.key");
+    assert!(keystore::is_encrypted(&std::fs::read(&key).unwrap()));
+    let kp = keystore::read_key_file(&key, Some("pw")).unwrap();
+    assert_eq!(
+        ring::signature::KeyPair::public_key(&kp).as_ref(),
+        ks.public_key()
+    );
+
+    // An unreadable secret is an error, never "no passphrase".
+    std::fs::create_dir(secrets.path().join("NEXUS_KEY_PASSPHRASE")).unwrap();
+    std::fs::remove_file(secrets.path().join("AMUNCHAIN_KEY_PASSPHRASE")).unwrap();
+    assert!(matches!(
+        Keystore::open_with(data.path().to_str().unwrap(), &provider),
+        Err(KeystoreError::Secret(_))
+    ));
+}
@@ -0,0 +1,185 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::consensus::signing::{
+    vote_signing_bytes_v1, vote_signing_bytes_v2, vote_signing_bytes_v3,
+};
+use amunchain::core::economics::staking::unjail_signing_bytes;
+use amunchain::core::security::audit;
+use amunchain::core::security::keystore::{Keystore, KeystoreError};
+use amunchain::core::security::sign_policy::{
+    parse_vote, PolicyError, SignDomain, SignPolicy, VotePosition,
+};
+use amunchain::core::types::{Epoch, Height, Round, SignPolicyConfig, ValidatorId, H256};
+
+fn voter() -> ValidatorId {
+    ValidatorId::from_slice(&[7; 32]).unwrap()
+}
+
+fn vote(height: u64, round: u64, hash: u8) -> Vec<u8> {
+    vote_signing_bytes_v1(
+        Height(height),
+        Round(round),
+        H256::from_bytes([hash; 32]),
+        &voter(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn parses_every_vote_version() {
+    let want = VotePosition {
+        height: 42,
+        round: 3,
+        block_hash: [0xab; 32],
+    };
+    let hash = H256::from_bytes([0xab; 32]);
+    let v2 = vote_signing_bytes_v2(Height(42), Round(3), Epoch(1), 9, 1_000, 30, hash, &voter())
+        .unwrap();
+    let v3 = vote_signing_bytes_v3(
+        "amun-testnet",
+        Height(42),
+        Round(3),
+        Epoch(1),
+        9,
+        1_000,
+        30,
+        hash,
+        H256::from_bytes([0xcd; 32]),
+        &voter(),
+    )
+    .unwrap();
+    for msg in [vote(42, 3, 0xab), v2, v3.clone()] {
+        assert_eq!(SignDomain::of(&msg), Some(SignDomain::Vote));
+        assert_eq!(parse_vote(&msg), Some(want));
+    }
+    assert_eq!(parse_vote(&v3[..40]), None);
+    assert_eq!(
+        SignDomain::of(&unjail_signing_bytes(b"v", 9)),
+        Some(SignDomain::Unjail)
+    );
+    assert_eq!(SignDomain::of(b"anything else"), None);
+}
+
+#[test]
+fn refuses_other_domains_by_default() {
+    let policy = SignPolicy::new(SignPolicyConfig::default());
+    assert_eq!(policy.check(&vote(1, 0, 1)), Ok(SignDomain::Vote));
+    assert_eq!(
+        policy.check(b"arbitrary bytes"),
+        Err(PolicyError::UnknownDomain)
+    );
+    assert_eq!(
+        policy.check(&unjail_signing_bytes(b"v", 9)),
+        Err(PolicyError::DomainNotAllowed(SignDomain::Unjail))
+    );
+
+    let policy = SignPolicy::new(SignPolicyConfig {
+        allowed_domains: vec![SignDomain::Vote, SignDomain::Unjail],
+        ..SignPolicyConfig::default()
+    });
+    assert!(policy.check(&unjail_signing_bytes(b"v", 9)).is_ok());
+}
+
+#[test]
+fn votes_never_go_backwards_or_conflict() {
+    let policy = SignPolicy::new(SignPolicyConfig::default());
+    policy.check(&vote(10, 0, 1)).unwrap();
+    // Same vote again (e.g. a retry) and later rounds and heights are fine.
+    policy.check(&vote(10, 0, 1)).unwrap();
+    policy.check(&vote(10, 1, 2)).unwrap();
+    policy.check(&vote(11, 0, 3)).unwrap();
+
+    assert_eq!(
+        policy.check(&vote(10, 5, 1)),
+        Err(PolicyError::HeightRegression {
+            height: 10,
+            round: 5
+        })
+    );
+    assert_eq!(
+        policy.check(&vote(11, 0, 4)),
+        Err(PolicyError::Conflict {
+            height: 11,
+            round: 0
+        })
+    );
+    assert_eq!(policy.last_vote().unwrap().height, 11);
+
+    let lax = SignPolicy::new(SignPolicyConfig {
+        monotonic_height: false,
+        ..SignPolicyConfig::default()
+    });
+    lax.check(&vote(11, 0, 3)).unwrap();
+    lax.check(&vote(10, 0, 3)).unwrap();
+}
+
+#[test]
+fn watermark_survives_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("sign_watermark.json");
+    let policy = SignPolicy::new(SignPolicyConfig::default())
+        .with_watermark_file(&path)
+        .unwrap();
+    policy.check(&vote(20, 2, 1)).unwrap();
+    drop(policy);
+
+    let policy = SignPolicy::new(SignPolicyConfig::default())
+        .with_watermark_file(&path)
+        .unwrap();
+    assert!(matches!(
+        policy.check(&vote(20, 1, 1)),
+        Err(PolicyError::HeightRegression { .. })
+    ));
+    assert!(matches!(
+        policy.check(&vote(20, 2, 9)),
+        Err(PolicyError::Conflict { .. })
+    ));
+    policy.check(&vote(21, 0, 1)).unwrap();
+}
+
+#[test]
+fn rate_limit_is_per_domain() {
+    let policy = SignPolicy::new(SignPolicyConfig {
+        allowed_domains: vec![SignDomain::Vote, SignDomain::Unjail],
+        monotonic_height: true,
+        max_signs_per_sec: 3,
+    });
+    for _ in 0..3 {
+        policy.check(&vote(1, 0, 1)).unwrap();
+    }
+    assert_eq!(
+        policy.check(&vote(1, 0, 1)),
+        Err(PolicyError::RateLimited(SignDomain::Vote))
+    );
+    assert!(policy.check(&unjail_signing_bytes(b"v", 9)).is_ok());
+}
+
+#[test]
+fn keystore_enforces_and_audits_refusals() {
+    let dir = tempfile::tempdir().unwrap();
+    let ks = Keystore::open(dir.path().to_str().unwrap())
+        .unwrap()
+        .with_sign_policy(SignPolicy::new(SignPolicyConfig::default()));
+    ks.sign(&vote(1, 0, 1)).unwrap();
+    assert!(matches!(
+        ks.sign(b"transfer everything"),
+        Err(KeystoreError::Policy(PolicyError::UnknownDomain))
+    ));
+    let actions: Vec<String> = audit::read_chain(dir.path())
+        .unwrap()
+        .into_iter()
+        .map(|e| e.action)
+        .collect();
+    assert_eq!(actions, vec!["sign", "deny"]);
+}
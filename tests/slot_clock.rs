@@ -0,0 +1,109 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::consensus::hydro::HydroConfig;
+use amunchain::core::consensus::slot_clock::{SlotClock, DRIFT_SAMPLES};
+use amunchain::monitoring::health::{HealthMonitor, HealthStatus, ReadinessCriteria};
+use amunchain::monitoring::metrics::Metrics;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn hydro(genesis_time_ms: u64, slot_ms: u64, skew_ms: u64) -> HydroConfig {
+    HydroConfig {
+        genesis_time_ms,
+        slot_ms,
+        skew_ms,
+        epoch_randomness: [0; 32],
+        chain_id: "amun-testnet".to_string(),
+    }
+}
+
+#[test]
+fn slots_count_from_genesis() {
+    let clock = SlotClock::new(&hydro(10_000, 1_000, 100));
+    assert_eq!(clock.slot_at(9_999), None);
+    assert_eq!(clock.slot_at(10_000), Some(0));
+    assert_eq!(clock.slot_at(12_999), Some(2));
+    assert_eq!(clock.slot_start_ms(3), 13_000);
+    assert_eq!(clock.until_next_slot(12_400), Duration::from_millis(600));
+    assert_eq!(clock.until_next_slot(4_000), Duration::from_millis(6_000));
+}
+
+#[test]
+fn drift_is_the_median_of_recent_offsets() {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let clock = SlotClock::new(&hydro(0, 1_000, 500)).with_metrics(metrics.clone());
+    assert_eq!(clock.drift_ms(), None);
+
+    // Legacy messages carry no timestamp.
+    clock.observe_peer_time_at(0, 50_000);
+    assert_eq!(clock.drift_ms(), None);
+
+    // One wild peer does not move the estimate.
+    for (sent, local) in [(50_000, 50_040), (60_000, 60_020), (70_000, 10_000)] {
+        clock.observe_peer_time_at(sent, local);
+    }
+    assert_eq!(clock.drift_ms(), Some(20));
+    assert_eq!(metrics.hydro_clock_skew_ms.get(), 20);
+    assert!(!clock.drift_exceeded());
+
+    // Once most samples show the local clock behind, the drift follows.
+    for i in 0..DRIFT_SAMPLES as u64 {
+        clock.observe_peer_time_at(100_000 + i, 99_000 + i);
+    }
+    assert_eq!(clock.drift_ms(), Some(-1_000));
+    assert_eq!(metrics.hydro_clock_skew_ms.get(), -1_000);
+    assert!(clock.drift_exceeded());
+}
+
+#[test]
+fn excessive_drift_fails_readiness() {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    metrics.p2p_listen_addrs.set(1);
+    metrics.p2p_peers.set(1);
+    let clock = SlotClock::new(&hydro(0, 1_000, 500));
+    let health =
+        HealthMonitor::new(ReadinessCriteria::default(), metrics).with_slot_clock(clock.clone());
+    assert_eq!(health.report().status, HealthStatus::Healthy);
+
+    clock.observe_peer_time_at(10_000, 10_400);
+    assert_eq!(health.report().status, HealthStatus::Healthy);
+    for _ in 0..2 {
+        clock.observe_peer_time_at(10_000, 11_000);
+    }
+    let r = health.report();
+    assert_eq!(r.status, HealthStatus::Unhealthy);
+    let check = r.checks.iter().find(|c| c.name == "slot_clock").unwrap();
+    assert_eq!(check.status, HealthStatus::Unhealthy);
+    assert_eq!(check.detail, "drift 1000ms (max 500)");
+}
+
+#[tokio::test]
+async fn ticker_fires_once_per_slot() {
+    let clock = SlotClock::new(&hydro(SlotClock::now_ms(), 20, 10));
+    let mut ticks = clock.ticks();
+    let task = clock.spawn_ticker();
+
+    let mut seen = Vec::new();
+    while seen.len() < 3 {
+        tokio::time::timeout(Duration::from_secs(5), ticks.changed())
+            .await
+            .unwrap()
+            .unwrap();
+        let tick = ticks.borrow_and_update().unwrap();
+        assert_eq!(tick.start_ms, clock.slot_start_ms(tick.slot));
+        seen.push(tick.slot);
+    }
+    task.abort();
+    assert!(seen.windows(2).all(|w| w[0] < w[1]), "{seen:?}");
+}
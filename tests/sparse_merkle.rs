@@ -0,0 +1,243 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use amunchain::core::state::checkpoint;
+use amunchain::core::state::integrity::{self, IntegrityError};
+use amunchain::core::state::merkle::{merkle_root_sorted, ProofCodecError};
+use amunchain::core::state::persistent_state::{KvOp, PersistentState, StateError};
+use amunchain::core::state::smt::{sparse_root, SparseProof, StateTree, SMT_TREE};
+use amunchain::errors::{Classify, ExitCode};
+use amunchain::node::cli;
+use common::fresh_copy;
+use proptest::prelude::*;
+
+fn sparse(dir: &Path) -> PersistentState {
+    let mut state = PersistentState::open(&dir.to_string_lossy()).unwrap();
+    state.require_state_tree(StateTree::Sparse).unwrap();
+    state
+}
+
+fn put(state: &PersistentState, n: u8) {
+    let ops = (0..n)
+        .map(|i| KvOp::Put {
+            key: vec![b'k', i],
+            value: vec![i; 16],
+        })
+        .collect();
+    state.commit_atomic(ops).unwrap();
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn stored_tree_matches_a_rebuild_after_every_commit(
+        batches in proptest::collection::vec(
+            proptest::collection::vec((0u8..24, proptest::option::of(any::<u8>())), 1..12),
+            1..8,
+        )
+    ) {
+        let dir = tempfile::tempdir().unwrap();
+        let state = sparse(dir.path());
+        let mut model = BTreeMap::new();
+        for batch in batches {
+            let ops = batch
+                .into_iter()
+                .map(|(k, v)| match v {
+                    Some(v) => {
+                        model.insert(vec![k], vec![v]);
+                        KvOp::Put { key: vec![k], value: vec![v] }
+                    }
+                    None => {
+                        model.remove(&vec![k]);
+                        KvOp::Del { key: vec![k] }
+                    }
+                })
+                .collect();
+            state.commit_atomic(ops).unwrap();
+            let pairs: Vec<_> = model.clone().into_iter().collect();
+            let root = state.state_root().unwrap();
+            prop_assert_eq!(root, sparse_root(&pairs));
+            for k in 0u8..24 {
+                let (value, at, proof) = state.prove_sparse(&[k]).unwrap();
+                prop_assert_eq!(at, root);
+                prop_assert_eq!(value.as_ref(), model.get(&vec![k]));
+                prop_assert!(proof.verify(root, &[k], value.as_deref()));
+                // The same proof cannot claim the opposite.
+                let flipped = match value {
+                    Some(_) => None,
+                    None => Some(b"x".as_slice()),
+                };
+                prop_assert!(!proof.verify(root, &[k], flipped));
+            }
+        }
+    }
+
+    #[test]
+    fn root_does_not_depend_on_insertion_order(
+        pairs in proptest::collection::btree_map(any::<u16>(), any::<u8>(), 0..64),
+        seed in any::<u64>(),
+    ) {
+        let pairs: Vec<_> = pairs
+            .into_iter()
+            .map(|(k, v)| (k.to_be_bytes().to_vec(), vec![v]))
+            .collect();
+        let mut shuffled = pairs.clone();
+        shuffled.sort_by_key(|(k, _)| {
+            let mut h = seed ^ u64::from(u16::from_be_bytes([k[0], k[1]]));
+            h = h.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+            h ^ (h >> 29)
+        });
+        prop_assert_eq!(sparse_root(&pairs), sparse_root(&shuffled));
+    }
+}
+
+#[test]
+fn proofs_round_trip_and_reject_tampering() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = sparse(dir.path());
+    put(&state, 40);
+    let root = state.state_root().unwrap();
+
+    for key in [b"k\x07".as_slice(), b"missing"] {
+        let (value, _, proof) = state.prove_sparse(key).unwrap();
+        let raw = proof.to_bytes().unwrap();
+        assert_eq!(
+            raw.len(),
+            SparseProof::encoded_len(proof.siblings.len(), proof.leaf.is_some())
+        );
+        assert_eq!(SparseProof::from_bytes(&raw).unwrap(), proof);
+        let json = serde_json::to_value(&proof).unwrap();
+        let back: SparseProof = serde_json::from_value(json).unwrap();
+        assert!(back.verify(root, key, value.as_deref()));
+
+        let mut bad = proof.clone();
+        bad.siblings[0][0] ^= 1;
+        assert!(!bad.verify(root, key, value.as_deref()));
+        assert!(!proof.verify(root, b"k\x08", value.as_deref()));
+    }
+
+    let (_, _, proof) = state.prove_sparse(b"k\x01").unwrap();
+    let raw = proof.to_bytes().unwrap();
+    assert_eq!(
+        SparseProof::from_bytes(&raw[..raw.len() - 1]),
+        Err(ProofCodecError::Length {
+            expected: raw.len(),
+            actual: raw.len() - 1
+        })
+    );
+    let mut flags = raw.clone();
+    flags[1] |= 0x80;
+    assert_eq!(
+        SparseProof::from_bytes(&flags),
+        Err(ProofCodecError::Flags(0x81))
+    );
+    let mut version = raw;
+    version[0] = 9;
+    assert_eq!(
+        SparseProof::from_bytes(&version),
+        Err(ProofCodecError::Version(9))
+    );
+
+    assert!(matches!(
+        state.prove_key(b"k\x01"),
+        Err(StateError::UnsupportedTree(StateTree::Sparse))
+    ));
+}
+
+#[test]
+fn databases_convert_between_trees() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().to_string_lossy().into_owned();
+    let pairs = {
+        let mut state = PersistentState::open(&path).unwrap();
+        put(&state, 30);
+        let pairs = state.scan_prefix(b"").unwrap();
+        assert_eq!(state.state_root().unwrap(), merkle_root_sorted(&pairs));
+
+        let err = state.require_state_tree(StateTree::Sparse).unwrap_err();
+        assert!(matches!(
+            err,
+            StateError::TreeMismatch {
+                stored: StateTree::Sorted,
+                configured: StateTree::Sparse
+            }
+        ));
+        assert_eq!(err.exit_code(), ExitCode::Config);
+
+        state.convert_state_tree(StateTree::Sparse).unwrap();
+        assert_eq!(state.state_root().unwrap(), sparse_root(&pairs));
+        state.flush().unwrap();
+        pairs
+    };
+
+    let copy = fresh_copy(dir.path());
+    let mut state = PersistentState::open(&copy.path().to_string_lossy()).unwrap();
+    assert_eq!(state.state_tree(), StateTree::Sparse);
+    assert_eq!(state.state_root().unwrap(), sparse_root(&pairs));
+    state
+        .commit_atomic(vec![KvOp::Del {
+            key: b"k\x00".to_vec(),
+        }])
+        .unwrap();
+    assert_eq!(
+        state.state_root().unwrap(),
+        state.rebuilt_state_root().unwrap()
+    );
+
+    state.convert_state_tree(StateTree::Sorted).unwrap();
+    assert_eq!(state.state_root().unwrap(), merkle_root_sorted(&pairs[1..]));
+    assert!(state.open_tree(SMT_TREE).unwrap().is_empty());
+}
+
+#[test]
+fn cli_migration_drops_the_checkpoint_and_tampering_is_detected() {
+    let data = tempfile::tempdir().unwrap();
+    {
+        let state = cli::open_state(data.path()).unwrap();
+        put(&state, 12);
+        let cp = checkpoint::write(&state, 4).unwrap();
+        integrity::write_marker(&data.path().join(cli::STATE_DIR), &cp).unwrap();
+        state.flush().unwrap();
+    }
+    let data = fresh_copy(data.path());
+    let path = data.path().join(cli::STATE_DIR);
+    let done = cli::migrate_state_tree(data.path(), StateTree::Sparse).unwrap();
+    assert_eq!((done.from, done.to), (StateTree::Sorted, StateTree::Sparse));
+    assert_eq!(integrity::read_marker(&path).unwrap(), None);
+
+    let migrated = fresh_copy(data.path());
+    let state =
+        PersistentState::open(&migrated.path().join(cli::STATE_DIR).to_string_lossy()).unwrap();
+    let report = integrity::check(&state).unwrap();
+    assert_eq!(
+        (report.checkpoint, hex::encode(report.state_root)),
+        (None, done.state_root)
+    );
+
+    // A node rewritten behind the state's back no longer matches the pairs.
+    let nodes = state.open_tree(SMT_TREE).unwrap();
+    let (pos, _) = nodes.first().unwrap().unwrap();
+    nodes
+        .insert(pos, [&[1u8][..], &[0xab; 32]].concat())
+        .unwrap();
+    let err = integrity::check(&state).unwrap_err();
+    assert!(matches!(err, IntegrityError::TreeMismatch { .. }));
+    assert_eq!(err.kind(), Some("tree_mismatch"));
+    assert_eq!(err.exit_code(), ExitCode::DbCorruption);
+}
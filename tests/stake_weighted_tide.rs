@@ -0,0 +1,90 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::signing::SigningDomain;
+use amunchain::core::consensus::tide::{
+    expected_set_hash, verify_commit_certificate_for_chain, NoopSlashing, TideConfig, TideError,
+    TideFinalizer, DEFAULT_CHAIN_ID,
+};
+use amunchain::core::economics::staking::{StakingLedger, Validator};
+use amunchain::core::types::{Vote, H256};
+use common::{id, keypairs, sign_v3, unsigned_vote, validators};
+use ring::signature::Ed25519KeyPair;
+
+/// Vote bound to the stake-weighted set hash `set_hash`.
+fn signed_vote(kp: &Ed25519KeyPair, set_hash: H256, height: u64) -> Vote {
+    let v = unsigned_vote(kp, height, H256::from_bytes([9u8; 32]));
+    sign_v3(kp, DEFAULT_CHAIN_ID, set_hash, v)
+}
+
+#[test]
+fn threshold_follows_stake_not_headcount() {
+    let kps = keypairs(4);
+    let validators = validators(&kps);
+
+    // Whale: 60 self stake + 10 delegated = 70 of 100.
+    let mut ledger = StakingLedger::default();
+    for (kp, stake) in kps.iter().zip([60u128, 10, 10, 10]) {
+        ledger.validators.insert(
+            id(kp).as_bytes().to_vec(),
+            Validator {
+                self_stake: stake,
+                ..Validator::default()
+            },
+        );
+    }
+    ledger
+        .bond(b"delegator".to_vec(), id(&kps[0]).as_bytes().to_vec(), 10)
+        .unwrap();
+
+    let cfg = TideConfig::new(validators.clone()).with_staking(&ledger);
+    let power = cfg.voting_power.clone().unwrap();
+    let set_hash = expected_set_hash(&validators, Some(&power)).unwrap();
+    let mut tide = TideFinalizer::new(cfg, NoopSlashing);
+
+    // Three small validators hold 30%: no commit even though they are 3 of 4.
+    for kp in kps.iter().skip(1) {
+        assert!(tide
+            .process_vote_verified(signed_vote(kp, set_hash, 1))
+            .unwrap()
+            .is_none());
+    }
+
+    // The whale alone (70%) finalizes height 2.
+    let commit = tide
+        .process_vote_verified(signed_vote(&kps[0], set_hash, 2))
+        .unwrap()
+        .expect("weighted quorum");
+    assert_eq!(commit.voting_power, 70);
+    let domain = SigningDomain {
+        chain_id: DEFAULT_CHAIN_ID,
+        legacy_until_height: None,
+    };
+    verify_commit_certificate_for_chain(&commit, &validators, Some(&power), domain).unwrap();
+
+    // By headcount the same certificate is insufficient.
+    assert!(matches!(
+        verify_commit_certificate_for_chain(&commit, &validators, None, domain),
+        Err(TideError::NotEnoughVotes)
+    ));
+
+    // The recorded power must match the signers.
+    let mut inflated = commit.clone();
+    inflated.voting_power = 100;
+    assert!(matches!(
+        verify_commit_certificate_for_chain(&inflated, &validators, Some(&power), domain),
+        Err(TideError::PowerMismatch)
+    ));
+}
@@ -0,0 +1,206 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::economics::staking::{Offense, StakingError, StakingLedger, StakingParams};
+
+fn params() -> StakingParams {
+    StakingParams {
+        unbonding_period_secs: 100,
+        max_commission_bps: 1_000,
+        min_self_stake: 50,
+        slash_double_sign_bps: 1_000,
+        slash_downtime_bps: 10,
+        ..StakingParams::default()
+    }
+}
+
+#[test]
+fn params_are_validated() {
+    let mut p = params();
+    p.slash_double_sign_bps = 10_001;
+    assert!(matches!(
+        StakingLedger::with_params(p),
+        Err(StakingError::InvalidParams)
+    ));
+    let mut p = params();
+    p.unbonding_period_secs = 0;
+    assert!(StakingLedger::with_params(p).is_err());
+}
+
+#[test]
+fn registration_enforces_commission_and_self_stake() {
+    let mut l = StakingLedger::with_params(params()).unwrap();
+    assert!(matches!(
+        l.register_validator(b"v".to_vec(), 1_001, 100),
+        Err(StakingError::CommissionTooHigh)
+    ));
+    assert!(matches!(
+        l.register_validator(b"v".to_vec(), 500, 49),
+        Err(StakingError::SelfStakeTooLow)
+    ));
+    l.register_validator(b"v".to_vec(), 500, 50).unwrap();
+    assert!(l.set_commission(b"v", 2_000).is_err());
+
+    // Below minimum self stake: no new delegations.
+    l.validators.get_mut(b"v".as_slice()).unwrap().self_stake = 10;
+    assert!(matches!(
+        l.bond(b"d".to_vec(), b"v".to_vec(), 10),
+        Err(StakingError::SelfStakeTooLow)
+    ));
+}
+
+#[test]
+fn unbonding_uses_configured_period() {
+    let mut l = StakingLedger::with_params(params()).unwrap();
+    l.bond(b"d".to_vec(), b"v".to_vec(), 10).unwrap();
+    l.begin_unbond(b"d".to_vec(), b"v".to_vec(), 10, 1_000, 1)
+        .unwrap();
+    assert_eq!(
+        l.finalize_unbond(b"d".to_vec(), b"v".to_vec(), 1_099)
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        l.finalize_unbond(b"d".to_vec(), b"v".to_vec(), 1_100)
+            .unwrap(),
+        10
+    );
+}
+
+#[test]
+fn rewards_deduct_commission_and_slashing_uses_offense_fraction() {
+    let mut l = StakingLedger::with_params(params()).unwrap();
+    l.register_validator(b"v".to_vec(), 1_000, 100).unwrap();
+    l.bond(b"a".to_vec(), b"v".to_vec(), 300).unwrap();
+    l.bond(b"b".to_vec(), b"v".to_vec(), 100).unwrap();
+
+    // 10% commission on 1000: validator accrues 100, delegators share 900 as 3:1.
+    assert_eq!(l.distribute_rewards(b"v", 1_000), 100);
+    assert_eq!(l.validators[b"v".as_slice()].self_stake, 100);
+    assert_eq!(l.pending_rewards(b"a", b"v"), 675);
+    assert_eq!(l.pending_rewards(b"b", b"v"), 225);
+    let stake = |l: &StakingLedger, d: &[u8]| l.delegations[&(d.to_vec(), b"v".to_vec())].amount;
+    assert_eq!(stake(&l, b"a"), 300);
+
+    // Double sign: 10% of the self stake and of each delegation's principal; accrued rewards
+    // are kept.
+    let slashed = l.slash(b"v", Offense::DoubleSign, 1, 0);
+    assert_eq!(slashed, 10 + 30 + 10);
+    assert_eq!(l.validators[b"v".as_slice()].self_stake, 90);
+    assert_eq!(stake(&l, b"a"), 270);
+    assert_eq!(l.pending_rewards(b"a", b"v"), 675);
+    assert_eq!(l.claim_commission(b"v"), 100);
+}
+
+#[test]
+fn rewards_accrue_lazily_and_claim_leaves_bond() {
+    let mut l = StakingLedger::with_params(params()).unwrap();
+    l.register_validator(b"v".to_vec(), 0, 100).unwrap();
+    l.bond(b"a".to_vec(), b"v".to_vec(), 100).unwrap();
+    l.distribute_rewards(b"v", 100);
+
+    // A later bond does not earn rewards distributed before it.
+    l.bond(b"b".to_vec(), b"v".to_vec(), 100).unwrap();
+    l.distribute_rewards(b"v", 100);
+    assert_eq!(l.pending_rewards(b"a", b"v"), 150);
+    assert_eq!(l.pending_rewards(b"b", b"v"), 50);
+
+    // Changing the bond settles first, so earlier rewards keep the old weight.
+    l.bond(b"a".to_vec(), b"v".to_vec(), 200).unwrap();
+    l.distribute_rewards(b"v", 400);
+    assert_eq!(l.pending_rewards(b"a", b"v"), 150 + 300);
+
+    assert_eq!(l.claim_rewards(b"a", b"v").unwrap(), 450);
+    assert_eq!(l.pending_rewards(b"a", b"v"), 0);
+    assert_eq!(l.delegations[&(b"a".to_vec(), b"v".to_vec())].amount, 300);
+    assert_eq!(l.voting_power(b"v"), 100 + 300 + 100);
+}
+
+#[test]
+fn slashing_reaches_unbonding_entries_created_after_the_offense() {
+    let mut l = StakingLedger::with_params(params()).unwrap();
+    l.register_validator(b"v".to_vec(), 0, 100).unwrap();
+    l.bond(b"early".to_vec(), b"v".to_vec(), 1_000).unwrap();
+    l.bond(b"late".to_vec(), b"v".to_vec(), 1_000).unwrap();
+
+    // Unbonded before the offense at height 10: not at stake.
+    l.begin_unbond(b"early".to_vec(), b"v".to_vec(), 1_000, 0, 9)
+        .unwrap();
+    // Unbonded after it: still slashable.
+    l.begin_unbond(b"late".to_vec(), b"v".to_vec(), 1_000, 0, 12)
+        .unwrap();
+
+    assert_eq!(l.slash(b"v", Offense::DoubleSign, 10, 0), 10 + 100);
+    assert_eq!(l.validators[b"v".as_slice()].slashed, 110);
+    assert_eq!(
+        l.finalize_unbond(b"early".to_vec(), b"v".to_vec(), 100)
+            .unwrap(),
+        1_000
+    );
+    assert_eq!(
+        l.finalize_unbond(b"late".to_vec(), b"v".to_vec(), 100)
+            .unwrap(),
+        900
+    );
+}
+
+#[test]
+fn slashing_self_stake_below_the_minimum_sidelines_the_validator() {
+    let mut l = StakingLedger::with_params(params()).unwrap();
+    l.register_validator(b"v".to_vec(), 0, 50).unwrap();
+    l.bond(b"d".to_vec(), b"v".to_vec(), 100).unwrap();
+    assert_eq!(l.eligible_validators(), vec![(b"v".to_vec(), 150)]);
+
+    assert_eq!(l.slash_validator(b"v", 1_000, 1), 5 + 10);
+    assert_eq!(l.validators[b"v".as_slice()].self_stake, 45);
+    assert!(l.eligible_validators().is_empty());
+    assert!(matches!(
+        l.bond(b"d".to_vec(), b"v".to_vec(), 10),
+        Err(StakingError::SelfStakeTooLow)
+    ));
+}
+
+#[test]
+fn dust_and_delegation_count_are_limited() {
+    let mut l = StakingLedger::with_params(StakingParams {
+        min_delegation_amount: 10,
+        max_delegations_per_validator: 2,
+        ..params()
+    })
+    .unwrap();
+    l.register_validator(b"v".to_vec(), 0, 50).unwrap();
+
+    assert!(matches!(
+        l.bond(b"d1".to_vec(), b"v".to_vec(), 9),
+        Err(StakingError::DelegationTooSmall)
+    ));
+    l.bond(b"d1".to_vec(), b"v".to_vec(), 10).unwrap();
+    // Top-ups below the minimum are fine once the delegation itself is above it.
+    l.bond(b"d1".to_vec(), b"v".to_vec(), 1).unwrap();
+    l.bond(b"d2".to_vec(), b"v".to_vec(), 10).unwrap();
+    assert!(matches!(
+        l.bond(b"d3".to_vec(), b"v".to_vec(), 10),
+        Err(StakingError::TooManyDelegations)
+    ));
+    // Existing delegators may still add stake.
+    l.bond(b"d2".to_vec(), b"v".to_vec(), 10).unwrap();
+
+    // Unbonding may not leave dust behind, but may empty the delegation.
+    assert!(matches!(
+        l.begin_unbond(b"d1".to_vec(), b"v".to_vec(), 5, 0, 0),
+        Err(StakingError::DelegationTooSmall)
+    ));
+    l.begin_unbond(b"d1".to_vec(), b"v".to_vec(), 11, 0, 0)
+        .unwrap();
+    l.bond(b"d3".to_vec(), b"v".to_vec(), 10).unwrap();
+}
@@ -0,0 +1,72 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::economics::staking::{StakingLedger, StakingParams, STAKING_PREFIX};
+use amunchain::core::state::persistent_state::PersistentState;
+
+fn ledger() -> StakingLedger {
+    let mut l = StakingLedger::with_params(StakingParams {
+        unbonding_period_secs: 60,
+        ..StakingParams::default()
+    })
+    .unwrap();
+    l.register_validator(b"val-1".to_vec(), 500, 100).unwrap();
+    // Ids that would collide without length prefixes: ("ab","c") vs ("a","bc").
+    l.bond(b"ab".to_vec(), b"c".to_vec(), 10).unwrap();
+    l.bond(b"a".to_vec(), b"bc".to_vec(), 20).unwrap();
+    l.bond(b"d".to_vec(), b"val-1".to_vec(), 30).unwrap();
+    l.begin_unbond(b"d".to_vec(), b"val-1".to_vec(), 5, 1_000, 1)
+        .unwrap();
+    l
+}
+
+#[test]
+fn ledger_round_trips_and_is_in_state_root() {
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    let empty_root = st.state_root().unwrap();
+
+    let l = ledger();
+    l.commit(&st).unwrap();
+    assert_ne!(st.state_root().unwrap(), empty_root);
+    assert_eq!(StakingLedger::load(&st).unwrap(), l);
+
+    // Same ledger built independently: same root.
+    let dir2 = tempfile::tempdir().unwrap();
+    let st2 = PersistentState::open(dir2.path().to_str().unwrap()).unwrap();
+    ledger().commit(&st2).unwrap();
+    assert_eq!(st.state_root().unwrap(), st2.state_root().unwrap());
+}
+
+#[test]
+fn commit_removes_stale_records() {
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    let mut l = ledger();
+    l.commit(&st).unwrap();
+    let before = st.scan_prefix(STAKING_PREFIX).unwrap().len();
+
+    l.begin_unbond(b"ab".to_vec(), b"c".to_vec(), 10, 0, 2)
+        .unwrap();
+    l.finalize_unbond(b"ab".to_vec(), b"c".to_vec(), 1_000)
+        .unwrap();
+    l.commit(&st).unwrap();
+
+    // The emptied delegation is gone and its unbonding list was drained.
+    assert_eq!(st.scan_prefix(STAKING_PREFIX).unwrap().len(), before - 1);
+    let loaded = StakingLedger::load(&st).unwrap();
+    assert!(!loaded
+        .delegations
+        .contains_key(&(b"ab".to_vec(), b"c".to_vec())));
+    assert_eq!(loaded.voting_power(b"val-1"), l.voting_power(b"val-1"));
+}
@@ -0,0 +1,111 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::errors::ExitCode;
+use amunchain::node::startup::{StageFailure, StageHandle, StartupError, StartupOrchestrator};
+use std::time::Duration;
+
+#[test]
+fn plan_follows_dependencies_and_rejects_cycles() {
+    let plan = StartupOrchestrator::new()
+        .stage("http", &["metrics"], ExitCode::PortBind, |_| {
+            Ok(StageHandle::empty())
+        })
+        .stage("p2p", &["metrics", "keys"], ExitCode::Config, |_| {
+            Ok(StageHandle::empty())
+        })
+        .stage("metrics", &[], ExitCode::Internal, |_| {
+            Ok(StageHandle::empty())
+        })
+        .stage("keys", &[], ExitCode::Key, |_| Ok(StageHandle::empty()))
+        .plan()
+        .unwrap();
+    assert_eq!(plan, vec!["metrics", "http", "keys", "p2p"]);
+
+    let err = StartupOrchestrator::new()
+        .stage(
+            "a",
+            &["b"],
+            ExitCode::Internal,
+            |_| Ok(StageHandle::empty()),
+        )
+        .stage(
+            "b",
+            &["a"],
+            ExitCode::Internal,
+            |_| Ok(StageHandle::empty()),
+        )
+        .plan()
+        .unwrap_err();
+    assert!(matches!(err, StartupError::Cycle { .. }));
+
+    let err = StartupOrchestrator::new()
+        .stage("a", &["missing"], ExitCode::Internal, |_| {
+            Ok(StageHandle::empty())
+        })
+        .plan()
+        .unwrap_err();
+    assert_eq!(err.stage(), "a");
+    assert_eq!(err.exit_code(), ExitCode::Internal);
+}
+
+#[test]
+fn classified_failure_overrides_stage_exit_code() {
+    use amunchain::core::state::persistent_state::StateError;
+
+    let err = match StartupOrchestrator::new()
+        .stage("state", &[], ExitCode::Internal, |_| {
+            Err(StageFailure::classified(StateError::DbOpen))
+        })
+        .start()
+    {
+        Ok(_) => panic!("startup must fail"),
+        Err(e) => e,
+    };
+    assert_eq!(err.exit_code(), ExitCode::DbCorruption);
+    assert_eq!(err.exit_code().code(), 14);
+}
+
+#[tokio::test]
+async fn failed_stage_tears_down_started_stages() {
+    let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+
+    let res = StartupOrchestrator::new()
+        .stage("worker", &[], ExitCode::Internal, move |res| {
+            res.insert(7u32);
+            let task = tokio::spawn(async move {
+                // Holds `tx` until aborted; dropping it signals teardown.
+                let _tx = tx;
+                tokio::time::sleep(Duration::from_secs(3600)).await;
+            });
+            Ok(StageHandle::empty().with_task(task))
+        })
+        .stage("broken", &["worker"], ExitCode::Config, |res| {
+            assert_eq!(res.get::<u32>(), Some(&7));
+            Err(StageFailure::msg("bind failed"))
+        })
+        .start();
+
+    let err = match res {
+        Ok(_) => panic!("startup must fail"),
+        Err(e) => e,
+    };
+    assert_eq!(err.stage(), "broken");
+    assert_eq!(err.exit_code(), ExitCode::Config);
+
+    // The worker's task was aborted, dropping its sender.
+    tokio::time::timeout(Duration::from_secs(5), rx)
+        .await
+        .expect("worker torn down")
+        .unwrap_err();
+}
@@ -0,0 +1,164 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::security::secrets::DirProvider;
+use amunchain::core::state::encryption::{StateEncryption, StateSecret, STATE_KEY_SECRET};
+use amunchain::core::state::persistent_state::{KvOp, PersistentState, StateError};
+use common::fresh_copy;
+use std::path::Path;
+
+const VALUE: &[u8] = b"account-balance-1000000";
+
+fn fill(state: &PersistentState) {
+    let ops = (0u8..8)
+        .map(|i| KvOp::Put {
+            key: vec![b'k', i],
+            value: [VALUE, &[i]].concat(),
+        })
+        .collect();
+    state.commit_atomic(ops).unwrap();
+}
+
+fn path(dir: &tempfile::TempDir) -> String {
+    dir.path().join("db").to_string_lossy().into_owned()
+}
+
+// Whether any stored value of the state tree contains `needle`, read past the cipher from
+// a copy of the closed database.
+fn raw_contains(path: &str, needle: &[u8]) -> bool {
+    let copy = fresh_copy(Path::new(path));
+    let db = sled::open(copy.path()).unwrap();
+    let found = db
+        .iter()
+        .map(Result::unwrap)
+        .any(|(_, v)| v.windows(needle.len()).any(|w| w == needle));
+    found
+}
+
+#[test]
+fn encrypted_values_keep_the_plaintext_root_and_proofs() {
+    let (plain_dir, enc_dir) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    let key = StateSecret::Key([9; 32]);
+
+    let plain = PersistentState::open(&path(&plain_dir)).unwrap();
+    fill(&plain);
+    let enc = PersistentState::open_encrypted(&path(&enc_dir), Some(&key)).unwrap();
+    assert!(enc.is_encrypted());
+    fill(&enc);
+
+    assert_eq!(enc.state_root().unwrap(), plain.state_root().unwrap());
+    assert_eq!(enc.get(b"k\x03").unwrap(), plain.get(b"k\x03").unwrap());
+    assert_eq!(enc.scan_prefix(b"k").unwrap().len(), 8);
+    let (_, value, root, proof) = enc.prove_key(b"k\x05").unwrap().unwrap();
+    assert_eq!(value, [VALUE, &[5]].concat());
+    assert!(PersistentState::verify_proof(root, &proof));
+
+    enc.commit_atomic(vec![KvOp::Del {
+        key: b"k\x00".to_vec(),
+    }])
+    .unwrap();
+    assert_eq!(enc.get(b"k\x00").unwrap(), None);
+    enc.flush().unwrap();
+    drop(enc);
+    assert!(!raw_contains(&path(&enc_dir), VALUE));
+}
+
+#[test]
+fn enabling_encryption_rewrites_an_existing_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = {
+        let st = PersistentState::open(&path(&dir)).unwrap();
+        fill(&st);
+        st.flush().unwrap();
+        st.state_root().unwrap()
+    };
+    assert!(raw_contains(&path(&dir), VALUE));
+
+    let key = StateSecret::Key([1; 32]);
+    let dir = fresh_copy(dir.path());
+    let st = PersistentState::open_encrypted(&path(&dir), Some(&key)).unwrap();
+    assert_eq!(st.state_root().unwrap(), root);
+    st.flush().unwrap();
+    drop(st);
+    assert!(!raw_contains(&path(&dir), VALUE));
+
+    // Reopening needs the same key, of the same kind.
+    let reopen = |secret: Option<&StateSecret>| {
+        PersistentState::open_encrypted(&path(&fresh_copy(dir.path())), secret)
+    };
+    assert!(matches!(
+        reopen(None),
+        Err(StateError::EncryptionKeyRequired)
+    ));
+    assert!(matches!(
+        reopen(Some(&StateSecret::Key([2; 32]))),
+        Err(StateError::WrongStateKey)
+    ));
+    assert!(matches!(
+        reopen(Some(&StateSecret::Passphrase("pw".into()))),
+        Err(StateError::WrongStateKey)
+    ));
+    let st = reopen(Some(&key)).unwrap();
+    assert_eq!(st.state_root().unwrap(), root);
+}
+
+#[test]
+fn passphrase_mode_round_trips() {
+    let dir = tempfile::tempdir().unwrap();
+    let pass = StateSecret::Passphrase("correct horse".into());
+    let root = {
+        let st = PersistentState::open_encrypted(&path(&dir), Some(&pass)).unwrap();
+        fill(&st);
+        st.flush().unwrap();
+        st.state_root().unwrap()
+    };
+    let reopen = |secret: &StateSecret| {
+        PersistentState::open_encrypted(&path(&fresh_copy(dir.path())), Some(secret))
+    };
+    assert_eq!(reopen(&pass).unwrap().state_root().unwrap(), root);
+    assert!(matches!(
+        reopen(&StateSecret::Passphrase("wrong".into())),
+        Err(StateError::WrongStateKey)
+    ));
+}
+
+#[test]
+fn state_key_comes_from_secrets() {
+    let secrets = tempfile::tempdir().unwrap();
+    let provider = DirProvider::new(secrets.path());
+    assert!(StateSecret::resolve_from(StateEncryption::Off, &provider)
+        .unwrap()
+        .is_none());
+    assert!(matches!(
+        StateSecret::resolve_from(StateEncryption::Key, &provider),
+        Err(StateError::KeyUnavailable(_))
+    ));
+
+    std::fs::write(secrets.path().join(STATE_KEY_SECRET), "abcd\n").unwrap();
+    assert!(matches!(
+        StateSecret::resolve_from(StateEncryption::Key, &provider),
+        Err(StateError::KeyUnavailable(_))
+    ));
+    std::fs::write(
+        secrets.path().join(STATE_KEY_SECRET),
+        format!("{}\n", "7f".repeat(32)),
+    )
+    .unwrap();
+    let secret = StateSecret::resolve_from(StateEncryption::Key, &provider)
+        .unwrap()
+        .unwrap();
+    assert_eq!(secret.mode(), StateEncryption::Key);
+    assert!(matches!(secret, StateSecret::Key(k) if k == [0x7f; 32]));
+}
@@ -0,0 +1,164 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use std::path::Path;
+
+use amunchain::core::state::checkpoint;
+use amunchain::core::state::encryption::StateEncryption;
+use amunchain::core::state::integrity::{self, IntegrityError, IntegrityReport};
+use amunchain::core::state::persistent_state::{KvOp, PersistentState, StateError};
+use amunchain::errors::{Classify, ExitCode};
+use amunchain::node::cli::{self, Cli, Command};
+use clap::Parser;
+use common::fresh_copy;
+
+fn put(state: &PersistentState, n: u8) {
+    let ops = (0..n)
+        .map(|i| KvOp::Put {
+            key: vec![b'k', i],
+            value: vec![i; 128],
+        })
+        .collect();
+    state.commit_atomic(ops).unwrap();
+}
+
+fn open_checked(path: &Path) -> Result<(PersistentState, IntegrityReport), IntegrityError> {
+    integrity::open_checked(path, StateEncryption::Off)
+}
+
+#[test]
+fn clean_database_passes_against_its_checkpoint() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = PersistentState::open(&dir.path().to_string_lossy()).unwrap();
+    put(&state, 10);
+    assert_eq!(integrity::check(&state).unwrap().checkpoint, None);
+
+    let cp = checkpoint::write(&state, 7).unwrap();
+    let report = integrity::check(&state).unwrap();
+    assert_eq!(report.checkpoint, Some(cp));
+    assert_eq!(report.state_root, state.state_root().unwrap());
+    assert!(report.entries >= 11, "{report:?}");
+}
+
+#[test]
+fn writes_after_a_clean_shutdown_are_detected() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = PersistentState::open(&dir.path().to_string_lossy()).unwrap();
+    put(&state, 4);
+    let cp = checkpoint::write(&state, 3).unwrap();
+    put(&state, 5);
+
+    let err = integrity::check(&state).unwrap_err();
+    assert!(
+        matches!(err, IntegrityError::CheckpointMismatch { height: 3, checkpoint, .. } if checkpoint == cp.state_root)
+    );
+    assert_eq!(err.kind(), Some("checkpoint_mismatch"));
+    assert_eq!(err.exit_code(), ExitCode::DbCorruption);
+}
+
+#[test]
+fn imported_snapshots_drop_the_checkpoint() {
+    let (src, dst) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    let snap = src.path().join("state.snap");
+    let state = cli::open_state(src.path()).unwrap();
+    put(&state, 6);
+    state.flush().unwrap();
+    drop(state);
+    let copy = fresh_copy(src.path());
+    let exported = cli::export_state(copy.path(), &snap).unwrap();
+
+    let state = cli::open_state(dst.path()).unwrap();
+    checkpoint::write(&state, 0).unwrap();
+    assert_eq!(cli::load_snapshot(&state, &snap).unwrap(), exported);
+    let report = integrity::check(&state).unwrap();
+    assert_eq!(report.checkpoint, None);
+    assert_eq!(hex::encode(report.state_root), exported.state_root);
+}
+
+#[test]
+fn damaged_files_fail_the_check_and_can_be_quarantined() {
+    let original = tempfile::tempdir().unwrap();
+    {
+        let path = original.path().join("state");
+        let state = PersistentState::open(&path.to_string_lossy()).unwrap();
+        put(&state, 200);
+        let cp = checkpoint::write(&state, 9).unwrap();
+        integrity::write_marker(&path, &cp).unwrap();
+        state.flush().unwrap();
+    }
+    let checked = fresh_copy(original.path());
+    let path = checked.path().join("state");
+    let (_, report) = open_checked(&path).unwrap();
+    assert_eq!(integrity::read_marker(&path).unwrap(), report.checkpoint);
+
+    // Flip bytes throughout the data file.
+    let damaged = fresh_copy(original.path());
+    let path = damaged.path().join("state");
+    let db_file = path.join("db");
+    let mut raw = std::fs::read(&db_file).unwrap();
+    for i in (0..raw.len()).step_by(97) {
+        raw[i] ^= 0x5a;
+    }
+    std::fs::write(&db_file, raw).unwrap();
+
+    let err = open_checked(&path).map(|_| ()).unwrap_err();
+    assert!(err.kind().is_some(), "{err}");
+    assert_eq!(err.exit_code(), ExitCode::DbCorruption);
+
+    let moved = integrity::quarantine(&path).unwrap();
+    assert!(!path.exists() && moved.join("db").exists());
+    assert!(moved
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .starts_with("state.corrupt-"));
+    let (fresh, report) = open_checked(&path).unwrap();
+    assert_eq!((report.entries, report.checkpoint), (0, None));
+    assert!(fresh.scan_prefix(b"").unwrap().is_empty());
+}
+
+#[test]
+fn missing_keys_and_locks_are_not_corruption() {
+    assert_eq!(IntegrityError::from(StateError::Locked).kind(), None);
+    assert_eq!(
+        IntegrityError::from(StateError::EncryptionKeyRequired).kind(),
+        None
+    );
+    assert_eq!(
+        IntegrityError::from(StateError::DbIo).kind(),
+        Some("unreadable")
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let _held = PersistentState::open(&dir.path().to_string_lossy()).unwrap();
+    assert!(matches!(
+        integrity::open_checked(dir.path(), StateEncryption::Off),
+        Err(IntegrityError::State(StateError::Locked))
+    ));
+}
+
+#[test]
+fn run_takes_repair_flags() {
+    let args = Cli::try_parse_from(["amunchain", "run", "--repair-from", "s.snap"]).unwrap();
+    assert!(matches!(
+        args.command,
+        Some(Command::Run { repair: false, repair_from: Some(f) }) if f == Path::new("s.snap")
+    ));
+    let args = Cli::try_parse_from(["amunchain", "run", "--repair"]).unwrap();
+    assert!(matches!(
+        args.command,
+        Some(Command::Run { repair: true, .. })
+    ));
+}
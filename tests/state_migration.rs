@@ -0,0 +1,170 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::state::migration::{
+    self, Migration, MigrationError, MigrationRegistry, SCHEMA_VERSION,
+};
+use amunchain::core::state::persistent_state::{KvOp, PersistentState, StateError};
+use amunchain::errors::{Classify, ExitCode};
+
+fn put(key: &[u8], value: &[u8]) -> KvOp {
+    KvOp::Put {
+        key: key.to_vec(),
+        value: value.to_vec(),
+    }
+}
+
+// v2 moves `old/` entries to `new/`; running it again finds nothing left to move.
+fn rename_prefix(state: &PersistentState) -> Result<(), StateError> {
+    let ops = state
+        .scan_prefix(b"old/")?
+        .into_iter()
+        .flat_map(|(k, v)| {
+            let renamed = [b"new/".as_slice(), &k[4..]].concat();
+            [KvOp::Del { key: k }, put(&renamed, &v)]
+        })
+        .collect();
+    state.commit_atomic(ops)
+}
+
+fn registry() -> MigrationRegistry {
+    MigrationRegistry::default()
+        .register(Migration {
+            version: 1,
+            name: "baseline",
+            apply: |_| Ok(()),
+        })
+        .unwrap()
+        .register(Migration {
+            version: 2,
+            name: "rename-prefix",
+            apply: rename_prefix,
+        })
+        .unwrap()
+}
+
+fn open(dir: &tempfile::TempDir) -> PersistentState {
+    PersistentState::open(&dir.path().to_string_lossy()).unwrap()
+}
+
+#[test]
+fn new_databases_start_at_the_latest_version() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = open(&dir);
+    let report = registry().run(&state).unwrap();
+    assert_eq!((report.from, report.to), (0, 2));
+    assert!(report.applied.is_empty());
+    assert_eq!(migration::stored_version(&state).unwrap(), Some(2));
+    assert_eq!(migration::builtin().latest(), SCHEMA_VERSION);
+}
+
+#[test]
+fn pending_migrations_run_in_order_once() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = open(&dir);
+    state
+        .commit_atomic(vec![put(b"old/a", b"1"), put(b"old/b", b"2")])
+        .unwrap();
+    let root_before = state.state_root().unwrap();
+
+    let report = registry().run(&state).unwrap();
+    assert_eq!(report.applied, ["baseline", "rename-prefix"]);
+    assert_eq!(migration::stored_version(&state).unwrap(), Some(2));
+    assert!(state.scan_prefix(b"old/").unwrap().is_empty());
+    assert_eq!(state.get(b"new/b").unwrap().as_deref(), Some(&b"2"[..]));
+    // Migrated state roots are the same on every node.
+    let other = tempfile::tempdir().unwrap();
+    let replica = open(&other);
+    replica
+        .commit_atomic(vec![put(b"old/a", b"1"), put(b"old/b", b"2")])
+        .unwrap();
+    registry().run(&replica).unwrap();
+    let root = state.state_root().unwrap();
+    assert_ne!(root, root_before);
+    assert_eq!(replica.state_root().unwrap(), root);
+
+    let again = registry().run(&state).unwrap();
+    assert_eq!((again.from, again.to), (2, 2));
+    assert!(again.applied.is_empty());
+    // Interrupted before its version was recorded, a migration runs again harmlessly.
+    migration::record_version(&state, 1).unwrap();
+    assert_eq!(registry().run(&state).unwrap().applied, ["rename-prefix"]);
+    assert_eq!(state.state_root().unwrap(), root);
+}
+
+#[test]
+fn failures_stop_at_the_last_completed_version() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = open(&dir);
+    state.commit_atomic(vec![put(b"k", b"v")]).unwrap();
+    let failing = MigrationRegistry::default()
+        .register(Migration {
+            version: 1,
+            name: "baseline",
+            apply: |_| Ok(()),
+        })
+        .unwrap()
+        .register(Migration {
+            version: 2,
+            name: "broken",
+            apply: |_| Err(StateError::DbIo),
+        })
+        .unwrap();
+    let err = failing.run(&state).unwrap_err();
+    assert!(matches!(
+        err,
+        MigrationError::Failed {
+            version: 2,
+            name: "broken",
+            ..
+        }
+    ));
+    assert_eq!(err.exit_code(), ExitCode::DbCorruption);
+    assert_eq!(migration::stored_version(&state).unwrap(), Some(1));
+    assert_eq!(registry().run(&state).unwrap().applied, ["rename-prefix"]);
+}
+
+#[test]
+fn newer_schemas_are_refused_untouched() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = open(&dir);
+    state.commit_atomic(vec![put(b"old/a", b"1")]).unwrap();
+    migration::record_version(&state, 3).unwrap();
+    let err = registry().run(&state).unwrap_err();
+    assert!(matches!(
+        err,
+        MigrationError::NewerSchema {
+            stored: 3,
+            supported: 2
+        }
+    ));
+    assert_eq!(err.exit_code(), ExitCode::Config);
+    assert!(state.get(b"old/a").unwrap().is_some());
+    assert_eq!(migration::stored_version(&state).unwrap(), Some(3));
+}
+
+#[test]
+fn registry_rejects_gaps() {
+    let skip = MigrationRegistry::default().register(Migration {
+        version: 2,
+        name: "skips-one",
+        apply: |_| Ok(()),
+    });
+    assert!(matches!(
+        skip,
+        Err(MigrationError::OutOfOrder {
+            version: 2,
+            expected: 1
+        })
+    ));
+}
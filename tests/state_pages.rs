@@ -0,0 +1,192 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::state::merkle::{verify_proof, MerkleProof};
+use amunchain::core::state::persistent_state::{KvOp, PersistentState, StatePage};
+use amunchain::core::state::smt::{SparseProof, StateTree};
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::rpc::server::{bind, serve_listener, RpcState};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+// 25 keys under `acct/`, with neighbours on both sides of the prefix.
+fn state(dir: &tempfile::TempDir) -> PersistentState {
+    let state = PersistentState::open(&dir.path().to_string_lossy()).unwrap();
+    let mut ops: Vec<_> = (0u8..25)
+        .map(|i| KvOp::Put {
+            key: [b"acct/".as_slice(), &[i]].concat(),
+            value: vec![i; 8],
+        })
+        .collect();
+    for key in [b"acc".as_slice(), b"acct", b"acct0", b"bank/x"] {
+        ops.push(KvOp::Put {
+            key: key.to_vec(),
+            value: b"other".to_vec(),
+        });
+    }
+    state.commit_atomic(ops).unwrap();
+    state
+}
+
+#[test]
+fn pages_cover_the_prefix_in_order() {
+    let dir = tempfile::tempdir().unwrap();
+    let st = state(&dir);
+    let mut seen = Vec::new();
+    let mut cursor: Option<Vec<u8>> = None;
+    let mut pages = 0;
+    loop {
+        let page = st
+            .scan_prefix_page(b"acct/", cursor.as_deref(), 10)
+            .unwrap();
+        assert!(page.entries.len() <= 10);
+        seen.extend(page.entries);
+        pages += 1;
+        match page.next_start_after {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(pages, 3);
+    assert_eq!(seen, st.scan_prefix(b"acct/").unwrap());
+
+    // An exact fit has no further page, and a cursor before the prefix starts at it.
+    let full = st.scan_prefix_page(b"acct/", Some(b"a"), 25).unwrap();
+    assert_eq!((full.entries.len(), full.next_start_after), (25, None));
+    let after_last = st
+        .scan_prefix_page(b"acct/", Some(b"acct/\x18"), 10)
+        .unwrap();
+    assert_eq!(after_last, StatePage::default());
+    assert_eq!(
+        st.scan_prefix_page(b"", None, 0).unwrap().entries.len(),
+        1,
+        "limit is at least one"
+    );
+}
+
+#[test]
+fn proven_pages_match_and_verify() {
+    let dir = tempfile::tempdir().unwrap();
+    let st = state(&dir);
+    let root = st.state_root().unwrap();
+    let start = Some(b"acct/\x04".as_slice());
+
+    let (page, proof) = st.scan_prefix_page_proven(b"acct/", start, 7).unwrap();
+    assert_eq!(page, st.scan_prefix_page(b"acct/", start, 7).unwrap());
+    let proof = proof.unwrap();
+    assert_eq!(proof.state_root, root);
+    assert!(proof.verify(&page));
+
+    // Dropping an entry from the middle, or changing a boundary value, is caught.
+    let mut gap = page.clone();
+    gap.entries.remove(3);
+    assert!(!proof.verify(&gap));
+    let mut edited = page.clone();
+    edited.entries[0].1 = b"forged".to_vec();
+    assert!(!proof.verify(&edited));
+
+    let (empty, none) = st.scan_prefix_page_proven(b"zzz", None, 7).unwrap();
+    assert!(empty.entries.is_empty() && none.is_none());
+}
+
+async fn get(addr: std::net::SocketAddr, path: &str) -> (u16, serde_json::Value) {
+    let mut s = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let req = format!("GET {path} HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n");
+    s.write_all(req.as_bytes()).await.unwrap();
+    let mut resp = String::new();
+    s.read_to_string(&mut resp).await.unwrap();
+    let code = resp[9..12].parse().unwrap();
+    let body = resp.split("\r\n\r\n").nth(1).unwrap_or_default();
+    (code, serde_json::from_str(body).unwrap_or_default())
+}
+
+#[tokio::test]
+async fn rpc_serves_paged_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    let st = state(&dir);
+    let root_bytes = st.state_root().unwrap();
+    let root = hex::encode(root_bytes);
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let listener = bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_listener(
+        listener,
+        RpcState::new(metrics).with_state(st),
+    ));
+
+    let prefix = hex::encode(b"acct/");
+    let (code, body) = get(addr, &format!("/state/keys?prefix={prefix}&limit=20")).await;
+    assert_eq!(code, 200);
+    assert_eq!(body["entries"].as_array().unwrap().len(), 20);
+    assert_eq!(body["entries"][0]["value"], hex::encode([0u8; 8]));
+    assert!(body["proof"].is_null());
+    let next = body["next_start_after"].as_str().unwrap();
+    assert_eq!(next, hex::encode(b"acct/\x13"));
+
+    let (_, body) = get(
+        addr,
+        &format!("/state/keys?prefix={prefix}&start_after={next}&proof=true"),
+    )
+    .await;
+    assert_eq!(body["entries"].as_array().unwrap().len(), 5);
+    assert!(body["next_start_after"].is_null());
+    assert_eq!(body["proof"]["state_root"], root);
+    let last: MerkleProof = serde_json::from_value(body["proof"]["last"].clone()).unwrap();
+    assert!(verify_proof(root_bytes, &last));
+
+    let (code, body) = get(addr, &format!("/state/proof/{}", hex::encode(b"acct/\x02"))).await;
+    assert_eq!(code, 200);
+    assert_eq!(body["value"], hex::encode([2u8; 8]));
+    assert_eq!(body["state_root"], root);
+    let proof: MerkleProof = serde_json::from_value(body["proof"].clone()).unwrap();
+    assert!(verify_proof(root_bytes, &proof));
+    assert_eq!(get(addr, "/state/proof/00ff").await.0, 404);
+
+    assert_eq!(get(addr, "/state/keys?prefix=zz").await.0, 400);
+    let bare = bind("127.0.0.1:0").unwrap();
+    let bare_addr = bare.local_addr().unwrap();
+    let metrics = Arc::new(Metrics::new().unwrap());
+    tokio::spawn(serve_listener(bare, RpcState::new(metrics)));
+    assert_eq!(get(bare_addr, "/state/keys").await.0, 503);
+}
+
+#[tokio::test]
+async fn rpc_proves_absence_on_the_sparse_tree() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut st = state(&dir);
+    st.convert_state_tree(StateTree::Sparse).unwrap();
+    let root = st.state_root().unwrap();
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let listener = bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_listener(
+        listener,
+        RpcState::new(metrics).with_state(st),
+    ));
+
+    let (code, body) = get(addr, &format!("/state/proof/{}", hex::encode(b"acct/\x02"))).await;
+    assert_eq!(code, 200);
+    assert_eq!(body["tree"], "sparse");
+    assert_eq!(body["state_root"], hex::encode(root));
+    let proof: SparseProof = serde_json::from_value(body["proof"].clone()).unwrap();
+    assert!(proof.verify(root, b"acct/\x02", Some(&[2u8; 8])));
+
+    let (code, body) = get(addr, "/state/proof/00ff").await;
+    assert_eq!(code, 200);
+    assert!(body["value"].is_null());
+    let proof: SparseProof = serde_json::from_value(body["proof"].clone()).unwrap();
+    assert!(proof.verify(root, b"\x00\xff", None));
+
+    assert_eq!(get(addr, "/state/keys?proof=true").await.0, 400);
+    assert_eq!(get(addr, "/state/keys?limit=3").await.0, 200);
+}
@@ -0,0 +1,129 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::state::encryption::StateSecret;
+use amunchain::core::state::persistent_state::{KvOp, PersistentState};
+use amunchain::core::state::watch::{KeyChange, WatchError, WatchFilter};
+
+fn put(key: &[u8], value: &[u8]) -> KvOp {
+    KvOp::Put {
+        key: key.to_vec(),
+        value: value.to_vec(),
+    }
+}
+
+fn change(key: &[u8], old: Option<&[u8]>, new: Option<&[u8]>, height: u64) -> KeyChange {
+    KeyChange {
+        key: key.to_vec(),
+        old: old.map(<[u8]>::to_vec),
+        new: new.map(<[u8]>::to_vec),
+        height,
+    }
+}
+
+#[test]
+fn watches_see_matching_changes_after_commit() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = PersistentState::open(&dir.path().to_string_lossy()).unwrap();
+    state.commit_atomic(vec![put(b"gov/fee", b"1")]).unwrap();
+
+    let mut gov = state.watch(vec![WatchFilter::Prefix(b"gov/".to_vec())], 16);
+    // Watches are shared by clones of the handle.
+    let mut fee = state
+        .clone()
+        .watch(vec![WatchFilter::Key(b"gov/fee".to_vec())], 16);
+    assert_eq!(gov.try_recv(), Err(WatchError::Empty));
+
+    state.set_block_height(7);
+    state
+        .commit_atomic(vec![
+            put(b"gov/fee", b"2"),
+            put(b"gov/quorum", b"67"),
+            put(b"bank/a", b"100"),
+            put(b"gov/fee", b"3"),
+        ])
+        .unwrap();
+    assert_eq!(
+        gov.try_recv().unwrap(),
+        change(b"gov/fee", Some(b"1"), Some(b"2"), 7)
+    );
+    assert_eq!(
+        gov.try_recv().unwrap(),
+        change(b"gov/quorum", None, Some(b"67"), 7)
+    );
+    assert_eq!(
+        gov.try_recv().unwrap(),
+        change(b"gov/fee", Some(b"2"), Some(b"3"), 7)
+    );
+    assert_eq!(gov.try_recv(), Err(WatchError::Empty));
+    assert_eq!(fee.try_recv().unwrap().new.as_deref(), Some(&b"2"[..]));
+    assert_eq!(fee.try_recv().unwrap().new.as_deref(), Some(&b"3"[..]));
+
+    // Rewriting a value, or deleting an absent key, changes nothing.
+    state
+        .commit_atomic(vec![
+            put(b"gov/fee", b"3"),
+            KvOp::Del {
+                key: b"gov/none".to_vec(),
+            },
+            KvOp::Del {
+                key: b"gov/quorum".to_vec(),
+            },
+        ])
+        .unwrap();
+    assert_eq!(
+        gov.try_recv().unwrap(),
+        change(b"gov/quorum", Some(b"67"), None, 7)
+    );
+    assert_eq!(gov.try_recv(), Err(WatchError::Empty));
+    assert_eq!(fee.try_recv(), Err(WatchError::Empty));
+
+    drop(state);
+    assert_eq!(gov.try_recv(), Err(WatchError::Closed));
+}
+
+#[test]
+fn slow_watches_lose_changes_without_blocking_commits() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = PersistentState::open(&dir.path().to_string_lossy()).unwrap();
+    let mut all = state.watch(vec![WatchFilter::Prefix(Vec::new())], 2);
+    let ops = (0u8..5).map(|i| put(&[b'k', i], &[i])).collect();
+    state.commit_atomic(ops).unwrap();
+
+    assert_eq!(all.try_recv(), Err(WatchError::Lagged(3)));
+    assert_eq!(all.try_recv().unwrap().key, b"k\x00");
+    assert_eq!(all.try_recv().unwrap().key, b"k\x01");
+    assert_eq!(all.try_recv(), Err(WatchError::Empty));
+
+    // A dropped watch is gone for good; commits carry on.
+    drop(all);
+    state.commit_atomic(vec![put(b"k\x09", b"9")]).unwrap();
+}
+
+#[tokio::test]
+async fn encrypted_state_reports_plaintext() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = PersistentState::open_encrypted(
+        &dir.path().to_string_lossy(),
+        Some(&StateSecret::Key([4; 32])),
+    )
+    .unwrap();
+    state.commit_atomic(vec![put(b"acct/a", b"10")]).unwrap();
+    let mut watch = state.watch(vec![WatchFilter::Key(b"acct/a".to_vec())], 4);
+    let writer = state.clone();
+    tokio::spawn(async move { writer.commit_atomic(vec![put(b"acct/a", b"11")]).unwrap() });
+    assert_eq!(
+        watch.recv().await.unwrap(),
+        change(b"acct/a", Some(b"10"), Some(b"11"), 0)
+    );
+}
@@ -0,0 +1,69 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::node::info::{BuildInfo, NodeIdentity};
+use amunchain::rpc::server::{bind, serve_listener, RpcState};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+async fn get(addr: std::net::SocketAddr, path: &str) -> String {
+    let mut s = tokio::net::TcpStream::connect(addr).await.unwrap();
+    s.write_all(format!("GET {path} HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n").as_bytes())
+        .await
+        .unwrap();
+    let mut resp = String::new();
+    s.read_to_string(&mut resp).await.unwrap();
+    resp
+}
+
+#[test]
+fn build_info_comes_from_build_script() {
+    let b = BuildInfo::current();
+    assert_eq!(b.version, env!("CARGO_PKG_VERSION"));
+    assert!(!b.rustc.is_empty());
+    assert!(!b.build_timestamp.is_empty());
+}
+
+#[tokio::test]
+async fn system_info_reports_identity_and_height() {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    metrics.block_height.set(42);
+    let listener = bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_listener(
+        listener,
+        RpcState::new(metrics).with_identity(NodeIdentity::new("testnet-1", "12D3KooWTest")),
+    ));
+
+    let resp = get(addr, "/system_info").await;
+    assert!(resp.starts_with("HTTP/1.1 200"));
+    let body: serde_json::Value =
+        serde_json::from_str(resp.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+    assert_eq!(body["chain_id"], "testnet-1");
+    assert_eq!(body["peer_id"], "12D3KooWTest");
+    assert_eq!(body["role"], "validator");
+    assert_eq!(body["finalized_height"], 42);
+    assert_eq!(body["build"]["version"], env!("CARGO_PKG_VERSION"));
+    assert!(body["build"]["git_sha"].is_string());
+    assert!(body["uptime_secs"].is_u64());
+}
+
+#[tokio::test]
+async fn system_info_is_503_without_identity() {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let listener = bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_listener(listener, RpcState::new(metrics)));
+    assert!(get(addr, "/system_info").await.starts_with("HTTP/1.1 503"));
+}
@@ -0,0 +1,66 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::types::{NodeConfig, TelemetryConfig};
+use amunchain::monitoring::telemetry::{self, TelemetryError};
+use amunchain::node::channel::{channel, ChannelConfig};
+
+#[test]
+fn channel_carries_sender_span() {
+    let subscriber = tracing_subscriber::registry();
+    tracing::subscriber::with_default(subscriber, || {
+        let (tx, mut rx) = channel::<u8>("test_span", ChannelConfig::blocking(4), None);
+        let span = tracing::info_span!("p2p.receive");
+        span.in_scope(|| tx.try_send(1)).unwrap();
+        tx.try_send(2).unwrap();
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let (v, origin) = rt.block_on(rx.recv_with_span()).unwrap();
+        assert_eq!(v, 1);
+        assert_eq!(origin.id(), span.id());
+        let (_, origin) = rt.block_on(rx.recv_with_span()).unwrap();
+        assert!(origin.is_none());
+    });
+}
+
+#[test]
+fn telemetry_config_section() {
+    let cfg: NodeConfig =
+        toml::from_str(&std::fs::read_to_string("configs/node.toml").unwrap()).unwrap();
+    assert_eq!(cfg.telemetry.otlp_endpoint, None);
+    assert_eq!(cfg.telemetry.sample_ratio, 1.0);
+
+    let bad = TelemetryConfig {
+        sample_ratio: 1.5,
+        ..TelemetryConfig::default()
+    };
+    assert!(matches!(
+        telemetry::init(&bad),
+        Err(TelemetryError::SampleRatio)
+    ));
+}
+
+#[cfg(not(feature = "otel"))]
+#[test]
+fn export_requires_otel_feature() {
+    let cfg = TelemetryConfig {
+        otlp_endpoint: Some("http://127.0.0.1:4318/v1/traces".to_string()),
+        ..TelemetryConfig::default()
+    };
+    assert!(matches!(
+        telemetry::init(&cfg),
+        Err(TelemetryError::Unsupported)
+    ));
+}
@@ -0,0 +1,128 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::tide::expected_set_hash;
+use amunchain::core::consensus::tide::{
+    NoopSlashing, TideConfig, TideError, TideFinalizer, DEFAULT_CHAIN_ID,
+};
+use amunchain::core::types::{Epoch, Round, ValidatorId, Vote, H256};
+use common::{keypairs, sign_v3, unsigned_vote, validators};
+use ring::signature::Ed25519KeyPair;
+use std::collections::BTreeSet;
+
+/// Vote for `height` (round = counter, so every vote lands in its own round).
+fn vote(
+    kp: &Ed25519KeyPair,
+    set: &BTreeSet<ValidatorId>,
+    height: u64,
+    epoch: u64,
+    counter: u64,
+) -> Vote {
+    let v = Vote {
+        round: Round(counter),
+        epoch: Epoch(epoch),
+        msg_counter: counter,
+        ..unsigned_vote(kp, height, H256::from_bytes([height as u8; 32]))
+    };
+    sign_v3(
+        kp,
+        DEFAULT_CHAIN_ID,
+        expected_set_hash(set, None).unwrap(),
+        v,
+    )
+}
+
+fn finalizer(kps: &[Ed25519KeyPair]) -> TideFinalizer<NoopSlashing> {
+    TideFinalizer::new(TideConfig::new(validators(kps)), NoopSlashing)
+}
+
+#[test]
+fn counters_must_advance_within_an_epoch() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let mut tide = finalizer(&kps);
+    tide.process_vote_verified(vote(&kps[0], &set, 1, 1, 5))
+        .unwrap();
+    assert_eq!(
+        tide.process_vote_verified(vote(&kps[0], &set, 1, 1, 5))
+            .unwrap_err(),
+        TideError::Replay
+    );
+    assert_eq!(
+        tide.process_vote_verified(vote(&kps[0], &set, 1, 1, 3))
+            .unwrap_err(),
+        TideError::Replay
+    );
+    tide.process_vote_verified(vote(&kps[0], &set, 1, 1, 6))
+        .unwrap();
+}
+
+#[test]
+fn an_older_epoch_does_not_reset_a_newer_one() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let mut tide = finalizer(&kps);
+    tide.process_vote_verified(vote(&kps[0], &set, 1, 2, 5))
+        .unwrap();
+    // Previously this overwrote the epoch-2 state and let counter 5 be replayed.
+    assert_eq!(
+        tide.process_vote_verified(vote(&kps[0], &set, 1, 1, 9))
+            .unwrap_err(),
+        TideError::Replay
+    );
+    assert_eq!(
+        tide.process_vote_verified(vote(&kps[0], &set, 1, 2, 5))
+            .unwrap_err(),
+        TideError::Replay
+    );
+    // Other validators may still be catching up on the older epoch.
+    tide.process_vote_verified(vote(&kps[1], &set, 1, 1, 1))
+        .unwrap();
+}
+
+#[test]
+fn epochs_beyond_the_window_are_pruned_and_rejected() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let mut tide = finalizer(&kps);
+    for epoch in 1..=5 {
+        tide.process_vote_verified(vote(&kps[0], &set, epoch, epoch, 1))
+            .unwrap();
+    }
+    assert_eq!(tide.retained_replay_epochs(), 2);
+    assert_eq!(
+        tide.process_vote_verified(vote(&kps[1], &set, 3, 3, 1))
+            .unwrap_err(),
+        TideError::Replay
+    );
+    tide.process_vote_verified(vote(&kps[1], &set, 4, 4, 1))
+        .unwrap();
+}
+
+#[test]
+fn forged_votes_do_not_advance_counters() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let mut tide = finalizer(&kps);
+    let mut forged = vote(&kps[0], &set, 1, 1, 100);
+    forged.block_hash = H256::from_bytes([0xee; 32]);
+    assert_eq!(
+        tide.process_vote_verified(forged).unwrap_err(),
+        TideError::BadSignature
+    );
+    tide.process_vote_verified(vote(&kps[0], &set, 1, 1, 2))
+        .unwrap();
+    assert_eq!(tide.retained_replay_epochs(), 1);
+}
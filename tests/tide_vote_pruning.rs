@@ -0,0 +1,135 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::tide::{
+    expected_set_hash, NoopSlashing, TideConfig, TideError, TideFinalizer, DEFAULT_CHAIN_ID,
+};
+use amunchain::core::types::{Height, Round, ValidatorId, Vote, H256};
+use common::{keypairs, sign_v3, unsigned_vote, validators};
+use ring::signature::Ed25519KeyPair;
+use std::collections::BTreeSet;
+
+fn signed_vote(kp: &Ed25519KeyPair, set: &BTreeSet<ValidatorId>, height: u64, round: u64) -> Vote {
+    let v = Vote {
+        round: Round(round),
+        ..unsigned_vote(kp, height, H256::from_bytes([height as u8; 32]))
+    };
+    sign_v3(
+        kp,
+        DEFAULT_CHAIN_ID,
+        expected_set_hash(set, None).unwrap(),
+        v,
+    )
+}
+
+fn finalizer(kps: &[Ed25519KeyPair]) -> TideFinalizer<NoopSlashing> {
+    let mut cfg = TideConfig::new(validators(kps));
+    cfg.max_future_heights = 4;
+    cfg.max_rounds_per_voter = 2;
+    TideFinalizer::new(cfg, NoopSlashing)
+}
+
+#[test]
+fn finalization_prunes_lower_heights() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let mut tide = finalizer(&kps);
+
+    // One vote each at heights 1..=3: nothing finalizes yet.
+    for h in 1..=3 {
+        assert!(tide
+            .process_vote_verified(signed_vote(&kps[0], &set, h, 0))
+            .unwrap()
+            .is_none());
+    }
+    assert_eq!(tide.retained_heights(), 3);
+
+    // Finalize height 2.
+    let mut commit = None;
+    for kp in kps.iter().skip(1).take(2) {
+        commit = tide
+            .process_vote_verified(signed_vote(kp, &set, 2, 0))
+            .unwrap();
+    }
+    assert_eq!(commit.map(|c| c.height), Some(Height(2)));
+    assert_eq!(tide.finalized_height(), Height(2));
+
+    // Height 1 is gone; height 2 is kept for double-vote detection.
+    assert_eq!(tide.retained_heights(), 2);
+    assert_eq!(tide.retained_votes(), 4);
+
+    // Votes below the finalized height are rejected and not buffered.
+    assert!(matches!(
+        tide.process_vote_verified(signed_vote(&kps[3], &set, 1, 0)),
+        Err(TideError::Replay)
+    ));
+    assert_eq!(tide.retained_heights(), 2);
+}
+
+#[test]
+fn far_future_heights_and_excess_rounds_are_rejected() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let mut tide = finalizer(&kps);
+
+    assert!(tide
+        .process_vote_verified(signed_vote(&kps[0], &set, 4, 0))
+        .is_ok());
+    assert!(matches!(
+        tide.process_vote_verified(signed_vote(&kps[0], &set, 5, 0)),
+        Err(TideError::OutOfWindow)
+    ));
+
+    assert!(tide
+        .process_vote_verified(signed_vote(&kps[0], &set, 1, 0))
+        .is_ok());
+    assert!(tide
+        .process_vote_verified(signed_vote(&kps[0], &set, 1, 1))
+        .is_ok());
+    // A third round replaces the voter's lowest one; going back to it is refused.
+    assert!(tide
+        .process_vote_verified(signed_vote(&kps[0], &set, 1, 2))
+        .is_ok());
+    assert_eq!(tide.retained_votes(), 3);
+    assert!(matches!(
+        tide.process_vote_verified(signed_vote(&kps[0], &set, 1, 0)),
+        Err(TideError::OutOfWindow)
+    ));
+    assert_eq!(tide.retained_votes(), 3);
+}
+
+#[test]
+fn one_signer_spamming_rounds_does_not_block_the_others() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let mut tide = finalizer(&kps);
+
+    for round in 0..32 {
+        assert!(tide
+            .process_vote_verified(signed_vote(&kps[0], &set, 2, round))
+            .is_ok());
+    }
+    assert_eq!(tide.retained_votes(), 2);
+
+    // Honest votes in a round the spammer never touched still finalize the height.
+    let mut commit = None;
+    for kp in &kps[1..] {
+        commit = tide
+            .process_vote_verified(signed_vote(kp, &set, 2, 40))
+            .unwrap();
+    }
+    assert_eq!(commit.map(|c| c.round), Some(Round(40)));
+    assert_eq!(tide.finalized_height(), Height(2));
+}
@@ -0,0 +1,171 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::consensus::signing::tx_signing_bytes;
+use amunchain::core::economics::fees::FeeLimits;
+use amunchain::core::runtime::tx::{verify_transaction, NonceTracker, TxError};
+use amunchain::core::state::persistent_state::PersistentState;
+use amunchain::core::types::{AccountKey, Signature, Transaction, TxCall, MAX_TX_BYTES};
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+
+const CHAIN: &str = "amun-testnet";
+
+fn keypair() -> Ed25519KeyPair {
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+    Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+}
+
+fn signed(kp: &Ed25519KeyPair, nonce: u64, call: TxCall) -> Transaction {
+    let mut tx = Transaction {
+        chain_id: CHAIN.to_string(),
+        sender: AccountKey::from_slice(kp.public_key().as_ref()).unwrap(),
+        nonce,
+        call,
+        gas_limit: 21_000,
+        max_fee_per_gas: 10,
+        max_priority_fee_per_gas: 2,
+        signature: Signature::from_bytes([0; 64]),
+    };
+    let sig = kp.sign(&tx_signing_bytes(&tx).unwrap());
+    tx.signature = Signature::from_slice(sig.as_ref()).unwrap();
+    tx
+}
+
+fn transfer(amount: u128) -> TxCall {
+    TxCall::Transfer {
+        to: b"bob".to_vec(),
+        amount,
+    }
+}
+
+#[test]
+fn signed_transactions_verify_and_round_trip() {
+    let kp = keypair();
+    let tx = signed(&kp, 0, transfer(5));
+    assert_eq!(verify_transaction(&tx, CHAIN), Ok(()));
+
+    let decoded = Transaction::from_bytes(&tx.to_bytes().unwrap()).unwrap();
+    assert_eq!(decoded, tx);
+    assert_eq!(decoded.hash().unwrap(), tx.hash().unwrap());
+    assert_eq!(
+        FeeLimits::from(&tx),
+        FeeLimits {
+            gas_limit: 21_000,
+            max_fee_per_gas: 10,
+            max_priority_fee_per_gas: 2,
+        }
+    );
+}
+
+#[test]
+fn every_signed_field_is_covered() {
+    let kp = keypair();
+    let tx = signed(&kp, 3, transfer(5));
+    let mutations: [fn(&mut Transaction); 6] = [
+        |t| t.nonce += 1,
+        |t| t.gas_limit += 1,
+        |t| t.max_fee_per_gas += 1,
+        |t| t.max_priority_fee_per_gas += 1,
+        |t| t.call = transfer(6),
+        |t| t.sender = AccountKey::from_bytes([1; 32]),
+    ];
+    for m in mutations {
+        let mut t = tx.clone();
+        m(&mut t);
+        assert_eq!(verify_transaction(&t, CHAIN), Err(TxError::BadSignature));
+        assert_ne!(t.hash().unwrap(), tx.hash().unwrap());
+    }
+
+    // The chain id is signed too, and checked before the signature.
+    assert_eq!(
+        verify_transaction(&tx, "amun-mainnet"),
+        Err(TxError::WrongChain)
+    );
+    let mut other = tx.clone();
+    other.chain_id = "amun-mainnet".to_string();
+    assert_eq!(
+        verify_transaction(&other, "amun-mainnet"),
+        Err(TxError::BadSignature)
+    );
+}
+
+#[test]
+fn oversized_transactions_are_rejected() {
+    let kp = keypair();
+    let tx = signed(
+        &kp,
+        0,
+        TxCall::Call {
+            to: b"contract".to_vec(),
+            value: 0,
+            input: vec![0; MAX_TX_BYTES],
+        },
+    );
+    assert_eq!(verify_transaction(&tx, CHAIN), Err(TxError::TooLarge));
+    assert!(Transaction::from_bytes(&tx.to_bytes().unwrap()).is_err());
+}
+
+#[test]
+fn nonces_execute_in_order_exactly_once() {
+    let mut nonces = NonceTracker::default();
+    let a = b"alice".as_slice();
+    assert_eq!(nonces.next(a), 0);
+    assert_eq!(
+        nonces.apply(a, 1),
+        Err(TxError::NonceGap {
+            expected: 0,
+            got: 1
+        })
+    );
+    nonces.apply(a, 0).unwrap();
+    nonces.apply(a, 1).unwrap();
+    assert_eq!(
+        nonces.apply(a, 1),
+        Err(TxError::NonceTooLow {
+            expected: 2,
+            got: 1
+        })
+    );
+    assert_eq!(nonces.next(a), 2);
+    assert_eq!(nonces.next(b"bob"), 0);
+
+    // The mempool may queue a bounded run ahead of the next nonce.
+    assert_eq!(nonces.check_pending(a, 5, 3), Ok(()));
+    assert!(matches!(
+        nonces.check_pending(a, 6, 3),
+        Err(TxError::NonceGap { .. })
+    ));
+    assert!(matches!(
+        nonces.check_pending(a, 1, 3),
+        Err(TxError::NonceTooLow { .. })
+    ));
+
+    nonces.nonces.insert(a.to_vec(), u64::MAX);
+    assert_eq!(nonces.apply(a, u64::MAX), Err(TxError::NonceOverflow));
+}
+
+#[test]
+fn nonces_persist() {
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    assert_eq!(NonceTracker::load(&st).unwrap(), NonceTracker::default());
+
+    let mut nonces = NonceTracker::default();
+    for n in 0..3 {
+        nonces.apply(b"alice", n).unwrap();
+    }
+    nonces.apply(b"bob", 0).unwrap();
+    nonces.commit(&st).unwrap();
+    assert_eq!(NonceTracker::load(&st).unwrap(), nonces);
+}
@@ -0,0 +1,142 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use std::collections::BTreeSet;
+
+use amunchain::core::security::sign_policy::SignDomain;
+use amunchain::core::types::{Epoch, Signature, ValidatorId};
+use amunchain::node::extensions::GossipHandler;
+use amunchain::node::validator_binding::{
+    binding_signing_bytes, BindingError, BindingRegistry, PeerBinding,
+};
+use libp2p::PeerId;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+
+const CHAIN: &str = "amunchain-test";
+
+fn keypair() -> Ed25519KeyPair {
+    let rng = ring::rand::SystemRandom::new();
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+    Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+}
+
+fn validator(kp: &Ed25519KeyPair) -> ValidatorId {
+    ValidatorId::from_slice(kp.public_key().as_ref()).unwrap()
+}
+
+fn bind(kp: &Ed25519KeyPair, peer: &PeerId, epoch: u64) -> PeerBinding {
+    PeerBinding::sign(CHAIN, validator(kp), peer, Epoch(epoch), |m| {
+        Ok(Signature::from_slice(kp.sign(m).as_ref()).unwrap())
+    })
+    .unwrap()
+}
+
+fn registry(kps: &[&Ed25519KeyPair], max_age: u64) -> BindingRegistry {
+    let set: BTreeSet<ValidatorId> = kps.iter().map(|kp| validator(kp)).collect();
+    BindingRegistry::new(CHAIN, set, max_age).unwrap()
+}
+
+#[test]
+fn bindings_round_trip_and_verify() {
+    let kp = keypair();
+    let peer = PeerId::random();
+    let b = bind(&kp, &peer, 3);
+    assert_eq!(PeerBinding::decode(&b.encode().unwrap()).unwrap(), b);
+    assert_eq!(b.verify(CHAIN).unwrap(), peer);
+
+    let msg = binding_signing_bytes(CHAIN, &b.validator, &b.peer_id, b.epoch).unwrap();
+    assert_eq!(SignDomain::of(&msg), Some(SignDomain::Binding));
+
+    assert!(matches!(
+        b.verify("other-chain"),
+        Err(BindingError::BadSignature)
+    ));
+    let mut moved = b.clone();
+    moved.peer_id = PeerId::random().to_bytes();
+    assert!(matches!(
+        moved.verify(CHAIN),
+        Err(BindingError::BadSignature)
+    ));
+    let mut later = b;
+    later.epoch = Epoch(4);
+    assert!(matches!(
+        later.verify(CHAIN),
+        Err(BindingError::BadSignature)
+    ));
+}
+
+#[test]
+fn registry_only_accepts_the_active_set() {
+    let (kp, outsider) = (keypair(), keypair());
+    let reg = registry(&[&kp], 2);
+    let peer = PeerId::random();
+
+    assert!(matches!(
+        reg.observe(&bind(&outsider, &PeerId::random(), 1)),
+        Err(BindingError::NotValidator)
+    ));
+    assert_eq!(reg.observe(&bind(&kp, &peer, 1)).unwrap(), peer);
+    assert_eq!(reg.validator_of(&peer), Some(validator(&kp)));
+    assert_eq!(
+        reg.validator_of_bytes(&peer.to_bytes()),
+        Some(validator(&kp))
+    );
+    assert_eq!(reg.peer_of(&validator(&kp)), Some(peer));
+    assert_eq!(reg.len(), 1);
+
+    reg.set_validators(BTreeSet::from([validator(&outsider)]));
+    assert!(reg.is_empty());
+    assert_eq!(reg.validator_of(&peer), None);
+}
+
+#[test]
+fn newest_binding_wins_and_old_epochs_expire() {
+    let kp = keypair();
+    let reg = registry(&[&kp], 2);
+    let (old_peer, new_peer) = (PeerId::random(), PeerId::random());
+
+    reg.observe(&bind(&kp, &old_peer, 5)).unwrap();
+    reg.observe(&bind(&kp, &new_peer, 6)).unwrap();
+    assert_eq!(reg.peer_of(&validator(&kp)), Some(new_peer));
+    assert_eq!(reg.validator_of(&old_peer), None);
+    assert!(matches!(
+        reg.observe(&bind(&kp, &old_peer, 5)),
+        Err(BindingError::Stale(5))
+    ));
+
+    reg.set_epoch(Epoch(8));
+    assert_eq!(reg.len(), 1);
+    reg.set_epoch(Epoch(9));
+    assert!(reg.is_empty());
+    assert!(matches!(
+        reg.observe(&bind(&kp, &new_peer, 6)),
+        Err(BindingError::Stale(6))
+    ));
+
+    // Legacy epoch 0 bindings do not expire.
+    reg.observe(&bind(&kp, &new_peer, 0)).unwrap();
+    reg.set_epoch(Epoch(100));
+    assert_eq!(reg.peer_of(&validator(&kp)), Some(new_peer));
+}
+
+#[test]
+fn gossip_handler_records_valid_bindings_and_drops_garbage() {
+    let kp = keypair();
+    let reg = registry(&[&kp], 2);
+    let peer = PeerId::random();
+
+    reg.on_message(b"peer", &[0xff; 40]);
+    assert!(reg.is_empty());
+    reg.on_message(b"peer", &bind(&kp, &peer, 1).encode().unwrap());
+    assert_eq!(reg.validator_of(&peer), Some(validator(&kp)));
+}
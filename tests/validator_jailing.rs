@@ -0,0 +1,118 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::driver::{ConsensusDriver, DriverError};
+use amunchain::core::economics::staking::{
+    unjail_signing_bytes, Offense, StakingError, StakingLedger, StakingParams, UnjailRequest,
+};
+use amunchain::core::types::{Signature, ValidatorId};
+use common::keypairs;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+
+fn id(kp: &Ed25519KeyPair) -> Vec<u8> {
+    kp.public_key().as_ref().to_vec()
+}
+
+fn ledger(kps: &[Ed25519KeyPair]) -> StakingLedger {
+    let mut l = StakingLedger::with_params(StakingParams {
+        min_self_stake: 10,
+        slash_double_sign_bps: 500,
+        jail_slash_threshold_bps: 100,
+        min_jail_secs: 100,
+        ..StakingParams::default()
+    })
+    .unwrap();
+    for kp in kps {
+        l.register_validator(id(kp), 0, 100).unwrap();
+    }
+    l
+}
+
+fn unjail_req(kp: &Ed25519KeyPair, jailed_until: u64) -> UnjailRequest {
+    let msg = unjail_signing_bytes(&id(kp), jailed_until);
+    UnjailRequest {
+        validator: id(kp),
+        signature: Signature::from_slice(kp.sign(&msg).as_ref()).unwrap(),
+    }
+}
+
+#[test]
+fn heavy_slash_jails_and_unjail_checks_period_signature_and_stake() {
+    let kps = keypairs(2);
+    let mut l = ledger(&kps);
+    let v = id(&kps[0]);
+
+    l.slash(&v, Offense::DoubleSign, 1, 1_000);
+    assert!(l.validators[&v].jailed);
+    assert_eq!(l.validators[&v].jailed_until, 1_100);
+    assert!(l.eligible_validators().iter().all(|(id, _)| *id != v));
+
+    assert!(matches!(
+        l.unjail(&unjail_req(&kps[0], 1_100), 1_099),
+        Err(StakingError::StillJailed)
+    ));
+    // Signed by someone else, or for another jail period.
+    let mut forged = unjail_req(&kps[1], 1_100);
+    forged.validator = v.clone();
+    assert!(matches!(
+        l.unjail(&forged, 1_100),
+        Err(StakingError::BadSignature)
+    ));
+    assert!(matches!(
+        l.unjail(&unjail_req(&kps[0], 1_000), 1_100),
+        Err(StakingError::BadSignature)
+    ));
+
+    l.validators.get_mut(&v).unwrap().self_stake = 9;
+    assert!(matches!(
+        l.unjail(&unjail_req(&kps[0], 1_100), 1_100),
+        Err(StakingError::SelfStakeTooLow)
+    ));
+    l.validators.get_mut(&v).unwrap().self_stake = 10;
+    l.unjail(&unjail_req(&kps[0], 1_100), 1_100).unwrap();
+    assert!(!l.validators[&v].jailed);
+    assert!(matches!(
+        l.unjail(&unjail_req(&kps[0], 1_100), 1_200),
+        Err(StakingError::NotJailed)
+    ));
+}
+
+#[test]
+fn driver_rotates_tide_onto_unjailed_set() {
+    let kps = keypairs(4);
+    let mut l = ledger(&kps);
+    let all = kps
+        .iter()
+        .map(|k| ValidatorId::try_from(id(k)).unwrap())
+        .collect();
+    let mut driver = ConsensusDriver::new(all).unwrap();
+
+    l.jail(&id(&kps[3]), 0);
+    driver.sync_staking(&l).unwrap();
+    assert_eq!(driver.tide.validators().len(), 3);
+    assert!(!driver
+        .tide
+        .validators()
+        .contains(&ValidatorId::try_from(id(&kps[3])).unwrap()));
+
+    assert!(matches!(
+        driver.unjail_staked(&mut l, &unjail_req(&kps[3], 100), 50),
+        Err(DriverError::Staking(StakingError::StillJailed))
+    ));
+    driver
+        .unjail_staked(&mut l, &unjail_req(&kps[3], 100), 100)
+        .unwrap();
+    assert_eq!(driver.tide.validators().len(), 4);
+}
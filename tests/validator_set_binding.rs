@@ -0,0 +1,152 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::signing::SigningDomain;
+use amunchain::core::consensus::tide::{
+    expected_set_hash, verify_commit_certificate_for_chain, NoopSlashing, TideConfig, TideError,
+    TideFinalizer, VotingPower,
+};
+use amunchain::core::state::commit_store::FinalityProof;
+use amunchain::core::types::{Commit, Height, Vote, H256};
+use common::{keypairs, sign_v3, unsigned_vote, validators};
+use ring::signature::Ed25519KeyPair;
+
+const CHAIN: &str = "amun-testnet";
+
+fn vote(kp: &Ed25519KeyPair, height: u64, set_hash: H256) -> Vote {
+    let v = unsigned_vote(kp, height, H256::from_bytes([height as u8; 32]));
+    sign_v3(kp, CHAIN, set_hash, v)
+}
+
+fn finalizer(kps: &[Ed25519KeyPair], legacy_until: Option<Height>) -> TideFinalizer<NoopSlashing> {
+    let cfg = TideConfig::new(validators(kps)).with_chain_id(CHAIN, legacy_until);
+    TideFinalizer::new(cfg, NoopSlashing)
+}
+
+fn domain(legacy_until: Option<Height>) -> SigningDomain<'static> {
+    SigningDomain {
+        chain_id: CHAIN,
+        legacy_until_height: legacy_until,
+    }
+}
+
+/// Commit for height 1 from the first three of `kps`, with votes bound to `set_hash`.
+fn commit(kps: &[Ed25519KeyPair], set_hash: H256, legacy_until: Option<Height>) -> Commit {
+    let mut tide = finalizer(kps, legacy_until);
+    let mut c = None;
+    for kp in kps.iter().take(3) {
+        c = tide.process_vote_verified(vote(kp, 1, set_hash)).unwrap();
+    }
+    c.unwrap()
+}
+
+#[test]
+fn commits_carry_and_verify_the_set_hash() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let vsh = expected_set_hash(&set, None).unwrap();
+    let c = commit(&kps, vsh, None);
+    assert_eq!(c.validator_set_hash, vsh);
+    verify_commit_certificate_for_chain(&c, &set, None, domain(None)).unwrap();
+
+    // Same keys, but a power table: a different set as far as the hash is concerned.
+    let power: VotingPower = set.iter().map(|v| (v.clone(), 1)).collect();
+    assert_ne!(expected_set_hash(&set, Some(&power)).unwrap(), vsh);
+    assert_eq!(
+        verify_commit_certificate_for_chain(&c, &set, Some(&power), domain(None)).unwrap_err(),
+        TideError::ValidatorSetMismatch
+    );
+}
+
+#[test]
+fn finality_proofs_for_another_set_are_a_mismatch() {
+    let kps = keypairs(5);
+    let set = validators(&kps[..4]);
+    let vsh = expected_set_hash(&set, None).unwrap();
+    let proof = FinalityProof {
+        commit: commit(&kps[..4], vsh, None),
+        validator_set_hash: vsh,
+    };
+    proof.verify_for_chain(&set, None, domain(None)).unwrap();
+    assert_eq!(
+        proof
+            .verify_for_chain(&validators(&kps), None, domain(None))
+            .unwrap_err(),
+        TideError::ValidatorSetMismatch
+    );
+}
+
+#[test]
+fn rebinding_a_commit_breaks_its_signatures() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let mut c = commit(&kps, expected_set_hash(&set, None).unwrap(), None);
+    let power: VotingPower = set.iter().map(|v| (v.clone(), 1)).collect();
+    c.validator_set_hash = expected_set_hash(&set, Some(&power)).unwrap();
+    assert_eq!(
+        verify_commit_certificate_for_chain(&c, &set, Some(&power), domain(None)).unwrap_err(),
+        TideError::BadSignature
+    );
+}
+
+#[test]
+fn unbound_messages_only_within_the_legacy_window() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+
+    let mut tide = finalizer(&kps, None);
+    assert_eq!(
+        tide.process_vote_verified(vote(&kps[0], 1, H256::ZERO))
+            .unwrap_err(),
+        TideError::BadSignature
+    );
+
+    // Unbound votes inside the window still assemble a bound commit.
+    let c = commit(&kps, H256::ZERO, Some(Height(10)));
+    assert_eq!(c.validator_set_hash, expected_set_hash(&set, None).unwrap());
+    verify_commit_certificate_for_chain(&c, &set, None, domain(Some(Height(10)))).unwrap();
+
+    let mut legacy = c.clone();
+    legacy.validator_set_hash = H256::ZERO;
+    verify_commit_certificate_for_chain(&legacy, &set, None, domain(Some(Height(10)))).unwrap();
+    assert_eq!(
+        verify_commit_certificate_for_chain(&legacy, &set, None, domain(None)).unwrap_err(),
+        TideError::ValidatorSetMismatch
+    );
+}
+
+#[test]
+fn votes_for_the_previous_set_are_stale_not_forged() {
+    let kps = keypairs(5);
+    let mut tide = finalizer(&kps[..4], None);
+    let old = tide.validator_set_hash().unwrap();
+    tide.set_validators(validators(&kps));
+    assert_ne!(tide.validator_set_hash().unwrap(), old);
+
+    let err = tide
+        .process_vote_verified(vote(&kps[0], 1, old))
+        .unwrap_err();
+    assert_eq!(err, TideError::ValidatorSetMismatch);
+    assert!(!err.is_invalid());
+
+    let mut forged = vote(&kps[0], 1, old);
+    forged.block_hash = H256::from_bytes([0xee; 32]);
+    assert_eq!(
+        tide.process_vote_verified(forged).unwrap_err(),
+        TideError::BadSignature
+    );
+    tide.process_vote_verified(vote(&kps[0], 1, tide.validator_set_hash().unwrap()))
+        .unwrap();
+}
@@ -0,0 +1,139 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::consensus::signing::vote_signing_bytes_auto;
+use amunchain::core::security::keystore::verify_pubkey_bytes;
+use amunchain::core::state::merkle::merkle_root_sorted;
+use amunchain::core::types::{
+    decode_canonical_limited, Commit, Epoch, Height, Round, Signature, ValidatorId, H256,
+};
+use amunchain::networking::peer_registry::{load_and_verify_peer_registry, PeerRegistryPolicy};
+use amunchain::node::vectors::{generate, VECTORS_VERSION};
+use serde_json::Value;
+
+fn unhex(v: &Value) -> Vec<u8> {
+    hex::decode(v.as_str().unwrap()).unwrap()
+}
+
+fn h256(v: &Value) -> H256 {
+    H256::from_bytes(unhex(v).try_into().unwrap())
+}
+
+fn verifies(pubkey: &[u8], msg: &[u8], sig: &Value) -> bool {
+    let pk: [u8; 32] = pubkey.try_into().unwrap();
+    let sig = Signature::from_slice(&unhex(sig)).unwrap();
+    verify_pubkey_bytes(&pk, msg, &sig).is_ok()
+}
+
+#[test]
+fn output_is_deterministic() {
+    let a = generate().unwrap();
+    assert_eq!(a, generate().unwrap());
+    assert_eq!(a["version"], VECTORS_VERSION);
+    assert_eq!(
+        serde_json::to_string(&a).unwrap(),
+        serde_json::to_string(&generate().unwrap()).unwrap()
+    );
+}
+
+#[test]
+fn vote_vectors_match_the_signing_code() {
+    let doc = generate().unwrap();
+    for (key, count) in [("vote_signing_bytes_v1", 3), ("vote_signing_bytes_v2", 3)] {
+        let cases = doc[key].as_array().unwrap();
+        assert_eq!(cases.len(), count);
+        for c in cases {
+            let voter = unhex(&c["voter"]);
+            let bytes = vote_signing_bytes_auto(
+                Height(c["height"].as_u64().unwrap()),
+                Round(c["round"].as_u64().unwrap()),
+                Epoch(c["epoch"].as_u64().unwrap_or(0)),
+                c["msg_counter"].as_u64().unwrap_or(0),
+                c["sent_ts_ms"].as_u64().unwrap_or(0),
+                c["ttl_ms"].as_u64().unwrap_or(0) as u32,
+                h256(&c["block_hash"]),
+                &ValidatorId::from_slice(&voter).unwrap(),
+            )
+            .unwrap();
+            assert_eq!(bytes, unhex(&c["bytes"]), "{key} {}", c["name"]);
+            assert!(verifies(&voter, &bytes, &c["signature"]));
+        }
+    }
+    let first = &doc["vote_signing_bytes_v1"][0]["bytes"];
+    assert!(unhex(first).starts_with(b"Amunchain-Tide-Vote-v1"));
+}
+
+#[test]
+fn commit_vectors_decode_to_their_fields() {
+    let doc = generate().unwrap();
+    for c in doc["commit_canonical_bytes"].as_array().unwrap() {
+        let commit: Commit = decode_canonical_limited(&unhex(&c["bytes"]), 1 << 16).unwrap();
+        assert_eq!(commit.height.get(), c["height"].as_u64().unwrap());
+        assert_eq!(commit.epoch.get(), c["epoch"].as_u64().unwrap());
+        assert_eq!(commit.block_hash, h256(&c["block_hash"]));
+        assert_eq!(commit.validator_set_hash, h256(&c["validator_set_hash"]));
+        let sigs = c["signatures"].as_array().unwrap();
+        assert_eq!(commit.signatures.len(), sigs.len());
+        for (s, (v, sig)) in sigs.iter().zip(&commit.signatures) {
+            assert_eq!(unhex(&s["validator"]), v.as_bytes().to_vec());
+            assert_eq!(unhex(&s["signature"]), sig.as_bytes().to_vec());
+        }
+    }
+}
+
+#[test]
+fn merkle_roots_match() {
+    let doc = generate().unwrap();
+    let roots = doc["merkle"]["roots"].as_array().unwrap();
+    assert_eq!(roots[0]["root"], hex::encode([0u8; 32]));
+    for r in roots {
+        let pairs: Vec<(Vec<u8>, Vec<u8>)> = r["pairs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| (unhex(&p["key"]), unhex(&p["value"])))
+            .collect();
+        assert_eq!(unhex(&r["root"]), merkle_root_sorted(&pairs).to_vec());
+    }
+    // A single pair's root is its leaf hash.
+    let leaf = &doc["merkle"]["leaves"][1];
+    let single = roots
+        .iter()
+        .find(|r| r["pairs"].as_array().unwrap().len() == 1);
+    assert_eq!(single.unwrap()["pairs"][0]["key"], leaf["key"]);
+    assert_eq!(single.unwrap()["root"], leaf["hash"]);
+}
+
+#[test]
+fn registry_vector_verifies() {
+    let doc = generate().unwrap();
+    let reg = &doc["peer_registry"][0];
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("peers.toml");
+    std::fs::write(&path, reg["toml"].as_str().unwrap()).unwrap();
+
+    let mut policy = PeerRegistryPolicy::default_with_now(1_700_000_000_001);
+    policy.expected_network = Some("amun-testnet");
+    let peers = load_and_verify_peer_registry(
+        path.to_str().unwrap(),
+        reg["pubkey"].as_str().unwrap(),
+        &policy,
+    )
+    .unwrap();
+    assert_eq!(peers.len(), 3);
+    assert!(verifies(
+        &unhex(&reg["pubkey"]),
+        &unhex(&reg["bytes"]),
+        &reg["signature"]
+    ));
+}
@@ -0,0 +1,74 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::security::keystore::{verify_pubkey_bytes, PublicKeyCache};
+use amunchain::core::types::Signature;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+
+fn key(n: u8) -> (Ed25519KeyPair, [u8; 32]) {
+    let kp = Ed25519KeyPair::from_seed_unchecked(&[n; 32]).unwrap();
+    let pk = kp.public_key().as_ref().try_into().unwrap();
+    (kp, pk)
+}
+
+fn sig(kp: &Ed25519KeyPair, msg: &[u8]) -> Signature {
+    Signature::from_slice(kp.sign(msg).as_ref()).unwrap()
+}
+
+#[test]
+fn cached_verification_matches_uncached() {
+    let (kp, pk) = key(1);
+    let (other_kp, other_pk) = key(2);
+    let cache = PublicKeyCache::new([&pk, &other_pk]);
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains(&pk));
+
+    let good = sig(&kp, b"vote");
+    let mut high_s = *good.as_bytes();
+    high_s[63] |= 0x80;
+    let mut flipped = *good.as_bytes();
+    flipped[0] ^= 1;
+    let cases = [
+        (pk, b"vote".as_slice(), good.clone()),
+        (pk, b"vote!".as_slice(), good.clone()),
+        (other_pk, b"vote".as_slice(), good.clone()),
+        (other_pk, b"vote".as_slice(), sig(&other_kp, b"vote")),
+        (pk, b"vote".as_slice(), Signature::from_bytes(high_s)),
+        (pk, b"vote".as_slice(), Signature::from_bytes(flipped)),
+        (pk, b"vote".as_slice(), Signature::from_bytes([0; 64])),
+    ];
+    let expected = [true, false, false, true, false, false, false];
+    for ((pk, msg, s), ok) in cases.iter().zip(expected) {
+        assert_eq!(verify_pubkey_bytes(pk, msg, s).is_ok(), ok);
+        assert_eq!(cache.verify(pk, msg, s).is_ok(), ok);
+    }
+}
+
+#[test]
+fn uncached_keys_fall_back() {
+    let (kp, pk) = key(3);
+    let empty = PublicKeyCache::default();
+    assert!(empty.is_empty());
+    assert!(empty.verify(&pk, b"m", &sig(&kp, b"m")).is_ok());
+    assert!(empty.verify(&pk, b"x", &sig(&kp, b"m")).is_err());
+
+    // Non-canonical encodings are not cached, so they verify exactly as without a cache.
+    let bogus = [0xff; 32];
+    let cache = PublicKeyCache::new([&bogus]);
+    assert!(!cache.contains(&bogus));
+    let s = sig(&kp, b"m");
+    assert_eq!(
+        cache.verify(&bogus, b"m", &s).is_ok(),
+        verify_pubkey_bytes(&bogus, b"m", &s).is_ok()
+    );
+}
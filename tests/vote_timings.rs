@@ -0,0 +1,156 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::driver::ConsensusDriver;
+use amunchain::core::consensus::tide::{expected_set_hash, DEFAULT_CHAIN_ID};
+use amunchain::core::consensus::vote_timing::{VoteTimings, LATENCY_WINDOW};
+use amunchain::core::state::commit_store::CommitStore;
+use amunchain::core::state::persistent_state::PersistentState;
+use amunchain::core::types::{
+    CanonicalMap, Commit, ConsensusMsg, Epoch, Height, Round, Signature, ValidatorId, Vote, H256,
+};
+use amunchain::monitoring::metrics::Metrics;
+use common::{keypairs, sign_v3, unsigned_vote, validators};
+use ring::signature::Ed25519KeyPair;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+fn timed_vote(kp: &Ed25519KeyPair, set: &BTreeSet<ValidatorId>, sent_ts_ms: u64) -> Vote {
+    let v = Vote {
+        msg_counter: 1,
+        sent_ts_ms,
+        ttl_ms: 30_000,
+        ..unsigned_vote(kp, 1, H256::from_bytes([7; 32]))
+    };
+    sign_v3(
+        kp,
+        DEFAULT_CHAIN_ID,
+        expected_set_hash(set, None).unwrap(),
+        v,
+    )
+}
+
+fn commit(signers: &[ValidatorId], height: u64, sent_ts_ms: u64) -> Commit {
+    let signatures: CanonicalMap<ValidatorId, Signature> = signers
+        .iter()
+        .map(|v| (v.clone(), Signature::from_slice(&[0; 64]).unwrap()))
+        .collect();
+    Commit {
+        height: Height(height),
+        round: Round::ZERO,
+        epoch: Epoch(1),
+        msg_counter: 1,
+        sent_ts_ms,
+        ttl_ms: 0,
+        block_hash: H256::from_bytes([height as u8; 32]),
+        signatures,
+        voting_power: 0,
+        validator_set_hash: H256::ZERO,
+    }
+}
+
+#[test]
+fn finalized_heights_keep_their_vote_arrivals() {
+    let kps = keypairs(4);
+    let set = validators(&kps);
+    let dir = tempfile::tempdir().unwrap();
+    let st = PersistentState::open(dir.path().to_str().unwrap()).unwrap();
+    let store = CommitStore::open(&st).unwrap();
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let mut driver = ConsensusDriver::new(set.clone())
+        .unwrap()
+        .with_metrics(metrics.clone())
+        .with_commit_store(store.clone());
+
+    let sent = now_ms().saturating_sub(40);
+    for kp in kps.iter().take(3) {
+        driver.on_msg(ConsensusMsg::Vote(timed_vote(kp, &set, sent)));
+    }
+    assert_eq!(driver.tide.finalized_height(), Height(1));
+
+    let timings = driver.commit_timings(1).unwrap().expect("height 1 timed");
+    assert_eq!(store.timings(1).unwrap(), Some(timings.clone()));
+    assert_eq!((timings.height, timings.sent_ts_ms), (Height(1), sent));
+    assert_eq!(timings.votes.len(), 3);
+    for a in &timings.votes {
+        assert!(timings.vote_delay_ms(&a.validator).unwrap() >= 40);
+    }
+    assert!(timings.finality_ms().unwrap() >= 40);
+    assert!(driver.commit_timings(2).unwrap().is_none());
+
+    let report = driver.vote_timings().report();
+    assert_eq!(report.len(), 3);
+    assert!(report.iter().all(|p| p.samples == 1 && p.p50_ms >= 40));
+    assert_eq!(
+        metrics
+            .consensus_finality_latency_seconds
+            .get_sample_count(),
+        1
+    );
+    let label = &report[0].validator;
+    let gauge = metrics
+        .consensus_validator_vote_delay_ms
+        .with_label_values(&[label]);
+    assert_eq!(gauge.get(), report[0].p50_ms as i64);
+}
+
+#[test]
+fn delays_roll_over_a_window_per_validator() {
+    let ids: Vec<ValidatorId> = (1u8..=2)
+        .map(|i| ValidatorId::from_slice(&[i; 32]).unwrap())
+        .collect();
+    let mut timings = VoteTimings::default();
+    // ids[0] is 10 ms late at height h, plus h; ids[1] votes only at height 1.
+    for h in 1..=(LATENCY_WINDOW as u64 + 100) {
+        let sent = 1_000_000 + h * 1_000;
+        timings.on_vote(Height(h), Round::ZERO, &ids[0], sent + 10 + h);
+        if h == 1 {
+            timings.on_vote(Height(h), Round::ZERO, &ids[1], sent + 5);
+        }
+        let t = timings.on_commit(&commit(&ids, h, sent), sent + 500);
+        assert_eq!(t.votes.len(), if h == 1 { 2 } else { 1 });
+    }
+
+    let fast = timings.performance(&ids[1]).unwrap();
+    assert_eq!((fast.samples, fast.p50_ms, fast.max_ms), (1, 5, 5));
+    let slow = timings.performance(&ids[0]).unwrap();
+    assert_eq!(slow.samples, LATENCY_WINDOW);
+    // The window holds delays 111..=622.
+    assert_eq!((slow.p50_ms, slow.max_ms), (366, 622));
+    assert_eq!(slow.p99_ms, 617);
+    assert_eq!(timings.report().len(), 2);
+}
+
+#[test]
+fn legacy_and_unseen_votes_are_not_timed() {
+    let id = ValidatorId::from_slice(&[3; 32]).unwrap();
+    let mut timings = VoteTimings::default();
+    timings.on_vote(Height(1), Round::ZERO, &id, 5_000);
+    let t = timings.on_commit(&commit(std::slice::from_ref(&id), 1, 0), 6_000);
+    assert_eq!((t.finality_ms(), t.vote_delay_ms(&id)), (None, None));
+
+    // A commit from a peer whose votes never reached this node.
+    let t = timings.on_commit(&commit(std::slice::from_ref(&id), 2, 5_000), 6_000);
+    assert!(t.votes.is_empty());
+    assert_eq!(t.finality_ms(), Some(1_000));
+    assert!(timings.performance(&id).is_none());
+}
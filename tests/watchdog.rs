@@ -0,0 +1,82 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::monitoring::health::{HealthMonitor, HealthStatus, ReadinessCriteria};
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::monitoring::watchdog::Watchdog;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn tasks_status(health: &HealthMonitor) -> HealthStatus {
+    health
+        .report()
+        .checks
+        .into_iter()
+        .find(|c| c.name == "tasks")
+        .unwrap()
+        .status
+}
+
+#[tokio::test]
+async fn panicking_task_is_reported() {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let watchdog = Watchdog::new(metrics.clone(), true);
+    let health = HealthMonitor::new(ReadinessCriteria::default(), metrics.clone())
+        .with_watchdog(watchdog.clone());
+    assert_eq!(tasks_status(&health), HealthStatus::Healthy);
+
+    let task = tokio::spawn(async { panic!("swarm gone") });
+    watchdog.watch("p2p", task).await.unwrap();
+
+    let failure = watchdog.failure().unwrap();
+    assert_eq!(failure.task, "p2p");
+    assert!(failure.reason.contains("swarm gone"));
+    assert_eq!(
+        metrics
+            .node_task_failures_total
+            .with_label_values(&["p2p"])
+            .get(),
+        1
+    );
+    assert_eq!(tasks_status(&health), HealthStatus::Unhealthy);
+}
+
+#[tokio::test]
+async fn exited_task_keeps_node_up_without_exit() {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let watchdog = Watchdog::new(metrics, false);
+    let watched = watchdog.watch("p2p-events", tokio::spawn(async {}));
+
+    // The failure is recorded but the watcher stays pending, so the node is not torn down.
+    let pending = tokio::time::timeout(Duration::from_millis(100), watched).await;
+    assert!(pending.is_err());
+    assert_eq!(watchdog.failure().unwrap().reason, "exited");
+}
+
+#[tokio::test]
+async fn teardown_is_not_a_failure() {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let watchdog = Watchdog::new(metrics, true);
+    let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+    let inner = tokio::spawn(async move {
+        let _tx = tx;
+        std::future::pending::<()>().await;
+    });
+    let watched = watchdog.watch("p2p", inner);
+    watched.abort();
+    let _ = watched.await;
+
+    // Aborting the watcher aborts the supervised task (its sender is dropped).
+    assert!(rx.await.is_err());
+    assert!(watchdog.failure().is_none());
+}
@@ -0,0 +1,154 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::config::ConfigLoader;
+use amunchain::core::types::{
+    Commit, ConsensusMsg, Epoch, Height, Round, Signature, ValidatorId, Vote, H256,
+};
+use amunchain::networking::wire::{decode_msg, WireCodec, WireError, MAX_WIRE_BYTES, WIRE_V2};
+use amunchain::networking::wire_limits::{MsgClass, WireLimits, WireLimitsError};
+use amunchain::node::config_check::check;
+
+fn vote() -> ConsensusMsg {
+    ConsensusMsg::Vote(Vote {
+        height: Height(5),
+        round: Round(1),
+        epoch: Epoch(1),
+        msg_counter: 2,
+        sent_ts_ms: 3,
+        ttl_ms: 4,
+        block_hash: H256::from_bytes([7; 32]),
+        voter: ValidatorId::from_bytes([1; 32]),
+        signature: Signature::from_bytes([2; 64]),
+    })
+}
+
+fn commit(signers: u16) -> ConsensusMsg {
+    let signatures = (0..signers)
+        .map(|i| {
+            let mut id = [0u8; 32];
+            id[..2].copy_from_slice(&i.to_be_bytes());
+            (ValidatorId::from_bytes(id), Signature::from_bytes([1; 64]))
+        })
+        .collect();
+    ConsensusMsg::Commit(Commit {
+        height: Height(5),
+        round: Round(1),
+        epoch: Epoch(1),
+        msg_counter: 2,
+        sent_ts_ms: 3,
+        ttl_ms: 4,
+        block_hash: H256::from_bytes([7; 32]),
+        signatures,
+        voting_power: u128::from(signers),
+        validator_set_hash: H256::ZERO,
+    })
+}
+
+#[test]
+fn each_class_is_held_to_its_own_limit() {
+    let limits = WireLimits {
+        vote_bytes: 512,
+        ..WireLimits::default()
+    };
+    for codec in [WireCodec::Bincode, WireCodec::Protobuf] {
+        let vote_bytes = codec.encode(&vote(), WIRE_V2).unwrap();
+        assert!(codec.decode_within(&vote_bytes, &limits).is_ok());
+
+        // A commit larger than the vote limit is fine; a vote that large is not.
+        let big = codec.encode(&commit(8), WIRE_V2).unwrap();
+        assert!(big.len() > limits.vote_bytes);
+        assert!(codec.decode_within(&big, &limits).is_ok());
+        let tight = WireLimits {
+            vote_bytes: 512,
+            commit_bytes: 512,
+            ..WireLimits::default()
+        };
+        assert!(matches!(
+            codec.decode_within(&big, &tight),
+            Err(WireError::TooLarge)
+        ));
+        assert!(matches!(
+            codec.encode_within(&commit(8), WIRE_V2, &tight),
+            Err(WireError::TooLarge)
+        ));
+    }
+
+    let bytes = WireCodec::Bincode.encode(&vote(), WIRE_V2).unwrap();
+    let below = WireLimits {
+        vote_bytes: bytes.len() - 1,
+        ..WireLimits::default()
+    };
+    assert!(matches!(
+        WireCodec::Bincode.decode_within(&bytes, &below),
+        Err(WireError::TooLarge)
+    ));
+}
+
+#[test]
+fn commits_may_exceed_the_default_cap_when_configured() {
+    let big = commit(3_000);
+    assert!(matches!(
+        WireCodec::Bincode.encode(&big, WIRE_V2),
+        Err(WireError::TooLarge)
+    ));
+
+    let limits = WireLimits {
+        commit_bytes: 1024 * 1024,
+        ..WireLimits::default()
+    };
+    let bytes = WireCodec::Bincode
+        .encode_within(&big, WIRE_V2, &limits)
+        .unwrap();
+    assert!(bytes.len() > MAX_WIRE_BYTES);
+    assert!(matches!(decode_msg(&bytes), Err(WireError::TooLarge)));
+    let back = WireCodec::Bincode.decode_within(&bytes, &limits).unwrap();
+    assert_eq!(MsgClass::of(&back), MsgClass::Commit);
+
+    // Gossipsub frames grow with the largest class, not per message.
+    assert!(limits.transmit_size() > limits.commit_bytes);
+    assert!(WireLimits::default().transmit_size() < limits.commit_bytes);
+}
+
+#[test]
+fn limits_are_configured_and_checked() {
+    let limits = WireLimits {
+        vote_bytes: 100,
+        evidence_bytes: MAX_WIRE_BYTES + 1,
+        ..WireLimits::default()
+    };
+    let fields: Vec<&str> = limits
+        .validate()
+        .iter()
+        .map(WireLimitsError::field)
+        .collect();
+    assert_eq!(fields, ["vote_bytes", "evidence_bytes"]);
+    assert!(WireLimits::default().validate().is_empty());
+
+    let cfg = ConfigLoader::new()
+        .set("p2p.limits.commit_bytes", "1048576")
+        .set("p2p.limits.vote_bytes", "64")
+        .load()
+        .unwrap();
+    assert_eq!(cfg.p2p.limits.commit_bytes, 1024 * 1024);
+    assert_eq!(cfg.p2p.limits.other_bytes, 64 * 1024);
+    let issues: Vec<String> = check(&cfg).into_iter().map(|i| i.field).collect();
+    assert!(issues.contains(&"p2p.limits.vote_bytes".to_string()));
+    assert!(!issues.contains(&"p2p.limits.commit_bytes".to_string()));
+
+    let err = ConfigLoader::new()
+        .set("p2p.limits.block_bytes", "1")
+        .load()
+        .unwrap_err();
+    assert!(err.to_string().contains("block_bytes"), "{err}");
+}
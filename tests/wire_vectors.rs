@@ -0,0 +1,207 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+//! Golden canonical encodings of the consensus types and the pinned `wire_digest`. A failure
+//! here means the encoding changed: if that was intended, bump the wire version and update the
+//! constants; otherwise it is a compatibility break. Gossip frames are covered separately by
+//! `codec_vectors.rs`.
+
+use amunchain::core::state::commit_store::FinalityProof;
+use amunchain::core::types::{
+    decode_canonical_json, decode_canonical_limited, encode_canonical, encode_canonical_json,
+    wire_digest, CanonicalMap, Commit, ConsensusMsg, Epoch, Height, Round, Signature, ValidatorId,
+    Vote, H256,
+};
+use amunchain::networking::proto;
+use proptest::prelude::*;
+
+const MAX: usize = 256 * 1024;
+
+const WIRE_DIGEST: &str = "27e387b9a77db4e60e1e0beae9e32b60f800697b9e708524e9f7b41bbaa5fab3";
+
+const VOTE: &str = concat!(
+    "0c00000000000000010000000000000002000000000000000300000000000000",
+    "040000000000000005000000bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+    "bbbbbbbbbbbbbbbbbbbbbbbb2000000000000000010101010101010101010101",
+    "0101010101010101010101010101010101010101400000000000000002020202",
+    "0202020202020202020202020202020202020202020202020202020202020202",
+    "02020202020202020202020202020202020202020202020202020202",
+);
+
+const COMMIT: &str = concat!(
+    "0c00000000000000010000000000000002000000000000000000000000000000",
+    "000000000000000000000000bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+    "bbbbbbbbbbbbbbbbbbbbbbbb0200000000000000200000000000000001010101",
+    "0101010101010101010101010101010101010101010101010101010140000000",
+    "0000000002020202020202020202020202020202020202020202020202020202",
+    "0202020202020202020202020202020202020202020202020202020202020202",
+    "0202020220000000000000000303030303030303030303030303030303030303",
+    "0303030303030303030303034000000000000000040404040404040404040404",
+    "0404040404040404040404040404040404040404040404040404040404040404",
+    "0404040404040404040404040404040404040404020000000000000000000000",
+    "00000000dddddddddddddddddddddddddddddddddddddddddddddddddddddddd",
+    "dddddddd",
+);
+
+const FINALITY_PROOF: &str = concat!(
+    "0c00000000000000010000000000000002000000000000000000000000000000",
+    "000000000000000000000000bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+    "bbbbbbbbbbbbbbbbbbbbbbbb0200000000000000200000000000000001010101",
+    "0101010101010101010101010101010101010101010101010101010140000000",
+    "0000000002020202020202020202020202020202020202020202020202020202",
+    "0202020202020202020202020202020202020202020202020202020202020202",
+    "0202020220000000000000000303030303030303030303030303030303030303",
+    "0303030303030303030303034000000000000000040404040404040404040404",
+    "0404040404040404040404040404040404040404040404040404040404040404",
+    "0404040404040404040404040404040404040404020000000000000000000000",
+    "00000000dddddddddddddddddddddddddddddddddddddddddddddddddddddddd",
+    "ddddddddcccccccccccccccccccccccccccccccccccccccccccccccccccccccc",
+    "cccccccc",
+);
+
+fn vote() -> Vote {
+    Vote {
+        height: Height(12),
+        round: Round(1),
+        epoch: Epoch(2),
+        msg_counter: 3,
+        sent_ts_ms: 4,
+        ttl_ms: 5,
+        block_hash: H256::from_bytes([0xbb; 32]),
+        voter: ValidatorId::from_bytes([0x01; 32]),
+        signature: Signature::from_bytes([0x02; 64]),
+    }
+}
+
+fn commit() -> Commit {
+    Commit {
+        height: Height(12),
+        round: Round(1),
+        epoch: Epoch(2),
+        msg_counter: 0,
+        sent_ts_ms: 0,
+        ttl_ms: 0,
+        block_hash: H256::from_bytes([0xbb; 32]),
+        signatures: CanonicalMap::from([
+            (
+                ValidatorId::from_bytes([0x03; 32]),
+                Signature::from_bytes([0x04; 64]),
+            ),
+            (
+                ValidatorId::from_bytes([0x01; 32]),
+                Signature::from_bytes([0x02; 64]),
+            ),
+        ]),
+        voting_power: 2,
+        validator_set_hash: H256::from_bytes([0xdd; 32]),
+    }
+}
+
+#[test]
+fn golden_encodings() {
+    let proof = FinalityProof {
+        commit: commit(),
+        validator_set_hash: H256::from_bytes([0xcc; 32]),
+    };
+    for (name, bytes, golden) in [
+        ("vote", encode_canonical(&vote()).unwrap(), VOTE),
+        ("commit", encode_canonical(&commit()).unwrap(), COMMIT),
+        ("proof", encode_canonical(&proof).unwrap(), FINALITY_PROOF),
+    ] {
+        assert_eq!(hex::encode(bytes), golden, "{name}");
+    }
+}
+
+#[test]
+fn wire_digest_is_pinned() {
+    assert_eq!(hex::encode(wire_digest().unwrap().as_bytes()), WIRE_DIGEST);
+}
+
+prop_compose! {
+    fn arb_vote()(
+        counters in any::<[u64; 5]>(),
+        ttl_ms in any::<u32>(),
+        hash in any::<[u8; 32]>(),
+        voter in any::<[u8; 32]>(),
+        sig in prop::collection::vec(any::<u8>(), 64),
+    ) -> Vote {
+        Vote {
+            height: Height(counters[0]),
+            round: Round(counters[1]),
+            epoch: Epoch(counters[2]),
+            msg_counter: counters[3],
+            sent_ts_ms: counters[4],
+            ttl_ms,
+            block_hash: H256::from_bytes(hash),
+            voter: ValidatorId::from_bytes(voter),
+            signature: Signature::from_slice(&sig).unwrap(),
+        }
+    }
+}
+
+prop_compose! {
+    fn arb_commit()(
+        v in arb_vote(),
+        signers in prop::collection::btree_map(any::<[u8; 32]>(), any::<u8>(), 0..6),
+        voting_power in any::<u128>(),
+        set_hash in any::<[u8; 32]>(),
+    ) -> Commit {
+        Commit {
+            height: v.height,
+            round: v.round,
+            epoch: v.epoch,
+            msg_counter: v.msg_counter,
+            sent_ts_ms: v.sent_ts_ms,
+            ttl_ms: v.ttl_ms,
+            block_hash: v.block_hash,
+            signatures: signers
+                .into_iter()
+                .map(|(id, s)| (ValidatorId::from_bytes(id), Signature::from_bytes([s; 64])))
+                .collect(),
+            voting_power,
+            validator_set_hash: H256::from_bytes(set_hash),
+        }
+    }
+}
+
+fn arb_msg() -> impl Strategy<Value = ConsensusMsg> {
+    prop_oneof![
+        arb_vote().prop_map(ConsensusMsg::Vote),
+        arb_commit().prop_map(ConsensusMsg::Commit),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn prop_every_encoding_round_trips(msg in arb_msg()) {
+        let bytes = encode_canonical(&msg).unwrap();
+
+        let back: ConsensusMsg = decode_canonical_limited(&bytes, MAX).unwrap();
+        prop_assert_eq!(encode_canonical(&back).unwrap(), bytes.clone());
+
+        let json = encode_canonical_json(&msg).unwrap();
+        let back: ConsensusMsg = decode_canonical_json(&json, MAX).unwrap();
+        prop_assert_eq!(encode_canonical(&back).unwrap(), bytes.clone());
+
+        let pb = proto::encode_msg(&msg).unwrap();
+        let back = proto::decode_msg(&pb).unwrap();
+        prop_assert_eq!(encode_canonical(&back).unwrap(), bytes);
+    }
+
+    #[test]
+    fn prop_truncation_never_decodes(msg in arb_msg(), cut in any::<prop::sample::Index>()) {
+        let bytes = encode_canonical(&msg).unwrap();
+        let short = &bytes[..cut.index(bytes.len())];
+        prop_assert!(decode_canonical_limited::<ConsensusMsg>(short, MAX).is_err());
+    }
+}
@@ -0,0 +1,274 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use amunchain::core::types::{
+    encode_canonical, Commit, ConsensusMsg, Epoch, Height, Round, Signature, ValidatorId, Vote,
+    H256,
+};
+use amunchain::networking::wire::{
+    decode_and_validate_consensus_msg, decode_msg, encode_msg, identify_protocol, negotiate,
+    parse_identify_protocol, PeerVersions, WireCodec, WireEnvelope, WireError, ENVELOPE_MAGIC,
+    MAX_WIRE_BYTES, SUPPORTED_WIRE_VERSIONS, WIRE_V1, WIRE_V2, WIRE_V3,
+};
+
+fn msg() -> ConsensusMsg {
+    ConsensusMsg::Vote(Vote {
+        height: Height(5),
+        round: Round(1),
+        epoch: Epoch(1),
+        msg_counter: 2,
+        sent_ts_ms: 3,
+        ttl_ms: 4,
+        block_hash: H256::from_bytes([7; 32]),
+        voter: ValidatorId::from_bytes([1; 32]),
+        signature: Signature::from_bytes([2; 64]),
+    })
+}
+
+fn with_height(h: u64) -> ConsensusMsg {
+    let ConsensusMsg::Vote(mut v) = msg() else {
+        unreachable!()
+    };
+    v.height = Height(h);
+    ConsensusMsg::Vote(v)
+}
+
+fn height(m: &ConsensusMsg) -> u64 {
+    match m {
+        ConsensusMsg::Vote(v) => v.height.get(),
+        ConsensusMsg::Commit(c) => c.height.get(),
+    }
+}
+
+#[test]
+fn v1_is_the_legacy_encoding_and_both_versions_decode() {
+    let legacy = bincode::serialize(&msg()).unwrap();
+    assert_eq!(encode_msg(&msg(), WIRE_V1).unwrap(), legacy);
+    let (v, m) = decode_msg(&legacy).unwrap();
+    assert_eq!((v, height(&m)), (WIRE_V1, 5));
+
+    let v2 = encode_msg(&msg(), WIRE_V2).unwrap();
+    assert!(v2.starts_with(&ENVELOPE_MAGIC));
+    let (v, m) = decode_msg(&v2).unwrap();
+    assert_eq!((v, height(&m)), (WIRE_V2, 5));
+
+    // A v1 payload inside an envelope is read too.
+    let wrapped = WireEnvelope {
+        version: WIRE_V1,
+        payload: legacy,
+    };
+    let mut bytes = ENVELOPE_MAGIC.to_vec();
+    bytes.extend(encode_canonical(&wrapped).unwrap());
+    assert_eq!(decode_msg(&bytes).unwrap().0, WIRE_V1);
+}
+
+#[test]
+fn unknown_versions_and_garbage_are_rejected() {
+    assert!(matches!(
+        encode_msg(&msg(), 9),
+        Err(WireError::UnsupportedVersion(9))
+    ));
+
+    let future = WireEnvelope {
+        version: 4,
+        payload: vec![1, 2, 3],
+    };
+    let mut bytes = ENVELOPE_MAGIC.to_vec();
+    bytes.extend(encode_canonical(&future).unwrap());
+    assert!(matches!(
+        decode_msg(&bytes),
+        Err(WireError::UnsupportedVersion(4))
+    ));
+
+    let mut trailing = encode_msg(&msg(), WIRE_V2).unwrap();
+    trailing.push(0);
+    assert!(matches!(decode_msg(&trailing), Err(WireError::Malformed)));
+    assert!(matches!(
+        decode_msg(&ENVELOPE_MAGIC),
+        Err(WireError::Malformed)
+    ));
+    assert!(matches!(
+        decode_msg(&vec![0; MAX_WIRE_BYTES + 1]),
+        Err(WireError::TooLarge)
+    ));
+}
+
+#[test]
+fn identify_strings_advertise_versions() {
+    let ours = identify_protocol(SUPPORTED_WIRE_VERSIONS);
+    assert_eq!(ours, "amunchain/1.0.0 wire=1,2,3");
+    assert_eq!(parse_identify_protocol(&ours), SUPPORTED_WIRE_VERSIONS);
+    // Nodes from before the envelope advertise no versions.
+    assert_eq!(parse_identify_protocol("amunchain/1.0.0"), [WIRE_V1]);
+    assert_eq!(
+        parse_identify_protocol("amunchain/1.1.0 wire=3,x,2,2"),
+        [2, 3]
+    );
+
+    assert_eq!(
+        negotiate(SUPPORTED_WIRE_VERSIONS, &[1, 2, 3]),
+        Some(WIRE_V3)
+    );
+    assert_eq!(negotiate(SUPPORTED_WIRE_VERSIONS, &[1, 2]), Some(WIRE_V2));
+    assert_eq!(negotiate(SUPPORTED_WIRE_VERSIONS, &[1]), Some(WIRE_V1));
+    assert_eq!(negotiate(SUPPORTED_WIRE_VERSIONS, &[4]), None);
+}
+
+#[test]
+fn publish_version_follows_the_oldest_peer() {
+    let mut peers = PeerVersions::new(SUPPORTED_WIRE_VERSIONS);
+    assert_eq!(peers.send_version(), WIRE_V3);
+
+    peers.connected(b"a".to_vec());
+    peers.connected(b"b".to_vec());
+    // Not identified yet: assume v1.
+    assert_eq!(peers.send_version(), WIRE_V1);
+    assert_eq!(
+        peers.identified(b"a".to_vec(), "amunchain/1.0.0 wire=1,2"),
+        Some(WIRE_V2)
+    );
+    assert_eq!(
+        peers.identified(b"b".to_vec(), "amunchain/1.0.0"),
+        Some(WIRE_V1)
+    );
+    assert_eq!(peers.send_version(), WIRE_V1);
+
+    // The old node leaves; everyone left reads v2.
+    peers.disconnected(b"b");
+    assert_eq!(peers.send_version(), WIRE_V2);
+
+    // A peer with nothing in common is not counted.
+    peers.connected(b"c".to_vec());
+    assert_eq!(
+        peers.identified(b"c".to_vec(), "amunchain/2.0.0 wire=4"),
+        None
+    );
+    assert_eq!(peers.send_version(), WIRE_V2);
+}
+
+#[test]
+fn inbound_validation_rejects_impossible_messages() {
+    let ok = encode_msg(&msg(), WIRE_V2).unwrap();
+    assert_eq!(height(&decode_and_validate_consensus_msg(&ok).unwrap()), 5);
+
+    let zero = encode_msg(&with_height(0), WIRE_V1).unwrap();
+    assert!(decode_msg(&zero).is_ok());
+    assert!(matches!(
+        decode_and_validate_consensus_msg(&zero),
+        Err(WireError::Invalid)
+    ));
+
+    let unsigned = ConsensusMsg::Commit(Commit {
+        height: Height(5),
+        round: Round::ZERO,
+        epoch: Epoch(1),
+        msg_counter: 0,
+        sent_ts_ms: 0,
+        ttl_ms: 0,
+        block_hash: H256::from_bytes([7; 32]),
+        signatures: Default::default(),
+        voting_power: 0,
+        validator_set_hash: H256::ZERO,
+    });
+    let bytes = WireCodec::Protobuf.encode(&unsigned, WIRE_V2).unwrap();
+    assert!(matches!(
+        WireCodec::Protobuf.decode_validated(&bytes),
+        Err(WireError::Invalid)
+    ));
+}
+
+#[test]
+fn fuzz_seeds_pass_validation() {
+    for entry in std::fs::read_dir("fuzz/corpus/consensus_frame").unwrap() {
+        let data = std::fs::read(entry.unwrap().path()).unwrap();
+        let (sel, frame) = data.split_first().unwrap();
+        let codec = if sel % 2 == 0 {
+            WireCodec::Bincode
+        } else {
+            WireCodec::Protobuf
+        };
+        assert!(codec.decode_validated(frame).is_ok());
+    }
+}
+
+fn commit(signers: u8) -> ConsensusMsg {
+    let signatures = (0..signers)
+        .map(|i| {
+            (
+                ValidatorId::from_bytes([i; 32]),
+                Signature::from_bytes([i; 64]),
+            )
+        })
+        .collect();
+    ConsensusMsg::Commit(Commit {
+        height: Height(5),
+        round: Round(1),
+        epoch: Epoch(1),
+        msg_counter: 2,
+        sent_ts_ms: 3,
+        ttl_ms: 4,
+        block_hash: H256::from_bytes([7; 32]),
+        signatures,
+        voting_power: u128::from(signers),
+        validator_set_hash: H256::ZERO,
+    })
+}
+
+fn envelope_version(bytes: &[u8]) -> u16 {
+    let framed = bytes.strip_prefix(&ENVELOPE_MAGIC).unwrap();
+    u16::from_le_bytes([framed[0], framed[1]])
+}
+
+#[test]
+fn v3_compresses_large_messages_only() {
+    let big = commit(200);
+    let v2 = encode_msg(&big, WIRE_V2).unwrap();
+    let v3 = encode_msg(&big, WIRE_V3).unwrap();
+    assert_eq!(envelope_version(&v3), WIRE_V3);
+    assert!(v3.len() < v2.len());
+    let (v, m) = decode_msg(&v3).unwrap();
+    assert_eq!(v, WIRE_V3);
+    assert_eq!(
+        encode_canonical(&m).unwrap(),
+        encode_canonical(&big).unwrap()
+    );
+    assert_eq!(
+        WireCodec::Bincode.peek_timing(&v3),
+        WireCodec::Bincode.peek_timing(&v2)
+    );
+    assert!(WireCodec::Bincode.peek_timing(&v3).is_some());
+
+    // Below the threshold a v3 sender keeps the plain v2 envelope.
+    let small = encode_msg(&msg(), WIRE_V3).unwrap();
+    assert_eq!(small, encode_msg(&msg(), WIRE_V2).unwrap());
+}
+
+#[test]
+fn v3_payloads_inflating_past_the_cap_are_rejected() {
+    let bomb = WireEnvelope {
+        version: WIRE_V3,
+        payload: zstd::bulk::compress(&vec![0u8; MAX_WIRE_BYTES + 1], 3).unwrap(),
+    };
+    let mut bytes = ENVELOPE_MAGIC.to_vec();
+    bytes.extend(encode_canonical(&bomb).unwrap());
+    assert!(bytes.len() < 1024);
+    assert!(matches!(decode_msg(&bytes), Err(WireError::TooLarge)));
+
+    let garbage = WireEnvelope {
+        version: WIRE_V3,
+        payload: vec![1, 2, 3],
+    };
+    let mut bytes = ENVELOPE_MAGIC.to_vec();
+    bytes.extend(encode_canonical(&garbage).unwrap());
+    assert!(matches!(decode_msg(&bytes), Err(WireError::Malformed)));
+}
@@ -0,0 +1,269 @@
+// Copyright (c) 2026 Amunchain
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+mod common;
+
+use amunchain::core::consensus::driver::ConsensusDriver;
+use amunchain::core::consensus::events::{ChainEvent, ChainEvents};
+use amunchain::core::economics::staking::{StakingLedger, StakingParams};
+use amunchain::core::state::persistent_state::{KvOp, PersistentState};
+use amunchain::core::types::{ConsensusMsg, ValidatorId, H256};
+use amunchain::monitoring::metrics::Metrics;
+use amunchain::rpc::server::{bind, serve_listener, RpcState};
+use common::{id, keypairs, vote};
+use futures::{SinkExt, StreamExt};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+type Ws = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+fn head(height: u64) -> ChainEvent {
+    ChainEvent::NewHead {
+        height,
+        round: 0,
+        block_hash: hex::encode([height as u8; 32]),
+    }
+}
+
+#[test]
+fn driver_publishes_heads_finality_and_set_changes() {
+    let kps = keypairs(4);
+    let validators: BTreeSet<ValidatorId> = kps.iter().map(id).collect();
+    let events = ChainEvents::new(16);
+    let mut rx = events.subscribe();
+    let mut driver = ConsensusDriver::new(validators.clone())
+        .unwrap()
+        .with_events(events);
+
+    let hash = H256::from_bytes([7; 32]);
+    for kp in &kps[..3] {
+        driver.on_msg(ConsensusMsg::Vote(vote(kp, &validators, 1, hash)));
+    }
+    // A rejected vote (unknown signer) is not a head.
+    let outsider = &keypairs(1)[0];
+    driver.on_msg(ConsensusMsg::Vote(vote(outsider, &validators, 2, hash)));
+
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        ChainEvent::NewHead {
+            height: 1,
+            round: 0,
+            block_hash: hex::encode([7; 32]),
+        }
+    );
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        ChainEvent::Finalized {
+            height: 1,
+            round: 0,
+            block_hash: hex::encode([7; 32]),
+            signers: 3,
+        }
+    );
+    assert!(rx.try_recv().is_err());
+
+    let mut ledger = StakingLedger::with_params(StakingParams {
+        min_self_stake: 10,
+        ..StakingParams::default()
+    })
+    .unwrap();
+    for kp in &kps {
+        ledger
+            .register_validator(id(kp).as_bytes().to_vec(), 0, 100)
+            .unwrap();
+    }
+    ledger.jail(id(&kps[3]).as_bytes(), 0);
+    driver.sync_staking(&ledger).unwrap();
+    let ChainEvent::ValidatorSetChanged { height, validators } = rx.try_recv().unwrap() else {
+        panic!("expected a validator set change");
+    };
+    assert_eq!(height, 1);
+    assert_eq!(validators.len(), 3);
+    assert!(!validators.contains(&hex::encode(id(&kps[3]).as_bytes())));
+    // Unchanged set, no event.
+    driver.sync_staking(&ledger).unwrap();
+    assert!(rx.try_recv().is_err());
+}
+
+async fn serve(events: ChainEvents, max_subscribers: usize) -> (SocketAddr, Arc<Metrics>) {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let listener = bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let state = RpcState::new(metrics.clone()).with_events(events, max_subscribers);
+    tokio::spawn(serve_listener(listener, state));
+    (addr, metrics)
+}
+
+async fn connect(addr: SocketAddr, query: &str) -> Ws {
+    tokio_tungstenite::connect_async(format!("ws://{addr}/ws{query}"))
+        .await
+        .unwrap()
+        .0
+}
+
+async fn next_json(ws: &mut Ws) -> Value {
+    let msg = tokio::time::timeout(Duration::from_secs(5), ws.next())
+        .await
+        .expect("frame")
+        .unwrap()
+        .unwrap();
+    serde_json::from_str(msg.to_text().unwrap()).unwrap()
+}
+
+// Subscriptions start once the server task runs.
+async fn wait_for_subscribers(events: &ChainEvents, n: usize) {
+    for _ in 0..100 {
+        if events.subscribers() == n {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("expected {n} subscribers, have {}", events.subscribers());
+}
+
+#[tokio::test]
+async fn ws_streams_filtered_events() {
+    let events = ChainEvents::new(16);
+    let (addr, metrics) = serve(events.clone(), 4).await;
+    let mut ws = connect(addr, "?events=new_head").await;
+    wait_for_subscribers(&events, 1).await;
+    assert_eq!(metrics.rpc_ws_subscribers.get(), 1);
+
+    events.publish(ChainEvent::Finalized {
+        height: 1,
+        round: 0,
+        block_hash: String::new(),
+        signers: 3,
+    });
+    events.publish(head(2));
+    assert_eq!(
+        next_json(&mut ws).await,
+        serde_json::to_value(head(2)).unwrap()
+    );
+
+    ws.send(Message::Text(r#"{"events":["finalized"]}"#.into()))
+        .await
+        .unwrap();
+    let ack = next_json(&mut ws).await;
+    assert_eq!(ack["type"], "subscribed");
+    assert_eq!(ack["events"], serde_json::json!(["finalized"]));
+    events.publish(head(3));
+    events.publish(ChainEvent::Finalized {
+        height: 2,
+        round: 0,
+        block_hash: String::new(),
+        signers: 3,
+    });
+    let ev = next_json(&mut ws).await;
+    assert_eq!(ev["type"], "finalized");
+    assert_eq!(ev["height"], 2);
+
+    ws.send(Message::Text(r#"{"events":["bogus"]}"#.into()))
+        .await
+        .unwrap();
+    assert_eq!(next_json(&mut ws).await["type"], "error");
+
+    ws.close(None).await.unwrap();
+    wait_for_subscribers(&events, 0).await;
+    assert_eq!(metrics.rpc_ws_subscribers.get(), 0);
+}
+
+#[tokio::test]
+async fn ws_rejects_unknown_kinds_and_excess_subscribers() {
+    let events = ChainEvents::new(16);
+    let (addr, _) = serve(events.clone(), 1).await;
+    assert!(
+        tokio_tungstenite::connect_async(format!("ws://{addr}/ws?events=blocks"))
+            .await
+            .is_err()
+    );
+
+    let _first = connect(addr, "").await;
+    wait_for_subscribers(&events, 1).await;
+    assert!(tokio_tungstenite::connect_async(format!("ws://{addr}/ws"))
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn slow_subscriber_is_told_what_it_missed() {
+    let events = ChainEvents::new(2);
+    let (addr, metrics) = serve(events.clone(), 4).await;
+    let mut ws = connect(addr, "").await;
+    wait_for_subscribers(&events, 1).await;
+
+    // The server task cannot run in between on this single-threaded runtime.
+    for h in 1..=5 {
+        events.publish(head(h));
+    }
+    let lagged = next_json(&mut ws).await;
+    assert_eq!(lagged["type"], "lagged");
+    assert_eq!(lagged["skipped"], 3);
+    assert_eq!(next_json(&mut ws).await["height"], 4);
+    assert_eq!(next_json(&mut ws).await["height"], 5);
+    assert_eq!(metrics.rpc_ws_events_dropped_total.get(), 3);
+}
+
+#[tokio::test]
+async fn ws_streams_storage_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = PersistentState::open(&dir.path().to_string_lossy()).unwrap();
+    let events = ChainEvents::new(16);
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let listener = bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let rpc = RpcState::new(metrics)
+        .with_events(events.clone(), 4)
+        .with_state(state.clone());
+    tokio::spawn(serve_listener(listener, rpc));
+    let mut ws = connect(addr, "?events=finalized").await;
+
+    ws.send(Message::Text(r#"{"storage":{"prefixes":["zz"]}}"#.into()))
+        .await
+        .unwrap();
+    assert_eq!(next_json(&mut ws).await["type"], "error");
+    let sub = format!(
+        r#"{{"storage":{{"keys":["{}"]}}}}"#,
+        hex::encode(b"gov/fee")
+    );
+    ws.send(Message::Text(sub)).await.unwrap();
+    let ack = next_json(&mut ws).await;
+    assert_eq!(ack["type"], "storage_subscribed");
+    assert_eq!(ack["keys"][0], hex::encode(b"gov/fee"));
+
+    state.set_block_height(3);
+    state
+        .commit_atomic(vec![
+            KvOp::Put {
+                key: b"gov/other".to_vec(),
+                value: b"x".to_vec(),
+            },
+            KvOp::Put {
+                key: b"gov/fee".to_vec(),
+                value: b"5".to_vec(),
+            },
+        ])
+        .unwrap();
+    let ev = next_json(&mut ws).await;
+    assert_eq!(ev["type"], "storage_changed");
+    assert_eq!(ev["key"], hex::encode(b"gov/fee"));
+    assert!(ev["old"].is_null());
+    assert_eq!(ev["new"], hex::encode(b"5"));
+    assert_eq!(ev["height"], 3);
+}